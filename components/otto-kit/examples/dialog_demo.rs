@@ -0,0 +1,118 @@
+use std::sync::{Arc, Mutex};
+
+use otto_kit::input::keycodes;
+use otto_kit::prelude::*;
+use wayland_client::protocol::wl_keyboard;
+
+const WIDTH: i32 = 420;
+const HEIGHT: i32 = 180;
+
+struct DialogApp {
+    window: Option<Window>,
+    /// Cancel, then OK
+    buttons: Arc<Mutex<Vec<Button>>>,
+}
+
+impl App for DialogApp {
+    fn on_app_ready(&mut self, _ctx: &AppContext) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = AppContext::current_theme();
+        let mut window = Window::new("Dialog Demo", WIDTH, HEIGHT)?;
+        window.set_background(theme.material_medium);
+
+        let cancel = ButtonBuilder::new("Cancel")
+            .secondary()
+            .on_click(|| {
+                println!("Cancelled");
+                std::process::exit(1);
+            })
+            .build();
+        let ok = ButtonBuilder::new("OK")
+            .primary()
+            .with_size(88.0, cancel.height())
+            .focused()
+            .on_click(|| {
+                println!("Saved");
+                std::process::exit(0);
+            })
+            .build();
+
+        // Right-align the buttons at the bottom of the dialog
+        let y = HEIGHT as f32 - 24.0 - ok.height();
+        let ok_x = WIDTH as f32 - 24.0 - ok.width();
+        let cancel_x = ok_x - 12.0 - cancel.width();
+        *self.buttons.lock().unwrap() = vec![cancel.at(cancel_x, y), ok.at(ok_x, y)];
+
+        let buttons = self.buttons.clone();
+        window.on_draw(move |canvas| {
+            Label::new("Save changes?")
+                .at(24.0, 24.0)
+                .with_style(styles::TITLE_3_EMPHASIZED)
+                .with_color(theme.text_primary)
+                .render(canvas);
+            Label::new("Your changes will be lost if you don't save them.")
+                .at(24.0, 56.0)
+                .with_style(styles::BODY)
+                .with_color(theme.text_secondary)
+                .render(canvas);
+            for button in buttons.lock().unwrap().iter() {
+                button.render(canvas);
+            }
+        });
+
+        let buttons = self.buttons.clone();
+        let window_clone = window.clone();
+        window.on_pointer_event(move |events| {
+            let mut redraw = false;
+            for button in buttons.lock().unwrap().iter_mut() {
+                redraw |= button.handle_pointer_events(events);
+            }
+            if redraw {
+                window_clone.request_frame();
+            }
+        });
+
+        self.window = Some(window);
+        Ok(())
+    }
+
+    fn on_keyboard_event(
+        &mut self,
+        _ctx: &AppContext,
+        key: u32,
+        key_state: wl_keyboard::KeyState,
+        _serial: u32,
+    ) {
+        let mut buttons = self.buttons.lock().unwrap();
+        let mut redraw = false;
+
+        // Tab moves focus between the buttons
+        if key == keycodes::TAB && key_state == wl_keyboard::KeyState::Pressed {
+            let focused = buttons.iter().position(|b| b.is_focused()).unwrap_or(0);
+            let next = (focused + 1) % buttons.len();
+            for (i, button) in buttons.iter_mut().enumerate() {
+                button.set_focused(i == next);
+            }
+            redraw = true;
+        }
+
+        for button in buttons.iter_mut() {
+            redraw |= button.handle_key(key, key_state);
+        }
+
+        if redraw {
+            if let Some(window) = self.window.as_ref() {
+                window.request_frame();
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let app = DialogApp {
+        window: None,
+        buttons: Arc::new(Mutex::new(Vec::new())),
+    };
+    AppRunner::new(app).run()?;
+    Ok(())
+}
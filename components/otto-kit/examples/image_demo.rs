@@ -0,0 +1,57 @@
+//! Draw an image file with each fit mode
+//!
+//! Usage: cargo run --example image_demo -- /path/to/picture.jpg
+
+use otto_kit::prelude::*;
+
+struct ImageDemoApp {
+    path: String,
+    window: Option<Window>,
+}
+
+impl App for ImageDemoApp {
+    fn on_app_ready(&mut self, _ctx: &AppContext) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = AppContext::current_theme();
+        let mut window = Window::new("Image Demo", 560, 260)?;
+        window.set_background(theme.material_medium);
+
+        let path = self.path.clone();
+        window.on_draw(move |canvas| {
+            let fits = [
+                ("Contain", ImageFit::Contain),
+                ("Cover", ImageFit::Cover),
+                ("Fill", ImageFit::Fill),
+            ];
+            for (i, (name, fit)) in fits.into_iter().enumerate() {
+                let x = 40.0 + i as f32 * 170.0;
+                Image::from_path(&path)
+                    .at(x, 40.0)
+                    .with_size(150.0, 150.0)
+                    .with_fit(fit)
+                    .with_corner_radius(12.0)
+                    .with_placeholder(theme.fill_tertiary)
+                    .render(canvas);
+                Label::new(name)
+                    .at(x, 205.0)
+                    .with_width(150.0)
+                    .with_align(TextAlign::Center)
+                    .with_style(styles::CALLOUT)
+                    .with_color(theme.text_secondary)
+                    .render(canvas);
+            }
+        });
+
+        self.window = Some(window);
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args()
+        .nth(1)
+        .ok_or("usage: image_demo <path-to-png-or-jpeg>")?;
+    let app = ImageDemoApp { path, window: None };
+    AppRunner::new(app).run()?;
+    Ok(())
+}
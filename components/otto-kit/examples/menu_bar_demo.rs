@@ -0,0 +1,101 @@
+//! Example demonstrating the MenuBar component
+//!
+//! This shows:
+//! - A global menu bar on a top layer-shell surface
+//! - Dropdown menus opened by clicking bar items
+//! - Keyboard traversal with Left/Right between dropdowns
+//! - Theming from the current color scheme
+
+use otto_kit::components::menu_bar::{MenuBarIcon, MenuBarItem};
+use otto_kit::prelude::*;
+use wayland_client::protocol::{wl_keyboard, wl_surface};
+
+fn build_menu_bar() -> MenuBar {
+    MenuBar::new()
+        .with_theme(AppContext::current_theme())
+        .add_item(
+            MenuBarItem {
+                label: None,
+                icon: Some(MenuBarIcon::Named("start-here".into())),
+            },
+            Some(vec![
+                MenuItem::action("About This Computer"),
+                MenuItem::separator(),
+                MenuItem::action("Lock Screen").with_shortcut("⌘L"),
+            ]),
+        )
+        .add_menu(
+            "File",
+            vec![
+                MenuItem::action("New Window").with_shortcut("⌘N"),
+                MenuItem::action("Open...").with_shortcut("⌘O"),
+                MenuItem::separator(),
+                MenuItem::submenu(
+                    "Open Recent",
+                    vec![
+                        MenuItem::action("document1.txt"),
+                        MenuItem::action("project.rs"),
+                    ],
+                ),
+                MenuItem::separator(),
+                MenuItem::action("Close Window").with_shortcut("⌘W"),
+            ],
+        )
+        .add_menu(
+            "Edit",
+            vec![
+                MenuItem::action("Undo").with_shortcut("⌘Z"),
+                MenuItem::action("Redo").with_shortcut("⇧⌘Z"),
+                MenuItem::separator(),
+                MenuItem::action("Cut").with_shortcut("⌘X"),
+                MenuItem::action("Copy").with_shortcut("⌘C"),
+                MenuItem::action("Paste").with_shortcut("⌘V"),
+            ],
+        )
+        .add_menu(
+            "View",
+            vec![
+                MenuItem::action("Zoom In").with_shortcut("⌘+"),
+                MenuItem::action("Zoom Out").with_shortcut("⌘-"),
+            ],
+        )
+        .add_menu("Help", vec![MenuItem::action("Documentation")])
+        .on_item_click(|menu, id| println!("menu {menu} > {id}"))
+}
+
+struct MenuBarDemo {
+    bar: Option<MenuBar>,
+}
+
+impl App for MenuBarDemo {
+    fn on_app_ready(&mut self, _ctx: &AppContext) -> Result<(), Box<dyn std::error::Error>> {
+        let bar = build_menu_bar();
+        bar.show()?;
+        self.bar = Some(bar);
+        Ok(())
+    }
+
+    fn on_keyboard_event(
+        &mut self,
+        _ctx: &AppContext,
+        key: u32,
+        state: wl_keyboard::KeyState,
+        _serial: u32,
+    ) {
+        if let Some(bar) = &self.bar {
+            bar.handle_key(key, state);
+        }
+    }
+
+    fn on_keyboard_leave(&mut self, _ctx: &AppContext, surface: &wl_surface::WlSurface) {
+        if let Some(bar) = &self.bar {
+            bar.handle_keyboard_leave(surface);
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    AppRunner::new(MenuBarDemo { bar: None }).run()?;
+    Ok(())
+}
@@ -1187,13 +1187,13 @@ fn detect_default_sink_node_id() -> Option<u32> {
 impl App for MusicNotchApp {
     fn on_app_ready(&mut self, _ctx: &AppContext) -> Result<(), Box<dyn std::error::Error>> {
         let layer_surface =
-            LayerShellSurface::new(Layer::Overlay, "music-notch", LAYER_W, LAYER_H)?;
-        layer_surface.set_anchor(Anchor::Top);
-        layer_surface.set_margin(2, 0, 0, 0);
-        layer_surface.set_exclusive_zone(1);
-        layer_surface.set_keyboard_interactivity(
-            wayland_protocols_wlr::layer_shell::v1::client::zwlr_layer_surface_v1::KeyboardInteractivity::OnDemand,
-        );
+            LayerShellSurface::new(Layer::Overlay, "music-notch", LAYER_W, LAYER_H)?
+                .anchor(Anchor::Top)
+                .margin(2, 0, 0, 0)
+                .exclusive_zone(1)
+                .keyboard_interactivity(
+                    wayland_protocols_wlr::layer_shell::v1::client::zwlr_layer_surface_v1::KeyboardInteractivity::OnDemand,
+                );
         if let Some(style) = layer_surface.base_surface().surface_style() {
             style.set_masks_to_bounds(ClipMode::Enabled);
         }
@@ -0,0 +1,134 @@
+//! A main window that opens a separate preferences window.
+//!
+//! Click the main window to open Preferences; click inside Preferences to
+//! toggle the setting, and press Escape or use the window's close button
+//! to close it again. Each window only sees its own pointer and keyboard
+//! events. Closing the main window while Preferences is open keeps the app
+//! running; it exits when the last window closes.
+//!
+//! Run with `cargo run --example preferences_window`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use otto_kit::prelude::*;
+use smithay_client_toolkit::seat::pointer::PointerEventKind;
+use wayland_client::protocol::wl_keyboard;
+
+/// evdev code of the Escape key
+const KEY_ESC: u32 = 1;
+
+struct PreferencesApp {
+    main: Option<Window>,
+}
+
+fn open_preferences(
+    main: &Window,
+    preferences: &Rc<RefCell<Option<Window>>>,
+    dark: &Arc<AtomicBool>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if preferences
+        .borrow()
+        .as_ref()
+        .is_some_and(|w| !w.is_closed())
+    {
+        return Ok(());
+    }
+
+    let theme = AppContext::current_theme();
+    let mut window = Window::new("Preferences", 320, 160)?;
+    window.set_background(theme.material_medium);
+
+    let draw_dark = dark.clone();
+    window.on_draw(move |canvas| {
+        let state = if draw_dark.load(Ordering::Relaxed) {
+            "on"
+        } else {
+            "off"
+        };
+        Label::new(format!("Dark mode: {state}"))
+            .at(24.0, 60.0)
+            .with_style(styles::BODY)
+            .with_color(theme.text_primary)
+            .render(canvas);
+        Label::new("Click to toggle, Esc to close")
+            .at(24.0, 100.0)
+            .with_style(styles::CAPTION_1)
+            .with_color(theme.text_secondary)
+            .render(canvas);
+    });
+
+    let (click_window, click_main, click_dark) = (window.clone(), main.clone(), dark.clone());
+    window.on_pointer_event(move |events| {
+        for event in events {
+            if let PointerEventKind::Press { .. } = event.kind {
+                click_dark.fetch_xor(true, Ordering::Relaxed);
+                click_window.request_frame();
+                click_main.request_frame();
+            }
+        }
+    });
+
+    let key_window = window.clone();
+    window.on_keyboard_event(move |key, state, _serial| {
+        if key == KEY_ESC && state == wl_keyboard::KeyState::Pressed {
+            key_window.close();
+        }
+    });
+
+    *preferences.borrow_mut() = Some(window);
+    Ok(())
+}
+
+impl App for PreferencesApp {
+    fn on_app_ready(&mut self, _ctx: &AppContext) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = AppContext::current_theme();
+        let mut main = Window::new("Main Window", 480, 300)?;
+        main.set_background(theme.material_medium);
+
+        let dark = Arc::new(AtomicBool::new(false));
+        let preferences: Rc<RefCell<Option<Window>>> = Rc::new(RefCell::new(None));
+
+        let draw_dark = dark.clone();
+        main.on_draw(move |canvas| {
+            let state = if draw_dark.load(Ordering::Relaxed) {
+                "on"
+            } else {
+                "off"
+            };
+            Label::new(format!("Dark mode is {state}"))
+                .at(32.0, 80.0)
+                .with_style(styles::TITLE_3)
+                .with_color(theme.text_primary)
+                .render(canvas);
+            Label::new("Click to open Preferences")
+                .at(32.0, 120.0)
+                .with_style(styles::BODY)
+                .with_color(theme.text_secondary)
+                .render(canvas);
+        });
+
+        let click_main = main.clone();
+        main.on_pointer_event(move |events| {
+            for event in events {
+                if let PointerEventKind::Press { .. } = event.kind {
+                    if let Err(e) = open_preferences(&click_main, &preferences, &dark) {
+                        eprintln!("could not open preferences: {e}");
+                    }
+                }
+            }
+        });
+
+        self.main = Some(main);
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let app = PreferencesApp { main: None };
+    AppRunner::new(app).run()?;
+    Ok(())
+}
@@ -0,0 +1,189 @@
+//! Scrolling a long menu in a popup
+//!
+//! Click the window to open a popup with more items than fit, then scroll it
+//! with a mouse wheel (high-resolution wheels scroll in fractions of a notch)
+//! or with two fingers on a touchpad. The items sit in a ScrollView: when the
+//! fingers lift, the menu keeps gliding for a moment, and a scrollbar fades
+//! out once scrolling ends.
+
+use std::{cell::RefCell, rc::Rc};
+
+use otto_kit::{components::menu_item::MenuItemGroup, prelude::*, surfaces::PopupSurface};
+use smithay_client_toolkit::{
+    seat::pointer::PointerEventKind,
+    shell::{xdg::XdgPositioner, WaylandSurface},
+};
+use wayland_client::{protocol::wl_surface, Proxy};
+use wayland_protocols::xdg::shell::client::xdg_positioner;
+
+const MENU_WIDTH: i32 = 240;
+const MENU_HEIGHT: i32 = 320;
+
+/// The popup and the scroll view holding its items
+struct ScrollingMenu {
+    popup: PopupSurface,
+    style: ContextMenuStyle,
+    scroll_view: ScrollView,
+}
+
+impl ScrollingMenu {
+    fn new(popup: PopupSurface, items: Vec<MenuItem>) -> Self {
+        let style = ContextMenuStyle::default();
+        let width = MENU_WIDTH as f32 - style.horizontal_padding * 2.0;
+        let height = style.vertical_padding * 2.0 + items.iter().map(|i| i.height).sum::<f32>();
+        let padding = (style.horizontal_padding, style.vertical_padding);
+        let scroll_view = ScrollView::new(ScrollAxes::Vertical)
+            .with_size(MENU_WIDTH as f32, MENU_HEIGHT as f32)
+            .with_content_fn(MENU_WIDTH as f32, height, move |canvas| {
+                canvas.translate(padding);
+                MenuItemGroup::new()
+                    .with_width(width)
+                    .items(items.clone())
+                    .render(canvas);
+            });
+        Self {
+            popup,
+            style,
+            scroll_view,
+        }
+    }
+
+    fn scroll(&mut self, scroll: &ScrollEvent) {
+        if self.scroll_view.handle_scroll(scroll) {
+            self.draw();
+            // Keep drawing while it glides and the scrollbar fades
+            AppContext::request_frame(self.popup.wl_surface());
+        }
+    }
+
+    fn draw(&mut self) {
+        let background = self.style.background_color();
+        let scroll_view = &mut self.scroll_view;
+        self.popup.draw(|canvas| {
+            canvas.clear(background);
+            scroll_view.render(canvas);
+        });
+    }
+}
+
+struct ScrollingMenuApp {
+    window: Option<Window>,
+    menu: Rc<RefCell<Option<ScrollingMenu>>>,
+}
+
+impl App for ScrollingMenuApp {
+    fn on_app_ready(&mut self, _ctx: &AppContext) -> Result<(), Box<dyn std::error::Error>> {
+        let mut window = Window::new("Scrolling Menu", 600, 400)?;
+        window.set_background(Color::WHITE);
+        window.on_draw(|canvas| {
+            Label::new("Click anywhere to open the menu")
+                .at(24.0, 24.0)
+                .with_style(styles::TITLE_3_EMPHASIZED)
+                .render(canvas);
+        });
+
+        let window_clone = window.clone();
+        let menu = self.menu.clone();
+        window.on_pointer_event(move |events| {
+            for event in events {
+                if let PointerEventKind::Press { button, serial, .. } = event.kind {
+                    if button == 0x110 {
+                        let (x, y) = event.position;
+                        open_menu(&window_clone, &menu, x as i32, y as i32, serial);
+                    }
+                }
+            }
+        });
+
+        self.window = Some(window);
+        Ok(())
+    }
+
+    fn on_scroll(
+        &mut self,
+        _ctx: &AppContext,
+        surface: &wl_surface::WlSurface,
+        scroll: &ScrollEvent,
+    ) {
+        let mut menu = self.menu.borrow_mut();
+        let Some(menu) = menu.as_mut() else {
+            return;
+        };
+        if menu.popup.wl_surface().id() == surface.id() {
+            menu.scroll(scroll);
+        }
+    }
+}
+
+/// Open the menu popup at (x, y) in the window
+fn open_menu(
+    window: &Window,
+    menu: &Rc<RefCell<Option<ScrollingMenu>>>,
+    x: i32,
+    y: i32,
+    serial: u32,
+) {
+    let Some(toplevel) = window.surface() else {
+        return;
+    };
+    let Ok(positioner) = XdgPositioner::new(AppContext::xdg_shell_state()) else {
+        return;
+    };
+    positioner.set_size(MENU_WIDTH, MENU_HEIGHT);
+    positioner.set_anchor_rect(x, y, 1, 1);
+    positioner.set_anchor(xdg_positioner::Anchor::TopLeft);
+    positioner.set_gravity(xdg_positioner::Gravity::BottomRight);
+    positioner.set_constraint_adjustment(
+        xdg_positioner::ConstraintAdjustment::FlipY | xdg_positioner::ConstraintAdjustment::SlideX,
+    );
+
+    let Ok(popup) = PopupSurface::new_with_grab(
+        toplevel.window().xdg_surface(),
+        &positioner,
+        MENU_WIDTH,
+        MENU_HEIGHT,
+        Some(serial),
+    ) else {
+        return;
+    };
+    if let Some(surface_style) = popup.base_surface().surface_style() {
+        surface_style.set_corner_radius(12.0);
+    }
+    let surface_id = popup.wl_surface().id();
+    let items = (1..=60)
+        .map(|i| MenuItem::action(format!("Item {i}")))
+        .collect();
+    *menu.borrow_mut() = Some(ScrollingMenu::new(popup, items));
+
+    let configured = menu.clone();
+    AppContext::register_popup_configure_callback(surface_id.clone(), move |_serial| {
+        if let Some(menu) = configured.borrow_mut().as_mut() {
+            menu.popup.mark_configured();
+            menu.draw();
+        }
+    });
+    let gliding = menu.clone();
+    AppContext::register_frame_callback(surface_id.clone(), move || {
+        let mut menu = gliding.borrow_mut();
+        if let Some(menu) = menu.as_mut() {
+            if menu.scroll_view.tick() {
+                menu.draw();
+                AppContext::request_frame(menu.popup.wl_surface());
+            }
+        }
+    });
+    let closed = menu.clone();
+    AppContext::register_popup_done_callback(surface_id, move || {
+        closed.borrow_mut().take();
+    });
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let app = ScrollingMenuApp {
+        window: None,
+        menu: Rc::new(RefCell::new(None)),
+    };
+    AppRunner::new(app).run()?;
+    Ok(())
+}
@@ -0,0 +1,586 @@
+//! Otto Settings: scale, theme, wallpaper, input and dock options.
+//!
+//! The window talks to the compositor over the `org.otto.Settings` D-Bus
+//! interface (see `docs/developer/settings-dbus.md`) instead of editing the
+//! config file itself. Buttons apply as soon as they are clicked; a slider
+//! applies its value when it is released, so a drag writes the config file
+//! once instead of on every step.
+//!
+//! Run inside an Otto session with `cargo run --example settings_app`.
+
+use std::sync::{Arc, Mutex};
+
+use otto_kit::components::text_input::CARET_BLINK_INTERVAL;
+use otto_kit::prelude::*;
+use smithay_client_toolkit::seat::pointer::{PointerEventKind, BTN_LEFT};
+use tokio::sync::mpsc;
+use wayland_client::protocol::wl_keyboard;
+use zbus::{proxy, Connection};
+
+#[proxy(
+    interface = "org.otto.Settings",
+    default_service = "org.otto.Settings",
+    default_path = "/org/otto/Settings"
+)]
+trait OttoSettings {
+    fn get_color_scheme(&self) -> zbus::Result<u32>;
+    fn get_screen_scale(&self) -> zbus::Result<f64>;
+    fn get_background_image(&self) -> zbus::Result<String>;
+    fn get_tap_to_click(&self) -> zbus::Result<bool>;
+    fn get_natural_scroll(&self) -> zbus::Result<bool>;
+    fn get_pointer_accel_speed(&self) -> zbus::Result<f64>;
+    fn get_dock_size(&self) -> zbus::Result<f64>;
+    fn get_dock_magnification(&self) -> zbus::Result<bool>;
+    fn get_dock_autohide(&self) -> zbus::Result<bool>;
+    fn get_dock_position(&self) -> zbus::Result<String>;
+
+    fn set_color_scheme(&self, scheme: u32) -> zbus::Result<()>;
+    fn set_screen_scale(&self, scale: f64) -> zbus::Result<()>;
+    fn set_background_image(&self, path: &str) -> zbus::Result<()>;
+    fn set_tap_to_click(&self, enabled: bool) -> zbus::Result<()>;
+    fn set_natural_scroll(&self, enabled: bool) -> zbus::Result<()>;
+    fn set_pointer_accel_speed(&self, speed: f64) -> zbus::Result<()>;
+    fn set_dock_size(&self, size: f64) -> zbus::Result<()>;
+    fn set_dock_magnification(&self, enabled: bool) -> zbus::Result<()>;
+    fn set_dock_autohide(&self, enabled: bool) -> zbus::Result<()>;
+    fn set_dock_position(&self, position: &str) -> zbus::Result<()>;
+}
+
+/// Color scheme values of `org.otto.Settings`
+const SCHEME_DARK: u32 = 1;
+const SCHEME_LIGHT: u32 = 2;
+
+const LABEL_X: f32 = 40.0;
+const CONTROL_X: f32 = 220.0;
+const CONTROL_WIDTH: f32 = 260.0;
+const ROW: f32 = 48.0;
+
+/// The settings shown in the window, as last read from or sent to the
+/// compositor
+#[derive(Debug, Clone)]
+struct Settings {
+    color_scheme: u32,
+    screen_scale: f64,
+    background_image: String,
+    tap_to_click: bool,
+    natural_scroll: bool,
+    pointer_speed: f64,
+    dock_size: f64,
+    dock_magnification: bool,
+    dock_autohide: bool,
+    dock_position: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            color_scheme: SCHEME_LIGHT,
+            screen_scale: 1.0,
+            background_image: String::new(),
+            tap_to_click: true,
+            natural_scroll: true,
+            pointer_speed: 0.0,
+            dock_size: 1.0,
+            dock_magnification: true,
+            dock_autohide: false,
+            dock_position: "bottom".to_string(),
+        }
+    }
+}
+
+impl Settings {
+    async fn load(proxy: &OttoSettingsProxy<'_>) -> zbus::Result<Self> {
+        Ok(Self {
+            color_scheme: proxy.get_color_scheme().await?,
+            screen_scale: proxy.get_screen_scale().await?,
+            background_image: proxy.get_background_image().await?,
+            tap_to_click: proxy.get_tap_to_click().await?,
+            natural_scroll: proxy.get_natural_scroll().await?,
+            pointer_speed: proxy.get_pointer_accel_speed().await?,
+            dock_size: proxy.get_dock_size().await?,
+            dock_magnification: proxy.get_dock_magnification().await?,
+            dock_autohide: proxy.get_dock_autohide().await?,
+            dock_position: proxy.get_dock_position().await?,
+        })
+    }
+
+    /// Record `change` and return it, to be sent on
+    fn apply(&mut self, change: Change) -> Change {
+        match &change {
+            Change::ColorScheme(scheme) => self.color_scheme = *scheme,
+            Change::ScreenScale(scale) => self.screen_scale = *scale,
+            Change::BackgroundImage(path) => self.background_image = path.clone(),
+            Change::TapToClick(enabled) => self.tap_to_click = *enabled,
+            Change::NaturalScroll(enabled) => self.natural_scroll = *enabled,
+            Change::PointerSpeed(speed) => self.pointer_speed = *speed,
+            Change::DockSize(size) => self.dock_size = *size,
+            Change::DockMagnification(enabled) => self.dock_magnification = *enabled,
+            Change::DockAutohide(enabled) => self.dock_autohide = *enabled,
+            Change::DockPosition(position) => self.dock_position = position.clone(),
+        }
+        change
+    }
+}
+
+/// A setting changed in the window
+#[derive(Debug, Clone)]
+enum Change {
+    ColorScheme(u32),
+    ScreenScale(f64),
+    BackgroundImage(String),
+    TapToClick(bool),
+    NaturalScroll(bool),
+    PointerSpeed(f64),
+    DockSize(f64),
+    DockMagnification(bool),
+    DockAutohide(bool),
+    DockPosition(String),
+}
+
+impl Change {
+    async fn send(&self, proxy: &OttoSettingsProxy<'_>) -> zbus::Result<()> {
+        match self {
+            Change::ColorScheme(scheme) => proxy.set_color_scheme(*scheme).await,
+            Change::ScreenScale(scale) => proxy.set_screen_scale(*scale).await,
+            Change::BackgroundImage(path) => proxy.set_background_image(path).await,
+            Change::TapToClick(enabled) => proxy.set_tap_to_click(*enabled).await,
+            Change::NaturalScroll(enabled) => proxy.set_natural_scroll(*enabled).await,
+            Change::PointerSpeed(speed) => proxy.set_pointer_accel_speed(*speed).await,
+            Change::DockSize(size) => proxy.set_dock_size(*size).await,
+            Change::DockMagnification(enabled) => proxy.set_dock_magnification(*enabled).await,
+            Change::DockAutohide(enabled) => proxy.set_dock_autohide(*enabled).await,
+            Change::DockPosition(position) => proxy.set_dock_position(position).await,
+        }
+    }
+}
+
+/// Send changes to the compositor in order. A burst, like repeated arrow
+/// keys on a slider, is cut down to the last change of each setting so the
+/// config file is not rewritten for every step.
+async fn send_changes(
+    proxy: Option<OttoSettingsProxy<'static>>,
+    mut rx: mpsc::UnboundedReceiver<Change>,
+    status: Arc<Mutex<String>>,
+) {
+    while let Some(first) = rx.recv().await {
+        let mut changes = vec![first];
+        while let Ok(next) = rx.try_recv() {
+            changes.retain(|c| std::mem::discriminant(c) != std::mem::discriminant(&next));
+            changes.push(next);
+        }
+        let Some(proxy) = proxy.as_ref() else {
+            continue;
+        };
+        for change in changes {
+            let message = match change.send(proxy).await {
+                Ok(()) => String::new(),
+                Err(err) => format!("Could not apply: {err}"),
+            };
+            *status.lock().unwrap() = message;
+        }
+    }
+}
+
+/// Buttons of the window; each is an on/off switch or one option of a group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Choice {
+    Light,
+    Dark,
+    TapToClick,
+    NaturalScroll,
+    Magnification,
+    Autohide,
+    Position(&'static str),
+    ApplyWallpaper,
+}
+
+impl Choice {
+    /// Whether the button shows as selected
+    fn is_selected(self, settings: &Settings) -> bool {
+        match self {
+            Choice::Light => settings.color_scheme == SCHEME_LIGHT,
+            Choice::Dark => settings.color_scheme == SCHEME_DARK,
+            Choice::TapToClick => settings.tap_to_click,
+            Choice::NaturalScroll => settings.natural_scroll,
+            Choice::Magnification => settings.dock_magnification,
+            Choice::Autohide => settings.dock_autohide,
+            Choice::Position(position) => settings.dock_position == position,
+            Choice::ApplyWallpaper => false,
+        }
+    }
+
+    /// The change a click makes, or `None` when it has its own handling
+    fn toggle(self, settings: &Settings) -> Option<Change> {
+        Some(match self {
+            Choice::Light => Change::ColorScheme(SCHEME_LIGHT),
+            Choice::Dark => Change::ColorScheme(SCHEME_DARK),
+            Choice::TapToClick => Change::TapToClick(!settings.tap_to_click),
+            Choice::NaturalScroll => Change::NaturalScroll(!settings.natural_scroll),
+            Choice::Magnification => Change::DockMagnification(!settings.dock_magnification),
+            Choice::Autohide => Change::DockAutohide(!settings.dock_autohide),
+            Choice::Position(position) => Change::DockPosition(position.to_string()),
+            Choice::ApplyWallpaper => return None,
+        })
+    }
+}
+
+/// Row titles, with the y of their row
+const ROWS: [(&str, f32); 10] = [
+    ("Appearance", 80.0),
+    ("Scale", 80.0 + ROW),
+    ("Wallpaper", 80.0 + 2.0 * ROW),
+    ("Input", 80.0 + 3.5 * ROW),
+    ("Touchpad", 80.0 + 4.5 * ROW),
+    ("Pointer speed", 80.0 + 5.5 * ROW),
+    ("Dock size", 80.0 + 7.0 * ROW),
+    ("Behavior", 80.0 + 8.0 * ROW),
+    ("Position", 80.0 + 9.0 * ROW),
+    ("", 80.0 + 10.0 * ROW),
+];
+
+fn row_y(title: &str) -> f32 {
+    ROWS.iter()
+        .find(|(name, _)| *name == title)
+        .map(|(_, y)| *y)
+        .unwrap_or_default()
+}
+
+struct Controls {
+    buttons: Vec<(Choice, Button)>,
+    /// Buttons clicked while handling the current pointer frame
+    clicked: Arc<Mutex<Vec<Choice>>>,
+    sliders: Vec<Slider>,
+    /// Slider values changed since they were last sent
+    moved: Arc<Mutex<Vec<Change>>>,
+    wallpaper: TextInput,
+}
+
+impl Controls {
+    fn new(settings: &Settings, changes: &mpsc::UnboundedSender<Change>) -> Self {
+        let clicked = Arc::new(Mutex::new(Vec::new()));
+        let button = |choice: Choice, label: &str, x: f32, row: &str| {
+            let clicked = clicked.clone();
+            let button = Button::new(label)
+                .at(x, row_y(row) - 8.0)
+                .secondary()
+                .on_click(move || clicked.lock().unwrap().push(choice));
+            (choice, button)
+        };
+        let buttons = vec![
+            button(Choice::Light, "Light", CONTROL_X, "Appearance"),
+            button(Choice::Dark, "Dark", CONTROL_X + 90.0, "Appearance"),
+            button(
+                Choice::ApplyWallpaper,
+                "Apply",
+                CONTROL_X + CONTROL_WIDTH - 70.0,
+                "Wallpaper",
+            ),
+            button(Choice::TapToClick, "Tap to click", CONTROL_X, "Touchpad"),
+            button(
+                Choice::NaturalScroll,
+                "Natural scroll",
+                CONTROL_X + 130.0,
+                "Touchpad",
+            ),
+            button(
+                Choice::Magnification,
+                "Magnification",
+                CONTROL_X,
+                "Behavior",
+            ),
+            button(Choice::Autohide, "Auto-hide", CONTROL_X + 150.0, "Behavior"),
+            button(Choice::Position("bottom"), "Bottom", CONTROL_X, "Position"),
+            button(
+                Choice::Position("left"),
+                "Left",
+                CONTROL_X + 90.0,
+                "Position",
+            ),
+            button(
+                Choice::Position("right"),
+                "Right",
+                CONTROL_X + 170.0,
+                "Position",
+            ),
+        ];
+
+        let moved = Arc::new(Mutex::new(Vec::new()));
+        let pending = moved.clone();
+        let scale = SliderBuilder::new(1.0, 3.0)
+            .at(CONTROL_X, row_y("Scale"))
+            .with_width(CONTROL_WIDTH - 50.0)
+            .with_step(0.25)
+            .with_value(settings.screen_scale as f32)
+            .with_value_format(|v| format!("{v:.2}×"))
+            .on_change(move |v| pending.lock().unwrap().push(Change::ScreenScale(v as f64)))
+            .build();
+        let pending = moved.clone();
+        let pointer_speed = SliderBuilder::new(-1.0, 1.0)
+            .at(CONTROL_X, row_y("Pointer speed"))
+            .with_width(CONTROL_WIDTH - 50.0)
+            .with_step(0.1)
+            .with_value(settings.pointer_speed as f32)
+            .with_value_label()
+            .on_change(move |v| pending.lock().unwrap().push(Change::PointerSpeed(v as f64)))
+            .build();
+        let pending = moved.clone();
+        let dock_size = SliderBuilder::new(0.5, 2.0)
+            .at(CONTROL_X, row_y("Dock size"))
+            .with_width(CONTROL_WIDTH - 50.0)
+            .with_step(0.05)
+            .with_value(settings.dock_size as f32)
+            .with_value_format(|v| format!("{:.0}%", v * 100.0))
+            .on_change(move |v| pending.lock().unwrap().push(Change::DockSize(v as f64)))
+            .build();
+
+        let tx = changes.clone();
+        let wallpaper = TextInput::new()
+            .at(CONTROL_X, row_y("Wallpaper") - 8.0)
+            .with_size(CONTROL_WIDTH - 80.0, 0.0)
+            .with_text(settings.background_image.clone())
+            .with_placeholder("/path/to/image.jpg")
+            .on_submit(move |text| {
+                let _ = tx.send(Change::BackgroundImage(text.trim().to_string()));
+            });
+
+        Self {
+            buttons,
+            clicked,
+            sliders: vec![scale, pointer_speed, dock_size],
+            moved,
+            wallpaper,
+        }
+    }
+
+    /// Slider changes ready to send: none while a slider is dragged, so a
+    /// drag is sent once, when it is released
+    fn take_moved(&self) -> Vec<Change> {
+        if self.sliders.iter().any(Slider::is_dragging) {
+            return Vec::new();
+        }
+        std::mem::take(&mut *self.moved.lock().unwrap())
+    }
+
+    /// Show the selected options of `settings`
+    fn sync(&mut self, settings: &Settings) {
+        for (choice, button) in self.buttons.iter_mut() {
+            let variant = if choice.is_selected(settings) {
+                ButtonVariant::Primary
+            } else {
+                ButtonVariant::Secondary
+            };
+            button.set_variant(variant);
+        }
+    }
+
+    fn render(&self, canvas: &Canvas) {
+        for (_, button) in self.buttons.iter() {
+            button.render(canvas);
+        }
+        for slider in self.sliders.iter() {
+            slider.render(canvas);
+        }
+        self.wallpaper.render(canvas);
+    }
+}
+
+struct SettingsApp {
+    window: Option<Window>,
+    settings: Arc<Mutex<Settings>>,
+    controls: Arc<Mutex<Option<Controls>>>,
+    changes: mpsc::UnboundedSender<Change>,
+    status: Arc<Mutex<String>>,
+    /// Status line as of the last update, to redraw when it changes
+    shown_status: String,
+}
+
+impl App for SettingsApp {
+    fn on_app_ready(&mut self, _ctx: &AppContext) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = AppContext::current_theme();
+        let mut window = Window::new("Settings", 520, (row_y("") + 60.0) as i32)?;
+        window.set_background(theme.material_medium);
+
+        let mut controls = Controls::new(&self.settings.lock().unwrap(), &self.changes);
+        controls.sync(&self.settings.lock().unwrap());
+        *self.controls.lock().unwrap() = Some(controls);
+
+        let controls = self.controls.clone();
+        let status = self.status.clone();
+        window.on_draw(move |canvas| {
+            Label::new("Settings")
+                .at(LABEL_X, 30.0)
+                .with_style(styles::TITLE_2_EMPHASIZED)
+                .with_color(theme.text_primary)
+                .render(canvas);
+            Label::new("Dock")
+                .at(LABEL_X, row_y("Dock size") - ROW * 0.6)
+                .with_style(styles::HEADLINE)
+                .with_color(theme.text_secondary)
+                .render(canvas);
+            for (title, y) in ROWS.iter() {
+                Label::new(*title)
+                    .at(LABEL_X, *y)
+                    .with_style(styles::BODY)
+                    .with_color(theme.text_primary)
+                    .render(canvas);
+            }
+            if let Some(controls) = controls.lock().unwrap().as_ref() {
+                controls.render(canvas);
+            }
+            Label::new(status.lock().unwrap().clone())
+                .at(LABEL_X, row_y(""))
+                .with_style(styles::CALLOUT)
+                .with_color(theme.text_secondary)
+                .render(canvas);
+        });
+
+        let controls = self.controls.clone();
+        let settings = self.settings.clone();
+        let changes = self.changes.clone();
+        let window_clone = window.clone();
+        window.on_pointer_event(move |events| {
+            let mut controls = controls.lock().unwrap();
+            let Some(controls) = controls.as_mut() else {
+                return;
+            };
+            let mut settings = settings.lock().unwrap();
+            let mut redraw = false;
+
+            for slider in controls.sliders.iter_mut() {
+                redraw |= slider.handle_pointer_events(events);
+            }
+            for change in controls.take_moved() {
+                let _ = changes.send(settings.apply(change));
+            }
+            for event in events {
+                if let PointerEventKind::Press { button, .. } = event.kind {
+                    if button == BTN_LEFT {
+                        let (x, y) = (event.position.0 as f32, event.position.1 as f32);
+                        redraw |= controls.wallpaper.press_at(x, y, false);
+                    }
+                }
+            }
+            for (_, button) in controls.buttons.iter_mut() {
+                redraw |= button.handle_pointer_events(events);
+            }
+            let clicked = std::mem::take(&mut *controls.clicked.lock().unwrap());
+            for choice in clicked {
+                let change = match choice.toggle(&settings) {
+                    Some(change) => change,
+                    None => Change::BackgroundImage(controls.wallpaper.text().trim().to_string()),
+                };
+                let _ = changes.send(settings.apply(change));
+            }
+
+            controls.sync(&settings);
+            if redraw {
+                window_clone.request_frame();
+            }
+        });
+
+        self.window = Some(window);
+        Ok(())
+    }
+
+    fn on_keyboard_event(
+        &mut self,
+        _ctx: &AppContext,
+        key: u32,
+        key_state: wl_keyboard::KeyState,
+        _serial: u32,
+    ) {
+        let mut controls = self.controls.lock().unwrap();
+        let Some(controls) = controls.as_mut() else {
+            return;
+        };
+        let mut redraw =
+            controls
+                .wallpaper
+                .handle_key(AppContext::keysym(), key_state, AppContext::modifiers());
+        for slider in controls.sliders.iter_mut() {
+            redraw |= slider.handle_key(key, key_state);
+        }
+        let mut settings = self.settings.lock().unwrap();
+        for change in controls.take_moved() {
+            let _ = self.changes.send(settings.apply(change));
+        }
+        drop(settings);
+        drop(controls);
+        if redraw {
+            self.redraw();
+        }
+    }
+
+    fn on_text_input(&mut self, _ctx: &AppContext, text: &str) {
+        let handled = self
+            .controls
+            .lock()
+            .unwrap()
+            .as_mut()
+            .is_some_and(|controls| controls.wallpaper.handle_text(text));
+        if handled {
+            self.redraw();
+        }
+    }
+
+    fn on_update(&mut self, _ctx: &AppContext) {
+        // Keep the caret blinking and the status line current
+        let status = self.status.lock().unwrap().clone();
+        let status_changed = status != self.shown_status;
+        self.shown_status = status;
+        let editing = self
+            .controls
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|controls| controls.wallpaper.is_focused());
+        if editing || status_changed {
+            self.redraw();
+        }
+    }
+
+    fn idle_timeout(&self) -> Option<std::time::Duration> {
+        Some(CARET_BLINK_INTERVAL)
+    }
+}
+
+impl SettingsApp {
+    fn redraw(&self) {
+        if let Some(window) = self.window.as_ref() {
+            window.request_frame();
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let status = Arc::new(Mutex::new(String::new()));
+    let proxy = match Connection::session().await {
+        Ok(connection) => OttoSettingsProxy::new(&connection).await.ok(),
+        Err(_) => None,
+    };
+    let settings = match proxy.as_ref() {
+        Some(proxy) => Settings::load(proxy).await.unwrap_or_else(|err| {
+            *status.lock().unwrap() = format!("Could not read settings: {err}");
+            Settings::default()
+        }),
+        None => {
+            *status.lock().unwrap() = "Otto is not running, changes are not applied".to_string();
+            Settings::default()
+        }
+    };
+
+    let (changes, rx) = mpsc::unbounded_channel();
+    tokio::spawn(send_changes(proxy, rx, status.clone()));
+
+    let app = SettingsApp {
+        window: None,
+        settings: Arc::new(Mutex::new(settings)),
+        controls: Arc::new(Mutex::new(None)),
+        changes,
+        shown_status: status.lock().unwrap().clone(),
+        status,
+    };
+    AppRunner::new(app).run()?;
+    Ok(())
+}
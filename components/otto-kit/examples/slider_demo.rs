@@ -0,0 +1,101 @@
+use std::sync::{Arc, Mutex};
+
+use otto_kit::prelude::*;
+use wayland_client::protocol::wl_keyboard;
+
+struct SliderDemoApp {
+    window: Option<Window>,
+    /// Volume, brightness and a disabled balance slider
+    sliders: Arc<Mutex<Vec<Slider>>>,
+}
+
+impl App for SliderDemoApp {
+    fn on_app_ready(&mut self, _ctx: &AppContext) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = AppContext::current_theme();
+        let mut window = Window::new("Slider Demo", 420, 260)?;
+        window.set_background(theme.material_medium);
+
+        *self.sliders.lock().unwrap() = vec![
+            SliderBuilder::new(0.0, 100.0)
+                .at(140.0, 70.0)
+                .with_width(200.0)
+                .with_step(1.0)
+                .with_value(65.0)
+                .with_value_format(|v| format!("{v:.0}%"))
+                .on_change(|v| println!("volume: {v}"))
+                .build(),
+            SliderBuilder::new(0.0, 1.0)
+                .at(140.0, 120.0)
+                .with_width(200.0)
+                .with_value(0.8)
+                .with_value_label()
+                .on_change(|v| println!("brightness: {v:.2}"))
+                .build(),
+            SliderBuilder::new(-1.0, 1.0)
+                .at(140.0, 170.0)
+                .with_width(200.0)
+                .with_step(0.25)
+                .with_value(0.0)
+                .with_value_label()
+                .disabled()
+                .build(),
+        ];
+
+        let sliders = self.sliders.clone();
+        window.on_draw(move |canvas| {
+            for (i, name) in ["Volume", "Brightness", "Balance"].iter().enumerate() {
+                Label::new(*name)
+                    .at(40.0, 70.0 + i as f32 * 50.0)
+                    .with_style(styles::BODY)
+                    .with_color(theme.text_primary)
+                    .render(canvas);
+            }
+            for slider in sliders.lock().unwrap().iter() {
+                slider.render(canvas);
+            }
+        });
+
+        let sliders = self.sliders.clone();
+        let window_clone = window.clone();
+        window.on_pointer_event(move |events| {
+            let mut redraw = false;
+            for slider in sliders.lock().unwrap().iter_mut() {
+                redraw |= slider.handle_pointer_events(events);
+            }
+            if redraw {
+                window_clone.request_frame();
+            }
+        });
+
+        self.window = Some(window);
+        Ok(())
+    }
+
+    fn on_keyboard_event(
+        &mut self,
+        _ctx: &AppContext,
+        key: u32,
+        key_state: wl_keyboard::KeyState,
+        _serial: u32,
+    ) {
+        let mut redraw = false;
+        for slider in self.sliders.lock().unwrap().iter_mut() {
+            redraw |= slider.handle_key(key, key_state);
+        }
+        if redraw {
+            if let Some(window) = self.window.as_ref() {
+                window.request_frame();
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let app = SliderDemoApp {
+        window: None,
+        sliders: Arc::new(Mutex::new(Vec::new())),
+    };
+    AppRunner::new(app).run()?;
+    Ok(())
+}
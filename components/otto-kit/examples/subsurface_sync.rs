@@ -0,0 +1,146 @@
+//! A "video" subsurface playing inside a framed window.
+//!
+//! Every frame the video subsurface and the window frame around it are
+//! drawn with the same frame number. With the subsurface synchronized (the
+//! default here) the video's commit is held until the window commits, so
+//! both numbers always match. Click the window to switch to desync mode and
+//! watch the video run a frame ahead of its frame.
+//!
+//! Run with `cargo run --example subsurface_sync`.
+
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use otto_kit::prelude::*;
+use otto_kit::surfaces::SubsurfaceSurface;
+use smithay_client_toolkit::seat::pointer::PointerEventKind;
+
+const WIDTH: i32 = 480;
+const HEIGHT: i32 = 340;
+const VIDEO_X: i32 = 40;
+const VIDEO_Y: i32 = 40;
+const VIDEO_WIDTH: i32 = 400;
+const VIDEO_HEIGHT: i32 = 225;
+
+struct SubsurfaceSyncApp {
+    window: Option<Window>,
+    video: Option<Rc<SubsurfaceSurface>>,
+}
+
+/// Draw the next video frame, then let the window redraw its frame.
+fn tick(window: Window, video: Rc<SubsurfaceSurface>, frame: Arc<AtomicU64>) {
+    let n = frame.fetch_add(1, Ordering::Relaxed) + 1;
+
+    video.draw(|canvas| draw_video(canvas, n));
+    // The window's redraw commits after the video: in sync mode that
+    // commit is what makes the video frame visible.
+    window.request_frame();
+
+    let next = window.clone();
+    window.request_animation_frame(move || tick(next, video, frame));
+}
+
+fn draw_video(canvas: &Canvas, n: u64) {
+    let (w, h) = (VIDEO_WIDTH as f32, VIDEO_HEIGHT as f32);
+    canvas.clear(Color::from_rgb(16, 16, 24));
+
+    // A bar sweeping across the picture, one step per frame
+    let x = (n % 120) as f32 / 120.0 * w;
+    let paint = Paint::new(
+        skia_safe::Color4f::from(Color::from_rgb(90, 160, 255)),
+        None,
+    );
+    canvas.draw_rect(Rect::from_xywh(x - 6.0, 0.0, 12.0, h), &paint);
+
+    Label::new(format!("video frame {n}"))
+        .at(16.0, h - 20.0)
+        .with_style(styles::BODY)
+        .with_color(Color::WHITE)
+        .render(canvas);
+}
+
+impl App for SubsurfaceSyncApp {
+    fn on_app_ready(&mut self, _ctx: &AppContext) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = AppContext::current_theme();
+        let mut window = Window::new("Subsurface Sync", WIDTH, HEIGHT)?;
+        window.set_background(theme.material_medium);
+
+        let parent = window.wl_surface().ok_or("window has no surface")?;
+        let video = Rc::new(SubsurfaceSurface::new(
+            &parent,
+            VIDEO_X,
+            VIDEO_Y,
+            VIDEO_WIDTH,
+            VIDEO_HEIGHT,
+        )?);
+        video.set_sync();
+
+        let frame = Arc::new(AtomicU64::new(0));
+        let sync = Arc::new(AtomicBool::new(true));
+
+        let (draw_frame, draw_sync) = (frame.clone(), sync.clone());
+        window.on_draw(move |canvas| {
+            let n = draw_frame.load(Ordering::Relaxed);
+            let mode = if draw_sync.load(Ordering::Relaxed) {
+                "sync"
+            } else {
+                "desync"
+            };
+
+            // The frame around the video
+            let mut border = Paint::new(skia_safe::Color4f::from(theme.text_primary), None);
+            border.set_style(skia_safe::PaintStyle::Stroke);
+            border.set_stroke_width(4.0);
+            border.set_anti_alias(true);
+            canvas.draw_rect(
+                Rect::from_xywh(
+                    VIDEO_X as f32 - 2.0,
+                    VIDEO_Y as f32 - 2.0,
+                    VIDEO_WIDTH as f32 + 4.0,
+                    VIDEO_HEIGHT as f32 + 4.0,
+                ),
+                &border,
+            );
+
+            Label::new(format!("window frame {n} · {mode} (click to toggle)"))
+                .at(VIDEO_X as f32, (VIDEO_Y + VIDEO_HEIGHT) as f32 + 30.0)
+                .with_style(styles::BODY)
+                .with_color(theme.text_primary)
+                .render(canvas);
+        });
+
+        let click_video = video.clone();
+        let click_window = window.clone();
+        window.on_pointer_event(move |events| {
+            for event in events {
+                if let PointerEventKind::Press { .. } = event.kind {
+                    if click_video.is_sync() {
+                        click_video.set_desync();
+                        sync.store(false, Ordering::Relaxed);
+                    } else {
+                        click_video.set_sync();
+                        sync.store(true, Ordering::Relaxed);
+                    }
+                    click_window.request_frame();
+                }
+            }
+        });
+
+        tick(window.clone(), video.clone(), frame);
+
+        self.window = Some(window);
+        self.video = Some(video);
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let app = SubsurfaceSyncApp {
+        window: None,
+        video: None,
+    };
+    AppRunner::new(app).run()?;
+    Ok(())
+}
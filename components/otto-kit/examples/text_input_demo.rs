@@ -0,0 +1,128 @@
+use std::sync::{Arc, Mutex};
+
+use otto_kit::components::text_input::CARET_BLINK_INTERVAL;
+use otto_kit::prelude::*;
+use smithay_client_toolkit::seat::pointer::{PointerEventKind, BTN_LEFT};
+use wayland_client::protocol::wl_keyboard;
+
+struct TextInputDemoApp {
+    window: Option<Window>,
+    input: Arc<Mutex<TextInput>>,
+}
+
+impl App for TextInputDemoApp {
+    fn on_app_ready(&mut self, _ctx: &AppContext) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = AppContext::current_theme();
+        let mut window = Window::new("TextInput Demo", 480, 220)?;
+        window.set_background(theme.material_medium);
+
+        let submitted = Arc::new(Mutex::new(String::new()));
+        let submitted_clone = submitted.clone();
+        *self.input.lock().unwrap() = TextInput::new()
+            .at(40.0, 90.0)
+            .with_size(400.0, 0.0)
+            .with_placeholder("Search apps…")
+            .on_change(|text| println!("changed: {text}"))
+            .on_submit(move |text| {
+                *submitted_clone.lock().unwrap() = text.to_string();
+            });
+
+        let input = self.input.clone();
+        window.on_draw(move |canvas| {
+            Label::new("TextInput Demo")
+                .at(40.0, 40.0)
+                .with_style(styles::TITLE_2_EMPHASIZED)
+                .with_color(theme.text_primary)
+                .render(canvas);
+            input.lock().unwrap().render(canvas);
+            Label::new(format!("Submitted: {}", submitted.lock().unwrap()))
+                .at(40.0, 150.0)
+                .with_style(styles::CALLOUT)
+                .with_color(theme.text_secondary)
+                .render(canvas);
+        });
+
+        // Click to place the caret, drag to select
+        let input = self.input.clone();
+        let window_clone = window.clone();
+        let mut pressed = false;
+        window.on_pointer_event(move |events| {
+            let mut input = input.lock().unwrap();
+            let mut redraw = false;
+            for event in events {
+                let (x, y) = (event.position.0 as f32, event.position.1 as f32);
+                match event.kind {
+                    PointerEventKind::Press { button, .. } if button == BTN_LEFT => {
+                        pressed = true;
+                        redraw |= input.press_at(x, y, AppContext::modifiers().shift);
+                    }
+                    PointerEventKind::Release { button, .. } if button == BTN_LEFT => {
+                        pressed = false;
+                    }
+                    PointerEventKind::Motion { .. } if pressed => {
+                        redraw |= input.drag_to(x);
+                    }
+                    _ => {}
+                }
+            }
+            if redraw {
+                window_clone.request_frame();
+            }
+        });
+
+        self.window = Some(window);
+        Ok(())
+    }
+
+    fn on_keyboard_event(
+        &mut self,
+        _ctx: &AppContext,
+        _key: u32,
+        key_state: wl_keyboard::KeyState,
+        _serial: u32,
+    ) {
+        let handled = self.input.lock().unwrap().handle_key(
+            AppContext::keysym(),
+            key_state,
+            AppContext::modifiers(),
+        );
+        if handled {
+            self.redraw();
+        }
+    }
+
+    fn on_text_input(&mut self, _ctx: &AppContext, text: &str) {
+        if self.input.lock().unwrap().handle_text(text) {
+            self.redraw();
+        }
+    }
+
+    fn on_update(&mut self, _ctx: &AppContext) {
+        // Keep the caret blinking
+        if self.input.lock().unwrap().is_focused() {
+            self.redraw();
+        }
+    }
+
+    fn idle_timeout(&self) -> Option<std::time::Duration> {
+        Some(CARET_BLINK_INTERVAL)
+    }
+}
+
+impl TextInputDemoApp {
+    fn redraw(&self) {
+        if let Some(window) = self.window.as_ref() {
+            window.request_frame();
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let app = TextInputDemoApp {
+        window: None,
+        input: Arc::new(Mutex::new(TextInput::new())),
+    };
+    AppRunner::new(app).run()?;
+    Ok(())
+}
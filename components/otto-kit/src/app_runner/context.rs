@@ -2,15 +2,20 @@
 
 use super::{App, AppData};
 use crate::protocols::otto_surface_style_manager_v1;
+use crate::surfaces::popup::PopupGeometry;
 use smithay_client_toolkit::{
     compositor::CompositorState,
+    data_device_manager::DataDeviceManagerState,
     output::OutputState,
-    seat::SeatState,
-    shell::xdg::{window::WindowConfigure, XdgShell},
+    seat::{
+        keyboard::{Keysym, Modifiers},
+        SeatState,
+    },
+    shell::xdg::{popup::PopupConfigure, window::WindowConfigure, XdgShell},
     shm::Shm,
 };
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::sync::{LazyLock, RwLock};
 use wayland_client::backend::ObjectId;
@@ -29,7 +34,9 @@ thread_local! {
     #[allow(clippy::type_complexity)]
     static FRAME_REQUEST_FN: RefCell<Option<Box<dyn Fn(&wl_surface::WlSurface)>>> = const { RefCell::new(None) };
     static CURRENT_CONFIGURE: RefCell<Option<(ObjectId, WindowConfigure, u32)>> = const { RefCell::new(None) };
-    static WINDOWS: RefCell<Vec<crate::components::window::Window>> = const { RefCell::new(Vec::new()) };
+    static WINDOWS: RefCell<HashMap<ObjectId, crate::components::window::Window>> = RefCell::new(HashMap::new());
+    static KEYBOARD_FOCUS: RefCell<Option<ObjectId>> = const { RefCell::new(None) };
+    static EXIT_REQUESTED: Cell<bool> = const { Cell::new(false) };
 }
 
 // -- Callback registries --
@@ -38,10 +45,15 @@ thread_local! {
     static CONFIGURE_HANDLERS: RefCell<Vec<Box<dyn FnMut()>>> = const { RefCell::new(Vec::new()) };
     #[allow(clippy::type_complexity)]
     static POINTER_CALLBACKS: RefCell<Vec<Box<dyn FnMut(&[smithay_client_toolkit::seat::pointer::PointerEvent])>>> = const { RefCell::new(Vec::new()) };
+    #[allow(clippy::type_complexity)]
+    static TOUCH_CALLBACKS: RefCell<Vec<Box<dyn FnMut(&[crate::input::TouchEvent])>>> = const { RefCell::new(Vec::new()) };
     static FRAME_CALLBACKS: RefCell<HashMap<ObjectId, Box<dyn FnMut()>>> = RefCell::new(HashMap::new());
     #[allow(clippy::type_complexity)]
+    static ANIMATION_FRAME_CALLBACKS: RefCell<HashMap<ObjectId, Vec<Box<dyn FnOnce()>>>> = RefCell::new(HashMap::new());
+    #[allow(clippy::type_complexity)]
     static POPUP_CONFIGURE_CALLBACKS: RefCell<HashMap<ObjectId, Box<dyn FnOnce(u32)>>> = RefCell::new(HashMap::new());
     static POPUP_DONE_CALLBACKS: RefCell<HashMap<ObjectId, Box<dyn FnOnce()>>> = RefCell::new(HashMap::new());
+    static POPUP_GEOMETRIES: RefCell<HashMap<ObjectId, PopupGeometry>> = RefCell::new(HashMap::new());
     #[allow(clippy::type_complexity)]
     static LAYER_SHELL_CONFIGURE_CALLBACKS: RefCell<HashMap<ObjectId, Box<dyn FnMut(i32, i32, u32)>>> = RefCell::new(HashMap::new());
     static TRANSACTION_COMPLETION_CALLBACKS: RefCell<HashMap<ObjectId, Box<dyn FnOnce()>>> = RefCell::new(HashMap::new());
@@ -54,6 +66,13 @@ thread_local! {
     static LAST_POINTER_ENTER_SERIAL: RefCell<u32> = const { RefCell::new(0) };
 }
 
+// -- Keyboard state --
+
+thread_local! {
+    static MODIFIERS: RefCell<Modifiers> = RefCell::new(Modifiers::default());
+    static KEYSYM: Cell<Keysym> = const { Cell::new(Keysym::NoSymbol) };
+}
+
 // -- Rendering state --
 
 thread_local! {
@@ -118,6 +137,7 @@ pub struct AppContextData {
     pub subcompositor: Option<wayland_client::protocol::wl_subcompositor::WlSubcompositor>,
     pub otto_dock_manager: Option<crate::protocols::otto_dock_manager_v1::OttoDockManagerV1>,
     pub cursor_shape_manager: Option<wayland_protocols::wp::cursor_shape::v1::client::wp_cursor_shape_manager_v1::WpCursorShapeManagerV1>,
+    pub data_device_manager: Option<DataDeviceManagerState>,
     pub display_ptr: *mut std::ffi::c_void,
 }
 
@@ -207,6 +227,13 @@ impl<'a> AppContext<'a> {
         })
     }
 
+    pub fn data_device_manager() -> Option<&'static DataDeviceManagerState> {
+        Self::with_global(|ctx| unsafe {
+            ctx.data_device_manager_ref()
+                .map(|r| &*(r as *const DataDeviceManagerState))
+        })
+    }
+
     pub fn display_ptr() -> *mut std::ffi::c_void {
         Self::with_global(|ctx| ctx.display_ptr_ref())
     }
@@ -249,6 +276,10 @@ impl<'a> AppContext<'a> {
         self.data.otto_dock_manager.as_ref()
     }
 
+    pub fn data_device_manager_ref(&self) -> Option<&DataDeviceManagerState> {
+        self.data.data_device_manager.as_ref()
+    }
+
     pub fn display_ptr_ref(&self) -> *mut std::ffi::c_void {
         self.data.display_ptr
     }
@@ -407,6 +438,29 @@ impl<'a> AppContext<'a> {
         Theme::for_scheme(crate::color_scheme::current_color_scheme())
     }
 
+    // ========================================================================
+    // Keyboard state
+    // ========================================================================
+
+    /// Modifiers currently held (shift, ctrl, alt, logo)
+    pub fn modifiers() -> Modifiers {
+        MODIFIERS.with(|m| *m.borrow())
+    }
+
+    pub(crate) fn set_modifiers(modifiers: Modifiers) {
+        MODIFIERS.with(|m| *m.borrow_mut() = modifiers);
+    }
+
+    /// Keysym of the key being reported to `App::on_keyboard_event`, as
+    /// translated by the keymap
+    pub fn keysym() -> Keysym {
+        KEYSYM.with(|k| k.get())
+    }
+
+    pub(crate) fn set_keysym(keysym: Keysym) {
+        KEYSYM.with(|k| k.set(keysym));
+    }
+
     // ========================================================================
     // Cursor shape (wp_cursor_shape_v1)
     // ========================================================================
@@ -454,6 +508,16 @@ impl<'a> AppContext<'a> {
         CURRENT_CONFIGURE.with(|cfg| cfg.borrow().clone())
     }
 
+    /// The configure being dispatched, when it is for `surface`.
+    pub fn current_configure_for(
+        surface: &wl_surface::WlSurface,
+    ) -> Option<(WindowConfigure, u32)> {
+        use wayland_client::Proxy;
+
+        let (surface_id, configure, serial) = Self::current_surface_configure()?;
+        (surface_id == surface.id()).then_some((configure, serial))
+    }
+
     pub fn register_configure_handler<F>(handler: F)
     where
         F: FnMut() + 'static,
@@ -472,12 +536,82 @@ impl<'a> AppContext<'a> {
         });
     }
 
+    pub fn register_touch_callback<F>(callback: F)
+    where
+        F: FnMut(&[crate::input::TouchEvent]) + 'static,
+    {
+        TOUCH_CALLBACKS.with(|callbacks| {
+            callbacks.borrow_mut().push(Box::new(callback));
+        });
+    }
+
+    /// Track `window` so it receives the events of its surface.
     pub fn register_window(window: crate::components::window::Window) {
+        use wayland_client::Proxy;
+
+        let Some(surface) = window.wl_surface() else {
+            return;
+        };
         WINDOWS.with(|windows| {
-            windows.borrow_mut().push(window);
+            windows.borrow_mut().insert(surface.id(), window);
         });
     }
 
+    /// Stop tracking the window of `surface_id`; returns how many remain.
+    pub(crate) fn unregister_window(surface_id: &ObjectId) -> usize {
+        KEYBOARD_FOCUS.with(|focus| {
+            let mut focus = focus.borrow_mut();
+            if focus.as_ref() == Some(surface_id) {
+                *focus = None;
+            }
+        });
+        WINDOWS.with(|windows| {
+            let mut windows = windows.borrow_mut();
+            windows.remove(surface_id);
+            windows.len()
+        })
+    }
+
+    /// The open window whose surface is `surface`
+    pub fn window_for_surface(
+        surface: &wl_surface::WlSurface,
+    ) -> Option<crate::components::window::Window> {
+        use wayland_client::Proxy;
+
+        WINDOWS.with(|windows| windows.borrow().get(&surface.id()).cloned())
+    }
+
+    /// All open windows
+    pub fn windows() -> Vec<crate::components::window::Window> {
+        WINDOWS.with(|windows| windows.borrow().values().cloned().collect())
+    }
+
+    /// The open window that has keyboard focus
+    pub fn keyboard_focus_window() -> Option<crate::components::window::Window> {
+        let focus = KEYBOARD_FOCUS.with(|focus| focus.borrow().clone())?;
+        WINDOWS.with(|windows| windows.borrow().get(&focus).cloned())
+    }
+
+    pub(crate) fn set_keyboard_focus(surface_id: Option<ObjectId>) {
+        KEYBOARD_FOCUS.with(|focus| *focus.borrow_mut() = surface_id);
+    }
+
+    /// Leave the event loop at the end of the current iteration.
+    pub fn request_exit() {
+        EXIT_REQUESTED.with(|exit| exit.set(true));
+    }
+
+    pub(crate) fn take_exit_request() -> bool {
+        EXIT_REQUESTED.with(|exit| exit.replace(false))
+    }
+
+    /// Drop the frame callbacks and drop target of a destroyed surface.
+    pub(crate) fn forget_surface(surface_id: &ObjectId) {
+        FRAME_CALLBACKS.with(|callbacks| callbacks.borrow_mut().remove(surface_id));
+        ANIMATION_FRAME_CALLBACKS.with(|callbacks| callbacks.borrow_mut().remove(surface_id));
+        Self::unregister_drop_target(surface_id);
+    }
+
     pub fn register_popup_configure_callback<F>(surface_id: ObjectId, callback: F)
     where
         F: FnOnce(u32) + 'static,
@@ -550,6 +684,31 @@ impl<'a> AppContext<'a> {
         Self::request_frame(surface);
     }
 
+    /// Run `callback` once, when the compositor signals that it is a good
+    /// time to draw the next frame of `surface`.
+    ///
+    /// The frame request is sent with the next commit of the surface, so
+    /// call this before drawing (or commit the surface afterwards).
+    /// Callbacks requested for the same frame run in order; request another
+    /// one from inside the callback to keep animating.
+    pub fn request_animation_frame<F>(surface: &wl_surface::WlSurface, callback: F)
+    where
+        F: FnOnce() + 'static,
+    {
+        use wayland_client::Proxy;
+
+        let first = ANIMATION_FRAME_CALLBACKS.with(|callbacks| {
+            let mut callbacks = callbacks.borrow_mut();
+            let pending = callbacks.entry(surface.id()).or_default();
+            pending.push(Box::new(callback));
+            pending.len() == 1
+        });
+        // One wl_surface.frame per surface and frame is enough.
+        if first && !Self::has_frame_callback(&surface.id()) {
+            Self::request_frame(surface);
+        }
+    }
+
     /// Wake the main event loop from any thread.
     ///
     /// Background tasks (tokio, threads) should call this after updating
@@ -599,6 +758,16 @@ impl<'a> AppContext<'a> {
         });
     }
 
+    pub(crate) fn dispatch_animation_frame_callbacks(surface_id: &ObjectId) {
+        // Take the callbacks first so they can request the next frame.
+        let pending = ANIMATION_FRAME_CALLBACKS
+            .with(|callbacks| callbacks.borrow_mut().remove(surface_id))
+            .unwrap_or_default();
+        for callback in pending {
+            callback();
+        }
+    }
+
     pub(crate) fn set_current_configure(id: ObjectId, configure: WindowConfigure, serial: u32) {
         CURRENT_CONFIGURE.with(|cfg| {
             *cfg.borrow_mut() = Some((id, configure, serial));
@@ -629,14 +798,77 @@ impl<'a> AppContext<'a> {
         });
     }
 
-    pub(crate) fn dispatch_popup_configure(surface_id: &ObjectId, serial: u32) {
+    pub(crate) fn dispatch_touch_callbacks(events: &[crate::input::TouchEvent]) {
+        TOUCH_CALLBACKS.with(|callbacks| {
+            for callback in callbacks.borrow_mut().iter_mut() {
+                callback(events);
+            }
+        });
+    }
+
+    /// Hand each window the pointer events on its own surface.
+    pub(crate) fn dispatch_window_pointer_events(
+        events: &[smithay_client_toolkit::seat::pointer::PointerEvent],
+    ) {
+        use wayland_client::Proxy;
+
+        for window in Self::windows() {
+            let Some(surface) = window.wl_surface() else {
+                continue;
+            };
+            let ours: Vec<_> = events
+                .iter()
+                .filter(|e| e.surface.id() == surface.id())
+                .cloned()
+                .collect();
+            if !ours.is_empty() {
+                window.dispatch_pointer_events(&ours);
+            }
+        }
+    }
+
+    /// Hand each window the touch events of points that went down on it.
+    pub(crate) fn dispatch_window_touch_events(events: &[crate::input::TouchEvent]) {
+        use wayland_client::Proxy;
+
+        for window in Self::windows() {
+            let Some(surface) = window.wl_surface() else {
+                continue;
+            };
+            let ours: Vec<_> = events
+                .iter()
+                .filter(|e| e.surface.id() == surface.id())
+                .cloned()
+                .collect();
+            if !ours.is_empty() {
+                window.dispatch_touch_events(&ours);
+            }
+        }
+    }
+
+    pub(crate) fn dispatch_popup_configure(surface_id: &ObjectId, config: &PopupConfigure) {
+        POPUP_GEOMETRIES.with(|geometries| {
+            geometries
+                .borrow_mut()
+                .insert(surface_id.clone(), PopupGeometry::from(config));
+        });
         POPUP_CONFIGURE_CALLBACKS.with(|callbacks| {
             if let Some(callback) = callbacks.borrow_mut().remove(surface_id) {
-                callback(serial);
+                callback(config.serial);
             }
         });
     }
 
+    /// Where the compositor last placed a popup, after flipping or sliding
+    /// it to fit the screen
+    pub fn popup_geometry(surface_id: &ObjectId) -> Option<PopupGeometry> {
+        POPUP_GEOMETRIES.with(|geometries| geometries.borrow().get(surface_id).copied())
+    }
+
+    pub(crate) fn forget_popup_geometry(surface_id: &ObjectId) {
+        let _ = POPUP_GEOMETRIES.try_with(|geometries| geometries.borrow_mut().remove(surface_id));
+    }
+
     pub(crate) fn dispatch_popup_done(surface_id: &ObjectId) {
         POPUP_DONE_CALLBACKS.with(|callbacks| {
             if let Some(callback) = callbacks.borrow_mut().remove(surface_id) {
@@ -671,11 +903,10 @@ impl<'a> AppContext<'a> {
     // ========================================================================
 
     pub fn update_windows() {
-        WINDOWS.with(|windows| {
-            for window in windows.borrow_mut().iter_mut() {
-                window.update();
-            }
-        });
+        // A window may close while drawing; don't hold the registry.
+        for window in Self::windows() {
+            window.update();
+        }
     }
 
     // ========================================================================
@@ -702,13 +933,19 @@ impl<'a> AppContext<'a> {
         FRAME_REQUEST_FN.with(|f| *f.borrow_mut() = None);
         CURRENT_CONFIGURE.with(|cfg| *cfg.borrow_mut() = None);
         WINDOWS.with(|w| w.borrow_mut().clear());
+        KEYBOARD_FOCUS.with(|f| *f.borrow_mut() = None);
+        EXIT_REQUESTED.with(|e| e.set(false));
 
         // Clean up callback registries
         CONFIGURE_HANDLERS.with(|h| h.borrow_mut().clear());
         POINTER_CALLBACKS.with(|c| c.borrow_mut().clear());
+        TOUCH_CALLBACKS.with(|c| c.borrow_mut().clear());
+        super::dnd::clear();
         FRAME_CALLBACKS.with(|c| c.borrow_mut().clear());
+        ANIMATION_FRAME_CALLBACKS.with(|c| c.borrow_mut().clear());
         POPUP_CONFIGURE_CALLBACKS.with(|c| c.borrow_mut().clear());
         POPUP_DONE_CALLBACKS.with(|c| c.borrow_mut().clear());
+        POPUP_GEOMETRIES.with(|c| c.borrow_mut().clear());
         LAYER_SHELL_CONFIGURE_CALLBACKS.with(|c| c.borrow_mut().clear());
         TRANSACTION_COMPLETION_CALLBACKS.with(|c| c.borrow_mut().clear());
 
@@ -0,0 +1,510 @@
+//! Drag and drop - `wl_data_device` source and target handling
+//!
+//! Start a drag with [`AppContext::start_drag`], optionally with a [`DragIcon`]
+//! the compositor moves with the pointer. Accept drops on a surface with
+//! [`AppContext::register_drop_target`].
+//!
+//! Dropped data is read on a background thread, so a drag between two
+//! surfaces of the same app doesn't block the event loop. The drop callback
+//! runs on the main thread once all the data arrived.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use smithay_client_toolkit::data_device_manager::{
+    data_device::{DataDevice, DataDeviceHandler},
+    data_offer::{DataOfferHandler, DragOffer},
+    data_source::{DataSourceHandler, DragSource},
+    WritePipe,
+};
+use wayland_client::{
+    backend::ObjectId,
+    protocol::{
+        wl_data_device::WlDataDevice, wl_data_device_manager::DndAction,
+        wl_data_source::WlDataSource, wl_seat, wl_surface,
+    },
+    Connection, Proxy, QueueHandle,
+};
+
+use super::{App, AppContext, AppData};
+use crate::rendering::SkiaSurface;
+
+type DropCallback = Box<dyn FnMut(&DropEvent)>;
+type DropResult = Arc<Mutex<Option<std::io::Result<Vec<u8>>>>>;
+
+thread_local! {
+    static DATA_DEVICE: RefCell<Option<DataDevice>> = const { RefCell::new(None) };
+    static OUTGOING_DRAG: RefCell<Option<OutgoingDrag>> = const { RefCell::new(None) };
+    static DROP_TARGETS: RefCell<HashMap<ObjectId, DropTarget>> = RefCell::new(HashMap::new());
+    static PENDING_DROPS: RefCell<Vec<PendingDrop>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Data dropped on a surface
+#[derive(Debug, Clone)]
+pub struct DropEvent {
+    /// Surface the data was dropped on
+    pub surface: wl_surface::WlSurface,
+    /// Surface-local drop position
+    pub position: (f64, f64),
+    /// Mime type the data was received as
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Surface shown under the pointer while dragging
+///
+/// The compositor moves the icon with the pointer, its top-left corner at
+/// the pointer position. It is destroyed when the drop finishes or the drag
+/// is cancelled.
+pub struct DragIcon {
+    wl_surface: wl_surface::WlSurface,
+    skia_surface: Option<Rc<SkiaSurface>>,
+    draw_fn: Option<Box<dyn FnOnce(&skia_safe::Canvas)>>,
+}
+
+impl DragIcon {
+    /// Create a drag icon of `width` x `height` logical pixels drawn by `draw_fn`
+    pub fn new<F>(width: i32, height: i32, draw_fn: F) -> Self
+    where
+        F: FnOnce(&skia_safe::Canvas) + 'static,
+    {
+        let wl_surface = AppContext::compositor_state().create_surface(AppContext::queue_handle());
+        // SkiaSurface draws at 2x, like the other surfaces
+        let buffer_scale = 2;
+        wl_surface.set_buffer_scale(buffer_scale);
+        let skia_surface = AppContext::skia_context(|ctx| {
+            ctx.create_surface(&wl_surface, width * buffer_scale, height * buffer_scale)
+        })
+        .and_then(|result| {
+            result
+                .map_err(|e| tracing::warn!("failed to create drag icon surface: {e}"))
+                .ok()
+        })
+        .map(Rc::new);
+        Self {
+            wl_surface,
+            skia_surface,
+            draw_fn: Some(Box::new(draw_fn)),
+        }
+    }
+
+    /// Draw and commit the icon, once it has its role
+    fn draw(&mut self) {
+        let (Some(surface), Some(draw_fn)) = (self.skia_surface.as_ref(), self.draw_fn.take())
+        else {
+            return;
+        };
+        AppContext::skia_context(|ctx| {
+            surface.draw(ctx, draw_fn);
+            surface.swap_buffers(ctx);
+            surface.commit();
+        });
+    }
+}
+
+impl Drop for DragIcon {
+    fn drop(&mut self) {
+        self.skia_surface.take();
+        self.wl_surface.destroy();
+    }
+}
+
+/// A drag started by this app
+struct OutgoingDrag {
+    source: DragSource,
+    mime_type: String,
+    data: Arc<Vec<u8>>,
+    icon: Option<DragIcon>,
+}
+
+/// A surface accepting drops
+struct DropTarget {
+    mime_types: Vec<String>,
+    callback: DropCallback,
+}
+
+impl DropTarget {
+    /// The first offered mime type the target accepts
+    fn accepted_mime_type(&self, offered: &[String]) -> Option<String> {
+        self.mime_types
+            .iter()
+            .find(|mime_type| offered.contains(mime_type))
+            .cloned()
+    }
+}
+
+/// A drop whose data is still being read
+struct PendingDrop {
+    offer: DragOffer,
+    surface: wl_surface::WlSurface,
+    position: (f64, f64),
+    mime_type: String,
+    result: DropResult,
+}
+
+impl AppContext<'_> {
+    /// Start dragging `data` as `mime_type` from `surface`
+    ///
+    /// `serial` is the serial of the button press that started the drag.
+    /// Returns `false` when the compositor does not support drag and drop.
+    pub fn start_drag(
+        surface: &wl_surface::WlSurface,
+        serial: u32,
+        mime_type: &str,
+        data: Vec<u8>,
+        icon: Option<DragIcon>,
+    ) -> bool {
+        let Some(manager) = Self::data_device_manager() else {
+            return false;
+        };
+        let source = DATA_DEVICE.with(|device| {
+            let device = device.borrow();
+            let device = device.as_ref()?;
+            let source = manager.create_drag_and_drop_source(
+                Self::queue_handle(),
+                [mime_type],
+                DndAction::Copy | DndAction::Move,
+            );
+            source.start_drag(
+                device,
+                surface,
+                icon.as_ref().map(|icon| &icon.wl_surface),
+                serial,
+            );
+            Some(source)
+        });
+        let Some(source) = source else {
+            return false;
+        };
+
+        let mut icon = icon;
+        if let Some(icon) = icon.as_mut() {
+            icon.draw();
+        }
+        // A new drag replaces one that never finished
+        if let Some(previous) = OUTGOING_DRAG.with(|drag| drag.borrow_mut().take()) {
+            previous.source.inner().destroy();
+        }
+        OUTGOING_DRAG.with(|drag| {
+            *drag.borrow_mut() = Some(OutgoingDrag {
+                source,
+                mime_type: mime_type.to_string(),
+                data: Arc::new(data),
+                icon,
+            });
+        });
+        true
+    }
+
+    /// Accept drops of `mime_types` on the surface `surface_id`
+    ///
+    /// Mime types are listed by preference: the first one the drag offers is
+    /// received. Registering a surface again replaces its target.
+    pub fn register_drop_target<F>(surface_id: ObjectId, mime_types: &[&str], callback: F)
+    where
+        F: FnMut(&DropEvent) + 'static,
+    {
+        DROP_TARGETS.with(|targets| {
+            targets.borrow_mut().insert(
+                surface_id,
+                DropTarget {
+                    mime_types: mime_types.iter().map(|m| m.to_string()).collect(),
+                    callback: Box::new(callback),
+                },
+            );
+        });
+    }
+
+    /// Stop accepting drops on the surface `surface_id`
+    pub fn unregister_drop_target(surface_id: &ObjectId) {
+        DROP_TARGETS.with(|targets| {
+            targets.borrow_mut().remove(surface_id);
+        });
+    }
+}
+
+/// Create the data device of `seat`, unless there is one already
+pub(super) fn ensure_data_device<A: App + 'static>(
+    qh: &QueueHandle<AppData<A>>,
+    seat: &wl_seat::WlSeat,
+) {
+    let Some(manager) = AppContext::data_device_manager() else {
+        return;
+    };
+    DATA_DEVICE.with(|device| {
+        let mut device = device.borrow_mut();
+        if device.is_none() {
+            *device = Some(manager.get_data_device(qh, seat));
+        }
+    });
+}
+
+/// Hand the drops whose data arrived to their targets
+pub(super) fn dispatch_pending_drops() {
+    let finished: Vec<PendingDrop> = PENDING_DROPS.with(|pending| {
+        let mut pending = pending.borrow_mut();
+        let (finished, waiting) = pending
+            .drain(..)
+            .partition(|drop| drop.result.lock().unwrap().is_some());
+        *pending = waiting;
+        finished
+    });
+
+    for drop in finished {
+        drop.offer.finish();
+        drop.offer.destroy();
+        let data = match drop.result.lock().unwrap().take() {
+            Some(Ok(data)) => data,
+            Some(Err(e)) => {
+                tracing::warn!("failed to read dropped data: {e}");
+                continue;
+            }
+            None => continue,
+        };
+        let event = DropEvent {
+            surface: drop.surface,
+            position: drop.position,
+            mime_type: drop.mime_type,
+            data,
+        };
+
+        // Take the target out while its callback runs, so the callback can
+        // register targets itself.
+        let surface_id = event.surface.id();
+        let Some(mut target) =
+            DROP_TARGETS.with(|targets| targets.borrow_mut().remove(&surface_id))
+        else {
+            continue;
+        };
+        (target.callback)(&event);
+        DROP_TARGETS.with(|targets| {
+            targets.borrow_mut().entry(surface_id).or_insert(target);
+        });
+    }
+}
+
+pub(super) fn clear() {
+    OUTGOING_DRAG.with(|drag| drag.borrow_mut().take());
+    DROP_TARGETS.with(|targets| targets.borrow_mut().clear());
+    PENDING_DROPS.with(|pending| pending.borrow_mut().clear());
+    DATA_DEVICE.with(|device| device.borrow_mut().take());
+}
+
+fn current_drag_offer() -> Option<DragOffer> {
+    DATA_DEVICE.with(|device| device.borrow().as_ref()?.data().drag_offer())
+}
+
+/// The mime type the target under `offer` accepts, if any
+fn accepted_mime_type(offer: &DragOffer) -> Option<String> {
+    DROP_TARGETS.with(|targets| {
+        let targets = targets.borrow();
+        let target = targets.get(&offer.surface.id())?;
+        offer.with_mime_types(|offered| target.accepted_mime_type(offered))
+    })
+}
+
+/// Drop the outgoing drag: its source and its icon
+fn end_outgoing_drag(source: &WlDataSource) {
+    OUTGOING_DRAG.with(|drag| {
+        let mut drag = drag.borrow_mut();
+        if drag
+            .as_ref()
+            .is_some_and(|drag| drag.source.inner() == source)
+        {
+            drag.take();
+        }
+    });
+    source.destroy();
+}
+
+impl<A: App + 'static> DataDeviceHandler for AppData<A> {
+    fn enter(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _data_device: &WlDataDevice,
+        _x: f64,
+        _y: f64,
+        _surface: &wl_surface::WlSurface,
+    ) {
+        let Some(offer) = current_drag_offer() else {
+            return;
+        };
+        let mime_type = accepted_mime_type(&offer);
+        if mime_type.is_some() {
+            offer.set_actions(DndAction::Copy | DndAction::Move, DndAction::Copy);
+        } else {
+            offer.set_actions(DndAction::empty(), DndAction::empty());
+        }
+        offer.accept_mime_type(offer.serial, mime_type);
+    }
+
+    fn leave(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _data_device: &WlDataDevice) {}
+
+    fn motion(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _data_device: &WlDataDevice,
+        _x: f64,
+        _y: f64,
+    ) {
+    }
+
+    fn selection(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _data_device: &WlDataDevice,
+    ) {
+    }
+
+    fn drop_performed(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _data_device: &WlDataDevice,
+    ) {
+        let Some(offer) = current_drag_offer() else {
+            return;
+        };
+        let Some(mime_type) = accepted_mime_type(&offer) else {
+            offer.destroy();
+            return;
+        };
+        let mut pipe = match offer.receive(mime_type.clone()) {
+            Ok(pipe) => pipe,
+            Err(e) => {
+                tracing::warn!("failed to receive dropped data: {e}");
+                offer.destroy();
+                return;
+            }
+        };
+
+        let result: DropResult = Arc::new(Mutex::new(None));
+        let thread_result = result.clone();
+        std::thread::spawn(move || {
+            let mut data = Vec::new();
+            let read = pipe.read_to_end(&mut data).map(|_| data);
+            *thread_result.lock().unwrap() = Some(read);
+            AppContext::request_wakeup();
+        });
+
+        PENDING_DROPS.with(|pending| {
+            pending.borrow_mut().push(PendingDrop {
+                surface: offer.surface.clone(),
+                position: (offer.x, offer.y),
+                offer,
+                mime_type,
+                result,
+            });
+        });
+    }
+}
+
+impl<A: App + 'static> DataOfferHandler for AppData<A> {
+    fn source_actions(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _offer: &mut DragOffer,
+        _actions: DndAction,
+    ) {
+    }
+
+    fn selected_action(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _offer: &mut DragOffer,
+        _actions: DndAction,
+    ) {
+    }
+}
+
+impl<A: App + 'static> DataSourceHandler for AppData<A> {
+    fn accept_mime(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _source: &WlDataSource,
+        _mime: Option<String>,
+    ) {
+    }
+
+    fn send_request(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        source: &WlDataSource,
+        mime: String,
+        mut fd: WritePipe,
+    ) {
+        let data = OUTGOING_DRAG.with(|drag| {
+            drag.borrow()
+                .as_ref()
+                .filter(|drag| drag.source.inner() == source && drag.mime_type == mime)
+                .map(|drag| drag.data.clone())
+        });
+        let Some(data) = data else {
+            return;
+        };
+        // Write off the main thread: the reader may be this app.
+        std::thread::spawn(move || {
+            if let Err(e) = fd.write_all(&data) {
+                tracing::warn!("failed to send dragged data: {e}");
+            }
+        });
+    }
+
+    fn cancelled(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, source: &WlDataSource) {
+        end_outgoing_drag(source);
+    }
+
+    fn dnd_dropped(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, source: &WlDataSource) {
+        // The icon goes away with the drop; the source stays until the
+        // target finished reading.
+        OUTGOING_DRAG.with(|drag| {
+            if let Some(drag) = drag.borrow_mut().as_mut() {
+                if drag.source.inner() == source {
+                    drag.icon.take();
+                }
+            }
+        });
+    }
+
+    fn dnd_finished(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, source: &WlDataSource) {
+        end_outgoing_drag(source);
+    }
+
+    fn action(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _source: &WlDataSource,
+        _action: DndAction,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drop_target_prefers_its_own_order() {
+        let target = DropTarget {
+            mime_types: vec!["text/uri-list".into(), "text/plain".into()],
+            callback: Box::new(|_| {}),
+        };
+        let offered = vec!["text/plain".to_string(), "text/uri-list".to_string()];
+        assert_eq!(
+            target.accepted_mime_type(&offered),
+            Some("text/uri-list".to_string())
+        );
+        assert_eq!(target.accepted_mime_type(&["image/png".to_string()]), None);
+    }
+}
@@ -4,10 +4,11 @@
 //! for creating window-based applications.
 
 pub mod context;
+mod dnd;
 mod handlers;
 
 pub use context::AppContext;
-use wayland_client::backend::ObjectId;
+pub use dnd::{DragIcon, DropEvent};
 
 use crate::protocols::{
     otto_dock_item_v1, otto_dock_manager_v1, otto_style_transaction_v1,
@@ -15,12 +16,14 @@ use crate::protocols::{
 };
 use smithay_client_toolkit::{
     compositor::{CompositorHandler, CompositorState},
+    data_device_manager::DataDeviceManagerState,
     output::{OutputHandler, OutputState},
     registry::{ProvidesRegistryState, RegistryState},
     registry_handlers,
     seat::{
         keyboard::{KeyboardHandler, Keysym, Modifiers},
         pointer::{PointerEvent, PointerHandler},
+        touch::TouchHandler,
         Capability, SeatHandler, SeatState,
     },
     shell::xdg::{
@@ -32,7 +35,7 @@ use smithay_client_toolkit::{
 };
 use wayland_client::{
     globals::registry_queue_init,
-    protocol::{wl_keyboard, wl_output, wl_pointer, wl_seat, wl_surface},
+    protocol::{wl_keyboard, wl_output, wl_pointer, wl_seat, wl_surface, wl_touch},
     Connection, Dispatch, QueueHandle,
 };
 use wayland_protocols_wlr::layer_shell::v1::client::{
@@ -40,6 +43,7 @@ use wayland_protocols_wlr::layer_shell::v1::client::{
 };
 
 // Re-export context items
+use crate::input::{ScrollEvent, TouchEvent, TouchEventKind};
 use context::AppContextData;
 
 /// The App trait - implement this to create a runnable application
@@ -87,6 +91,12 @@ pub trait App {
         // Default: do nothing
     }
 
+    /// Called with the text a key press produces, after `on_keyboard_event`
+    /// Override this to feed text fields; the text is already translated by the keymap
+    fn on_text_input(&mut self, _ctx: &AppContext, _text: &str) {
+        // Default: do nothing
+    }
+
     /// Called when keyboard focus is lost from a surface
     /// Override this to handle focus loss (e.g., close menus)
     fn on_keyboard_leave(&mut self, _ctx: &AppContext, _surface: &wl_surface::WlSurface) {
@@ -98,6 +108,23 @@ pub trait App {
         // Default: do nothing
     }
 
+    /// Called when the pointer scrolls over a surface
+    /// Override this to scroll content (wheel, touchpad, horizontal scroll)
+    fn on_scroll(
+        &mut self,
+        _ctx: &AppContext,
+        _surface: &wl_surface::WlSurface,
+        _scroll: &ScrollEvent,
+    ) {
+        // Default: do nothing
+    }
+
+    /// Called when a touch point goes down, moves, goes up or is cancelled
+    /// Events carry the touch point id, so several fingers can be tracked at once
+    fn on_touch_event(&mut self, _ctx: &AppContext, _events: &[TouchEvent]) {
+        // Default: do nothing
+    }
+
     /// Called when the compositor requests to show a dock menu at coordinates (x, y)
     fn on_dock_menu_requested(&mut self, _ctx: &AppContext, _x: i32, _y: i32) {
         // Default: do nothing
@@ -177,6 +204,20 @@ impl App for DefaultApp {
     fn on_pointer_event(&mut self, ctx: &AppContext, events: &[PointerEvent]) {
         self.inner.on_pointer_event(ctx, events)
     }
+    fn on_text_input(&mut self, ctx: &AppContext, text: &str) {
+        self.inner.on_text_input(ctx, text)
+    }
+    fn on_scroll(
+        &mut self,
+        ctx: &AppContext,
+        surface: &wl_surface::WlSurface,
+        scroll: &ScrollEvent,
+    ) {
+        self.inner.on_scroll(ctx, surface, scroll)
+    }
+    fn on_touch_event(&mut self, ctx: &AppContext, events: &[TouchEvent]) {
+        self.inner.on_touch_event(ctx, events)
+    }
     fn on_update(&mut self, ctx: &AppContext) {
         self.inner.on_update(ctx)
     }
@@ -272,12 +313,13 @@ impl<A: App + 'static> AppRunnerWithType<A> {
         let seat_state = SeatState::new(&globals, &qh);
         let output_state = OutputState::new(&globals, &qh);
         let registry_state = RegistryState::new(&globals);
-        let surface_style_manager = globals.bind(&qh, 1..=1, ()).ok();
+        let surface_style_manager = globals.bind(&qh, 1..=2, ()).ok();
         let wlr_layer_shell: Option<ZwlrLayerShellV1> = globals.bind(&qh, 1..=4, ()).ok();
-        let otto_dock_manager = globals.bind(&qh, 1..=1, ()).ok();
+        let otto_dock_manager = globals.bind(&qh, 1..=2, ()).ok();
         let subcompositor = globals.bind(&qh, 1..=1, ()).ok();
         let cursor_shape_manager: Option<wayland_protocols::wp::cursor_shape::v1::client::wp_cursor_shape_manager_v1::WpCursorShapeManagerV1> =
             globals.bind(&qh, 1..=2, ()).ok();
+        let data_device_manager = DataDeviceManagerState::bind(&globals, &qh).ok();
 
         // Get display pointer for creating surfaces
         let display_ptr = conn.backend().display_ptr() as *mut std::ffi::c_void;
@@ -296,6 +338,7 @@ impl<A: App + 'static> AppRunnerWithType<A> {
             subcompositor,
             otto_dock_manager,
             cursor_shape_manager,
+            data_device_manager,
             display_ptr,
         });
 
@@ -304,6 +347,7 @@ impl<A: App + 'static> AppRunnerWithType<A> {
             app: self.app,
             registry_state,
             context_data: context,
+            touch_points: std::collections::HashMap::new(),
             exit: false,
         };
 
@@ -362,17 +406,23 @@ impl<A: App + 'static> AppRunnerInitialized<A> {
         while !self.app_data.exit {
             // 1. Drain any events already queued (no I/O).
             self.event_queue.dispatch_pending(&mut self.app_data)?;
-            self.conn.flush()?;
 
             AppContext::update_windows();
+            dnd::dispatch_pending_drops();
 
             let ctx = AppContext::new(&self.app_data.context_data);
             self.app_data.app.on_update(&ctx);
 
+            if AppContext::take_exit_request() {
+                self.app_data.exit = true;
+            }
             if self.app_data.exit {
                 break;
             }
 
+            // Send the commits and frame requests made above before blocking.
+            self.conn.flush()?;
+
             // 2. Prepare to block for the next batch of events.
             let guard = loop {
                 match self.event_queue.prepare_read() {
@@ -440,6 +490,8 @@ pub struct AppData<A: App + 'static> {
     app: A,
     registry_state: RegistryState,
     pub(super) context_data: Box<AppContextData>, // Box prevents movement after pointer is stored
+    /// Touch points currently down: surface they went down on and last position
+    touch_points: std::collections::HashMap<i32, (wl_surface::WlSurface, (f64, f64))>,
     exit: bool,
 }
 
@@ -464,13 +516,14 @@ impl<A: App + 'static> CompositorHandler for AppData<A> {
     ) {
         use wayland_client::Proxy;
 
-        let has_callback = AppContext::has_frame_callback(&surface.id());
-
-        if has_callback {
+        // Continuous `on_frame` callbacks keep the loop going; one-shot
+        // animation frames request their own next frame if they need one.
+        if AppContext::has_frame_callback(&surface.id()) {
             AppContext::request_frame(surface);
         }
 
         AppContext::dispatch_frame_callback(&surface.id());
+        AppContext::dispatch_animation_frame_callbacks(&surface.id());
     }
 
     fn transform_changed(
@@ -532,7 +585,22 @@ impl<A: App + 'static> OutputHandler for AppData<A> {
 }
 
 impl<A: App + 'static> WindowHandler for AppData<A> {
-    fn request_close(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _window: &StkWindow) {
+    fn request_close(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, window: &StkWindow) {
+        use smithay_client_toolkit::shell::WaylandSurface;
+
+        // An otto-kit Window closes on its own; the app is only asked when
+        // it is the last one.
+        if let Some(window) = AppContext::window_for_surface(window.wl_surface()) {
+            if !window.close_requested() {
+                return;
+            }
+            if AppContext::windows().len() == 1 && !self.app.on_close() {
+                return;
+            }
+            window.close();
+            return;
+        }
+
         // Ask the app if it wants to close
         if self.app.on_close() {
             self.exit = true;
@@ -543,11 +611,19 @@ impl<A: App + 'static> WindowHandler for AppData<A> {
         &mut self,
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
-        _window: &StkWindow,
+        window: &StkWindow,
         configure: WindowConfigure,
         serial: u32,
     ) {
-        AppContext::set_current_configure(ObjectId::null(), configure.clone(), serial);
+        use smithay_client_toolkit::shell::WaylandSurface;
+        use wayland_client::Proxy;
+
+        if let Some(window) = AppContext::window_for_surface(window.wl_surface()) {
+            window.handle_configure(configure.clone(), serial);
+        }
+
+        // Handlers match the surface id to only react to their own window's configure
+        AppContext::set_current_configure(window.wl_surface().id(), configure.clone(), serial);
         AppContext::dispatch_configure_handlers();
 
         let ctx = AppContext::new(&self.context_data);
@@ -578,13 +654,20 @@ impl<A: App + 'static> SeatHandler for AppData<A> {
                 .get_keyboard(qh, &seat, None)
                 .is_err()
         {
-            eprintln!("Failed to create keyboard");
+            tracing::warn!("failed to create keyboard");
         }
 
-        if capability == Capability::Pointer
-            && self.context_data.seat_state.get_pointer(qh, &seat).is_err()
+        if capability == Capability::Pointer {
+            if self.context_data.seat_state.get_pointer(qh, &seat).is_err() {
+                tracing::warn!("failed to create pointer");
+            }
+            dnd::ensure_data_device(qh, &seat);
+        }
+
+        if capability == Capability::Touch
+            && self.context_data.seat_state.get_touch(qh, &seat).is_err()
         {
-            eprintln!("Failed to create pointer");
+            tracing::warn!("failed to create touch");
         }
     }
 
@@ -613,11 +696,14 @@ impl<A: App + 'static> KeyboardHandler for AppData<A> {
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
         _keyboard: &wl_keyboard::WlKeyboard,
-        _surface: &wl_surface::WlSurface,
+        surface: &wl_surface::WlSurface,
         _serial: u32,
         _raw: &[u32],
         _keysyms: &[Keysym],
     ) {
+        use wayland_client::Proxy;
+
+        AppContext::set_keyboard_focus(Some(surface.id()));
     }
 
     fn leave(
@@ -628,6 +714,7 @@ impl<A: App + 'static> KeyboardHandler for AppData<A> {
         surface: &wl_surface::WlSurface,
         _serial: u32,
     ) {
+        AppContext::set_keyboard_focus(None);
         let ctx = AppContext::new(&self.context_data);
         self.app.on_keyboard_leave(&ctx, surface);
     }
@@ -640,9 +727,20 @@ impl<A: App + 'static> KeyboardHandler for AppData<A> {
         serial: u32,
         event: smithay_client_toolkit::seat::keyboard::KeyEvent,
     ) {
+        AppContext::set_keysym(event.keysym);
         let ctx = AppContext::new(&self.context_data);
         self.app
             .on_keyboard_event(&ctx, event.raw_code, wl_keyboard::KeyState::Pressed, serial);
+        if let Some(text) = event.utf8.as_deref() {
+            self.app.on_text_input(&ctx, text);
+        }
+
+        if let Some(window) = AppContext::keyboard_focus_window() {
+            window.dispatch_key(event.raw_code, wl_keyboard::KeyState::Pressed, serial);
+            if let Some(text) = event.utf8.as_deref() {
+                window.dispatch_text(text);
+            }
+        }
     }
 
     fn release_key(
@@ -653,6 +751,7 @@ impl<A: App + 'static> KeyboardHandler for AppData<A> {
         serial: u32,
         event: smithay_client_toolkit::seat::keyboard::KeyEvent,
     ) {
+        AppContext::set_keysym(event.keysym);
         let ctx = AppContext::new(&self.context_data);
         self.app.on_keyboard_event(
             &ctx,
@@ -660,6 +759,10 @@ impl<A: App + 'static> KeyboardHandler for AppData<A> {
             wl_keyboard::KeyState::Released,
             serial,
         );
+
+        if let Some(window) = AppContext::keyboard_focus_window() {
+            window.dispatch_key(event.raw_code, wl_keyboard::KeyState::Released, serial);
+        }
     }
 
     fn update_modifiers(
@@ -668,9 +771,10 @@ impl<A: App + 'static> KeyboardHandler for AppData<A> {
         _qh: &QueueHandle<Self>,
         _keyboard: &wl_keyboard::WlKeyboard,
         _serial: u32,
-        _modifiers: Modifiers,
+        modifiers: Modifiers,
         _layout: u32,
     ) {
+        AppContext::set_modifiers(modifiers);
     }
 }
 
@@ -695,8 +799,127 @@ impl<A: App + 'static> PointerHandler for AppData<A> {
         }
 
         AppContext::dispatch_pointer_callbacks(events);
+        AppContext::dispatch_window_pointer_events(events);
         let ctx = AppContext::new(&self.context_data);
         self.app.on_pointer_event(&ctx, events);
+        for event in events {
+            if let Some(scroll) = ScrollEvent::from_pointer_event(event) {
+                self.app.on_scroll(&ctx, &event.surface, &scroll);
+            }
+        }
+    }
+}
+
+impl<A: App + 'static> TouchHandler for AppData<A> {
+    #[allow(clippy::too_many_arguments)]
+    fn down(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _touch: &wl_touch::WlTouch,
+        serial: u32,
+        time: u32,
+        surface: wl_surface::WlSurface,
+        id: i32,
+        position: (f64, f64),
+    ) {
+        self.touch_points.insert(id, (surface.clone(), position));
+        self.dispatch_touch(&[TouchEvent {
+            id,
+            surface,
+            position,
+            kind: TouchEventKind::Down { serial, time },
+        }]);
+    }
+
+    fn up(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _touch: &wl_touch::WlTouch,
+        serial: u32,
+        time: u32,
+        id: i32,
+    ) {
+        let Some((surface, position)) = self.touch_points.remove(&id) else {
+            return;
+        };
+        self.dispatch_touch(&[TouchEvent {
+            id,
+            surface,
+            position,
+            kind: TouchEventKind::Up { serial, time },
+        }]);
+    }
+
+    fn motion(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _touch: &wl_touch::WlTouch,
+        time: u32,
+        id: i32,
+        position: (f64, f64),
+    ) {
+        let Some((surface, last_position)) = self.touch_points.get_mut(&id) else {
+            return;
+        };
+        *last_position = position;
+        let surface = surface.clone();
+        self.dispatch_touch(&[TouchEvent {
+            id,
+            surface,
+            position,
+            kind: TouchEventKind::Motion { time },
+        }]);
+    }
+
+    fn shape(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _touch: &wl_touch::WlTouch,
+        _id: i32,
+        _major: f64,
+        _minor: f64,
+    ) {
+    }
+
+    fn orientation(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _touch: &wl_touch::WlTouch,
+        _id: i32,
+        _orientation: f64,
+    ) {
+    }
+
+    fn cancel(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _touch: &wl_touch::WlTouch) {
+        let mut events: Vec<TouchEvent> = self
+            .touch_points
+            .drain()
+            .map(|(id, (surface, position))| TouchEvent {
+                id,
+                surface,
+                position,
+                kind: TouchEventKind::Cancel,
+            })
+            .collect();
+        if events.is_empty() {
+            return;
+        }
+        events.sort_by_key(|event| event.id);
+        self.dispatch_touch(&events);
+    }
+}
+
+impl<A: App + 'static> AppData<A> {
+    fn dispatch_touch(&mut self, events: &[TouchEvent]) {
+        AppContext::dispatch_touch_callbacks(events);
+        AppContext::dispatch_window_touch_events(events);
+        let ctx = AppContext::new(&self.context_data);
+        self.app.on_touch_event(&ctx, events);
     }
 }
 
@@ -709,7 +932,7 @@ impl<A: App + 'static> PopupHandler for AppData<A> {
         config: PopupConfigure,
     ) {
         use wayland_client::Proxy;
-        AppContext::dispatch_popup_configure(&popup.wl_surface().id(), config.serial);
+        AppContext::dispatch_popup_configure(&popup.wl_surface().id(), &config);
     }
 
     fn done(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, popup: &Popup) {
@@ -791,6 +1014,8 @@ smithay_client_toolkit::delegate_shm!(@<A: App + 'static> AppData<A>);
 smithay_client_toolkit::delegate_seat!(@<A: App + 'static> AppData<A>);
 smithay_client_toolkit::delegate_keyboard!(@<A: App + 'static> AppData<A>);
 smithay_client_toolkit::delegate_pointer!(@<A: App + 'static> AppData<A>);
+smithay_client_toolkit::delegate_touch!(@<A: App + 'static> AppData<A>);
+smithay_client_toolkit::delegate_data_device!(@<A: App + 'static> AppData<A>);
 smithay_client_toolkit::delegate_xdg_shell!(@<A: App + 'static> AppData<A>);
 smithay_client_toolkit::delegate_xdg_window!(@<A: App + 'static> AppData<A>);
 smithay_client_toolkit::delegate_xdg_popup!(@<A: App + 'static> AppData<A>);
@@ -1,11 +1,15 @@
-//! XDG color scheme detection via the freedesktop Settings portal.
+//! XDG color scheme and accent color detection via the freedesktop Settings
+//! portal.
 //!
-//! Queries `org.freedesktop.appearance color-scheme` on startup and watches for
-//! `SettingChanged` signals, keeping a global atomic up to date.
+//! Queries `org.freedesktop.appearance color-scheme` and `accent-color` on
+//! startup and watches for `SettingChanged` signals, keeping global atomics up
+//! to date.
 //!
-//! Any otto-kit app gets automatic light/dark switching via `AppContext::current_theme()`.
+//! Any otto-kit app gets automatic light/dark switching and the system accent
+//! via `AppContext::current_theme()`.
 
-use std::sync::atomic::{AtomicU32, Ordering};
+use skia_safe::Color;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::LazyLock;
 use zbus::zvariant::{OwnedValue, Value};
 
@@ -14,11 +18,25 @@ use crate::theme::ColorScheme;
 /// Raw portal value stored atomically.  0 = no preference, 1 = dark, 2 = light.
 static COLOR_SCHEME_VALUE: LazyLock<AtomicU32> = LazyLock::new(|| AtomicU32::new(0));
 
+/// Accent color stored atomically: 0 when unset, else `1 << 32 | argb`.
+static ACCENT_COLOR_VALUE: AtomicU64 = AtomicU64::new(0);
+
 /// Read the current color scheme.
 pub fn current_color_scheme() -> ColorScheme {
     ColorScheme::from_portal_value(COLOR_SCHEME_VALUE.load(Ordering::Relaxed))
 }
 
+/// Read the system accent color, `None` when the portal doesn't set one.
+pub fn current_accent_color() -> Option<Color> {
+    let value = ACCENT_COLOR_VALUE.load(Ordering::Relaxed);
+    (value != 0).then(|| Color::new(value as u32))
+}
+
+fn store_accent_color(color: Option<Color>) {
+    let value = color.map_or(0, |c| 1 << 32 | u32::from(c) as u64);
+    ACCENT_COLOR_VALUE.store(value, Ordering::Relaxed);
+}
+
 /// Spawn a background tokio task that:
 /// 1. Reads the initial `color-scheme` from the XDG Settings portal.
 /// 2. Subscribes to `SettingChanged` and updates the atomic on every change.
@@ -50,6 +68,35 @@ fn extract_u32(val: Value<'_>) -> Option<u32> {
     }
 }
 
+/// Extract an `(ddd)` RGB color from a possibly variant-wrapped `Value`.
+///
+/// Channels go from 0.0 to 1.0; the portal sends values out of that range
+/// when no accent is set.
+fn extract_rgb(val: Value<'_>) -> Option<Color> {
+    match val {
+        Value::Structure(fields) => {
+            let channels: Vec<f64> = fields
+                .fields()
+                .iter()
+                .filter_map(|field| match field {
+                    Value::F64(c) => Some(*c),
+                    _ => None,
+                })
+                .collect();
+            let [r, g, b] = channels[..] else {
+                return None;
+            };
+            if ![r, g, b].iter().all(|c| (0.0..=1.0).contains(c)) {
+                return None;
+            }
+            let channel = |c: f64| (c * 255.0).round() as u8;
+            Some(Color::from_rgb(channel(r), channel(g), channel(b)))
+        }
+        Value::Value(inner) => extract_rgb(*inner),
+        _ => None,
+    }
+}
+
 async fn run_watcher() -> Result<(), zbus::Error> {
     use zbus::{proxy, Connection};
 
@@ -83,6 +130,17 @@ async fn run_watcher() -> Result<(), zbus::Error> {
         }
         Err(e) => tracing::debug!("color-scheme read failed (portal absent?): {e}"),
     }
+    match proxy
+        .read("org.freedesktop.appearance", "accent-color")
+        .await
+    {
+        Ok(owned) => {
+            let accent = extract_rgb(owned.into());
+            tracing::debug!("accent-color initial value: {accent:?}");
+            store_accent_color(accent);
+        }
+        Err(e) => tracing::debug!("accent-color read failed: {e}"),
+    }
 
     // Watch for changes via zbus signal stream.
     let mut stream = proxy.receive_setting_changed().await?;
@@ -92,11 +150,22 @@ async fn run_watcher() -> Result<(), zbus::Error> {
             break;
         };
         let args = signal.args()?;
-        if args.namespace == "org.freedesktop.appearance" && args.key == "color-scheme" {
-            if let Some(v) = extract_u32(args.value) {
-                tracing::debug!("color-scheme changed to: {v}");
-                COLOR_SCHEME_VALUE.store(v, Ordering::Relaxed);
+        if args.namespace != "org.freedesktop.appearance" {
+            continue;
+        }
+        match args.key {
+            "color-scheme" => {
+                if let Some(v) = extract_u32(args.value) {
+                    tracing::debug!("color-scheme changed to: {v}");
+                    COLOR_SCHEME_VALUE.store(v, Ordering::Relaxed);
+                }
+            }
+            "accent-color" => {
+                let accent = extract_rgb(args.value);
+                tracing::debug!("accent-color changed to: {accent:?}");
+                store_accent_color(accent);
             }
+            _ => {}
         }
     }
 
@@ -1,10 +1,18 @@
 use skia_safe::{Canvas, Color, Paint, RRect, Rect};
+use smithay_client_toolkit::seat::pointer::{PointerEvent, PointerEventKind, BTN_LEFT};
+use wayland_client::protocol::wl_keyboard;
 
 use crate::common::Renderable;
 use crate::components::icon::Icon;
 use crate::components::label::{Label, TextAlign};
+use crate::input::{keycodes, TouchEvent, TouchEventKind};
+use crate::theme::Theme;
 use crate::typography::TextStyle;
 
+const DANGER_RED: Color = Color::from_rgb(239, 68, 68);
+
+type ClickCallback = Box<dyn FnMut() + Send>;
+
 /// Button state for visual feedback
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ButtonState {
@@ -34,6 +42,13 @@ pub enum ButtonVariant {
 }
 
 /// A flexible button component that can contain a label, icon, or both
+///
+/// The app owns the button and forwards input to it:
+/// - `handle_pointer_events()` from `Window::on_pointer_event`
+/// - `handle_touch_events()` from `Window::on_touch_event`
+/// - `handle_key()` from `App::on_keyboard_event` (Enter/Space while focused)
+///
+/// Each handler returns `true` when the button needs a redraw.
 pub struct Button {
     pub x: f32,
     pub y: f32,
@@ -57,6 +72,18 @@ pub struct Button {
     icon_size: f32,
     icon_spacing: f32,
     text_style: TextStyle,
+    theme: Theme,
+
+    // Interaction
+    focused: bool,
+    hovered: bool,
+    /// The left button went down inside and has not been released
+    pointer_pressed: bool,
+    /// Enter or Space is held while focused
+    key_pressed: bool,
+    /// The finger that went down inside, and whether it is still inside
+    touch: Option<(i32, bool)>,
+    on_click: Option<ClickCallback>,
 }
 
 impl Button {
@@ -81,6 +108,13 @@ impl Button {
             icon_size: 20.0,
             icon_spacing: 8.0,
             text_style: crate::typography::styles::BODY,
+            theme: Theme::for_scheme(crate::color_scheme::current_color_scheme()),
+            focused: false,
+            hovered: false,
+            pointer_pressed: false,
+            touch: None,
+            key_pressed: false,
+            on_click: None,
         }
     }
 
@@ -105,6 +139,13 @@ impl Button {
             icon_size: 20.0,
             icon_spacing: 8.0,
             text_style: crate::typography::styles::BODY,
+            theme: Theme::for_scheme(crate::color_scheme::current_color_scheme()),
+            focused: false,
+            hovered: false,
+            pointer_pressed: false,
+            touch: None,
+            key_pressed: false,
+            on_click: None,
         }
     }
 
@@ -218,127 +259,296 @@ impl Button {
         self
     }
 
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn focused(mut self) -> Self {
+        self.focused = true;
+        self
+    }
+
+    /// Called when the button is activated by a click, a tap, Enter or Space
+    pub fn on_click<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut() + Send + 'static,
+    {
+        self.on_click = Some(Box::new(callback));
+        self
+    }
+
     pub fn build(self) -> Self {
         self
     }
 
+    // === State ===
+
+    pub fn state(&self) -> ButtonState {
+        self.state
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.state == ButtonState::Disabled
+    }
+
+    pub fn variant(&self) -> ButtonVariant {
+        self.variant
+    }
+
+    /// Change the style, e.g. to show the selected option of a group
+    pub fn set_variant(&mut self, variant: ButtonVariant) {
+        self.variant = variant;
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.pointer_pressed = false;
+        self.touch = None;
+        self.key_pressed = false;
+        self.state = if disabled {
+            ButtonState::Disabled
+        } else {
+            ButtonState::Normal
+        };
+        self.refresh_state();
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        if !focused && self.key_pressed {
+            self.key_pressed = false;
+            self.refresh_state();
+        }
+    }
+
+    /// Whether (x, y) is inside the rounded background
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        let (width, height) = self.calculate_dimensions();
+        rounded_rect_contains((self.x, self.y, width, height), self.corner_radius, x, y)
+    }
+
+    // === Input ===
+
+    /// Pointer moved to (x, y): update the hover state
+    pub fn pointer_motion(&mut self, x: f32, y: f32) -> bool {
+        self.hovered = self.contains(x, y);
+        self.refresh_state()
+    }
+
+    /// Pointer left the surface
+    pub fn pointer_leave(&mut self) -> bool {
+        self.hovered = false;
+        self.pointer_pressed = false;
+        self.refresh_state()
+    }
+
+    /// Left button pressed at (x, y)
+    pub fn pointer_press(&mut self, x: f32, y: f32) -> bool {
+        self.hovered = self.contains(x, y);
+        if self.hovered && !self.is_disabled() {
+            self.pointer_pressed = true;
+        }
+        self.refresh_state()
+    }
+
+    /// Left button released at (x, y). Clicks when the press started
+    /// inside and the release is still inside.
+    pub fn pointer_release(&mut self, x: f32, y: f32) -> bool {
+        let was_pressed = std::mem::take(&mut self.pointer_pressed);
+        self.hovered = self.contains(x, y);
+        let clicked = was_pressed && self.hovered && !self.is_disabled();
+        if clicked {
+            self.click();
+        }
+        self.refresh_state() || clicked
+    }
+
+    /// Feed a frame of pointer events from `Window::on_pointer_event`
+    pub fn handle_pointer_events(&mut self, events: &[PointerEvent]) -> bool {
+        let mut redraw = false;
+        for event in events {
+            let (x, y) = (event.position.0 as f32, event.position.1 as f32);
+            redraw |= match event.kind {
+                PointerEventKind::Enter { .. } | PointerEventKind::Motion { .. } => {
+                    self.pointer_motion(x, y)
+                }
+                PointerEventKind::Leave { .. } => self.pointer_leave(),
+                PointerEventKind::Press { button, .. } if button == BTN_LEFT => {
+                    self.pointer_press(x, y)
+                }
+                PointerEventKind::Release { button, .. } if button == BTN_LEFT => {
+                    self.pointer_release(x, y)
+                }
+                _ => false,
+            };
+        }
+        redraw
+    }
+
+    /// Finger `id` touched (x, y). A touch inside presses the button unless
+    /// another finger already does.
+    pub fn touch_down(&mut self, id: i32, x: f32, y: f32) -> bool {
+        if self.touch.is_none() && !self.is_disabled() && self.contains(x, y) {
+            self.touch = Some((id, true));
+        }
+        self.refresh_state()
+    }
+
+    /// Finger `id` moved to (x, y): the button shows pressed while it is inside
+    pub fn touch_motion(&mut self, id: i32, x: f32, y: f32) -> bool {
+        let inside = self.contains(x, y);
+        match self.touch.as_mut() {
+            Some((touch_id, touch_inside)) if *touch_id == id => *touch_inside = inside,
+            _ => return false,
+        }
+        self.refresh_state()
+    }
+
+    /// Finger `id` lifted at (x, y). Clicks when it went down inside and
+    /// lifts inside.
+    pub fn touch_up(&mut self, id: i32, x: f32, y: f32) -> bool {
+        if !matches!(self.touch, Some((touch_id, _)) if touch_id == id) {
+            return false;
+        }
+        self.touch = None;
+        let clicked = self.contains(x, y) && !self.is_disabled();
+        if clicked {
+            self.click();
+        }
+        self.refresh_state() || clicked
+    }
+
+    /// The compositor took the touch sequence over: release without clicking
+    pub fn touch_cancel(&mut self) -> bool {
+        self.touch = None;
+        self.refresh_state()
+    }
+
+    /// Feed a frame of touch events from `Window::on_touch_event`
+    pub fn handle_touch_events(&mut self, events: &[TouchEvent]) -> bool {
+        let mut redraw = false;
+        for event in events {
+            let (x, y) = (event.position.0 as f32, event.position.1 as f32);
+            redraw |= match event.kind {
+                TouchEventKind::Down { .. } => self.touch_down(event.id, x, y),
+                TouchEventKind::Motion { .. } => self.touch_motion(event.id, x, y),
+                TouchEventKind::Up { .. } => self.touch_up(event.id, x, y),
+                TouchEventKind::Cancel => self.touch_cancel(),
+            };
+        }
+        redraw
+    }
+
+    /// Enter or Space while focused: show pressed on key down, click on key up
+    pub fn handle_key(&mut self, key: u32, key_state: wl_keyboard::KeyState) -> bool {
+        if !self.focused || self.is_disabled() {
+            return false;
+        }
+        if key != keycodes::ENTER && key != keycodes::SPACE {
+            return false;
+        }
+        match key_state {
+            wl_keyboard::KeyState::Pressed => {
+                self.key_pressed = true;
+                self.refresh_state()
+            }
+            wl_keyboard::KeyState::Released if self.key_pressed => {
+                self.key_pressed = false;
+                self.click();
+                self.refresh_state();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // === Internal ===
+
     /// Get the default colors for the current variant and state
     fn get_colors(&self) -> (Color, Color, Option<Color>) {
         // Returns: (background, text, border)
+        let theme = &self.theme;
+        let disabled = (theme.fill_quaternary, theme.text_tertiary, None);
         match self.variant {
             ButtonVariant::Primary => match self.state {
-                ButtonState::Normal => (
-                    Color::from_rgb(59, 130, 246), // Blue
-                    Color::WHITE,
-                    None,
-                ),
-                ButtonState::Hovered => (
-                    Color::from_rgb(37, 99, 235), // Darker blue
-                    Color::WHITE,
-                    None,
-                ),
-                ButtonState::Pressed => (
-                    Color::from_rgb(29, 78, 216), // Even darker blue
-                    Color::WHITE,
-                    None,
-                ),
-                ButtonState::Disabled => (
-                    Color::from_rgb(156, 163, 175), // Gray
-                    Color::from_rgb(209, 213, 219),
-                    None,
-                ),
+                ButtonState::Normal => (theme.accent, Color::WHITE, None),
+                ButtonState::Hovered => (shade(theme.accent, 0.1), Color::WHITE, None),
+                ButtonState::Pressed => (shade(theme.accent, 0.2), Color::WHITE, None),
+                ButtonState::Disabled => disabled,
             },
             ButtonVariant::Secondary => match self.state {
-                ButtonState::Normal => (
-                    Color::from_rgb(229, 231, 235),
-                    Color::from_rgb(55, 65, 81),
-                    None,
-                ),
-                ButtonState::Hovered => (
-                    Color::from_rgb(209, 213, 219),
-                    Color::from_rgb(31, 41, 55),
-                    None,
-                ),
-                ButtonState::Pressed => (
-                    Color::from_rgb(156, 163, 175),
-                    Color::from_rgb(17, 24, 39),
-                    None,
-                ),
-                ButtonState::Disabled => (
-                    Color::from_rgb(243, 244, 246),
-                    Color::from_rgb(156, 163, 175),
-                    None,
-                ),
+                ButtonState::Normal => (theme.fill_secondary, theme.text_primary, None),
+                ButtonState::Hovered => (theme.fill_tertiary, theme.text_primary, None),
+                ButtonState::Pressed => (theme.fill_primary, theme.text_primary, None),
+                ButtonState::Disabled => disabled,
             },
             ButtonVariant::Outline => match self.state {
                 ButtonState::Normal => (
                     Color::TRANSPARENT,
-                    Color::from_rgb(55, 65, 81),
-                    Some(Color::from_rgb(209, 213, 219)),
+                    theme.text_primary,
+                    Some(theme.fill_primary),
                 ),
                 ButtonState::Hovered => (
-                    Color::from_rgb(249, 250, 251),
-                    Color::from_rgb(31, 41, 55),
-                    Some(Color::from_rgb(156, 163, 175)),
+                    theme.fill_quaternary,
+                    theme.text_primary,
+                    Some(theme.fill_primary),
                 ),
                 ButtonState::Pressed => (
-                    Color::from_rgb(243, 244, 246),
-                    Color::from_rgb(17, 24, 39),
-                    Some(Color::from_rgb(107, 114, 128)),
+                    theme.fill_tertiary,
+                    theme.text_primary,
+                    Some(theme.fill_primary),
                 ),
                 ButtonState::Disabled => (
                     Color::TRANSPARENT,
-                    Color::from_rgb(156, 163, 175),
-                    Some(Color::from_rgb(229, 231, 235)),
+                    theme.text_tertiary,
+                    Some(theme.fill_secondary),
                 ),
             },
             ButtonVariant::Ghost => match self.state {
-                ButtonState::Normal => (
-                    Color::TRANSPARENT,
-                    Color::from_argb(0x80, 0x00, 0x00, 0x00), // text_secondary - 50% opacity black
-                    None,
-                ),
-                ButtonState::Hovered => (
-                    Color::from_rgb(249, 250, 251),
-                    Color::from_argb(0xD9, 0x00, 0x00, 0x00), // text_primary on hover
-                    None,
-                ),
-                ButtonState::Pressed => (
-                    Color::from_rgb(243, 244, 246),
-                    Color::from_argb(0xD9, 0x00, 0x00, 0x00), // text_primary when pressed
-                    None,
-                ),
-                ButtonState::Disabled => (
-                    Color::TRANSPARENT,
-                    Color::from_argb(0x40, 0x00, 0x00, 0x00), // text_tertiary - 25% opacity
-                    None,
-                ),
+                ButtonState::Normal => (Color::TRANSPARENT, theme.text_secondary, None),
+                ButtonState::Hovered => (theme.fill_quaternary, theme.text_primary, None),
+                ButtonState::Pressed => (theme.fill_tertiary, theme.text_primary, None),
+                ButtonState::Disabled => (Color::TRANSPARENT, theme.text_tertiary, None),
             },
+            // The theme has no destructive color, so Danger keeps its own red
             ButtonVariant::Danger => match self.state {
-                ButtonState::Normal => (
-                    Color::from_rgb(239, 68, 68), // Red
-                    Color::WHITE,
-                    None,
-                ),
-                ButtonState::Hovered => (
-                    Color::from_rgb(220, 38, 38), // Darker red
-                    Color::WHITE,
-                    None,
-                ),
-                ButtonState::Pressed => (
-                    Color::from_rgb(185, 28, 28), // Even darker red
-                    Color::WHITE,
-                    None,
-                ),
-                ButtonState::Disabled => (
-                    Color::from_rgb(156, 163, 175),
-                    Color::from_rgb(209, 213, 219),
-                    None,
-                ),
+                ButtonState::Normal => (DANGER_RED, Color::WHITE, None),
+                ButtonState::Hovered => (shade(DANGER_RED, 0.1), Color::WHITE, None),
+                ButtonState::Pressed => (shade(DANGER_RED, 0.2), Color::WHITE, None),
+                ButtonState::Disabled => disabled,
             },
         }
     }
 
+    /// Recompute the visual state from hover, press and key activation.
+    /// Returns `true` when it changed.
+    fn refresh_state(&mut self) -> bool {
+        if self.state == ButtonState::Disabled {
+            return false;
+        }
+        let touch_pressed = matches!(self.touch, Some((_, true)));
+        let state = if (self.pointer_pressed && self.hovered) || self.key_pressed || touch_pressed {
+            ButtonState::Pressed
+        } else if self.hovered {
+            ButtonState::Hovered
+        } else {
+            ButtonState::Normal
+        };
+        let changed = state != self.state;
+        self.state = state;
+        changed
+    }
+
+    fn click(&mut self) {
+        if let Some(callback) = self.on_click.as_mut() {
+            callback();
+        }
+    }
+
     /// Calculate the button dimensions if not explicitly set
     fn calculate_dimensions(&self) -> (f32, f32) {
         if self.width > 0.0 && self.height > 0.0 {
@@ -422,6 +632,18 @@ impl Renderable for Button {
             canvas.draw_rrect(rrect, &border_paint);
         }
 
+        // Focus ring, just outside the background
+        if self.focused {
+            let mut focus_paint = Paint::default();
+            focus_paint.set_color(self.theme.accent);
+            focus_paint.set_style(skia_safe::PaintStyle::Stroke);
+            focus_paint.set_stroke_width(2.0);
+            focus_paint.set_anti_alias(true);
+            let ring = Rect::from_xywh(self.x - 2.0, self.y - 2.0, width + 4.0, height + 4.0);
+            let radius = self.corner_radius + 2.0;
+            canvas.draw_rrect(RRect::new_rect_xy(ring, radius, radius), &focus_paint);
+        }
+
         // Calculate content layout
         let font = self.text_style.font();
         let content_x = self.x + self.padding_horizontal;
@@ -552,3 +774,112 @@ impl Renderable for Button {
         Some(self.calculate_dimensions())
     }
 }
+
+impl Default for Button {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+// Builder alias, matching LabelBuilder
+pub type ButtonBuilder = Button;
+
+/// Darken `color` by `amount` (0.0 - 1.0), keeping its alpha
+fn shade(color: Color, amount: f32) -> Color {
+    let scale = |c: u8| (c as f32 * (1.0 - amount)).round() as u8;
+    Color::from_argb(
+        color.a(),
+        scale(color.r()),
+        scale(color.g()),
+        scale(color.b()),
+    )
+}
+
+/// Hit test against a rect of (x, y, width, height) with rounded corners
+fn rounded_rect_contains(rect: (f32, f32, f32, f32), radius: f32, x: f32, y: f32) -> bool {
+    let (left, top, width, height) = rect;
+    let (right, bottom) = (left + width, top + height);
+    if x < left || x > right || y < top || y > bottom {
+        return false;
+    }
+    let radius = radius.min(width / 2.0).min(height / 2.0);
+    // Distance from the nearest corner circle's center, 0 outside the corners
+    let dx = (left + radius - x).max(x - (right - radius)).max(0.0);
+    let dy = (top + radius - y).max(y - (bottom - radius)).max(0.0);
+    dx * dx + dy * dy <= radius * radius
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rounded_hit_area_excludes_corners() {
+        let rect = (10.0, 10.0, 100.0, 40.0);
+        assert!(rounded_rect_contains(rect, 8.0, 60.0, 30.0));
+        assert!(rounded_rect_contains(rect, 8.0, 10.0, 30.0));
+        assert!(rounded_rect_contains(rect, 8.0, 13.0, 13.0));
+        // The very corner is outside the rounded background
+        assert!(!rounded_rect_contains(rect, 8.0, 10.5, 10.5));
+        assert!(!rounded_rect_contains(rect, 8.0, 109.5, 49.5));
+        assert!(!rounded_rect_contains(rect, 8.0, 5.0, 30.0));
+    }
+
+    #[test]
+    fn test_radius_is_clamped_to_half_height() {
+        // A pill: the ends are half circles
+        let rect = (0.0, 0.0, 100.0, 20.0);
+        assert!(rounded_rect_contains(rect, 50.0, 50.0, 0.0));
+        assert!(rounded_rect_contains(rect, 50.0, 0.0, 10.0));
+        assert!(!rounded_rect_contains(rect, 50.0, 2.0, 2.0));
+    }
+
+    fn counting_button() -> (Button, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        let clicks = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = clicks.clone();
+        let button = Button::new("OK")
+            .at(10.0, 10.0)
+            .with_size(100.0, 40.0)
+            .on_click(move || {
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            });
+        (button, clicks)
+    }
+
+    #[test]
+    fn test_tap_clicks() {
+        let (mut button, clicks) = counting_button();
+        assert!(button.touch_down(3, 50.0, 30.0));
+        assert_eq!(button.state(), ButtonState::Pressed);
+        assert!(button.touch_up(3, 52.0, 31.0));
+        assert_eq!(button.state(), ButtonState::Normal);
+        assert_eq!(clicks.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_touch_dragged_off_does_not_click() {
+        let (mut button, clicks) = counting_button();
+        button.touch_down(1, 50.0, 30.0);
+        assert!(button.touch_motion(1, 200.0, 30.0));
+        assert_eq!(button.state(), ButtonState::Normal);
+        button.touch_up(1, 200.0, 30.0);
+        assert_eq!(clicks.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_other_fingers_and_cancel_do_not_click() {
+        let (mut button, clicks) = counting_button();
+        button.touch_down(1, 50.0, 30.0);
+        button.touch_down(2, 60.0, 30.0);
+        assert!(!button.touch_up(2, 60.0, 30.0));
+        assert!(button.touch_cancel());
+        assert!(!button.touch_up(1, 50.0, 30.0));
+        assert_eq!(clicks.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_shade_keeps_alpha() {
+        let color = shade(Color::from_argb(0x80, 200, 100, 50), 0.5);
+        assert_eq!(color, Color::from_argb(0x80, 100, 50, 25));
+    }
+}
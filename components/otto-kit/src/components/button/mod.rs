@@ -1,4 +1,4 @@
 #![allow(clippy::module_inception)]
 mod button;
 
-pub use button::{Button, ButtonState, ButtonVariant, IconPosition};
+pub use button::{Button, ButtonBuilder, ButtonState, ButtonVariant, IconPosition};
@@ -0,0 +1,405 @@
+use skia_safe::{Canvas, Rect};
+
+use super::stack::StackAlignment;
+use super::traits::{Container, EdgeInsets};
+
+/// A child of a grid and the cells it covers
+struct GridItem {
+    child: Box<dyn Container>,
+    column_span: usize,
+    row_span: usize,
+}
+
+/// A container that lays children out in columns, wrapping onto new rows
+///
+/// Children fill the grid left to right, top to bottom. A child can span
+/// several columns and rows; it takes the first free place it fits in.
+/// Columns share the width equally; each row is as tall as its tallest
+/// child.
+///
+/// # Examples
+///
+/// ```no_run
+/// use otto_kit::components::container::{Grid, StackAlignment};
+///
+/// let mut grid = Grid::new(3)
+///     .with_gap(8.0)
+///     .with_alignment(StackAlignment::Center);
+///
+/// // Add children; a header across the whole row:
+/// // grid.add_spanning(header, 3, 1);
+/// ```
+pub struct Grid {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    columns: usize,
+    alignment: StackAlignment,
+    column_gap: f32,
+    row_gap: f32,
+    padding: EdgeInsets,
+    items: Vec<GridItem>,
+    auto_size: bool,
+}
+
+impl Grid {
+    /// Create a new grid with the given number of columns
+    pub fn new(columns: usize) -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 0.0,
+            height: 0.0,
+            columns: columns.max(1),
+            alignment: StackAlignment::Start,
+            column_gap: 0.0,
+            row_gap: 0.0,
+            padding: EdgeInsets::zero(),
+            items: Vec::new(),
+            auto_size: true,
+        }
+    }
+
+    /// Set the same gap between columns and between rows
+    pub fn with_gap(mut self, gap: f32) -> Self {
+        self.column_gap = gap;
+        self.row_gap = gap;
+        self
+    }
+
+    /// Set the gap between columns
+    pub fn with_column_gap(mut self, gap: f32) -> Self {
+        self.column_gap = gap;
+        self
+    }
+
+    /// Set the gap between rows
+    pub fn with_row_gap(mut self, gap: f32) -> Self {
+        self.row_gap = gap;
+        self
+    }
+
+    /// Set the alignment of children inside their cells
+    pub fn with_alignment(mut self, alignment: StackAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Set padding around children
+    pub fn with_padding(mut self, padding: EdgeInsets) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set a fixed size (disables auto-sizing)
+    pub fn with_size(mut self, width: f32, height: f32) -> Self {
+        self.width = width;
+        self.height = height;
+        self.auto_size = false;
+        self
+    }
+
+    /// Add a child covering one cell
+    pub fn add(&mut self, child: Box<dyn Container>) {
+        self.add_spanning(child, 1, 1);
+    }
+
+    /// Add a child covering `column_span` columns and `row_span` rows
+    pub fn add_spanning(&mut self, child: Box<dyn Container>, column_span: usize, row_span: usize) {
+        self.items.push(GridItem {
+            child,
+            column_span: column_span.clamp(1, self.columns),
+            row_span: row_span.max(1),
+        });
+        self.relayout();
+    }
+
+    /// Recalculate layout for all children
+    pub fn relayout(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        let spans: Vec<(usize, usize)> = self
+            .items
+            .iter()
+            .map(|item| (item.column_span, item.row_span))
+            .collect();
+        let cells = place_items(&spans, self.columns);
+        let row_count = cells
+            .iter()
+            .zip(&spans)
+            .map(|((row, _), (_, rows))| row + rows)
+            .max()
+            .unwrap_or(0);
+
+        // Column width: given by a fixed size, or by the widest child
+        let column_width = if self.auto_size {
+            self.items
+                .iter()
+                .map(|item| {
+                    let gaps = (item.column_span - 1) as f32 * self.column_gap;
+                    (item.child.bounds().width() - gaps) / item.column_span as f32
+                })
+                .fold(0.0, f32::max)
+        } else {
+            let gaps = (self.columns - 1) as f32 * self.column_gap;
+            ((self.width - self.padding.horizontal() - gaps) / self.columns as f32).max(0.0)
+        };
+
+        // Row heights: single-row children first, then grow the last row
+        // of a spanning child until it fits
+        let mut row_heights = vec![0.0_f32; row_count];
+        for (item, (row, _)) in self.items.iter().zip(&cells) {
+            if item.row_span == 1 {
+                row_heights[*row] = row_heights[*row].max(item.child.bounds().height());
+            }
+        }
+        for (item, (row, _)) in self.items.iter().zip(&cells) {
+            if item.row_span > 1 {
+                let rows = *row..row + item.row_span;
+                let spanned: f32 = row_heights[rows.clone()].iter().sum::<f32>()
+                    + (item.row_span - 1) as f32 * self.row_gap;
+                let missing = item.child.bounds().height() - spanned;
+                if missing > 0.0 {
+                    row_heights[rows.end - 1] += missing;
+                }
+            }
+        }
+
+        let content_x = self.x + self.padding.left;
+        let content_y = self.y + self.padding.top;
+        let row_top = |row: usize| {
+            content_y + row_heights[..row].iter().sum::<f32>() + row as f32 * self.row_gap
+        };
+
+        for (item, (row, column)) in self.items.iter_mut().zip(&cells) {
+            let cell_x = content_x + *column as f32 * (column_width + self.column_gap);
+            let cell_y = row_top(*row);
+            let cell_width = column_width * item.column_span as f32
+                + (item.column_span - 1) as f32 * self.column_gap;
+            let cell_height = row_heights[*row..row + item.row_span].iter().sum::<f32>()
+                + (item.row_span - 1) as f32 * self.row_gap;
+
+            let bounds = item.child.bounds();
+            let (child_x, child_y) = match self.alignment {
+                StackAlignment::Start => (cell_x, cell_y),
+                StackAlignment::Center => (
+                    cell_x + (cell_width - bounds.width()) / 2.0,
+                    cell_y + (cell_height - bounds.height()) / 2.0,
+                ),
+                StackAlignment::End => (
+                    cell_x + cell_width - bounds.width(),
+                    cell_y + cell_height - bounds.height(),
+                ),
+                StackAlignment::Stretch => {
+                    item.child.set_size(cell_width, cell_height);
+                    (cell_x, cell_y)
+                }
+            };
+            item.child.set_position(child_x, child_y);
+        }
+
+        // Update grid size if auto-sizing
+        if self.auto_size {
+            self.width = column_width * self.columns as f32
+                + (self.columns - 1) as f32 * self.column_gap
+                + self.padding.horizontal();
+            self.height = row_heights.iter().sum::<f32>()
+                + row_count.saturating_sub(1) as f32 * self.row_gap
+                + self.padding.vertical();
+        }
+    }
+
+    /// Get the number of columns
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Get the gaps between columns and rows
+    pub fn gap(&self) -> (f32, f32) {
+        (self.column_gap, self.row_gap)
+    }
+
+    /// Get the alignment
+    pub fn alignment(&self) -> StackAlignment {
+        self.alignment
+    }
+}
+
+/// The (row, column) each item starts at, filling rows left to right
+///
+/// Like CSS grid auto-placement without `dense`: an item goes to the first
+/// free place at or after the previous item.
+fn place_items(spans: &[(usize, usize)], columns: usize) -> Vec<(usize, usize)> {
+    let mut occupied: Vec<Vec<bool>> = Vec::new();
+    let mut cursor = (0, 0);
+    let mut cells = Vec::with_capacity(spans.len());
+
+    for &(column_span, row_span) in spans {
+        let column_span = column_span.clamp(1, columns);
+        let (mut row, mut column) = cursor;
+        loop {
+            if column + column_span > columns {
+                row += 1;
+                column = 0;
+                continue;
+            }
+            let free = (row..row + row_span).all(|r| {
+                (column..column + column_span)
+                    .all(|c| !occupied.get(r).is_some_and(|cells| cells[c]))
+            });
+            if free {
+                break;
+            }
+            column += 1;
+        }
+
+        for r in row..row + row_span {
+            if occupied.len() <= r {
+                occupied.resize(r + 1, vec![false; columns]);
+            }
+            for c in column..column + column_span {
+                occupied[r][c] = true;
+            }
+        }
+        cells.push((row, column));
+        cursor = (row, column + column_span);
+    }
+
+    cells
+}
+
+impl Container for Grid {
+    fn bounds(&self) -> Rect {
+        Rect::from_xywh(self.x, self.y, self.width, self.height)
+    }
+
+    fn set_position(&mut self, x: f32, y: f32) {
+        let dx = x - self.x;
+        let dy = y - self.y;
+
+        self.x = x;
+        self.y = y;
+
+        // Move all children by the same offset
+        for item in &mut self.items {
+            let child_bounds = item.child.bounds();
+            item.child
+                .set_position(child_bounds.left + dx, child_bounds.top + dy);
+        }
+    }
+
+    fn set_size(&mut self, width: f32, height: f32) {
+        self.width = width;
+        self.height = height;
+        self.auto_size = false;
+        self.relayout();
+    }
+
+    fn render(&mut self, canvas: &Canvas) {
+        // Grids don't draw anything themselves, just render children
+        for item in &mut self.items {
+            item.child.render(canvas);
+        }
+    }
+
+    fn handle_pointer(&mut self, x: f32, y: f32) -> bool {
+        // Check children in reverse order (top-to-bottom)
+        for item in self.items.iter_mut().rev() {
+            if item.child.handle_pointer(x, y) {
+                return true;
+            }
+        }
+
+        // Grid itself doesn't handle events
+        false
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Container> {
+        self.items
+            .iter_mut()
+            .map(|item| item.child.as_mut() as &mut dyn Container)
+            .collect()
+    }
+
+    fn add_child(&mut self, child: Box<dyn Container>) {
+        self.add(child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::container::frame::FrameBuilder;
+    use skia_safe::Color;
+
+    fn frame(width: f32, height: f32) -> Box<dyn Container> {
+        Box::new(
+            FrameBuilder::new(width, height)
+                .with_background(Color::RED)
+                .build(),
+        )
+    }
+
+    #[test]
+    fn items_wrap_onto_new_rows() {
+        let cells = place_items(&[(1, 1); 5], 2);
+        assert_eq!(cells, vec![(0, 0), (0, 1), (1, 0), (1, 1), (2, 0)]);
+    }
+
+    #[test]
+    fn spanning_items_skip_occupied_cells() {
+        // A 2x2 item, then singles filling around it, then a full-width row
+        let cells = place_items(&[(2, 2), (1, 1), (1, 1), (3, 1)], 3);
+        assert_eq!(cells, vec![(0, 0), (0, 2), (1, 2), (2, 0)]);
+    }
+
+    #[test]
+    fn too_wide_spans_are_clamped() {
+        let cells = place_items(&[(1, 1), (5, 1)], 2);
+        assert_eq!(cells, vec![(0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn test_grid_auto_size() {
+        let mut grid = Grid::new(3).with_gap(10.0);
+        for _ in 0..4 {
+            grid.add(frame(50.0, 40.0));
+        }
+
+        // Width: 3 * 50 + 2 * 10 gaps
+        assert_eq!(grid.bounds().width(), 170.0);
+        // Height: two rows of 40 + 10 gap
+        assert_eq!(grid.bounds().height(), 90.0);
+    }
+
+    #[test]
+    fn test_grid_positions_children() {
+        let mut grid = Grid::new(2)
+            .with_gap(8.0)
+            .with_padding(EdgeInsets::uniform(4.0));
+        grid.add_spanning(frame(100.0, 20.0), 2, 1);
+        grid.add(frame(40.0, 30.0));
+        grid.add(frame(40.0, 30.0));
+
+        let children = grid.children_mut();
+        // Columns are (100 - 8) / 2 = 46 wide
+        assert_eq!(children[0].bounds().left, 4.0);
+        assert_eq!(children[1].bounds().top, 4.0 + 20.0 + 8.0);
+        assert_eq!(children[2].bounds().left, 4.0 + 46.0 + 8.0);
+    }
+
+    #[test]
+    fn test_grid_fixed_size_stretch() {
+        let mut grid = Grid::new(2)
+            .with_gap(10.0)
+            .with_alignment(StackAlignment::Stretch)
+            .with_size(210.0, 100.0);
+        grid.add(frame(20.0, 20.0));
+
+        assert_eq!(grid.children_mut()[0].bounds().width(), 100.0);
+        assert_eq!(grid.bounds().width(), 210.0);
+    }
+}
@@ -1,9 +1,13 @@
 pub mod frame;
+pub mod grid;
+pub mod scroll_view;
 pub mod stack;
 pub mod traits;
 
 pub use frame::{Frame, FrameBuilder};
-pub use stack::{Stack, StackDirection};
+pub use grid::Grid;
+pub use scroll_view::{ScrollAxes, ScrollState, ScrollView};
+pub use stack::{Stack, StackAlignment, StackDirection};
 pub use traits::{Container, ContainerBackend, DrawingBackend, SurfaceBackend};
 
 // Re-export common styling types
@@ -0,0 +1,672 @@
+use std::time::{Duration, Instant};
+
+use skia_safe::{Canvas, Color, Contains, Paint, Point, RRect, Rect};
+
+use super::traits::Container;
+use crate::input::{ScrollEvent, ScrollSource};
+
+type ContentFn = Box<dyn FnMut(&Canvas) + Send>;
+
+/// Distance one wheel notch scrolls, in logical pixels
+pub const NOTCH_DISTANCE: f32 = 48.0;
+/// How long a wheel notch takes to scroll
+const NOTCH_DURATION: Duration = Duration::from_millis(150);
+/// How long the glide after a touchpad fling lasts
+const GLIDE_DURATION: Duration = Duration::from_millis(700);
+/// Glide distance per pixel of the last touchpad scroll before the fling
+const GLIDE_DISTANCE: f32 = 12.0;
+/// How long the scrollbar stays fully visible after the last scroll
+const SCROLLBAR_VISIBLE: Duration = Duration::from_millis(800);
+/// How long the scrollbar takes to fade out afterwards
+const SCROLLBAR_FADE: Duration = Duration::from_millis(300);
+const SCROLLBAR_WIDTH: f32 = 6.0;
+const SCROLLBAR_INSET: f32 = 2.0;
+const SCROLLBAR_MIN_LENGTH: f32 = 24.0;
+
+/// Axes a ScrollView scrolls along
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScrollAxes {
+    Vertical,
+    Horizontal,
+    Both,
+}
+
+impl ScrollAxes {
+    fn horizontal(self) -> bool {
+        matches!(self, Self::Horizontal | Self::Both)
+    }
+
+    fn vertical(self) -> bool {
+        matches!(self, Self::Vertical | Self::Both)
+    }
+}
+
+/// An eased move of the scroll offset, from where it was to a target
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ScrollTransition {
+    from: (f32, f32),
+    to: (f32, f32),
+    started: Instant,
+    duration: Duration,
+}
+
+impl ScrollTransition {
+    /// Progress at `at`, from 0.0 to 1.0
+    fn progress(&self, at: Instant) -> f32 {
+        let elapsed = at.saturating_duration_since(self.started).as_secs_f32();
+        (elapsed / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    /// Offset at `at`, easing out towards the target
+    fn offset_at(&self, at: Instant) -> (f32, f32) {
+        let t = ease_out_cubic(self.progress(at));
+        (
+            self.from.0 + (self.to.0 - self.from.0) * t,
+            self.from.1 + (self.to.1 - self.from.1) * t,
+        )
+    }
+}
+
+/// Scroll offset and transition of a viewport over larger content
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScrollState {
+    axes: ScrollAxes,
+    viewport: (f32, f32),
+    content: (f32, f32),
+    offset: (f32, f32),
+    transition: Option<ScrollTransition>,
+}
+
+impl ScrollState {
+    pub fn new(axes: ScrollAxes) -> Self {
+        Self {
+            axes,
+            viewport: (0.0, 0.0),
+            content: (0.0, 0.0),
+            offset: (0.0, 0.0),
+            transition: None,
+        }
+    }
+
+    pub fn offset(&self) -> (f32, f32) {
+        self.offset
+    }
+
+    /// Where the offset comes to rest: the end of a running transition,
+    /// else the current offset
+    pub fn target(&self) -> (f32, f32) {
+        self.transition.map_or(self.offset, |t| t.to)
+    }
+
+    /// The largest offset along each axis, zero when the content fits
+    pub fn max_offset(&self) -> (f32, f32) {
+        let max = |content: f32, viewport: f32, enabled: bool| {
+            if enabled {
+                (content - viewport).max(0.0)
+            } else {
+                0.0
+            }
+        };
+        (
+            max(self.content.0, self.viewport.0, self.axes.horizontal()),
+            max(self.content.1, self.viewport.1, self.axes.vertical()),
+        )
+    }
+
+    /// Update the viewport and content sizes, keeping the offset in range
+    pub fn set_sizes(&mut self, viewport: (f32, f32), content: (f32, f32)) {
+        self.viewport = viewport;
+        self.content = content;
+        self.set_offset(self.offset);
+        if let Some(transition) = self.transition.as_mut() {
+            transition.to = clamp_offset(transition.to, self.max_offset());
+        }
+    }
+
+    /// Jump to `offset`, clamped to the content. Stops any transition.
+    /// Returns `true` when the offset changed.
+    pub fn scroll_to(&mut self, offset: (f32, f32)) -> bool {
+        self.stop();
+        self.set_offset(offset)
+    }
+
+    /// Move by (dx, dy), clamped to the content.
+    /// Returns `true` when the offset changed.
+    pub fn scroll_by(&mut self, dx: f32, dy: f32) -> bool {
+        self.set_offset((self.offset.0 + dx, self.offset.1 + dy))
+    }
+
+    /// Start a transition from the current offset to `offset`, clamped to
+    /// the content, over `duration` from `now`. Advance it with `glide()`.
+    /// Returns `false` when there is nowhere to go.
+    pub fn animate_to(&mut self, offset: (f32, f32), duration: Duration, now: Instant) -> bool {
+        let to = clamp_offset(offset, self.max_offset());
+        if to == self.offset {
+            self.stop();
+            return false;
+        }
+        self.transition = Some(ScrollTransition {
+            from: self.offset,
+            to,
+            started: now,
+            duration,
+        });
+        true
+    }
+
+    /// Glide on after a touchpad fling whose last step moved (dx, dy)
+    pub fn fling(&mut self, dx: f32, dy: f32, now: Instant) -> bool {
+        let target = (
+            self.offset.0 + dx * GLIDE_DISTANCE,
+            self.offset.1 + dy * GLIDE_DISTANCE,
+        );
+        self.animate_to(target, GLIDE_DURATION, now)
+    }
+
+    /// Stop the running transition where it is
+    pub fn stop(&mut self) {
+        self.transition = None;
+    }
+
+    pub fn is_gliding(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// Move the offset to where the transition is at `now`, and end the
+    /// transition once it is over. Returns `true` when the offset moved.
+    pub fn glide(&mut self, now: Instant) -> bool {
+        let Some(transition) = self.transition else {
+            return false;
+        };
+        if transition.progress(now) >= 1.0 {
+            self.transition = None;
+        }
+        self.set_offset(transition.offset_at(now))
+    }
+
+    fn set_offset(&mut self, offset: (f32, f32)) -> bool {
+        let offset = clamp_offset(offset, self.max_offset());
+        let changed = offset != self.offset;
+        self.offset = offset;
+        changed
+    }
+}
+
+fn clamp_offset(offset: (f32, f32), max: (f32, f32)) -> (f32, f32) {
+    (offset.0.clamp(0.0, max.0), offset.1.clamp(0.0, max.1))
+}
+
+fn ease_out_cubic(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+/// A container that clips its content to its bounds and scrolls it
+///
+/// The content is either a child container or a draw function with a
+/// fixed content size. Forward input with `handle_scroll()` and call
+/// `tick()` once per frame while `is_animating()` to run the eased
+/// transitions of wheel notches and touchpad flings, and the scrollbar fade.
+///
+/// # Examples
+///
+/// ```no_run
+/// use otto_kit::components::container::{ScrollAxes, ScrollView};
+///
+/// let mut scroll_view = ScrollView::new(ScrollAxes::Vertical)
+///     .at(0.0, 0.0)
+///     .with_size(240.0, 320.0)
+///     .with_content_fn(240.0, 1200.0, |canvas| {
+///         // Draw the full content at (0, 0)...
+///     });
+///
+/// scroll_view.scroll_to((0.0, 400.0));
+/// ```
+pub struct ScrollView {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    state: ScrollState,
+    content: Option<Box<dyn Container>>,
+    content_fn: Option<ContentFn>,
+    content_fn_size: (f32, f32),
+    show_scrollbar: bool,
+    scrollbar_color: Color,
+    last_scroll: Option<Instant>,
+    /// Distance of the last touchpad scroll, the speed of the next glide
+    last_delta: (f32, f32),
+}
+
+impl ScrollView {
+    pub fn new(axes: ScrollAxes) -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 0.0,
+            height: 0.0,
+            state: ScrollState::new(axes),
+            content: None,
+            content_fn: None,
+            content_fn_size: (0.0, 0.0),
+            show_scrollbar: true,
+            scrollbar_color: Color::from_argb(0x80, 0x00, 0x00, 0x00),
+            last_scroll: None,
+            last_delta: (0.0, 0.0),
+        }
+    }
+
+    pub fn at(mut self, x: f32, y: f32) -> Self {
+        self.set_position(x, y);
+        self
+    }
+
+    pub fn with_size(mut self, width: f32, height: f32) -> Self {
+        self.set_size(width, height);
+        self
+    }
+
+    /// Scroll a child container, placed at the top-left of the view
+    pub fn with_content(mut self, content: Box<dyn Container>) -> Self {
+        self.set_content(content);
+        self
+    }
+
+    /// Scroll content drawn by `draw_fn`, `width` x `height` in size.
+    /// The canvas is translated so the content's top-left is at (0, 0).
+    pub fn with_content_fn<F>(mut self, width: f32, height: f32, draw_fn: F) -> Self
+    where
+        F: FnMut(&Canvas) + Send + 'static,
+    {
+        self.content = None;
+        self.content_fn = Some(Box::new(draw_fn));
+        self.content_fn_size = (width, height);
+        self.update_sizes();
+        self
+    }
+
+    pub fn with_scrollbar(mut self, show: bool) -> Self {
+        self.show_scrollbar = show;
+        self
+    }
+
+    pub fn with_scrollbar_color(mut self, color: Color) -> Self {
+        self.scrollbar_color = color;
+        self
+    }
+
+    pub fn set_content(&mut self, mut content: Box<dyn Container>) {
+        content.set_position(self.x, self.y);
+        self.content = Some(content);
+        self.content_fn = None;
+        self.update_sizes();
+    }
+
+    /// Update the size of content drawn by the content function
+    pub fn set_content_size(&mut self, width: f32, height: f32) {
+        self.content_fn_size = (width, height);
+        self.update_sizes();
+    }
+
+    // === Scrolling ===
+
+    /// Size of the scrolled content
+    pub fn content_size(&self) -> (f32, f32) {
+        match self.content.as_ref() {
+            Some(content) => {
+                let bounds = content.bounds();
+                (bounds.width(), bounds.height())
+            }
+            None => self.content_fn_size,
+        }
+    }
+
+    pub fn offset(&self) -> (f32, f32) {
+        self.state.offset()
+    }
+
+    /// Jump to `offset`, clamped to the content. Use `animate_to()` to
+    /// get there with a transition.
+    pub fn scroll_to(&mut self, offset: impl Into<Point>) -> bool {
+        let offset = offset.into();
+        let changed = self.state.scroll_to((offset.x, offset.y));
+        if changed {
+            self.show_scrollbar_now();
+        }
+        changed
+    }
+
+    pub fn scroll_by(&mut self, dx: f32, dy: f32) -> bool {
+        let changed = self.state.scroll_by(dx, dy);
+        if changed {
+            self.show_scrollbar_now();
+        }
+        changed
+    }
+
+    /// Ease to `offset`, clamped to the content, over `duration`.
+    /// Drive the transition with `tick()`.
+    pub fn animate_to(&mut self, offset: impl Into<Point>, duration: Duration) -> bool {
+        let offset = offset.into();
+        let started = self
+            .state
+            .animate_to((offset.x, offset.y), duration, Instant::now());
+        if started {
+            self.show_scrollbar_now();
+        }
+        started
+    }
+
+    /// Scroll for a pointer axis event inside the view.
+    /// Returns `true` when the view needs a redraw.
+    ///
+    /// Wheels ease by `NOTCH_DISTANCE` per notch. Touchpad scrolls follow
+    /// the fingers and keep gliding after they lift. Drive the transitions
+    /// with `tick()`.
+    pub fn handle_scroll(&mut self, scroll: &ScrollEvent) -> bool {
+        let (x, y) = (scroll.position.0 as f32, scroll.position.1 as f32);
+        if !self.bounds().contains(Point::new(x, y)) {
+            return false;
+        }
+        if scroll.is_discrete() {
+            // Notches in quick succession add up to one longer transition
+            let (steps_x, steps_y) = scroll.steps();
+            let (target_x, target_y) = self.state.target();
+            return self.animate_to(
+                (
+                    target_x + steps_x as f32 * NOTCH_DISTANCE,
+                    target_y + steps_y as f32 * NOTCH_DISTANCE,
+                ),
+                NOTCH_DURATION,
+            );
+        }
+        if scroll.is_stop() {
+            // Fingers lifted: glide on in the direction of the last step
+            let (dx, dy) = std::mem::take(&mut self.last_delta);
+            if scroll.source != ScrollSource::Finger {
+                return false;
+            }
+            let gliding = self.state.fling(dx, dy, Instant::now());
+            if gliding {
+                self.show_scrollbar_now();
+            }
+            return gliding;
+        }
+        // Touching again catches a glide
+        self.state.stop();
+        let (dx, dy) = (scroll.dx as f32, scroll.dy as f32);
+        self.last_delta = (dx, dy);
+        self.scroll_by(dx, dy)
+    }
+
+    /// Whether `tick()` still has work: a transition or a fading scrollbar
+    pub fn is_animating(&self) -> bool {
+        self.state.is_gliding()
+            || self
+                .last_scroll
+                .is_some_and(|at| at.elapsed() < SCROLLBAR_VISIBLE + SCROLLBAR_FADE)
+    }
+
+    /// Advance the running transition to the current time.
+    /// Returns `true` when the view needs a redraw.
+    pub fn tick(&mut self) -> bool {
+        if self.state.glide(Instant::now()) {
+            self.show_scrollbar_now();
+            return true;
+        }
+        self.is_animating()
+    }
+
+    // === Internal ===
+
+    fn update_sizes(&mut self) {
+        let content = self.content_size();
+        self.state.set_sizes((self.width, self.height), content);
+    }
+
+    fn show_scrollbar_now(&mut self) {
+        self.last_scroll = Some(Instant::now());
+    }
+
+    fn scrollbar_opacity(&self) -> f32 {
+        self.last_scroll
+            .map(|at| scrollbar_opacity(at.elapsed()))
+            .unwrap_or(0.0)
+    }
+
+    fn render_scrollbars(&self, canvas: &Canvas) {
+        let opacity = self.scrollbar_opacity();
+        if !self.show_scrollbar || opacity <= 0.0 {
+            return;
+        }
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(self.scrollbar_color);
+        paint.set_alpha_f(self.scrollbar_color.a() as f32 / 255.0 * opacity);
+
+        let (content_width, content_height) = self.content_size();
+        let (offset_x, offset_y) = self.offset();
+        let radius = SCROLLBAR_WIDTH / 2.0;
+        let thickness = SCROLLBAR_WIDTH + SCROLLBAR_INSET;
+
+        if let Some((start, length)) = scrollbar_thumb(self.height, content_height, offset_y) {
+            let rect = Rect::from_xywh(
+                self.x + self.width - thickness,
+                self.y + start,
+                SCROLLBAR_WIDTH,
+                length,
+            );
+            canvas.draw_rrect(RRect::new_rect_xy(rect, radius, radius), &paint);
+        }
+        if let Some((start, length)) = scrollbar_thumb(self.width, content_width, offset_x) {
+            let rect = Rect::from_xywh(
+                self.x + start,
+                self.y + self.height - thickness,
+                length,
+                SCROLLBAR_WIDTH,
+            );
+            canvas.draw_rrect(RRect::new_rect_xy(rect, radius, radius), &paint);
+        }
+    }
+}
+
+impl Container for ScrollView {
+    fn bounds(&self) -> Rect {
+        Rect::from_xywh(self.x, self.y, self.width, self.height)
+    }
+
+    fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+        if let Some(content) = self.content.as_mut() {
+            content.set_position(x, y);
+        }
+    }
+
+    fn set_size(&mut self, width: f32, height: f32) {
+        self.width = width;
+        self.height = height;
+        self.update_sizes();
+    }
+
+    fn render(&mut self, canvas: &Canvas) {
+        // The child may have grown or shrunk since the last frame
+        self.update_sizes();
+        let (offset_x, offset_y) = self.offset();
+
+        canvas.save();
+        canvas.clip_rect(self.bounds(), None, true);
+        canvas.translate((-offset_x, -offset_y));
+        if let Some(content) = self.content.as_mut() {
+            content.render(canvas);
+        } else if let Some(draw_fn) = self.content_fn.as_mut() {
+            canvas.translate((self.x, self.y));
+            draw_fn(canvas);
+        }
+        canvas.restore();
+
+        self.render_scrollbars(canvas);
+    }
+
+    fn handle_pointer(&mut self, x: f32, y: f32) -> bool {
+        if !self.bounds().contains(Point::new(x, y)) {
+            return false;
+        }
+        let (offset_x, offset_y) = self.offset();
+        match self.content.as_mut() {
+            Some(content) => content.handle_pointer(x + offset_x, y + offset_y),
+            None => true,
+        }
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Container> {
+        self.content
+            .iter_mut()
+            .map(|child| child.as_mut() as &mut dyn Container)
+            .collect()
+    }
+
+    fn add_child(&mut self, child: Box<dyn Container>) {
+        self.set_content(child);
+    }
+}
+
+/// Start and length of the scrollbar thumb along a track of
+/// `viewport` length, `None` when the content fits
+fn scrollbar_thumb(viewport: f32, content: f32, offset: f32) -> Option<(f32, f32)> {
+    if content <= viewport || viewport <= 0.0 {
+        return None;
+    }
+    let track = viewport - SCROLLBAR_INSET * 2.0;
+    let length = (track * viewport / content).clamp(SCROLLBAR_MIN_LENGTH.min(track), track);
+    let progress = offset / (content - viewport);
+    Some((SCROLLBAR_INSET + (track - length) * progress, length))
+}
+
+/// Scrollbar opacity `elapsed` after the last scroll
+fn scrollbar_opacity(elapsed: Duration) -> f32 {
+    let Some(fading) = elapsed.checked_sub(SCROLLBAR_VISIBLE) else {
+        return 1.0;
+    };
+    1.0 - (fading.as_secs_f32() / SCROLLBAR_FADE.as_secs_f32()).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vertical_state() -> ScrollState {
+        let mut state = ScrollState::new(ScrollAxes::Vertical);
+        state.set_sizes((200.0, 300.0), (200.0, 1000.0));
+        state
+    }
+
+    #[test]
+    fn test_scroll_is_clamped_to_content() {
+        let mut state = vertical_state();
+        assert_eq!(state.max_offset(), (0.0, 700.0));
+        assert!(!state.scroll_by(0.0, -10.0));
+        assert!(state.scroll_by(0.0, 900.0));
+        assert_eq!(state.offset(), (0.0, 700.0));
+
+        // Shrinking the content pulls the offset back in range
+        state.set_sizes((200.0, 300.0), (200.0, 500.0));
+        assert_eq!(state.offset(), (0.0, 200.0));
+    }
+
+    #[test]
+    fn test_disabled_axis_does_not_scroll() {
+        let mut state = ScrollState::new(ScrollAxes::Vertical);
+        state.set_sizes((200.0, 300.0), (800.0, 1000.0));
+        state.scroll_to((100.0, 100.0));
+        assert_eq!(state.offset(), (0.0, 100.0));
+
+        let mut state = ScrollState::new(ScrollAxes::Both);
+        state.set_sizes((200.0, 300.0), (800.0, 1000.0));
+        state.scroll_to((100.0, 100.0));
+        assert_eq!(state.offset(), (100.0, 100.0));
+    }
+
+    #[test]
+    fn test_fling_eases_out_and_stops() {
+        let mut state = vertical_state();
+        let start = Instant::now();
+        assert!(state.fling(0.0, 20.0, start));
+        assert_eq!(state.target(), (0.0, 240.0));
+
+        // Most of the distance is covered in the first half
+        assert!(state.glide(start + GLIDE_DURATION / 2));
+        let halfway = state.offset().1;
+        assert!(halfway > 180.0 && halfway < 240.0);
+
+        assert!(state.glide(start + GLIDE_DURATION));
+        assert_eq!(state.offset(), (0.0, 240.0));
+        assert!(!state.is_gliding());
+        assert!(!state.glide(start + GLIDE_DURATION * 2));
+    }
+
+    #[test]
+    fn test_fling_stops_at_edge() {
+        let mut state = vertical_state();
+        state.scroll_to((0.0, 690.0));
+        let start = Instant::now();
+        state.fling(0.0, 20.0, start);
+        assert_eq!(state.target(), (0.0, 700.0));
+        state.glide(start + GLIDE_DURATION);
+        assert_eq!(state.offset(), (0.0, 700.0));
+        assert!(!state.is_gliding());
+
+        // Already against the edge: nothing to animate
+        assert!(!state.fling(0.0, 20.0, start));
+    }
+
+    #[test]
+    fn test_notches_add_up_to_one_transition() {
+        let mut state = vertical_state();
+        let start = Instant::now();
+        state.animate_to((0.0, NOTCH_DISTANCE), NOTCH_DURATION, start);
+        let target = state.target();
+        state.animate_to(
+            (target.0, target.1 + NOTCH_DISTANCE),
+            NOTCH_DURATION,
+            start + NOTCH_DURATION / 3,
+        );
+        assert_eq!(state.target(), (0.0, 2.0 * NOTCH_DISTANCE));
+        state.glide(start + NOTCH_DURATION * 2);
+        assert_eq!(state.offset(), (0.0, 2.0 * NOTCH_DISTANCE));
+    }
+
+    #[test]
+    fn test_scroll_to_stops_transition() {
+        let mut state = vertical_state();
+        let start = Instant::now();
+        state.animate_to((0.0, 300.0), NOTCH_DURATION, start);
+        assert!(state.scroll_to((0.0, 50.0)));
+        assert!(!state.is_gliding());
+        assert!(!state.glide(start + NOTCH_DURATION));
+        assert_eq!(state.offset(), (0.0, 50.0));
+    }
+
+    #[test]
+    fn test_scrollbar_thumb() {
+        assert_eq!(scrollbar_thumb(300.0, 300.0, 0.0), None);
+        let (start, length) = scrollbar_thumb(304.0, 608.0, 0.0).unwrap();
+        assert_eq!((start, length), (SCROLLBAR_INSET, 150.0));
+        let (start, _) = scrollbar_thumb(304.0, 608.0, 304.0).unwrap();
+        assert_eq!(start, SCROLLBAR_INSET + 150.0);
+        // Very long content keeps a grabbable thumb
+        let (_, length) = scrollbar_thumb(300.0, 100_000.0, 0.0).unwrap();
+        assert_eq!(length, SCROLLBAR_MIN_LENGTH);
+    }
+
+    #[test]
+    fn test_scrollbar_fades_out() {
+        assert_eq!(scrollbar_opacity(Duration::ZERO), 1.0);
+        assert_eq!(scrollbar_opacity(SCROLLBAR_VISIBLE), 1.0);
+        let half = scrollbar_opacity(SCROLLBAR_VISIBLE + SCROLLBAR_FADE / 2);
+        assert!((half - 0.5).abs() < 0.01);
+        assert_eq!(
+            scrollbar_opacity(SCROLLBAR_VISIBLE + SCROLLBAR_FADE * 2),
+            0.0
+        );
+    }
+}
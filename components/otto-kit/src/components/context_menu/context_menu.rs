@@ -4,14 +4,22 @@ use std::rc::Rc;
 
 use super::{ContextMenuRenderer, ContextMenuState, ContextMenuStyle};
 use crate::app_runner::AppContext;
+use crate::components::container::scroll_view::NOTCH_DISTANCE;
 use crate::components::menu_item::MenuItem;
-use crate::input::keycodes;
+use crate::input::{keycodes, ScrollEvent, TouchEventKind};
 use crate::protocols::otto_surface_style_v1::{BlendMode, ClipMode};
-use crate::surfaces::PopupSurface;
+use crate::surfaces::{PopupGeometry, PopupSurface};
 use smithay_client_toolkit::seat::pointer::PointerEventKind;
 use wayland_client::{backend::ObjectId, protocol::wl_keyboard, Proxy};
 use wayland_protocols::xdg::shell::client::xdg_surface;
 
+/// Whether a submenu placed at `geometry` ended up on the left of its
+/// `parent_width` wide parent, e.g. because the compositor flipped it away
+/// from the right screen edge
+fn opened_left(geometry: PopupGeometry, parent_width: i32) -> bool {
+    geometry.x * 2 + geometry.width < parent_width
+}
+
 type PopupStack = Rc<RefCell<Vec<Rc<RefCell<Option<PopupSurface>>>>>>;
 type ItemClickCallback = Rc<RefCell<Option<Rc<dyn Fn(&str)>>>>;
 type CloseCallback = Rc<RefCell<Option<Rc<dyn Fn()>>>>;
@@ -63,9 +71,10 @@ impl ContextMenu {
             registered_surfaces: Rc::new(RefCell::new(HashMap::new())),
         };
 
-        // Register pointer handler only for root menu
+        // Register pointer and touch handlers only for root menu
         if register_handler {
             s.register_pointer_handler();
+            s.register_touch_handler();
         }
         s
     }
@@ -100,6 +109,16 @@ impl ContextMenu {
         self
     }
 
+    /// Called when the user dismisses the menu without picking an item:
+    /// Escape at the top level, or a click outside the menu
+    pub fn on_close<F>(self, callback: F) -> Self
+    where
+        F: Fn() + 'static,
+    {
+        *self.on_close.borrow_mut() = Some(Rc::new(callback));
+        self
+    }
+
     // === Surface Management ===
 
     /// Show the menu with an explicit grab serial (recommended for GNOME)
@@ -181,6 +200,7 @@ impl ContextMenu {
             // Register done callback to close menu when clicked outside
             let menu_self = Rc::new(self.clone());
             AppContext::register_popup_done_callback(surface_id.clone(), move || {
+                menu_self.notify_close();
                 menu_self.hide_animated();
             });
 
@@ -260,6 +280,7 @@ impl ContextMenu {
             // Register done callback to close menu when clicked outside
             let menu_self = Rc::new(self.clone());
             AppContext::register_popup_done_callback(surface_id.clone(), move || {
+                menu_self.notify_close();
                 menu_self.hide_animated();
             });
 
@@ -504,27 +525,60 @@ impl ContextMenu {
                         .map(|item| item.height)
                         .unwrap_or(22.0);
 
-                    // Y position includes top padding
+                    // Y position includes top padding, less the parent's scroll
+                    let y_offset = y_offset - state_borrow.scroll_at_depth(depth);
                     (p_width, y_offset + style_borrow.vertical_padding, item_h)
                 };
 
+                // A parent submenu the compositor flipped to the left of its
+                // own parent keeps the chain going left, instead of bouncing
+                // back over the screen edge at every level
+                let opens_left = depth > 0 && {
+                    let grandparent_width = {
+                        let state_borrow = state.borrow();
+                        let items = state_borrow.items_at_depth(depth - 1);
+                        ContextMenuRenderer::measure_items(items, &style).0
+                    };
+                    let parent_geometry = popups.borrow().get(depth).and_then(|popup| {
+                        popup
+                            .borrow()
+                            .as_ref()
+                            .and_then(|p| AppContext::popup_geometry(&p.wl_surface().id()))
+                    });
+                    parent_geometry
+                        .is_some_and(|geometry| opened_left(geometry, grandparent_width as i32))
+                };
+
                 // Set submenu size
                 positioner.set_size(width as i32, height as i32);
 
-                // Define anchor rectangle as a 1px vertical line at parent's right edge
-                // positioned at the selected item
+                // Anchor rectangle spans the parent, inset by 5px so the
+                // submenu slightly overlaps it, at the top of the selected item
                 positioner.set_anchor_rect(
-                    parent_width as i32 - 5, // x: at right edge of parent
-                    anchor_y as i32,         // y: top of selected item
-                    1,                       // width: thin vertical line
-                    1_i32,                   // height: selected item height
+                    5,                                 // x: left edge of parent
+                    anchor_y as i32,                   // y: top of selected item
+                    (parent_width as i32 - 10).max(1), // width: across the parent
+                    1,                                 // height: thin line
                 );
 
-                // Anchor to top-left of this line (which is at parent's right edge)
-                positioner.set_anchor(xdg_positioner::Anchor::TopLeft);
+                // Open beside the parent's right edge, or its left edge when
+                // the chain already turned left
+                if opens_left {
+                    positioner.set_anchor(xdg_positioner::Anchor::TopLeft);
+                    positioner.set_gravity(xdg_positioner::Gravity::BottomLeft);
+                } else {
+                    positioner.set_anchor(xdg_positioner::Anchor::TopRight);
+                    positioner.set_gravity(xdg_positioner::Gravity::BottomRight);
+                }
 
-                // Place submenu to the right of the anchor point
-                positioner.set_gravity(xdg_positioner::Gravity::BottomRight);
+                // Near a screen edge let the compositor flip the submenu to
+                // the other side of the parent, and slide it back on screen
+                // when neither side fits or it runs off the bottom
+                positioner.set_constraint_adjustment(
+                    xdg_positioner::ConstraintAdjustment::FlipX
+                        | xdg_positioner::ConstraintAdjustment::SlideX
+                        | xdg_positioner::ConstraintAdjustment::SlideY,
+                );
 
                 // Ensure popups vec is large enough for submenu
                 while popups.borrow().len() <= depth + 1 {
@@ -662,6 +716,19 @@ impl ContextMenu {
                                 );
                             }
                         }
+                        PointerEventKind::Axis { .. } => {
+                            if let Some(scroll) = ScrollEvent::from_pointer_event(event) {
+                                Self::handle_scroll_static(
+                                    &state,
+                                    &popups,
+                                    &style,
+                                    &registered_surfaces,
+                                    &parent_xdg,
+                                    depth,
+                                    &scroll,
+                                );
+                            }
+                        }
                         _ => {}
                     }
                 }
@@ -669,6 +736,108 @@ impl ContextMenu {
         });
     }
 
+    /// Register touch event handler (called only by root menu)
+    ///
+    /// A finger selects items like the pointer does, and lifting it on an
+    /// item activates it.
+    fn register_touch_handler(&mut self) {
+        let registered_surfaces = self.registered_surfaces.clone();
+        let state = self.state.clone();
+        let style = self.style.clone();
+        let popups = self.popups.clone();
+        let on_item_click = self.on_item_click.clone();
+        let parent_xdg = self.parent_xdg.clone();
+
+        AppContext::register_touch_callback(move |events| {
+            for event in events {
+                let depth = registered_surfaces
+                    .borrow()
+                    .get(&event.surface.id())
+                    .cloned();
+                let Some(depth) = depth else {
+                    continue;
+                };
+                let (x, y) = event.position;
+
+                match event.kind {
+                    TouchEventKind::Down { .. } | TouchEventKind::Motion { .. } => {
+                        Self::handle_motion_static(
+                            &state,
+                            &popups,
+                            &style,
+                            &registered_surfaces,
+                            &parent_xdg,
+                            depth,
+                            x,
+                            y,
+                        );
+                    }
+                    TouchEventKind::Up { .. } => {
+                        Self::handle_click_static(
+                            &state,
+                            &popups,
+                            &style,
+                            &on_item_click,
+                            depth,
+                            x as f32,
+                            y as f32,
+                        );
+                    }
+                    TouchEventKind::Cancel => {}
+                }
+            }
+        });
+    }
+
+    /// Scroll a menu taller than `max_height` at specific depth, then
+    /// highlight the item that moved under the pointer
+    #[allow(clippy::too_many_arguments)]
+    fn handle_scroll_static(
+        state: &Rc<RefCell<ContextMenuState>>,
+        popups: &PopupStack,
+        style: &Rc<RefCell<ContextMenuStyle>>,
+        registered_surfaces: &Rc<RefCell<HashMap<ObjectId, usize>>>,
+        parent_xdg: &Rc<RefCell<Option<xdg_surface::XdgSurface>>>,
+        depth: usize,
+        scroll: &ScrollEvent,
+    ) {
+        let delta = if scroll.is_discrete() {
+            scroll.steps().1 as f32 * NOTCH_DISTANCE
+        } else {
+            scroll.dy as f32
+        };
+        let (offset, max_scroll) = {
+            let state_borrow = state.borrow();
+            let items = state_borrow.items_at_depth(depth);
+            (
+                state_borrow.scroll_at_depth(depth) + delta,
+                ContextMenuRenderer::max_scroll(items, &style.borrow()),
+            )
+        };
+        if !state
+            .borrow_mut()
+            .set_scroll_at_depth(depth, offset, max_scroll)
+        {
+            return;
+        }
+
+        let popup_ref = popups.borrow().get(depth).cloned();
+        if let Some(popup_ref) = popup_ref {
+            Self::render_menu_at_depth(state, &style.borrow(), &popup_ref, depth);
+        }
+        let (x, y) = scroll.position;
+        Self::handle_motion_static(
+            state,
+            popups,
+            style,
+            registered_surfaces,
+            parent_xdg,
+            depth,
+            x,
+            y,
+        );
+    }
+
     /// Handle pointer motion at specific depth
     #[allow(clippy::too_many_arguments)]
     fn handle_motion_static(
@@ -682,15 +851,23 @@ impl ContextMenu {
         y: f64,
     ) {
         // Get items for this depth
-        let items = {
+        let (items, scroll) = {
             let state_borrow = state.borrow();
-            state_borrow.items_at_depth(depth).to_vec()
+            (
+                state_borrow.items_at_depth(depth).to_vec(),
+                state_borrow.scroll_at_depth(depth),
+            )
         };
 
         // Hit test
         let style_borrowed = style.borrow();
-        let item_index =
-            ContextMenuRenderer::hit_test_items(&items, &style_borrowed, x as f32, y as f32);
+        let item_index = ContextMenuRenderer::hit_test_items_scrolled(
+            &items,
+            &style_borrowed,
+            x as f32,
+            y as f32,
+            scroll,
+        );
         drop(style_borrowed);
 
         // Update selection at this depth
@@ -789,29 +966,22 @@ impl ContextMenu {
         y: f32,
     ) {
         // Get items for this depth
-        let items = {
+        let (items, scroll) = {
             let state_borrow = state.borrow();
-            state_borrow.items_at_depth(depth).to_vec()
+            (
+                state_borrow.items_at_depth(depth).to_vec(),
+                state_borrow.scroll_at_depth(depth),
+            )
         };
 
         // Hit test
         let style_borrowed = style.borrow();
-        let item_index = ContextMenuRenderer::hit_test_items(&items, &style_borrowed, x, y);
+        let item_index =
+            ContextMenuRenderer::hit_test_items_scrolled(&items, &style_borrowed, x, y, scroll);
         drop(style_borrowed);
 
         if let Some(idx) = item_index {
-            let action_id = items
-                .get(idx)
-                .and_then(|item| item.action_id().map(|s| s.to_string()));
-            if action_id.is_some() || items.get(idx).and_then(|item| item.label()).is_some() {
-                let callback_id = action_id
-                    .or_else(|| {
-                        items
-                            .get(idx)
-                            .and_then(|item| item.label().map(|s| s.to_string()))
-                    })
-                    .unwrap_or_default();
-
+            if let Some(callback_id) = items.get(idx).and_then(Self::callback_id) {
                 Self::flash_and_activate(
                     state,
                     popups,
@@ -882,177 +1052,202 @@ impl ContextMenu {
         depth: usize,
     ) {
         // Get items and dimensions before borrowing popup
-        let (items_vec, selected, width, height) = {
+        let (items_vec, selected, scroll, width, height) = {
             let state_borrow = state.borrow();
             let items = state_borrow.items_at_depth(depth);
             let selected = state_borrow.selected_at_depth(depth);
+            let scroll = state_borrow.scroll_at_depth(depth);
             let (w, h) = ContextMenuRenderer::measure_items(items, style);
-            (items.to_vec(), selected, w, h)
+            (items.to_vec(), selected, scroll, w, h)
         };
 
         // Now borrow popup and draw (no other borrows held)
         if let Some(popup_surface) = popup.borrow().as_ref() {
             popup_surface.draw(|canvas| {
-                ContextMenuRenderer::render_depth(
-                    canvas, &items_vec, selected, style, width, height,
+                ContextMenuRenderer::render_depth_scrolled(
+                    canvas, &items_vec, selected, style, width, height, scroll,
                 );
             });
         }
     }
 
     /// Handle keyboard input
+    ///
+    /// Forward key events here from `App::on_keyboard_event` while the menu
+    /// is visible; the root popup's grab (or an exclusive layer surface)
+    /// keeps the keyboard on the menu while it is open.
+    ///
+    /// - Up/Down move the highlight, wrapping around; Home/End jump to the
+    ///   first/last item. Separators and disabled items are skipped, and
+    ///   menus taller than `max_height` scroll to the highlight.
+    /// - Right opens the highlighted submenu, Left closes the innermost one.
+    /// - Enter/Space activate the highlighted item, or open its submenu.
+    /// - Escape closes the innermost submenu, or the whole menu at the root.
     pub fn handle_key(&mut self, key: u32, key_state: wl_keyboard::KeyState) {
         if key_state != wl_keyboard::KeyState::Pressed {
             return;
         }
-        let style = self.style.borrow(); // Borrow once
 
         match key {
-            keycodes::DOWN => {
-                let mut state_mut = self.state.borrow_mut();
-                state_mut.select_next_at_depth(None);
-                let current_depth = state_mut.depth();
-                // Last input wins: keyboard owns the selection, clear all others
-                let cleared = state_mut.clear_selections_except(current_depth);
-                drop(state_mut);
-                // Re-render cleared depths
-                for d in cleared {
-                    if d < self.popups.borrow().len() {
-                        let popup_ref = self.popups.borrow()[d].clone();
-                        Self::render_menu_at_depth(&self.state, &style, &popup_ref, d);
-                    }
-                }
-                // Render at current depth
-                if current_depth < self.popups.borrow().len() {
-                    let popup_ref = self.popups.borrow()[current_depth].clone();
-                    Self::render_menu_at_depth(&self.state, &style, &popup_ref, current_depth);
-                }
-            }
-            keycodes::UP => {
-                let mut state_mut = self.state.borrow_mut();
-                state_mut.select_previous_at_depth(None);
-                let current_depth = state_mut.depth();
-                // Last input wins: keyboard owns the selection, clear all others
-                let cleared = state_mut.clear_selections_except(current_depth);
-                drop(state_mut);
-                // Re-render cleared depths
-                for d in cleared {
-                    if d < self.popups.borrow().len() {
-                        let popup_ref = self.popups.borrow()[d].clone();
-                        Self::render_menu_at_depth(&self.state, &style, &popup_ref, d);
-                    }
-                }
-                // Render at current depth
-                if current_depth < self.popups.borrow().len() {
-                    let popup_ref = self.popups.borrow()[current_depth].clone();
-                    Self::render_menu_at_depth(&self.state, &style, &popup_ref, current_depth);
-                }
-            }
-            keycodes::ENTER => {
-                let current_depth = self.state.borrow().depth();
-                let state = self.state.borrow();
-                let selected_idx = state.selected_at_depth(current_depth);
-                let label = state.selected_label(None);
-
-                if let (Some(idx), Some(label)) = (selected_idx, label) {
-                    let label_owned = label.to_string();
-                    drop(state);
-
-                    Self::flash_and_activate(
-                        &self.state,
-                        &self.popups,
-                        &self.style,
-                        &self.on_item_click,
-                        current_depth,
-                        idx,
-                        &label_owned,
-                    );
+            keycodes::DOWN => self.move_selection(|state| state.select_next_at_depth(None)),
+            keycodes::UP => self.move_selection(|state| state.select_previous_at_depth(None)),
+            keycodes::HOME => self.move_selection(|state| state.select_first_at_depth(None)),
+            keycodes::END => self.move_selection(|state| state.select_last_at_depth(None)),
+            keycodes::ENTER | keycodes::KP_ENTER | keycodes::SPACE => {
+                if !self.open_selected_submenu() {
+                    self.activate_selected();
                 }
             }
             keycodes::ESC => {
                 tracing::debug!("context_menu: ESC pressed");
-                self.state.borrow_mut().request_close();
-                drop(style); // Drop before check_close
-                self.check_close();
+                if !self.close_innermost_submenu() {
+                    self.state.borrow_mut().request_close();
+                    self.check_close();
+                }
             }
             keycodes::RIGHT => {
-                let current_depth = self.state.borrow().depth();
-                // Open submenu if current item has one
-                let state = self.state.borrow();
-                let has_submenu = state.selected_has_submenu(None); // Use state's depth
-                let selected_idx = state.selected_index(None); // Use state's depth
-                drop(state);
-
-                if has_submenu {
-                    if let Some(idx) = selected_idx {
-                        // 1. Update state: open submenu and move to first item of submenu
-                        //    (open_submenu clears the parent selection)
-                        self.state.borrow_mut().open_submenu(current_depth, idx);
-                        self.state
-                            .borrow_mut()
-                            .select_at_depth(current_depth + 1, Some(0));
-
-                        // 2. Re-render parent to clear its highlight
-                        if current_depth < self.popups.borrow().len() {
-                            let popup_ref = self.popups.borrow()[current_depth].clone();
-                            Self::render_menu_at_depth(
-                                &self.state,
-                                &style,
-                                &popup_ref,
-                                current_depth,
-                            );
-                        }
-
-                        // 3. Show the submenu surface
-                        let show_delay = self.style.borrow().show_delay_keyboard;
-                        Self::show_submenu_static(
-                            &self.state,
-                            &self.popups,
-                            &self.style,
-                            &self.registered_surfaces,
-                            &self.parent_xdg,
-                            current_depth,
-                            idx,
-                            show_delay as f64,
-                        );
-                    }
-                }
+                self.open_selected_submenu();
             }
             keycodes::LEFT => {
-                let current_depth = self.state.borrow().depth();
-                tracing::debug!("context_menu: LEFT pressed, current_depth={current_depth}");
-                // Close submenu and move back to parent
-                if current_depth > 0 {
-                    let target_depth = current_depth - 1;
-                    tracing::debug!("context_menu: closing submenu at depth={current_depth}, target={target_depth}");
-
-                    // Remember which parent item had the open submenu so we
-                    // can restore selection on it after closing.
-                    let parent_item_idx = self.state.borrow().open_submenu_at(target_depth);
-
-                    // Hide submenu surfaces from current depth onwards
-                    Self::hide_submenus_from_static(&self.state, &self.popups, current_depth);
-
-                    // Update state: truncate to target_depth and set depth to target_depth
-                    self.state.borrow_mut().close_submenus_from(target_depth);
-
-                    // Restore selection on the parent item that had the submenu
-                    if let Some(idx) = parent_item_idx {
-                        self.state
-                            .borrow_mut()
-                            .select_at_depth(target_depth, Some(idx));
-                    }
+                self.close_innermost_submenu();
+            }
+            _ => {}
+        }
+    }
 
-                    tracing::debug!("context_menu: after LEFT, is_visible={}", self.is_visible());
+    /// Move the keyboard highlight within the innermost open menu,
+    /// scrolling it into view
+    fn move_selection(&self, select: impl FnOnce(&mut ContextMenuState)) {
+        let (current_depth, cleared) = {
+            let mut state_mut = self.state.borrow_mut();
+            select(&mut *state_mut);
+            let current_depth = state_mut.depth();
+            if let Some(idx) = state_mut.selected_at_depth(current_depth) {
+                let style = self.style.borrow();
+                let items = state_mut.items_at_depth(current_depth);
+                let scroll = state_mut.scroll_at_depth(current_depth);
+                let scroll = ContextMenuRenderer::scroll_to_reveal(items, &style, idx, scroll);
+                let max_scroll = ContextMenuRenderer::max_scroll(items, &style);
+                state_mut.set_scroll_at_depth(current_depth, scroll, max_scroll);
+            }
+            // Last input wins: keyboard owns the selection, clear all others
+            (
+                current_depth,
+                state_mut.clear_selections_except(current_depth),
+            )
+        };
+        for d in cleared.into_iter().chain(std::iter::once(current_depth)) {
+            self.render_depth(d);
+        }
+    }
 
-                    // Re-render parent menu
-                    if target_depth < self.popups.borrow().len() {
-                        let popup_ref = self.popups.borrow()[target_depth].clone();
-                        Self::render_menu_at_depth(&self.state, &style, &popup_ref, target_depth);
-                    }
-                }
+    /// Open the highlighted item's submenu and highlight its first item.
+    /// Returns false when the highlighted item has no submenu.
+    fn open_selected_submenu(&self) -> bool {
+        let (current_depth, selected_idx) = {
+            let state = self.state.borrow();
+            if !state.selected_has_submenu(None) {
+                return false;
             }
-            _ => {}
+            (state.depth(), state.selected_index(None))
+        };
+        let Some(idx) = selected_idx else {
+            return false;
+        };
+
+        // Open the submenu and move to its first item
+        // (open_submenu clears the parent selection)
+        {
+            let mut state_mut = self.state.borrow_mut();
+            state_mut.open_submenu(current_depth, idx);
+            state_mut.select_first_at_depth(Some(current_depth + 1));
+        }
+
+        // Re-render parent to clear its highlight
+        self.render_depth(current_depth);
+
+        let show_delay = self.style.borrow().show_delay_keyboard;
+        Self::show_submenu_static(
+            &self.state,
+            &self.popups,
+            &self.style,
+            &self.registered_surfaces,
+            &self.parent_xdg,
+            current_depth,
+            idx,
+            show_delay as f64,
+        );
+        true
+    }
+
+    /// Close the innermost submenu and highlight the item that opened it.
+    /// Returns false at the root menu.
+    fn close_innermost_submenu(&self) -> bool {
+        let current_depth = self.state.borrow().depth();
+        tracing::debug!("context_menu: closing submenu, current_depth={current_depth}");
+        if current_depth == 0 {
+            return false;
+        }
+        let target_depth = current_depth - 1;
+
+        // Remember which parent item had the open submenu so we
+        // can restore selection on it after closing.
+        let parent_item_idx = self.state.borrow().open_submenu_at(target_depth);
+
+        // Hide submenu surfaces from current depth onwards
+        Self::hide_submenus_from_static(&self.state, &self.popups, current_depth);
+
+        // Update state: truncate to target_depth and set depth to target_depth
+        {
+            let mut state_mut = self.state.borrow_mut();
+            state_mut.close_submenus_from(target_depth);
+            if let Some(idx) = parent_item_idx {
+                state_mut.select_at_depth(target_depth, Some(idx));
+            }
+        }
+
+        self.render_depth(target_depth);
+        true
+    }
+
+    /// Activate the highlighted item like a click would
+    fn activate_selected(&self) {
+        let (current_depth, selected) = {
+            let state = self.state.borrow();
+            let current_depth = state.depth();
+            let selected = state
+                .selected_at_depth(current_depth)
+                .zip(state.selected_item(None))
+                .filter(|(_, item)| item.is_enabled())
+                .and_then(|(idx, item)| Self::callback_id(item).map(|id| (idx, id)));
+            (current_depth, selected)
+        };
+
+        if let Some((idx, callback_id)) = selected {
+            Self::flash_and_activate(
+                &self.state,
+                &self.popups,
+                &self.style,
+                &self.on_item_click,
+                current_depth,
+                idx,
+                &callback_id,
+            );
+        }
+    }
+
+    /// Id passed to `on_item_click` for `item`: its action id, else its label
+    fn callback_id(item: &MenuItem) -> Option<String> {
+        item.action_id()
+            .or_else(|| item.label())
+            .map(str::to_string)
+    }
+
+    /// Redraw the popup at `depth`, if it is open
+    fn render_depth(&self, depth: usize) {
+        let popup_ref = self.popups.borrow().get(depth).cloned();
+        if let Some(popup_ref) = popup_ref {
+            Self::render_menu_at_depth(&self.state, &self.style.borrow(), &popup_ref, depth);
         }
     }
 
@@ -1109,10 +1304,39 @@ impl ContextMenu {
         let should = self.state.borrow().should_close();
         tracing::debug!("context_menu: check_close should_close={should}");
         if should {
-            if let Some(callback) = self.on_close.borrow().as_ref() {
-                callback();
-            }
+            self.notify_close();
             self.hide();
         }
     }
+
+    /// Fire the `on_close` callback
+    fn notify_close(&self) {
+        let callback = self.on_close.borrow().clone();
+        if let Some(callback) = callback {
+            callback();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placed_at(x: i32) -> PopupGeometry {
+        PopupGeometry {
+            x,
+            y: 0,
+            width: 120,
+            height: 200,
+        }
+    }
+
+    #[test]
+    fn flipped_submenus_open_left() {
+        assert!(!opened_left(placed_at(195), 200));
+        assert!(opened_left(placed_at(-115), 200));
+        // Slid back over the parent: the side holding most of it wins
+        assert!(!opened_left(placed_at(60), 200));
+        assert!(opened_left(placed_at(30), 200));
+    }
 }
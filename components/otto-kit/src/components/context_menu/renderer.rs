@@ -18,12 +18,11 @@ impl ContextMenuRenderer {
     /// Calculate dimensions for specific items (used for submenus)
     ///
     /// Returned dimensions are already multiplied by `style.draw_scale`.
+    /// The height stops at `style.max_height`; the items scroll beyond it.
     pub fn measure_items(items: &[MenuItem], style: &ContextMenuStyle) -> (f32, f32) {
         let s = style.draw_scale;
 
-        // Calculate height from items (item heights are in logical pixels)
-        let height: f32 = items.iter().map(|item| item.height).sum();
-        let height = (height + style.vertical_padding * 2.0) * s;
+        let height = Self::viewport_height(items, style) * s;
 
         // Use provided width or compute from content, then scale
         let width = style
@@ -34,15 +33,51 @@ impl ContextMenuRenderer {
         (width, height)
     }
 
+    /// Height of all items plus padding, in logical pixels
+    pub fn content_height(items: &[MenuItem], style: &ContextMenuStyle) -> f32 {
+        items.iter().map(|item| item.height).sum::<f32>() + style.vertical_padding * 2.0
+    }
+
+    /// Visible height of the menu in logical pixels, up to `style.max_height`
+    pub fn viewport_height(items: &[MenuItem], style: &ContextMenuStyle) -> f32 {
+        Self::content_height(items, style).min(style.max_height)
+    }
+
+    /// How far the items scroll, zero when they fit in `style.max_height`
+    pub fn max_scroll(items: &[MenuItem], style: &ContextMenuStyle) -> f32 {
+        (Self::content_height(items, style) - style.max_height).max(0.0)
+    }
+
+    /// Scroll offset that brings the item at `index` into view, moving the
+    /// current `scroll` as little as possible
+    pub fn scroll_to_reveal(
+        items: &[MenuItem],
+        style: &ContextMenuStyle,
+        index: usize,
+        scroll: f32,
+    ) -> f32 {
+        let top: f32 = items.iter().take(index).map(|item| item.height).sum();
+        let bottom = top + items.get(index).map_or(0.0, |item| item.height);
+        let visible = Self::viewport_height(items, style) - style.vertical_padding * 2.0;
+        let scroll = if top < scroll {
+            top
+        } else if bottom > scroll + visible {
+            bottom - visible
+        } else {
+            scroll
+        };
+        scroll.clamp(0.0, Self::max_scroll(items, style))
+    }
+
     /// Compute the optimal menu width based on item label text measurements.
     ///
     /// Measures every label (and shortcut) with the actual font, adds padding
     /// for icons and submenu arrows, and returns the widest row in logical pixels.
     pub fn compute_optimal_width(items: &[MenuItem], style: &ContextMenuStyle) -> f32 {
-        use crate::components::menu_item::{MenuItemKind, MenuItemStyle};
+        use crate::components::menu_item::MenuItemKind;
 
         let font = crate::typography::styles::BODY_MEDIUM.font();
-        let item_style = MenuItemStyle::default();
+        let item_style = style.item_style();
         let icon_size: f32 = 16.0;
         let icon_gap: f32 = 6.0;
         let submenu_arrow_space: f32 = 20.0;
@@ -89,6 +124,20 @@ impl ContextMenuRenderer {
         style: &ContextMenuStyle,
         width: f32,
         height: f32,
+    ) {
+        Self::render_depth_scrolled(canvas, items, selected, style, width, height, 0.0);
+    }
+
+    /// Render items at a specific depth, scrolled down by `scroll` logical
+    /// pixels (see `max_scroll()`)
+    pub fn render_depth_scrolled(
+        canvas: &Canvas,
+        items: &[MenuItem],
+        selected: Option<usize>,
+        style: &ContextMenuStyle,
+        width: f32,
+        height: f32,
+        scroll: f32,
     ) {
         let s = style.draw_scale;
         let logical_w = width / s;
@@ -101,7 +150,9 @@ impl ContextMenuRenderer {
         Self::draw_background(canvas, style, logical_w, logical_h);
 
         // Draw menu items with states
-        Self::draw_items_with_selection(canvas, items, selected, style, logical_w);
+        Self::draw_items_with_selection(
+            canvas, items, selected, style, logical_w, logical_h, scroll,
+        );
 
         canvas.restore();
     }
@@ -136,10 +187,23 @@ impl ContextMenuRenderer {
         selected: Option<usize>,
         style: &ContextMenuStyle,
         width: f32,
+        height: f32,
+        scroll: f32,
     ) {
-        // Save canvas state and translate for padding
+        // Save canvas state, keep the items inside the padding and
+        // translate them for padding and scroll
         canvas.save();
-        canvas.translate((style.horizontal_padding, style.vertical_padding));
+        canvas.clip_rect(
+            Rect::from_xywh(
+                0.0,
+                style.vertical_padding,
+                width,
+                height - style.vertical_padding * 2.0,
+            ),
+            None,
+            None,
+        );
+        canvas.translate((style.horizontal_padding, style.vertical_padding - scroll));
 
         // Apply hover state to items and convert to MenuItem components
         let menu_items_with_state: Vec<MenuItem> = items
@@ -158,6 +222,7 @@ impl ContextMenuRenderer {
         MenuItemGroup::new()
             .at(0.0, 0.0)
             .with_width(width - style.horizontal_padding * 2.0)
+            .with_style(style.item_style())
             .items(menu_items_with_state)
             .render(canvas);
 
@@ -185,6 +250,24 @@ impl ContextMenuRenderer {
         x: f32,
         y: f32,
     ) -> Option<usize> {
+        Self::hit_test_items_scrolled(items, style, x, y, 0.0)
+    }
+
+    /// Hit test specific items scrolled down by `scroll` logical pixels.
+    /// Items scrolled out of the menu are never hit.
+    pub fn hit_test_items_scrolled(
+        items: &[MenuItem],
+        style: &ContextMenuStyle,
+        x: f32,
+        y: f32,
+        scroll: f32,
+    ) -> Option<usize> {
+        let viewport_bottom = Self::viewport_height(items, style) - style.vertical_padding;
+        if y < style.vertical_padding || y > viewport_bottom {
+            return None;
+        }
+        let y = y + scroll;
+
         // Use the same width logic as measure_items
         let total_width = style
             .width
@@ -259,4 +342,78 @@ mod tests {
 
         assert_eq!(width, 300.0);
     }
+
+    fn long_items() -> Vec<MenuItem> {
+        (0..40)
+            .map(|i| MenuItem::action(format!("Item {i}")))
+            .collect()
+    }
+
+    #[test]
+    fn test_long_menus_stop_at_max_height() {
+        let items = long_items();
+        let style = ContextMenuStyle::default().with_max_height(200.0);
+
+        let (_, height) = ContextMenuRenderer::measure_items(&items, &style);
+        assert_eq!(height, 200.0);
+
+        let content = ContextMenuRenderer::content_height(&items, &style);
+        assert_eq!(
+            ContextMenuRenderer::max_scroll(&items, &style),
+            content - 200.0
+        );
+
+        // Short menus keep their size and don't scroll
+        let state = create_test_state();
+        assert_eq!(ContextMenuRenderer::max_scroll(state.items(), &style), 0.0);
+    }
+
+    #[test]
+    fn test_scroll_to_reveal() {
+        let items = long_items();
+        let style = ContextMenuStyle::default().with_max_height(200.0);
+        let item_h = items[0].height;
+        let visible = 200.0 - style.vertical_padding * 2.0;
+
+        // Already visible: no scroll
+        assert_eq!(
+            ContextMenuRenderer::scroll_to_reveal(&items, &style, 1, 0.0),
+            0.0
+        );
+
+        // Below the viewport: scroll just enough to show its bottom
+        let reveal = ContextMenuRenderer::scroll_to_reveal(&items, &style, 20, 0.0);
+        assert_eq!(reveal, item_h * 21.0 - visible);
+
+        // Above the viewport: scroll up to its top
+        let reveal = ContextMenuRenderer::scroll_to_reveal(&items, &style, 2, reveal);
+        assert_eq!(reveal, item_h * 2.0);
+
+        // The last item stops at the end of the content
+        let last = ContextMenuRenderer::scroll_to_reveal(&items, &style, 39, 0.0);
+        assert_eq!(last, ContextMenuRenderer::max_scroll(&items, &style));
+    }
+
+    #[test]
+    fn test_hit_test_follows_scroll() {
+        let items = long_items();
+        let style = ContextMenuStyle::default().with_max_height(200.0);
+        let item_h = items[0].height;
+        let x = style.horizontal_padding + 10.0;
+        let y = style.vertical_padding + item_h / 2.0;
+
+        assert_eq!(
+            ContextMenuRenderer::hit_test_items_scrolled(&items, &style, x, y, 0.0),
+            Some(0)
+        );
+        assert_eq!(
+            ContextMenuRenderer::hit_test_items_scrolled(&items, &style, x, y, item_h * 5.0),
+            Some(5)
+        );
+        // Below the viewport nothing is hit, even though items are there
+        assert_eq!(
+            ContextMenuRenderer::hit_test_items_scrolled(&items, &style, x, 250.0, 0.0),
+            None
+        );
+    }
 }
@@ -1,10 +1,12 @@
+use std::hash::{Hash, Hasher};
+
 use crate::{components::menu_item::MenuItem, prelude::ContextMenuStyle};
 
 /// State for ContextMenu
 ///
 /// Pure state management - no rendering, no surface logic.
 /// Handles menu items, selection, hover state, and submenu navigation.
-#[derive(Clone, Debug, Hash)]
+#[derive(Clone, Debug)]
 pub struct ContextMenuState {
     /// Menu items
     items: Vec<MenuItem>,
@@ -19,6 +21,9 @@ pub struct ContextMenuState {
     /// Which item has an open submenu at each depth
     open_submenu_by_depth: Vec<Option<usize>>,
 
+    /// Scroll offset of the menu at each depth, in logical pixels
+    scroll_by_depth: Vec<f32>,
+
     /// Current depth level (0 = root, 1 = first submenu level, etc.)
     depth: usize,
 
@@ -28,6 +33,21 @@ pub struct ContextMenuState {
     pub style: ContextMenuStyle,
 }
 
+impl Hash for ContextMenuState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.items.hash(state);
+        self.selected_index.hash(state);
+        self.selections_by_depth.hash(state);
+        self.open_submenu_by_depth.hash(state);
+        for scroll in &self.scroll_by_depth {
+            scroll.to_bits().hash(state);
+        }
+        self.depth.hash(state);
+        self.should_close.hash(state);
+        self.style.hash(state);
+    }
+}
+
 impl ContextMenuState {
     pub fn new(items: Vec<MenuItem>) -> Self {
         Self {
@@ -35,6 +55,7 @@ impl ContextMenuState {
             selected_index: None,
             selections_by_depth: Vec::new(),
             open_submenu_by_depth: Vec::new(),
+            scroll_by_depth: Vec::new(),
             depth: 0,
             should_close: false,
             style: ContextMenuStyle::default(),
@@ -79,17 +100,22 @@ impl ContextMenuState {
 
     // === Navigation Logic ===
 
-    /// Select next non-separator item
+    /// Select next enabled, non-separator item
     pub fn select_next(&mut self) {
         self.selected_index = Self::find_next_selectable(&self.items, self.selected_index, true);
     }
 
-    /// Select previous non-separator item
+    /// Select previous enabled, non-separator item
     pub fn select_previous(&mut self) {
         self.selected_index = Self::find_next_selectable(&self.items, self.selected_index, false);
     }
 
-    /// Find the next selectable (non-separator) item
+    /// Whether keyboard navigation can land on `item`
+    fn is_selectable(item: &MenuItem) -> bool {
+        !item.is_separator() && item.is_enabled()
+    }
+
+    /// Find the next selectable (enabled, non-separator) item
     fn find_next_selectable(
         items: &[MenuItem],
         current: Option<usize>,
@@ -113,10 +139,10 @@ impl ContextMenuState {
             _ => 0,
         };
 
-        // Search for next non-separator item
+        // Search for next selectable item
         let mut idx = start;
         for _ in 0..items.len() {
-            if !items[idx].is_separator() {
+            if Self::is_selectable(&items[idx]) {
                 return Some(idx);
             }
             idx = if forward {
@@ -206,6 +232,27 @@ impl ContextMenuState {
         self.select_at_depth(target_depth, prev);
     }
 
+    /// Select the first item at specific depth (or current depth if None)
+    pub fn select_first_at_depth(&mut self, depth: Option<usize>) {
+        let target_depth = depth.unwrap_or(self.depth);
+        let first = Self::find_next_selectable(self.items_at_depth(target_depth), None, true);
+        self.select_at_depth(target_depth, first);
+    }
+
+    /// Select the last item at specific depth (or current depth if None)
+    pub fn select_last_at_depth(&mut self, depth: Option<usize>) {
+        let target_depth = depth.unwrap_or(self.depth);
+        let last = Self::find_next_selectable(self.items_at_depth(target_depth), None, false);
+        self.select_at_depth(target_depth, last);
+    }
+
+    /// Selected item at specific depth (or current depth if None)
+    pub fn selected_item(&self, depth: Option<usize>) -> Option<&MenuItem> {
+        let target_depth = depth.unwrap_or(self.depth);
+        let idx = self.selected_at_depth(target_depth)?;
+        self.items_at_depth(target_depth).get(idx)
+    }
+
     /// Check if selected item at current depth has a submenu
     pub fn selected_has_submenu(&self, depth: Option<usize>) -> bool {
         let target_depth = depth.unwrap_or(self.depth);
@@ -236,6 +283,25 @@ impl ContextMenuState {
         self.selected_at_depth(maybe_depth.unwrap_or(self.depth))
     }
 
+    /// Scroll offset of the menu at `depth`
+    pub fn scroll_at_depth(&self, depth: usize) -> f32 {
+        self.scroll_by_depth.get(depth).copied().unwrap_or(0.0)
+    }
+
+    /// Scroll the menu at `depth` to `offset`, clamped to `0.0..=max_scroll`.
+    /// Returns `true` when the offset changed.
+    pub fn set_scroll_at_depth(&mut self, depth: usize, offset: f32, max_scroll: f32) -> bool {
+        let offset = offset.clamp(0.0, max_scroll.max(0.0));
+        if offset == self.scroll_at_depth(depth) {
+            return false;
+        }
+        if self.scroll_by_depth.len() <= depth {
+            self.scroll_by_depth.resize(depth + 1, 0.0);
+        }
+        self.scroll_by_depth[depth] = offset;
+        true
+    }
+
     /// Mark a submenu as open at the given depth
     pub fn open_submenu(&mut self, depth: usize, item_idx: usize) {
         // Ensure vec is large enough
@@ -269,6 +335,8 @@ impl ContextMenuState {
         if depth + 1 < self.selections_by_depth.len() {
             self.selections_by_depth.truncate(depth + 1);
         }
+        // Submenus opened again start at their top
+        self.scroll_by_depth.truncate(depth + 1);
         self.depth = depth;
     }
 
@@ -345,6 +413,7 @@ impl ContextMenuState {
     pub fn reset(&mut self) {
         self.selected_index = None;
         self.close_all_submenus();
+        self.scroll_by_depth.clear();
         self.should_close = false;
     }
 }
@@ -401,6 +470,66 @@ mod tests {
         assert_eq!(state.selected(), Some(0)); // Back to first, skipping separator
     }
 
+    #[test]
+    fn test_navigation_skips_disabled_items() {
+        let mut items = create_test_items();
+        items.push(MenuItem::action("Item 3").disabled());
+        let mut state = ContextMenuState::new(items);
+
+        state.select_last_at_depth(None);
+        assert_eq!(state.selected(), Some(2)); // Disabled item 3 is skipped
+
+        state.select_next_at_depth(None);
+        assert_eq!(state.selected(), Some(0)); // Wraps past it
+
+        state.select_first_at_depth(None);
+        assert_eq!(state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_keyboard_selection_in_submenu() {
+        let items = vec![
+            MenuItem::action("Open"),
+            MenuItem::submenu(
+                "More",
+                vec![MenuItem::action("First"), MenuItem::action("Second")],
+            ),
+        ];
+        let mut state = ContextMenuState::new(items);
+
+        state.select_at_depth(0, Some(1));
+        assert!(state.selected_has_submenu(None));
+
+        state.open_submenu(0, 1);
+        state.select_first_at_depth(None);
+        assert_eq!(state.selected(), None); // Parent highlight moves to the submenu
+        assert_eq!(
+            state.selected_item(None).and_then(|item| item.label()),
+            Some("First")
+        );
+
+        state.select_last_at_depth(None);
+        assert_eq!(state.selected_label(None), Some("Second"));
+    }
+
+    #[test]
+    fn test_scroll_is_clamped_and_reset() {
+        let mut state = ContextMenuState::new(create_test_items());
+
+        assert!(state.set_scroll_at_depth(0, 500.0, 120.0));
+        assert_eq!(state.scroll_at_depth(0), 120.0);
+        assert!(!state.set_scroll_at_depth(0, 200.0, 120.0));
+        assert!(state.set_scroll_at_depth(2, 40.0, 120.0));
+
+        // Closing a submenu forgets its scroll
+        state.close_submenus_from(1);
+        assert_eq!(state.scroll_at_depth(2), 0.0);
+        assert_eq!(state.scroll_at_depth(0), 120.0);
+
+        state.reset();
+        assert_eq!(state.scroll_at_depth(0), 0.0);
+    }
+
     #[test]
     fn test_submenu_management() {
         let mut state = ContextMenuState::new(vec![]);
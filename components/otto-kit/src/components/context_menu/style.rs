@@ -1,5 +1,6 @@
 use std::hash::Hash;
 
+use crate::components::menu_item::MenuItemStyle;
 use crate::theme::Theme;
 
 /// Visual styling for ContextMenuNext
@@ -21,6 +22,9 @@ pub struct ContextMenuStyle {
     /// Vertical padding inside menu
     pub vertical_padding: f32,
 
+    /// Tallest the menu gets; longer menus scroll
+    pub max_height: f32,
+
     // === Shapes ===
     /// Corner radius for rounded corners
     pub corner_radius: f32,
@@ -53,15 +57,23 @@ impl Hash for ContextMenuStyle {
         self.min_width.to_bits().hash(state);
         self.horizontal_padding.to_bits().hash(state);
         self.vertical_padding.to_bits().hash(state);
+        self.max_height.to_bits().hash(state);
         self.corner_radius.to_bits().hash(state);
         self.border_width.to_bits().hash(state);
         self.show_delay_mouse.to_bits().hash(state);
         self.show_delay_keyboard.to_bits().hash(state);
         self.close_delay.to_bits().hash(state);
         self.draw_scale.to_bits().hash(state);
-        // For theme, we can hash the relevant colors
-        // self.theme.material_titlebar.hash(state);
-        // self.theme.fill_secondary.hash(state);
+        // The theme colors the menu draws with
+        for color in [
+            self.theme.accent,
+            self.theme.material_medium,
+            self.theme.material_selection_focused,
+            self.theme.fill_primary,
+            self.theme.text_primary,
+        ] {
+            u32::from(color).hash(state);
+        }
     }
 }
 
@@ -72,6 +84,7 @@ impl Default for ContextMenuStyle {
             min_width: 220.0,
             horizontal_padding: 5.0,
             vertical_padding: 5.0,
+            max_height: 640.0,
             corner_radius: 8.0,
             border_width: 1.0,
             show_delay_mouse: 0.2,
@@ -121,6 +134,11 @@ impl ContextMenuStyle {
         self
     }
 
+    pub fn with_max_height(mut self, max_height: f32) -> Self {
+        self.max_height = max_height;
+        self
+    }
+
     // === Animation Delay Builders ===
 
     pub fn with_show_delay_mouse(mut self, delay: f32) -> Self {
@@ -159,4 +177,10 @@ impl ContextMenuStyle {
     pub fn border_color(&self) -> skia_safe::Color {
         self.theme.fill_primary
     }
+
+    /// Style of the menu items, colored by the theme: hovered and selected
+    /// items are highlighted with its accent
+    pub fn item_style(&self) -> MenuItemStyle {
+        MenuItemStyle::default().with_theme(&self.theme)
+    }
 }
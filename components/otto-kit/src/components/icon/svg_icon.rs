@@ -279,7 +279,7 @@ impl Renderable for Icon {
                 match cache.get(icon_name) {
                     Some((data, size)) => (data, *size),
                     None => {
-                        eprintln!("Tabler icon '{}' not found", icon_name);
+                        tracing::debug!("Icon '{}' not found in Tabler set", icon_name);
                         return;
                     }
                 }
@@ -289,7 +289,7 @@ impl Renderable for Icon {
                 match cache.get(icon_name) {
                     Some((data, size)) => (data, *size),
                     None => {
-                        eprintln!("Filled icon '{}' not found", icon_name);
+                        tracing::debug!("Icon '{}' not found in filled set", icon_name);
                         return;
                     }
                 }
@@ -311,7 +311,7 @@ impl Renderable for Icon {
         let path = match Path::from_svg(path_data) {
             Some(p) => p,
             None => {
-                eprintln!("Failed to parse SVG path for icon '{}'", self.icon_name);
+                tracing::warn!("Failed to parse SVG path for icon '{}'", self.icon_name);
                 return;
             }
         };
@@ -0,0 +1,367 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use skia_safe as skia;
+use skia_safe::{Canvas, Color, Paint, RRect, Rect};
+
+use crate::app_runner::AppContext;
+use crate::common::Renderable;
+
+// ---------------------------------------------------------------------------
+// Image cache
+// ---------------------------------------------------------------------------
+
+type ImageCache = Arc<RwLock<HashMap<String, Option<skia::Image>>>>;
+
+static IMAGE_CACHE: OnceLock<ImageCache> = OnceLock::new();
+
+fn image_cache() -> ImageCache {
+    IMAGE_CACHE
+        .get_or_init(|| Arc::new(RwLock::new(HashMap::new())))
+        .clone()
+}
+
+/// Drop every cached image, e.g. after the files on disk changed
+pub fn clear_image_cache() {
+    image_cache().write().unwrap().clear();
+}
+
+/// Where an Image gets its encoded data
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImageSource {
+    /// A PNG, JPEG or SVG file
+    Path(PathBuf),
+    /// Encoded PNG or JPEG data, identified by `key` in the cache
+    Bytes { key: String, data: Arc<[u8]> },
+}
+
+impl ImageSource {
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        Self::Path(path.into())
+    }
+
+    /// Encoded bytes, keyed in the cache by a hash of the data
+    pub fn bytes(data: impl Into<Arc<[u8]>>) -> Self {
+        let data = data.into();
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        Self::Bytes {
+            key: format!("{:016x}", hasher.finish()),
+            data,
+        }
+    }
+
+    fn cache_key(&self, size: (i32, i32)) -> String {
+        let (width, height) = size;
+        match self {
+            Self::Path(path) => format!("file:{}@{width}x{height}", path.display()),
+            Self::Bytes { key, .. } => format!("bytes:{key}@{width}x{height}"),
+        }
+    }
+
+    /// Decode the source, scaled down to cover `size` pixels
+    fn load(&self, size: (i32, i32)) -> Option<skia::Image> {
+        let image = match self {
+            Self::Path(path) => crate::icons::image_from_path(&path.to_string_lossy(), size)?,
+            Self::Bytes { data, .. } => skia::Image::from_encoded(skia::Data::new_copy(data))?,
+        };
+        let scale = cover_scale((image.width(), image.height()), size);
+        if scale >= 1.0 {
+            return Some(image);
+        }
+        // Keep large photos from being sampled down on every draw
+        let scaled = (
+            ((image.width() as f32 * scale).round() as i32).max(1),
+            ((image.height() as f32 * scale).round() as i32).max(1),
+        );
+        let mut surface = skia::surfaces::raster_n32_premul(scaled)?;
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        surface.canvas().draw_image_rect_with_sampling_options(
+            &image,
+            None,
+            Rect::from_iwh(scaled.0, scaled.1),
+            skia::SamplingOptions::new(skia::FilterMode::Linear, skia::MipmapMode::Linear),
+            &paint,
+        );
+        Some(surface.image_snapshot())
+    }
+}
+
+/// How an Image fills its bounds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImageFit {
+    /// Scale to fit inside the bounds, keeping the aspect ratio
+    #[default]
+    Contain,
+    /// Scale to fill the bounds, keeping the aspect ratio and cropping the rest
+    Cover,
+    /// Stretch to the bounds
+    Fill,
+}
+
+/// A raster image loaded from a file or encoded bytes
+///
+/// Decoded images are cached by source and pixel size, so rendering the same
+/// Image every frame decodes it once. As a layer (`to_layer()`), the engine
+/// keeps the drawn content until the layer gets a new draw function, so
+/// replace it only when the source changes.
+///
+/// # Examples
+///
+/// ```no_run
+/// use otto_kit::components::image::{Image, ImageFit};
+///
+/// let album_art = Image::from_path("/tmp/cover.jpg")
+///     .at(16.0, 16.0)
+///     .with_size(64.0, 64.0)
+///     .with_fit(ImageFit::Cover)
+///     .with_corner_radius(8.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Image {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    source: Option<ImageSource>,
+    fit: ImageFit,
+    corner_radius: f32,
+    /// Drawn when the source is missing or fails to load
+    placeholder: Option<Color>,
+}
+
+impl Image {
+    pub fn new(source: ImageSource) -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 64.0,
+            height: 64.0,
+            source: Some(source),
+            fit: ImageFit::default(),
+            corner_radius: 0.0,
+            placeholder: None,
+        }
+    }
+
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        Self::new(ImageSource::path(path))
+    }
+
+    pub fn from_bytes(data: impl Into<Arc<[u8]>>) -> Self {
+        Self::new(ImageSource::bytes(data))
+    }
+
+    /// An image with no source yet; draws only the placeholder
+    pub fn empty() -> Self {
+        Self {
+            source: None,
+            ..Self::new(ImageSource::path(""))
+        }
+    }
+
+    pub fn at(mut self, x: f32, y: f32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    pub fn with_size(mut self, width: f32, height: f32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn with_fit(mut self, fit: ImageFit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    pub fn with_corner_radius(mut self, radius: f32) -> Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    pub fn with_placeholder(mut self, color: Color) -> Self {
+        self.placeholder = Some(color);
+        self
+    }
+
+    pub fn build(self) -> Self {
+        self
+    }
+
+    pub fn source(&self) -> Option<&ImageSource> {
+        self.source.as_ref()
+    }
+
+    /// Replace the source. Returns `true` when it changed.
+    pub fn set_source(&mut self, source: Option<ImageSource>) -> bool {
+        if self.source == source {
+            return false;
+        }
+        self.source = source;
+        true
+    }
+
+    /// The decoded image at the current size, from the cache when possible
+    pub fn image(&self) -> Option<skia::Image> {
+        let source = self.source.as_ref()?;
+        let scale = AppContext::scale_factor().max(1) as f32;
+        let size = (
+            (self.width * scale).ceil() as i32,
+            (self.height * scale).ceil() as i32,
+        );
+        if size.0 <= 0 || size.1 <= 0 {
+            return None;
+        }
+
+        let cache_key = source.cache_key(size);
+        let cache = image_cache();
+        if let Some(entry) = cache.read().unwrap().get(&cache_key) {
+            return entry.clone();
+        }
+
+        let image = source.load(size);
+        if image.is_none() {
+            tracing::warn!("Failed to load image {cache_key}");
+        }
+        cache.write().unwrap().insert(cache_key, image.clone());
+        image
+    }
+}
+
+impl Renderable for Image {
+    fn render(&self, canvas: &Canvas) {
+        let bounds = Rect::from_xywh(self.x, self.y, self.width, self.height);
+        let rrect = RRect::new_rect_xy(bounds, self.corner_radius, self.corner_radius);
+
+        let Some(image) = self.image() else {
+            if let Some(color) = self.placeholder {
+                let mut paint = Paint::default();
+                paint.set_anti_alias(true);
+                paint.set_color(color);
+                canvas.draw_rrect(rrect, &paint);
+            }
+            return;
+        };
+
+        let (src, dst) = fit_rects((image.width(), image.height()), bounds, self.fit);
+        canvas.save();
+        if self.corner_radius > 0.0 {
+            canvas.clip_rrect(rrect, None, true);
+        }
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        canvas.draw_image_rect_with_sampling_options(
+            &image,
+            Some((&src, skia::canvas::SrcRectConstraint::Fast)),
+            dst,
+            skia::SamplingOptions::new(skia::FilterMode::Linear, skia::MipmapMode::None),
+            &paint,
+        );
+        canvas.restore();
+    }
+
+    fn intrinsic_size(&self) -> Option<(f32, f32)> {
+        Some((self.width, self.height))
+    }
+}
+
+/// Scale that makes an image of `image` pixels cover `target` pixels,
+/// capped at 1 so images are only ever scaled down
+fn cover_scale(image: (i32, i32), target: (i32, i32)) -> f32 {
+    if image.0 <= 0 || image.1 <= 0 {
+        return 1.0;
+    }
+    let scale_x = target.0 as f32 / image.0 as f32;
+    let scale_y = target.1 as f32 / image.1 as f32;
+    scale_x.max(scale_y).min(1.0)
+}
+
+/// Source rect in the image and destination rect in `bounds` for `fit`
+fn fit_rects(image: (i32, i32), bounds: Rect, fit: ImageFit) -> (Rect, Rect) {
+    let (image_width, image_height) = (image.0 as f32, image.1 as f32);
+    let full = Rect::from_wh(image_width, image_height);
+    if image_width <= 0.0 || image_height <= 0.0 {
+        return (full, bounds);
+    }
+    let scale_x = bounds.width() / image_width;
+    let scale_y = bounds.height() / image_height;
+    match fit {
+        ImageFit::Fill => (full, bounds),
+        ImageFit::Contain => {
+            let scale = scale_x.min(scale_y);
+            let (width, height) = (image_width * scale, image_height * scale);
+            let dst = Rect::from_xywh(
+                bounds.left + (bounds.width() - width) / 2.0,
+                bounds.top + (bounds.height() - height) / 2.0,
+                width,
+                height,
+            );
+            (full, dst)
+        }
+        ImageFit::Cover => {
+            let scale = scale_x.max(scale_y);
+            let (width, height) = (bounds.width() / scale, bounds.height() / scale);
+            let src = Rect::from_xywh(
+                (image_width - width) / 2.0,
+                (image_height - height) / 2.0,
+                width,
+                height,
+            );
+            (src, bounds)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contain_letterboxes() {
+        let bounds = Rect::from_xywh(10.0, 10.0, 100.0, 100.0);
+        let (src, dst) = fit_rects((200, 100), bounds, ImageFit::Contain);
+        assert_eq!(src, Rect::from_wh(200.0, 100.0));
+        assert_eq!(dst, Rect::from_xywh(10.0, 35.0, 100.0, 50.0));
+    }
+
+    #[test]
+    fn test_cover_crops_center() {
+        let bounds = Rect::from_xywh(0.0, 0.0, 100.0, 100.0);
+        let (src, dst) = fit_rects((200, 100), bounds, ImageFit::Cover);
+        assert_eq!(src, Rect::from_xywh(50.0, 0.0, 100.0, 100.0));
+        assert_eq!(dst, bounds);
+    }
+
+    #[test]
+    fn test_fill_stretches() {
+        let bounds = Rect::from_xywh(0.0, 0.0, 30.0, 90.0);
+        let (src, dst) = fit_rects((200, 100), bounds, ImageFit::Fill);
+        assert_eq!(src, Rect::from_wh(200.0, 100.0));
+        assert_eq!(dst, bounds);
+    }
+
+    #[test]
+    fn test_cover_scale_only_shrinks() {
+        assert_eq!(cover_scale((1000, 500), (100, 100)), 0.2);
+        assert_eq!(cover_scale((50, 50), (100, 100)), 1.0);
+    }
+
+    #[test]
+    fn test_cache_keys() {
+        let a = ImageSource::bytes(vec![1u8, 2, 3]);
+        let b = ImageSource::bytes(vec![1u8, 2, 3]);
+        assert_eq!(a.cache_key((64, 64)), b.cache_key((64, 64)));
+        assert_ne!(a.cache_key((64, 64)), a.cache_key((128, 128)));
+        assert_eq!(
+            ImageSource::path("/tmp/a.png").cache_key((32, 16)),
+            "file:/tmp/a.png@32x16"
+        );
+    }
+}
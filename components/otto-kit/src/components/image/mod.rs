@@ -0,0 +1,4 @@
+#![allow(clippy::module_inception)]
+mod image;
+
+pub use image::{clear_image_cache, Image, ImageFit, ImageSource};
@@ -1,7 +1,7 @@
 use skia_safe::{Canvas, Color, Font, Paint, Point};
 
 use crate::common::Renderable;
-use crate::typography::TextStyle;
+use crate::typography::{shape_text, TextStyle};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TextAlign {
@@ -11,6 +11,9 @@ pub enum TextAlign {
 }
 
 /// A simple text label
+///
+/// The text is shaped, so ligatures, complex scripts, right-to-left text
+/// and emoji render correctly.
 pub struct Label {
     pub x: f32,
     pub y: f32,
@@ -71,7 +74,8 @@ impl Renderable for Label {
         paint.set_color(self.color);
         paint.set_anti_alias(true);
 
-        let (text_width, _) = self.font.measure_str(&self.text, None);
+        let shaped = shape_text(&self.text, &self.font);
+        let text_width = shaped.width;
         let width = self.width.unwrap_or(text_width);
 
         let x = match self.align {
@@ -82,11 +86,11 @@ impl Renderable for Label {
 
         let y = self.y + self.font.size() * 0.8;
 
-        canvas.draw_str(&self.text, Point::new(x, y), &self.font, &paint);
+        shaped.draw(canvas, Point::new(x, y), &paint);
     }
 
     fn intrinsic_size(&self) -> Option<(f32, f32)> {
-        let (text_width, _) = self.font.measure_str(&self.text, None);
+        let text_width = shape_text(&self.text, &self.font).width;
         let width = self.width.unwrap_or(text_width);
         Some((width, self.font.size()))
     }
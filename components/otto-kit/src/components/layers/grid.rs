@@ -0,0 +1,228 @@
+use crate::app_runner::AppContext;
+use layers::prelude::*;
+
+use super::frame::LayerFrame;
+use super::stack::{LayerStack, StackAlignment};
+
+fn align_items(alignment: StackAlignment) -> taffy::AlignItems {
+    match alignment {
+        StackAlignment::Start => taffy::AlignItems::Start,
+        StackAlignment::Center => taffy::AlignItems::Center,
+        StackAlignment::End => taffy::AlignItems::End,
+        StackAlignment::Stretch => taffy::AlignItems::Stretch,
+    }
+}
+
+/// `count` equally sized tracks
+fn equal_tracks(count: usize) -> Vec<taffy::TrackSizingFunction> {
+    vec![taffy::style_helpers::fr(1.0); count.max(1)]
+}
+
+/// A grid of equal columns laid out by taffy
+///
+/// Children fill the columns left to right and wrap onto new rows, each
+/// row as tall as its tallest child. Children can span several columns
+/// and rows.
+pub struct LayerGrid {
+    layer: Layer,
+}
+
+impl LayerGrid {
+    /// Create a new grid with `columns` columns (automatically added to scene)
+    pub fn new(columns: usize) -> Self {
+        let engine = AppContext::layers_engine()
+            .expect("Layers engine not initialized. Make sure to call this after app starts.");
+
+        let layer = engine.new_layer();
+
+        layer.set_layout_style(taffy::Style {
+            display: taffy::Display::Grid,
+            grid_template_columns: equal_tracks(columns),
+            ..Default::default()
+        });
+
+        let _ = engine.add_layer(&layer.id());
+
+        LayerGrid { layer }
+    }
+
+    pub fn layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    pub fn id(&self) -> layers::engine::NodeRef {
+        self.layer.id()
+    }
+
+    // Direct setters - read current style and update
+
+    pub fn set_columns(&self, columns: usize) {
+        let mut style = self.layer.node_layout_style();
+        style.grid_template_columns = equal_tracks(columns);
+        self.layer.set_layout_style(style);
+    }
+
+    /// Split the height into `rows` equal rows instead of sizing rows to
+    /// their content
+    pub fn set_rows(&self, rows: usize) {
+        let mut style = self.layer.node_layout_style();
+        style.grid_template_rows = equal_tracks(rows);
+        self.layer.set_layout_style(style);
+    }
+
+    pub fn set_gap(&self, gap: f32) {
+        self.set_gaps(gap, gap);
+    }
+
+    pub fn set_gaps(&self, column_gap: f32, row_gap: f32) {
+        let mut style = self.layer.node_layout_style();
+
+        style.gap = taffy::Size {
+            width: taffy::LengthPercentage::Length(column_gap),
+            height: taffy::LengthPercentage::Length(row_gap),
+        };
+
+        self.layer.set_layout_style(style);
+    }
+
+    /// Align children inside their cells, in both directions
+    pub fn set_alignment(&self, alignment: StackAlignment) {
+        let mut style = self.layer.node_layout_style();
+
+        style.align_items = Some(align_items(alignment));
+        style.justify_items = Some(align_items(alignment));
+
+        self.layer.set_layout_style(style);
+    }
+
+    pub fn add_child(&self, child: &LayerFrame) {
+        let _ = self.layer.add_sublayer(&child.id());
+    }
+
+    /// Add a child covering `columns` columns and `rows` rows
+    pub fn add_child_spanning(&self, child: &LayerFrame, columns: u16, rows: u16) {
+        let mut style = child.layer().node_layout_style();
+        style.grid_column = taffy::Line {
+            start: taffy::GridPlacement::Span(columns.max(1)),
+            end: taffy::GridPlacement::Auto,
+        };
+        style.grid_row = taffy::Line {
+            start: taffy::GridPlacement::Span(rows.max(1)),
+            end: taffy::GridPlacement::Auto,
+        };
+        child.layer().set_layout_style(style);
+
+        self.add_child(child);
+    }
+
+    pub fn add_stack(&self, child: &LayerStack) {
+        let _ = self.layer.add_sublayer(&child.id());
+    }
+
+    pub fn add_grid(&self, child: &LayerGrid) {
+        let _ = self.layer.add_sublayer(&child.id());
+    }
+
+    pub fn set_draw(&self, draw_fn: impl Into<ContentDrawFunction>) {
+        self.layer.set_draw_content(draw_fn.into());
+    }
+}
+
+/// A row of children that wraps onto new lines when it runs out of width
+///
+/// Unlike `LayerGrid`, children keep their own widths, like words in a
+/// paragraph. Useful for tags, chips or icon pickers.
+pub struct LayerFlow {
+    layer: Layer,
+}
+
+impl LayerFlow {
+    /// Create a new flow layout (automatically added to scene)
+    pub fn new() -> Self {
+        let engine = AppContext::layers_engine()
+            .expect("Layers engine not initialized. Make sure to call this after app starts.");
+
+        let layer = engine.new_layer();
+
+        layer.set_layout_style(taffy::Style {
+            display: taffy::Display::Flex,
+            flex_direction: taffy::FlexDirection::Row,
+            flex_wrap: taffy::FlexWrap::Wrap,
+            ..Default::default()
+        });
+
+        let _ = engine.add_layer(&layer.id());
+
+        LayerFlow { layer }
+    }
+
+    pub fn layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    pub fn id(&self) -> layers::engine::NodeRef {
+        self.layer.id()
+    }
+
+    // Direct setters - read current style and update
+
+    pub fn set_gap(&self, gap: f32) {
+        self.set_gaps(gap, gap);
+    }
+
+    pub fn set_gaps(&self, column_gap: f32, row_gap: f32) {
+        let mut style = self.layer.node_layout_style();
+
+        style.gap = taffy::Size {
+            width: taffy::LengthPercentage::Length(column_gap),
+            height: taffy::LengthPercentage::Length(row_gap),
+        };
+
+        self.layer.set_layout_style(style);
+    }
+
+    /// Align children within their line
+    pub fn set_alignment(&self, alignment: StackAlignment) {
+        let mut style = self.layer.node_layout_style();
+
+        style.align_items = Some(align_items(alignment));
+
+        self.layer.set_layout_style(style);
+    }
+
+    /// Place each line's children at the start, center or end of the line
+    pub fn set_line_alignment(&self, alignment: StackAlignment) {
+        let mut style = self.layer.node_layout_style();
+
+        style.justify_content = Some(match alignment {
+            StackAlignment::Start => taffy::JustifyContent::Start,
+            StackAlignment::Center => taffy::JustifyContent::Center,
+            StackAlignment::End => taffy::JustifyContent::End,
+            StackAlignment::Stretch => taffy::JustifyContent::SpaceBetween,
+        });
+
+        self.layer.set_layout_style(style);
+    }
+
+    pub fn add_child(&self, child: &LayerFrame) {
+        let _ = self.layer.add_sublayer(&child.id());
+    }
+
+    pub fn add_stack(&self, child: &LayerStack) {
+        let _ = self.layer.add_sublayer(&child.id());
+    }
+
+    pub fn add_grid(&self, child: &LayerGrid) {
+        let _ = self.layer.add_sublayer(&child.id());
+    }
+
+    pub fn set_draw(&self, draw_fn: impl Into<ContentDrawFunction>) {
+        self.layer.set_draw_content(draw_fn.into());
+    }
+}
+
+impl Default for LayerFlow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,7 +3,9 @@
 /// High-level wrappers around the layers engine that provide
 /// ergonomic builder patterns for creating UI elements.
 mod frame;
+mod grid;
 mod stack;
 
 pub use frame::LayerFrame;
+pub use grid::{LayerFlow, LayerGrid};
 pub use stack::{LayerStack, StackAlignment, StackDirection};
@@ -85,6 +85,10 @@ impl LayerStack {
         let _ = self.layer.add_sublayer(&child.id());
     }
 
+    pub fn add_grid(&self, child: &super::grid::LayerGrid) {
+        let _ = self.layer.add_sublayer(&child.id());
+    }
+
     pub fn set_draw(&self, draw_fn: impl Into<ContentDrawFunction>) {
         self.layer.set_draw_content(draw_fn.into());
     }
@@ -0,0 +1,482 @@
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use smithay_client_toolkit::seat::pointer::{PointerEventKind, BTN_LEFT};
+use smithay_client_toolkit::shell::xdg::XdgPositioner;
+use wayland_client::{protocol::wl_keyboard, protocol::wl_surface::WlSurface, Proxy};
+use wayland_protocols::xdg::shell::client::xdg_positioner;
+
+use super::renderer::ItemBounds;
+use super::surface::MenuBarSurface;
+use super::{MenuBarItem, MenuBarRenderer, MenuBarState, MenuBarStyle};
+use crate::app_runner::AppContext;
+use crate::components::context_menu::{ContextMenu, ContextMenuStyle};
+use crate::components::menu_item::MenuItem;
+use crate::input::keycodes;
+use crate::surfaces::SurfaceError;
+use crate::theme::Theme;
+
+type ItemClickCallback = Rc<RefCell<Option<Rc<dyn Fn(usize, &str)>>>>;
+
+/// Next (or previous) item after `from` that has a dropdown, wrapping
+/// around the bar; `None` when no other item has one
+fn next_menu_index(from: usize, has_menu: &[bool], forward: bool) -> Option<usize> {
+    let len = has_menu.len();
+    (1..len)
+        .map(|step| {
+            if forward {
+                (from + step) % len
+            } else {
+                (from + len - step) % len
+            }
+        })
+        .find(|&index| has_menu[index])
+}
+
+/// Global menu bar on a top layer-shell strip
+///
+/// Holds a row of [`MenuBarItem`]s, each optionally owning a [`ContextMenu`]
+/// dropdown. Clicking an item opens its dropdown below it; while one is
+/// open, hovering another item switches to it and Left/Right move between
+/// dropdowns. Clicking the active item again, clicking outside, Escape or
+/// picking an item closes it.
+///
+/// # Example
+/// ```no_run
+/// use otto_kit::components::menu_bar::MenuBar;
+/// use otto_kit::components::menu_item::MenuItem;
+///
+/// let bar = MenuBar::new()
+///     .add_menu("File", vec![MenuItem::action("New"), MenuItem::action("Quit")])
+///     .on_item_click(|menu, id| println!("menu {menu}: {id}"));
+/// bar.show()?;
+/// ```
+#[derive(Clone)]
+pub struct MenuBar {
+    state: Rc<RefCell<MenuBarState>>,
+    style: Rc<RefCell<MenuBarStyle>>,
+    menu_style: Rc<RefCell<ContextMenuStyle>>,
+
+    // Dropdown per bar item, `None` for items without one
+    menus: Rc<RefCell<Vec<Option<ContextMenu>>>>,
+
+    surface: Rc<RefCell<Option<MenuBarSurface>>>,
+
+    // Item rects from the last render, for hit testing
+    item_bounds: Rc<RefCell<Vec<ItemBounds>>>,
+
+    on_item_click: ItemClickCallback,
+}
+
+/// A [`MenuBar`] that does not keep it alive, for callbacks the bar owns
+struct WeakMenuBar {
+    state: Weak<RefCell<MenuBarState>>,
+    style: Weak<RefCell<MenuBarStyle>>,
+    menu_style: Weak<RefCell<ContextMenuStyle>>,
+    menus: Weak<RefCell<Vec<Option<ContextMenu>>>>,
+    surface: Weak<RefCell<Option<MenuBarSurface>>>,
+    item_bounds: Weak<RefCell<Vec<ItemBounds>>>,
+    on_item_click: Weak<RefCell<Option<Rc<dyn Fn(usize, &str)>>>>,
+}
+
+impl WeakMenuBar {
+    fn upgrade(&self) -> Option<MenuBar> {
+        Some(MenuBar {
+            state: self.state.upgrade()?,
+            style: self.style.upgrade()?,
+            menu_style: self.menu_style.upgrade()?,
+            menus: self.menus.upgrade()?,
+            surface: self.surface.upgrade()?,
+            item_bounds: self.item_bounds.upgrade()?,
+            on_item_click: self.on_item_click.upgrade()?,
+        })
+    }
+}
+
+impl MenuBar {
+    // === Construction ===
+
+    /// Create an empty menu bar
+    pub fn new() -> Self {
+        Self {
+            state: Rc::new(RefCell::new(MenuBarState::new())),
+            style: Rc::new(RefCell::new(MenuBarStyle::default())),
+            menu_style: Rc::new(RefCell::new(ContextMenuStyle::default())),
+            menus: Rc::new(RefCell::new(Vec::new())),
+            surface: Rc::new(RefCell::new(None)),
+            item_bounds: Rc::new(RefCell::new(Vec::new())),
+            on_item_click: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    // === Builder API ===
+
+    pub fn with_style(self, style: MenuBarStyle) -> Self {
+        *self.style.borrow_mut() = style;
+        self.render();
+        self
+    }
+
+    /// Style the bar and its dropdowns from `theme`
+    pub fn with_theme(self, theme: Theme) -> Self {
+        let style = self.style.borrow().clone().with_theme(theme.clone());
+        *self.style.borrow_mut() = style;
+
+        let menu_style = self.menu_style.borrow().clone().with_theme(theme);
+        *self.menu_style.borrow_mut() = menu_style.clone();
+        for menu in self.menus.borrow().iter().flatten() {
+            menu.clone().with_style(menu_style.clone());
+        }
+
+        self.render();
+        self
+    }
+
+    /// Add a text item that opens a dropdown with `items`
+    pub fn add_menu(self, label: impl Into<String>, items: Vec<MenuItem>) -> Self {
+        let item = MenuBarItem {
+            label: Some(label.into()),
+            icon: None,
+        };
+        self.add_item(item, Some(items))
+    }
+
+    /// Add an item, with a dropdown when `items` is `Some`
+    pub fn add_item(self, item: MenuBarItem, items: Option<Vec<MenuItem>>) -> Self {
+        let index = self.menus.borrow().len();
+        let menu = items.map(|items| self.build_menu(index, items));
+        self.state.borrow_mut().add(item);
+        self.menus.borrow_mut().push(menu);
+        self.render();
+        self
+    }
+
+    /// Called with the bar item index and the picked item's id
+    /// (its action id, else its label)
+    pub fn on_item_click<F>(self, callback: F) -> Self
+    where
+        F: Fn(usize, &str) + 'static,
+    {
+        *self.on_item_click.borrow_mut() = Some(Rc::new(callback));
+        self
+    }
+
+    /// The dropdown is stored in the bar, so its callbacks hold the bar
+    /// weakly
+    fn build_menu(&self, index: usize, items: Vec<MenuItem>) -> ContextMenu {
+        let click_bar = self.downgrade();
+        let close_bar = self.downgrade();
+        ContextMenu::new(items)
+            .with_style(self.menu_style.borrow().clone())
+            .on_item_click(move |id| {
+                let Some(bar) = click_bar.upgrade() else {
+                    return;
+                };
+                bar.dismiss(index);
+                let callback = bar.on_item_click.borrow().clone();
+                if let Some(callback) = callback {
+                    callback(index, id);
+                }
+            })
+            .on_close(move || {
+                if let Some(bar) = close_bar.upgrade() {
+                    bar.dismiss(index);
+                }
+            })
+    }
+
+    fn downgrade(&self) -> WeakMenuBar {
+        WeakMenuBar {
+            state: Rc::downgrade(&self.state),
+            style: Rc::downgrade(&self.style),
+            menu_style: Rc::downgrade(&self.menu_style),
+            menus: Rc::downgrade(&self.menus),
+            surface: Rc::downgrade(&self.surface),
+            item_bounds: Rc::downgrade(&self.item_bounds),
+            on_item_click: Rc::downgrade(&self.on_item_click),
+        }
+    }
+
+    // === Surface Management ===
+
+    /// Create the layer surface and start handling pointer input
+    pub fn show(&self) -> Result<(), SurfaceError> {
+        let height = self.style.borrow().height.ceil() as u32;
+        let surface = MenuBarSurface::new(height)?;
+
+        let bar = self.downgrade();
+        surface.on_configure(move || {
+            if let Some(bar) = bar.upgrade() {
+                bar.render();
+            }
+        });
+        let bar = self.downgrade();
+        surface.on_resize(move |_, _| {
+            if let Some(bar) = bar.upgrade() {
+                bar.render();
+            }
+        });
+        *self.surface.borrow_mut() = Some(surface);
+
+        self.register_pointer_handler();
+        Ok(())
+    }
+
+    /// The bar surface, once [`show`](Self::show) has been called
+    pub fn surface(&self) -> Option<MenuBarSurface> {
+        self.surface.borrow().clone()
+    }
+
+    /// Redraw the bar
+    pub fn render(&self) {
+        let Some(surface) = self.surface() else {
+            return;
+        };
+        let width = surface.width();
+        if width <= 0.0 {
+            return;
+        }
+
+        let state = self.state.borrow();
+        let style = self.style.borrow();
+        let mut item_bounds = Vec::new();
+        surface.draw(|canvas| {
+            item_bounds = MenuBarRenderer::render(canvas, &state, &style, width).item_bounds;
+        });
+        *self.item_bounds.borrow_mut() = item_bounds;
+    }
+
+    fn register_pointer_handler(&self) {
+        let bar = self.clone();
+        AppContext::register_pointer_callback(move |events| {
+            let Some(surface) = bar.surface() else {
+                return;
+            };
+            let surface_id = surface.wl_surface().id();
+
+            for event in events {
+                if event.surface.id() != surface_id {
+                    continue;
+                }
+                let hit = MenuBarRenderer::hit_test(
+                    &bar.item_bounds.borrow(),
+                    event.position.0 as f32,
+                    event.position.1 as f32,
+                );
+
+                match event.kind {
+                    PointerEventKind::Enter { .. } | PointerEventKind::Motion { .. } => {
+                        bar.handle_hover(hit);
+                    }
+                    PointerEventKind::Leave { .. } => bar.handle_hover(None),
+                    PointerEventKind::Press { button, .. } if button == BTN_LEFT => {
+                        bar.handle_press(hit);
+                    }
+                    _ => {}
+                }
+            }
+        });
+    }
+
+    fn handle_hover(&self, hit: Option<usize>) {
+        let changed = {
+            let mut state = self.state.borrow_mut();
+            let changed = state.hover_index() != hit;
+            state.set_hover(hit);
+            changed
+        };
+
+        // With a dropdown open, sliding over another item switches to it
+        let active = self.active_index();
+        if let (Some(active), Some(index)) = (active, hit) {
+            if index != active && self.has_menu(index) {
+                self.open_menu_at(index, false);
+                return;
+            }
+        }
+
+        if changed {
+            self.render();
+        }
+    }
+
+    fn handle_press(&self, hit: Option<usize>) {
+        match hit {
+            Some(index) if self.active_index() == Some(index) => self.close_menu(),
+            Some(index) if self.has_menu(index) => self.open_menu_at(index, false),
+            _ => self.close_menu(),
+        }
+    }
+
+    // === Dropdowns ===
+
+    /// Index of the item whose dropdown is open
+    pub fn active_index(&self) -> Option<usize> {
+        self.state.borrow().active_index()
+    }
+
+    fn has_menu(&self, index: usize) -> bool {
+        matches!(self.menus.borrow().get(index), Some(Some(_)))
+    }
+
+    fn menu_at(&self, index: usize) -> Option<ContextMenu> {
+        self.menus.borrow().get(index).cloned().flatten()
+    }
+
+    /// Open the dropdown of the item at `index`, highlighting its first
+    /// entry as if opened from the keyboard
+    pub fn open_menu(&self, index: usize) {
+        self.open_menu_at(index, true);
+    }
+
+    fn open_menu_at(&self, index: usize, select_first: bool) {
+        let Some(menu) = self.menu_at(index) else {
+            return;
+        };
+        let Some(surface) = self.surface() else {
+            return;
+        };
+        let Some(rect) = self
+            .item_bounds
+            .borrow()
+            .iter()
+            .find(|bounds| bounds.index == index)
+            .map(|bounds| bounds.rect)
+        else {
+            return;
+        };
+
+        self.hide_open_menu();
+        self.state.borrow_mut().set_active(Some(index));
+        self.render();
+
+        if select_first {
+            menu.state().borrow_mut().select_first_at_depth(Some(0));
+        }
+
+        let Ok(positioner) = XdgPositioner::new(AppContext::xdg_shell_state()) else {
+            return;
+        };
+        let (menu_w, menu_h) = menu.get_size_at_depth(0);
+        positioner.set_size(menu_w as i32, menu_h as i32);
+        positioner.set_anchor_rect(
+            rect.left as i32,
+            rect.top as i32,
+            rect.width().max(1.0) as i32,
+            rect.height().max(1.0) as i32,
+        );
+        // Dropdown top-left corner aligns to the item's bottom-left corner
+        positioner.set_anchor(xdg_positioner::Anchor::BottomLeft);
+        positioner.set_gravity(xdg_positioner::Gravity::BottomRight);
+        positioner.set_constraint_adjustment(
+            xdg_positioner::ConstraintAdjustment::SlideX
+                | xdg_positioner::ConstraintAdjustment::SlideY
+                | xdg_positioner::ConstraintAdjustment::FlipX
+                | xdg_positioner::ConstraintAdjustment::FlipY,
+        );
+
+        menu.show_for_layer(&surface.layer_surface(), &positioner);
+
+        // Grab keyboard focus on the bar for arrow-key navigation
+        surface.set_keyboard_grab(true);
+    }
+
+    fn hide_open_menu(&self) {
+        if let Some(menu) = self.active_index().and_then(|index| self.menu_at(index)) {
+            menu.hide();
+        }
+    }
+
+    /// Close the open dropdown, if any
+    pub fn close_menu(&self) {
+        if let Some(index) = self.active_index() {
+            self.hide_open_menu();
+            self.dismiss(index);
+        }
+    }
+
+    /// Clear the active highlight after the dropdown of `index` closed.
+    /// Ignored when another dropdown has been opened since.
+    fn dismiss(&self, index: usize) {
+        if self.active_index() != Some(index) {
+            return;
+        }
+        self.state.borrow_mut().set_active(None);
+        if let Some(surface) = self.surface() {
+            surface.set_keyboard_grab(false);
+        }
+        self.render();
+    }
+
+    // === Keyboard ===
+
+    /// Handle a key while a dropdown is open. Left/Right on the top level
+    /// of a dropdown move to the neighbouring dropdown; everything else is
+    /// forwarded to the open [`ContextMenu`]. Returns whether the key was
+    /// consumed.
+    pub fn handle_key(&self, key: u32, key_state: wl_keyboard::KeyState) -> bool {
+        let Some(active) = self.active_index() else {
+            return false;
+        };
+        let Some(mut menu) = self.menu_at(active) else {
+            return false;
+        };
+
+        if key_state == wl_keyboard::KeyState::Pressed {
+            let (depth, opens_submenu) = {
+                let state = menu.state().borrow();
+                (state.depth(), state.selected_has_submenu(None))
+            };
+            let forward = match key {
+                keycodes::LEFT if depth == 0 => Some(false),
+                keycodes::RIGHT if depth == 0 && !opens_submenu => Some(true),
+                _ => None,
+            };
+            if let Some(forward) = forward {
+                let has_menu: Vec<bool> = self.menus.borrow().iter().map(Option::is_some).collect();
+                if let Some(next) = next_menu_index(active, &has_menu, forward) {
+                    self.open_menu_at(next, true);
+                }
+                return true;
+            }
+        }
+
+        menu.handle_key(key, key_state);
+        if !menu.is_visible() {
+            self.dismiss(active);
+        }
+        true
+    }
+
+    /// Close the open dropdown when the bar loses keyboard focus
+    pub fn handle_keyboard_leave(&self, surface: &WlSurface) {
+        let ours = self
+            .surface()
+            .is_some_and(|bar| bar.wl_surface() == *surface);
+        if ours {
+            self.close_menu();
+        }
+    }
+}
+
+impl Default for MenuBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_menu_index_skips_items_without_menu() {
+        let has_menu = [false, true, false, true, true];
+
+        assert_eq!(next_menu_index(1, &has_menu, true), Some(3));
+        assert_eq!(next_menu_index(4, &has_menu, true), Some(1));
+        assert_eq!(next_menu_index(1, &has_menu, false), Some(4));
+        assert_eq!(next_menu_index(3, &has_menu, false), Some(1));
+        assert_eq!(next_menu_index(1, &[false, true], true), None);
+    }
+}
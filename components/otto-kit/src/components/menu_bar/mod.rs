@@ -1,7 +1,11 @@
+#![allow(clippy::module_inception)]
+mod menu_bar;
 mod renderer;
 mod state;
 mod style;
+pub mod surface;
 
+pub use menu_bar::MenuBar;
 pub use renderer::MenuBarRenderer;
 pub use state::{MenuBarIcon, MenuBarItem, MenuBarState};
 pub use style::MenuBarStyle;
@@ -179,6 +179,17 @@ impl MenuBarRenderer {
         canvas.draw_str(text, (x, text_y), font, &text_paint);
     }
 
+    /// Index of the item under (x, y), using the bounds of the last render
+    pub fn hit_test(item_bounds: &[ItemBounds], x: f32, y: f32) -> Option<usize> {
+        item_bounds
+            .iter()
+            .find(|bounds| {
+                let rect = bounds.rect;
+                x >= rect.left && x < rect.right && y >= rect.top && y < rect.bottom
+            })
+            .map(|bounds| bounds.index)
+    }
+
     /// Calculate minimum width needed for the component
     pub fn measure_width(state: &MenuBarState, style: &MenuBarStyle) -> f32 {
         let font = typography::get_font_with_fallback("Inter", style.font_style(), style.font_size);
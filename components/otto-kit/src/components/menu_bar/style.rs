@@ -1,6 +1,7 @@
 use skia_safe::Color;
 
 use super::state::MenuBarItem;
+use crate::theme::Theme;
 
 /// Visual styling for MenuBar component
 #[derive(Clone, Debug)]
@@ -67,6 +68,18 @@ impl Default for MenuBarStyle {
 }
 
 impl MenuBarStyle {
+    /// Take the bar, text, highlight and icon colors from `theme`
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.background_color = theme.material_titlebar;
+        self.text_color = theme.text_primary;
+        self.text_active_color = Color::WHITE;
+        self.hover_color = theme.fill_quaternary;
+        self.active_color = theme.accent;
+        self.icon_tint = theme.text_primary;
+        self.icon_active_tint = Color::WHITE;
+        self
+    }
+
     /// Calculate the width needed for a text label
     pub fn text_width(&self, text: &str, font: &skia_safe::Font) -> f32 {
         let (_, bounds) = font.measure_str(text, None);
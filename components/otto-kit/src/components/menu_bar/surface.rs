@@ -0,0 +1,95 @@
+use wayland_client::protocol::wl_surface::WlSurface;
+use wayland_protocols_wlr::layer_shell::v1::client::{
+    zwlr_layer_shell_v1::Layer,
+    zwlr_layer_surface_v1::{Anchor, KeyboardInteractivity, ZwlrLayerSurfaceV1},
+};
+
+use crate::surfaces::{LayerShellSurface, SurfaceError};
+
+/// Layer-shell namespace used by the menu bar surface
+pub const NAMESPACE: &str = "otto-menubar";
+
+/// Top layer-shell strip hosting a [`MenuBar`](super::MenuBar)
+///
+/// The surface spans the full output width, reserves its height as an
+/// exclusive zone and only takes keyboard focus while a dropdown is open.
+#[derive(Clone)]
+pub struct MenuBarSurface {
+    surface: LayerShellSurface,
+}
+
+impl MenuBarSurface {
+    /// Create the bar anchored to the top edge, `height` logical pixels tall
+    pub fn new(height: u32) -> Result<Self, SurfaceError> {
+        let surface = LayerShellSurface::with_anchor(
+            Layer::Top,
+            NAMESPACE,
+            0,
+            height,
+            Some(Anchor::Top | Anchor::Left | Anchor::Right),
+            Some(height as i32),
+        )?;
+        surface.set_keyboard_interactivity(KeyboardInteractivity::None);
+
+        Ok(Self { surface })
+    }
+
+    /// Get the underlying layer shell surface
+    pub fn layer_shell_surface(&self) -> &LayerShellSurface {
+        &self.surface
+    }
+
+    /// Get the wlr-layer-surface, used as parent for dropdown popups
+    pub fn layer_surface(&self) -> ZwlrLayerSurfaceV1 {
+        self.surface.layer_surface()
+    }
+
+    /// Get the underlying Wayland surface
+    pub fn wl_surface(&self) -> WlSurface {
+        self.surface.wl_surface()
+    }
+
+    /// Width assigned by the compositor, in logical pixels
+    pub fn width(&self) -> f32 {
+        self.surface.dimensions().0 as f32
+    }
+
+    /// Set a callback to be called when the compositor configures the bar
+    pub fn on_configure<F>(&self, callback: F)
+    where
+        F: Fn() + 'static,
+    {
+        self.surface.on_configure(callback);
+    }
+
+    /// Set a callback to be called when the output width changes
+    pub fn on_resize<F>(&self, callback: F)
+    where
+        F: Fn(i32, i32) + 'static,
+    {
+        self.surface.on_resize(callback);
+    }
+
+    /// Clear the surface and draw a new frame
+    pub fn draw<F>(&self, draw_fn: F)
+    where
+        F: FnOnce(&skia_safe::Canvas),
+    {
+        self.surface.draw(|canvas| {
+            canvas.clear(skia_safe::Color::TRANSPARENT);
+            draw_fn(canvas);
+        });
+    }
+
+    /// Take exclusive keyboard focus while a dropdown is open, so arrow
+    /// keys reach the bar; release it again when the dropdown closes
+    pub fn set_keyboard_grab(&self, grab: bool) {
+        let interactivity = if grab {
+            KeyboardInteractivity::Exclusive
+        } else {
+            KeyboardInteractivity::None
+        };
+        self.surface.set_keyboard_interactivity(interactivity);
+        self.surface.commit();
+    }
+}
@@ -1,5 +1,7 @@
 use skia_safe::Color;
 
+use crate::theme::Theme;
+
 /// Visual styling for MenuItem
 #[derive(Clone, Debug)]
 pub struct MenuItemStyle {
@@ -67,6 +69,17 @@ impl MenuItemStyle {
         self
     }
 
+    /// Take the text and separator colors from `theme`, and its accent
+    /// selection color behind hovered items
+    pub fn with_theme(mut self, theme: &Theme) -> Self {
+        self.text_color_normal = theme.text_primary;
+        self.text_color_disabled = theme.text_tertiary;
+        self.shortcut_color_normal = theme.text_tertiary;
+        self.bg_color_hovered = theme.material_selection_focused;
+        self.separator_color = theme.fill_secondary;
+        self
+    }
+
     pub fn with_text_colors(mut self, normal: Color, hovered: Color, disabled: Color) -> Self {
         self.text_color_normal = normal;
         self.text_color_hovered = hovered;
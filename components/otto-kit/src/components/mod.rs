@@ -2,11 +2,14 @@ pub mod button;
 pub mod container;
 pub mod context_menu;
 pub mod icon;
+pub mod image;
 pub mod label;
 pub mod layer;
 pub mod layers;
 pub mod menu_bar;
 pub mod menu_item;
+pub mod slider;
+pub mod text_input;
 pub mod titlebar;
 pub mod toolbar;
 pub mod window;
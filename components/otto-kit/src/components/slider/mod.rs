@@ -0,0 +1,4 @@
+#![allow(clippy::module_inception)]
+mod slider;
+
+pub use slider::{Slider, SliderBuilder};
@@ -0,0 +1,516 @@
+use skia_safe::{Canvas, Color, Paint, Point, RRect, Rect};
+use smithay_client_toolkit::seat::pointer::{PointerEvent, PointerEventKind, BTN_LEFT};
+use wayland_client::protocol::wl_keyboard;
+
+use crate::common::Renderable;
+use crate::components::label::Label;
+use crate::input::keycodes;
+use crate::theme::Theme;
+use crate::typography::TextStyle;
+
+type ValueCallback = Box<dyn FnMut(f32) + Send>;
+type ValueFormat = Box<dyn Fn(f32) -> String + Send>;
+
+/// Keyboard steps moved by Page Up / Page Down
+const PAGE_STEPS: f32 = 10.0;
+/// Keyboard step when the slider has no step, as a share of the range
+const FREE_KEY_STEP: f32 = 0.01;
+
+/// A horizontal slider picking a value between `min` and `max`
+///
+/// The app owns the slider and forwards input to it:
+/// - `handle_pointer_events()` from `Window::on_pointer_event`
+///   (click to set, drag the thumb)
+/// - `handle_key()` from `App::on_keyboard_event` (arrows while focused)
+///
+/// Each handler returns `true` when the slider needs a redraw.
+/// `on_change` is called whenever the user changes the value.
+pub struct Slider {
+    pub x: f32,
+    pub y: f32,
+    /// Width of the track, excluding the value label
+    pub width: f32,
+
+    // Value
+    min: f32,
+    max: f32,
+    /// Values snap to multiples of `step` from `min`; 0 means continuous
+    step: f32,
+    value: f32,
+
+    // Interaction
+    focused: bool,
+    disabled: bool,
+    dragging: bool,
+    on_change: Option<ValueCallback>,
+
+    // Styling
+    theme: Theme,
+    track_height: f32,
+    thumb_radius: f32,
+    show_value: bool,
+    value_width: f32,
+    value_format: Option<ValueFormat>,
+    text_style: TextStyle,
+}
+
+impl Slider {
+    /// Create a slider over `min..=max`, starting at `min`
+    pub fn new(min: f32, max: f32) -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 200.0,
+            min,
+            max: max.max(min),
+            step: 0.0,
+            value: min,
+            focused: false,
+            disabled: false,
+            dragging: false,
+            on_change: None,
+            theme: Theme::for_scheme(crate::color_scheme::current_color_scheme()),
+            track_height: 4.0,
+            thumb_radius: 10.0,
+            show_value: false,
+            value_width: 48.0,
+            value_format: None,
+            text_style: crate::typography::styles::CALLOUT,
+        }
+    }
+
+    pub fn at(mut self, x: f32, y: f32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step.max(0.0);
+        self.value = snap_value(self.value, self.min, self.max, self.step);
+        self
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn with_thumb_radius(mut self, radius: f32) -> Self {
+        self.thumb_radius = radius;
+        self
+    }
+
+    pub fn with_track_height(mut self, height: f32) -> Self {
+        self.track_height = height;
+        self
+    }
+
+    /// Show the value right of the track
+    pub fn with_value_label(mut self) -> Self {
+        self.show_value = true;
+        self
+    }
+
+    /// Format the value label, e.g. `|v| format!("{v:.0}%")`
+    pub fn with_value_format<F>(mut self, format: F) -> Self
+    where
+        F: Fn(f32) -> String + Send + 'static,
+    {
+        self.show_value = true;
+        self.value_format = Some(Box::new(format));
+        self
+    }
+
+    pub fn with_text_style(mut self, style: TextStyle) -> Self {
+        self.text_style = style;
+        self
+    }
+
+    pub fn disabled(mut self) -> Self {
+        self.disabled = true;
+        self
+    }
+
+    pub fn focused(mut self) -> Self {
+        self.focused = true;
+        self
+    }
+
+    /// Called with the new value whenever the user changes it
+    pub fn on_change<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(f32) + Send + 'static,
+    {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    pub fn build(self) -> Self {
+        self
+    }
+
+    // === State ===
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Set the value, snapped to the step, without calling `on_change`
+    pub fn set_value(&mut self, value: f32) {
+        self.value = snap_value(value, self.min, self.max, self.step);
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if disabled {
+            self.dragging = false;
+        }
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
+    /// Height of the slider: the thumb's diameter
+    pub fn height(&self) -> f32 {
+        self.thumb_radius * 2.0
+    }
+
+    /// Width including the value label
+    pub fn total_width(&self) -> f32 {
+        if self.show_value {
+            self.width + self.value_width
+        } else {
+            self.width
+        }
+    }
+
+    /// Whether (x, y) is on the track or the thumb
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && x <= self.x + self.width && y >= self.y && y <= self.y + self.height()
+    }
+
+    // === Input ===
+
+    /// Left button pressed at (x, y): focus and jump the thumb under the
+    /// pointer, or unfocus when the press is outside
+    pub fn pointer_press(&mut self, x: f32, y: f32) -> bool {
+        if !self.contains(x, y) {
+            let was_focused = self.focused;
+            self.focused = false;
+            return was_focused;
+        }
+        if self.disabled {
+            return false;
+        }
+        self.focused = true;
+        self.dragging = true;
+        self.change_value(self.value_at(x));
+        true
+    }
+
+    /// Pointer moved to x: follow it while dragging
+    pub fn pointer_motion(&mut self, x: f32) -> bool {
+        if !self.dragging {
+            return false;
+        }
+        self.change_value(self.value_at(x))
+    }
+
+    /// Left button released: end the drag
+    pub fn pointer_release(&mut self) -> bool {
+        std::mem::take(&mut self.dragging)
+    }
+
+    /// Feed a frame of pointer events from `Window::on_pointer_event`
+    pub fn handle_pointer_events(&mut self, events: &[PointerEvent]) -> bool {
+        let mut redraw = false;
+        for event in events {
+            let (x, y) = (event.position.0 as f32, event.position.1 as f32);
+            redraw |= match event.kind {
+                PointerEventKind::Motion { .. } => self.pointer_motion(x),
+                PointerEventKind::Press { button, .. } if button == BTN_LEFT => {
+                    self.pointer_press(x, y)
+                }
+                PointerEventKind::Release { button, .. } if button == BTN_LEFT => {
+                    self.pointer_release()
+                }
+                PointerEventKind::Leave { .. } => self.pointer_release(),
+                _ => false,
+            };
+        }
+        redraw
+    }
+
+    /// Arrow keys while focused: Left/Down decrease and Right/Up increase
+    /// by one step, Page Up/Down by ten, Home/End jump to the ends
+    pub fn handle_key(&mut self, key: u32, key_state: wl_keyboard::KeyState) -> bool {
+        if !self.focused || self.disabled || key_state != wl_keyboard::KeyState::Pressed {
+            return false;
+        }
+        let step = self.key_step();
+        let value = match key {
+            keycodes::LEFT | keycodes::DOWN => self.value - step,
+            keycodes::RIGHT | keycodes::UP => self.value + step,
+            keycodes::PAGE_DOWN => self.value - step * PAGE_STEPS,
+            keycodes::PAGE_UP => self.value + step * PAGE_STEPS,
+            keycodes::HOME => self.min,
+            keycodes::END => self.max,
+            _ => return false,
+        };
+        self.change_value(value)
+    }
+
+    // === Internal ===
+
+    fn key_step(&self) -> f32 {
+        if self.step > 0.0 {
+            self.step
+        } else {
+            (self.max - self.min) * FREE_KEY_STEP
+        }
+    }
+
+    /// Snap and set `value`, calling `on_change` when it changed
+    fn change_value(&mut self, value: f32) -> bool {
+        let value = snap_value(value, self.min, self.max, self.step);
+        if value == self.value {
+            return false;
+        }
+        self.value = value;
+        if let Some(callback) = self.on_change.as_mut() {
+            callback(value);
+        }
+        true
+    }
+
+    /// The thumb's center travels between the track ends inset by its radius
+    fn track_span(&self) -> (f32, f32) {
+        let start = self.x + self.thumb_radius;
+        let end = self.x + self.width - self.thumb_radius;
+        (start, end.max(start))
+    }
+
+    fn value_at(&self, x: f32) -> f32 {
+        let (start, end) = self.track_span();
+        let fraction = if end > start {
+            ((x - start) / (end - start)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        self.min + fraction * (self.max - self.min)
+    }
+
+    fn fraction(&self) -> f32 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
+    fn format_value(&self) -> String {
+        match self.value_format.as_ref() {
+            Some(format) => format(self.value),
+            None if self.step >= 1.0 || (self.step == 0.0 && self.max - self.min >= 10.0) => {
+                format!("{:.0}", self.value)
+            }
+            None => format!("{:.2}", self.value),
+        }
+    }
+}
+
+impl Renderable for Slider {
+    fn render(&self, canvas: &Canvas) {
+        let theme = &self.theme;
+        let center_y = self.y + self.thumb_radius;
+        let (start, end) = self.track_span();
+        let thumb_x = start + (end - start) * self.fraction();
+
+        // Track, filled up to the thumb
+        let track = Rect::from_xywh(
+            self.x,
+            center_y - self.track_height / 2.0,
+            self.width,
+            self.track_height,
+        );
+        let radius = self.track_height / 2.0;
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(theme.fill_secondary);
+        canvas.draw_rrect(RRect::new_rect_xy(track, radius, radius), &paint);
+
+        let filled = Rect::from_ltrb(track.left, track.top, thumb_x, track.bottom);
+        paint.set_color(if self.disabled {
+            theme.accent_gray
+        } else {
+            theme.accent
+        });
+        canvas.draw_rrect(RRect::new_rect_xy(filled, radius, radius), &paint);
+
+        // Focus ring
+        if self.focused && !self.disabled {
+            let mut ring = Paint::default();
+            ring.set_anti_alias(true);
+            ring.set_style(skia_safe::PaintStyle::Stroke);
+            ring.set_stroke_width(2.0);
+            ring.set_color(theme.accent);
+            canvas.draw_circle(
+                Point::new(thumb_x, center_y),
+                self.thumb_radius + 2.0,
+                &ring,
+            );
+        }
+
+        // Thumb, with a soft shadow and a hairline border
+        let thumb_radius = self.thumb_radius - 0.5;
+        paint.set_color(theme.shadow);
+        canvas.draw_circle(Point::new(thumb_x, center_y + 1.0), thumb_radius, &paint);
+        paint.set_color(if self.disabled {
+            theme.fill_primary
+        } else {
+            Color::WHITE
+        });
+        canvas.draw_circle(Point::new(thumb_x, center_y), thumb_radius, &paint);
+        let mut border = Paint::default();
+        border.set_anti_alias(true);
+        border.set_style(skia_safe::PaintStyle::Stroke);
+        border.set_stroke_width(1.0);
+        border.set_color(theme.fill_primary);
+        canvas.draw_circle(Point::new(thumb_x, center_y), thumb_radius, &border);
+
+        // Value label
+        if self.show_value {
+            Label::new(self.format_value())
+                .at(
+                    self.x + self.width + 8.0,
+                    center_y - self.text_style.size / 2.0,
+                )
+                .with_style(self.text_style)
+                .with_color(if self.disabled {
+                    theme.text_tertiary
+                } else {
+                    theme.text_secondary
+                })
+                .render(canvas);
+        }
+    }
+
+    fn intrinsic_size(&self) -> Option<(f32, f32)> {
+        Some((self.total_width(), self.height()))
+    }
+}
+
+// Builder alias, matching LabelBuilder
+pub type SliderBuilder = Slider;
+
+/// Clamp `value` to `min..=max` and snap it to the nearest multiple of
+/// `step` from `min`. A `step` of 0 leaves the value continuous.
+fn snap_value(value: f32, min: f32, max: f32, step: f32) -> f32 {
+    let value = value.clamp(min, max);
+    if step <= 0.0 {
+        return value;
+    }
+    let snapped = min + ((value - min) / step).round() * step;
+    // The last step may overshoot when the range is not a multiple of it
+    if snapped > max {
+        snapped - step
+    } else {
+        snapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snap_value() {
+        assert_eq!(snap_value(0.34, 0.0, 1.0, 0.0), 0.34);
+        assert_eq!(snap_value(-5.0, 0.0, 100.0, 5.0), 0.0);
+        assert_eq!(snap_value(12.4, 0.0, 100.0, 5.0), 10.0);
+        assert_eq!(snap_value(12.6, 0.0, 100.0, 5.0), 15.0);
+        assert_eq!(snap_value(150.0, 0.0, 100.0, 5.0), 100.0);
+        // Steps count from min
+        assert_eq!(snap_value(4.0, 1.0, 10.0, 2.0), 5.0);
+        // 10 is not reachable in steps of 4 from 0
+        assert_eq!(snap_value(10.0, 0.0, 10.0, 4.0), 8.0);
+    }
+
+    #[test]
+    fn test_value_at_position() {
+        let slider = Slider::new(0.0, 100.0)
+            .with_width(220.0)
+            .with_thumb_radius(10.0);
+        // The thumb's center travels from x = 10 to x = 210
+        assert_eq!(slider.value_at(10.0), 0.0);
+        assert_eq!(slider.value_at(110.0), 50.0);
+        assert_eq!(slider.value_at(210.0), 100.0);
+        assert_eq!(slider.value_at(-40.0), 0.0);
+        assert_eq!(slider.value_at(500.0), 100.0);
+    }
+
+    #[test]
+    fn test_keys_step_and_clamp() {
+        let mut slider = Slider::new(0.0, 100.0)
+            .with_step(5.0)
+            .with_value(50.0)
+            .focused();
+        let pressed = wl_keyboard::KeyState::Pressed;
+        assert!(slider.handle_key(keycodes::RIGHT, pressed));
+        assert_eq!(slider.value(), 55.0);
+        assert!(slider.handle_key(keycodes::PAGE_DOWN, pressed));
+        assert_eq!(slider.value(), 5.0);
+        assert!(slider.handle_key(keycodes::PAGE_DOWN, pressed));
+        assert_eq!(slider.value(), 0.0);
+        assert!(!slider.handle_key(keycodes::LEFT, pressed));
+        assert!(slider.handle_key(keycodes::END, pressed));
+        assert_eq!(slider.value(), 100.0);
+
+        slider.set_disabled(true);
+        assert!(!slider.handle_key(keycodes::HOME, pressed));
+    }
+
+    #[test]
+    fn test_press_and_drag_report_changes() {
+        let changes = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = changes.clone();
+        let mut slider = Slider::new(0.0, 10.0)
+            .with_step(1.0)
+            .with_width(120.0)
+            .with_thumb_radius(10.0)
+            .on_change(move |value| recorded.lock().unwrap().push(value));
+
+        assert!(slider.pointer_press(60.0, 10.0));
+        assert!(slider.is_focused());
+        // Motion within the same step does not change the value
+        assert!(!slider.pointer_motion(61.0));
+        assert!(slider.pointer_motion(110.0));
+        assert!(slider.pointer_release());
+        assert!(!slider.pointer_motion(10.0));
+        assert_eq!(*changes.lock().unwrap(), vec![5.0, 10.0]);
+    }
+}
@@ -0,0 +1,6 @@
+#![allow(clippy::module_inception)]
+mod state;
+mod text_input;
+
+pub use state::TextInputState;
+pub use text_input::{TextInput, CARET_BLINK_INTERVAL};
@@ -0,0 +1,255 @@
+use std::ops::Range;
+
+/// Editing state for TextInput: text, caret and selection
+///
+/// Positions are byte offsets into the text, always on a char boundary.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextInputState {
+    text: String,
+    /// Caret position
+    cursor: usize,
+    /// Other end of the selection; equal to `cursor` when nothing is selected
+    anchor: usize,
+}
+
+impl TextInputState {
+    /// Create a state holding `text`, with the caret at its end
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let end = text.len();
+        Self {
+            text,
+            cursor: end,
+            anchor: end,
+        }
+    }
+
+    // === Getters ===
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// The selected byte range, `None` when nothing is selected
+    pub fn selection(&self) -> Option<Range<usize>> {
+        if self.cursor == self.anchor {
+            return None;
+        }
+        Some(self.cursor.min(self.anchor)..self.cursor.max(self.anchor))
+    }
+
+    pub fn selected_text(&self) -> Option<&str> {
+        self.selection().map(|range| &self.text[range])
+    }
+
+    // === Editing ===
+
+    /// Replace the text, with the caret at its end
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        *self = Self::new(text);
+    }
+
+    /// Insert `text` at the caret, replacing the selection.
+    /// Returns `true` when the text changed.
+    pub fn insert(&mut self, text: &str) -> bool {
+        let removed = self.delete_selection();
+        if text.is_empty() {
+            return removed;
+        }
+        self.text.insert_str(self.cursor, text);
+        self.cursor += text.len();
+        self.anchor = self.cursor;
+        true
+    }
+
+    /// Delete the selection, or the char before the caret.
+    /// Returns `true` when the text changed.
+    pub fn backspace(&mut self) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        if self.cursor == 0 {
+            return false;
+        }
+        let start = self.prev_boundary(self.cursor);
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+        self.anchor = start;
+        true
+    }
+
+    /// Delete the selection, or the char after the caret.
+    /// Returns `true` when the text changed.
+    pub fn delete(&mut self) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        if self.cursor == self.text.len() {
+            return false;
+        }
+        let end = self.next_boundary(self.cursor);
+        self.text.replace_range(self.cursor..end, "");
+        true
+    }
+
+    // === Caret movement ===
+
+    /// Move the caret one char left. Without `extend`, a selection collapses
+    /// to its start instead.
+    pub fn move_left(&mut self, extend: bool) {
+        let target = match self.selection() {
+            Some(range) if !extend => range.start,
+            _ => self.prev_boundary(self.cursor),
+        };
+        self.set_cursor(target, extend);
+    }
+
+    /// Move the caret one char right. Without `extend`, a selection collapses
+    /// to its end instead.
+    pub fn move_right(&mut self, extend: bool) {
+        let target = match self.selection() {
+            Some(range) if !extend => range.end,
+            _ => self.next_boundary(self.cursor),
+        };
+        self.set_cursor(target, extend);
+    }
+
+    pub fn move_home(&mut self, extend: bool) {
+        self.set_cursor(0, extend);
+    }
+
+    pub fn move_end(&mut self, extend: bool) {
+        self.set_cursor(self.text.len(), extend);
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = 0;
+        self.cursor = self.text.len();
+    }
+
+    /// Put the caret at `position`, rounded down to a char boundary.
+    /// With `extend`, the selection grows from its anchor to the caret.
+    pub fn set_cursor(&mut self, position: usize, extend: bool) {
+        let mut position = position.min(self.text.len());
+        while !self.text.is_char_boundary(position) {
+            position -= 1;
+        }
+        self.cursor = position;
+        if !extend {
+            self.anchor = position;
+        }
+    }
+
+    /// Char boundaries of the text, from 0 to its length
+    pub fn boundaries(&self) -> impl Iterator<Item = usize> + '_ {
+        self.text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(self.text.len()))
+    }
+
+    // === Internal ===
+
+    fn delete_selection(&mut self) -> bool {
+        let Some(range) = self.selection() else {
+            return false;
+        };
+        self.text.replace_range(range.clone(), "");
+        self.cursor = range.start;
+        self.anchor = range.start;
+        true
+    }
+
+    fn prev_boundary(&self, position: usize) -> usize {
+        self.text[..position]
+            .char_indices()
+            .next_back()
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+
+    fn next_boundary(&self, position: usize) -> usize {
+        self.text[position..]
+            .chars()
+            .next()
+            .map(|c| position + c.len_utf8())
+            .unwrap_or(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_at_cursor() {
+        let mut state = TextInputState::new("hllo");
+        state.set_cursor(1, false);
+        assert!(state.insert("e"));
+        assert_eq!(state.text(), "hello");
+        assert_eq!(state.cursor(), 2);
+    }
+
+    #[test]
+    fn test_insert_replaces_selection() {
+        let mut state = TextInputState::new("hello world");
+        state.set_cursor(6, false);
+        state.move_end(true);
+        assert_eq!(state.selected_text(), Some("world"));
+        assert!(state.insert("otto"));
+        assert_eq!(state.text(), "hello otto");
+        assert_eq!(state.selection(), None);
+    }
+
+    #[test]
+    fn test_unicode_editing() {
+        let mut state = TextInputState::new("añ😀b");
+        state.move_left(false);
+        state.move_left(false);
+        assert_eq!(state.cursor(), "añ".len());
+        assert!(state.delete());
+        assert_eq!(state.text(), "añb");
+        assert!(state.backspace());
+        assert_eq!(state.text(), "ab");
+        assert_eq!(state.cursor(), 1);
+
+        // Positions inside a char round down to its start
+        let mut state = TextInputState::new("😀");
+        state.set_cursor(2, false);
+        assert_eq!(state.cursor(), 0);
+    }
+
+    #[test]
+    fn test_backspace_and_delete_at_edges() {
+        let mut state = TextInputState::new("ab");
+        assert!(!state.delete());
+        state.move_home(false);
+        assert!(!state.backspace());
+        assert_eq!(state.text(), "ab");
+    }
+
+    #[test]
+    fn test_selection_collapses_on_move() {
+        let mut state = TextInputState::new("hello");
+        state.select_all();
+        state.move_left(false);
+        assert_eq!(state.cursor(), 0);
+        assert_eq!(state.selection(), None);
+
+        state.move_right(true);
+        state.move_right(true);
+        assert_eq!(state.selected_text(), Some("he"));
+        state.move_right(false);
+        assert_eq!(state.cursor(), 2);
+    }
+
+    #[test]
+    fn test_boundaries() {
+        let state = TextInputState::new("aé");
+        assert_eq!(state.boundaries().collect::<Vec<_>>(), vec![0, 1, 3]);
+    }
+}
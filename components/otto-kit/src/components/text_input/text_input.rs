@@ -0,0 +1,416 @@
+use std::time::{Duration, Instant};
+
+use skia_safe::{Canvas, Paint, Point, RRect, Rect};
+use smithay_client_toolkit::seat::keyboard::{Keysym, Modifiers};
+use wayland_client::protocol::wl_keyboard;
+
+use super::TextInputState;
+use crate::common::Renderable;
+use crate::theme::Theme;
+use crate::typography::{shape_text, ShapedText, TextStyle};
+
+/// Time the caret stays shown, then hidden, while blinking.
+/// Redraw at least this often while a TextInput is focused.
+pub const CARET_BLINK_INTERVAL: Duration = Duration::from_millis(530);
+
+type TextCallback = Box<dyn FnMut(&str) + Send>;
+
+/// A single-line editable text field
+///
+/// The app owns the field and forwards input to it:
+/// - `handle_text()` from `App::on_text_input`
+/// - `handle_key()` from `App::on_keyboard_event`, with `AppContext::keysym()`
+/// - `press_at()`/`drag_to()` from pointer presses and motion
+///
+/// Each handler returns `true` when the field needs a redraw.
+pub struct TextInput {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+
+    state: TextInputState,
+    placeholder: Option<String>,
+    focused: bool,
+    /// The caret shows for a full interval after every edit or move
+    caret_reset: Instant,
+
+    // Styling
+    text_style: TextStyle,
+    theme: Theme,
+    corner_radius: f32,
+    padding_horizontal: f32,
+    padding_vertical: f32,
+
+    on_change: Option<TextCallback>,
+    on_submit: Option<TextCallback>,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 200.0,
+            height: 0.0, // Auto-calculated from the text style
+            state: TextInputState::default(),
+            placeholder: None,
+            focused: false,
+            caret_reset: Instant::now(),
+            text_style: crate::typography::styles::BODY,
+            theme: Theme::for_scheme(crate::color_scheme::current_color_scheme()),
+            corner_radius: 6.0,
+            padding_horizontal: 8.0,
+            padding_vertical: 6.0,
+            on_change: None,
+            on_submit: None,
+        }
+    }
+
+    pub fn at(mut self, x: f32, y: f32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    pub fn with_size(mut self, width: f32, height: f32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.state.set_text(text);
+        self
+    }
+
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = Some(placeholder.into());
+        self
+    }
+
+    pub fn with_text_style(mut self, style: TextStyle) -> Self {
+        self.text_style = style;
+        self
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn with_corner_radius(mut self, radius: f32) -> Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    pub fn with_padding(mut self, horizontal: f32, vertical: f32) -> Self {
+        self.padding_horizontal = horizontal;
+        self.padding_vertical = vertical;
+        self
+    }
+
+    /// Called with the new text after every edit
+    pub fn on_change<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&str) + Send + 'static,
+    {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    /// Called with the text when Enter is pressed
+    pub fn on_submit<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&str) + Send + 'static,
+    {
+        self.on_submit = Some(Box::new(callback));
+        self
+    }
+
+    pub fn build(self) -> Self {
+        self
+    }
+
+    // === State ===
+
+    pub fn text(&self) -> &str {
+        self.state.text()
+    }
+
+    /// Replace the text without calling `on_change`
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.state.set_text(text);
+        self.reset_caret();
+    }
+
+    pub fn state(&self) -> &TextInputState {
+        &self.state
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.reset_caret();
+    }
+
+    /// Whether the blinking caret is shown right now
+    pub fn caret_visible(&self) -> bool {
+        let interval = CARET_BLINK_INTERVAL.as_millis();
+        self.focused && (self.caret_reset.elapsed().as_millis() / interval) % 2 == 0
+    }
+
+    /// Get the field's height, calculated from the text style unless set
+    pub fn field_height(&self) -> f32 {
+        if self.height > 0.0 {
+            self.height
+        } else {
+            self.text_style.size + self.padding_vertical * 2.0
+        }
+    }
+
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && x <= self.x + self.width && y >= self.y && y <= self.y + self.field_height()
+    }
+
+    // === Input ===
+
+    /// Insert typed text at the caret. Control characters are ignored.
+    pub fn handle_text(&mut self, text: &str) -> bool {
+        if !self.focused || text.chars().any(char::is_control) {
+            return false;
+        }
+        let changed = self.state.insert(text);
+        self.after_edit(changed);
+        changed
+    }
+
+    /// Handle an editing or navigation key. Shift extends the selection.
+    /// Keys are matched by keysym, so Ctrl+A selects all on any layout.
+    pub fn handle_key(
+        &mut self,
+        keysym: Keysym,
+        key_state: wl_keyboard::KeyState,
+        modifiers: Modifiers,
+    ) -> bool {
+        if !self.focused || key_state != wl_keyboard::KeyState::Pressed {
+            return false;
+        }
+        let extend = modifiers.shift;
+        match keysym {
+            Keysym::BackSpace => {
+                let changed = self.state.backspace();
+                self.after_edit(changed);
+            }
+            Keysym::Delete => {
+                let changed = self.state.delete();
+                self.after_edit(changed);
+            }
+            Keysym::Left => self.state.move_left(extend),
+            Keysym::Right => self.state.move_right(extend),
+            Keysym::Home | Keysym::Up => self.state.move_home(extend),
+            Keysym::End | Keysym::Down => self.state.move_end(extend),
+            Keysym::a | Keysym::A if modifiers.ctrl => self.state.select_all(),
+            Keysym::Return | Keysym::KP_Enter => {
+                if let Some(callback) = self.on_submit.as_mut() {
+                    callback(self.state.text());
+                }
+                return false;
+            }
+            _ => return false,
+        }
+        self.reset_caret();
+        true
+    }
+
+    /// A button press at (x, y): focus the field and put the caret under the
+    /// pointer, or unfocus it when the press is outside. With `extend`
+    /// (e.g. shift held), select from the caret to the pointer.
+    pub fn press_at(&mut self, x: f32, y: f32, extend: bool) -> bool {
+        if !self.contains(x, y) {
+            let was_focused = self.focused;
+            self.set_focused(false);
+            return was_focused;
+        }
+        self.focused = true;
+        let position = self.position_at(x);
+        self.state.set_cursor(position, extend);
+        self.reset_caret();
+        true
+    }
+
+    /// Pointer motion with the button held: select from the press to `x`
+    pub fn drag_to(&mut self, x: f32) -> bool {
+        if !self.focused {
+            return false;
+        }
+        let position = self.position_at(x);
+        if position == self.state.cursor() {
+            return false;
+        }
+        self.state.set_cursor(position, true);
+        self.reset_caret();
+        true
+    }
+
+    // === Internal ===
+
+    fn after_edit(&mut self, changed: bool) {
+        self.reset_caret();
+        if changed {
+            if let Some(callback) = self.on_change.as_mut() {
+                callback(self.state.text());
+            }
+        }
+    }
+
+    fn reset_caret(&mut self) {
+        self.caret_reset = Instant::now();
+    }
+
+    /// Horizontal scroll keeping the caret inside the field
+    fn scroll_offset(&self, shaped: &ShapedText) -> f32 {
+        let inner_width = self.width - self.padding_horizontal * 2.0;
+        let caret_x = shaped.caret_x(self.state.cursor());
+        (caret_x - inner_width).max(0.0)
+    }
+
+    /// The char boundary closest to `x`
+    fn position_at(&self, x: f32) -> usize {
+        let shaped = shape_text(self.text(), &self.text_style.font());
+        let local_x = x - self.x - self.padding_horizontal + self.scroll_offset(&shaped);
+        self.state
+            .boundaries()
+            .min_by(|a, b| {
+                let da = (shaped.caret_x(*a) - local_x).abs();
+                let db = (shaped.caret_x(*b) - local_x).abs();
+                da.total_cmp(&db)
+            })
+            .unwrap_or(0)
+    }
+}
+
+impl Default for TextInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Renderable for TextInput {
+    fn render(&self, canvas: &Canvas) {
+        let font = self.text_style.font();
+        let height = self.field_height();
+        let rect = Rect::from_xywh(self.x, self.y, self.width, height);
+        let rrect = RRect::new_rect_xy(rect, self.corner_radius, self.corner_radius);
+
+        // Background
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(self.theme.fill_quaternary);
+        canvas.draw_rrect(rrect, &paint);
+
+        // Border, accented while focused
+        let mut border = Paint::default();
+        border.set_anti_alias(true);
+        border.set_style(skia_safe::paint::Style::Stroke);
+        if self.focused {
+            border.set_color(self.theme.accent);
+            border.set_stroke_width(2.0);
+        } else {
+            border.set_color(self.theme.fill_secondary);
+            border.set_stroke_width(1.0);
+        }
+        canvas.draw_rrect(rrect, &border);
+
+        canvas.save();
+        canvas.clip_rect(rect, None, true);
+        let shaped = shape_text(self.text(), &font);
+        let text_x = self.x + self.padding_horizontal - self.scroll_offset(&shaped);
+        let baseline = self.y + (height - font.size()) / 2.0 + font.size() * 0.8;
+        let x_at = |position: usize| text_x + shaped.caret_x(position);
+
+        // Selection
+        if let Some(range) = self.state.selection() {
+            let mut selection = Paint::default();
+            selection.set_color(self.theme.material_selection_focused);
+            let top = self.y + self.padding_vertical;
+            // Right-to-left selections end left of where they start
+            let (start, end) = (x_at(range.start), x_at(range.end));
+            canvas.draw_rect(
+                Rect::from_ltrb(
+                    start.min(end),
+                    top,
+                    start.max(end),
+                    top + height - self.padding_vertical * 2.0,
+                ),
+                &selection,
+            );
+        }
+
+        // Text, or the placeholder when empty
+        let mut text_paint = Paint::default();
+        text_paint.set_anti_alias(true);
+        match self.placeholder.as_deref() {
+            Some(placeholder) if self.text().is_empty() => {
+                text_paint.set_color(self.theme.text_tertiary);
+                shape_text(placeholder, &font).draw(
+                    canvas,
+                    Point::new(text_x, baseline),
+                    &text_paint,
+                );
+            }
+            _ => {
+                text_paint.set_color(self.theme.text_primary);
+                shaped.draw(canvas, Point::new(text_x, baseline), &text_paint);
+            }
+        }
+
+        // Caret
+        if self.caret_visible() {
+            let mut caret = Paint::default();
+            caret.set_anti_alias(true);
+            caret.set_color(self.theme.accent);
+            let caret_x = x_at(self.state.cursor());
+            let top = self.y + self.padding_vertical;
+            canvas.draw_rect(
+                Rect::from_xywh(caret_x, top, 1.5, height - self.padding_vertical * 2.0),
+                &caret,
+            );
+        }
+        canvas.restore();
+    }
+
+    fn intrinsic_size(&self) -> Option<(f32, f32)> {
+        Some((self.width, self.field_height()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctrl() -> Modifiers {
+        Modifiers {
+            ctrl: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_ctrl_a_selects_all_by_keysym() {
+        let mut input = TextInput::new().with_text("hello");
+        input.set_focused(true);
+        let pressed = wl_keyboard::KeyState::Pressed;
+        // The key that types `q` doesn't select all, whatever its position
+        input.handle_key(Keysym::q, pressed, ctrl());
+        assert!(input.handle_text("x"));
+        assert_eq!(input.text(), "hellox");
+
+        assert!(input.handle_key(Keysym::a, pressed, ctrl()));
+        assert!(input.handle_text("y"));
+        assert_eq!(input.text(), "y");
+    }
+}
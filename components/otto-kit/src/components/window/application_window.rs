@@ -103,13 +103,11 @@ impl ApplicationWindow {
         // Register configure handler
         let window_clone = window.clone();
         AppContext::register_configure_handler(move || {
-            if let Some((surface_id, configure, serial)) = AppContext::current_surface_configure() {
-                if let Some(our_surface) = window_clone.wl_surface() {
-                    use wayland_client::Proxy;
-                    if our_surface.id() == surface_id {
-                        window_clone.on_configure(configure, serial);
-                    }
-                }
+            let Some(surface) = window_clone.wl_surface() else {
+                return;
+            };
+            if let Some((configure, serial)) = AppContext::current_configure_for(&surface) {
+                window_clone.on_configure(configure, serial);
             }
         });
 
@@ -202,16 +200,17 @@ impl ApplicationWindow {
 
         // Apply styling
         if let Some(layer) = titlebar.layer() {
-            println!("Setting titlebar corner radius to 16.0");
+            tracing::debug!("application_window: styling titlebar");
             // layer.set_background_color(0.8, 0.8, 0.8, 1.0);
             // layer.set_border(2.0, 0.0, 0.0, 0.0, 0.6);
             // layer.set_corner_radius(16.0);
             layer.set_masks_to_bounds(otto_surface_style_v1::ClipMode::Enabled);
         } else {
-            println!("WARNING: No layer available for titlebar!");
+            tracing::warn!("application_window: no surface style layer for titlebar");
         }
 
         // Store titlebar
+        titlebar.set_sync();
         *self.titlebar.write().unwrap() = Some(titlebar);
 
         Ok(())
@@ -251,16 +250,17 @@ impl ApplicationWindow {
 
         // Apply styling
         if let Some(layer) = sidebar.layer() {
-            println!("Setting sidebar styling (full height)");
+            tracing::debug!("application_window: styling sidebar");
             layer.set_background_color(0.9, 0.9, 0.9, 0.9);
             // layer.set_corner_radius(36.0);
             // layer.set_masks_to_bounds(otto_surface_style_v1::ClipMode::Enabled);
             layer.set_blend_mode(BlendMode::BackgroundBlur);
         } else {
-            println!("WARNING: No layer available for sidebar!");
+            tracing::warn!("application_window: no surface style layer for sidebar");
         }
 
         // Store sidebar
+        sidebar.set_sync();
         *self.sidebar.write().unwrap() = Some(sidebar);
 
         Ok(())
@@ -300,16 +300,17 @@ impl ApplicationWindow {
 
         // Apply styling
         if let Some(layer) = content.layer() {
-            println!("Setting content area styling");
+            tracing::debug!("application_window: styling content area");
             // layer.set_border(10.0, 1.0, 0.0, 0.0, 1.0);
             layer.set_background_color(0.8, 0.8, 0.8, 1.0);
             layer.set_shadow(0.3, 5.0, 0.0, 0.0, 0.0, 0.0, 0.0);
             // layer.set_masks_to_bounds(otto_surface_style_v1::ClipMode::Enabled);
         } else {
-            println!("WARNING: No layer available for content!");
+            tracing::warn!("application_window: no surface style layer for content");
         }
 
         // Store content
+        content.set_sync();
         *self.content.write().unwrap() = Some(content);
 
         Ok(())
@@ -402,19 +403,19 @@ impl ApplicationWindow {
         // Order: sidebar, content, then titlebar (so titlebar overlaps)
         if self.sidebar.read().unwrap().is_none() {
             if let Err(e) = self.create_sidebar() {
-                eprintln!("Failed to create sidebar: {:?}", e);
+                tracing::error!("application_window: failed to create sidebar: {e:?}");
             }
         }
 
         if self.content.read().unwrap().is_none() {
             if let Err(e) = self.create_content() {
-                eprintln!("Failed to create content: {:?}", e);
+                tracing::error!("application_window: failed to create content: {e:?}");
             }
         }
 
         if self.titlebar.read().unwrap().is_none() {
             if let Err(e) = self.create_titlebar() {
-                eprintln!("Failed to create titlebar: {:?}", e);
+                tracing::error!("application_window: failed to create titlebar: {e:?}");
             }
         }
 
@@ -425,10 +426,21 @@ impl ApplicationWindow {
         if let Some(wl_surface) = self.wl_surface() {
             wl_surface.commit();
         }
+        self.for_each_subsurface(|sub| sub.parent_committed());
 
         self.render();
     }
 
+    fn for_each_subsurface(&self, f: impl Fn(&SubsurfaceSurface)) {
+        for slot in [&self.titlebar, &self.sidebar, &self.content] {
+            if let Ok(guard) = slot.read() {
+                if let Some(ref sub) = *guard {
+                    f(sub);
+                }
+            }
+        }
+    }
+
     /// Render the window
     pub fn render(&self) {
         if let Ok(surface_guard) = self.surface.read() {
@@ -437,21 +449,8 @@ impl ApplicationWindow {
                     return;
                 }
 
-                // Render main surface FIRST
-                let on_draw_fn = self.on_draw_fn.clone();
-                // let bg_color = self.background_color;
-
-                surface.draw(|canvas| {
-                    // canvas.clear(bg_color);
-
-                    // Draw custom content if provided
-                    if let Ok(mut draw_fn_guard) = on_draw_fn.lock() {
-                        if let Some(ref mut content_fn) = *draw_fn_guard {
-                            content_fn(canvas);
-                        }
-                    }
-                });
-
+                // Subsurfaces are synchronized: draw them first so the main
+                // surface's commit shows the whole window at once.
                 // Render titlebar if it exists
                 if let Ok(titlebar_guard) = self.titlebar.read() {
                     if let Some(ref titlebar) = *titlebar_guard {
@@ -499,6 +498,22 @@ impl ApplicationWindow {
                         });
                     }
                 }
+
+                // Render main surface LAST; its commit applies the subsurfaces
+                let on_draw_fn = self.on_draw_fn.clone();
+                // let bg_color = self.background_color;
+
+                surface.draw(|canvas| {
+                    // canvas.clear(bg_color);
+
+                    // Draw custom content if provided
+                    if let Ok(mut draw_fn_guard) = on_draw_fn.lock() {
+                        if let Some(ref mut content_fn) = *draw_fn_guard {
+                            content_fn(canvas);
+                        }
+                    }
+                });
+                self.for_each_subsurface(|sub| sub.parent_committed());
             }
         }
     }
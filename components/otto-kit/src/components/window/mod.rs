@@ -2,10 +2,14 @@ mod application_window;
 
 use smithay_client_toolkit::seat::pointer::PointerEvent;
 use smithay_client_toolkit::shell::xdg::window::WindowConfigure;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
-use wayland_client::protocol::wl_seat;
+use wayland_client::protocol::{wl_keyboard, wl_seat};
 
-use crate::app_runner::AppContext;
+use crate::app_runner::{AppContext, DropEvent};
+use crate::input::{ScrollEvent, TouchEvent};
 pub use crate::protocols::otto_surface_style_v1;
 use crate::surfaces::{SurfaceError, ToplevelSurface};
 
@@ -19,6 +23,17 @@ fn default_layer_augmentation(layer: &otto_surface_style_v1::OttoSurfaceStyleV1)
 
 type CanvasDrawFn = Arc<Mutex<Option<Box<dyn FnMut(&skia_safe::Canvas) + Send>>>>;
 
+/// Event handlers registered on one window
+#[derive(Default)]
+#[allow(clippy::type_complexity)]
+struct WindowHandlers {
+    pointer: Vec<Box<dyn FnMut(&[PointerEvent])>>,
+    touch: Vec<Box<dyn FnMut(&[TouchEvent])>>,
+    keyboard: Vec<Box<dyn FnMut(u32, wl_keyboard::KeyState, u32)>>,
+    text: Vec<Box<dyn FnMut(&str)>>,
+    close_requested: Option<Box<dyn FnMut() -> bool>>,
+}
+
 /// Window component using ToplevelSurface
 ///
 /// This is a high-level window component that uses ToplevelSurface for
@@ -31,6 +46,8 @@ type CanvasDrawFn = Arc<Mutex<Option<Box<dyn FnMut(&skia_safe::Canvas) + Send>>>
 /// Assign a Layer node to this window to render it.
 ///
 /// Window is Clone-able, allowing it to be shared across the application.
+/// An app can open several windows: each one only receives the configure,
+/// pointer, touch, keyboard and frame events of its own surface.
 #[derive(Clone)]
 pub struct Window {
     #[allow(clippy::arc_with_non_send_sync)]
@@ -38,6 +55,10 @@ pub struct Window {
     background_color: Arc<RwLock<skia_safe::Color>>,
     title: Arc<RwLock<String>>,
     on_draw_fn: CanvasDrawFn,
+    /// A frame callback is outstanding; redraws wait for it.
+    frame_pending: Arc<AtomicBool>,
+    handlers: Rc<RefCell<WindowHandlers>>,
+    closed: Rc<Cell<bool>>,
 }
 
 impl Window {
@@ -53,10 +74,12 @@ impl Window {
 
         // Apply default layer styling immediately
         if let Some(surface_style) = surface.surface_style() {
-            eprintln!("Applying corner radius to window surface style");
+            tracing::debug!("window: applying default corner radius");
             default_layer_augmentation(surface_style);
         } else {
-            eprintln!("Warning: No surface style available - window will not have rounded corners");
+            tracing::warn!(
+                "window: no surface style available, window will not have rounded corners"
+            );
         }
 
         let window = Self {
@@ -65,20 +88,12 @@ impl Window {
             background_color: Arc::new(RwLock::new(skia_safe::Color::from_rgb(245, 245, 245))),
             title: Arc::new(RwLock::new(title.to_string())),
             on_draw_fn: Arc::new(Mutex::new(None)),
+            frame_pending: Arc::new(AtomicBool::new(false)),
+            handlers: Rc::new(RefCell::new(WindowHandlers::default())),
+            closed: Rc::new(Cell::new(false)),
         };
 
-        // Auto-register configure handler now that Window is Clone
-        let window_clone = window.clone();
-        AppContext::register_configure_handler(move || {
-            if let Some((_surface_id, configure, serial)) = AppContext::current_surface_configure()
-            {
-                // Just call configure for all configure events since we can't reliably match surface IDs
-                // The window will check if it's configured and render
-                window_clone.on_configure(configure, serial);
-            }
-        });
-
-        // Register window for automatic updates
+        // AppRunner routes this surface's events and redraws to the window
         AppContext::register_window(window.clone());
 
         Ok(window)
@@ -166,7 +181,7 @@ impl Window {
     }
 
     /// Internal: Handle window configure event
-    fn on_configure(&self, configure: WindowConfigure, serial: u32) {
+    pub(crate) fn handle_configure(&self, configure: WindowConfigure, serial: u32) {
         if let Ok(mut surface_guard) = self.surface.write() {
             if let Some(ref mut surface) = *surface_guard {
                 let _ = surface.handle_configure(configure, serial);
@@ -217,13 +232,29 @@ impl Window {
     }
 
     /// Update the window - render if dirty
+    ///
+    /// At most one redraw per compositor frame: after drawing, the window
+    /// waits for the frame callback before drawing again.
     pub(crate) fn update(&self) {
-        if let Some(surface) = self.surface() {
-            if surface.is_dirty() {
-                self.render();
-                surface.clear_dirty();
-            }
+        let Some(surface) = self.surface() else {
+            return;
+        };
+        if !surface.is_dirty()
+            || !surface.is_configured()
+            || self.frame_pending.load(Ordering::Relaxed)
+        {
+            return;
         }
+
+        // Requested before drawing so it goes out with the draw's commit.
+        self.frame_pending.store(true, Ordering::Relaxed);
+        let frame_pending = self.frame_pending.clone();
+        AppContext::request_animation_frame(surface.wl_surface(), move || {
+            frame_pending.store(false, Ordering::Relaxed);
+        });
+
+        self.render();
+        surface.clear_dirty();
     }
 
     /// Get the underlying ToplevelSurface
@@ -271,33 +302,199 @@ impl Window {
     ///     }
     /// });
     /// ```
-    pub fn on_pointer_event<F>(&self, mut callback: F)
+    pub fn on_pointer_event<F>(&self, callback: F)
     where
         F: FnMut(&[PointerEvent]) + 'static,
     {
-        // Clone the window Arc to check surface on each event
-        let window_clone = self.clone();
-
-        AppContext::register_pointer_callback(move |events| {
-            // Get our surface ID dynamically each time
-            if let Some(our_wl_surface) = window_clone.wl_surface() {
-                use wayland_client::Proxy;
-                // Filter to only events for our surface
-                let our_events: Vec<&PointerEvent> = events
-                    .iter()
-                    .filter(|e| e.surface.id() == our_wl_surface.id())
-                    .collect();
-
-                if !our_events.is_empty() {
-                    // eprintln!("Window got {} pointer events", our_events.len());
-                    let borrowed_events: Vec<PointerEvent> =
-                        our_events.iter().map(|&e| e.clone()).collect();
-                    callback(&borrowed_events);
-                }
+        self.handlers.borrow_mut().pointer.push(Box::new(callback));
+    }
+
+    /// Register a scroll handler for this window
+    /// The callback receives wheel, touchpad and horizontal scrolls over the window
+    ///
+    /// # Example
+    /// ```no_run
+    /// window.on_scroll(|scroll| {
+    ///     let (_, steps) = scroll.steps();
+    ///     println!("scrolled {} px, {} notches", scroll.dy, steps);
+    /// });
+    /// ```
+    pub fn on_scroll<F>(&self, mut callback: F)
+    where
+        F: FnMut(&ScrollEvent) + 'static,
+    {
+        self.on_pointer_event(move |events| {
+            for scroll in events.iter().filter_map(ScrollEvent::from_pointer_event) {
+                callback(&scroll);
             }
         });
     }
 
+    /// Register a touch event handler for this window
+    /// The callback receives the events of touch points that went down on the window
+    ///
+    /// # Example
+    /// ```no_run
+    /// window.on_touch_event(|events| {
+    ///     for event in events {
+    ///         if let TouchEventKind::Up { .. } = event.kind {
+    ///             // Handle tap of finger `event.id` at `event.position`
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub fn on_touch_event<F>(&self, callback: F)
+    where
+        F: FnMut(&[TouchEvent]) + 'static,
+    {
+        self.handlers.borrow_mut().touch.push(Box::new(callback));
+    }
+
+    /// Register a keyboard handler for this window
+    /// The callback receives key presses and releases while the window has keyboard focus,
+    /// after `App::on_keyboard_event`
+    ///
+    /// # Example
+    /// ```no_run
+    /// window.on_keyboard_event(|key, state, _serial| {
+    ///     if key == KEY_ESC && state == wl_keyboard::KeyState::Pressed {
+    ///         preferences.close();
+    ///     }
+    /// });
+    /// ```
+    pub fn on_keyboard_event<F>(&self, callback: F)
+    where
+        F: FnMut(u32, wl_keyboard::KeyState, u32) + 'static,
+    {
+        self.handlers.borrow_mut().keyboard.push(Box::new(callback));
+    }
+
+    /// Register a text input handler for this window
+    /// The callback receives the text key presses produce while the window has keyboard focus
+    pub fn on_text_input<F>(&self, callback: F)
+    where
+        F: FnMut(&str) + 'static,
+    {
+        self.handlers.borrow_mut().text.push(Box::new(callback));
+    }
+
+    /// Decide what happens when the user asks to close this window
+    /// Return `false` to keep it open; by default the window closes
+    pub fn on_close_requested<F>(&self, callback: F)
+    where
+        F: FnMut() -> bool + 'static,
+    {
+        self.handlers.borrow_mut().close_requested = Some(Box::new(callback));
+    }
+
+    /// Close this window
+    ///
+    /// Destroys the surface and drops the window's handlers; other windows
+    /// stay open. Closing the last window exits the app.
+    pub fn close(&self) {
+        use wayland_client::Proxy;
+
+        if self.closed.replace(true) {
+            return;
+        }
+        let remaining = match self.wl_surface() {
+            Some(surface) => {
+                AppContext::forget_surface(&surface.id());
+                AppContext::unregister_window(&surface.id())
+            }
+            None => AppContext::windows().len(),
+        };
+
+        // Handlers usually hold clones of the window; drop them to free it
+        *self.handlers.borrow_mut() = WindowHandlers::default();
+        if let Ok(mut draw_fn) = self.on_draw_fn.lock() {
+            *draw_fn = None;
+        }
+        if let Ok(mut surface) = self.surface.write() {
+            *surface = None;
+        }
+
+        if remaining == 0 {
+            AppContext::request_exit();
+        }
+    }
+
+    /// Whether `close()` was called on this window
+    pub fn is_closed(&self) -> bool {
+        self.closed.get()
+    }
+
+    /// Run the handlers picked by `select`; handlers may register more
+    /// handlers or close the window while running.
+    fn run_handlers<H>(
+        &self,
+        select: fn(&mut WindowHandlers) -> &mut Vec<H>,
+        mut call: impl FnMut(&mut H),
+    ) {
+        let mut running = std::mem::take(select(&mut self.handlers.borrow_mut()));
+        for handler in running.iter_mut() {
+            call(handler);
+        }
+        if self.is_closed() {
+            return;
+        }
+        let mut handlers = self.handlers.borrow_mut();
+        let added = std::mem::replace(select(&mut handlers), running);
+        select(&mut handlers).extend(added);
+    }
+
+    pub(crate) fn dispatch_pointer_events(&self, events: &[PointerEvent]) {
+        self.run_handlers(|h| &mut h.pointer, |handler| handler(events));
+    }
+
+    pub(crate) fn dispatch_touch_events(&self, events: &[TouchEvent]) {
+        self.run_handlers(|h| &mut h.touch, |handler| handler(events));
+    }
+
+    pub(crate) fn dispatch_key(&self, key: u32, state: wl_keyboard::KeyState, serial: u32) {
+        self.run_handlers(|h| &mut h.keyboard, |handler| handler(key, state, serial));
+    }
+
+    pub(crate) fn dispatch_text(&self, text: &str) {
+        self.run_handlers(|h| &mut h.text, |handler| handler(text));
+    }
+
+    /// Ask the close handler whether the window may close
+    pub(crate) fn close_requested(&self) -> bool {
+        let handler = self.handlers.borrow_mut().close_requested.take();
+        let Some(mut handler) = handler else {
+            return true;
+        };
+        let allow = handler();
+        if !self.is_closed() {
+            self.handlers
+                .borrow_mut()
+                .close_requested
+                .get_or_insert(handler);
+        }
+        allow
+    }
+
+    /// Accept drops of `mime_types` on this window
+    /// Mime types are listed by preference; the callback receives the dropped data
+    ///
+    /// # Example
+    /// ```no_run
+    /// window.on_drop(&["text/uri-list", "text/plain"], |drop| {
+    ///     let text = String::from_utf8_lossy(&drop.data);
+    ///     println!("dropped {} at {:?}: {}", drop.mime_type, drop.position, text);
+    /// });
+    /// ```
+    pub fn on_drop<F>(&self, mime_types: &[&str], callback: F)
+    where
+        F: FnMut(&DropEvent) + 'static,
+    {
+        use wayland_client::Proxy;
+        if let Some(wl_surface) = self.wl_surface() {
+            AppContext::register_drop_target(wl_surface.id(), mime_types, callback);
+        }
+    }
+
     /// Start an interactive window move
     /// Call this in response to a pointer button press to make the window draggable
     ///
@@ -330,6 +527,39 @@ impl Window {
             }
         }
     }
+
+    /// Run `callback` once, at the window's next frame callback.
+    ///
+    /// Use it to step animations in sync with the compositor; call
+    /// `request_frame()` inside the callback to redraw, and request another
+    /// animation frame to continue.
+    ///
+    /// # Example
+    /// ```ignore
+    /// fn tick(window: Window) {
+    ///     let next = window.clone();
+    ///     window.request_animation_frame(move || {
+    ///         // advance the animation...
+    ///         next.request_frame();
+    ///         tick(next);
+    ///     });
+    /// }
+    /// ```
+    pub fn request_animation_frame<F>(&self, callback: F)
+    where
+        F: FnOnce() + 'static,
+    {
+        let Some(surface) = self.wl_surface() else {
+            return;
+        };
+        AppContext::request_animation_frame(&surface, callback);
+        // Unless a redraw is about to commit (or the pending frame covers
+        // it), commit so the request reaches the compositor.
+        let redraw_due = self.surface().is_some_and(|s| s.is_dirty());
+        if !self.frame_pending.load(Ordering::Relaxed) && !redraw_due {
+            surface.commit();
+        }
+    }
     pub fn title(&self) -> String {
         self.title
             .read()
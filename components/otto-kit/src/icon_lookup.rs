@@ -0,0 +1,398 @@
+//! Freedesktop icon theme lookup.
+//!
+//! Implements the lookup of the Icon Theme Specification: the requested theme
+//! is searched first, then the themes it `Inherits`, then `hicolor`, then the
+//! unthemed icons in the base directories and `/usr/share/pixmaps`. Within a
+//! theme, a directory matching the requested size and scale wins; otherwise
+//! the closest size is used. SVG is preferred over PNG at every step, since
+//! icons are drawn at many sizes.
+//!
+//! Parsed themes and resolved paths, including misses, are cached until
+//! `clear_cache()`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// File extensions tried for every icon, most preferred first
+const EXTENSIONS: [&str; 2] = ["svg", "png"];
+
+/// Theme every other theme implicitly inherits from
+const FALLBACK_THEME: &str = "hicolor";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DirType {
+    Fixed,
+    Scalable,
+    Threshold,
+}
+
+/// A size directory of a theme, as described by its `index.theme` section
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ThemeDir {
+    path: String,
+    size: i32,
+    scale: i32,
+    kind: DirType,
+    min_size: i32,
+    max_size: i32,
+    threshold: i32,
+}
+
+impl ThemeDir {
+    fn matches_size(&self, size: i32, scale: i32) -> bool {
+        if self.scale != scale {
+            return false;
+        }
+        match self.kind {
+            DirType::Fixed => self.size == size,
+            DirType::Scalable => (self.min_size..=self.max_size).contains(&size),
+            DirType::Threshold => {
+                (self.size - self.threshold..=self.size + self.threshold).contains(&size)
+            }
+        }
+    }
+
+    fn size_distance(&self, size: i32, scale: i32) -> i32 {
+        let wanted = size * scale;
+        let (min, max) = match self.kind {
+            DirType::Fixed => (self.size, self.size),
+            DirType::Scalable => (self.min_size, self.max_size),
+            DirType::Threshold => (self.size - self.threshold, self.size + self.threshold),
+        };
+        if wanted < min * self.scale {
+            min * self.scale - wanted
+        } else if wanted > max * self.scale {
+            wanted - max * self.scale
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct IconTheme {
+    /// `<base dir>/<theme name>` for every base dir that has the theme
+    roots: Vec<PathBuf>,
+    inherits: Vec<String>,
+    dirs: Vec<ThemeDir>,
+}
+
+type PathKey = (Option<String>, String, i32, i32);
+
+static THEMES: LazyLock<RwLock<HashMap<String, Option<Arc<IconTheme>>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+static PATHS: LazyLock<RwLock<HashMap<PathKey, Option<PathBuf>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Find the file of `icon_name` at `size` and `scale` in `theme`, or in the
+/// user's GTK icon theme when `theme` is `None`.
+///
+/// `icon_name` may also be an absolute path, as desktop entries allow.
+pub fn lookup_icon(icon_name: &str, size: i32, scale: i32, theme: Option<&str>) -> Option<PathBuf> {
+    let key = (
+        theme.map(str::to_string),
+        icon_name.to_string(),
+        size,
+        scale,
+    );
+    if let Some(cached) = PATHS.read().unwrap().get(&key) {
+        return cached.clone();
+    }
+
+    let theme_name = theme.map(str::to_string).or_else(default_theme_name);
+    let result = find_icon(icon_name, size, scale.max(1), theme_name.as_deref());
+    PATHS.write().unwrap().insert(key, result.clone());
+    result
+}
+
+/// Forget parsed themes and resolved icons, after themes or apps are
+/// installed.
+pub fn clear_cache() {
+    THEMES.write().unwrap().clear();
+    PATHS.write().unwrap().clear();
+}
+
+fn find_icon(icon_name: &str, size: i32, scale: i32, theme: Option<&str>) -> Option<PathBuf> {
+    if icon_name.is_empty() {
+        return None;
+    }
+    let path = Path::new(icon_name);
+    if path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    let mut visited = Vec::new();
+    theme
+        .and_then(|theme| find_in_theme_chain(theme, icon_name, size, scale, &mut visited))
+        .or_else(|| find_in_theme_chain(FALLBACK_THEME, icon_name, size, scale, &mut visited))
+        .or_else(|| find_unthemed(icon_name))
+}
+
+fn find_in_theme_chain(
+    theme_name: &str,
+    icon_name: &str,
+    size: i32,
+    scale: i32,
+    visited: &mut Vec<String>,
+) -> Option<PathBuf> {
+    if visited.iter().any(|name| name == theme_name) {
+        return None;
+    }
+    visited.push(theme_name.to_string());
+    let theme = load_theme(theme_name)?;
+    find_in_theme(&theme, icon_name, size, scale).or_else(|| {
+        theme
+            .inherits
+            .iter()
+            .find_map(|parent| find_in_theme_chain(parent, icon_name, size, scale, visited))
+    })
+}
+
+fn find_in_theme(theme: &IconTheme, icon_name: &str, size: i32, scale: i32) -> Option<PathBuf> {
+    let files_in = |dir: &ThemeDir| {
+        theme.roots.iter().flat_map(move |root| {
+            EXTENSIONS
+                .iter()
+                .map(move |ext| root.join(&dir.path).join(format!("{icon_name}.{ext}")))
+        })
+    };
+
+    for dir in theme
+        .dirs
+        .iter()
+        .filter(|dir| dir.matches_size(size, scale))
+    {
+        if let Some(path) = files_in(dir).find(|path| path.is_file()) {
+            return Some(path);
+        }
+    }
+
+    // Closest size; on ties the earlier directory and extension win
+    let mut best: Option<(i32, PathBuf)> = None;
+    for dir in &theme.dirs {
+        let distance = dir.size_distance(size, scale);
+        if best.as_ref().is_some_and(|(best, _)| distance >= *best) {
+            continue;
+        }
+        if let Some(path) = files_in(dir).find(|path| path.is_file()) {
+            best = Some((distance, path));
+        }
+    }
+    best.map(|(_, path)| path)
+}
+
+fn find_unthemed(icon_name: &str) -> Option<PathBuf> {
+    base_dirs()
+        .into_iter()
+        .chain(std::iter::once(PathBuf::from("/usr/share/pixmaps")))
+        .flat_map(|dir| {
+            EXTENSIONS
+                .iter()
+                .map(move |ext| dir.join(format!("{icon_name}.{ext}")))
+        })
+        .find(|path| path.is_file())
+}
+
+fn load_theme(name: &str) -> Option<Arc<IconTheme>> {
+    if let Some(cached) = THEMES.read().unwrap().get(name) {
+        return cached.clone();
+    }
+
+    let roots: Vec<PathBuf> = base_dirs()
+        .into_iter()
+        .map(|dir| dir.join(name))
+        .filter(|root| root.is_dir())
+        .collect();
+    // The first index.theme found describes the theme
+    let theme = roots
+        .iter()
+        .find_map(|root| std::fs::read_to_string(root.join("index.theme")).ok())
+        .map(|index| {
+            let (inherits, dirs) = parse_index(&index);
+            Arc::new(IconTheme {
+                roots,
+                inherits,
+                dirs,
+            })
+        });
+    if theme.is_none() {
+        tracing::debug!("Icon theme '{name}' not found");
+    }
+
+    THEMES
+        .write()
+        .unwrap()
+        .insert(name.to_string(), theme.clone());
+    theme
+}
+
+/// `Inherits` and the size directories of an `index.theme`
+fn parse_index(content: &str) -> (Vec<String>, Vec<ThemeDir>) {
+    let mut sections: HashMap<&str, HashMap<&str, &str>> = HashMap::new();
+    let mut current = None;
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = Some(name);
+            sections.entry(name).or_default();
+        } else if let (Some(section), Some((key, value))) = (current, line.split_once('=')) {
+            sections
+                .entry(section)
+                .or_default()
+                .insert(key.trim(), value.trim());
+        }
+    }
+
+    let list = |value: Option<&&str>| -> Vec<String> {
+        value
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let Some(header) = sections.get("Icon Theme") else {
+        return (Vec::new(), Vec::new());
+    };
+    let inherits = list(header.get("Inherits"));
+    let mut names = list(header.get("Directories"));
+    for name in list(header.get("ScaledDirectories")) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    let dirs = names
+        .into_iter()
+        .filter_map(|name| {
+            let section = sections.get(name.as_str())?;
+            let int = |key: &str| section.get(key).and_then(|value| value.parse::<i32>().ok());
+            let size = int("Size")?;
+            let kind = match section.get("Type").copied() {
+                Some("Fixed") => DirType::Fixed,
+                Some("Scalable") => DirType::Scalable,
+                _ => DirType::Threshold,
+            };
+            Some(ThemeDir {
+                path: name,
+                size,
+                scale: int("Scale").unwrap_or(1),
+                kind,
+                min_size: int("MinSize").unwrap_or(size),
+                max_size: int("MaxSize").unwrap_or(size),
+                threshold: int("Threshold").unwrap_or(2),
+            })
+        })
+        .collect();
+    (inherits, dirs)
+}
+
+/// Icon base directories, in lookup order
+fn base_dirs() -> Vec<PathBuf> {
+    let home = std::env::var("HOME").ok().map(PathBuf::from);
+    let data_home = std::env::var("XDG_DATA_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join(".local/share")));
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+    let mut dirs: Vec<PathBuf> = home.map(|home| home.join(".icons")).into_iter().collect();
+    dirs.extend(data_home.map(|dir| dir.join("icons")));
+    dirs.extend(
+        data_dirs
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| Path::new(dir).join("icons")),
+    );
+    dirs
+}
+
+/// The icon theme GTK apps use, from `gtk-3.0/settings.ini`
+fn default_theme_name() -> Option<String> {
+    let config_home = std::env::var("XDG_CONFIG_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var("HOME")
+                .ok()
+                .map(|home| PathBuf::from(home).join(".config"))
+        })?;
+    let settings = std::fs::read_to_string(config_home.join("gtk-3.0/settings.ini")).ok()?;
+    settings.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "gtk-icon-theme-name")
+            .then(|| value.trim().trim_matches('"').to_string())
+            .filter(|name| !name.is_empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INDEX: &str = "\
+[Icon Theme]
+Name=Test
+Inherits=Adwaita, hicolor
+Directories=48x48/apps,scalable/apps
+ScaledDirectories=48x48@2/apps
+
+# comment
+[48x48/apps]
+Size=48
+Type=Fixed
+
+[48x48@2/apps]
+Size=48
+Scale=2
+Type=Fixed
+
+[scalable/apps]
+Size=128
+MinSize=8
+MaxSize=512
+Type=Scalable
+";
+
+    #[test]
+    fn index_lists_parents_and_directories() {
+        let (inherits, dirs) = parse_index(INDEX);
+        assert_eq!(inherits, ["Adwaita", "hicolor"]);
+        let paths: Vec<_> = dirs.iter().map(|dir| dir.path.as_str()).collect();
+        assert_eq!(paths, ["48x48/apps", "scalable/apps", "48x48@2/apps"]);
+        assert_eq!(dirs[1].kind, DirType::Scalable);
+        assert_eq!((dirs[1].min_size, dirs[1].max_size), (8, 512));
+        assert_eq!(dirs[2].scale, 2);
+    }
+
+    #[test]
+    fn directories_match_size_and_scale() {
+        let (_, dirs) = parse_index(INDEX);
+        assert!(dirs[0].matches_size(48, 1));
+        assert!(!dirs[0].matches_size(48, 2));
+        assert!(dirs[1].matches_size(256, 1));
+        assert!(!dirs[1].matches_size(1024, 1));
+        assert!(dirs[2].matches_size(48, 2));
+    }
+
+    #[test]
+    fn size_distance_is_zero_inside_the_range() {
+        let (_, dirs) = parse_index(INDEX);
+        assert_eq!(dirs[0].size_distance(64, 1), 16);
+        assert_eq!(dirs[1].size_distance(256, 1), 0);
+        assert_eq!(dirs[1].size_distance(1024, 1), 512);
+        assert_eq!(dirs[2].size_distance(48, 1), 48);
+    }
+}
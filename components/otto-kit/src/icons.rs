@@ -92,14 +92,14 @@ pub fn cached_file_icon(path: &str, size: i32) -> Option<skia::Image> {
 /// Find an icon file path using XDG icon theme directories.
 ///
 /// Searches the compositor's configured icon theme (from the portal) first,
-/// then falls back to auto-detection.
+/// then falls back to the user's GTK theme.
 pub fn find_icon(icon_name: &str, size: i32, scale: i32) -> Option<String> {
     let theme = crate::icon_theme::current_icon_theme();
     let result = find_icon_in_theme(icon_name, size, scale, theme.as_deref());
 
-    // xdgkit may return a fallback icon (e.g. application-default-icon) even
-    // when the requested icon doesn't exist.  Reject results whose filename
-    // doesn't match what we asked for.
+    // find_icon_in_theme falls back to a generic application icon when the
+    // requested icon doesn't exist. Reject results whose filename doesn't
+    // match what we asked for.
     result.filter(|path| {
         std::path::Path::new(path)
             .file_stem()
@@ -109,44 +109,23 @@ pub fn find_icon(icon_name: &str, size: i32, scale: i32) -> Option<String> {
     })
 }
 
-/// Find an icon in a specific theme (or auto-detect if `theme_name` is None).
+/// Find an icon in a specific theme, or the user's GTK theme if `theme_name`
+/// is None, following the theme's `Inherits` chain and `hicolor`. Falls back
+/// to a generic application icon.
 pub fn find_icon_in_theme(
     icon_name: &str,
     size: i32,
     scale: i32,
     theme_name: Option<&str>,
 ) -> Option<String> {
-    let dir_list = xdgkit::icon_finder::generate_dir_list();
-
-    let result = if let Some(name) = theme_name {
-        // Try specified theme first
-        let theme_dir = dir_list.iter().find(|dir| dir.theme == name).cloned();
-
-        if let Some(theme_dir) = theme_dir {
-            let theme = xdgkit::icon_theme::IconTheme::from_pathbuff(theme_dir.index());
-            xdgkit::icon_finder::multiple_find_icon(
-                icon_name.to_string(),
-                size,
-                scale,
-                dir_list.clone(),
-                theme,
-            )
-            .map(|p| p.to_string_lossy().into_owned())
-        } else {
-            tracing::warn!("Icon theme '{name}' not found, falling back to auto-detection");
-            xdgkit::icon_finder::find_icon(icon_name.to_string(), size, scale)
-                .map(|p| p.to_string_lossy().into_owned())
-        }
-    } else {
-        xdgkit::icon_finder::find_icon(icon_name.to_string(), size, scale)
-            .map(|p| p.to_string_lossy().into_owned())
-    };
+    let result = crate::icon_lookup::lookup_icon(icon_name, size, scale, theme_name)
+        .map(|p| p.to_string_lossy().into_owned());
 
     // Fallbacks
     result.or_else(|| {
         if icon_name != "application-default-icon" && icon_name != "application-x-executable" {
-            find_icon("application-default-icon", size, scale)
-                .or_else(|| find_icon("application-x-executable", size, scale))
+            find_icon_in_theme("application-default-icon", size, scale, theme_name)
+                .or_else(|| find_icon_in_theme("application-x-executable", size, scale, theme_name))
         } else {
             None
         }
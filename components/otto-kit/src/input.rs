@@ -1,6 +1,7 @@
-//! Keyboard input handling utilities
+//! Keyboard, pointer and touch input handling utilities
 //!
-//! Provides key code constants for handling keyboard events in HelloDesign components.
+//! Provides key code constants for handling keyboard events in HelloDesign components,
+//! [`ScrollEvent`] for handling pointer axis events and [`TouchEvent`] for touchscreens.
 //!
 //! ## Key Codes vs Keysyms
 //!
@@ -38,6 +39,28 @@
 //!
 //! Keycode source: Linux kernel `<linux/input-event-codes.h>`
 //! - Full list: https://github.com/torvalds/linux/blob/master/include/uapi/linux/input-event-codes.h
+//!
+//! ## Scrolling
+//!
+//! [`ScrollEvent`] collects the axis events of one pointer frame:
+//! - `dx`/`dy`: scroll distance in surface pixels, for both wheels and touchpads
+//! - `value120`: wheel steps in 1/120 of a notch, so high-resolution wheels scroll smoothly
+//! - `source`: wheel, finger, continuous or wheel tilt
+//! - `stop`: the touchpad scroll ended; start kinetic scrolling here
+//!
+//! ```rust,ignore
+//! window.on_scroll(|scroll| {
+//!     if scroll.is_discrete() {
+//!         let (_, steps) = scroll.steps();
+//!         offset += steps * LINE_HEIGHT;
+//!     } else {
+//!         offset += scroll.dy;
+//!     }
+//! });
+//! ```
+
+use smithay_client_toolkit::seat::pointer::{AxisScroll, PointerEvent, PointerEventKind};
+use wayland_client::protocol::{wl_pointer, wl_surface};
 
 /// Linux kernel keycode constants (evdev scancodes)
 pub mod keycodes {
@@ -47,6 +70,9 @@ pub mod keycodes {
     /// Enter/Return key (scancode 28)
     pub const ENTER: u32 = 28;
 
+    /// Keypad Enter key (scancode 96)
+    pub const KP_ENTER: u32 = 96;
+
     /// Backspace key (scancode 14)
     pub const BACKSPACE: u32 = 14;
 
@@ -133,6 +159,134 @@ pub mod keycodes {
     pub const INSERT: u32 = 110;
 }
 
+/// The device a scroll comes from, as reported by `wl_pointer.axis_source`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScrollSource {
+    /// Mouse wheel, scrolling in notches
+    Wheel,
+    /// Fingers on a touchpad; the scroll ends with an axis stop
+    Finger,
+    /// Continuous motion without fingers, e.g. a trackpoint
+    Continuous,
+    /// Sideways tilt of a mouse wheel, scrolling in notches
+    WheelTilt,
+    /// The compositor did not report a source
+    #[default]
+    Unknown,
+}
+
+impl From<Option<wl_pointer::AxisSource>> for ScrollSource {
+    fn from(source: Option<wl_pointer::AxisSource>) -> Self {
+        match source {
+            Some(wl_pointer::AxisSource::Wheel) => Self::Wheel,
+            Some(wl_pointer::AxisSource::Finger) => Self::Finger,
+            Some(wl_pointer::AxisSource::Continuous) => Self::Continuous,
+            Some(wl_pointer::AxisSource::WheelTilt) => Self::WheelTilt,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// A scroll on a surface: the axis events of one pointer frame
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ScrollEvent {
+    /// Surface-local pointer position
+    pub position: (f64, f64),
+    /// Horizontal distance in surface pixels; positive scrolls right
+    pub dx: f64,
+    /// Vertical distance in surface pixels; positive scrolls down
+    pub dy: f64,
+    /// Horizontal and vertical wheel steps in 1/120 of a notch.
+    /// Zero for continuous sources.
+    pub value120: (i32, i32),
+    /// The device the scroll comes from
+    pub source: ScrollSource,
+    /// The horizontal and vertical scroll ended (`wl_pointer.axis_stop`),
+    /// e.g. the fingers left the touchpad
+    pub stop: (bool, bool),
+}
+
+impl ScrollEvent {
+    /// Build a scroll from an axis pointer event, `None` for other events
+    pub fn from_pointer_event(event: &PointerEvent) -> Option<Self> {
+        let PointerEventKind::Axis {
+            horizontal,
+            vertical,
+            source,
+            ..
+        } = &event.kind
+        else {
+            return None;
+        };
+        Some(Self {
+            position: event.position,
+            dx: horizontal.absolute,
+            dy: vertical.absolute,
+            value120: (value120(horizontal), value120(vertical)),
+            source: (*source).into(),
+            stop: (horizontal.stop, vertical.stop),
+        })
+    }
+
+    /// Whether the scroll comes in wheel notches rather than pixel deltas
+    pub fn is_discrete(&self) -> bool {
+        matches!(self.source, ScrollSource::Wheel | ScrollSource::WheelTilt)
+            || self.value120 != (0, 0)
+    }
+
+    /// Horizontal and vertical wheel steps; fractional on high-resolution wheels
+    pub fn steps(&self) -> (f64, f64) {
+        (
+            self.value120.0 as f64 / 120.0,
+            self.value120.1 as f64 / 120.0,
+        )
+    }
+
+    /// Whether the scroll ended on either axis
+    pub fn is_stop(&self) -> bool {
+        self.stop.0 || self.stop.1
+    }
+}
+
+/// Wheel steps of an axis in 1/120 of a notch. Compositors older than
+/// `wl_pointer` version 8 only send whole notches.
+fn value120(axis: &AxisScroll) -> i32 {
+    if axis.value120 != 0 {
+        axis.value120
+    } else {
+        axis.discrete * 120
+    }
+}
+
+/// What happened to a touch point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchEventKind {
+    /// A finger touched the surface
+    Down { serial: u32, time: u32 },
+    /// The finger left the screen
+    Up { serial: u32, time: u32 },
+    /// The finger moved
+    Motion { time: u32 },
+    /// The compositor took the touch sequence over, e.g. for a gesture.
+    /// Drop the touch point without acting on it.
+    Cancel,
+}
+
+/// A touch point event
+///
+/// Every event of a touch point goes to the surface the finger went down on,
+/// even after the finger moved off it.
+#[derive(Debug, Clone)]
+pub struct TouchEvent {
+    /// Touch point id, unique among the fingers currently down
+    pub id: i32,
+    /// Surface the finger went down on
+    pub surface: wl_surface::WlSurface,
+    /// Surface-local position. Up and cancel repeat the last known position.
+    pub position: (f64, f64),
+    pub kind: TouchEventKind,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,4 +305,34 @@ mod tests {
         assert_eq!(keycodes::ENTER, 28);
         assert_eq!(keycodes::BACKSPACE, 14);
     }
+
+    #[test]
+    fn test_scroll_source() {
+        assert_eq!(
+            ScrollSource::from(Some(wl_pointer::AxisSource::Finger)),
+            ScrollSource::Finger
+        );
+        assert_eq!(ScrollSource::from(None), ScrollSource::Unknown);
+    }
+
+    #[test]
+    fn test_scroll_steps() {
+        let scroll = ScrollEvent {
+            dy: 7.5,
+            value120: (0, 60),
+            ..Default::default()
+        };
+        assert!(scroll.is_discrete());
+        assert_eq!(scroll.steps(), (0.0, 0.5));
+
+        let scroll = ScrollEvent {
+            dx: -3.0,
+            source: ScrollSource::Finger,
+            stop: (false, true),
+            ..Default::default()
+        };
+        assert!(!scroll.is_discrete());
+        assert_eq!(scroll.steps(), (0.0, 0.0));
+        assert!(scroll.is_stop());
+    }
 }
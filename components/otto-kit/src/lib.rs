@@ -6,6 +6,7 @@ pub mod color_scheme;
 pub mod common;
 pub mod components;
 pub mod desktop_entry;
+pub mod icon_lookup;
 pub mod icon_theme;
 pub mod icons;
 pub mod input;
@@ -26,7 +27,7 @@ pub use components::container::{
 };
 pub use components::label::{Label, LabelBuilder, TextAlign};
 pub use components::layer::{surface::LayerSurface, Layer};
-// pub use components::menu_bar::{surface::MenuBarSurface, MenuBar, MenuBarItem};
+pub use components::menu_bar::{surface::MenuBarSurface, MenuBar, MenuBarItem};
 pub use components::window::Window;
 
 // Re-export new surface types
@@ -42,25 +43,35 @@ pub use wayland_protocols::wp::cursor_shape::v1::client::wp_cursor_shape_device_
 
 /// Convenience prelude for application development
 pub mod prelude {
-    pub use crate::app_runner::{App, AppContext, AppRunner, AppRunnerWithType};
+    pub use crate::app_runner::{
+        App, AppContext, AppRunner, AppRunnerWithType, DragIcon, DropEvent,
+    };
     pub use crate::color_scheme::current_color_scheme;
     pub use crate::common::Renderable;
+    pub use crate::components::button::{Button, ButtonBuilder, ButtonVariant};
     pub use crate::components::container::stack::StackAlignment;
     pub use crate::components::container::{
-        Border, BoxShadow, Container, CornerRadius, EdgeInsets, Frame, FrameBuilder,
-        LayoutConstraints, Stack, StackDirection,
+        Border, BoxShadow, Container, CornerRadius, EdgeInsets, Frame, FrameBuilder, Grid,
+        LayoutConstraints, ScrollAxes, ScrollView, Stack, StackDirection,
     };
     pub use crate::components::context_menu::ContextMenuStyle;
+    pub use crate::components::image::{Image, ImageFit, ImageSource};
     pub use crate::components::label::{Label, LabelBuilder, TextAlign};
+    pub use crate::components::menu_bar::MenuBar;
     pub use crate::components::menu_item::{
         MenuItem, MenuItemGroup, MenuItemIcon, MenuItemKind, MenuItemState,
     };
+    pub use crate::components::slider::{Slider, SliderBuilder};
+    pub use crate::components::text_input::TextInput;
     pub use crate::components::window::Window;
     pub use crate::icon_theme::current_icon_theme;
     pub use crate::icons::{named_icon, named_icon_sized};
+    pub use crate::input::{ScrollEvent, ScrollSource, TouchEvent, TouchEventKind};
     pub use crate::theme::ColorScheme;
     pub use crate::theme::Theme;
-    pub use crate::typography::{get_font, get_font_with_fallback, styles, TextStyle};
+    pub use crate::typography::{
+        get_font, get_font_with_fallback, shape_text, styles, ShapedText, TextStyle,
+    };
     pub use skia_safe::{Canvas, Color, Font, Paint, Rect};
     // Add more common types as needed
 }
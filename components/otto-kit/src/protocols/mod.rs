@@ -23,4 +23,4 @@ pub use sc_layer_protocol::{
     otto_timing_function_v1,
 };
 
-pub use otto_dock_protocol::{otto_dock_item_v1, otto_dock_manager_v1};
+pub use otto_dock_protocol::{otto_dock_item_v1, otto_dock_launch_v1, otto_dock_manager_v1};
@@ -36,10 +36,7 @@ impl LayersRenderer {
     /// Returns true if a redraw is needed
     /// Limited to 60fps - will skip updates if called too frequently
     pub fn update(&self) -> bool {
-        // println!("LayersRenderer update called");
-
         // Update engine (layout + animations)
-        // println!("Engine update returned: {}", ret);
         self.engine.update(0.016)
     }
 
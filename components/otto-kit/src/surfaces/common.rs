@@ -78,8 +78,8 @@ impl BaseWaylandSurface {
             ));
         } else {
             // No shared context yet - create it with this first surface
-            println!(
-                "No shared context yet , Creating new Skia context for surface {}",
+            tracing::debug!(
+                "No shared Skia context yet, creating it for surface {}",
                 self.wl_surface.id()
             );
             let (new_ctx, new_surface) = SkiaContext::new(
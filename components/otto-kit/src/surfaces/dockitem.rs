@@ -79,9 +79,10 @@ impl DockItem {
         configure: WindowConfigure,
         _serial: u32,
     ) -> Result<(), SurfaceError> {
-        println!(
+        tracing::debug!(
             "DockItemSurface configure event: new_size={:?}, serial={}",
-            configure.new_size, _serial
+            configure.new_size,
+            _serial
         );
         // Get configured size or use initial size
         let (width, height) = match configure.new_size {
@@ -109,6 +110,17 @@ impl DockItem {
     pub fn dock_item(&self) -> &otto_dock_item_v1::OttoDockItemV1 {
         &self.dock_item
     }
+
+    /// Show `text` as the badge on the app's dock icon, or clear it with `None`.
+    pub fn set_badge(&self, text: Option<&str>) {
+        self.dock_item.set_badge(text.map(str::to_string));
+    }
+
+    /// Show `value`, from 0.0 to 1.0, as a progress bar on the app's dock
+    /// icon, or hide it with `None`.
+    pub fn set_progress(&self, value: Option<f64>) {
+        self.dock_item.set_progress(value.unwrap_or(-1.0));
+    }
 }
 
 impl DockItem {
@@ -20,6 +20,23 @@ struct LayerShellSurfaceInner {
     layer_surface: ZwlrLayerSurfaceV1,
     configured: bool,
     on_configure: Option<Rc<dyn Fn()>>,
+    on_resize: Option<Rc<dyn Fn(i32, i32)>>,
+}
+
+/// Size to use for a configure of `configured`, given the current size.
+/// A zero dimension means the compositor left it up to us, so keep ours.
+fn granted_size(configured: (i32, i32), current: (i32, i32)) -> (i32, i32) {
+    let width = if configured.0 > 0 {
+        configured.0
+    } else {
+        current.0
+    };
+    let height = if configured.1 > 0 {
+        configured.1
+    } else {
+        current.1
+    };
+    (width, height)
 }
 
 /// Manages a wlr-layer-shell surface with Skia rendering
@@ -171,6 +188,7 @@ impl LayerShellSurface {
             layer_surface: layer_surface.clone(),
             configured: false,
             on_configure: None,
+            on_resize: None,
         };
 
         let layer_shell_surface = Self {
@@ -202,25 +220,19 @@ impl LayerShellSurface {
                     inner.layer_surface.ack_configure(serial);
 
                     // Update dimensions
-                    let width = if width > 0 {
-                        width
-                    } else {
-                        inner.base_surface.width
-                    };
-                    let height = if height > 0 {
-                        height
-                    } else {
-                        inner.base_surface.height
-                    };
+                    let (width, height) = granted_size(
+                        (width, height),
+                        (inner.base_surface.width, inner.base_surface.height),
+                    );
 
                     // Initialize or resize Skia surface
-                    let mut callback_to_call = None;
+                    let mut callback_to_call: Option<Rc<dyn Fn()>> = None;
                     if !inner.configured {
                         // First time configuration - create the Skia surface
                         inner.base_surface.width = width;
                         inner.base_surface.height = height;
                         if let Err(e) = inner.base_surface.create_skia_surface() {
-                            eprintln!("Error creating Skia surface: {:?}", e);
+                            tracing::error!("layer_shell: error creating Skia surface: {e:?}");
                             return;
                         }
                         // Update layer node size if using layers engine
@@ -240,6 +252,10 @@ impl LayerShellSurface {
                     {
                         // Subsequent resize - use resize method
                         inner.base_surface.resize(width, height);
+                        if let Some(on_resize) = inner.on_resize.clone() {
+                            let on_resize = move || on_resize(width, height);
+                            callback_to_call = Some(Rc::new(on_resize));
+                        }
                     }
 
                     callback_to_call
@@ -255,6 +271,56 @@ impl LayerShellSurface {
         Ok(layer_shell_surface)
     }
 
+    // === Builder API ===
+    //
+    // Each builder sends the request and commits, so it also works on a
+    // surface that is already mapped; the compositor answers with a new
+    // configure carrying the granted size.
+
+    /// Anchor the surface to the given screen edges and commit
+    ///
+    /// # Example
+    /// ```no_run
+    /// let panel = LayerShellSurface::new(Layer::Top, "panel", 0, 32)?
+    ///     .anchor(Anchor::Top | Anchor::Left | Anchor::Right)
+    ///     .exclusive_zone(32)
+    ///     .keyboard_interactivity(KeyboardInteractivity::OnDemand);
+    /// ```
+    pub fn anchor(self, anchor: Anchor) -> Self {
+        self.set_anchor(anchor);
+        self.commit();
+        self
+    }
+
+    /// Set margins from the anchor edges and commit
+    pub fn margin(self, top: i32, right: i32, bottom: i32, left: i32) -> Self {
+        self.set_margin(top, right, bottom, left);
+        self.commit();
+        self
+    }
+
+    /// Set the exclusive zone and commit
+    pub fn exclusive_zone(self, zone: i32) -> Self {
+        self.set_exclusive_zone(zone);
+        self.commit();
+        self
+    }
+
+    /// Set keyboard interactivity and commit
+    pub fn keyboard_interactivity(self, interactivity: KeyboardInteractivity) -> Self {
+        self.set_keyboard_interactivity(interactivity);
+        self.commit();
+        self
+    }
+
+    /// Commit pending layer surface state
+    ///
+    /// The `set_*` methods only queue their request; call this (or draw a
+    /// frame) to apply them.
+    pub fn commit(&self) {
+        self.inner.borrow().base_surface.wl_surface().commit();
+    }
+
     /// Configure anchoring for the layer surface
     ///
     /// # Example
@@ -321,6 +387,17 @@ impl LayerShellSurface {
         self.inner.borrow_mut().on_configure = Some(Rc::new(callback));
     }
 
+    /// Set a callback to be called when a later configure resizes the surface
+    ///
+    /// The Skia surface has already been resized to the granted
+    /// (width, height) when this runs, so the callback can redraw directly.
+    pub fn on_resize<F>(&self, callback: F)
+    where
+        F: Fn(i32, i32) + 'static,
+    {
+        self.inner.borrow_mut().on_resize = Some(Rc::new(callback));
+    }
+
     /// Handle layer surface closed event
     pub fn handle_closed(&mut self) {
         self.inner.borrow_mut().configured = false;
@@ -383,3 +460,15 @@ impl LayerShellSurface {
         self.base_surface().on_frame(callback);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_granted_size_keeps_current_for_zero_dimensions() {
+        assert_eq!(granted_size((1920, 32), (0, 32)), (1920, 32));
+        assert_eq!(granted_size((0, 0), (400, 300)), (400, 300));
+        assert_eq!(granted_size((800, 0), (400, 300)), (800, 300));
+    }
+}
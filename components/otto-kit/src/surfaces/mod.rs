@@ -8,6 +8,6 @@ pub mod toplevel;
 pub use common::{BaseWaylandSurface, SurfaceError};
 pub use dockitem::DockItem;
 pub use layer_shell::LayerShellSurface;
-pub use popup::PopupSurface;
+pub use popup::{PopupGeometry, PopupSurface};
 pub use subsurface::SubsurfaceSurface;
 pub use toplevel::ToplevelSurface;
@@ -2,7 +2,7 @@ use smithay_client_toolkit::{
     compositor::{CompositorState, SurfaceData},
     reexports::client::{protocol::wl_surface, QueueHandle},
     shell::xdg::{
-        popup::{Popup, PopupData},
+        popup::{Popup, PopupConfigure, PopupData},
         XdgPositioner, XdgShell,
     },
 };
@@ -15,6 +15,27 @@ use super::common::{
     otto_surface_style_manager_v1, otto_surface_style_v1, BaseWaylandSurface, SurfaceError,
 };
 
+/// Position and size the compositor gave a popup in its last `configure`,
+/// relative to the parent surface's window geometry
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PopupGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl From<&PopupConfigure> for PopupGeometry {
+    fn from(config: &PopupConfigure) -> Self {
+        Self {
+            x: config.position.0,
+            y: config.position.1,
+            width: config.width,
+            height: config.height,
+        }
+    }
+}
+
 /// Manages an XDG popup surface with Skia rendering
 ///
 /// This surface type represents a popup menu or tooltip that appears
@@ -446,6 +467,8 @@ impl PopupSurface {
         // Clean up sc_layer
         self.base_surface.surface_style.take();
 
+        crate::app_runner::AppContext::forget_popup_geometry(&self.base_surface.wl_surface.id());
+
         // Destroy Wayland objects
         if let Some(popup) = self.popup.take() {
             popup.xdg_popup().destroy();
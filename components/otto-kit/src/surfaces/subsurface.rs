@@ -1,3 +1,5 @@
+use std::cell::Cell;
+
 use smithay_client_toolkit::{
     compositor::{CompositorState, SurfaceData},
     reexports::client::{protocol::wl_surface, QueueHandle},
@@ -17,9 +19,18 @@ use super::common::{
 /// This surface type represents a child surface positioned relative to a parent.
 /// It's useful for elements like menubars, decorations, or overlays that need
 /// to be part of a window but managed separately.
+///
+/// Subsurfaces start desynchronized: every `draw` is shown right away.
+/// Call `set_sync()` to tie the subsurface to its parent; its commits are
+/// then held by the compositor and applied together with the parent's
+/// next commit, so draw synchronized subsurfaces before their parent.
 pub struct SubsurfaceSurface {
     base_surface: BaseWaylandSurface,
     subsurface: Option<wl_subsurface::WlSubsurface>,
+    parent: wl_surface::WlSurface,
+    sync: Cell<bool>,
+    /// A commit made in sync mode that the parent has not applied yet.
+    awaiting_parent: Cell<bool>,
 }
 
 impl SubsurfaceSurface {
@@ -128,9 +139,67 @@ impl SubsurfaceSurface {
         Ok(Self {
             base_surface: core,
             subsurface: Some(subsurface),
+            parent: parent_surface.clone(),
+            sync: Cell::new(false),
+            awaiting_parent: Cell::new(false),
         })
     }
 
+    /// Synchronize this subsurface with its parent.
+    ///
+    /// From now on its commits only become visible when the parent commits.
+    pub fn set_sync(&self) {
+        if let Some(ref sub) = self.subsurface {
+            sub.set_sync();
+        }
+        self.sync.set(true);
+    }
+
+    /// Let this subsurface update independently of its parent.
+    ///
+    /// A commit still held for the parent is applied immediately.
+    pub fn set_desync(&self) {
+        if let Some(ref sub) = self.subsurface {
+            sub.set_desync();
+        }
+        self.sync.set(false);
+        self.awaiting_parent.set(false);
+    }
+
+    /// Whether this subsurface is synchronized with its parent
+    pub fn is_sync(&self) -> bool {
+        self.sync.get()
+    }
+
+    /// Whether a synchronized commit is waiting for the parent to commit
+    pub fn is_awaiting_parent(&self) -> bool {
+        self.awaiting_parent.get()
+    }
+
+    /// Tell the subsurface that its parent has committed.
+    ///
+    /// Call this after committing the parent yourself; `present()` does it
+    /// for you.
+    pub fn parent_committed(&self) {
+        self.awaiting_parent.set(false);
+    }
+
+    /// Make the last synchronized draw visible by committing the parent.
+    ///
+    /// Only needed when nothing else is about to commit the parent; does
+    /// nothing for desynchronized subsurfaces.
+    pub fn present(&self) {
+        if self.awaiting_parent.get() {
+            self.parent.commit();
+            self.awaiting_parent.set(false);
+        }
+    }
+
+    /// The surface this subsurface is attached to
+    pub fn parent(&self) -> &wl_surface::WlSurface {
+        &self.parent
+    }
+
     /// Resize the subsurface
     pub fn resize(&mut self, width: i32, height: i32) {
         self.base_surface.resize(width, height);
@@ -156,8 +225,11 @@ impl SubsurfaceSurface {
     }
 
     /// Commit changes to the subsurface
+    ///
+    /// In sync mode the changes are applied with the parent's next commit.
     pub fn commit(&self) {
         self.base_surface.wl_surface().commit();
+        self.awaiting_parent.set(self.sync.get());
     }
 
     /// Get direct access to the sc_layer
@@ -206,11 +278,14 @@ impl SubsurfaceSurface {
     }
 
     /// Draw on the surface using a callback
+    ///
+    /// In sync mode the frame is shown with the parent's next commit.
     pub fn draw<F>(&self, draw_fn: F)
     where
         F: FnOnce(&skia_safe::Canvas),
     {
         self.base_surface.draw(draw_fn);
+        self.awaiting_parent.set(self.sync.get());
     }
 
     /// Register a callback to be called on every compositor frame
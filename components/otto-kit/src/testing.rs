@@ -29,6 +29,9 @@ use wayland_client::{
     Connection, Dispatch, EventQueue, QueueHandle,
 };
 use wayland_protocols::xdg::shell::client::{xdg_surface, xdg_toplevel, xdg_wm_base};
+use wayland_protocols_wlr::virtual_pointer::v1::client::{
+    zwlr_virtual_pointer_manager_v1, zwlr_virtual_pointer_v1,
+};
 
 /// Shared state for the test client's Wayland event dispatching.
 #[derive(Debug)]
@@ -37,6 +40,8 @@ pub struct TestClientState {
     pub wl_shm: Option<wl_shm::WlShm>,
     pub wl_seat: Option<wl_seat::WlSeat>,
     pub xdg_wm_base: Option<xdg_wm_base::XdgWmBase>,
+    pub virtual_pointer_manager:
+        Option<zwlr_virtual_pointer_manager_v1::ZwlrVirtualPointerManagerV1>,
     pub shm_formats: Vec<wl_shm::Format>,
 }
 
@@ -47,6 +52,7 @@ impl TestClientState {
             wl_shm: None,
             wl_seat: None,
             xdg_wm_base: None,
+            virtual_pointer_manager: None,
             shm_formats: Vec::new(),
         }
     }
@@ -115,6 +121,15 @@ impl TestClient {
             .create_surface(&self.qh, ())
     }
 
+    /// Create a virtual pointer on the compositor's default seat.
+    pub fn create_virtual_pointer(&self) -> zwlr_virtual_pointer_v1::ZwlrVirtualPointerV1 {
+        self.state
+            .virtual_pointer_manager
+            .as_ref()
+            .expect("zwlr_virtual_pointer_manager_v1 not bound")
+            .create_virtual_pointer(None, &self.qh, ())
+    }
+
     /// Create an XDG toplevel window and attach a minimal SHM buffer.
     ///
     /// Returns a shared reference to the toplevel state which tracks
@@ -252,6 +267,10 @@ impl Dispatch<wl_registry::WlRegistry, ()> for TestClientState {
                 "xdg_wm_base" => {
                     state.xdg_wm_base = Some(registry.bind(name, version.min(6), qh, ()));
                 }
+                "zwlr_virtual_pointer_manager_v1" => {
+                    state.virtual_pointer_manager =
+                        Some(registry.bind(name, version.min(2), qh, ()));
+                }
                 _ => {}
             }
         }
@@ -324,6 +343,32 @@ impl Dispatch<wl_seat::WlSeat, ()> for TestClientState {
     }
 }
 
+impl Dispatch<zwlr_virtual_pointer_manager_v1::ZwlrVirtualPointerManagerV1, ()>
+    for TestClientState
+{
+    fn event(
+        _state: &mut Self,
+        _proxy: &zwlr_virtual_pointer_manager_v1::ZwlrVirtualPointerManagerV1,
+        _event: zwlr_virtual_pointer_manager_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<zwlr_virtual_pointer_v1::ZwlrVirtualPointerV1, ()> for TestClientState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &zwlr_virtual_pointer_v1::ZwlrVirtualPointerV1,
+        _event: zwlr_virtual_pointer_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
 impl Dispatch<wl_surface::WlSurface, ()> for TestClientState {
     fn event(
         _state: &mut Self,
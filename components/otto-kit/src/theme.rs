@@ -24,6 +24,9 @@ impl ColorScheme {
 #[derive(Debug, Clone)]
 pub struct Theme {
     // Accent colors
+    /// Selections, focus rings, slider fills and primary buttons; the system
+    /// accent when one is set, `accent_blue` otherwise
+    pub accent: Color,
     pub accent_blue: Color,
     pub accent_gray: Color,
 
@@ -53,6 +56,7 @@ impl Theme {
     /// Light theme
     pub fn light() -> Self {
         Self {
+            accent: Color::from_argb(0xFF, 0x0A, 0x84, 0xFF),
             accent_blue: Color::from_argb(0xFF, 0x0A, 0x84, 0xFF),
             accent_gray: Color::from_argb(0xFF, 0x8E, 0x8E, 0x93),
 
@@ -78,6 +82,7 @@ impl Theme {
     /// Dark theme
     pub fn dark() -> Self {
         Self {
+            accent: Color::from_argb(0xFF, 0x0A, 0x84, 0xFF),
             accent_blue: Color::from_argb(0xFF, 0x0A, 0x84, 0xFF),
             accent_gray: Color::from_argb(0xFF, 0x8E, 0x8E, 0x93),
 
@@ -102,14 +107,26 @@ impl Theme {
         }
     }
 
-    /// Return the appropriate theme for the given color scheme.
+    /// Return the appropriate theme for the given color scheme, with the
+    /// system accent color when one is set.
     /// Falls back to light for `NoPreference`.
     pub fn for_scheme(scheme: ColorScheme) -> Self {
-        match scheme {
+        let theme = match scheme {
             ColorScheme::Dark => Self::dark(),
             _ => Self::light(),
+        };
+        match crate::color_scheme::current_accent_color() {
+            Some(accent) => theme.with_accent(accent),
+            None => theme,
         }
     }
+
+    /// Use `accent` for selections, focus rings and highlights
+    pub fn with_accent(mut self, accent: Color) -> Self {
+        self.accent = accent.with_a(0xFF);
+        self.material_selection_focused = accent.with_a(0xBF);
+        self
+    }
 }
 
 impl Default for Theme {
@@ -117,3 +134,21 @@ impl Default for Theme {
         Self::light()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_accent_recolors_selection() {
+        let accent = Color::from_argb(0xFF, 0xBF, 0x5A, 0xF2);
+        let theme = Theme::dark().with_accent(accent);
+        assert_eq!(theme.accent, accent);
+        assert_eq!(
+            theme.material_selection_focused,
+            Color::from_argb(0xBF, 0xBF, 0x5A, 0xF2)
+        );
+        // The named accents stay available
+        assert_eq!(theme.accent_blue, Theme::dark().accent_blue);
+    }
+}
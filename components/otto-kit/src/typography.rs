@@ -1,5 +1,11 @@
-use skia_safe::{self as skia, Font, FontMgr, FontStyle};
+use skia_safe::shaper::run_handler::{Buffer, RunInfo};
+use skia_safe::shaper::RunHandler;
+use skia_safe::{
+    self as skia, Canvas, Font, FontMgr, FontStyle, GlyphId, Paint, Point, Shaper, TextBlob,
+    TextBlobBuilder,
+};
 use std::cell::RefCell;
+use std::ops::Range;
 
 /// Cache key that doesn't rely on FontStyle being Hash/Eq
 #[derive(Hash, PartialEq, Eq)]
@@ -27,16 +33,36 @@ impl CacheKey {
 pub struct FontCache {
     font_mgr: FontMgr,
     cache: RefCell<std::collections::HashMap<CacheKey, Font>>,
+    /// HarfBuzz shaper falling back through the system fonts
+    shaper: Shaper,
 }
 
 impl FontCache {
     fn new() -> Self {
+        let font_mgr = FontMgr::new();
         Self {
-            font_mgr: FontMgr::new(),
+            shaper: Shaper::new(font_mgr.clone()),
+            font_mgr,
             cache: RefCell::new(std::collections::HashMap::new()),
         }
     }
 
+    /// Shape `text` with `font`, falling back to other system fonts for
+    /// characters it lacks (emoji, other scripts)
+    pub fn shape_text(&self, text: &str, font: &Font) -> ShapedText {
+        let right_to_left = starts_right_to_left(text);
+        let mut collector = GlyphCollector::default();
+        if !text.is_empty() {
+            self.shaper
+                .shape(text, font, !right_to_left, f32::MAX, &mut collector);
+        }
+        ShapedText {
+            runs: collector.runs,
+            width: collector.x,
+            right_to_left,
+        }
+    }
+
     /// Get or create a font with caching
     pub fn get_font(&self, family: &str, style: FontStyle, size: f32) -> Option<Font> {
         let key = CacheKey::from_style(family, style, size);
@@ -66,16 +92,17 @@ impl FontCache {
         // Try common fallback fonts
         for fallback in ["sans-serif", "DejaVu Sans", "Liberation Sans", "Arial"] {
             if let Some(font) = self.get_font(fallback, style, size) {
-                eprintln!(
+                tracing::debug!(
                     "Font '{}' not found, using fallback: '{}'",
-                    family, fallback
+                    family,
+                    fallback
                 );
                 return font;
             }
         }
 
         // Last resort: system default
-        eprintln!("Font '{}' and all fallbacks failed, using default", family);
+        tracing::warn!("Font '{}' and all fallbacks failed, using default", family);
         let typeface = self
             .font_mgr
             .legacy_make_typeface(None, style)
@@ -101,6 +128,167 @@ pub fn get_font_with_fallback(family: &str, style: FontStyle, size: f32) -> Font
     FONT_CACHE.with(|cache| cache.get_font_with_fallback(family, style, size))
 }
 
+/// Shape `text` using the thread-local font cache
+///
+/// Handles ligatures, complex scripts (Arabic, Devanagari, ...), mixed
+/// left-to-right and right-to-left text and emoji. The text stays on one
+/// line.
+pub fn shape_text(text: &str, font: &Font) -> ShapedText {
+    FONT_CACHE.with(|cache| cache.shape_text(text, font))
+}
+
+/// Whether the first character with a strong direction is right-to-left
+///
+/// Decides the base direction of a paragraph, like `dir="auto"` on the web.
+pub fn starts_right_to_left(text: &str) -> bool {
+    for c in text.chars() {
+        let code = c as u32;
+        let rtl = matches!(
+            code,
+            0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF
+        );
+        if rtl {
+            return true;
+        }
+        if c.is_alphabetic() {
+            return false;
+        }
+    }
+    false
+}
+
+/// Glyphs of one font and direction, as placed by the shaper
+#[derive(Clone)]
+pub struct ShapedRun {
+    /// The font the glyphs come from, after fallback
+    pub font: Font,
+    pub glyphs: Vec<GlyphId>,
+    /// Glyph origins on the baseline, relative to the start of the text
+    pub positions: Vec<Point>,
+    /// Byte offset in the text of the character each glyph draws
+    pub clusters: Vec<u32>,
+    /// The part of the text the run covers
+    pub utf8_range: Range<usize>,
+    pub right_to_left: bool,
+    /// Where the run starts and how far it advances
+    pub x: f32,
+    pub advance: f32,
+}
+
+/// A line of shaped text, ready to measure and draw
+#[derive(Clone, Default)]
+pub struct ShapedText {
+    /// Runs in visual order, left to right
+    pub runs: Vec<ShapedRun>,
+    pub width: f32,
+    /// Base direction of the line
+    pub right_to_left: bool,
+}
+
+impl ShapedText {
+    /// Build a text blob of all the runs
+    pub fn text_blob(&self) -> Option<TextBlob> {
+        let mut builder = TextBlobBuilder::new();
+        for run in self.runs.iter().filter(|run| !run.glyphs.is_empty()) {
+            let (glyphs, positions) = builder.alloc_run_pos(&run.font, run.glyphs.len(), None);
+            glyphs.copy_from_slice(&run.glyphs);
+            positions.copy_from_slice(&run.positions);
+        }
+        builder.make()
+    }
+
+    /// Draw the text with its baseline starting at `origin`
+    pub fn draw(&self, canvas: &Canvas, origin: impl Into<Point>, paint: &Paint) {
+        if let Some(blob) = self.text_blob() {
+            canvas.draw_text_blob(&blob, origin, paint);
+        }
+    }
+
+    /// The x of a caret placed before the character at byte `index`
+    ///
+    /// Follows the direction of the run the character is in, so carets
+    /// move right to left through Arabic or Hebrew.
+    pub fn caret_x(&self, index: usize) -> f32 {
+        // The glyph of the first character at or after `index`, and the
+        // glyph of the last character, with their leading/trailing edges
+        let mut next: Option<(u32, f32)> = None;
+        let mut last: Option<(u32, f32)> = None;
+        for run in &self.runs {
+            for (i, &cluster) in run.clusters.iter().enumerate() {
+                let left = run.positions[i].x;
+                let right = run
+                    .positions
+                    .get(i + 1)
+                    .map(|p| p.x)
+                    .unwrap_or(run.x + run.advance);
+                let (leading, trailing) = if run.right_to_left {
+                    (right, left)
+                } else {
+                    (left, right)
+                };
+                if cluster as usize >= index && next.is_none_or(|(c, _)| cluster < c) {
+                    next = Some((cluster, leading));
+                }
+                if last.is_none_or(|(c, _)| cluster >= c) {
+                    last = Some((cluster, trailing));
+                }
+            }
+        }
+        match next.or(last) {
+            Some((_, x)) => x,
+            None if self.right_to_left => self.width,
+            None => 0.0,
+        }
+    }
+}
+
+/// Collects the shaper's output into `ShapedRun`s
+#[derive(Default)]
+struct GlyphCollector {
+    runs: Vec<ShapedRun>,
+    current: Option<ShapedRun>,
+    x: f32,
+}
+
+impl RunHandler for GlyphCollector {
+    fn begin_line(&mut self) {}
+
+    fn run_info(&mut self, _info: &RunInfo) {}
+
+    fn commit_run_info(&mut self) {}
+
+    fn run_buffer(&mut self, info: &RunInfo) -> Buffer {
+        let count = info.glyph_count;
+        let point = Point::new(self.x, 0.0);
+        let run = self.current.insert(ShapedRun {
+            font: info.font.clone(),
+            glyphs: vec![0; count],
+            positions: vec![Point::default(); count],
+            clusters: vec![0; count],
+            utf8_range: info.utf8_range.clone(),
+            right_to_left: info.bidi_level % 2 == 1,
+            x: self.x,
+            advance: info.advance.x,
+        });
+        Buffer {
+            glyphs: &mut run.glyphs,
+            positions: &mut run.positions,
+            offsets: None,
+            clusters: Some(&mut run.clusters),
+            point,
+        }
+    }
+
+    fn commit_run_buffer(&mut self, info: &RunInfo) {
+        if let Some(run) = self.current.take() {
+            self.runs.push(run);
+        }
+        self.x += info.advance.x;
+    }
+
+    fn commit_line(&mut self) {}
+}
+
 /// Predefined text styles for a consistent design system
 #[derive(Debug, Clone, Copy)]
 pub struct TextStyle {
@@ -306,6 +494,74 @@ mod tests {
         assert_eq!(font1.typeface().unique_id(), font2.typeface().unique_id());
     }
 
+    fn boundaries(text: &str) -> Vec<usize> {
+        text.char_indices()
+            .map(|(i, _)| i)
+            .chain([text.len()])
+            .collect()
+    }
+
+    #[test]
+    fn base_direction_follows_first_strong_character() {
+        assert!(!starts_right_to_left("Hello"));
+        assert!(starts_right_to_left("مرحبا"));
+        assert!(starts_right_to_left("123 שלום"));
+        assert!(!starts_right_to_left("(ok) שלום"));
+        assert!(!starts_right_to_left(""));
+    }
+
+    #[test]
+    fn arabic_is_shaped_right_to_left() {
+        let font = get_font_with_fallback("sans-serif", FontStyle::normal(), 16.0);
+        let text = "مرحبا بالعالم";
+        let shaped = shape_text(text, &font);
+
+        assert!(shaped.right_to_left);
+        assert!(shaped.runs.iter().all(|run| run.right_to_left));
+        // The caret starts on the right and moves left as the text goes on
+        assert!(shaped.caret_x(0) > shaped.caret_x(text.len()));
+    }
+
+    #[test]
+    fn devanagari_clusters_land_on_characters() {
+        let font = get_font_with_fallback("sans-serif", FontStyle::normal(), 16.0);
+        let text = "नमस्ते दुनिया";
+        let shaped = shape_text(text, &font);
+
+        let starts = boundaries(text);
+        let clusters: Vec<u32> = shaped
+            .runs
+            .iter()
+            .flat_map(|r| r.clusters.clone())
+            .collect();
+        assert!(!clusters.is_empty());
+        assert!(clusters.iter().all(|c| starts.contains(&(*c as usize))));
+        assert!(shaped.width > 0.0);
+    }
+
+    #[test]
+    fn emoji_mixed_with_text_keeps_carets_in_order() {
+        let font = get_font_with_fallback("sans-serif", FontStyle::normal(), 16.0);
+        let text = "hi 👋 there";
+        let shaped = shape_text(text, &font);
+
+        assert!(!shaped.right_to_left);
+        let carets: Vec<f32> = boundaries(text)
+            .into_iter()
+            .map(|i| shaped.caret_x(i))
+            .collect();
+        assert!(carets.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(carets.last().copied(), Some(shaped.width));
+    }
+
+    #[test]
+    fn empty_text_has_no_runs() {
+        let font = get_font_with_fallback("sans-serif", FontStyle::normal(), 16.0);
+        let shaped = shape_text("", &font);
+        assert!(shaped.runs.is_empty());
+        assert_eq!(shaped.caret_x(0), 0.0);
+    }
+
     #[test]
     fn test_text_styles() {
         let _title = styles::TITLE_1.font();
@@ -0,0 +1,172 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+use otto_kit::{App, AppContext};
+use smithay_client_toolkit::seat::pointer::{PointerEvent, PointerEventKind, BTN_LEFT};
+use wayland_client::Proxy;
+
+use crate::dbus;
+use crate::notification::{CloseReason, NotificationId};
+use crate::state::SharedState;
+use crate::toast::{Toast, ToastEvent, TOAST_MARGIN};
+
+/// Shows notifications from `SharedState` as a stack of toasts in the
+/// top-right corner, newest on top
+pub struct NotificationsApp {
+    state: SharedState,
+    toasts: Vec<Toast>,
+    events_tx: Sender<ToastEvent>,
+    events_rx: Receiver<ToastEvent>,
+}
+
+impl NotificationsApp {
+    pub fn new(state: SharedState) -> Self {
+        let (events_tx, events_rx) = mpsc::channel();
+        Self {
+            state,
+            toasts: Vec::new(),
+            events_tx,
+            events_rx,
+        }
+    }
+
+    /// Bring the toasts in line with the notification state
+    fn sync_toasts(&mut self) {
+        let now = Instant::now();
+        let (expired, visible) = {
+            let mut state = self.state.lock().unwrap();
+            let expired = state.take_expired(now);
+            if !state.dirty {
+                return;
+            }
+            state.dirty = false;
+
+            let visible: Vec<_> = state
+                .show_visible(now)
+                .into_iter()
+                .filter_map(|id| state.get(id).cloned())
+                .collect();
+            (expired, visible)
+        };
+
+        for id in expired {
+            dbus::emit_notification_closed(id, CloseReason::Expired);
+        }
+
+        // Drop toasts that are gone or held back
+        self.toasts.retain(|toast| {
+            let keep = visible.iter().any(|n| n.id == toast.id);
+            if !keep {
+                toast.destroy();
+            }
+            keep
+        });
+
+        let mut toasts = Vec::with_capacity(visible.len());
+        let mut top = TOAST_MARGIN;
+        for notification in &visible {
+            let existing = self
+                .toasts
+                .iter()
+                .position(|toast| toast.id == notification.id)
+                .map(|index| self.toasts.remove(index));
+            let toast = match existing {
+                Some(toast) => {
+                    toast.update(notification);
+                    toast.set_top(top);
+                    toast
+                }
+                None => match Toast::new(notification, top, self.events_tx.clone()) {
+                    Ok(toast) => toast,
+                    Err(e) => {
+                        tracing::warn!(id = notification.id, "failed to create toast: {e:?}");
+                        continue;
+                    }
+                },
+            };
+            top += toast.height() + TOAST_MARGIN;
+            toasts.push(toast);
+        }
+        self.toasts = toasts;
+    }
+
+    fn handle_toast_events(&mut self) {
+        while let Ok(event) = self.events_rx.try_recv() {
+            match event {
+                ToastEvent::Action(id, key) => {
+                    dbus::emit_action_invoked(id, key);
+                    let resident = self
+                        .state
+                        .lock()
+                        .unwrap()
+                        .get(id)
+                        .is_some_and(|n| n.resident);
+                    if !resident {
+                        self.dismiss(id);
+                    }
+                }
+                ToastEvent::Clicked(id) => {
+                    let has_default = self
+                        .state
+                        .lock()
+                        .unwrap()
+                        .get(id)
+                        .is_some_and(|n| n.has_default_action);
+                    if has_default {
+                        dbus::emit_action_invoked(id, "default".into());
+                    }
+                    self.dismiss(id);
+                }
+            }
+        }
+    }
+
+    /// Close a notification on behalf of the user
+    fn dismiss(&self, id: NotificationId) {
+        if self.state.lock().unwrap().close(id).is_some() {
+            dbus::emit_notification_closed(id, CloseReason::Dismissed);
+        }
+    }
+}
+
+impl App for NotificationsApp {
+    fn on_app_ready(&mut self, _ctx: &AppContext) -> Result<(), Box<dyn std::error::Error>> {
+        // Toast surfaces are created as notifications arrive
+        Ok(())
+    }
+
+    fn on_pointer_event(&mut self, _ctx: &AppContext, events: &[PointerEvent]) {
+        for toast in &self.toasts {
+            let surface_id = toast.wl_surface().id();
+            let toast_events: Vec<PointerEvent> = events
+                .iter()
+                .filter(|event| event.surface.id() == surface_id)
+                .cloned()
+                .collect();
+            if toast_events.is_empty() {
+                continue;
+            }
+
+            toast.handle_pointer_events(&toast_events);
+
+            // Clicking anywhere but an action button dismisses the toast
+            let clicked = toast_events.iter().any(|event| {
+                matches!(event.kind, PointerEventKind::Press { button, .. } if button == BTN_LEFT)
+                    && !toast.is_on_button(event.position.0 as f32, event.position.1 as f32)
+            });
+            if clicked {
+                let _ = self.events_tx.send(ToastEvent::Clicked(toast.id));
+            }
+        }
+    }
+
+    fn on_update(&mut self, _ctx: &AppContext) {
+        self.handle_toast_events();
+        self.sync_toasts();
+    }
+
+    fn idle_timeout(&self) -> Option<Duration> {
+        let next = self.state.lock().unwrap().next_expiry()?;
+        Some(next.saturating_duration_since(Instant::now()))
+    }
+}
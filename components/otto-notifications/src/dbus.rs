@@ -0,0 +1,239 @@
+//! org.freedesktop.Notifications server.
+//!
+//! Implements the Desktop Notifications Specification (1.2) so that
+//! native apps (notify-send, Firefox, Thunderbird, etc.) can show toasts,
+//! plus a small `org.otto.Notifications1` interface for do-not-disturb.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use otto_kit::AppContext;
+use zbus::zvariant::Value;
+use zbus::{interface, Connection, SignalContext};
+
+use crate::notification::{
+    parse_actions, timeout_for, CloseReason, Notification, NotificationId, NotificationImage,
+    Urgency,
+};
+use crate::state::SharedState;
+
+pub const DBUS_NAME: &str = "org.freedesktop.Notifications";
+pub const DBUS_PATH: &str = "/org/freedesktop/Notifications";
+
+/// The connection that owns the org.freedesktop.Notifications name.
+/// Signals must come from it, since senders match on that name.
+static CONNECTION: OnceLock<Connection> = OnceLock::new();
+
+pub struct NotificationServer {
+    state: SharedState,
+}
+
+impl NotificationServer {
+    pub fn new(state: SharedState) -> Self {
+        Self { state }
+    }
+}
+
+#[interface(name = "org.freedesktop.Notifications")]
+impl NotificationServer {
+    async fn get_capabilities(&self) -> Vec<String> {
+        vec![
+            "actions".into(),
+            "body".into(),
+            // Markup is accepted and shown as plain text
+            "body-markup".into(),
+            "icon-static".into(),
+        ]
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: Vec<String>,
+        hints: HashMap<String, Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::fdo::Result<u32> {
+        let urgency = match hints.get("urgency") {
+            Some(Value::U8(u)) => Urgency::from(*u),
+            _ => Urgency::Normal,
+        };
+        let (actions, has_default_action) = parse_actions(&actions);
+
+        let notification = Notification {
+            id: 0,
+            app_name: app_name.to_string(),
+            summary: summary.to_string(),
+            body: body.to_string(),
+            actions,
+            has_default_action,
+            urgency,
+            timeout: timeout_for(expire_timeout, urgency),
+            image: parse_image(&hints, app_icon),
+            resident: matches!(hints.get("resident"), Some(Value::Bool(true))),
+            shown_at: None,
+        };
+
+        let id = self.state.lock().unwrap().notify(replaces_id, notification);
+        AppContext::request_wakeup();
+        tracing::info!(id, app_name, summary, "notification received");
+        Ok(id)
+    }
+
+    async fn close_notification(
+        &self,
+        id: u32,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<()> {
+        let closed = self.state.lock().unwrap().close(id).is_some();
+        if closed {
+            AppContext::request_wakeup();
+            Self::notification_closed(&ctxt, id, CloseReason::ClosedByCall as u32).await?;
+        }
+        Ok(())
+    }
+
+    async fn get_server_information(&self) -> (String, String, String, String) {
+        (
+            "otto-notifications".into(),
+            "otto".into(),
+            env!("CARGO_PKG_VERSION").into(),
+            "1.2".into(),
+        )
+    }
+
+    #[zbus(signal)]
+    async fn notification_closed(
+        ctxt: &SignalContext<'_>,
+        id: u32,
+        reason: u32,
+    ) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn action_invoked(
+        ctxt: &SignalContext<'_>,
+        id: u32,
+        action_key: &str,
+    ) -> zbus::Result<()>;
+}
+
+/// Otto specific controls, served next to the standard interface
+pub struct OttoNotifications {
+    state: SharedState,
+}
+
+impl OttoNotifications {
+    pub fn new(state: SharedState) -> Self {
+        Self { state }
+    }
+}
+
+#[interface(name = "org.otto.Notifications1")]
+impl OttoNotifications {
+    /// While set, only critical notifications are shown
+    #[zbus(property)]
+    async fn do_not_disturb(&self) -> bool {
+        self.state.lock().unwrap().do_not_disturb()
+    }
+
+    #[zbus(property)]
+    async fn set_do_not_disturb(&mut self, enabled: bool) {
+        self.state.lock().unwrap().set_do_not_disturb(enabled);
+        AppContext::request_wakeup();
+        tracing::info!(enabled, "do not disturb");
+    }
+}
+
+/// Image for a notification: the `image-data` hint, then `image-path`,
+/// then the `app_icon` argument
+fn parse_image(hints: &HashMap<String, Value>, app_icon: &str) -> Option<NotificationImage> {
+    // "image_data" and "icon_data" are the names used by older senders
+    ["image-data", "image_data", "icon_data"]
+        .iter()
+        .find_map(|key| hints.get(*key).and_then(parse_image_data))
+        .or_else(|| match hints.get("image-path") {
+            Some(Value::Str(path)) if !path.is_empty() => {
+                Some(NotificationImage::Named(path.to_string()))
+            }
+            _ => None,
+        })
+        .or_else(|| (!app_icon.is_empty()).then(|| NotificationImage::Named(app_icon.into())))
+}
+
+/// Decode the `(iiibiiay)` raw image structure
+fn parse_image_data(value: &Value) -> Option<NotificationImage> {
+    let Value::Structure(structure) = value else {
+        return None;
+    };
+    match structure.fields() {
+        [Value::I32(width), Value::I32(height), Value::I32(rowstride), Value::Bool(has_alpha), _, _, Value::Array(bytes)] =>
+        {
+            let data = bytes
+                .iter()
+                .filter_map(|byte| match byte {
+                    Value::U8(b) => Some(*b),
+                    _ => None,
+                })
+                .collect();
+            Some(NotificationImage::Pixels {
+                width: *width,
+                height: *height,
+                rowstride: *rowstride,
+                has_alpha: *has_alpha,
+                data,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Claim the bus name and serve both interfaces until the process exits
+pub async fn run(state: SharedState) -> zbus::Result<()> {
+    let connection = zbus::ConnectionBuilder::session()?
+        .name(DBUS_NAME)?
+        .serve_at(DBUS_PATH, NotificationServer::new(state.clone()))?
+        .serve_at(DBUS_PATH, OttoNotifications::new(state))?
+        .build()
+        .await?;
+
+    let _ = CONNECTION.set(connection);
+    tracing::info!("notification server running on {DBUS_NAME}");
+    std::future::pending::<()>().await;
+    Ok(())
+}
+
+/// Emit `NotificationClosed` from the UI thread
+pub fn emit_notification_closed(id: NotificationId, reason: CloseReason) {
+    emit(move |ctxt| async move {
+        NotificationServer::notification_closed(&ctxt, id, reason as u32).await
+    });
+}
+
+/// Emit `ActionInvoked` from the UI thread
+pub fn emit_action_invoked(id: NotificationId, action_key: String) {
+    emit(
+        move |ctxt| async move { NotificationServer::action_invoked(&ctxt, id, &action_key).await },
+    );
+}
+
+fn emit<F, Fut>(signal: F)
+where
+    F: FnOnce(SignalContext<'static>) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = zbus::Result<()>> + Send + 'static,
+{
+    let Some(connection) = CONNECTION.get() else {
+        return;
+    };
+    let Ok(ctxt) = SignalContext::new(connection, DBUS_PATH) else {
+        return;
+    };
+    tokio::spawn(async move {
+        if let Err(e) = signal(ctxt).await {
+            tracing::warn!("notification signal failed: {e}");
+        }
+    });
+}
@@ -0,0 +1,36 @@
+mod app;
+mod dbus;
+mod notification;
+mod state;
+mod toast;
+
+use std::sync::{Arc, Mutex};
+
+use app::NotificationsApp;
+use otto_kit::AppRunner;
+use state::{NotificationState, SharedState};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .init();
+
+    let state: SharedState = Arc::new(Mutex::new(NotificationState::new()));
+
+    let dbus_state = state.clone();
+    tokio::spawn(async move {
+        // Only one process can own the name; another daemon may be running
+        if let Err(e) = dbus::run(dbus_state).await {
+            tracing::error!("notification server stopped: {e}");
+        }
+    });
+
+    let app = NotificationsApp::new(state);
+    AppRunner::new(app).run()?;
+
+    Ok(())
+}
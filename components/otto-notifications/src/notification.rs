@@ -0,0 +1,174 @@
+use std::time::{Duration, Instant};
+
+pub type NotificationId = u32;
+
+/// Timeout used when the sender passes `expire_timeout = -1`
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Low urgency notifications leave sooner
+const LOW_URGENCY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// `urgency` hint of the Desktop Notifications Specification
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Urgency {
+    Low,
+    Normal,
+    Critical,
+}
+
+impl From<u8> for Urgency {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Urgency::Low,
+            2 => Urgency::Critical,
+            _ => Urgency::Normal,
+        }
+    }
+}
+
+/// Reason sent with the `NotificationClosed` signal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloseReason {
+    Expired = 1,
+    Dismissed = 2,
+    ClosedByCall = 3,
+}
+
+/// An action button; `key` goes back to the sender in `ActionInvoked`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotificationAction {
+    pub key: String,
+    pub label: String,
+}
+
+/// Picture shown next to the text
+#[derive(Clone, Debug)]
+pub enum NotificationImage {
+    /// Icon theme name, file path or `file://` URI
+    Named(String),
+    /// Raw pixels from the `image-data` hint, RGB(A) with `rowstride` bytes per row
+    Pixels {
+        width: i32,
+        height: i32,
+        rowstride: i32,
+        has_alpha: bool,
+        data: Vec<u8>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct Notification {
+    pub id: NotificationId,
+    pub app_name: String,
+    pub summary: String,
+    pub body: String,
+    pub actions: Vec<NotificationAction>,
+    /// The sender registered a `default` action, invoked by clicking the toast
+    pub has_default_action: bool,
+    pub urgency: Urgency,
+    /// `None` keeps the toast until it is dismissed
+    pub timeout: Option<Duration>,
+    pub image: Option<NotificationImage>,
+    /// Keep the toast after one of its actions is invoked
+    pub resident: bool,
+    /// When the toast was first put on screen; the timeout runs from here
+    pub shown_at: Option<Instant>,
+}
+
+impl Notification {
+    /// When the toast should go away, once it is on screen
+    pub fn expires_at(&self) -> Option<Instant> {
+        self.shown_at.zip(self.timeout).map(|(at, t)| at + t)
+    }
+}
+
+/// Split the flat `[key, label, key, label, ...]` action list. The
+/// `default` action gets no button; it is reported separately.
+pub fn parse_actions(actions: &[String]) -> (Vec<NotificationAction>, bool) {
+    let mut parsed = Vec::new();
+    let mut has_default = false;
+    for pair in actions.chunks_exact(2) {
+        if pair[0] == "default" {
+            has_default = true;
+        } else {
+            parsed.push(NotificationAction {
+                key: pair[0].clone(),
+                label: pair[1].clone(),
+            });
+        }
+    }
+    (parsed, has_default)
+}
+
+/// Timeout for a `Notify` call: `-1` picks the server default, `0` never
+/// expires. Critical notifications stay until dismissed regardless.
+pub fn timeout_for(expire_timeout: i32, urgency: Urgency) -> Option<Duration> {
+    match (urgency, expire_timeout) {
+        (Urgency::Critical, _) | (_, 0) => None,
+        (Urgency::Low, t) if t < 0 => Some(LOW_URGENCY_TIMEOUT),
+        (_, t) if t < 0 => Some(DEFAULT_TIMEOUT),
+        (_, t) => Some(Duration::from_millis(t as u64)),
+    }
+}
+
+/// Body text without markup: tags are dropped and the basic entities decoded
+pub fn strip_markup(body: &str) -> String {
+    let mut text = String::with_capacity(body.len());
+    let mut in_tag = false;
+    for c in body.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_actions_separates_default() {
+        let (actions, has_default) =
+            parse_actions(&strings(&["default", "Open", "reply", "Reply", "dangling"]));
+
+        assert!(has_default);
+        assert_eq!(
+            actions,
+            vec![NotificationAction {
+                key: "reply".into(),
+                label: "Reply".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_timeout_for() {
+        assert_eq!(timeout_for(-1, Urgency::Normal), Some(DEFAULT_TIMEOUT));
+        assert_eq!(timeout_for(-1, Urgency::Low), Some(LOW_URGENCY_TIMEOUT));
+        assert_eq!(
+            timeout_for(1500, Urgency::Low),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(timeout_for(0, Urgency::Normal), None);
+        assert_eq!(timeout_for(1500, Urgency::Critical), None);
+    }
+
+    #[test]
+    fn test_strip_markup() {
+        assert_eq!(
+            strip_markup("<b>Build</b> done &amp; <a href=\"x\">logs</a> &lt;3"),
+            "Build done & logs <3"
+        );
+    }
+}
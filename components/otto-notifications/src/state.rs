@@ -0,0 +1,207 @@
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::notification::{Notification, NotificationId, Urgency};
+
+pub type SharedState = Arc<Mutex<NotificationState>>;
+
+/// Most toasts on screen at once; the rest wait their turn
+pub const MAX_VISIBLE: usize = 4;
+
+/// Notifications received over D-Bus, shared with the UI thread
+pub struct NotificationState {
+    next_id: NotificationId,
+    /// Oldest first
+    notifications: Vec<Notification>,
+    do_not_disturb: bool,
+    /// Set on every change; the UI clears it after syncing its toasts
+    pub dirty: bool,
+}
+
+impl NotificationState {
+    pub fn new() -> Self {
+        Self {
+            next_id: 1,
+            notifications: Vec::new(),
+            do_not_disturb: false,
+            dirty: false,
+        }
+    }
+
+    /// Add `notification`, or update the one with `replaces_id` in place
+    /// when it is still around. Returns the notification id.
+    pub fn notify(
+        &mut self,
+        replaces_id: NotificationId,
+        mut notification: Notification,
+    ) -> NotificationId {
+        self.dirty = true;
+
+        if let Some(existing) = self
+            .notifications
+            .iter_mut()
+            .find(|n| replaces_id != 0 && n.id == replaces_id)
+        {
+            notification.id = replaces_id;
+            // A replacement restarts the timeout if the toast is showing
+            notification.shown_at = existing.shown_at.map(|_| Instant::now());
+            *existing = notification;
+            return replaces_id;
+        }
+
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        notification.id = id;
+        notification.shown_at = None;
+        self.notifications.push(notification);
+        id
+    }
+
+    /// Remove a notification, returning it if it existed
+    pub fn close(&mut self, id: NotificationId) -> Option<Notification> {
+        let index = self.notifications.iter().position(|n| n.id == id)?;
+        self.dirty = true;
+        Some(self.notifications.remove(index))
+    }
+
+    pub fn get(&self, id: NotificationId) -> Option<&Notification> {
+        self.notifications.iter().find(|n| n.id == id)
+    }
+
+    pub fn do_not_disturb(&self) -> bool {
+        self.do_not_disturb
+    }
+
+    /// In do-not-disturb mode only critical notifications are shown; the
+    /// others are kept and appear once it is turned off
+    pub fn set_do_not_disturb(&mut self, enabled: bool) {
+        if self.do_not_disturb != enabled {
+            self.do_not_disturb = enabled;
+            self.dirty = true;
+        }
+    }
+
+    /// Ids of the toasts to show, newest first. Starts the timeout of
+    /// those appearing for the first time.
+    pub fn show_visible(&mut self, now: Instant) -> Vec<NotificationId> {
+        let do_not_disturb = self.do_not_disturb;
+        let mut visible = Vec::new();
+        for notification in self
+            .notifications
+            .iter_mut()
+            .rev()
+            .filter(|n| !do_not_disturb || n.urgency == Urgency::Critical)
+            .take(MAX_VISIBLE)
+        {
+            notification.shown_at.get_or_insert(now);
+            visible.push(notification.id);
+        }
+        visible
+    }
+
+    /// Remove the notifications whose timeout has passed
+    pub fn take_expired(&mut self, now: Instant) -> Vec<NotificationId> {
+        let expired: Vec<NotificationId> = self
+            .notifications
+            .iter()
+            .filter(|n| n.expires_at().is_some_and(|at| at <= now))
+            .map(|n| n.id)
+            .collect();
+        if !expired.is_empty() {
+            self.notifications.retain(|n| !expired.contains(&n.id));
+            self.dirty = true;
+        }
+        expired
+    }
+
+    /// Earliest pending expiry, to know how long the UI may sleep
+    pub fn next_expiry(&self) -> Option<Instant> {
+        self.notifications
+            .iter()
+            .filter_map(Notification::expires_at)
+            .min()
+    }
+}
+
+impl Default for NotificationState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn notification(summary: &str, urgency: Urgency) -> Notification {
+        Notification {
+            id: 0,
+            app_name: "test".into(),
+            summary: summary.into(),
+            body: String::new(),
+            actions: Vec::new(),
+            has_default_action: false,
+            urgency,
+            timeout: Some(Duration::from_secs(5)),
+            image: None,
+            resident: false,
+            shown_at: None,
+        }
+    }
+
+    #[test]
+    fn test_notify_replaces_by_id() {
+        let mut state = NotificationState::new();
+        let first = state.notify(0, notification("one", Urgency::Normal));
+        let second = state.notify(0, notification("two", Urgency::Normal));
+        assert_ne!(first, second);
+
+        assert_eq!(
+            state.notify(first, notification("one'", Urgency::Normal)),
+            first
+        );
+        assert_eq!(state.get(first).unwrap().summary, "one'");
+        // An unknown id gets a fresh one
+        assert_ne!(state.notify(99, notification("three", Urgency::Normal)), 99);
+    }
+
+    #[test]
+    fn test_show_visible_newest_first_and_capped() {
+        let mut state = NotificationState::new();
+        let ids: Vec<_> = (0..MAX_VISIBLE + 1)
+            .map(|i| state.notify(0, notification(&i.to_string(), Urgency::Normal)))
+            .collect();
+
+        let visible = state.show_visible(Instant::now());
+        assert_eq!(visible.len(), MAX_VISIBLE);
+        assert_eq!(visible[0], *ids.last().unwrap());
+        assert!(state.get(ids[0]).unwrap().shown_at.is_none());
+    }
+
+    #[test]
+    fn test_do_not_disturb_holds_back_non_critical() {
+        let mut state = NotificationState::new();
+        state.set_do_not_disturb(true);
+        let normal = state.notify(0, notification("normal", Urgency::Normal));
+        let critical = state.notify(0, notification("critical", Urgency::Critical));
+
+        assert_eq!(state.show_visible(Instant::now()), vec![critical]);
+
+        state.set_do_not_disturb(false);
+        assert_eq!(state.show_visible(Instant::now()), vec![critical, normal]);
+    }
+
+    #[test]
+    fn test_timeout_runs_from_first_shown() {
+        let mut state = NotificationState::new();
+        let id = state.notify(0, notification("one", Urgency::Normal));
+        let now = Instant::now();
+        assert!(state.take_expired(now + Duration::from_secs(60)).is_empty());
+
+        state.show_visible(now);
+        assert_eq!(state.next_expiry(), Some(now + Duration::from_secs(5)));
+        assert_eq!(state.take_expired(now + Duration::from_secs(5)), vec![id]);
+        assert!(state.get(id).is_none());
+    }
+}
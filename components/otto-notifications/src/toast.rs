@@ -0,0 +1,444 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::mpsc::Sender;
+
+use otto_kit::components::button::Button;
+use otto_kit::protocols::otto_surface_style_v1::{BlendMode, ClipMode};
+use otto_kit::surfaces::{LayerShellSurface, SurfaceError};
+use otto_kit::theme::Theme;
+use otto_kit::typography::styles;
+use otto_kit::{AppContext, Renderable};
+use skia_safe::{
+    AlphaType, Canvas, Color, ColorType, Data, Font, Image, ImageInfo, Paint, Point, Rect,
+};
+use smithay_client_toolkit::seat::pointer::PointerEvent;
+use wayland_client::protocol::wl_surface::WlSurface;
+use wayland_protocols_wlr::layer_shell::v1::client::{
+    zwlr_layer_shell_v1::Layer,
+    zwlr_layer_surface_v1::{Anchor, KeyboardInteractivity},
+};
+
+use crate::notification::{strip_markup, Notification, NotificationId, NotificationImage, Urgency};
+
+const NAMESPACE: &str = "otto-notification";
+
+pub const TOAST_WIDTH: f32 = 360.0;
+/// Distance from the top-right corner of the output, and between toasts
+pub const TOAST_MARGIN: f32 = 10.0;
+
+const PADDING: f32 = 14.0;
+const ICON_SIZE: f32 = 40.0;
+/// Largest width or height accepted for an `image-data` hint
+const MAX_IMAGE_SIDE: i32 = 4096;
+const ICON_GAP: f32 = 12.0;
+const CORNER_RADIUS: f32 = 14.0;
+const MAX_BODY_LINES: usize = 4;
+const BUTTON_HEIGHT: f32 = 28.0;
+const BUTTON_SPACING: f32 = 8.0;
+/// Marker along the left edge of critical toasts
+const CRITICAL_COLOR: Color = Color::from_rgb(239, 68, 68);
+
+/// Input from a toast, handled by the app after the pointer frame
+#[derive(Debug)]
+pub enum ToastEvent {
+    /// An action button was clicked
+    Action(NotificationId, String),
+    /// The toast itself was clicked
+    Clicked(NotificationId),
+}
+
+/// Greedy word wrap of `text` into at most `max_lines` lines no wider than
+/// `max_width`; an overflowing last line ends with an ellipsis
+pub fn wrap_lines(
+    text: &str,
+    max_width: f32,
+    max_lines: usize,
+    measure: impl Fn(&str) -> f32,
+) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{current} {word}")
+        };
+        if current.is_empty() || measure(&candidate) <= max_width {
+            current = candidate;
+            continue;
+        }
+
+        lines.push(std::mem::replace(&mut current, word.to_string()));
+        if lines.len() == max_lines {
+            // No room for the rest: mark the last line as cut
+            let mut last = lines.pop().unwrap_or_default();
+            while !last.is_empty() && measure(&format!("{last}…")) > max_width {
+                last.pop();
+            }
+            lines.push(format!("{}…", last.trim_end()));
+            return lines;
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// What a toast draws, laid out for `TOAST_WIDTH`
+struct ToastContent {
+    app_name: String,
+    summary: String,
+    body_lines: Vec<String>,
+    urgency: Urgency,
+    image: Option<Image>,
+    height: f32,
+}
+
+impl ToastContent {
+    fn new(notification: &Notification) -> Self {
+        let text_width = TOAST_WIDTH - PADDING * 2.0 - ICON_SIZE - ICON_GAP;
+        let body_font = styles::SUBHEADLINE.font();
+        let body_lines = wrap_lines(
+            &strip_markup(&notification.body),
+            text_width,
+            MAX_BODY_LINES,
+            |s| body_font.measure_str(s, None).0,
+        );
+
+        let text_height = styles::CAPTION_1.font().size() * 1.3
+            + styles::HEADLINE.font().size() * 1.3
+            + body_lines.len() as f32 * body_font.size() * 1.3;
+        let mut height = PADDING * 2.0 + text_height.max(ICON_SIZE);
+        if !notification.actions.is_empty() {
+            height += BUTTON_SPACING + BUTTON_HEIGHT;
+        }
+
+        Self {
+            app_name: notification.app_name.clone(),
+            summary: notification.summary.clone(),
+            body_lines,
+            urgency: notification.urgency,
+            image: notification.image.as_ref().and_then(load_image),
+            height: height.ceil(),
+        }
+    }
+
+    fn draw(&self, canvas: &Canvas, buttons: &[Button], theme: &Theme) {
+        canvas.clear(Color::TRANSPARENT);
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+
+        if self.urgency == Urgency::Critical {
+            paint.set_color(CRITICAL_COLOR);
+            canvas.draw_rect(Rect::from_xywh(0.0, 0.0, 4.0, self.height), &paint);
+        }
+
+        if let Some(image) = &self.image {
+            let dst = Rect::from_xywh(PADDING, PADDING, ICON_SIZE, ICON_SIZE);
+            canvas.draw_image_rect(image, None, dst, &paint);
+        }
+
+        let x = PADDING + ICON_SIZE + ICON_GAP;
+        let mut y = PADDING;
+        let mut draw_line = |text: &str, font: Font, color: Color| {
+            paint.set_color(color);
+            let size = font.size();
+            canvas.draw_str(text, Point::new(x, y + size), &font, &paint);
+            y += size * 1.3;
+        };
+        draw_line(
+            &self.app_name,
+            styles::CAPTION_1.font(),
+            theme.text_secondary,
+        );
+        draw_line(&self.summary, styles::HEADLINE.font(), theme.text_primary);
+        for line in &self.body_lines {
+            draw_line(line, styles::SUBHEADLINE.font(), theme.text_primary);
+        }
+
+        for button in buttons {
+            button.render(canvas);
+        }
+    }
+}
+
+/// Action buttons, right aligned along the bottom edge
+fn build_buttons(
+    notification: &Notification,
+    height: f32,
+    events: &Sender<ToastEvent>,
+) -> Vec<Button> {
+    let y = height - PADDING - BUTTON_HEIGHT;
+    let mut right = TOAST_WIDTH - PADDING;
+    let mut buttons: Vec<Button> = notification
+        .actions
+        .iter()
+        .rev()
+        .map(|action| {
+            let events = events.clone();
+            let (id, key) = (notification.id, action.key.clone());
+            let mut button = Button::new(action.label.clone())
+                .secondary()
+                .with_text_style(styles::SUBHEADLINE)
+                .with_padding(12.0, 4.0)
+                .on_click(move || {
+                    let _ = events.send(ToastEvent::Action(id, key.clone()));
+                });
+            right -= button.width();
+            button = button.at(right, y).with_size(button.width(), BUTTON_HEIGHT);
+            right -= BUTTON_SPACING;
+            button
+        })
+        .collect();
+    buttons.reverse();
+    buttons
+}
+
+/// Decode a notification image into a Skia image of `ICON_SIZE`
+fn load_image(image: &NotificationImage) -> Option<Image> {
+    let scale = AppContext::scale_factor().max(1);
+    let size = ICON_SIZE as i32 * scale;
+    match image {
+        NotificationImage::Named(name) => {
+            let path = name.strip_prefix("file://").unwrap_or(name);
+            if path.starts_with('/') {
+                otto_kit::icons::image_from_path(path, (size, size))
+            } else {
+                otto_kit::icons::named_icon_sized(path, size)
+            }
+        }
+        NotificationImage::Pixels {
+            width,
+            height,
+            rowstride,
+            has_alpha,
+            data,
+        } => {
+            let rgba = unpack_pixels(*width, *height, *rowstride, *has_alpha, data)?;
+            let info = ImageInfo::new(
+                (*width, *height),
+                ColorType::RGBA8888,
+                AlphaType::Unpremul,
+                None,
+            );
+            skia_safe::images::raster_from_data(&info, Data::new_copy(&rgba), *width as usize * 4)
+        }
+    }
+}
+
+/// Expand `image-data` rows to tightly packed RGBA, as Skia has no packed
+/// 24-bit format. The hint comes from any client, so sizes that are negative,
+/// too large or not covered by `data` are rejected.
+fn unpack_pixels(
+    width: i32,
+    height: i32,
+    rowstride: i32,
+    has_alpha: bool,
+    data: &[u8],
+) -> Option<Vec<u8>> {
+    if !(1..=MAX_IMAGE_SIDE).contains(&width) || !(1..=MAX_IMAGE_SIDE).contains(&height) {
+        return None;
+    }
+    let (width, height) = (width as usize, height as usize);
+    let rowstride = usize::try_from(rowstride).ok()?;
+    let channels = if has_alpha { 4 } else { 3 };
+    let row_len = width.checked_mul(channels)?;
+    let needed = rowstride.checked_mul(height - 1)?.checked_add(row_len)?;
+    if rowstride < row_len || data.len() < needed {
+        return None;
+    }
+
+    let mut rgba = Vec::with_capacity(width.checked_mul(height)?.checked_mul(4)?);
+    for y in 0..height {
+        let row = &data[y * rowstride..y * rowstride + row_len];
+        for pixel in row.chunks_exact(channels) {
+            rgba.extend_from_slice(&pixel[..3]);
+            rgba.push(if has_alpha { pixel[3] } else { 255 });
+        }
+    }
+    Some(rgba)
+}
+
+/// One notification on screen: a layer surface anchored to the top-right
+/// corner, pushed down by the toasts above it
+pub struct Toast {
+    pub id: NotificationId,
+    surface: LayerShellSurface,
+    content: Rc<RefCell<ToastContent>>,
+    buttons: Rc<RefCell<Vec<Button>>>,
+    theme: Rc<Theme>,
+    events: Sender<ToastEvent>,
+}
+
+impl Toast {
+    pub fn new(
+        notification: &Notification,
+        top: f32,
+        events: Sender<ToastEvent>,
+    ) -> Result<Self, SurfaceError> {
+        let content = ToastContent::new(notification);
+        let buttons = build_buttons(notification, content.height, &events);
+
+        let surface = LayerShellSurface::new(
+            Layer::Overlay,
+            NAMESPACE,
+            TOAST_WIDTH as u32,
+            content.height as u32,
+        )?
+        .anchor(Anchor::Top | Anchor::Right)
+        .margin(top as i32, TOAST_MARGIN as i32, 0, 0)
+        .keyboard_interactivity(KeyboardInteractivity::None);
+
+        let theme = AppContext::current_theme();
+        if let Some(style) = surface.base_surface().surface_style() {
+            let bg = theme.material_medium;
+            style.set_background_color(
+                bg.r() as f64 / 255.0,
+                bg.g() as f64 / 255.0,
+                bg.b() as f64 / 255.0,
+                bg.a() as f64 / 255.0,
+            );
+            style.set_corner_radius(CORNER_RADIUS as f64);
+            style.set_masks_to_bounds(ClipMode::Enabled);
+            style.set_shadow(0.2, 2.0, 0.0, 8.0, 0.0, 0.0, 0.0);
+            style.set_blend_mode(BlendMode::BackgroundBlur);
+        }
+
+        let toast = Self {
+            id: notification.id,
+            surface,
+            content: Rc::new(RefCell::new(content)),
+            buttons: Rc::new(RefCell::new(buttons)),
+            theme: Rc::new(theme),
+            events,
+        };
+
+        let (surface, content, buttons, theme) = (
+            toast.surface.clone(),
+            toast.content.clone(),
+            toast.buttons.clone(),
+            toast.theme.clone(),
+        );
+        let draw = Rc::new(move || {
+            surface.draw(|canvas| content.borrow().draw(canvas, &buttons.borrow(), &theme));
+        });
+        let on_configure = draw.clone();
+        toast.surface.on_configure(move || on_configure());
+        // A replaced notification of another height is drawn once the
+        // compositor grants the new size
+        toast.surface.on_resize(move |_, _| draw());
+
+        Ok(toast)
+    }
+
+    /// Show new content after the notification was replaced
+    pub fn update(&self, notification: &Notification) {
+        let content = ToastContent::new(notification);
+        let resized = content.height != self.content.borrow().height;
+        *self.buttons.borrow_mut() = build_buttons(notification, content.height, &self.events);
+        *self.content.borrow_mut() = content;
+
+        if resized {
+            // Redrawn from `on_resize` at the new size
+            self.surface
+                .set_size(TOAST_WIDTH as u32, self.height() as u32);
+            self.surface.commit();
+        } else {
+            self.redraw();
+        }
+    }
+
+    pub fn height(&self) -> f32 {
+        self.content.borrow().height
+    }
+
+    /// Move the toast to `top` pixels below the top edge
+    pub fn set_top(&self, top: f32) {
+        self.surface
+            .set_margin(top as i32, TOAST_MARGIN as i32, 0, 0);
+        self.surface.commit();
+    }
+
+    pub fn wl_surface(&self) -> WlSurface {
+        self.surface.wl_surface()
+    }
+
+    pub fn redraw(&self) {
+        self.surface.draw(|canvas| {
+            self.content
+                .borrow()
+                .draw(canvas, &self.buttons.borrow(), &self.theme)
+        });
+    }
+
+    /// Whether (x, y) is on one of the action buttons
+    pub fn is_on_button(&self, x: f32, y: f32) -> bool {
+        self.buttons.borrow().iter().any(|b| b.contains(x, y))
+    }
+
+    /// Feed pointer events on this toast to its buttons
+    pub fn handle_pointer_events(&self, events: &[PointerEvent]) {
+        let mut redraw = false;
+        for button in self.buttons.borrow_mut().iter_mut() {
+            redraw |= button.handle_pointer_events(events);
+        }
+        if redraw {
+            self.redraw();
+        }
+    }
+
+    pub fn destroy(&self) {
+        self.surface.destroy();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One unit per character
+    fn measure(s: &str) -> f32 {
+        s.chars().count() as f32
+    }
+
+    #[test]
+    fn test_wrap_lines_breaks_on_words() {
+        assert_eq!(
+            wrap_lines("the quick brown fox", 10.0, 4, measure),
+            vec!["the quick", "brown fox"]
+        );
+    }
+
+    #[test]
+    fn test_wrap_lines_ellipsizes_overflow() {
+        assert_eq!(
+            wrap_lines("aaa bbb ccc ddd", 7.0, 1, measure),
+            vec!["aaa bb…"]
+        );
+        assert!(wrap_lines("", 10.0, 4, measure).is_empty());
+    }
+
+    #[test]
+    fn test_unpack_pixels_expands_rgb_rows() {
+        // 2x2 RGB with one byte of padding per row
+        let data = [1, 2, 3, 4, 5, 6, 0, 7, 8, 9, 10, 11, 12];
+        assert_eq!(
+            unpack_pixels(2, 2, 7, false, &data).unwrap(),
+            [1, 2, 3, 255, 4, 5, 6, 255, 7, 8, 9, 255, 10, 11, 12, 255]
+        );
+    }
+
+    #[test]
+    fn test_unpack_pixels_rejects_bad_sizes() {
+        let data = [0u8; 64];
+        assert!(unpack_pixels(-1, 2, 8, true, &data).is_none());
+        assert!(unpack_pixels(2, 2, -8, true, &data).is_none());
+        assert!(unpack_pixels(40000, 40000, 160000, true, &data).is_none());
+        // Rows shorter than the width, or data shorter than the rows
+        assert!(unpack_pixels(4, 2, 8, true, &data).is_none());
+        assert!(unpack_pixels(4, 5, 16, true, &data).is_none());
+        assert!(unpack_pixels(4, 4, 16, true, &data).is_some());
+    }
+}
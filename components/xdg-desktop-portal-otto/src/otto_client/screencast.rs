@@ -11,6 +11,7 @@ use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
 use zbus::Result;
 
 use crate::otto_client::OttoClient;
+use crate::portal::SelectedSource;
 
 /// D-Bus proxy for `org.otto.ScreenCast` service.
 #[zbus::proxy(
@@ -25,6 +26,12 @@ trait ScreenCast {
 
     /// Lists available output connectors.
     async fn list_outputs(&self) -> Result<Vec<String>>;
+
+    /// Runs the compositor's source picker; empty when cancelled.
+    async fn pick_source(
+        &self,
+        properties: HashMap<&str, Value<'_>>,
+    ) -> Result<HashMap<String, OwnedValue>>;
 }
 
 /// D-Bus proxy for `org.otto.ScreenCast.Session`.
@@ -40,9 +47,19 @@ trait ScreenCastSession {
         properties: HashMap<&str, Value<'_>>,
     ) -> Result<OwnedObjectPath>;
 
-    /// Starts recording a window (not yet implemented in compositor).
+    /// Starts recording a single window.
     async fn record_window(&self, properties: HashMap<&str, Value<'_>>) -> Result<OwnedObjectPath>;
 
+    /// Starts recording a rectangle in global logical coordinates.
+    async fn record_region(
+        &self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        properties: HashMap<&str, Value<'_>>,
+    ) -> Result<OwnedObjectPath>;
+
     /// Starts all streams in the session.
     async fn start(&self) -> Result<()>;
 
@@ -67,6 +84,10 @@ trait ScreenCastStream {
 
     /// Returns static stream metadata (mapping id, geometry, etc.).
     async fn metadata(&self) -> Result<HashMap<String, OwnedValue>>;
+
+    /// The compositor ended the stream, e.g. because its window closed.
+    #[zbus(signal)]
+    fn closed(&self) -> Result<()>;
 }
 
 impl OttoClient {
@@ -97,6 +118,21 @@ impl OttoClient {
         Ok(outputs)
     }
 
+    /// Lets the user pick a source of the allowed portal `types`.
+    ///
+    /// Returns `None` when the user cancelled.
+    pub async fn pick_source(&self, types: u32) -> Result<Option<SelectedSource>> {
+        let proxy = ScreenCastProxy::builder(&self.connection).build().await?;
+
+        let mut properties: HashMap<&str, Value<'_>> = HashMap::new();
+        properties.insert("types", Value::U32(types));
+
+        debug!(types, "Running source picker");
+        let picked = proxy.pick_source(properties).await?;
+        debug!(?picked, "Source picker finished");
+        Ok(parse_picked_source(&picked))
+    }
+
     /// Starts recording a monitor identified by connector name.
     pub async fn record_monitor(
         &self,
@@ -118,6 +154,49 @@ impl OttoClient {
         Ok(stream_path)
     }
 
+    /// Starts recording a window identified by its compositor handle.
+    pub async fn record_window(
+        &self,
+        session_path: &OwnedObjectPath,
+        window_id: &str,
+        cursor_mode: u32,
+    ) -> Result<OwnedObjectPath> {
+        let proxy = ScreenCastSessionProxy::builder(&self.connection)
+            .path(session_path)?
+            .build()
+            .await?;
+
+        let mut properties = HashMap::new();
+        properties.insert("window-id", Value::from(window_id));
+        properties.insert("cursor-mode", Value::U32(cursor_mode));
+        debug!(window_id, "Recording window");
+        let stream_path = proxy.record_window(properties).await?;
+        debug!(%stream_path, "Stream created");
+
+        Ok(stream_path)
+    }
+
+    /// Starts recording a rectangle in global logical coordinates.
+    pub async fn record_region(
+        &self,
+        session_path: &OwnedObjectPath,
+        (x, y, width, height): (i32, i32, i32, i32),
+        cursor_mode: u32,
+    ) -> Result<OwnedObjectPath> {
+        let proxy = ScreenCastSessionProxy::builder(&self.connection)
+            .path(session_path)?
+            .build()
+            .await?;
+
+        let mut properties = HashMap::new();
+        properties.insert("cursor-mode", Value::U32(cursor_mode));
+        debug!(x, y, width, height, "Recording region");
+        let stream_path = proxy.record_region(x, y, width, height, properties).await?;
+        debug!(%stream_path, "Stream created");
+
+        Ok(stream_path)
+    }
+
     /// Starts all streams in the session.
     pub async fn start_session(&self, session_path: &OwnedObjectPath) -> Result<()> {
         let proxy = ScreenCastSessionProxy::builder(&self.connection)
@@ -224,3 +303,30 @@ impl OttoClient {
         }
     }
 }
+
+/// Decodes the result of `PickSource`; `None` when it is empty (cancelled)
+/// or names no known source.
+pub fn parse_picked_source(picked: &HashMap<String, OwnedValue>) -> Option<SelectedSource> {
+    let string = |key: &str| {
+        picked
+            .get(key)
+            .and_then(|value| value.try_clone().ok())
+            .and_then(|owned| String::try_from(owned).ok())
+    };
+    if let Some(connector) = string("output") {
+        return Some(SelectedSource::Monitor(connector));
+    }
+    if let Some(window_id) = string("window-id") {
+        return Some(SelectedSource::Window(window_id));
+    }
+    let (x, y, width, height) = picked
+        .get("region")
+        .and_then(|value| value.try_clone().ok())
+        .and_then(|owned| <(i32, i32, i32, i32)>::try_from(owned).ok())?;
+    Some(SelectedSource::Region {
+        x,
+        y,
+        width,
+        height,
+    })
+}
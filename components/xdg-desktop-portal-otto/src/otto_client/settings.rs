@@ -24,4 +24,7 @@ trait OttoSettings {
     ///
     /// Returns an empty string if no theme is configured.
     async fn get_icon_theme(&self) -> Result<String>;
+
+    /// Get the accent color from the compositor, as RGB from 0.0 to 1.0.
+    async fn get_accent_color(&self) -> Result<(f64, f64, f64)>;
 }
@@ -14,9 +14,9 @@ use zbus::zvariant::{OwnedFd, OwnedObjectPath, OwnedValue};
 
 use crate::otto_client::OttoClient;
 use crate::portal::{
-    build_streams_value_from_descriptors, make_output_mapping_id, PortalState, Request, Session,
-    SessionState, StreamDescriptor, CURSOR_MODE_EMBEDDED, SOURCE_TYPE_MONITOR,
-    SUPPORTED_CURSOR_MODES,
+    build_streams_value_from_descriptors, make_output_mapping_id, PortalState, Request,
+    SelectedSource, Session, SessionState, StreamDescriptor, CURSOR_MODE_EMBEDDED,
+    SOURCE_TYPE_MONITOR, SUPPORTED_CURSOR_MODES, SUPPORTED_SOURCE_TYPES,
 };
 use zbus::zvariant::Str;
 
@@ -137,7 +137,7 @@ impl ScreenCastPortal {
                     session_handle.to_string(),
                     SessionState {
                         sc_session: sc_session_obj_path.clone(),
-                        selected_outputs: Vec::new(),
+                        selected_sources: Vec::new(),
                         cursor_mode: default_cursor_mode,
                         persist_mode: None,
                         next_stream_id: 0,
@@ -186,8 +186,9 @@ impl ScreenCastPortal {
                 .get("types")
                 .and_then(|value| u32::try_from(value).ok())
                 .unwrap_or(SOURCE_TYPE_MONITOR);
+            let types = requested_types & SUPPORTED_SOURCE_TYPES;
 
-            if requested_types & SOURCE_TYPE_MONITOR == 0 {
+            if types == 0 {
                 warn!(
                     session = %session_handle,
                     requested_types,
@@ -214,31 +215,23 @@ impl ScreenCastPortal {
                 .and_then(|value| bool::try_from(value).ok())
                 .unwrap_or(false);
 
-            info!(session = %session_handle, "Requesting available outputs from compositor");
-            let available_outputs =
-                self.sc_client.list_outputs().await.map_err(|err| {
-                    error!(session = %session_handle, ?err, "Failed to enumerate outputs");
-                    fdo::Error::Failed(format!("Failed to enumerate outputs: {err}"))
-                })?;
-
-            info!(session = %session_handle, count = available_outputs.len(), ?available_outputs, "Received outputs from compositor");
-
-            if available_outputs.is_empty() {
-                warn!(session = %session_handle, "No outputs available for screencast");
-                return Ok((3, HashMap::new()));
-            }
-
             if multiple {
                 info!(
                     session = %session_handle,
-                    "Multiple selection requested; limiting to first output"
+                    "Multiple selection requested; the picker selects one source"
                 );
             }
 
-            let chosen_output = available_outputs
-                .first()
-                .cloned()
-                .unwrap_or_else(|| "".to_string());
+            info!(session = %session_handle, types, "Asking the compositor to pick a source");
+            let picked = self.sc_client.pick_source(types).await.map_err(|err| {
+                error!(session = %session_handle, ?err, "Source picker failed");
+                fdo::Error::Failed(format!("Source picker failed: {err}"))
+            })?;
+
+            let Some(source) = picked else {
+                info!(session = %session_handle, "Source selection cancelled by the user");
+                return Ok((1, HashMap::new()));
+            };
 
             {
                 let mut state = self.state.lock().await;
@@ -247,7 +240,7 @@ impl ScreenCastPortal {
                     .get_mut(session_handle.as_str())
                     .ok_or_else(|| fdo::Error::Failed("Session not found".to_string()))?;
 
-                entry.selected_outputs = vec![chosen_output.clone()];
+                entry.selected_sources = vec![source.clone()];
                 entry.cursor_mode = cursor_mode;
                 entry.persist_mode = persist_mode;
                 entry.next_stream_id = 0;
@@ -255,7 +248,7 @@ impl ScreenCastPortal {
 
             info!(
                 session = %session_handle,
-                output = %chosen_output,
+                ?source,
                 cursor_mode,
                 persist_mode = ?persist_mode,
                 "Stored source selection"
@@ -291,49 +284,62 @@ impl ScreenCastPortal {
             .await?;
 
         let result = async {
-            let (sc_session_path, selected_output, cursor_mode, stream_index, persist_mode) = {
+            let (sc_session_path, source, cursor_mode, stream_index, persist_mode) = {
                 let mut state = self.state.lock().await;
                 let entry = state
                     .sessions
                     .get_mut(session_handle.as_str())
                     .ok_or_else(|| fdo::Error::Failed("Session not found".to_string()))?;
 
-                if entry.selected_outputs.is_empty() {
+                let Some(source) = entry.selected_sources.first().cloned() else {
                     return Err(fdo::Error::Failed(
-                        "No output selected for session".to_string(),
+                        "No source selected for session".to_string(),
                     ));
-                }
+                };
 
                 entry.next_stream_id += 1;
 
                 (
                     entry.sc_session.clone(),
-                    entry.selected_outputs[0].clone(),
+                    source,
                     entry.cursor_mode,
                     entry.next_stream_id,
                     entry.persist_mode,
                 )
             };
 
-            let stream_identifier = format!("screen-{stream_index}");
+            let stream_identifier = format!("{}-{stream_index}", source.stream_prefix());
 
             info!(
                 sc_session = %sc_session_path,
-                output = %selected_output,
+                ?source,
                 cursor_mode,
-                "Calling RecordMonitor on ScreenComposer session"
+                "Recording source on ScreenComposer session"
             );
 
-            let sc_stream_path = self
-                .sc_client
-                .record_monitor(&sc_session_path, selected_output.as_str(), cursor_mode)
-                .await
-                .map_err(|err| {
-                    fdo::Error::Failed(format!(
-                        "Failed to record monitor '{}': {err}",
-                        selected_output
-                    ))
-                })?;
+            let sc_stream_path = match &source {
+                SelectedSource::Monitor(connector) => {
+                    self.sc_client
+                        .record_monitor(&sc_session_path, connector, cursor_mode)
+                        .await
+                }
+                SelectedSource::Window(window_id) => {
+                    self.sc_client
+                        .record_window(&sc_session_path, window_id, cursor_mode)
+                        .await
+                }
+                SelectedSource::Region {
+                    x,
+                    y,
+                    width,
+                    height,
+                } => {
+                    self.sc_client
+                        .record_region(&sc_session_path, (*x, *y, *width, *height), cursor_mode)
+                        .await
+                }
+            }
+            .map_err(|err| fdo::Error::Failed(format!("Failed to record {source:?}: {err}")))?;
 
             info!(sc_stream = %sc_stream_path, "Got stream path, starting session");
 
@@ -350,11 +356,17 @@ impl ScreenCastPortal {
                     fdo::Error::Failed(format!("Failed to get stream metadata: {err}"))
                 })?;
 
-            let mapping_id = stream_metadata
-                .get("mapping-id")
-                .and_then(|value| value.try_clone().ok())
-                .and_then(|owned| TryInto::<String>::try_into(owned).ok())
-                .unwrap_or_else(|| fallback_mapping_id(&selected_output));
+            // Only whole outputs map to an output of the compositor
+            let mapping_id = match &source {
+                SelectedSource::Monitor(connector) => Some(
+                    stream_metadata
+                        .get("mapping-id")
+                        .and_then(|value| value.try_clone().ok())
+                        .and_then(|owned| TryInto::<String>::try_into(owned).ok())
+                        .unwrap_or_else(|| fallback_mapping_id(connector)),
+                ),
+                _ => None,
+            };
 
             let logical_position = stream_metadata
                 .get("position")
@@ -436,8 +448,9 @@ impl ScreenCastPortal {
 
             let descriptor = StreamDescriptor {
                 node_id: pipewire_node_id,
+                source_type: source.source_type(),
                 stream_id: stream_identifier.clone(),
-                mapping_id: Some(mapping_id.clone()),
+                mapping_id,
                 width,
                 height,
                 position,
@@ -508,7 +521,7 @@ impl ScreenCastPortal {
 
     #[zbus(property)]
     fn available_source_types(&self) -> u32 {
-        SOURCE_TYPE_MONITOR
+        SUPPORTED_SOURCE_TYPES
     }
 
     #[zbus(property)]
@@ -15,7 +15,7 @@ pub use interface::{
     fallback_mapping_id, validate_cursor_mode, validate_persist_mode, ScreenCastPortal,
 };
 pub use settings::SettingsPortal;
-pub use state::{PortalState, SessionState};
+pub use state::{PortalState, SelectedSource, SessionState};
 pub use stream::{build_streams_value_from_descriptors, StreamDescriptor};
 
 pub(crate) use request::Request;
@@ -26,7 +26,6 @@ pub const DESKTOP_PATH: &str = "/org/freedesktop/portal/desktop";
 
 // Source type bitmask values per XDG Desktop Portal spec.
 pub const SOURCE_TYPE_MONITOR: u32 = 1;
-#[allow(dead_code)]
 pub const SOURCE_TYPE_WINDOW: u32 = 2;
 #[allow(dead_code)]
 pub const SOURCE_TYPE_VIRTUAL: u32 = 4;
@@ -37,6 +36,8 @@ pub const CURSOR_MODE_EMBEDDED: u32 = 2;
 pub const CURSOR_MODE_METADATA: u32 = 4;
 // We only support HIDDEN and EMBEDDED modes
 pub const SUPPORTED_CURSOR_MODES: u32 = CURSOR_MODE_HIDDEN | CURSOR_MODE_EMBEDDED;
+// Regions are shared as monitors
+pub const SUPPORTED_SOURCE_TYPES: u32 = SOURCE_TYPE_MONITOR | SOURCE_TYPE_WINDOW;
 
 /// Returns the D-Bus object path for the portal desktop interface.
 #[inline]
@@ -25,6 +25,7 @@ impl SettingsPortal {
     async fn get_all_settings(&self) -> fdo::Result<HashMap<String, HashMap<String, OwnedValue>>> {
         let color_scheme = self.read_color_scheme().await?;
         let icon_theme = self.read_icon_theme().await?;
+        let accent_color = self.read_accent_color().await?;
 
         let mut namespaces = HashMap::new();
         let mut appearance = HashMap::new();
@@ -34,6 +35,10 @@ impl SettingsPortal {
             "icon-theme".to_string(),
             Value::from(icon_theme).try_into().unwrap(),
         );
+        appearance.insert(
+            "accent-color".to_string(),
+            Value::from(accent_color).try_into().unwrap(),
+        );
 
         namespaces.insert("org.freedesktop.appearance".to_string(), appearance);
         Ok(namespaces)
@@ -50,6 +55,10 @@ impl SettingsPortal {
                 let icon_theme = self.read_icon_theme().await?;
                 Ok(Value::from(icon_theme).try_into().unwrap())
             }
+            ("org.freedesktop.appearance", "accent-color") => {
+                let accent_color = self.read_accent_color().await?;
+                Ok(Value::from(accent_color).try_into().unwrap())
+            }
             _ => Err(fdo::Error::Failed(format!(
                 "Unknown setting: {}.{}",
                 namespace, key
@@ -85,6 +94,15 @@ impl SettingsPortal {
         })
     }
 
+    /// Reads the accent color from the compositor.
+    async fn read_accent_color(&self) -> fdo::Result<(f64, f64, f64)> {
+        let proxy = self.get_settings_proxy().await?;
+        proxy.get_accent_color().await.map_err(|err| {
+            error!(?err, "Failed to read accent color from compositor");
+            fdo::Error::Failed(format!("Failed to read accent color: {err}"))
+        })
+    }
+
     /// Helper to match namespace patterns (supports trailing wildcard).
     fn matches_namespace(namespace: &str, pattern: &str) -> bool {
         if pattern.ends_with(".*") {
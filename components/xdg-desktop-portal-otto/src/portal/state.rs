@@ -4,6 +4,8 @@ use std::collections::HashMap;
 
 use zbus::zvariant::OwnedObjectPath;
 
+use crate::portal::{SOURCE_TYPE_MONITOR, SOURCE_TYPE_WINDOW};
+
 /// Global portal state tracking all active sessions.
 #[derive(Default)]
 pub struct PortalState {
@@ -16,8 +18,8 @@ pub struct PortalState {
 pub struct SessionState {
     /// Object path of the corresponding compositor session.
     pub sc_session: OwnedObjectPath,
-    /// Output connectors selected for this session.
-    pub selected_outputs: Vec<String>,
+    /// Sources picked for this session.
+    pub selected_sources: Vec<SelectedSource>,
     /// Cursor mode (Hidden=1, Embedded=2, Metadata=4).
     pub cursor_mode: u32,
     /// Persistence mode (None=0, Application=1, Permanent=2).
@@ -25,3 +27,38 @@ pub struct SessionState {
     /// Counter for generating unique stream IDs.
     pub next_stream_id: u32,
 }
+
+/// A source picked by the user in `SelectSources`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SelectedSource {
+    /// A whole output, by connector name.
+    Monitor(String),
+    /// A window, by its compositor handle.
+    Window(String),
+    /// A rectangle in global logical coordinates, shared as a monitor.
+    Region {
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    },
+}
+
+impl SelectedSource {
+    /// Portal source type reported for this source's stream.
+    pub fn source_type(&self) -> u32 {
+        match self {
+            SelectedSource::Window(_) => SOURCE_TYPE_WINDOW,
+            SelectedSource::Monitor(_) | SelectedSource::Region { .. } => SOURCE_TYPE_MONITOR,
+        }
+    }
+
+    /// Prefix of the portal stream id, e.g. `screen-1`.
+    pub fn stream_prefix(&self) -> &'static str {
+        match self {
+            SelectedSource::Monitor(_) => "screen",
+            SelectedSource::Window(_) => "window",
+            SelectedSource::Region { .. } => "region",
+        }
+    }
+}
@@ -4,13 +4,13 @@ use std::collections::HashMap;
 
 use zbus::zvariant::{OwnedValue, Str, Value};
 
-use crate::portal::SOURCE_TYPE_MONITOR;
-
 /// Describes a PipeWire stream for the portal response.
 #[derive(Clone, Debug)]
 pub struct StreamDescriptor {
     /// PipeWire node ID.
     pub node_id: u32,
+    /// Portal source type (monitor or window).
+    pub source_type: u32,
     /// Unique identifier for this stream within the session.
     pub stream_id: String,
     /// Mapping ID for correlating with compositor outputs.
@@ -48,7 +48,7 @@ pub fn build_streams_value_from_descriptors(
         let mut dict: HashMap<String, OwnedValue> = HashMap::new();
         dict.insert(
             "source_type".to_string(),
-            OwnedValue::from(SOURCE_TYPE_MONITOR),
+            OwnedValue::from(descriptor.source_type),
         );
         dict.insert(
             "id".to_string(),
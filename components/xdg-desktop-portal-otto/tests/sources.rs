@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use xdg_desktop_portal_otto::otto_client::screencast::parse_picked_source;
+use xdg_desktop_portal_otto::portal::{SelectedSource, SOURCE_TYPE_MONITOR, SOURCE_TYPE_WINDOW};
+use zbus::zvariant::{OwnedValue, Value};
+
+fn picked(key: &str, value: Value<'_>) -> HashMap<String, OwnedValue> {
+    let mut picked = HashMap::new();
+    picked.insert(key.to_string(), OwnedValue::try_from(value).unwrap());
+    picked
+}
+
+#[test]
+fn picked_sources_decode_by_kind() {
+    assert_eq!(
+        parse_picked_source(&picked("output", Value::from("eDP-1"))),
+        Some(SelectedSource::Monitor("eDP-1".to_string()))
+    );
+    assert_eq!(
+        parse_picked_source(&picked("window-id", Value::from("00ff"))),
+        Some(SelectedSource::Window("00ff".to_string()))
+    );
+    assert_eq!(
+        parse_picked_source(&picked("region", Value::new((10, 20, 300, 200)))),
+        Some(SelectedSource::Region {
+            x: 10,
+            y: 20,
+            width: 300,
+            height: 200,
+        })
+    );
+}
+
+#[test]
+fn cancelled_picker_selects_nothing() {
+    assert_eq!(parse_picked_source(&HashMap::new()), None);
+}
+
+#[test]
+fn regions_are_shared_as_monitors() {
+    let region = SelectedSource::Region {
+        x: 0,
+        y: 0,
+        width: 1,
+        height: 1,
+    };
+    assert_eq!(region.source_type(), SOURCE_TYPE_MONITOR);
+    assert_eq!(
+        SelectedSource::Window("00ff".to_string()).source_type(),
+        SOURCE_TYPE_WINDOW
+    );
+}
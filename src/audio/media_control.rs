@@ -1,11 +1,33 @@
 //! Media player control via MPRIS D-Bus interface
 //!
 //! Provides control over media players (Spotify, VLC, Firefox, Chrome, etc.)
-//! using the MPRIS2 D-Bus protocol. Supports play/pause, next, previous, and stop.
+//! using the MPRIS2 D-Bus protocol. Supports play/pause, next, previous, stop,
+//! seeking, and reading the current track metadata and playback status.
+//!
+//! Every command takes an optional player bus name (e.g.
+//! `org.mpris.MediaPlayer2.spotify`); `None` targets the most recently
+//! active player.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 
-use mpris::PlayerFinder;
+use mpris::{DBusError, Player, PlayerFinder};
+use once_cell::sync::Lazy;
 use tracing::{debug, error, info};
 
+pub use mpris::PlaybackStatus;
+
+/// How often [`MediaController::metadata_changed`] polls the player
+const METADATA_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Bus name of the last player seen playing, used as the default target
+static LAST_ACTIVE_PLAYER: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
 #[derive(Debug)]
 pub enum MediaError {
     NoPlayerFound,
@@ -25,52 +47,95 @@ impl std::fmt::Display for MediaError {
 
 impl std::error::Error for MediaError {}
 
+/// A running MPRIS player
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerInfo {
+    /// D-Bus bus name, used to target the player
+    pub bus_name: String,
+    /// Human readable name (e.g. "Spotify")
+    pub identity: String,
+    pub status: PlaybackStatus,
+}
+
+/// Snapshot of a player's state, delivered to metadata observers
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaState {
+    pub player: PlayerInfo,
+    pub metadata: MediaMetadata,
+    /// Playback position when the snapshot was taken
+    pub position: Option<Duration>,
+}
+
 /// Media controller for MPRIS-compatible players
 pub struct MediaController;
 
 impl MediaController {
-    /// Find the currently active media player
-    /// Returns the first playing player, or the first paused player, or the first player found
-    fn find_active_player() -> Result<mpris::Player, MediaError> {
-        let player_finder = PlayerFinder::new().map_err(|e| {
-            MediaError::ConnectionFailed(format!("Failed to connect to D-Bus: {}", e))
-        })?;
+    /// Connect to the session bus
+    fn connect() -> Result<PlayerFinder, MediaError> {
+        PlayerFinder::new()
+            .map_err(|e| MediaError::ConnectionFailed(format!("Failed to connect to D-Bus: {}", e)))
+    }
 
-        // Try to find active player (playing or paused)
-        if let Ok(player) = player_finder.find_active() {
-            debug!("Found active player: {}", player.identity());
-            return Ok(player);
-        }
+    /// Find the player to control on a new connection
+    fn find_player(bus_name: Option<&str>) -> Result<Player, MediaError> {
+        Self::find_player_on(&Self::connect()?, bus_name)
+    }
 
-        // Fall back to first available player
+    /// Find the player to control on `player_finder`'s connection.
+    ///
+    /// With a bus name, that player; otherwise the playing player, then the
+    /// player last seen playing, then a paused player, then any player.
+    fn find_player_on(
+        player_finder: &PlayerFinder,
+        bus_name: Option<&str>,
+    ) -> Result<Player, MediaError> {
         let players = player_finder.find_all().map_err(|e| {
             MediaError::ConnectionFailed(format!("Failed to enumerate players: {}", e))
         })?;
+        let candidates: Vec<(String, PlaybackStatus)> = players
+            .iter()
+            .map(|p| {
+                let status = p.get_playback_status().unwrap_or(PlaybackStatus::Stopped);
+                (p.bus_name().to_string(), status)
+            })
+            .collect();
 
-        if let Some(player) = players.into_iter().next() {
-            debug!("Using first available player: {}", player.identity());
-            Ok(player)
-        } else {
-            Err(MediaError::NoPlayerFound)
+        let last_active = LAST_ACTIVE_PLAYER.lock().unwrap().clone();
+        let index = pick_player(&candidates, bus_name, last_active.as_deref())
+            .ok_or(MediaError::NoPlayerFound)?;
+        if candidates[index].1 == PlaybackStatus::Playing {
+            *LAST_ACTIVE_PLAYER.lock().unwrap() = Some(candidates[index].0.clone());
         }
+
+        let player = players
+            .into_iter()
+            .nth(index)
+            .ok_or(MediaError::NoPlayerFound)?;
+        debug!("Using player: {}", player.identity());
+        Ok(player)
     }
 
-    /// Toggle play/pause on the active player
-    pub fn play_pause() -> Result<(), MediaError> {
-        std::thread::spawn(|| match Self::find_active_player() {
+    /// Run `command` on the selected player in a background thread
+    fn spawn_command(
+        bus_name: Option<&str>,
+        description: &'static str,
+        command: impl FnOnce(&Player) -> Result<(), DBusError> + Send + 'static,
+    ) -> Result<(), MediaError> {
+        let bus_name = bus_name.map(str::to_string);
+        std::thread::spawn(move || match Self::find_player(bus_name.as_deref()) {
             Ok(player) => {
-                let identity = player.identity();
-                match player.play_pause() {
+                let identity = player.identity().to_string();
+                match command(&player) {
                     Ok(_) => {
-                        info!(player = %identity, "Toggled play/pause");
+                        info!(player = %identity, "{}", description);
                     }
                     Err(e) => {
-                        error!(player = %identity, error = %e, "Failed to toggle play/pause");
+                        error!(player = %identity, error = %e, "Failed: {}", description);
                     }
                 }
             }
             Err(MediaError::NoPlayerFound) => {
-                debug!("No media player found for play/pause");
+                debug!("No media player found: {}", description);
             }
             Err(e) => {
                 error!(error = %e, "Failed to find media player");
@@ -79,104 +144,267 @@ impl MediaController {
         Ok(())
     }
 
+    /// List the running players
+    pub fn list_players() -> Result<Vec<PlayerInfo>, MediaError> {
+        let players = Self::connect()?.find_all().map_err(|e| {
+            MediaError::ConnectionFailed(format!("Failed to enumerate players: {}", e))
+        })?;
+        Ok(players.iter().map(player_info).collect())
+    }
+
+    /// Toggle play/pause
+    pub fn play_pause(bus_name: Option<&str>) -> Result<(), MediaError> {
+        Self::spawn_command(bus_name, "Toggled play/pause", |p| p.play_pause())
+    }
+
     /// Skip to next track
-    pub fn next() -> Result<(), MediaError> {
-        std::thread::spawn(|| match Self::find_active_player() {
-            Ok(player) => {
-                let identity = player.identity();
-                match player.next() {
-                    Ok(_) => {
-                        info!(player = %identity, "Skipped to next track");
-                    }
-                    Err(e) => {
-                        error!(player = %identity, error = %e, "Failed to skip to next");
-                    }
-                }
-            }
-            Err(MediaError::NoPlayerFound) => {
-                debug!("No media player found for next track");
-            }
-            Err(e) => {
-                error!(error = %e, "Failed to find media player");
-            }
-        });
-        Ok(())
+    pub fn next(bus_name: Option<&str>) -> Result<(), MediaError> {
+        Self::spawn_command(bus_name, "Skipped to next track", |p| p.next())
     }
 
     /// Go to previous track
-    pub fn previous() -> Result<(), MediaError> {
-        std::thread::spawn(|| match Self::find_active_player() {
-            Ok(player) => {
-                let identity = player.identity();
-                match player.previous() {
-                    Ok(_) => {
-                        info!(player = %identity, "Skipped to previous track");
-                    }
-                    Err(e) => {
-                        error!(player = %identity, error = %e, "Failed to skip to previous");
-                    }
-                }
-            }
-            Err(MediaError::NoPlayerFound) => {
-                debug!("No media player found for previous track");
-            }
-            Err(e) => {
-                error!(error = %e, "Failed to find media player");
-            }
-        });
-        Ok(())
+    pub fn previous(bus_name: Option<&str>) -> Result<(), MediaError> {
+        Self::spawn_command(bus_name, "Skipped to previous track", |p| p.previous())
     }
 
     /// Stop playback
-    pub fn stop() -> Result<(), MediaError> {
-        std::thread::spawn(|| match Self::find_active_player() {
-            Ok(player) => {
-                let identity = player.identity();
-                match player.stop() {
-                    Ok(_) => {
-                        info!(player = %identity, "Stopped playback");
-                    }
-                    Err(e) => {
-                        error!(player = %identity, error = %e, "Failed to stop playback");
-                    }
+    pub fn stop(bus_name: Option<&str>) -> Result<(), MediaError> {
+        Self::spawn_command(bus_name, "Stopped playback", |p| p.stop())
+    }
+
+    /// Seek relative to the current position (negative seeks backwards)
+    pub fn seek(bus_name: Option<&str>, offset_micros: i64) -> Result<(), MediaError> {
+        Self::spawn_command(bus_name, "Seeked", move |p| p.seek(offset_micros))
+    }
+
+    /// Jump to an absolute position in the current track
+    pub fn set_position(bus_name: Option<&str>, position: Duration) -> Result<(), MediaError> {
+        Self::spawn_command(bus_name, "Set playback position", move |p| {
+            match p.get_metadata()?.track_id() {
+                Some(track_id) => p.set_position(track_id, &position),
+                // Players without track ids only support relative seeks
+                None => {
+                    let current = p.get_position()?;
+                    let offset = position.as_micros() as i64 - current.as_micros() as i64;
+                    p.seek(offset)
                 }
             }
-            Err(MediaError::NoPlayerFound) => {
-                debug!("No media player found for stop");
-            }
-            Err(e) => {
-                error!(error = %e, "Failed to find media player");
-            }
-        });
-        Ok(())
+        })
     }
 
-    /// Get current playback metadata (for future OSD)
-    #[allow(dead_code)]
-    pub fn get_metadata() -> Option<MediaMetadata> {
-        match Self::find_active_player() {
-            Ok(player) => {
-                if let Ok(metadata) = player.get_metadata() {
-                    Some(MediaMetadata {
-                        title: metadata.title().map(|s| s.to_string()),
-                        artist: metadata
-                            .artists()
-                            .and_then(|a| a.first().map(|s| s.to_string())),
-                        album: metadata.album_name().map(|s| s.to_string()),
+    /// Get the playback status (blocking)
+    pub fn playback_status(bus_name: Option<&str>) -> Result<PlaybackStatus, MediaError> {
+        let player = Self::find_player(bus_name)?;
+        player
+            .get_playback_status()
+            .map_err(|e| MediaError::OperationFailed(e.to_string()))
+    }
+
+    /// Get current track metadata (blocking)
+    pub fn get_metadata(bus_name: Option<&str>) -> Option<MediaMetadata> {
+        let player = Self::find_player(bus_name).ok()?;
+        player.get_metadata().ok().map(|m| MediaMetadata::from(&m))
+    }
+
+    /// Call `callback` whenever the track metadata or playback status of the
+    /// selected player changes, and once with the initial state.
+    ///
+    /// With `bus_name` `None` the observed player follows the most recently
+    /// active one. The callback runs on a background thread; observing stops
+    /// when the returned handle is dropped.
+    ///
+    /// The observer keeps one bus connection, and keeps its player while that
+    /// is running (and, when following the active player, playing).
+    pub fn metadata_changed(
+        bus_name: Option<String>,
+        callback: impl Fn(Option<&MediaState>) + Send + 'static,
+    ) -> MetadataObserver {
+        let running = Arc::new(AtomicBool::new(true));
+        let thread_running = running.clone();
+        std::thread::spawn(move || {
+            let mut last: Option<MediaState> = None;
+            let mut first = true;
+            let mut finder: Option<PlayerFinder> = None;
+            let mut player: Option<Player> = None;
+            while thread_running.load(Ordering::Relaxed) {
+                let keep = player.as_ref().is_some_and(|player| {
+                    player.is_running()
+                        && (bus_name.is_some()
+                            || player.get_playback_status().ok() == Some(PlaybackStatus::Playing))
+                });
+                if !keep {
+                    if finder.is_none() {
+                        finder = Self::connect().ok();
+                    }
+                    player = match finder
+                        .as_ref()
+                        .map(|finder| Self::find_player_on(finder, bus_name.as_deref()))
+                    {
+                        Some(Ok(player)) => Some(player),
+                        // Reconnect on the next poll
+                        Some(Err(MediaError::ConnectionFailed(_))) => {
+                            finder = None;
+                            None
+                        }
+                        _ => None,
+                    };
+                }
+
+                let state = player.as_ref().and_then(|player| {
+                    let metadata = player.get_metadata().ok()?;
+                    Some(MediaState {
+                        player: player_info(player),
+                        metadata: MediaMetadata::from(&metadata),
+                        position: player.get_position().ok(),
                     })
-                } else {
-                    None
+                });
+
+                // Position moves on its own; only report track / status changes
+                let changed = match (&last, &state) {
+                    (Some(a), Some(b)) => a.player != b.player || a.metadata != b.metadata,
+                    (None, None) => false,
+                    _ => true,
+                };
+                if changed || first {
+                    callback(state.as_ref());
                 }
+                first = false;
+                last = state;
+                std::thread::sleep(METADATA_POLL_INTERVAL);
             }
-            Err(_) => None,
-        }
+        });
+        MetadataObserver { running }
     }
 }
 
-/// Media metadata for OSD display
-#[derive(Debug, Clone)]
+/// Handle returned by [`MediaController::metadata_changed`]; stops observing when dropped
+pub struct MetadataObserver {
+    running: Arc<AtomicBool>,
+}
+
+impl Drop for MetadataObserver {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+fn player_info(player: &Player) -> PlayerInfo {
+    PlayerInfo {
+        bus_name: player.bus_name().to_string(),
+        identity: player.identity().to_string(),
+        status: player
+            .get_playback_status()
+            .unwrap_or(PlaybackStatus::Stopped),
+    }
+}
+
+/// Choose a player index from `(bus_name, status)` candidates.
+///
+/// A requested bus name must match exactly. Otherwise prefer a playing
+/// player (the last active one if several are playing), then the last active
+/// player, then a paused player, then the first one.
+fn pick_player(
+    candidates: &[(String, PlaybackStatus)],
+    requested: Option<&str>,
+    last_active: Option<&str>,
+) -> Option<usize> {
+    if let Some(requested) = requested {
+        return candidates.iter().position(|(name, _)| name == requested);
+    }
+    let is_last = |name: &String| Some(name.as_str()) == last_active;
+    candidates
+        .iter()
+        .position(|(name, status)| *status == PlaybackStatus::Playing && is_last(name))
+        .or_else(|| {
+            candidates
+                .iter()
+                .position(|(_, status)| *status == PlaybackStatus::Playing)
+        })
+        .or_else(|| candidates.iter().position(|(name, _)| is_last(name)))
+        .or_else(|| {
+            candidates
+                .iter()
+                .position(|(_, status)| *status == PlaybackStatus::Paused)
+        })
+        .or(if candidates.is_empty() { None } else { Some(0) })
+}
+
+/// Media metadata for OSD display and media widgets
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct MediaMetadata {
     pub title: Option<String>,
     pub artist: Option<String>,
     pub album: Option<String>,
+    pub art_url: Option<String>,
+    /// Track length
+    pub length: Option<Duration>,
+}
+
+impl From<&mpris::Metadata> for MediaMetadata {
+    fn from(metadata: &mpris::Metadata) -> Self {
+        Self {
+            title: metadata.title().map(|s| s.to_string()),
+            artist: metadata
+                .artists()
+                .and_then(|a| a.first().map(|s| s.to_string())),
+            album: metadata.album_name().map(|s| s.to_string()),
+            art_url: metadata.art_url().map(|s| s.to_string()),
+            length: metadata.length(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(list: &[(&str, PlaybackStatus)]) -> Vec<(String, PlaybackStatus)> {
+        list.iter().map(|(n, s)| (n.to_string(), *s)).collect()
+    }
+
+    #[test]
+    fn requested_player_must_match() {
+        let c = candidates(&[
+            ("org.mpris.MediaPlayer2.spotify", PlaybackStatus::Playing),
+            ("org.mpris.MediaPlayer2.vlc", PlaybackStatus::Paused),
+        ]);
+        assert_eq!(
+            pick_player(&c, Some("org.mpris.MediaPlayer2.vlc"), None),
+            Some(1)
+        );
+        assert_eq!(
+            pick_player(&c, Some("org.mpris.MediaPlayer2.mpv"), None),
+            None
+        );
+    }
+
+    #[test]
+    fn playing_player_wins_over_last_active() {
+        let c = candidates(&[
+            ("a", PlaybackStatus::Paused),
+            ("b", PlaybackStatus::Playing),
+        ]);
+        assert_eq!(pick_player(&c, None, Some("a")), Some(1));
+    }
+
+    #[test]
+    fn last_active_breaks_ties() {
+        let c = candidates(&[
+            ("a", PlaybackStatus::Playing),
+            ("b", PlaybackStatus::Playing),
+        ]);
+        assert_eq!(pick_player(&c, None, Some("b")), Some(1));
+
+        let c = candidates(&[
+            ("a", PlaybackStatus::Paused),
+            ("b", PlaybackStatus::Stopped),
+        ]);
+        assert_eq!(pick_player(&c, None, Some("b")), Some(1));
+        assert_eq!(pick_player(&c, None, None), Some(0));
+    }
+
+    #[test]
+    fn no_players() {
+        assert_eq!(pick_player(&[], None, None), None);
+    }
 }
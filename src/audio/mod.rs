@@ -7,6 +7,9 @@ pub mod media_control;
 pub mod sound_player;
 pub mod volume;
 
-pub use media_control::{MediaController, MediaError};
+pub use media_control::{
+    MediaController, MediaError, MediaMetadata, MediaState, MetadataObserver, PlaybackStatus,
+    PlayerInfo,
+};
 pub use sound_player::SoundPlayer;
-pub use volume::{AudioManager, AudioState, VolumeError};
+pub use volume::{AudioEvent, AudioManager, AudioState, AudioStream, VolumeError};
@@ -4,11 +4,16 @@
 //! - Enumerate audio sink nodes via Registry
 //! - Get/set volume via node parameters
 //! - Track mute state
+//! - Per-application playback stream volume and mute
 //! - Event-driven updates for OSD integration
 
-use std::sync::{Arc, Mutex};
+use std::{
+    io::{BufRead, BufReader},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 #[derive(Debug)]
 pub enum VolumeError {
@@ -38,6 +43,8 @@ pub struct AudioState {
     pub volume: u32,
     /// Mute state
     pub muted: bool,
+    /// Microphone (default source) mute state
+    pub source_muted: bool,
 }
 
 impl Default for AudioState {
@@ -45,14 +52,56 @@ impl Default for AudioState {
         Self {
             volume: 50,
             muted: false,
+            source_muted: false,
         }
     }
 }
 
+/// An application's playback stream (one PipeWire node)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioStream {
+    /// PipeWire node id, used to address the stream
+    pub node_id: u32,
+    /// Human readable application name
+    pub app_name: String,
+    /// Application id (`application.id`, or the process binary), for matching windows
+    pub app_id: Option<String>,
+    /// Volume (0.0-1.0), on the same cubic scale as `wpctl`
+    pub volume: f64,
+    /// Mute state
+    pub muted: bool,
+}
+
+/// Change notification delivered to observers registered with [`AudioManager::subscribe`]
+#[derive(Debug, Clone)]
+pub enum AudioEvent {
+    /// Master volume or mute changed
+    Master(AudioState),
+    /// The list of playback streams, or one of their volumes, changed
+    Streams(Vec<AudioStream>),
+}
+
+type AudioObserver = Box<dyn Fn(&AudioEvent) + Send + Sync>;
+
+/// Minimum time between two enumerations of the PipeWire graph
+const STREAM_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Cached playback streams, refreshed in the background
+#[derive(Default)]
+struct StreamCache {
+    streams: Vec<AudioStream>,
+    refreshed_at: Option<Instant>,
+    refreshing: bool,
+}
+
 /// Audio manager using PipeWire for volume control
 pub struct AudioManager {
     /// Cached audio state
     state: Arc<Mutex<AudioState>>,
+    /// Cached per-application streams
+    streams: Arc<Mutex<StreamCache>>,
+    /// Change observers
+    observers: Arc<Mutex<Vec<AudioObserver>>>,
 }
 
 impl AudioManager {
@@ -60,9 +109,16 @@ impl AudioManager {
     pub fn new() -> Result<Self, VolumeError> {
         info!("Initializing PipeWire audio manager");
 
-        Ok(Self {
-            state: Arc::new(Mutex::new(AudioState::default())),
-        })
+        let manager = Self::default();
+
+        // Restore the microphone mute from the previous session
+        if load_source_muted() {
+            manager.state.lock().unwrap().source_muted = true;
+            run_wpctl(source_mute_args(true));
+        }
+        watch_source_changes(manager.state.clone());
+
+        Ok(manager)
     }
 
     /// Get current audio state
@@ -85,6 +141,9 @@ impl AudioManager {
         // Apply via wpctl for now (will be replaced with native PipeWire)
         self.set_volume_wpctl(new_volume)?;
         state.volume = new_volume;
+        let event = AudioEvent::Master(state.clone());
+        drop(state);
+        self.notify(&event);
 
         Ok(())
     }
@@ -104,10 +163,154 @@ impl AudioManager {
         // Apply via wpctl for now (will be replaced with native PipeWire)
         self.set_mute_wpctl(new_muted)?;
         state.muted = new_muted;
+        let event = AudioEvent::Master(state.clone());
+        drop(state);
+        self.notify(&event);
 
         Ok(())
     }
 
+    /// Microphone (default source) mute state
+    pub fn source_muted(&self) -> bool {
+        self.state.lock().unwrap().source_muted
+    }
+
+    /// Mute or unmute the microphone (default source).
+    ///
+    /// The state is persisted and restored on the next start.
+    pub fn set_source_mute(&self, muted: bool) -> Result<(), VolumeError> {
+        tracing::trace!(muted, "Setting microphone mute");
+
+        run_wpctl(source_mute_args(muted));
+        store_source_muted(muted);
+
+        let event = {
+            let mut state = self.state.lock().unwrap();
+            state.source_muted = muted;
+            AudioEvent::Master(state.clone())
+        };
+        self.notify(&event);
+        Ok(())
+    }
+
+    /// Register an observer called on every master or stream change.
+    ///
+    /// Observers may be called from a background thread.
+    pub fn subscribe(&self, observer: impl Fn(&AudioEvent) + Send + Sync + 'static) {
+        self.observers.lock().unwrap().push(Box::new(observer));
+    }
+
+    fn notify(&self, event: &AudioEvent) {
+        notify_observers(&self.observers, event);
+    }
+
+    /// List the applications' playback streams.
+    ///
+    /// Returns the cached list; when it is older than `STREAM_REFRESH_INTERVAL`
+    /// a background refresh is started and observers receive
+    /// [`AudioEvent::Streams`] once it completes. The first call returns an
+    /// empty list.
+    pub fn list_streams(&self) -> Vec<AudioStream> {
+        let mut cache = self.streams.lock().unwrap();
+        let stale = cache
+            .refreshed_at
+            .is_none_or(|at| at.elapsed() >= STREAM_REFRESH_INTERVAL);
+        if stale && !cache.refreshing {
+            cache.refreshing = true;
+            let streams = self.streams.clone();
+            let observers = self.observers.clone();
+            std::thread::spawn(move || {
+                let result = query_streams_pw_dump();
+                let changed = {
+                    let mut cache = streams.lock().unwrap();
+                    cache.refreshing = false;
+                    cache.refreshed_at = Some(Instant::now());
+                    match result {
+                        Ok(list) if list != cache.streams => {
+                            cache.streams = list;
+                            Some(cache.streams.clone())
+                        }
+                        Ok(_) => None,
+                        Err(e) => {
+                            error!("Failed to enumerate audio streams: {}", e);
+                            None
+                        }
+                    }
+                };
+                if let Some(list) = changed {
+                    notify_observers(&observers, &AudioEvent::Streams(list));
+                }
+            });
+        }
+        cache.streams.clone()
+    }
+
+    /// Set the volume (0.0-1.0) of one application stream
+    pub fn set_stream_volume(&self, node_id: u32, volume: f64) -> Result<(), VolumeError> {
+        let volume = volume.clamp(0.0, 1.0);
+        tracing::trace!(node_id, volume, "Setting stream volume");
+
+        // wpctl takes 4 decimals, anything closer is the same volume
+        let changed = self.update_cached_stream(node_id, |stream| {
+            let changed = (stream.volume - volume).abs() >= 0.0001;
+            stream.volume = volume;
+            changed
+        })?;
+        if changed {
+            run_wpctl(vec![
+                "set-volume".to_string(),
+                node_id.to_string(),
+                format!("{:.4}", volume),
+            ]);
+        }
+        Ok(())
+    }
+
+    /// Mute or unmute one application stream
+    pub fn set_stream_mute(&self, node_id: u32, muted: bool) -> Result<(), VolumeError> {
+        tracing::trace!(node_id, muted, "Setting stream mute");
+
+        let changed = self.update_cached_stream(node_id, |stream| {
+            let changed = stream.muted != muted;
+            stream.muted = muted;
+            changed
+        })?;
+        if changed {
+            run_wpctl(vec![
+                "set-mute".to_string(),
+                node_id.to_string(),
+                if muted { "1" } else { "0" }.to_string(),
+            ]);
+        }
+        Ok(())
+    }
+
+    /// Apply `update` to the cached stream; it returns whether the stream
+    /// changed, and observers are only notified when it did. Fails without
+    /// touching anything for an unknown stream.
+    fn update_cached_stream(
+        &self,
+        node_id: u32,
+        update: impl FnOnce(&mut AudioStream) -> bool,
+    ) -> Result<bool, VolumeError> {
+        let streams = {
+            let mut cache = self.streams.lock().unwrap();
+            let stream = cache
+                .streams
+                .iter_mut()
+                .find(|s| s.node_id == node_id)
+                .ok_or_else(|| {
+                    VolumeError::OperationFailed(format!("No audio stream with id {}", node_id))
+                })?;
+            if !update(stream) {
+                return Ok(false);
+            }
+            cache.streams.clone()
+        };
+        self.notify(&AudioEvent::Streams(streams));
+        Ok(true)
+    }
+
     /// Set volume via wpctl (temporary implementation)
     fn set_volume_wpctl(&self, volume: u32) -> Result<(), VolumeError> {
         let volume_fraction = (volume as f32 / 100.0).clamp(0.0, 1.0);
@@ -184,10 +387,277 @@ impl AudioManager {
     }
 }
 
+fn notify_observers(observers: &Mutex<Vec<AudioObserver>>, event: &AudioEvent) {
+    for observer in observers.lock().unwrap().iter() {
+        observer(event);
+    }
+}
+
+/// Run a wpctl command on a background thread, logging failures
+fn run_wpctl(args: Vec<String>) {
+    std::thread::spawn(
+        move || match std::process::Command::new("wpctl").args(&args).output() {
+            Ok(out) if out.status.success() => {
+                tracing::trace!("wpctl {} succeeded", args.join(" "));
+            }
+            Ok(out) => {
+                error!("wpctl failed: {}", String::from_utf8_lossy(&out.stderr));
+            }
+            Err(e) => {
+                error!("Failed to execute wpctl: {}", e);
+            }
+        },
+    );
+}
+
+fn source_mute_args(muted: bool) -> Vec<String> {
+    vec![
+        "set-mute".to_string(),
+        "@DEFAULT_AUDIO_SOURCE@".to_string(),
+        if muted { "1" } else { "0" }.to_string(),
+    ]
+}
+
+/// File holding the persisted microphone mute state
+fn source_mute_state_path() -> Option<std::path::PathBuf> {
+    std::env::var_os("XDG_STATE_HOME")
+        .map(std::path::PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".local/state"))
+        })
+        .map(|dir| dir.join("otto").join("mic-muted"))
+}
+
+fn load_source_muted() -> bool {
+    source_mute_state_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .is_some_and(|content| content.trim() == "1")
+}
+
+fn store_source_muted(muted: bool) {
+    let Some(path) = source_mute_state_path() else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Err(e) = std::fs::write(&path, if muted { "1" } else { "0" }) {
+        error!("Failed to persist microphone mute state: {}", e);
+    }
+}
+
+/// Enumerate playback streams with `pw-dump` (blocking)
+fn query_streams_pw_dump() -> Result<Vec<AudioStream>, VolumeError> {
+    let output = std::process::Command::new("pw-dump")
+        .output()
+        .map_err(|e| VolumeError::ConnectionFailed(format!("pw-dump: {}", e)))?;
+    if !output.status.success() {
+        return Err(VolumeError::OperationFailed(
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ));
+    }
+    parse_pw_dump_streams(&output.stdout)
+}
+
+/// Extract application playback streams from `pw-dump` JSON output
+fn parse_pw_dump_streams(json: &[u8]) -> Result<Vec<AudioStream>, VolumeError> {
+    let objects: Vec<serde_json::Value> = serde_json::from_slice(json)
+        .map_err(|e| VolumeError::OperationFailed(format!("Invalid pw-dump output: {}", e)))?;
+
+    let streams = objects
+        .iter()
+        .filter(|obj| obj["type"] == "PipeWire:Interface:Node")
+        .filter_map(|obj| {
+            let info = &obj["info"];
+            let props = &info["props"];
+            if props["media.class"] != "Stream/Output/Audio" {
+                return None;
+            }
+            let node_id = obj["id"].as_u64()? as u32;
+            let str_prop = |key: &str| props[key].as_str().map(str::to_string);
+            let app_name = str_prop("application.name")
+                .or_else(|| str_prop("node.name"))
+                .unwrap_or_else(|| format!("Stream {}", node_id));
+            let app_id =
+                str_prop("application.id").or_else(|| str_prop("application.process.binary"));
+
+            let params = &info["params"]["Props"][0];
+            let channel_volumes: Vec<f64> = params["channelVolumes"]
+                .as_array()
+                .map(|v| v.iter().filter_map(|c| c.as_f64()).collect())
+                .unwrap_or_default();
+            // PipeWire stores linear volumes; wpctl presents them cubed-root
+            let volume = if channel_volumes.is_empty() {
+                1.0
+            } else {
+                let linear = channel_volumes.iter().sum::<f64>() / channel_volumes.len() as f64;
+                linear.cbrt()
+            };
+            let muted = params["mute"].as_bool().unwrap_or(false);
+
+            Some(AudioStream {
+                node_id,
+                app_name,
+                app_id,
+                volume,
+                muted,
+            })
+        })
+        .collect();
+
+    Ok(streams)
+}
+
 impl Default for AudioManager {
     fn default() -> Self {
         Self {
             state: Arc::new(Mutex::new(AudioState::default())),
+            streams: Arc::new(Mutex::new(StreamCache::default())),
+            observers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+/// Keep the microphone muted across source changes (e.g. plugging a headset).
+///
+/// A newly added or newly default source starts unmuted, so the mute is
+/// re-applied whenever `pactl subscribe` (served by pipewire-pulse) reports
+/// one. Nothing is watched if it cannot be started.
+fn watch_source_changes(state: Arc<Mutex<AudioState>>) {
+    std::thread::spawn(move || {
+        let mut child = match std::process::Command::new("pactl")
+            .arg("subscribe")
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                warn!("Cannot watch audio sources, pactl subscribe failed: {}", e);
+                return;
+            }
+        };
+        let Some(stdout) = child.stdout.take() else {
+            return;
+        };
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if is_source_change(&line) && state.lock().unwrap().source_muted {
+                mute_default_source_if_live();
+            }
+        }
+        let _ = child.wait();
+        warn!("pactl subscribe exited, no longer watching audio sources");
+    });
+}
+
+/// Whether a `pactl subscribe` line reports a new source or a new default
+fn is_source_change(line: &str) -> bool {
+    // e.g. "Event 'new' on source #57", "Event 'change' on server #0"
+    line.contains("'new' on source") || line.contains("'change' on server")
+}
+
+/// Mute the current default source unless it already is
+fn mute_default_source_if_live() {
+    let output = std::process::Command::new("wpctl")
+        .args(["get-volume", "@DEFAULT_AUDIO_SOURCE@"])
+        .output();
+    match output {
+        // e.g. "Volume: 0.40 [MUTED]"
+        Ok(out) if out.status.success() => {
+            if !String::from_utf8_lossy(&out.stdout).contains("[MUTED]") {
+                info!("Default audio source changed, muting it");
+                run_wpctl(source_mute_args(true));
+            }
         }
+        Ok(out) => {
+            debug!(
+                "wpctl get-volume failed: {}",
+                String::from_utf8_lossy(&out.stderr)
+            );
+        }
+        Err(e) => {
+            error!("Failed to execute wpctl: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PW_DUMP: &str = r#"[
+        { "id": 31, "type": "PipeWire:Interface:Node",
+          "info": { "props": { "media.class": "Audio/Sink", "node.name": "alsa_output" } } },
+        { "id": 87, "type": "PipeWire:Interface:Node",
+          "info": {
+            "props": {
+              "media.class": "Stream/Output/Audio",
+              "application.name": "Firefox",
+              "application.process.binary": "firefox"
+            },
+            "params": { "Props": [ { "channelVolumes": [0.125, 0.125], "mute": true } ] }
+          } },
+        { "id": 90, "type": "PipeWire:Interface:Node",
+          "info": { "props": { "media.class": "Stream/Output/Audio", "node.name": "mpv" } } },
+        { "id": 12, "type": "PipeWire:Interface:Client", "info": { "props": {} } }
+    ]"#;
+
+    #[test]
+    fn parses_only_playback_streams() {
+        let streams = parse_pw_dump_streams(PW_DUMP.as_bytes()).unwrap();
+        let ids: Vec<u32> = streams.iter().map(|s| s.node_id).collect();
+        assert_eq!(ids, vec![87, 90]);
+    }
+
+    #[test]
+    fn parses_stream_volume_and_names() {
+        let streams = parse_pw_dump_streams(PW_DUMP.as_bytes()).unwrap();
+        let firefox = &streams[0];
+        assert_eq!(firefox.app_name, "Firefox");
+        assert_eq!(firefox.app_id.as_deref(), Some("firefox"));
+        assert!((firefox.volume - 0.5).abs() < 1e-9);
+        assert!(firefox.muted);
+
+        // No Props param: full volume, name from the node
+        let mpv = &streams[1];
+        assert_eq!(mpv.app_name, "mpv");
+        assert_eq!(mpv.app_id, None);
+        assert_eq!(mpv.volume, 1.0);
+        assert!(!mpv.muted);
+    }
+
+    #[test]
+    fn rejects_invalid_output() {
+        assert!(parse_pw_dump_streams(b"not json").is_err());
+    }
+
+    #[test]
+    fn unknown_stream_update_is_an_error() {
+        let manager = AudioManager::default();
+        assert!(manager.set_stream_mute(42, true).is_err());
+        assert!(manager.set_stream_volume(42, 0.5).is_err());
+    }
+
+    #[test]
+    fn unchanged_stream_is_left_alone() {
+        let manager = AudioManager::default();
+        manager.streams.lock().unwrap().streams =
+            parse_pw_dump_streams(PW_DUMP.as_bytes()).unwrap();
+        let notified = Arc::new(Mutex::new(0));
+        let count = notified.clone();
+        manager.subscribe(move |_| *count.lock().unwrap() += 1);
+
+        // Firefox is muted at 0.5 already: nothing to apply or report
+        manager.set_stream_mute(87, true).unwrap();
+        manager.set_stream_volume(87, 0.50001).unwrap();
+        assert_eq!(*notified.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn source_changes_are_detected() {
+        assert!(is_source_change("Event 'new' on source #57"));
+        assert!(is_source_change("Event 'change' on server #0"));
+        assert!(!is_source_change("Event 'change' on source #57"));
+        assert!(!is_source_change("Event 'new' on sink-input #90"));
     }
 }
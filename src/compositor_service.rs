@@ -0,0 +1,541 @@
+//! D-Bus service implementation for `org.otto.Compositor`.
+//!
+//! Lets external tools (status bars, scripts, the settings app) query and
+//! control the compositor: list outputs, windows and workspaces, switch
+//! workspace, focus or close a window, set the wallpaper, take a screenshot,
+//! toggle night light, read the battery state and browse the clipboard
+//! history.
+//!
+//! Requests are forwarded to the main loop as [`CompositorCommand`]s. State
+//! changes come back as [`CompositorEvent`]s and are emitted as signals. The
+//! interface is documented in `docs/developer/compositor-dbus.md`.
+
+use std::hash::{Hash, Hasher};
+
+use smithay::reexports::calloop::channel::Sender;
+use smithay::reexports::wayland_server::backend::ObjectId;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, info, warn};
+use zbus::object_server::SignalContext;
+use zbus::{fdo, interface, Connection};
+
+use crate::config::Config;
+use crate::power::PowerState;
+use crate::screenshare::CompositorCommand;
+
+const COMPOSITOR_PATH: &str = "/org/otto/Compositor";
+
+/// A window as reported over D-Bus.
+#[derive(Debug, Clone)]
+pub struct WindowInfo {
+    /// Opaque handle, see [`window_handle`]
+    pub id: String,
+    pub app_id: String,
+    pub title: String,
+    /// Name of the output the window's workspace belongs to
+    pub output: String,
+    pub workspace: usize,
+    pub focused: bool,
+    pub minimized: bool,
+}
+
+/// A workspace of the primary output as reported over D-Bus.
+#[derive(Debug, Clone)]
+pub struct WorkspaceInfo {
+    pub index: usize,
+    /// Empty for unnamed workspaces
+    pub name: String,
+    pub windows: usize,
+    pub current: bool,
+}
+
+/// A clipboard history entry as reported over D-Bus.
+#[derive(Debug, Clone)]
+pub struct ClipboardEntryInfo {
+    pub id: u32,
+    pub mime_type: String,
+    /// One line of text; empty for images
+    pub preview: String,
+    /// Seconds since the Unix epoch when it was last copied
+    pub timestamp: i64,
+}
+
+/// State changes pushed from the main loop, emitted as signals.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompositorEvent {
+    WorkspaceChanged {
+        index: usize,
+        name: String,
+    },
+    /// `id` is empty when no window has keyboard focus
+    FocusChanged {
+        id: String,
+        app_id: String,
+        title: String,
+    },
+    OutputsChanged {
+        outputs: Vec<String>,
+    },
+    /// `percentage` is negative without a battery
+    PowerChanged {
+        on_battery: bool,
+        percentage: f64,
+        low: bool,
+    },
+    ClipboardHistoryChanged,
+    /// A screencast stream ended on the compositor side, e.g. because its
+    /// window closed; emitted as `Closed` on the stream object.
+    StreamClosed {
+        session_id: String,
+        stream_key: String,
+    },
+}
+
+/// `(on_battery, percentage, low)` of a power state as sent over D-Bus;
+/// `percentage` is `-1` without a battery.
+pub fn power_values(state: &PowerState) -> (bool, f64, bool) {
+    let low = Config::with(|c| state.is_low(c.power_management.low_battery_percent as f64));
+    (state.on_battery, state.percentage.unwrap_or(-1.0), low)
+}
+
+/// Opaque, per-session handle of a window, stable while the window lives.
+///
+/// Wayland object ids are only unique per client, so the handle is derived
+/// from the whole [`ObjectId`].
+pub fn window_handle(id: &ObjectId) -> String {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    id.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Compositor D-Bus interface for health checks, queries and control.
+///
+/// Implements `org.otto.Compositor` at `/org/otto/Compositor`.
+pub struct CompositorInterface {
+    compositor_tx: Sender<CompositorCommand>,
+}
+
+impl CompositorInterface {
+    fn new(compositor_tx: Sender<CompositorCommand>) -> Self {
+        Self { compositor_tx }
+    }
+
+    fn send(&self, cmd: CompositorCommand) -> fdo::Result<()> {
+        self.compositor_tx
+            .send(cmd)
+            .map_err(|e| fdo::Error::Failed(format!("channel send failed: {e}")))
+    }
+
+    /// Send a command built around a response channel and wait for the reply.
+    async fn request<T>(
+        &self,
+        cmd: impl FnOnce(oneshot::Sender<T>) -> CompositorCommand,
+    ) -> fdo::Result<T> {
+        let (tx, rx) = oneshot::channel();
+        self.send(cmd(tx))?;
+        rx.await
+            .map_err(|e| fdo::Error::Failed(format!("response channel error: {e}")))
+    }
+}
+
+#[interface(name = "org.otto.Compositor")]
+impl CompositorInterface {
+    /// Ping method for watchdog health checks.
+    ///
+    /// Returns "pong" if the compositor is responsive.
+    async fn ping(&self) -> fdo::Result<String> {
+        debug!("Ping received from watchdog");
+        Ok("pong".to_string())
+    }
+
+    /// Focus an application window by app_id.
+    ///
+    /// Sends a focus command to the compositor for the given app_id.
+    /// Returns true if the command was dispatched (not whether a window was found).
+    async fn focus_app(&self, app_id: &str) -> fdo::Result<bool> {
+        info!(app_id, "focus_app requested via D-Bus");
+        self.send(CompositorCommand::FocusApp {
+            app_id: app_id.to_string(),
+        })?;
+        Ok(true)
+    }
+
+    /// Lists outputs as `(name, width, height, refresh_mhz)`, in physical pixels.
+    async fn list_outputs(&self) -> fdo::Result<Vec<(String, u32, u32, u32)>> {
+        let outputs = self
+            .request(|response_tx| CompositorCommand::ListOutputs { response_tx })
+            .await?;
+        Ok(outputs
+            .into_iter()
+            .map(|o| (o.name, o.width, o.height, o.refresh_rate))
+            .collect())
+    }
+
+    /// Lists the modes of an output as `(width, height, refresh_hz)`.
+    async fn list_output_modes(&self, output: String) -> fdo::Result<Vec<(u32, u32, f64)>> {
+        let modes = self
+            .request(|response_tx| CompositorCommand::ListOutputModes {
+                output,
+                response_tx,
+            })
+            .await?
+            .map_err(fdo::Error::InvalidArgs)?;
+        Ok(modes
+            .into_iter()
+            .map(|(w, h, refresh)| (w, h, refresh as f64 / 1000.0))
+            .collect())
+    }
+
+    /// Switches an output to another resolution and refresh rate, live.
+    ///
+    /// A `refresh_hz` of 0 picks the fastest mode of that size. Modes the
+    /// display does not support are rejected with the list of those it does.
+    async fn set_output_mode(
+        &self,
+        output: String,
+        width: u32,
+        height: u32,
+        refresh_hz: f64,
+    ) -> fdo::Result<()> {
+        info!(
+            output,
+            width, height, refresh_hz, "set_output_mode requested via D-Bus"
+        );
+        self.request(|response_tx| CompositorCommand::SetOutputMode {
+            output,
+            width,
+            height,
+            refresh_hz: (refresh_hz > 0.0).then_some(refresh_hz),
+            response_tx,
+        })
+        .await?
+        .map_err(fdo::Error::InvalidArgs)
+    }
+
+    /// Creates a virtual output streamed over PipeWire and returns its node id.
+    ///
+    /// An empty `mirror_of` extends the desktop to the right of the origin;
+    /// otherwise the output mirrors the named one.
+    async fn create_virtual_output(
+        &self,
+        name: String,
+        width: u32,
+        height: u32,
+        refresh_hz: f64,
+        mirror_of: String,
+    ) -> fdo::Result<u32> {
+        info!(
+            name,
+            width, height, refresh_hz, "create_virtual_output requested via D-Bus"
+        );
+        let config = crate::config::VirtualOutputConfig {
+            name,
+            resolution: crate::config::DisplayResolution { width, height },
+            refresh_hz: if refresh_hz > 0.0 { refresh_hz } else { 60.0 },
+            position: None,
+            mirror_of: (!mirror_of.is_empty()).then_some(mirror_of),
+        };
+        self.request(|response_tx| CompositorCommand::CreateVirtualOutput {
+            config,
+            response_tx,
+        })
+        .await?
+        .map_err(fdo::Error::InvalidArgs)
+    }
+
+    /// Removes a virtual output created from config or by `CreateVirtualOutput`.
+    async fn remove_virtual_output(&self, name: String) -> fdo::Result<()> {
+        let removed = self
+            .request(|response_tx| CompositorCommand::RemoveVirtualOutput {
+                name: name.clone(),
+                response_tx,
+            })
+            .await?
+            .map_err(fdo::Error::NotSupported)?;
+        if removed {
+            Ok(())
+        } else {
+            Err(fdo::Error::InvalidArgs(format!(
+                "no virtual output named {name}"
+            )))
+        }
+    }
+
+    /// Lists windows as `(id, app_id, title, output, workspace, focused, minimized)`.
+    async fn list_windows(
+        &self,
+    ) -> fdo::Result<Vec<(String, String, String, String, u32, bool, bool)>> {
+        let windows = self
+            .request(|response_tx| CompositorCommand::ListWindows { response_tx })
+            .await?;
+        Ok(windows
+            .into_iter()
+            .map(|w| {
+                (
+                    w.id,
+                    w.app_id,
+                    w.title,
+                    w.output,
+                    w.workspace as u32,
+                    w.focused,
+                    w.minimized,
+                )
+            })
+            .collect())
+    }
+
+    /// Lists the workspaces of the primary output as
+    /// `(index, name, window_count, current)`.
+    async fn list_workspaces(&self) -> fdo::Result<Vec<(u32, String, u32, bool)>> {
+        let workspaces = self
+            .request(|response_tx| CompositorCommand::ListWorkspaces { response_tx })
+            .await?;
+        Ok(workspaces
+            .into_iter()
+            .map(|w| (w.index as u32, w.name, w.windows as u32, w.current))
+            .collect())
+    }
+
+    /// Switches the focused output to the workspace at `index`.
+    async fn switch_workspace(&self, index: u32) -> fdo::Result<()> {
+        let switched = self
+            .request(|response_tx| CompositorCommand::SwitchWorkspace {
+                index: index as usize,
+                response_tx,
+            })
+            .await?;
+        if !switched {
+            return Err(fdo::Error::InvalidArgs(format!("no workspace {index}")));
+        }
+        Ok(())
+    }
+
+    /// Raises and focuses the window with the given id, switching workspace
+    /// or restoring it from the dock as needed.
+    async fn focus_window(&self, id: String) -> fdo::Result<()> {
+        let found = self
+            .request(|response_tx| CompositorCommand::FocusWindow {
+                id: id.clone(),
+                response_tx,
+            })
+            .await?;
+        if !found {
+            return Err(fdo::Error::InvalidArgs(format!("no window {id}")));
+        }
+        Ok(())
+    }
+
+    /// Asks the window with the given id to close.
+    async fn close_window(&self, id: String) -> fdo::Result<()> {
+        let found = self
+            .request(|response_tx| CompositorCommand::CloseWindow {
+                id: id.clone(),
+                response_tx,
+            })
+            .await?;
+        if !found {
+            return Err(fdo::Error::InvalidArgs(format!("no window {id}")));
+        }
+        Ok(())
+    }
+
+    /// Sets the wallpaper to the image at `path`; an empty path removes it.
+    ///
+    /// Saved to the config file like `org.otto.Settings.SetBackgroundImage`.
+    async fn set_wallpaper(&self, path: String) -> fdo::Result<()> {
+        crate::settings_service::save_background_image(path)
+    }
+
+    /// Starts an interactive region screenshot, saved to the pictures folder.
+    async fn screenshot(&self) -> fdo::Result<()> {
+        self.send(CompositorCommand::Screenshot)
+    }
+
+    /// Opens the source picker and records the picked output, window or
+    /// region to a video file.
+    async fn start_recording(&self) -> fdo::Result<()> {
+        self.send(CompositorCommand::StartScreenRecording)
+    }
+
+    /// Stops the screen recording; the file is finalized in the background.
+    async fn stop_recording(&self) -> fdo::Result<()> {
+        self.send(CompositorCommand::StopScreenRecording)
+    }
+
+    /// Turns night light on or off until its schedule next changes.
+    async fn toggle_night_light(&self) -> fdo::Result<()> {
+        self.send(CompositorCommand::ToggleNightLight)
+    }
+
+    /// Switches between the light and dark scheme and saves it to the config.
+    async fn toggle_dark_mode(&self) -> fdo::Result<()> {
+        self.send(CompositorCommand::ToggleDarkMode)
+    }
+
+    /// The power source and battery charge as `(on_battery, percentage, low)`.
+    ///
+    /// `percentage` is `-1` without a battery; `low` follows
+    /// `low_battery_percent`. Fails when UPower is not running.
+    async fn get_power_state(&self) -> fdo::Result<(bool, f64, bool)> {
+        let state = self
+            .request(|response_tx| CompositorCommand::GetPowerState { response_tx })
+            .await?
+            .ok_or_else(|| fdo::Error::NotSupported("UPower is not available".into()))?;
+        Ok(power_values(&state))
+    }
+
+    /// Lists the clipboard history, most recent first, as
+    /// `(id, mime_type, preview, timestamp)`.
+    ///
+    /// `preview` is one line of text, empty for images; `timestamp` is in
+    /// seconds since the Unix epoch.
+    async fn list_clipboard_history(&self) -> fdo::Result<Vec<(u32, String, String, i64)>> {
+        let entries = self
+            .request(|response_tx| CompositorCommand::ListClipboardHistory { response_tx })
+            .await?;
+        Ok(entries
+            .into_iter()
+            .map(|e| (e.id, e.mime_type, e.preview, e.timestamp))
+            .collect())
+    }
+
+    /// The mime type and content of a clipboard history entry.
+    async fn get_clipboard_entry(&self, id: u32) -> fdo::Result<(String, Vec<u8>)> {
+        self.request(|response_tx| CompositorCommand::GetClipboardEntry { id, response_tx })
+            .await?
+            .ok_or_else(|| fdo::Error::InvalidArgs(format!("no clipboard entry {id}")))
+    }
+
+    /// Makes a clipboard history entry the clipboard selection again.
+    async fn apply_clipboard_entry(&self, id: u32) -> fdo::Result<()> {
+        let found = self
+            .request(|response_tx| CompositorCommand::ApplyClipboardEntry { id, response_tx })
+            .await?;
+        if !found {
+            return Err(fdo::Error::InvalidArgs(format!("no clipboard entry {id}")));
+        }
+        Ok(())
+    }
+
+    /// Forgets a clipboard history entry.
+    async fn remove_clipboard_entry(&self, id: u32) -> fdo::Result<()> {
+        let found = self
+            .request(|response_tx| CompositorCommand::RemoveClipboardEntry { id, response_tx })
+            .await?;
+        if !found {
+            return Err(fdo::Error::InvalidArgs(format!("no clipboard entry {id}")));
+        }
+        Ok(())
+    }
+
+    /// Forgets the whole clipboard history.
+    async fn clear_clipboard_history(&self) -> fdo::Result<()> {
+        self.send(CompositorCommand::ClearClipboardHistory)
+    }
+
+    /// Opens the clipboard history picker, or closes it when shown.
+    async fn show_clipboard_history(&self) -> fdo::Result<()> {
+        self.send(CompositorCommand::ShowClipboardHistory)
+    }
+
+    /// The current workspace of the primary output changed.
+    #[zbus(signal)]
+    async fn workspace_changed(
+        ctxt: &SignalContext<'_>,
+        index: u32,
+        name: &str,
+    ) -> zbus::Result<()>;
+
+    /// Keyboard focus moved to another window; `id` is empty when no window
+    /// has focus.
+    #[zbus(signal)]
+    async fn focus_changed(
+        ctxt: &SignalContext<'_>,
+        id: &str,
+        app_id: &str,
+        title: &str,
+    ) -> zbus::Result<()>;
+
+    /// An output was connected or disconnected.
+    #[zbus(signal)]
+    async fn outputs_changed(ctxt: &SignalContext<'_>, outputs: Vec<String>) -> zbus::Result<()>;
+
+    /// Switched between AC and battery, or the battery charge changed.
+    #[zbus(signal)]
+    async fn power_changed(
+        ctxt: &SignalContext<'_>,
+        on_battery: bool,
+        percentage: f64,
+        low: bool,
+    ) -> zbus::Result<()>;
+
+    /// An entry was added to, moved in or removed from the clipboard history.
+    #[zbus(signal)]
+    async fn clipboard_history_changed(ctxt: &SignalContext<'_>) -> zbus::Result<()>;
+}
+
+/// Registers the Compositor interface on the existing D-Bus connection.
+pub async fn register_compositor_interface(
+    connection: &Connection,
+    compositor_tx: Sender<CompositorCommand>,
+) -> zbus::Result<()> {
+    connection
+        .object_server()
+        .at(COMPOSITOR_PATH, CompositorInterface::new(compositor_tx))
+        .await?;
+
+    connection.request_name("org.otto.Compositor").await?;
+
+    info!("Compositor D-Bus interface registered at org.otto.Compositor");
+
+    Ok(())
+}
+
+/// Emits every event pushed by the main loop as a signal, until the
+/// compositor drops its sender.
+pub async fn emit_events(
+    connection: &Connection,
+    mut events: mpsc::UnboundedReceiver<CompositorEvent>,
+) {
+    let iface = match connection
+        .object_server()
+        .interface::<_, CompositorInterface>(COMPOSITOR_PATH)
+        .await
+    {
+        Ok(iface) => iface,
+        Err(e) => {
+            warn!("Compositor interface missing, signals disabled: {e}");
+            return;
+        }
+    };
+    let ctxt = iface.signal_context();
+
+    while let Some(event) = events.recv().await {
+        let result = match &event {
+            CompositorEvent::WorkspaceChanged { index, name } => {
+                CompositorInterface::workspace_changed(ctxt, *index as u32, name).await
+            }
+            CompositorEvent::FocusChanged { id, app_id, title } => {
+                CompositorInterface::focus_changed(ctxt, id, app_id, title).await
+            }
+            CompositorEvent::OutputsChanged { outputs } => {
+                CompositorInterface::outputs_changed(ctxt, outputs.clone()).await
+            }
+            CompositorEvent::PowerChanged {
+                on_battery,
+                percentage,
+                low,
+            } => CompositorInterface::power_changed(ctxt, *on_battery, *percentage, *low).await,
+            CompositorEvent::ClipboardHistoryChanged => {
+                CompositorInterface::clipboard_history_changed(ctxt).await
+            }
+            CompositorEvent::StreamClosed {
+                session_id,
+                stream_key,
+            } => crate::screenshare::close_stream(connection, session_id, stream_key).await,
+        };
+        if let Err(e) = result {
+            warn!(?event, "Failed to emit compositor signal: {e}");
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Config validation for `otto --check-config` and the startup warnings.
+//!
+//! Loading merges every file over the defaults and falls back to them on a
+//! bad value, so a mistyped key or a value of the wrong type goes unnoticed.
+//! Here each file is checked on its own, merged over the defaults the same
+//! way: syntax errors, values that don't deserialize and keys no setting
+//! reads are reported with the file and, where it can be found, the line.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use tracing::warn;
+
+use super::{config_file_candidates, merge_value, Config};
+
+/// A problem found in a config file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub file: PathBuf,
+    /// 1-based line, when the issue could be located
+    pub line: Option<usize>,
+    pub kind: ConfigIssueKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigIssueKind {
+    /// The file is not valid TOML, or could not be read
+    Syntax,
+    /// A value has the wrong type or is out of range
+    InvalidValue,
+    /// A key that no setting reads, most likely a typo
+    UnknownKey,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.file.display(), line, self.message),
+            None => write!(f, "{}: {}", self.file.display(), self.message),
+        }
+    }
+}
+
+/// Check every config file that exists, in load order
+pub fn check_config_files() -> Vec<ConfigIssue> {
+    config_file_candidates()
+        .into_iter()
+        .filter(|path| path.exists())
+        .flat_map(|path| check_file(&path))
+        .collect()
+}
+
+/// Check a single config file
+pub fn check_file(path: &Path) -> Vec<ConfigIssue> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => check_content(path, &content),
+        Err(err) => vec![ConfigIssue {
+            file: path.to_path_buf(),
+            line: None,
+            kind: ConfigIssueKind::Syntax,
+            message: err.to_string(),
+        }],
+    }
+}
+
+/// Warn about the unknown keys of a file that was loaded; syntax and value
+/// errors are already reported by the loader.
+pub(super) fn warn_unknown_keys(path: &Path, content: &str) {
+    for issue in check_content(path, content) {
+        if issue.kind == ConfigIssueKind::UnknownKey {
+            warn!("{issue}");
+        }
+    }
+}
+
+fn check_content(file: &Path, content: &str) -> Vec<ConfigIssue> {
+    let issue = |line, kind, message| ConfigIssue {
+        file: file.to_path_buf(),
+        line,
+        kind,
+        message,
+    };
+
+    let value = match content.parse::<toml::Value>() {
+        Ok(value) => value,
+        Err(err) => {
+            let line = err.span().map(|span| line_of_offset(content, span.start));
+            return vec![issue(
+                line,
+                ConfigIssueKind::Syntax,
+                err.message().to_string(),
+            )];
+        }
+    };
+
+    let mut merged =
+        toml::Value::try_from(Config::default()).expect("default config is always valid toml");
+    merge_value(&mut merged, value);
+
+    let mut unknown = Vec::new();
+    let result: Result<Config, _> =
+        serde_ignored::deserialize(merged, |path| unknown.push(path.to_string()));
+
+    let mut issues: Vec<ConfigIssue> = unknown
+        .into_iter()
+        .map(|path| {
+            issue(
+                key_line(content, &path),
+                ConfigIssueKind::UnknownKey,
+                format!("unknown key `{path}`"),
+            )
+        })
+        .collect();
+
+    if let Err(err) = result {
+        // The file on its own keeps the spans the merged value lost; its
+        // missing fields are filled in by the defaults when loading
+        let spanned = toml::from_str::<Config>(content)
+            .err()
+            .filter(|err| err.span().is_some() && !err.message().starts_with("missing field"));
+        issues.push(match spanned {
+            Some(err) => issue(
+                err.span().map(|span| line_of_offset(content, span.start)),
+                ConfigIssueKind::InvalidValue,
+                err.message().to_string(),
+            ),
+            None => issue(None, ConfigIssueKind::InvalidValue, err.to_string()),
+        });
+    }
+    issues
+}
+
+fn line_of_offset(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+/// Line defining the key at `path` (`dock.size`, `window_rules.0.app_id`),
+/// either as `key = ...` inside its table or as a `[table.key]` header
+fn key_line(content: &str, path: &str) -> Option<usize> {
+    let segments: Vec<&str> = path
+        .split('.')
+        .filter(|segment| *segment != "?" && segment.parse::<usize>().is_err())
+        .collect();
+    let (key, parents) = segments.split_last()?;
+    let parent = parents.join(".");
+
+    let mut in_parent = parent.is_empty();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            let header = line.trim_matches(|c| c == '[' || c == ']').trim();
+            if header.rsplit('.').next() == Some(*key) && header.starts_with(parent.as_str()) {
+                return Some(index + 1);
+            }
+            in_parent = !parent.is_empty() && header == parent;
+            continue;
+        }
+        let defines_key = line
+            .trim_start_matches('"')
+            .strip_prefix(*key)
+            .map(|rest| rest.trim_start_matches('"').trim_start())
+            .is_some_and(|rest| rest.starts_with('=') || rest.starts_with('.'));
+        if in_parent && defines_key {
+            return Some(index + 1);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(content: &str) -> Vec<ConfigIssue> {
+        check_content(Path::new("config.toml"), content)
+    }
+
+    #[test]
+    fn valid_config_has_no_issues() {
+        assert!(check("screen_scale = 1.5\n\n[dock]\nsize = 64.0\n").is_empty());
+    }
+
+    #[test]
+    fn unknown_keys_are_reported_with_their_line() {
+        let issues = check("scale = 1.5\n\n[dock]\nsize = 64.0\nautohid = true\n");
+        let reported: Vec<_> = issues
+            .iter()
+            .map(|issue| (issue.kind, issue.line, issue.message.as_str()))
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                (ConfigIssueKind::UnknownKey, Some(1), "unknown key `scale`"),
+                (
+                    ConfigIssueKind::UnknownKey,
+                    Some(5),
+                    "unknown key `dock.autohid`"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn type_mismatch_is_reported_with_its_line() {
+        let issues = check("screen_scale = 1.0\n\n[dock]\nsize = \"big\"\n");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, ConfigIssueKind::InvalidValue);
+        assert_eq!(issues[0].line, Some(4));
+    }
+
+    #[test]
+    fn syntax_error_is_reported_with_its_line() {
+        let issues = check("screen_scale = 1.0\ndock = [\n");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, ConfigIssueKind::Syntax);
+        assert!(issues[0].line.is_some());
+    }
+}
@@ -1,17 +1,25 @@
 use std::collections::BTreeMap;
-use std::path::PathBuf;
-use std::sync::OnceLock;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 
+use layers::prelude::{TimingFunction, Transition};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+pub mod check;
 pub mod default_apps;
 pub mod shortcuts;
+pub mod window_rules;
 
-use shortcuts::{build_bindings, RunCommandConfig, ShortcutBinding, ShortcutMap};
+use shortcuts::{
+    build_bindings, build_pointer_bindings, parse_trigger, PointerBinding, RunCommandConfig,
+    ShortcutBinding, ShortcutMap, ShortcutTrigger,
+};
 use toml::map::Entry;
 use tracing::warn;
+use window_rules::WindowRule;
 
 use crate::theme::ThemeScheme;
+use crate::workspaces::WorkspaceLayout;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -22,6 +30,13 @@ pub struct Config {
     pub cursor_theme: String,
     pub icon_theme: Option<String>,
     pub cursor_size: u32,
+    /// Hide the cursor on key presses until the pointer is used again
+    #[serde(default)]
+    pub hide_cursor_when_typing: bool,
+    /// Hide the cursor after this many milliseconds without pointer input;
+    /// 0 keeps it shown
+    #[serde(default)]
+    pub hide_cursor_idle_ms: u64,
     #[serde(default)]
     pub input: InputConfig,
     #[serde(default)]
@@ -31,11 +46,27 @@ pub struct Config {
     #[serde(default)]
     pub power_management: PowerManagementConfig,
     #[serde(default)]
+    pub security: SecurityConfig,
+    #[serde(default)]
     pub audio: AudioConfig,
+    #[serde(default)]
+    pub night_light: NightLightConfig,
+    #[serde(default)]
+    pub backlight: BacklightConfig,
+    #[serde(default)]
+    pub zoom: ZoomConfig,
+    #[serde(default)]
+    pub window_border: WindowBorderConfig,
+    #[serde(default)]
+    pub screen_recording: ScreenRecordingConfig,
     pub font_family: String,
     pub keyboard_repeat_delay: i32,
     pub keyboard_repeat_rate: i32,
     pub theme_scheme: ThemeScheme,
+    /// TOML or JSON file overriding the theme colors, accent and material
+    /// blur; relative paths are looked up next to the user config file
+    #[serde(default)]
+    pub theme_file: Option<String>,
     pub gtk_theme: Option<String>,
     pub background_image: String,
     pub background_color: String,
@@ -45,10 +76,54 @@ pub struct Config {
     pub accent_color: String,
     #[serde(default = "shortcuts::default_shortcut_map")]
     pub keyboard_shortcuts: ShortcutMap,
+    /// Pointer buttons and scroll directions, with the same actions as
+    /// `keyboard_shortcuts`
+    #[serde(default)]
+    pub pointer_shortcuts: ShortcutMap,
+    /// Chord that, pressed twice in a row, takes shortcuts back from a
+    /// client that inhibits them (VMs, remote desktops)
+    #[serde(default = "default_shortcuts_inhibit_escape")]
+    pub shortcuts_inhibit_escape: String,
     #[serde(default)]
     pub virtual_outputs: Vec<VirtualOutputConfig>,
     #[serde(default)]
+    pub window_rules: Vec<WindowRule>,
+    #[serde(default)]
+    pub tiling: TilingConfig,
+    #[serde(default)]
+    pub workspaces: WorkspacesConfig,
+    #[serde(default)]
     pub occlusion_culling: bool,
+    /// Let fullscreen clients that request it through `wp_tearing_control_v1`
+    /// present without waiting for vblank
+    #[serde(default)]
+    pub allow_tearing: bool,
+    /// Turn VRR on, where the display supports it, while a fullscreen window
+    /// declaring the `game` content type (`wp_content_type_v1`) is scanned out
+    #[serde(default)]
+    pub game_vrr: bool,
+    /// Opacity of windows without keyboard focus, 0.0 - 1.0; 1.0 keeps them
+    /// opaque
+    #[serde(
+        default = "default_inactive_opacity",
+        deserialize_with = "deserialize_inactive_opacity"
+    )]
+    pub inactive_opacity: f32,
+    /// Apply dock, workspace, window and surface style changes instantly
+    /// instead of animating them, and skip the genie minimize animation
+    #[serde(default)]
+    pub reduce_motion: bool,
+    /// Corner radius, in logical pixels, Otto clips every window to; 0.0
+    /// leaves the corners to the client. Maximized and fullscreen windows stay
+    /// square.
+    #[serde(default)]
+    pub window_corner_radius: f32,
+    /// Keep the last `clipboard_history_size` text and image selections, in
+    /// memory, for the ClipboardHistory picker
+    #[serde(default = "default_clipboard_history_enabled")]
+    pub clipboard_history_enabled: bool,
+    #[serde(default = "default_clipboard_history_size")]
+    pub clipboard_history_size: usize,
     #[serde(default)]
     pub exec_once: Vec<RunCommandConfig>,
     #[serde(default)]
@@ -58,9 +133,20 @@ pub struct Config {
     #[serde(skip)]
     #[serde(default)]
     shortcut_bindings: Vec<ShortcutBinding>,
+    #[serde(skip)]
+    #[serde(default)]
+    pointer_bindings: Vec<PointerBinding>,
+    #[serde(skip)]
+    #[serde(default)]
+    shortcuts_inhibit_escape_trigger: Option<ShortcutTrigger>,
 }
 
-static CONFIG: OnceLock<Config> = OnceLock::new();
+static CONFIG: OnceLock<RwLock<Arc<Config>>> = OnceLock::new();
+
+/// Held while the config file is rewritten, so that saves coming from the
+/// compositor (dock, workspaces) and from the settings service never
+/// interleave.
+static SAVE_LOCK: Mutex<()> = Mutex::new(());
 
 impl Default for Config {
     fn default() -> Self {
@@ -70,15 +156,24 @@ impl Default for Config {
             cursor_theme: "Notwaita-Black".to_string(),
             icon_theme: None,
             cursor_size: 24,
+            hide_cursor_when_typing: false,
+            hide_cursor_idle_ms: 0,
             input: InputConfig::default(),
             dock: DockConfig::default(),
             layer_shell: LayerShellConfig::default(),
             power_management: PowerManagementConfig::default(),
+            security: SecurityConfig::default(),
             audio: AudioConfig::default(),
+            night_light: NightLightConfig::default(),
+            backlight: BacklightConfig::default(),
+            zoom: ZoomConfig::default(),
+            window_border: WindowBorderConfig::default(),
+            screen_recording: ScreenRecordingConfig::default(),
             font_family: "Inter".to_string(),
             keyboard_repeat_delay: 300,
             keyboard_repeat_rate: 30,
             theme_scheme: ThemeScheme::Light,
+            theme_file: None,
             gtk_theme: None,
             background_image: "".to_string(),
             background_color: "#1a1a2e".to_string(),
@@ -86,9 +181,23 @@ impl Default for Config {
             use_10bit_color: false,
             accent_color: default_accent_color(),
             keyboard_shortcuts: shortcuts::default_shortcut_map(),
+            pointer_shortcuts: ShortcutMap::new(),
+            shortcuts_inhibit_escape: default_shortcuts_inhibit_escape(),
             shortcut_bindings: Vec::new(),
+            pointer_bindings: Vec::new(),
+            shortcuts_inhibit_escape_trigger: None,
             virtual_outputs: Vec::new(),
+            window_rules: Vec::new(),
+            tiling: TilingConfig::default(),
+            workspaces: WorkspacesConfig::default(),
             occlusion_culling: false,
+            allow_tearing: false,
+            game_vrr: false,
+            inactive_opacity: default_inactive_opacity(),
+            reduce_motion: false,
+            window_corner_radius: 0.0,
+            clipboard_history_enabled: default_clipboard_history_enabled(),
+            clipboard_history_size: default_clipboard_history_size(),
             exec_once: Vec::new(),
             xdg_autostart: false,
             systemd_notify: false,
@@ -101,10 +210,155 @@ pub const WINIT_DISPLAY_ID: &str = "winit";
 
 impl Config {
     pub fn with<R>(f: impl FnOnce(&Config) -> R) -> R {
-        let config = CONFIG.get_or_init(Config::init);
-        f(config)
+        let config = Self::current();
+        f(&config)
+    }
+
+    /// Whether animations are off, through `reduce_motion` or because the
+    /// battery is low (see `[power_management] low_battery_reduce_motion`)
+    pub fn reduce_motion() -> bool {
+        Self::with(|c| c.reduce_motion) || crate::power::low_battery_reduce_motion()
+    }
+
+    /// `transition`, or an instant one when `reduce_motion` is set. Changes
+    /// scheduled with it still apply through the animation engine, on the next
+    /// frame, so completion callbacks keep firing.
+    pub fn transition_or_instant(transition: Transition) -> Transition {
+        if Self::reduce_motion() {
+            Transition {
+                delay: 0.0,
+                timing: TimingFunction::linear(0.0),
+            }
+        } else {
+            transition
+        }
+    }
+
+    /// The active config. A reload swaps in a new `Arc`; holders of the old
+    /// one keep a consistent snapshot.
+    pub fn current() -> Arc<Config> {
+        CONFIG
+            .get_or_init(|| RwLock::new(Arc::new(Config::init())))
+            .read()
+            .unwrap()
+            .clone()
+    }
+
+    /// Re-read all config files and make the result the active config.
+    ///
+    /// Returns the previous config, so the caller can apply what changed, or
+    /// `None` when a file is invalid and the active config was kept.
+    pub fn reload() -> Option<Arc<Config>> {
+        let config = Arc::new(Self::from_files(true)?);
+        let lock = CONFIG.get_or_init(|| RwLock::new(config.clone()));
+        Some(std::mem::replace(&mut *lock.write().unwrap(), config))
     }
+
+    /// Save a copy of the active config changed by `f`.
+    ///
+    /// Only the keys `f` changed are written into the writable config file;
+    /// everything else in it is kept, so defaults and values coming from other
+    /// config files are not copied over. The running compositor picks the
+    /// change up through the config file watcher, not from this call.
+    pub fn save_with(f: impl FnOnce(&mut Config)) -> std::io::Result<()> {
+        let current = Self::current();
+        let mut config = (*current).clone();
+        f(&mut config);
+        let before = toml::Table::try_from(&*current).map_err(std::io::Error::other)?;
+        let after = toml::Table::try_from(&config).map_err(std::io::Error::other)?;
+        if before == after {
+            return Ok(());
+        }
+        update_config_file(&writable_config_path(), |doc| {
+            apply_changes(doc, &before, &after)
+        })
+    }
+
+    /// The theme file, with `~` expanded and relative paths resolved next to
+    /// the user config file
+    pub fn theme_file_path(&self) -> Option<PathBuf> {
+        Self::resolve_theme_file(self.theme_file.as_deref()?)
+    }
+
+    /// Where `theme_file = "<theme_file>"` points, as for [`Self::theme_file_path`]
+    pub fn resolve_theme_file(theme_file: &str) -> Option<PathBuf> {
+        let path = expand_home(theme_file, std::env::var("HOME").ok());
+        if path.is_absolute() {
+            return Some(path);
+        }
+        let config_dir = user_config_location()?.parent()?.to_path_buf();
+        Some(config_dir.join(path))
+    }
+
+    /// Directory StartRecording saves videos to: `screen_recording.directory`,
+    /// else `$XDG_VIDEOS_DIR`, else `~/Videos`
+    pub fn recordings_dir(&self) -> Option<PathBuf> {
+        let home = std::env::var("HOME").ok();
+        if let Some(directory) = &self.screen_recording.directory {
+            return Some(expand_home(directory, home));
+        }
+        std::env::var_os("XDG_VIDEOS_DIR")
+            .map(PathBuf::from)
+            .or_else(|| home.map(|home| Path::new(&home).join("Videos")))
+    }
+
+    /// Settings that differ in `new` but are only read at startup.
+    pub fn restart_required_changes(&self, new: &Config) -> Vec<&'static str> {
+        [
+            ("displays", differs(&self.displays, &new.displays)),
+            (
+                "virtual_outputs",
+                differs(&self.virtual_outputs, &new.virtual_outputs),
+            ),
+            (
+                "use_10bit_color",
+                self.use_10bit_color != new.use_10bit_color,
+            ),
+            ("icon_theme", self.icon_theme != new.icon_theme),
+            ("gtk_theme", self.gtk_theme != new.gtk_theme),
+            ("font_family", self.font_family != new.font_family),
+            ("locales", self.locales != new.locales),
+        ]
+        .into_iter()
+        .filter_map(|(name, changed)| changed.then_some(name))
+        .collect()
+    }
+
     fn init() -> Self {
+        let config = Self::load();
+
+        // Environment variables for Wayland session
+        std::env::set_var("XDG_SESSION_TYPE", "wayland");
+        std::env::set_var("XDG_CURRENT_DESKTOP", "otto");
+
+        tracing::info!("Config initialized: {:#?}", config.theme_scheme);
+        config
+    }
+
+    /// On first run, leave a commented starter config where users look for
+    /// it. Called by the compositor at startup rather than from `load`, so
+    /// tests reading the config never write to the user's config directory.
+    pub fn write_starter_config_on_first_run() {
+        let found_any_config = get_system_config_path().is_some()
+            || get_user_config_path().is_some()
+            || std::path::Path::new("otto_config.toml").exists()
+            || std::env::var("OTTO_BACKEND").is_ok_and(|backend| {
+                backend_override_candidates(&backend)
+                    .iter()
+                    .any(|candidate| std::path::Path::new(candidate).exists())
+            });
+        if !found_any_config {
+            write_starter_config();
+        }
+    }
+
+    fn load() -> Self {
+        Self::from_files(false).unwrap_or_default()
+    }
+
+    /// Merge the config files in priority order. With `strict`, any file that
+    /// fails to parse aborts the load instead of being skipped.
+    fn from_files(strict: bool) -> Option<Self> {
         let mut merged =
             toml::Value::try_from(Self::default()).expect("default config is always valid toml");
 
@@ -116,11 +370,17 @@ impl Config {
             if let Ok(content) = std::fs::read_to_string(&system_config) {
                 match content.parse::<toml::Value>() {
                     Ok(value) => {
+                        check::warn_unknown_keys(&system_config, &content);
                         merge_value(&mut merged, value);
                         found_any_config = true;
                         tracing::info!("Loaded system config from {}", system_config.display());
                     }
-                    Err(err) => warn!("Failed to parse {}: {err}", system_config.display()),
+                    Err(err) => {
+                        warn!("Failed to parse {}: {err}", system_config.display());
+                        if strict {
+                            return None;
+                        }
+                    }
                 }
             }
         }
@@ -130,11 +390,17 @@ impl Config {
             if let Ok(content) = std::fs::read_to_string(&user_config) {
                 match content.parse::<toml::Value>() {
                     Ok(value) => {
+                        check::warn_unknown_keys(&user_config, &content);
                         merge_value(&mut merged, value);
                         found_any_config = true;
                         tracing::info!("Loaded user config from {}", user_config.display());
                     }
-                    Err(err) => warn!("Failed to parse {}: {err}", user_config.display()),
+                    Err(err) => {
+                        warn!("Failed to parse {}: {err}", user_config.display());
+                        if strict {
+                            return None;
+                        }
+                    }
                 }
             }
         }
@@ -143,11 +409,17 @@ impl Config {
         if let Ok(content) = std::fs::read_to_string("otto_config.toml") {
             match content.parse::<toml::Value>() {
                 Ok(value) => {
+                    check::warn_unknown_keys(std::path::Path::new("otto_config.toml"), &content);
                     merge_value(&mut merged, value);
                     found_any_config = true;
                     tracing::info!("Loaded local config from ./otto_config.toml");
                 }
-                Err(err) => warn!("Failed to parse otto_config.toml: {err}"),
+                Err(err) => {
+                    warn!("Failed to parse otto_config.toml: {err}");
+                    if strict {
+                        return None;
+                    }
+                }
             }
         }
 
@@ -158,6 +430,7 @@ impl Config {
                 if let Ok(content) = std::fs::read_to_string(&candidate) {
                     match content.parse::<toml::Value>() {
                         Ok(value) => {
+                            check::warn_unknown_keys(std::path::Path::new(&candidate), &content);
                             merge_value(&mut merged, value);
                             found_any_config = true;
                             tracing::info!("Loaded backend override config from {}", &candidate);
@@ -165,40 +438,64 @@ impl Config {
                         }
                         Err(err) => {
                             warn!("Failed to parse {candidate}: {err}");
+                            if strict {
+                                return None;
+                            }
                         }
                     }
                 }
             }
         }
 
-        // If no config was found, copy example config to current directory
         if !found_any_config {
             warn!("No configuration file found, using default config");
         }
 
-        let mut config: Config = merged.try_into().unwrap_or_else(|err| {
-            warn!("Falling back to default config due to invalid overrides: {err}");
-            Self::default()
-        });
+        let mut config: Config = match merged.try_into() {
+            Ok(config) => config,
+            Err(err) if strict => {
+                warn!("Ignoring invalid config: {err}");
+                return None;
+            }
+            Err(err) => {
+                warn!("Falling back to default config due to invalid overrides: {err}");
+                Self::default()
+            }
+        };
 
         config.rebuild_shortcut_bindings();
-
-        // Environment variables for Wayland session
-        std::env::set_var("XDG_SESSION_TYPE", "wayland");
-        std::env::set_var("XDG_CURRENT_DESKTOP", "otto");
-
-        tracing::info!("Config initialized: {:#?}", config.theme_scheme);
-        config
+        Some(config)
     }
 
     fn rebuild_shortcut_bindings(&mut self) {
         self.shortcut_bindings = build_bindings(&self.keyboard_shortcuts);
+        self.pointer_bindings = build_pointer_bindings(&self.pointer_shortcuts);
+        self.shortcuts_inhibit_escape_trigger = match parse_trigger(&self.shortcuts_inhibit_escape)
+        {
+            Ok(trigger) => Some(trigger),
+            Err(err) => {
+                warn!(
+                    trigger = %self.shortcuts_inhibit_escape,
+                    error = %err,
+                    "invalid shortcuts_inhibit_escape, shortcut inhibitors cannot be escaped"
+                );
+                None
+            }
+        };
     }
 
     pub fn shortcut_bindings(&self) -> &[ShortcutBinding] {
         &self.shortcut_bindings
     }
 
+    pub fn pointer_bindings(&self) -> &[PointerBinding] {
+        &self.pointer_bindings
+    }
+
+    pub fn shortcuts_inhibit_escape_trigger(&self) -> Option<&ShortcutTrigger> {
+        self.shortcuts_inhibit_escape_trigger.as_ref()
+    }
+
     pub fn resolve_display_profile(
         &self,
         name: &str,
@@ -208,6 +505,12 @@ impl Config {
     }
 }
 
+/// Whether two config values serialize differently; for sections that don't
+/// implement `PartialEq`.
+pub fn differs<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() != serde_json::to_value(b).ok()
+}
+
 fn merge_value(base: &mut toml::Value, overrides: toml::Value) {
     match (base, overrides) {
         (toml::Value::Table(base_map), toml::Value::Table(override_map)) => {
@@ -235,7 +538,7 @@ fn get_system_config_path() -> Option<PathBuf> {
     }
 }
 
-fn get_user_config_path() -> Option<PathBuf> {
+fn user_config_location() -> Option<PathBuf> {
     let config_dir = std::env::var("XDG_CONFIG_HOME")
         .ok()
         .map(PathBuf::from)
@@ -245,12 +548,145 @@ fn get_user_config_path() -> Option<PathBuf> {
                 .map(|home| PathBuf::from(home).join(".config"))
         })?;
 
-    let path = config_dir.join("otto").join("config.toml");
-    if path.exists() {
-        Some(path)
-    } else {
-        None
+    Some(config_dir.join("otto").join("config.toml"))
+}
+
+fn get_user_config_path() -> Option<PathBuf> {
+    user_config_location().filter(|path| path.exists())
+}
+
+/// Write the default config, with a comment above each setting, to the
+/// user config location. An existing file is never overwritten.
+fn write_starter_config() {
+    let Some(path) = user_config_location() else {
+        return;
+    };
+    let result = starter_config().and_then(|content| {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        std::io::Write::write_all(&mut file, content.as_bytes())
+    });
+    match result {
+        Ok(()) => tracing::info!("Wrote a starter config to {}", path.display()),
+        Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {}
+        Err(err) => warn!(
+            "Failed to write starter config to {}: {err}",
+            path.display()
+        ),
+    }
+}
+
+/// `Config::default()` as a commented TOML document
+fn starter_config() -> std::io::Result<String> {
+    let table = toml::Table::try_from(Config::default()).map_err(std::io::Error::other)?;
+    // Plain keys must come before the first [section] header
+    let (sections, keys): (Vec<_>, Vec<_>) =
+        table.into_iter().partition(|(_, value)| match value {
+            toml::Value::Table(_) => true,
+            toml::Value::Array(items) => {
+                !items.is_empty() && items.iter().all(toml::Value::is_table)
+            }
+            _ => false,
+        });
+
+    let mut out = String::from(
+        "# Otto configuration, generated with the default values on first run.\n\
+         # Changes are applied live; display, color depth, icon/GTK theme, font\n\
+         # and locale settings need a restart. See otto_config.example.toml for\n\
+         # every option, including display profiles and window rules.\n",
+    );
+    for (key, value) in keys.into_iter().chain(sections) {
+        out.push('\n');
+        if let Some(comment) = starter_comment(&key) {
+            for line in comment.lines() {
+                out.push_str("# ");
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+        let entry = toml::Table::from_iter([(key, value)]);
+        out.push_str(&toml::to_string_pretty(&entry).map_err(std::io::Error::other)?);
+    }
+    Ok(out)
+}
+
+/// Comment written above a top-level setting of the starter config
+fn starter_comment(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "screen_scale" => "Default scale of outputs without a display profile",
+        "displays" => "Per-display mode, scale, position and transform profiles",
+        "cursor_theme" | "cursor_size" => "Cursor theme and size, in logical pixels",
+        "hide_cursor_when_typing" => "Hide the cursor while typing, until the pointer moves",
+        "hide_cursor_idle_ms" => "Hide the cursor after this long without pointer input; 0 never",
+        "icon_theme" => "Icon theme for the dock and app switcher; unset follows the system",
+        "input" => "Keyboard layout, touchpad and pointer options",
+        "dock" => "Dock size, magnification, autohide, pinned apps and stacks",
+        "layer_shell" => "Largest exclusive zone panels and bars may reserve on each edge",
+        "power_management" => "Lid switch handling and battery saving",
+        "security" => "Clients allowed to use privileged protocols",
+        "audio" => "Volume feedback sound and microphone indicator",
+        "night_light" => "Warmer colors at night, on a schedule or from sunset to sunrise",
+        "backlight" => "Step and lowest level of the BrightnessUp and BrightnessDown actions",
+        "zoom" => "Screen magnifier levels and how it follows the pointer",
+        "window_border" => "Border drawn around every window; 0 width disables it",
+        "screen_recording" => "Format, framerate and bitrate of StartRecording videos",
+        "font_family" => "Font of the compositor UI",
+        "keyboard_repeat_delay" | "keyboard_repeat_rate" => {
+            "Key repeat delay (ms) and rate (keys per second)"
+        }
+        "theme_scheme" => "Light, Dark, or Auto: dark at night, by the night light schedule",
+        "theme_file" => "TOML or JSON file overriding theme colors, accent and material blur",
+        "gtk_theme" => "GTK theme exported to apps; unset follows the system",
+        "background_image" | "background_color" => {
+            "Wallpaper image path, and the color shown when it is unset or missing"
+        }
+        "locales" => "Language preferences for app names and desktop entries",
+        "use_10bit_color" => "Scan out 10 bits per channel on displays that support it",
+        "accent_color" => "Accent color of focus rings, selections and window borders",
+        "keyboard_shortcuts" => "Key chords and the actions they trigger",
+        "pointer_shortcuts" => "Pointer buttons and scroll directions, with the same actions",
+        "shortcuts_inhibit_escape" => {
+            "Press twice to take shortcuts back from apps that inhibit them"
+        }
+        "virtual_outputs" => "Headless outputs, e.g. for screen sharing",
+        "window_rules" => "Per-app floating, size, output, workspace and opacity rules",
+        "tiling" => "Tiling layout options",
+        "workspaces" => "Number and names of workspaces, default layout and gaps",
+        "occlusion_culling" => "Skip drawing layers fully hidden behind opaque ones",
+        "allow_tearing" => "Let fullscreen games that ask for it present without vsync",
+        "game_vrr" => "Turn VRR on while a fullscreen game is shown",
+        "inactive_opacity" => "Opacity of windows without keyboard focus, 0.0 - 1.0",
+        "reduce_motion" => "Apply changes instantly instead of animating them",
+        "window_corner_radius" => {
+            "Corner radius every window is clipped to; 0 leaves it to the client"
+        }
+        "clipboard_history_enabled" | "clipboard_history_size" => {
+            "Remember recent text and image copies, and how many, for ClipboardHistory"
+        }
+        "exec_once" => "Commands started once, when Otto starts",
+        "xdg_autostart" => "Start the apps listed in the XDG autostart directories",
+        "systemd_notify" => "Notify systemd once Otto is ready",
+        _ => return None,
+    })
+}
+
+/// Every file `Config::load` may read, whether or not it exists yet, in load
+/// order. Used to watch for changes.
+pub fn config_file_candidates() -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from("/etc/otto/config.toml")];
+    paths.extend(user_config_location());
+    let mut local = vec!["otto_config.toml".to_string()];
+    if let Ok(backend) = std::env::var("OTTO_BACKEND") {
+        local.extend(backend_override_candidates(&backend));
     }
+    let cwd = std::env::current_dir().unwrap_or_default();
+    paths.extend(local.into_iter().map(|name| cwd.join(name)));
+    paths
 }
 
 /// Return the best writable config file path:
@@ -284,26 +720,86 @@ fn backend_override_candidates(backend: &str) -> Vec<String> {
     }
 }
 
+/// Rewrite the config file at `path`, letting `update` edit its top-level
+/// table.
+///
+/// All config writes go through here: they are serialized by `SAVE_LOCK`,
+/// and the new content is written to a temporary file renamed over the old
+/// one, so a reader (or the file watcher) never sees a half-written file.
+/// Comments in the file are not preserved.
+fn update_config_file(
+    path: &std::path::Path,
+    update: impl FnOnce(&mut toml::Table),
+) -> std::io::Result<()> {
+    let _guard = SAVE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let raw = std::fs::read_to_string(path).unwrap_or_default();
+    let mut doc: toml::Table = raw.parse().unwrap_or_default();
+    update(&mut doc);
+    let serialized = toml::to_string_pretty(&doc).map_err(std::io::Error::other)?;
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("toml.tmp");
+    std::fs::write(&tmp, serialized)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Edit `doc` so the keys that differ between `before` and `after` take their
+/// `after` value. Tables are compared key by key, so a changed `[dock]
+/// position` writes just that key; keys `after` lacks (options set to `None`)
+/// are removed.
+fn apply_changes(doc: &mut toml::Table, before: &toml::Table, after: &toml::Table) {
+    for key in before.keys().filter(|key| !after.contains_key(*key)) {
+        doc.remove(key);
+    }
+    for (key, new) in after {
+        match (before.get(key), new) {
+            (Some(old), new) if old == new => {}
+            (Some(toml::Value::Table(old)), toml::Value::Table(new)) => {
+                let entry = doc
+                    .entry(key.clone())
+                    .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+                match entry {
+                    toml::Value::Table(table) => apply_changes(table, old, new),
+                    other => *other = toml::Value::Table(new.clone()),
+                }
+            }
+            (_, new) => {
+                doc.insert(key.clone(), new.clone());
+            }
+        }
+    }
+}
+
+/// Replace the `[name]` table of the writable config file with `value`,
+/// leaving all other sections unchanged.
+fn save_config_section(name: &str, value: &impl Serialize) {
+    let path = writable_config_path();
+    let result = toml::Value::try_from(value)
+        .map_err(std::io::Error::other)
+        .and_then(|value| {
+            update_config_file(&path, |doc| {
+                doc.insert(name.to_string(), value);
+            })
+        });
+    if let Err(err) = result {
+        warn!("Failed to save [{}] to {}: {}", name, path.display(), err);
+    }
+}
+
 /// Persist a `DockConfig` into the `[dock]` section of the writable config file.
 ///
 /// The caller is responsible for passing the authoritative in-memory state.
 /// Only the `[dock]` table is touched; all other sections are left unchanged.
 pub fn save_dock_config(dock: &DockConfig) {
-    let path = writable_config_path();
-    let raw = std::fs::read_to_string(&path).unwrap_or_default();
-    let mut doc: toml::Value = raw
-        .parse()
-        .unwrap_or(toml::Value::Table(Default::default()));
-
-    if let Ok(dock_value) = toml::Value::try_from(dock) {
-        doc.as_table_mut()
-            .unwrap()
-            .insert("dock".to_string(), dock_value);
-    }
+    save_config_section("dock", dock);
+}
 
-    if let Ok(serialized) = toml::to_string_pretty(&doc) {
-        let _ = std::fs::write(&path, serialized);
-    }
+/// Persist the workspace list into the `[workspaces]` section of the writable
+/// config file, leaving all other sections unchanged.
+pub fn save_workspaces_config(workspaces: &WorkspacesConfig) {
+    save_config_section("workspaces", workspaces);
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -324,12 +820,56 @@ pub struct DockConfig {
     pub autohide: bool,
     #[serde(default = "default_magnification")]
     pub magnification: bool,
+    /// Clicking the icon of the focused app minimizes its windows, and
+    /// clicking again restores them (default: false)
+    #[serde(default)]
+    pub minimize_on_click: bool,
+    /// Which outputs show a dock (default: "all")
+    #[serde(default)]
+    pub outputs: DockOutputs,
+    /// Screen edge the dock sits on (default: "bottom")
+    #[serde(default)]
+    pub position: DockPosition,
     #[serde(
         default,
         serialize_with = "serialize_dock_bookmarks",
         deserialize_with = "deserialize_dock_bookmarks"
     )]
     pub bookmarks: Vec<DockBookmark>,
+    /// Folders and groups of apps that open in a popup, after the apps
+    #[serde(default)]
+    pub stacks: Vec<DockStack>,
+}
+
+/// Outputs that get their own dock
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DockOutputs {
+    /// One dock on every connected output
+    #[default]
+    All,
+    /// A single dock on the primary output
+    Primary,
+}
+
+/// Screen edge the dock is attached to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DockPosition {
+    /// Centered along the bottom edge, icons in a row
+    #[default]
+    Bottom,
+    /// Centered along the left edge, icons in a column
+    Left,
+    /// Centered along the right edge, icons in a column
+    Right,
+}
+
+impl DockPosition {
+    /// Whether the icons are laid out top to bottom
+    pub fn is_vertical(self) -> bool {
+        !matches!(self, DockPosition::Bottom)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -403,6 +943,30 @@ fn default_accent_color() -> String {
     "blue".to_string()
 }
 
+fn default_inactive_opacity() -> f32 {
+    1.0
+}
+
+fn deserialize_inactive_opacity<'de, D>(deserializer: D) -> Result<f32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = f32::deserialize(deserializer)?;
+    Ok(value.clamp(0.0, 1.0))
+}
+
+fn default_clipboard_history_enabled() -> bool {
+    true
+}
+
+fn default_clipboard_history_size() -> usize {
+    50
+}
+
+fn default_shortcuts_inhibit_escape() -> String {
+    "Logo+Escape".to_string()
+}
+
 /// Power management configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PowerManagementConfig {
@@ -418,6 +982,27 @@ pub struct PowerManagementConfig {
     ///   "disable_internal_screen" - Always disable screen but stay running (for display managers/kiosks)
     #[serde(default = "default_on_lid_close")]
     pub on_lid_close: LidCloseAction,
+
+    /// Refresh rate of every display while on battery, in Hz (default: unset,
+    /// keep the current rate). Displays without a mode at that rate keep
+    /// theirs; the previous rate comes back on AC.
+    #[serde(default)]
+    pub battery_refresh_hz: Option<f64>,
+
+    /// Charge, in percent, at or below which the battery counts as low
+    /// (default: 20)
+    #[serde(default = "default_low_battery_percent")]
+    pub low_battery_percent: u32,
+
+    /// Backlight brightness, in percent, to dim to on low battery (default:
+    /// unset, leave it alone). Never brightens; restored when charging starts.
+    #[serde(default)]
+    pub low_battery_brightness: Option<u32>,
+
+    /// Turn animations off on low battery, as `reduce_motion` does (default:
+    /// false)
+    #[serde(default)]
+    pub low_battery_reduce_motion: bool,
 }
 
 /// Action to take when laptop lid is closed
@@ -437,6 +1022,10 @@ impl Default for PowerManagementConfig {
         Self {
             manage_lid_switch: default_manage_lid_switch(),
             on_lid_close: default_on_lid_close(),
+            battery_refresh_hz: None,
+            low_battery_percent: default_low_battery_percent(),
+            low_battery_brightness: None,
+            low_battery_reduce_motion: false,
         }
     }
 }
@@ -449,6 +1038,110 @@ fn default_on_lid_close() -> LidCloseAction {
     LidCloseAction::Auto
 }
 
+fn default_low_battery_percent() -> u32 {
+    20
+}
+
+/// `[security]`: which clients may use privileged protocols, those that
+/// read or drive other clients (screencopy, data control, virtual input,
+/// input method, gamma, toplevel management) or restyle the desktop
+/// (surface style, dock manager)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SecurityConfig {
+    /// Clients allowed when restricted: absolute executable paths, e.g.
+    /// "/usr/bin/otto-bar", or sandbox app ids of clients connected through a
+    /// `wp_security_context_v1`, e.g. "com.obsproject.Studio" (default: none)
+    #[serde(default)]
+    pub allowed_clients: Vec<String>,
+    /// Restrict unsandboxed clients to `allowed_clients` too (default: false).
+    /// Sandboxed clients are always restricted.
+    #[serde(default)]
+    pub restrict_privileged: bool,
+}
+
+impl SecurityConfig {
+    /// Whether a client may use privileged protocols. `app_id` is the
+    /// sandbox app id of sandboxed clients, `executable` the binary the
+    /// client runs.
+    pub fn allows(&self, sandboxed: bool, app_id: Option<&str>, executable: Option<&Path>) -> bool {
+        if sandboxed {
+            return app_id.is_some_and(|app_id| self.allowed_clients.iter().any(|c| c == app_id));
+        }
+        !self.restrict_privileged
+            || executable.is_some_and(|executable| {
+                self.allowed_clients
+                    .iter()
+                    .any(|c| c.starts_with('/') && Path::new(c) == executable)
+            })
+    }
+}
+
+/// Tiling layout configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TilingConfig {
+    /// Layout new workspaces start with (default: Floating)
+    #[serde(default)]
+    pub default_layout: WorkspaceLayout,
+    /// Space between tiled windows, in logical pixels (default: 8)
+    #[serde(default = "default_tiling_gaps")]
+    pub gaps: i32,
+    /// Space between tiled windows and the edges of the usable area,
+    /// in logical pixels (default: 8)
+    #[serde(default = "default_tiling_gaps")]
+    pub outer_gap: i32,
+}
+
+impl Default for TilingConfig {
+    fn default() -> Self {
+        Self {
+            default_layout: WorkspaceLayout::default(),
+            gaps: default_tiling_gaps(),
+            outer_gap: default_tiling_gaps(),
+        }
+    }
+}
+
+fn default_tiling_gaps() -> i32 {
+    8
+}
+
+/// `[workspaces]`: the workspaces created at startup.
+///
+/// Kept up to date by the compositor when workspaces are created, renamed
+/// or removed, so the layout survives restarts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspacesConfig {
+    /// Number of workspaces (default: 2)
+    #[serde(default = "default_workspace_count")]
+    pub count: usize,
+    /// Workspace names by position; an empty string leaves it unnamed
+    #[serde(default)]
+    pub names: Vec<String>,
+}
+
+impl Default for WorkspacesConfig {
+    fn default() -> Self {
+        Self {
+            count: default_workspace_count(),
+            names: Vec::new(),
+        }
+    }
+}
+
+impl WorkspacesConfig {
+    /// Configured name of the workspace at `index`, if any.
+    pub fn name(&self, index: usize) -> Option<String> {
+        self.names
+            .get(index)
+            .filter(|name| !name.is_empty())
+            .cloned()
+    }
+}
+
+fn default_workspace_count() -> usize {
+    2
+}
+
 /// Audio configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioConfig {
@@ -461,6 +1154,10 @@ pub struct AudioConfig {
     /// When None, Otto will auto-detect the system sound theme
     #[serde(default)]
     pub sound_theme: Option<String>,
+
+    /// Show a badge in the top-right corner while the microphone is muted (default: true)
+    #[serde(default = "default_show_mic_indicator")]
+    pub show_mic_indicator: bool,
 }
 
 impl Default for AudioConfig {
@@ -468,14 +1165,262 @@ impl Default for AudioConfig {
         Self {
             sound_enabled: default_sound_enabled(),
             sound_theme: None,
+            show_mic_indicator: default_show_mic_indicator(),
         }
     }
 }
 
+fn default_show_mic_indicator() -> bool {
+    true
+}
+
 fn default_sound_enabled() -> bool {
     true
 }
 
+/// `[night_light]`: warmer colors at night, through the output gamma
+/// (udev backend only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NightLightConfig {
+    /// Follow the schedule (default: false). The ToggleNightLight action
+    /// works either way.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Color temperature at night, in Kelvin (default: 4000)
+    #[serde(default = "default_night_light_temperature")]
+    pub temperature: u32,
+    /// Local time the night starts, "HH:MM" (default: "20:00")
+    #[serde(default = "default_night_light_start")]
+    pub start: String,
+    /// Local time the night ends, "HH:MM" (default: "07:00")
+    #[serde(default = "default_night_light_end")]
+    pub end: String,
+    /// With `longitude`, run from sunset to sunrise instead of `start`/`end`
+    #[serde(default)]
+    pub latitude: Option<f64>,
+    #[serde(default)]
+    pub longitude: Option<f64>,
+    /// Minutes over which the temperature ramps at each end of the night
+    /// (default: 30)
+    #[serde(default = "default_night_light_transition")]
+    pub transition_minutes: u32,
+}
+
+impl Default for NightLightConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            temperature: default_night_light_temperature(),
+            start: default_night_light_start(),
+            end: default_night_light_end(),
+            latitude: None,
+            longitude: None,
+            transition_minutes: default_night_light_transition(),
+        }
+    }
+}
+
+fn default_night_light_temperature() -> u32 {
+    4000
+}
+
+fn default_night_light_start() -> String {
+    "20:00".to_string()
+}
+
+fn default_night_light_end() -> String {
+    "07:00".to_string()
+}
+
+fn default_night_light_transition() -> u32 {
+    30
+}
+
+/// `[backlight]`: the internal panel backlight, driven by BrightnessUp and
+/// BrightnessDown (udev backend only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacklightConfig {
+    /// Percent added or removed by each key press (default: 5)
+    #[serde(default = "default_backlight_step")]
+    pub step: u32,
+    /// Lowest level the keys go down to, in percent (default: 1), so the
+    /// panel never turns fully dark
+    #[serde(default = "default_backlight_min_percent")]
+    pub min_percent: u32,
+}
+
+impl Default for BacklightConfig {
+    fn default() -> Self {
+        Self {
+            step: default_backlight_step(),
+            min_percent: default_backlight_min_percent(),
+        }
+    }
+}
+
+fn default_backlight_step() -> u32 {
+    5
+}
+
+fn default_backlight_min_percent() -> u32 {
+    1
+}
+
+/// `[zoom]`: the full-screen magnifier driven by ToggleZoom, ZoomIn and
+/// ZoomOut
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoomConfig {
+    /// Highest zoom level (default: 8.0)
+    #[serde(default = "default_zoom_max_level")]
+    pub max_level: f64,
+    /// Factor applied by each ZoomIn or ZoomOut (default: 1.5)
+    #[serde(default = "default_zoom_step")]
+    pub step: f64,
+    /// Level ToggleZoom switches to (default: 2.0)
+    #[serde(default = "default_zoom_toggle_level")]
+    pub toggle_level: f64,
+    /// How the magnified region follows the pointer (default: push)
+    #[serde(default)]
+    pub edge_mode: ZoomEdgeMode,
+    /// Time constant of the zoom animation in ms; 0 disables it
+    /// (default: 100)
+    #[serde(default = "default_zoom_smoothing_ms")]
+    pub smoothing_ms: u32,
+}
+
+impl Default for ZoomConfig {
+    fn default() -> Self {
+        Self {
+            max_level: default_zoom_max_level(),
+            step: default_zoom_step(),
+            toggle_level: default_zoom_toggle_level(),
+            edge_mode: ZoomEdgeMode::default(),
+            smoothing_ms: default_zoom_smoothing_ms(),
+        }
+    }
+}
+
+fn default_zoom_max_level() -> f64 {
+    8.0
+}
+
+fn default_zoom_step() -> f64 {
+    1.5
+}
+
+fn default_zoom_toggle_level() -> f64 {
+    2.0
+}
+
+fn default_zoom_smoothing_ms() -> u32 {
+    100
+}
+
+/// `[window_border]`: a border Otto draws around every window, following
+/// `window_corner_radius`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowBorderConfig {
+    /// Border width in logical pixels; 0.0 draws no border (default: 0.0)
+    #[serde(default)]
+    pub width: f32,
+    /// Hex color of the focused window's border (default: the accent color)
+    #[serde(default)]
+    pub focused_color: Option<String>,
+    /// Hex color of the other windows' borders (default: "#808080")
+    #[serde(default = "default_window_border_unfocused_color")]
+    pub unfocused_color: String,
+}
+
+impl Default for WindowBorderConfig {
+    fn default() -> Self {
+        Self {
+            width: 0.0,
+            focused_color: None,
+            unfocused_color: default_window_border_unfocused_color(),
+        }
+    }
+}
+
+fn default_window_border_unfocused_color() -> String {
+    "#808080".to_string()
+}
+
+/// `[screen_recording]`: videos written by StartRecording and StopRecording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreenRecordingConfig {
+    /// Container and codec of the video (default: mp4)
+    #[serde(default)]
+    pub format: RecordingFormat,
+    /// Frames per second, at most the refresh rate of the output (default: 30)
+    #[serde(default = "default_recording_framerate")]
+    pub framerate: u32,
+    /// Video bitrate in kbit/s (default: 8000)
+    #[serde(default = "default_recording_bitrate_kbps")]
+    pub bitrate_kbps: u32,
+    /// Draw the cursor into output and region recordings (default: true)
+    #[serde(default = "default_recording_show_cursor")]
+    pub show_cursor: bool,
+    /// Where videos are saved; `~` is expanded (default: `$XDG_VIDEOS_DIR`,
+    /// or `~/Videos`)
+    #[serde(default)]
+    pub directory: Option<String>,
+}
+
+impl Default for ScreenRecordingConfig {
+    fn default() -> Self {
+        Self {
+            format: RecordingFormat::default(),
+            framerate: default_recording_framerate(),
+            bitrate_kbps: default_recording_bitrate_kbps(),
+            show_cursor: default_recording_show_cursor(),
+            directory: None,
+        }
+    }
+}
+
+fn default_recording_framerate() -> u32 {
+    30
+}
+
+fn default_recording_bitrate_kbps() -> u32 {
+    8000
+}
+
+fn default_recording_show_cursor() -> bool {
+    true
+}
+
+/// Container and codec of screen recordings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingFormat {
+    /// H.264 in an MP4 container
+    #[default]
+    Mp4,
+    /// VP9 in a WebM container
+    Webm,
+}
+
+impl RecordingFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            RecordingFormat::Mp4 => "mp4",
+            RecordingFormat::Webm => "webm",
+        }
+    }
+}
+
+/// How the magnified region follows the pointer
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ZoomEdgeMode {
+    /// The region moves only when the pointer pushes against its edge
+    #[default]
+    Push,
+    /// The region stays centred on the pointer
+    Center,
+}
+
 /// Input device configuration
 ///
 /// Note: These settings map directly to libinput configuration options.
@@ -504,25 +1449,57 @@ pub struct InputConfig {
     /// Scroll speed multiplier applied in software. Default is 1.0 (no change).
     /// Values > 1.0 increase scroll speed; values between 0.0 and 1.0 decrease it.
     /// Negative values are clamped to 0.0 to prevent inverted scrolling.
+    /// Also accepted as `scroll_factor`.
     #[serde(
         default = "default_scroll_speed",
-        deserialize_with = "deserialize_scroll_speed"
+        deserialize_with = "deserialize_scroll_speed",
+        alias = "scroll_factor"
     )]
     pub scroll_speed: f64,
     /// Pointer acceleration speed. Range: -1.0 (slowest) to 1.0 (fastest), default 0.0.
     /// Applies to all pointer devices (mice and touchpads).
-    #[serde(default = "default_pointer_accel_speed")]
+    /// Values outside the range are clamped.
+    #[serde(
+        default = "default_pointer_accel_speed",
+        deserialize_with = "deserialize_pointer_accel_speed"
+    )]
     pub pointer_accel_speed: f64,
     /// Pointer acceleration profile. "flat" disables acceleration (raw speed),
     /// "adaptive" applies libinput's default adaptive acceleration curve.
     #[serde(default = "default_pointer_accel_profile")]
     pub pointer_accel_profile: PointerAccelProfile,
+    /// How far the workspaces move for a given 3-finger swipe distance.
+    /// Default is 1.0; values are clamped to 0.1..=5.0.
+    #[serde(
+        default = "default_workspace_swipe_sensitivity",
+        deserialize_with = "deserialize_workspace_swipe_sensitivity"
+    )]
+    pub workspace_swipe_sensitivity: f64,
+    /// Fraction of the workspace width a slow swipe must cross to switch
+    /// workspace; shorter swipes snap back. Fast swipes (flings) always
+    /// switch. Default is 0.5; values are clamped to 0.05..=0.95.
+    #[serde(
+        default = "default_workspace_swipe_threshold",
+        deserialize_with = "deserialize_workspace_swipe_threshold"
+    )]
+    pub workspace_swipe_threshold: f64,
     #[serde(default)]
     pub xkb_layout: Option<String>,
+    /// Layouts to cycle through with `CycleKeyboardLayout`, e.g. `["us", "it"]`.
+    /// Takes precedence over `xkb_layout` when not empty.
+    #[serde(default)]
+    pub xkb_layouts: Vec<String>,
+    /// Remember the active layout of each window and restore it on focus.
+    #[serde(default)]
+    pub per_window_layout: bool,
     #[serde(default)]
     pub xkb_variant: Option<String>,
     #[serde(default)]
     pub xkb_options: Vec<String>,
+    /// Name of a second seat for virtual keyboards and pointers, with its
+    /// own focus; unset creates none. Read at startup.
+    #[serde(default)]
+    pub virtual_seat: Option<String>,
 }
 
 /// Touchpad click method configuration
@@ -557,6 +1534,18 @@ pub enum PointerAccelProfile {
     Adaptive,
 }
 
+impl InputConfig {
+    /// The xkb layout string: all `xkb_layouts` comma-separated (one xkb
+    /// group each), or the single `xkb_layout`.
+    pub fn xkb_layout_string(&self) -> String {
+        if self.xkb_layouts.is_empty() {
+            self.xkb_layout.clone().unwrap_or_default()
+        } else {
+            self.xkb_layouts.join(",")
+        }
+    }
+}
+
 impl Default for InputConfig {
     fn default() -> Self {
         Self {
@@ -571,9 +1560,14 @@ impl Default for InputConfig {
             scroll_speed: default_scroll_speed(),
             pointer_accel_speed: default_pointer_accel_speed(),
             pointer_accel_profile: default_pointer_accel_profile(),
+            workspace_swipe_sensitivity: default_workspace_swipe_sensitivity(),
+            workspace_swipe_threshold: default_workspace_swipe_threshold(),
             xkb_layout: None,
+            xkb_layouts: Vec::new(),
+            per_window_layout: false,
             xkb_variant: None,
             xkb_options: Vec::new(),
+            virtual_seat: None,
         }
     }
 }
@@ -626,10 +1620,42 @@ fn default_pointer_accel_speed() -> f64 {
     0.0
 }
 
+fn deserialize_pointer_accel_speed<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = f64::deserialize(deserializer)?;
+    Ok(value.clamp(-1.0, 1.0))
+}
+
 fn default_pointer_accel_profile() -> PointerAccelProfile {
     PointerAccelProfile::Adaptive
 }
 
+fn default_workspace_swipe_sensitivity() -> f64 {
+    1.0
+}
+
+fn deserialize_workspace_swipe_sensitivity<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = f64::deserialize(deserializer)?;
+    Ok(value.clamp(0.1, 5.0))
+}
+
+fn default_workspace_swipe_threshold() -> f64 {
+    0.5
+}
+
+fn deserialize_workspace_swipe_threshold<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = f64::deserialize(deserializer)?;
+    Ok(value.clamp(0.05, 0.95))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DockBookmark {
     pub desktop_id: String,
@@ -639,6 +1665,60 @@ pub struct DockBookmark {
     pub exec_args: Vec<String>,
 }
 
+/// A dock item showing a folder's contents or a group of apps in a popup
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DockStack {
+    /// Name shown in the dock label; also identifies the stack
+    pub name: String,
+    /// Folder whose entries are shown; a leading `~` is the home directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    /// Desktop ids of the apps shown, used when there is no `folder`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub apps: Vec<String>,
+    /// Icon name for the dock item (default: "folder")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub display: StackDisplay,
+}
+
+/// How the entries of a stack are laid out
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StackDisplay {
+    /// A grid of icons on a panel
+    #[default]
+    Grid,
+    /// A column of icons curving up from the dock
+    Fan,
+}
+
+/// What a stack shows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StackSource {
+    Folder(PathBuf),
+    Apps(Vec<String>),
+}
+
+impl DockStack {
+    pub fn source(&self) -> StackSource {
+        match &self.folder {
+            Some(folder) => StackSource::Folder(expand_home(folder, std::env::var("HOME").ok())),
+            None => StackSource::Apps(self.apps.clone()),
+        }
+    }
+}
+
+fn expand_home(path: &str, home: Option<String>) -> PathBuf {
+    match (path.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            PathBuf::from(format!("{home}{rest}"))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 enum DockBookmarkToml {
@@ -737,6 +1817,55 @@ pub struct DisplayProfile {
     pub refresh_hz: Option<f64>,
     #[serde(default)]
     pub position: Option<DisplayPosition>,
+    /// Output scale, overriding the global `screen_scale` for this display.
+    #[serde(default)]
+    pub scale: Option<f64>,
+    /// Output rotation/flip, e.g. "90" for a monitor turned on its side.
+    #[serde(default)]
+    pub transform: Option<DisplayTransform>,
+    /// Variable refresh rate (adaptive sync), if the display supports it.
+    #[serde(default)]
+    pub vrr: bool,
+}
+
+/// Rotation (counter-clockwise) and flip applied to a display's content.
+///
+/// Maps to `wl_output.transform`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum DisplayTransform {
+    #[default]
+    #[serde(rename = "normal")]
+    Normal,
+    #[serde(rename = "90")]
+    Rotate90,
+    #[serde(rename = "180")]
+    Rotate180,
+    #[serde(rename = "270")]
+    Rotate270,
+    #[serde(rename = "flipped")]
+    Flipped,
+    #[serde(rename = "flipped-90")]
+    Flipped90,
+    #[serde(rename = "flipped-180")]
+    Flipped180,
+    #[serde(rename = "flipped-270")]
+    Flipped270,
+}
+
+impl From<DisplayTransform> for smithay::utils::Transform {
+    fn from(transform: DisplayTransform) -> Self {
+        use smithay::utils::Transform;
+        match transform {
+            DisplayTransform::Normal => Transform::Normal,
+            DisplayTransform::Rotate90 => Transform::_90,
+            DisplayTransform::Rotate180 => Transform::_180,
+            DisplayTransform::Rotate270 => Transform::_270,
+            DisplayTransform::Flipped => Transform::Flipped,
+            DisplayTransform::Flipped90 => Transform::Flipped90,
+            DisplayTransform::Flipped180 => Transform::Flipped180,
+            DisplayTransform::Flipped270 => Transform::Flipped270,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -771,6 +1900,12 @@ pub struct VirtualOutputConfig {
     /// Output position in the compositor layout.
     #[serde(default)]
     pub position: Option<DisplayPosition>,
+    /// Name of an output (e.g. "eDP-1") to mirror instead of extending the desktop.
+    ///
+    /// The source is scaled to this output's resolution and letterboxed when
+    /// the aspect ratios differ. A mirror is not part of the layout.
+    #[serde(default)]
+    pub mirror_of: Option<String>,
 }
 
 fn default_virtual_refresh_hz() -> f64 {
@@ -896,6 +2031,192 @@ mod tests {
         assert!(matches!(config.theme_scheme, ThemeScheme::Dark));
     }
 
+    #[test]
+    fn theme_file_absolute_path_is_kept() {
+        let config: Config = toml::from_str(
+            r#"
+            theme_scheme = "Auto"
+            theme_file = "/usr/share/otto/themes/nord.toml"
+            "#,
+        )
+        .expect("Config should deserialize");
+        assert!(matches!(config.theme_scheme, ThemeScheme::Auto));
+        assert_eq!(
+            config.theme_file_path(),
+            Some(PathBuf::from("/usr/share/otto/themes/nord.toml"))
+        );
+        assert_eq!(Config::default().theme_file_path(), None);
+    }
+
+    #[test]
+    fn screen_recording_settings_deserialize() {
+        let config: Config = toml::from_str(
+            r#"
+            [screen_recording]
+            format = "webm"
+            framerate = 60
+            directory = "/tmp/recordings"
+            "#,
+        )
+        .expect("Config should deserialize");
+        let recording = &config.screen_recording;
+        assert_eq!(recording.format, RecordingFormat::Webm);
+        assert_eq!(recording.framerate, 60);
+        assert_eq!(recording.bitrate_kbps, 8000);
+        assert!(recording.show_cursor);
+        assert_eq!(
+            config.recordings_dir(),
+            Some(PathBuf::from("/tmp/recordings"))
+        );
+    }
+
+    #[test]
+    fn shortcuts_inhibit_escape_trigger_is_parsed() {
+        let config = Config::default();
+        let trigger = config
+            .shortcuts_inhibit_escape_trigger()
+            .expect("Default escape chord should parse");
+        assert!(trigger.logo && !trigger.ctrl && !trigger.alt && !trigger.shift);
+        assert_eq!(trigger.keysym, xkbcommon::xkb::Keysym::Escape);
+
+        let mut config: Config = toml::from_str(r#"shortcuts_inhibit_escape = "Nope+Escape""#)
+            .expect("Config should deserialize");
+        config.rebuild_shortcut_bindings();
+        assert!(config.shortcuts_inhibit_escape_trigger().is_none());
+    }
+
+    #[test]
+    fn security_restricts_sandboxed_clients_by_app_id() {
+        let config: Config = toml::from_str(
+            r#"
+            [security]
+            allowed_clients = ["com.obsproject.Studio", "/usr/bin/otto-bar"]
+            "#,
+        )
+        .expect("Config should deserialize");
+        let security = &config.security;
+        assert!(security.allows(true, Some("com.obsproject.Studio"), None));
+        assert!(!security.allows(true, Some("org.mozilla.firefox"), None));
+        assert!(!security.allows(true, None, Some(Path::new("/usr/bin/otto-bar"))));
+        // Permissive for everything else by default
+        assert!(security.allows(false, None, None));
+    }
+
+    #[test]
+    fn security_lockdown_checks_executable_paths() {
+        let security = SecurityConfig {
+            allowed_clients: vec!["/usr/bin/otto-bar".into(), "otto-bar".into()],
+            restrict_privileged: true,
+        };
+        assert!(security.allows(false, None, Some(Path::new("/usr/bin/otto-bar"))));
+        assert!(!security.allows(false, None, Some(Path::new("/tmp/otto-bar"))));
+        assert!(!security.allows(false, None, None));
+    }
+
+    #[test]
+    fn restart_required_changes_ignores_live_settings() {
+        let old = Config::default();
+        let new: Config = toml::from_str(
+            r#"
+            theme_scheme = "Dark"
+            screen_scale = 1.5
+            keyboard_repeat_rate = 50
+            cursor_size = 32
+            use_10bit_color = true
+
+            [displays.named."winit"]
+            resolution = { width = 1280, height = 800 }
+            "#,
+        )
+        .expect("Config should deserialize");
+
+        assert_eq!(
+            old.restart_required_changes(&new),
+            vec!["displays", "use_10bit_color"]
+        );
+        assert!(old.restart_required_changes(&old).is_empty());
+    }
+
+    #[test]
+    fn display_profile_scale_and_transform() {
+        let toml_str = r#"
+            [displays.named."HDMI-A-1"]
+            scale = 1.0
+            transform = "90"
+            position = { x = 2560, y = 0 }
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let profile = config
+            .resolve_display_profile("HDMI-A-1", &DisplayDescriptor::new("HDMI-A-1"))
+            .unwrap();
+        assert_eq!(profile.scale, Some(1.0));
+        assert_eq!(profile.transform, Some(DisplayTransform::Rotate90));
+        assert_eq!(
+            smithay::utils::Transform::from(profile.transform.unwrap()),
+            smithay::utils::Transform::_90
+        );
+    }
+
+    #[test]
+    fn default_config_round_trips_through_toml() {
+        let config = Config::default();
+        let serialized = toml::to_string_pretty(&config).unwrap();
+        let parsed: Config = toml::from_str(&serialized).unwrap();
+        assert!(!differs(&config, &parsed));
+    }
+
+    #[test]
+    fn update_config_file_keeps_other_sections() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("otto").join("config.toml");
+        update_config_file(&path, |doc| {
+            doc.insert("custom".into(), toml::Value::Integer(1));
+        })
+        .unwrap();
+        let mut config = Config::default();
+        config.theme_scheme = ThemeScheme::Dark;
+        let values = toml::Table::try_from(&config).unwrap();
+        update_config_file(&path, |doc| doc.extend(values)).unwrap();
+
+        let doc: toml::Table = fs::read_to_string(&path).unwrap().parse().unwrap();
+        assert_eq!(doc["custom"].as_integer(), Some(1));
+        assert_eq!(doc["theme_scheme"].as_str(), Some("Dark"));
+        assert!(!path.with_extension("toml.tmp").exists());
+    }
+
+    #[test]
+    fn only_changed_keys_are_written() {
+        let mut before = Config::default();
+        before.theme_file = Some("theme.toml".into());
+        let mut after = before.clone();
+        after.reduce_motion = !before.reduce_motion;
+        after.dock.autohide = !before.dock.autohide;
+        after.theme_file = None;
+
+        let mut doc: toml::Table = "theme_file = \"theme.toml\"\n[dock]\nsize = 0.5\n"
+            .parse()
+            .unwrap();
+        apply_changes(
+            &mut doc,
+            &toml::Table::try_from(&before).unwrap(),
+            &toml::Table::try_from(&after).unwrap(),
+        );
+        assert_eq!(doc["dock"]["size"].as_float(), Some(0.5));
+        assert_eq!(doc["dock"]["autohide"].as_bool(), Some(after.dock.autohide));
+        assert_eq!(doc["dock"].as_table().unwrap().len(), 2);
+        assert_eq!(doc["reduce_motion"].as_bool(), Some(after.reduce_motion));
+        assert!(!doc.contains_key("theme_file"));
+        assert_eq!(doc.len(), 2);
+    }
+
+    #[test]
+    fn starter_config_parses_back_to_the_default() {
+        let content = starter_config().unwrap();
+        assert!(content.contains("# Dock size"));
+        let parsed: Config = toml::from_str(&content).unwrap();
+        assert!(!differs(&parsed, &Config::default()));
+    }
+
     #[test]
     #[serial]
     fn test_get_user_config_path_with_xdg_config_home() {
@@ -1093,6 +2414,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_dock_position_deserialize() {
+        let dock: DockConfig = toml::from_str("position = \"left\"").unwrap();
+        assert_eq!(dock.position, DockPosition::Left);
+        assert!(dock.position.is_vertical());
+
+        let dock: DockConfig = toml::from_str("").unwrap();
+        assert_eq!(dock.position, DockPosition::Bottom);
+        assert!(!dock.position.is_vertical());
+    }
+
+    #[test]
+    fn test_dock_stacks_deserialize() {
+        let dock: DockConfig = toml::from_str(
+            r#"
+            [[stacks]]
+            name = "Downloads"
+            folder = "~/Downloads"
+            display = "fan"
+
+            [[stacks]]
+            name = "Office"
+            apps = ["libreoffice-writer", "libreoffice-calc"]
+            "#,
+        )
+        .expect("stacks should parse");
+
+        assert_eq!(dock.stacks.len(), 2);
+        assert_eq!(dock.stacks[0].display, StackDisplay::Fan);
+        assert_eq!(dock.stacks[1].display, StackDisplay::Grid);
+        assert_eq!(
+            dock.stacks[1].source(),
+            StackSource::Apps(vec![
+                "libreoffice-writer".to_string(),
+                "libreoffice-calc".to_string()
+            ])
+        );
+        assert_eq!(
+            expand_home("~/Downloads", Some("/home/u".to_string())),
+            PathBuf::from("/home/u/Downloads")
+        );
+        assert_eq!(
+            expand_home("~other/x", Some("/home/u".to_string())),
+            PathBuf::from("~other/x")
+        );
+    }
+
     #[test]
     #[serial]
     fn test_backend_override_candidates() {
@@ -1126,6 +2494,52 @@ mod tests {
         assert_eq!(val, 2.5, "positive scroll_speed should be preserved");
     }
 
+    #[test]
+    fn xkb_layouts_take_precedence_over_single_layout() {
+        let mut input = InputConfig {
+            xkb_layout: Some("de".into()),
+            ..Default::default()
+        };
+        assert_eq!(input.xkb_layout_string(), "de");
+        input.xkb_layouts = vec!["us".into(), "it".into()];
+        assert_eq!(input.xkb_layout_string(), "us,it");
+    }
+
+    #[test]
+    fn pointer_settings_for_flat_gaming_mouse() {
+        let toml_str = r#"
+            [input]
+            pointer_accel_profile = "flat"
+            pointer_accel_speed = 1.5
+            scroll_factor = 2.0
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.input.pointer_accel_profile,
+            PointerAccelProfile::Flat
+        );
+        assert_eq!(config.input.pointer_accel_speed, 1.0);
+        assert_eq!(config.input.scroll_speed, 2.0);
+    }
+
+    #[test]
+    fn workspace_swipe_settings_are_clamped() {
+        let config: Config = toml::from_str(
+            r#"
+            [input]
+            workspace_swipe_sensitivity = 10.0
+            workspace_swipe_threshold = 0.0
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.input.workspace_swipe_sensitivity, 5.0);
+        assert_eq!(config.input.workspace_swipe_threshold, 0.05);
+
+        let defaults = InputConfig::default();
+        assert_eq!(defaults.workspace_swipe_sensitivity, 1.0);
+        assert_eq!(defaults.workspace_swipe_threshold, 0.5);
+    }
+
     #[test]
     fn test_scroll_speed_zero_preserved() {
         // Test that zero is preserved
@@ -17,15 +17,21 @@ pub enum ShortcutActionConfig {
     BuiltinName(String),
 
     /// `action = { builtin = "Screen", index = 0 }`
+    /// or `action = { builtin = "GoToWorkspace", name = "web" }`
     BuiltinDetailed {
         builtin: String,
         #[serde(default)]
         index: Option<usize>,
+        #[serde(default)]
+        name: Option<String>,
     },
 
     /// `action = { run = { cmd = "kitty", args = [] } }`
     RunCommand { run: RunCommandConfig },
 
+    /// `action = { exec = ["kitty", "-e", "htop"] }`
+    Exec { exec: Vec<String> },
+
     /// `action = { open_default = "browser" }`
     OpenDefault { open_default: OpenDefaultConfig },
 }
@@ -50,7 +56,8 @@ pub struct RunCommandConfig {
 
 #[derive(Debug, Clone)]
 pub struct ShortcutBinding {
-    pub trigger: ShortcutTrigger,
+    /// Key combinations to press in order; most bindings have a single one
+    pub chords: Vec<ShortcutTrigger>,
     pub action: ShortcutAction,
     #[allow(dead_code)]
     pub trigger_repr: String,
@@ -94,45 +101,168 @@ impl ShortcutTrigger {
     }
 }
 
+/// A pointer button or scroll step that can be bound with modifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerInput {
+    /// Linux input event code of the button, e.g. `BTN_LEFT`
+    Button(u32),
+    Scroll(ScrollDirection),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScrollDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PointerTrigger {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub logo: bool,
+    pub input: PointerInput,
+}
+
+impl PointerTrigger {
+    pub fn matches(&self, modifiers: &ModifiersState, input: PointerInput) -> bool {
+        self.ctrl == modifiers.ctrl
+            && self.alt == modifiers.alt
+            && self.shift == modifiers.shift
+            && self.logo == modifiers.logo
+            && self.input == input
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PointerBinding {
+    pub trigger: PointerTrigger,
+    pub action: ShortcutAction,
+    #[allow(dead_code)]
+    pub trigger_repr: String,
+}
+
 #[derive(Debug, Clone)]
 pub enum ShortcutAction {
     Builtin(BuiltinAction),
     RunCommand(RunCommandConfig),
+    /// Program and arguments, spawned detached from the compositor
+    Exec(Vec<String>),
     OpenDefaultApp {
         role: String,
         fallback: Option<String>,
     },
 }
 
+/// A workspace addressed by position (0-based) or by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkspaceTarget {
+    Index(usize),
+    Name(String),
+    /// The workspace after the current one, staying on the last
+    Next,
+    /// The workspace before the current one, staying on the first
+    Previous,
+}
+
 #[derive(Debug, Clone)]
 pub enum BuiltinAction {
     Quit,
-    Screen { index: usize },
+    Screen {
+        index: usize,
+    },
     ScaleUp,
     ScaleDown,
     RotateOutput,
+    /// Turn variable refresh rate on or off for the output under the pointer
+    ToggleVrr,
+    /// Turn night light on or off until the schedule next changes
+    ToggleNightLight,
+    /// Show or hide the FPS and frame time overlay on every output
+    ToggleFpsOverlay,
+    /// Show or hide a gradient test pattern on every output, to check for
+    /// banding with `use_10bit_color`
+    ToggleTestPattern,
+    /// Turn `reduce_motion` on or off and save it to the config file
+    ToggleReduceMotion,
+    /// Switch between the light and dark scheme and save it to the config file
+    ToggleDarkMode,
+    /// Turn the magnifier on or off
+    ToggleZoom,
+    ZoomIn,
+    ZoomOut,
     ToggleDecorations,
     ApplicationSwitchNext,
     ApplicationSwitchPrev,
     ApplicationSwitchNextWindow,
     ApplicationSwitchQuit,
+    /// Show the window switcher and select the next or previous window
+    WindowSwitchNext,
+    WindowSwitchPrev,
     CloseWindow,
     ToggleMaximizeWindow,
+    ToggleAlwaysOnTop,
     TileWindowLeft,
     TileWindowRight,
+    SnapLeft,
+    SnapRight,
+    SnapUp,
+    SnapTopLeft,
+    SnapTopRight,
+    SnapBottomLeft,
+    SnapBottomRight,
+    FocusLeft,
+    FocusRight,
+    FocusUp,
+    FocusDown,
+    SwapWindowLeft,
+    SwapWindowRight,
+    SwapWindowUp,
+    SwapWindowDown,
+    /// Switch the current workspace to the next layout: floating, BSP, columns
+    CycleWorkspaceLayout,
     ExposeShowDesktop,
     ExposeShowAll,
-    WorkspaceNum { index: usize },
+    WorkspaceNum {
+        index: usize,
+    },
+    GoToWorkspace {
+        target: WorkspaceTarget,
+    },
+    MoveWindowToWorkspace {
+        target: WorkspaceTarget,
+    },
+    CreateWorkspace {
+        name: Option<String>,
+    },
+    /// Rename the current workspace; without a name it becomes unnamed
+    RenameWorkspace {
+        name: Option<String>,
+    },
+    RemoveEmptyWorkspaces,
+    CycleKeyboardLayout,
+    /// Drag the window under the cursor; only valid on pointer buttons
+    MoveWindow,
     SceneSnapshot,
     BrightnessUp,
     BrightnessDown,
     VolumeUp,
     VolumeDown,
     VolumeMute,
+    ToggleMicMute,
     MediaPlayPause,
     MediaNext,
     MediaPrev,
     MediaStop,
+    ScreenshotRegion,
+    /// Pick an output, window or region and record it to a video file
+    StartRecording,
+    /// Stop the screen recording and finalize the file
+    StopRecording,
+    /// Open the clipboard history picker
+    ClipboardHistory,
 }
 
 #[derive(Debug, Error)]
@@ -147,26 +277,32 @@ pub enum ShortcutError {
     UnknownBuiltin(String),
     #[error("missing index for action that requires one: '{0}'")]
     MissingIndex(String),
+    #[error("exec action without a command")]
+    EmptyCommand,
+    #[error("unknown pointer button or scroll direction '{0}'")]
+    UnknownPointerInput(String),
+    #[error("'{0}' can only be bound to a pointer button")]
+    PointerOnlyAction(String),
 }
 
 pub fn build_bindings(map: &ShortcutMap) -> Vec<ShortcutBinding> {
     let mut bindings = Vec::new();
-    let mut seen: HashMap<ShortcutTrigger, String> = HashMap::new();
+    let mut seen: HashMap<Vec<ShortcutTrigger>, String> = HashMap::new();
 
     for (trigger_str, action_cfg) in map {
-        match parse_trigger(trigger_str) {
-            Ok(trigger) => match parse_action(action_cfg) {
+        match parse_sequence(trigger_str) {
+            Ok(chords) => match parse_action(action_cfg).and_then(keyboard_action) {
                 Ok(action) => {
-                    if let Some(existing) = seen.insert(trigger.clone(), trigger_str.clone()) {
+                    if let Some(existing) = seen.insert(chords.clone(), trigger_str.clone()) {
                         warn!(
-                            trigger = %trigger.canonical_id(),
+                            trigger = %sequence_id(&chords),
                             existing = %existing,
                             new = %trigger_str,
                             "duplicate shortcut definition, new entry replaces the previous one"
                         );
                     }
                     bindings.push(ShortcutBinding {
-                        trigger,
+                        chords,
                         action,
                         trigger_repr: trigger_str.clone(),
                     });
@@ -181,6 +317,18 @@ pub fn build_bindings(map: &ShortcutMap) -> Vec<ShortcutBinding> {
         }
     }
 
+    for binding in &bindings {
+        if let Some(longer) = bindings.iter().find(|other| {
+            other.chords.len() > binding.chords.len() && other.chords.starts_with(&binding.chords)
+        }) {
+            warn!(
+                trigger = %binding.trigger_repr,
+                sequence = %longer.trigger_repr,
+                "shortcut is a prefix of a longer sequence and will never fire"
+            );
+        }
+    }
+
     if !bindings.is_empty() {
         info!("loaded {} keyboard shortcut bindings", bindings.len());
     } else {
@@ -190,17 +338,85 @@ pub fn build_bindings(map: &ShortcutMap) -> Vec<ShortcutBinding> {
     bindings
 }
 
+/// Build the pointer bindings from the `[pointer_shortcuts]` table.
+pub fn build_pointer_bindings(map: &ShortcutMap) -> Vec<PointerBinding> {
+    let mut bindings: Vec<PointerBinding> = Vec::new();
+
+    for (trigger_str, action_cfg) in map {
+        let binding = parse_pointer_trigger(trigger_str).and_then(|trigger| {
+            let action = parse_action(action_cfg)?;
+            if matches!(action, ShortcutAction::Builtin(BuiltinAction::MoveWindow))
+                && !matches!(trigger.input, PointerInput::Button(_))
+            {
+                return Err(ShortcutError::PointerOnlyAction("MoveWindow".into()));
+            }
+            Ok(PointerBinding {
+                trigger,
+                action,
+                trigger_repr: trigger_str.clone(),
+            })
+        });
+        match binding {
+            Ok(binding) => {
+                if let Some(existing) = bindings.iter().find(|b| b.trigger == binding.trigger) {
+                    warn!(
+                        existing = %existing.trigger_repr,
+                        new = %trigger_str,
+                        "duplicate pointer shortcut definition, the first one is used"
+                    );
+                }
+                bindings.push(binding);
+            }
+            Err(err) => {
+                warn!(trigger = %trigger_str, error = %err, "skipping pointer shortcut")
+            }
+        }
+    }
+
+    if !bindings.is_empty() {
+        info!("loaded {} pointer shortcut bindings", bindings.len());
+    }
+
+    bindings
+}
+
+/// The binding for a pointer button or scroll step with the given modifiers.
+pub fn find_pointer_binding<'a>(
+    bindings: &'a [PointerBinding],
+    modifiers: &ModifiersState,
+    input: PointerInput,
+) -> Option<&'a PointerBinding> {
+    bindings
+        .iter()
+        .find(|binding| binding.trigger.matches(modifiers, input))
+}
+
+fn keyboard_action(action: ShortcutAction) -> Result<ShortcutAction, ShortcutError> {
+    match action {
+        ShortcutAction::Builtin(BuiltinAction::MoveWindow) => {
+            Err(ShortcutError::PointerOnlyAction("MoveWindow".into()))
+        }
+        action => Ok(action),
+    }
+}
+
 fn parse_action(cfg: &ShortcutActionConfig) -> Result<ShortcutAction, ShortcutError> {
     match cfg {
         ShortcutActionConfig::BuiltinName(name) => {
-            let action = parse_builtin(name, None)?;
+            let action = parse_builtin(name, None, None)?;
             Ok(ShortcutAction::Builtin(action))
         }
-        ShortcutActionConfig::BuiltinDetailed { builtin, index } => {
-            let action = parse_builtin(builtin, *index)?;
+        ShortcutActionConfig::BuiltinDetailed {
+            builtin,
+            index,
+            name,
+        } => {
+            let action = parse_builtin(builtin, *index, name.clone())?;
             Ok(ShortcutAction::Builtin(action))
         }
         ShortcutActionConfig::RunCommand { run } => Ok(ShortcutAction::RunCommand(run.clone())),
+        ShortcutActionConfig::Exec { exec } if exec.is_empty() => Err(ShortcutError::EmptyCommand),
+        ShortcutActionConfig::Exec { exec } => Ok(ShortcutAction::Exec(exec.clone())),
         ShortcutActionConfig::OpenDefault {
             open_default: OpenDefaultConfig::Role(role),
         } => Ok(ShortcutAction::OpenDefaultApp {
@@ -216,21 +432,59 @@ fn parse_action(cfg: &ShortcutActionConfig) -> Result<ShortcutAction, ShortcutEr
     }
 }
 
-fn parse_builtin(name: &str, index: Option<usize>) -> Result<BuiltinAction, ShortcutError> {
+fn parse_builtin(
+    name: &str,
+    index: Option<usize>,
+    workspace_name: Option<String>,
+) -> Result<BuiltinAction, ShortcutError> {
+    let workspace_target =
+        |index: Option<usize>, workspace_name: Option<String>| match (workspace_name, index) {
+            (Some(workspace_name), _) => Ok(WorkspaceTarget::Name(workspace_name)),
+            (None, Some(index)) => Ok(WorkspaceTarget::Index(index)),
+            (None, None) => Err(ShortcutError::MissingIndex(name.to_string())),
+        };
     Ok(match name {
         "Quit" => BuiltinAction::Quit,
         "ScaleUp" => BuiltinAction::ScaleUp,
         "ScaleDown" => BuiltinAction::ScaleDown,
         "RotateOutput" => BuiltinAction::RotateOutput,
+        "ToggleVrr" => BuiltinAction::ToggleVrr,
+        "ToggleNightLight" => BuiltinAction::ToggleNightLight,
+        "ToggleFpsOverlay" => BuiltinAction::ToggleFpsOverlay,
+        "ToggleTestPattern" => BuiltinAction::ToggleTestPattern,
+        "ToggleReduceMotion" => BuiltinAction::ToggleReduceMotion,
+        "ToggleDarkMode" => BuiltinAction::ToggleDarkMode,
+        "ToggleZoom" => BuiltinAction::ToggleZoom,
+        "ZoomIn" => BuiltinAction::ZoomIn,
+        "ZoomOut" => BuiltinAction::ZoomOut,
         "ToggleDecorations" => BuiltinAction::ToggleDecorations,
         "ApplicationSwitchNext" => BuiltinAction::ApplicationSwitchNext,
         "ApplicationSwitchPrev" => BuiltinAction::ApplicationSwitchPrev,
         "ApplicationSwitchNextWindow" => BuiltinAction::ApplicationSwitchNextWindow,
         "ApplicationSwitchQuit" => BuiltinAction::ApplicationSwitchQuit,
+        "WindowSwitchNext" => BuiltinAction::WindowSwitchNext,
+        "WindowSwitchPrev" => BuiltinAction::WindowSwitchPrev,
         "CloseWindow" => BuiltinAction::CloseWindow,
         "ToggleMaximizeWindow" => BuiltinAction::ToggleMaximizeWindow,
+        "ToggleAlwaysOnTop" => BuiltinAction::ToggleAlwaysOnTop,
         "TileWindowLeft" => BuiltinAction::TileWindowLeft,
         "TileWindowRight" => BuiltinAction::TileWindowRight,
+        "SnapLeft" => BuiltinAction::SnapLeft,
+        "SnapRight" => BuiltinAction::SnapRight,
+        "SnapUp" => BuiltinAction::SnapUp,
+        "SnapTopLeft" => BuiltinAction::SnapTopLeft,
+        "SnapTopRight" => BuiltinAction::SnapTopRight,
+        "SnapBottomLeft" => BuiltinAction::SnapBottomLeft,
+        "SnapBottomRight" => BuiltinAction::SnapBottomRight,
+        "FocusLeft" => BuiltinAction::FocusLeft,
+        "FocusRight" => BuiltinAction::FocusRight,
+        "FocusUp" => BuiltinAction::FocusUp,
+        "FocusDown" => BuiltinAction::FocusDown,
+        "SwapWindowLeft" => BuiltinAction::SwapWindowLeft,
+        "SwapWindowRight" => BuiltinAction::SwapWindowRight,
+        "SwapWindowUp" => BuiltinAction::SwapWindowUp,
+        "SwapWindowDown" => BuiltinAction::SwapWindowDown,
+        "CycleWorkspaceLayout" => BuiltinAction::CycleWorkspaceLayout,
         "ExposeShowDesktop" => BuiltinAction::ExposeShowDesktop,
         "ExposeShowAll" => BuiltinAction::ExposeShowAll,
         "SceneSnapshot" => BuiltinAction::SceneSnapshot,
@@ -239,10 +493,15 @@ fn parse_builtin(name: &str, index: Option<usize>) -> Result<BuiltinAction, Shor
         "VolumeUp" => BuiltinAction::VolumeUp,
         "VolumeDown" => BuiltinAction::VolumeDown,
         "VolumeMute" => BuiltinAction::VolumeMute,
+        "ToggleMicMute" => BuiltinAction::ToggleMicMute,
         "MediaPlayPause" => BuiltinAction::MediaPlayPause,
         "MediaNext" => BuiltinAction::MediaNext,
         "MediaPrev" => BuiltinAction::MediaPrev,
         "MediaStop" => BuiltinAction::MediaStop,
+        "ScreenshotRegion" => BuiltinAction::ScreenshotRegion,
+        "StartRecording" => BuiltinAction::StartRecording,
+        "StopRecording" => BuiltinAction::StopRecording,
+        "ClipboardHistory" => BuiltinAction::ClipboardHistory,
         "Screen" => {
             let index = index.ok_or_else(|| ShortcutError::MissingIndex(name.to_string()))?;
             BuiltinAction::Screen { index }
@@ -251,11 +510,108 @@ fn parse_builtin(name: &str, index: Option<usize>) -> Result<BuiltinAction, Shor
             let index = index.ok_or_else(|| ShortcutError::MissingIndex(name.to_string()))?;
             BuiltinAction::WorkspaceNum { index }
         }
+        "GoToWorkspace" => BuiltinAction::GoToWorkspace {
+            target: workspace_target(index, workspace_name)?,
+        },
+        "MoveWindowToWorkspace" => BuiltinAction::MoveWindowToWorkspace {
+            target: workspace_target(index, workspace_name)?,
+        },
+        "CreateWorkspace" => BuiltinAction::CreateWorkspace {
+            name: workspace_name,
+        },
+        "RenameWorkspace" => BuiltinAction::RenameWorkspace {
+            name: workspace_name,
+        },
+        "RemoveEmptyWorkspaces" => BuiltinAction::RemoveEmptyWorkspaces,
+        "CycleKeyboardLayout" => BuiltinAction::CycleKeyboardLayout,
+        "MoveWindow" => BuiltinAction::MoveWindow,
+        "NextWorkspace" => BuiltinAction::GoToWorkspace {
+            target: WorkspaceTarget::Next,
+        },
+        "PreviousWorkspace" => BuiltinAction::GoToWorkspace {
+            target: WorkspaceTarget::Previous,
+        },
         other => return Err(ShortcutError::UnknownBuiltin(other.to_string())),
     })
 }
 
-fn parse_trigger(trigger: &str) -> Result<ShortcutTrigger, ShortcutError> {
+/// Outcome of feeding one key press to the shortcut sequence matcher.
+#[derive(Debug)]
+pub enum SequenceMatch<'a> {
+    /// The key completes this binding
+    Complete(&'a ShortcutBinding),
+    /// The key is the next chord of at least one longer sequence
+    Prefix(ShortcutTrigger),
+    /// No binding continues with this key
+    None,
+}
+
+/// Match a key press against the bindings, given the chords of a sequence
+/// already typed (`pending`, empty when no sequence is in progress).
+///
+/// A key that both completes a binding and continues a longer sequence is
+/// treated as a prefix, so the longer sequence stays reachable.
+pub fn match_sequence<'a>(
+    bindings: &'a [ShortcutBinding],
+    pending: &[ShortcutTrigger],
+    modifiers: &ModifiersState,
+    keysym: xkb::Keysym,
+) -> SequenceMatch<'a> {
+    let depth = pending.len();
+    let mut complete = None;
+    for binding in bindings {
+        if binding.chords.len() <= depth || !binding.chords.starts_with(pending) {
+            continue;
+        }
+        let chord = &binding.chords[depth];
+        if !chord.matches(modifiers, keysym) {
+            continue;
+        }
+        if binding.chords.len() > depth + 1 {
+            return SequenceMatch::Prefix(chord.clone());
+        }
+        complete.get_or_insert(binding);
+    }
+    complete.map_or(SequenceMatch::None, SequenceMatch::Complete)
+}
+
+/// Human readable form of a chord sequence, e.g. `Logo+w c`.
+pub fn sequence_id(chords: &[ShortcutTrigger]) -> String {
+    chords
+        .iter()
+        .map(ShortcutTrigger::canonical_id)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse a trigger made of one or more whitespace separated chords, e.g.
+/// `Logo+w c`. Spaces around `+` stay part of a single chord.
+fn parse_sequence(trigger: &str) -> Result<Vec<ShortcutTrigger>, ShortcutError> {
+    let joined = trigger
+        .split('+')
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("+");
+    let chords = joined
+        .split_whitespace()
+        .map(parse_trigger)
+        .collect::<Result<Vec<_>, _>>()?;
+    if chords.is_empty() {
+        return Err(ShortcutError::InvalidTrigger(trigger.to_string()));
+    }
+    Ok(chords)
+}
+
+/// Modifiers of a trigger and its final, non-modifier part.
+struct ParsedModifiers<'a> {
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    logo: bool,
+    key: &'a str,
+}
+
+fn parse_modifiers(trigger: &str) -> Result<ParsedModifiers<'_>, ShortcutError> {
     let parts: Vec<&str> = trigger.split('+').collect();
     if parts.is_empty() {
         return Err(ShortcutError::InvalidTrigger(trigger.to_string()));
@@ -283,17 +639,57 @@ fn parse_trigger(trigger: &str) -> Result<ShortcutTrigger, ShortcutError> {
         return Err(ShortcutError::InvalidTrigger(trigger.to_string()));
     }
 
-    let keysym = parse_keysym(key)?;
-
-    Ok(ShortcutTrigger {
+    Ok(ParsedModifiers {
         ctrl,
         alt,
         shift,
         logo,
+        key,
+    })
+}
+
+pub fn parse_trigger(trigger: &str) -> Result<ShortcutTrigger, ShortcutError> {
+    let parsed = parse_modifiers(trigger)?;
+    let keysym = parse_keysym(parsed.key)?;
+
+    Ok(ShortcutTrigger {
+        ctrl: parsed.ctrl,
+        alt: parsed.alt,
+        shift: parsed.shift,
+        logo: parsed.logo,
         keysym,
     })
 }
 
+fn parse_pointer_trigger(trigger: &str) -> Result<PointerTrigger, ShortcutError> {
+    let parsed = parse_modifiers(trigger)?;
+    let input = parse_pointer_input(parsed.key)?;
+
+    Ok(PointerTrigger {
+        ctrl: parsed.ctrl,
+        alt: parsed.alt,
+        shift: parsed.shift,
+        logo: parsed.logo,
+        input,
+    })
+}
+
+fn parse_pointer_input(name: &str) -> Result<PointerInput, ShortcutError> {
+    // Button codes from linux/input-event-codes.h
+    Ok(match name.to_ascii_lowercase().as_str() {
+        "leftclick" | "left" | "btn_left" => PointerInput::Button(0x110),
+        "rightclick" | "right" | "btn_right" => PointerInput::Button(0x111),
+        "middleclick" | "middle" | "btn_middle" => PointerInput::Button(0x112),
+        "backclick" | "back" | "btn_side" => PointerInput::Button(0x113),
+        "forwardclick" | "forward" | "btn_extra" => PointerInput::Button(0x114),
+        "scrollup" => PointerInput::Scroll(ScrollDirection::Up),
+        "scrolldown" => PointerInput::Scroll(ScrollDirection::Down),
+        "scrollleft" => PointerInput::Scroll(ScrollDirection::Left),
+        "scrollright" => PointerInput::Scroll(ScrollDirection::Right),
+        _ => return Err(ShortcutError::UnknownPointerInput(name.to_string())),
+    })
+}
+
 fn parse_keysym(key: &str) -> Result<xkb::Keysym, ShortcutError> {
     let alias = match key {
         "ArrowUp" => Some("Up"),
@@ -386,10 +782,10 @@ mod tests {
             .iter()
             .find(|binding| matches!(binding.action, ShortcutAction::Builtin(_)))
             .expect("quit binding present");
-        assert!(quit_binding.trigger.logo);
-        assert!(!quit_binding.trigger.ctrl);
+        assert!(quit_binding.chords[0].logo);
+        assert!(!quit_binding.chords[0].ctrl);
         assert_eq!(
-            quit_binding.trigger.keysym,
+            quit_binding.chords[0].keysym,
             xkb::keysym_from_name("q", xkb::KEYSYM_NO_FLAGS)
         );
 
@@ -397,10 +793,10 @@ mod tests {
             .iter()
             .find(|binding| matches!(binding.action, ShortcutAction::OpenDefaultApp { .. }))
             .expect("open default binding present");
-        assert!(open_default_binding.trigger.logo);
-        assert!(open_default_binding.trigger.shift);
+        assert!(open_default_binding.chords[0].logo);
+        assert!(open_default_binding.chords[0].shift);
         assert_eq!(
-            open_default_binding.trigger.keysym,
+            open_default_binding.chords[0].keysym,
             xkb::keysym_from_name("Return", xkb::KEYSYM_NO_FLAGS)
         );
     }
@@ -425,7 +821,148 @@ mod tests {
         let uppercase = xkb::keysym_from_name("W", xkb::KEYSYM_NO_FLAGS);
         let lowercase = xkb::keysym_from_name("w", xkb::KEYSYM_NO_FLAGS);
 
-        assert!(binding.trigger.matches(&modifiers, uppercase));
-        assert!(binding.trigger.matches(&modifiers, lowercase));
+        assert!(binding.chords[0].matches(&modifiers, uppercase));
+        assert!(binding.chords[0].matches(&modifiers, lowercase));
+    }
+
+    #[test]
+    fn workspace_actions_take_index_or_name() {
+        let by_name = parse_action(&ShortcutActionConfig::BuiltinDetailed {
+            builtin: "GoToWorkspace".into(),
+            index: Some(1),
+            name: Some("web".into()),
+        });
+        assert!(matches!(
+            by_name,
+            Ok(ShortcutAction::Builtin(BuiltinAction::GoToWorkspace {
+                target: WorkspaceTarget::Name(ref name)
+            })) if name == "web"
+        ));
+
+        let by_index = parse_action(&ShortcutActionConfig::BuiltinDetailed {
+            builtin: "MoveWindowToWorkspace".into(),
+            index: Some(2),
+            name: None,
+        });
+        assert!(matches!(
+            by_index,
+            Ok(ShortcutAction::Builtin(
+                BuiltinAction::MoveWindowToWorkspace {
+                    target: WorkspaceTarget::Index(2)
+                }
+            ))
+        ));
+
+        let missing = parse_action(&ShortcutActionConfig::BuiltinName("GoToWorkspace".into()));
+        assert!(matches!(missing, Err(ShortcutError::MissingIndex(_))));
+    }
+
+    #[test]
+    fn exec_action_takes_a_command_line() {
+        let map: ShortcutMap = toml::from_str(
+            r#"
+            "Logo+Return" = { exec = ["kitty", "-e", "htop"] }
+            "Logo+Print" = { exec = [] }
+            "#,
+        )
+        .unwrap();
+        let bindings = build_bindings(&map);
+        assert_eq!(bindings.len(), 1);
+        assert!(matches!(
+            &bindings[0].action,
+            ShortcutAction::Exec(argv) if argv == &["kitty", "-e", "htop"]
+        ));
+    }
+
+    #[test]
+    fn chord_sequences_parse_and_match_step_by_step() {
+        let mut map = ShortcutMap::new();
+        map.insert(
+            "Logo + w c".into(),
+            ShortcutActionConfig::BuiltinName("CloseWindow".into()),
+        );
+        map.insert(
+            "Logo+w".into(),
+            ShortcutActionConfig::BuiltinName("Quit".into()),
+        );
+        let bindings = build_bindings(&map);
+        let sequence = bindings
+            .iter()
+            .find(|binding| binding.chords.len() == 2)
+            .unwrap();
+        assert_eq!(sequence_id(&sequence.chords), "Logo+w c");
+
+        let mut logo = ModifiersState::default();
+        logo.logo = true;
+        let none = ModifiersState::default();
+        let w = xkb::keysym_from_name("w", xkb::KEYSYM_NO_FLAGS);
+        let c = xkb::keysym_from_name("c", xkb::KEYSYM_NO_FLAGS);
+
+        let SequenceMatch::Prefix(prefix) = match_sequence(&bindings, &[], &logo, w) else {
+            panic!("Logo+w should start the sequence");
+        };
+        let pending = vec![prefix];
+        assert!(matches!(
+            match_sequence(&bindings, &pending, &none, c),
+            SequenceMatch::Complete(binding)
+                if matches!(binding.action, ShortcutAction::Builtin(BuiltinAction::CloseWindow))
+        ));
+        assert!(matches!(
+            match_sequence(&bindings, &pending, &none, w),
+            SequenceMatch::None
+        ));
+        assert!(matches!(
+            match_sequence(&bindings, &[], &none, c),
+            SequenceMatch::None
+        ));
+    }
+
+    #[test]
+    fn pointer_bindings_parse_buttons_and_scroll() {
+        let mut map = ShortcutMap::new();
+        map.insert(
+            "Super+LeftClick".into(),
+            ShortcutActionConfig::BuiltinName("MoveWindow".into()),
+        );
+        map.insert(
+            "Super+ScrollDown".into(),
+            ShortcutActionConfig::BuiltinName("NextWorkspace".into()),
+        );
+        map.insert(
+            "Super+ScrollUp".into(),
+            ShortcutActionConfig::BuiltinName("MoveWindow".into()),
+        );
+        map.insert(
+            "Super+Wheel".into(),
+            ShortcutActionConfig::BuiltinName("NextWorkspace".into()),
+        );
+        let bindings = build_pointer_bindings(&map);
+        assert_eq!(bindings.len(), 2);
+
+        let mut logo = ModifiersState::default();
+        logo.logo = true;
+        let drag = find_pointer_binding(&bindings, &logo, PointerInput::Button(0x110)).unwrap();
+        assert!(matches!(
+            drag.action,
+            ShortcutAction::Builtin(BuiltinAction::MoveWindow)
+        ));
+        let scroll = PointerInput::Scroll(ScrollDirection::Down);
+        assert!(matches!(
+            find_pointer_binding(&bindings, &logo, scroll).map(|b| &b.action),
+            Some(ShortcutAction::Builtin(BuiltinAction::GoToWorkspace {
+                target: WorkspaceTarget::Next
+            }))
+        ));
+        assert!(find_pointer_binding(&bindings, &ModifiersState::default(), scroll).is_none());
+    }
+
+    #[test]
+    fn move_window_is_rejected_on_keys() {
+        let mut map = ShortcutMap::new();
+        map.insert(
+            "Logo+m".into(),
+            ShortcutActionConfig::BuiltinName("MoveWindow".into()),
+        );
+        assert!(build_bindings(&map).is_empty());
     }
 }
@@ -0,0 +1,203 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// A `[[window_rules]]` entry: matchers plus the actions applied when a
+/// toplevel first maps.
+///
+/// Every matcher that is set must match; a rule without matchers applies to
+/// all windows. Rules are evaluated in order and only the first match is used.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WindowRule {
+    /// Exact app id (xdg `app_id`, or X11 class).
+    #[serde(default)]
+    pub app_id: Option<String>,
+    /// Regex searched in the window title; unanchored, so a plain string
+    /// matches any title containing it.
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Content type the client declared through `wp_content_type_v1` by its
+    /// first commit.
+    #[serde(default)]
+    pub content_type: Option<ContentType>,
+
+    /// Workspace index (0-based) to open the window on.
+    #[serde(default)]
+    pub workspace: Option<usize>,
+    /// Name of the output (e.g. "HDMI-A-1") to open the window on.
+    #[serde(default)]
+    pub output: Option<String>,
+    /// Keep the window out of tiling and snapping layouts.
+    #[serde(default)]
+    pub floating: Option<bool>,
+    /// Keep the window above normal windows.
+    #[serde(default)]
+    pub always_on_top: Option<bool>,
+    /// Open the window maximized.
+    #[serde(default)]
+    pub maximized: Option<bool>,
+    /// Opacity of the window content, 0.0 - 1.0.
+    #[serde(default)]
+    pub opacity: Option<f32>,
+
+    #[serde(skip)]
+    title_regex: OnceLock<Option<Regex>>,
+}
+
+/// Kind of content a surface shows, as declared by the client through
+/// `wp_content_type_v1`. Windows that declare nothing are `none`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentType {
+    #[default]
+    None,
+    Photo,
+    Video,
+    Game,
+}
+
+impl WindowRule {
+    /// Whether this rule applies to a window with the given app id, title
+    /// and content type.
+    pub fn matches(&self, app_id: &str, title: &str, content_type: ContentType) -> bool {
+        if let Some(expected) = &self.app_id {
+            if expected != app_id {
+                return false;
+            }
+        }
+        if self
+            .content_type
+            .is_some_and(|expected| expected != content_type)
+        {
+            return false;
+        }
+        if let Some(pattern) = &self.title {
+            let regex = self.title_regex.get_or_init(|| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(err) => {
+                    warn!("Invalid window rule title regex '{pattern}': {err}");
+                    None
+                }
+            });
+            match regex {
+                Some(regex) if regex.is_match(title) => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+}
+
+/// First rule matching the window, if any.
+pub fn find_window_rule<'a>(
+    rules: &'a [WindowRule],
+    app_id: &str,
+    title: &str,
+    content_type: ContentType,
+) -> Option<&'a WindowRule> {
+    rules
+        .iter()
+        .find(|rule| rule.matches(app_id, title, content_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(toml_str: &str) -> Vec<WindowRule> {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            window_rules: Vec<WindowRule>,
+        }
+        toml::from_str::<Wrapper>(toml_str)
+            .expect("rules should deserialize")
+            .window_rules
+    }
+
+    #[test]
+    fn title_matches_substring_for_any_app() {
+        let rules = rules(
+            r#"
+            [[window_rules]]
+            title = "Picture-in-Picture"
+            floating = true
+            "#,
+        );
+        let rule = find_window_rule(
+            &rules,
+            "firefox",
+            "Picture-in-Picture - YouTube",
+            ContentType::None,
+        );
+        assert_eq!(rule.and_then(|r| r.floating), Some(true));
+        assert!(
+            find_window_rule(&rules, "firefox", "Mozilla Firefox", ContentType::None).is_none()
+        );
+    }
+
+    #[test]
+    fn all_matchers_must_match() {
+        let rules = rules(
+            r#"
+            [[window_rules]]
+            app_id = "kitty"
+            title = "^scratch$"
+            opacity = 0.9
+            "#,
+        );
+        assert!(find_window_rule(&rules, "kitty", "scratch", ContentType::None).is_some());
+        assert!(find_window_rule(&rules, "kitty", "scratchpad", ContentType::None).is_none());
+        assert!(find_window_rule(&rules, "alacritty", "scratch", ContentType::None).is_none());
+    }
+
+    #[test]
+    fn first_match_wins() {
+        let rules = rules(
+            r#"
+            [[window_rules]]
+            app_id = "firefox"
+            workspace = 1
+
+            [[window_rules]]
+            app_id = "firefox"
+            workspace = 2
+            maximized = true
+            "#,
+        );
+        let rule = find_window_rule(&rules, "firefox", "", ContentType::None).unwrap();
+        assert_eq!(rule.workspace, Some(1));
+        assert_eq!(rule.maximized, None);
+    }
+
+    #[test]
+    fn content_type_must_match_when_set() {
+        let rules = rules(
+            r#"
+            [[window_rules]]
+            content_type = "game"
+            output = "DP-1"
+            "#,
+        );
+        assert!(find_window_rule(&rules, "steam_app_1", "", ContentType::Game).is_some());
+        assert!(find_window_rule(&rules, "mpv", "", ContentType::Video).is_none());
+        assert!(find_window_rule(&rules, "kitty", "", ContentType::None).is_none());
+    }
+
+    #[test]
+    fn invalid_regex_never_matches() {
+        let rules = rules(
+            r#"
+            [[window_rules]]
+            title = "("
+            floating = true
+
+            [[window_rules]]
+            floating = false
+            "#,
+        );
+        let rule = find_window_rule(&rules, "any", "(", ContentType::None).unwrap();
+        assert_eq!(rule.floating, Some(false));
+    }
+}
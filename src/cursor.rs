@@ -1,9 +1,10 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs::File;
 use std::io::Read;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context};
 use smithay::backend::allocator::Fourcc;
@@ -88,10 +89,24 @@ impl Cursor {
 
 type XCursorCache = HashMap<(CursorIcon, i32), Option<Rc<XCursor>>>;
 
+/// How long a seat cursor takes to resize when it moves onto an output with
+/// another scale
+const CURSOR_SCALE_DURATION: Duration = Duration::from_millis(150);
+
+/// Loads themed cursors and tracks the cursor image of every seat.
+///
+/// Seats are keyed by name. A seat has a cursor on screen once it is added,
+/// either explicitly or when a client first sets its cursor image.
+///
+/// The cursor size is logical: named cursors are drawn at `size` times the
+/// scale of the output under the seat's pointer, easing between scales when
+/// the pointer crosses outputs.
 pub struct CursorManager {
     theme: CursorTheme,
     size: u8,
-    current_cursor: CursorImageStatus,
+    seat_cursors: HashMap<String, CursorImageStatus>,
+    cursor_scales: HashMap<String, CursorScale>,
+    visibility: CursorVisibility,
     named_cursor_cache: RefCell<XCursorCache>,
 }
 
@@ -104,7 +119,9 @@ impl CursorManager {
         Self {
             theme,
             size,
-            current_cursor: CursorImageStatus::default_named(),
+            seat_cursors: HashMap::new(),
+            cursor_scales: HashMap::new(),
+            visibility: CursorVisibility::default(),
             named_cursor_cache: Default::default(),
         }
     }
@@ -116,18 +133,87 @@ impl CursorManager {
         self.named_cursor_cache.get_mut().clear();
     }
 
+    /// Give `seat` a cursor on screen, with the default image unless it
+    /// already has one
+    pub fn add_seat(&mut self, seat: &str) {
+        if !self.seat_cursors.contains_key(seat) {
+            self.seat_cursors
+                .insert(seat.to_string(), CursorImageStatus::default_named());
+            self.visibility.pointer_input(seat, Instant::now());
+        }
+    }
+
+    /// Hide the cursor of `seat` until its pointer is used again. Returns
+    /// whether it was shown.
+    pub fn hide_cursor(&mut self, seat: &str) -> bool {
+        self.seat_cursors.contains_key(seat) && self.visibility.hide(seat)
+    }
+
+    /// The pointer of `seat` was used: show its cursor again. Returns
+    /// whether it was hidden.
+    pub fn pointer_input(&mut self, seat: &str) -> bool {
+        self.visibility.pointer_input(seat, Instant::now())
+    }
+
+    /// Hide the cursors whose pointer has not been used for `timeout`.
+    /// Returns whether any was hidden, and how long until the next shown
+    /// cursor goes idle.
+    pub fn hide_idle_cursors(&mut self, timeout: Duration) -> (bool, Option<Duration>) {
+        self.visibility.hide_idle(timeout, Instant::now())
+    }
+
+    /// Names of the seats with a cursor on screen
+    pub fn seats(&self) -> impl Iterator<Item = &str> {
+        self.seat_cursors.keys().map(String::as_str)
+    }
+
     pub fn check_cursor_image_surface_alive(&mut self) {
-        if let CursorImageStatus::Surface(surface) = &self.current_cursor {
-            if !surface.alive() {
-                self.current_cursor = CursorImageStatus::default_named();
+        for cursor in self.seat_cursors.values_mut() {
+            if let CursorImageStatus::Surface(surface) = cursor {
+                if !surface.alive() {
+                    *cursor = CursorImageStatus::default_named();
+                }
+            }
+        }
+    }
+
+    /// `seat`'s pointer is over an output with `scale`: resize its cursor to
+    /// it, eased unless `animate` is false. Returns whether the scale changed.
+    pub fn set_output_scale(&mut self, seat: &str, scale: f64, animate: bool) -> bool {
+        let now = Instant::now();
+        match self.cursor_scales.get_mut(seat) {
+            Some(cursor_scale) => cursor_scale.set_target(scale, now, animate),
+            None => {
+                self.cursor_scales
+                    .insert(seat.to_string(), CursorScale::new(scale, now));
+                true
             }
         }
     }
 
-    pub fn get_render_cursor(&self, scale: i32) -> RenderCursor {
-        match self.current_cursor.clone() {
-            CursorImageStatus::Hidden => RenderCursor::Hidden,
-            CursorImageStatus::Surface(surface) => {
+    /// Scale `seat`'s cursor is drawn at right now, if its output is known
+    pub fn cursor_scale(&self, seat: &str) -> Option<f64> {
+        self.cursor_scales
+            .get(seat)
+            .map(|cursor_scale| cursor_scale.at(Instant::now()))
+    }
+
+    /// Whether a seat cursor is still resizing after crossing outputs
+    pub fn is_scale_animating(&self) -> bool {
+        let now = Instant::now();
+        self.cursor_scales
+            .values()
+            .any(|cursor_scale| cursor_scale.is_animating(now))
+    }
+
+    /// The cursor to draw for `seat`, with named cursors sized for `scale`
+    pub fn get_render_cursor(&self, seat: &str, scale: f64) -> RenderCursor {
+        if self.visibility.is_hidden(seat) {
+            return RenderCursor::Hidden;
+        }
+        match self.seat_cursors.get(seat).cloned() {
+            None | Some(CursorImageStatus::Hidden) => RenderCursor::Hidden,
+            Some(CursorImageStatus::Surface(surface)) => {
                 let hotspot = with_states(&surface, |states| {
                     states
                         .data_map
@@ -140,32 +226,41 @@ impl CursorManager {
 
                 RenderCursor::Surface { hotspot, surface }
             }
-            CursorImageStatus::Named(icon) => self.get_render_cursor_named(icon, scale),
+            Some(CursorImageStatus::Named(icon)) => self.get_render_cursor_named(icon, scale),
         }
     }
 
-    fn get_render_cursor_named(&self, icon: CursorIcon, scale: i32) -> RenderCursor {
-        self.get_cursor_with_name(icon, scale)
+    fn get_render_cursor_named(&self, icon: CursorIcon, scale: f64) -> RenderCursor {
+        let size = self.size as f64 * scale;
+        let xcursor_scale = xcursor_scale(scale);
+        self.get_cursor_with_name(icon, xcursor_scale)
             .map(|cursor| RenderCursor::Named {
                 icon,
-                scale,
+                scale: xcursor_scale,
+                size,
                 cursor,
             })
             .unwrap_or_else(|| RenderCursor::Named {
                 icon: Default::default(),
-                scale,
-                cursor: self.get_default_cursor(scale),
+                scale: xcursor_scale,
+                size,
+                cursor: self.get_default_cursor(xcursor_scale),
             })
     }
 
-    pub fn is_current_cursor_animated(&self, scale: i32) -> bool {
-        match &self.current_cursor {
-            CursorImageStatus::Hidden => false,
-            CursorImageStatus::Surface(_) => false,
-            CursorImageStatus::Named(icon) => self
-                .get_cursor_with_name(*icon, scale)
-                .unwrap_or_else(|| self.get_default_cursor(scale))
-                .is_animated_cursor(),
+    pub fn is_current_cursor_animated(&self, seat: &str, scale: f64) -> bool {
+        if self.visibility.is_hidden(seat) {
+            return false;
+        }
+        match self.seat_cursors.get(seat) {
+            None | Some(CursorImageStatus::Hidden) => false,
+            Some(CursorImageStatus::Surface(_)) => false,
+            Some(CursorImageStatus::Named(icon)) => {
+                let scale = xcursor_scale(scale);
+                self.get_cursor_with_name(*icon, scale)
+                    .unwrap_or_else(|| self.get_default_cursor(scale))
+                    .is_animated_cursor()
+            }
         }
     }
 
@@ -204,12 +299,12 @@ impl CursorManager {
             .unwrap()
     }
 
-    pub fn cursor_image(&self) -> &CursorImageStatus {
-        &self.current_cursor
+    pub fn cursor_image(&self, seat: &str) -> Option<&CursorImageStatus> {
+        self.seat_cursors.get(seat)
     }
 
-    pub fn set_cursor_image(&mut self, cursor: CursorImageStatus) {
-        self.current_cursor = cursor;
+    pub fn set_cursor_image(&mut self, seat: &str, cursor: CursorImageStatus) {
+        self.seat_cursors.insert(seat.to_string(), cursor);
     }
 
     fn load_xcursor(theme: &CursorTheme, name: &str, size: i32) -> anyhow::Result<XCursor> {
@@ -264,15 +359,111 @@ impl CursorManager {
     }
 }
 
+/// Integer scale to load xcursor images at for a cursor drawn at `scale`.
+/// Rounding up keeps fractional and mid-transition sizes sharp, since the
+/// images are only ever scaled down.
+pub fn xcursor_scale(scale: f64) -> i32 {
+    (scale.ceil() as i32).max(1)
+}
+
+/// Scale a seat cursor is drawn at, easing from the scale of the output it
+/// left to the scale of the output it is on
+#[derive(Debug, Clone, Copy)]
+struct CursorScale {
+    from: f64,
+    to: f64,
+    start: Instant,
+}
+
+impl CursorScale {
+    fn new(scale: f64, now: Instant) -> Self {
+        Self {
+            from: scale,
+            to: scale,
+            start: now,
+        }
+    }
+
+    fn progress(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.start);
+        (elapsed.as_secs_f64() / CURSOR_SCALE_DURATION.as_secs_f64()).min(1.0)
+    }
+
+    fn at(&self, now: Instant) -> f64 {
+        // Ease out cubic
+        let t = 1.0 - (1.0 - self.progress(now)).powi(3);
+        self.from + (self.to - self.from) * t
+    }
+
+    fn set_target(&mut self, scale: f64, now: Instant, animate: bool) -> bool {
+        if self.to == scale {
+            return false;
+        }
+        self.from = if animate { self.at(now) } else { scale };
+        self.to = scale;
+        self.start = now;
+        true
+    }
+
+    fn is_animating(&self, now: Instant) -> bool {
+        self.from != self.to && self.progress(now) < 1.0
+    }
+}
+
+/// Which seat cursors are hidden while typing or idle, and when each seat's
+/// pointer was last used. Hiding only affects drawing: the cursor image and
+/// pointer focus are kept.
+#[derive(Debug, Default)]
+struct CursorVisibility {
+    hidden: HashSet<String>,
+    last_pointer_input: HashMap<String, Instant>,
+}
+
+impl CursorVisibility {
+    fn is_hidden(&self, seat: &str) -> bool {
+        self.hidden.contains(seat)
+    }
+
+    fn hide(&mut self, seat: &str) -> bool {
+        self.hidden.insert(seat.to_string())
+    }
+
+    fn pointer_input(&mut self, seat: &str, now: Instant) -> bool {
+        self.last_pointer_input.insert(seat.to_string(), now);
+        self.hidden.remove(seat)
+    }
+
+    fn hide_idle(&mut self, timeout: Duration, now: Instant) -> (bool, Option<Duration>) {
+        let mut changed = false;
+        let mut next = None::<Duration>;
+        for (seat, last) in &self.last_pointer_input {
+            if self.hidden.contains(seat) {
+                continue;
+            }
+            let idle = now.saturating_duration_since(*last);
+            if idle >= timeout {
+                changed |= self.hidden.insert(seat.clone());
+            } else {
+                let remaining = timeout - idle;
+                next = Some(next.map_or(remaining, |next| next.min(remaining)));
+            }
+        }
+        (changed, next)
+    }
+}
+
 pub enum RenderCursor {
     Hidden,
     Surface {
         hotspot: Point<i32, Logical>,
         surface: WlSurface,
     },
+    /// A themed cursor loaded at the integer `scale`, to be drawn `size`
+    /// physical pixels tall
     Named {
         icon: CursorIcon,
         scale: i32,
+        size: f64,
         cursor: Rc<XCursor>,
     },
 }
@@ -308,7 +499,7 @@ impl CursorTextureCache {
                             &frame.pixels_rgba,
                             Fourcc::Argb8888,
                             (frame.width as i32, frame.height as i32),
-                            scale,
+                            1,
                             Transform::Normal,
                             None,
                         )
@@ -355,4 +546,83 @@ impl XCursor {
     pub fn hotspot(image: &Image) -> Point<i32, Physical> {
         (image.xhot as i32, image.yhot as i32).into()
     }
+
+    /// Factor to draw `image` by so the cursor is `size` physical pixels,
+    /// whichever nominal size the theme had closest
+    pub fn image_scale(image: &Image, size: f64) -> f64 {
+        size / image.size.max(1) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pointer_input_shows_a_cursor_hidden_by_typing() {
+        let mut visibility = CursorVisibility::default();
+        let now = Instant::now();
+        visibility.pointer_input("seat0", now);
+        assert!(visibility.hide("seat0"));
+        assert!(!visibility.hide("seat0"));
+        assert!(visibility.is_hidden("seat0"));
+        assert!(!visibility.is_hidden("virtual"));
+        assert!(visibility.pointer_input("seat0", now));
+        assert!(!visibility.is_hidden("seat0"));
+    }
+
+    #[test]
+    fn cursor_scale_eases_to_the_new_output() {
+        let start = Instant::now();
+        let mut scale = CursorScale::new(1.0, start);
+        assert!(!scale.set_target(1.0, start, true));
+        assert!(scale.set_target(2.0, start, true));
+        assert_eq!(scale.at(start), 1.0);
+        assert!(scale.is_animating(start));
+
+        let halfway = scale.at(start + CURSOR_SCALE_DURATION / 2);
+        assert!(halfway > 1.5 && halfway < 2.0);
+
+        let end = start + CURSOR_SCALE_DURATION;
+        assert_eq!(scale.at(end), 2.0);
+        assert!(!scale.is_animating(end));
+
+        // Turning back midway starts from where the cursor is
+        let mid = start + CURSOR_SCALE_DURATION / 2;
+        let mut scale = CursorScale::new(1.0, start);
+        scale.set_target(2.0, start, true);
+        scale.set_target(1.0, mid, true);
+        assert_eq!(scale.at(mid), halfway);
+
+        assert!(scale.set_target(1.5, mid, false));
+        assert_eq!(scale.at(mid), 1.5);
+        assert!(!scale.is_animating(mid));
+    }
+
+    #[test]
+    fn xcursors_load_at_the_next_integer_scale() {
+        assert_eq!(xcursor_scale(1.0), 1);
+        assert_eq!(xcursor_scale(1.25), 2);
+        assert_eq!(xcursor_scale(2.0), 2);
+        assert_eq!(xcursor_scale(0.5), 1);
+    }
+
+    #[test]
+    fn idle_cursors_hide_independently() {
+        let mut visibility = CursorVisibility::default();
+        let start = Instant::now();
+        let timeout = Duration::from_secs(3);
+        visibility.pointer_input("seat0", start);
+        visibility.pointer_input("virtual", start + Duration::from_secs(2));
+
+        let (changed, next) = visibility.hide_idle(timeout, start + Duration::from_secs(1));
+        assert!(!changed);
+        assert_eq!(next, Some(Duration::from_secs(2)));
+
+        let (changed, next) = visibility.hide_idle(timeout, start + Duration::from_secs(4));
+        assert!(changed);
+        assert!(visibility.is_hidden("seat0"));
+        assert!(!visibility.is_hidden("virtual"));
+        assert_eq!(next, Some(Duration::from_secs(1)));
+    }
 }
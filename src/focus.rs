@@ -35,7 +35,9 @@ use crate::{
     interactive_view::InteractiveView,
     shell::WindowElement,
     state::{Backend, Otto},
-    workspaces::{AppSwitcherView, DockView, WindowSelectorView, WorkspaceSelectorView},
+    workspaces::{
+        AppSwitcherView, DockView, WindowSelectorView, WindowSwitcherView, WorkspaceSelectorView,
+    },
 };
 
 #[allow(clippy::large_enum_variant)]
@@ -703,6 +705,16 @@ impl<B: Backend> From<AppSwitcherView> for PointerFocusTarget<B> {
     }
 }
 
+impl<B: Backend> From<WindowSwitcherView> for PointerFocusTarget<B> {
+    fn from(value: WindowSwitcherView) -> Self {
+        let view = value.clone();
+        let d = InteractiveView {
+            view: Box::new(view),
+        };
+        PointerFocusTarget::View(d)
+    }
+}
+
 impl<B: Backend> From<DockView> for PointerFocusTarget<B> {
     fn from(value: DockView) -> Self {
         let view = value.clone();
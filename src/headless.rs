@@ -365,6 +365,21 @@ impl HeadlessHandle {
         });
     }
 
+    /// Current pointer position (logical pixels).
+    pub fn pointer_location(&self) -> (f64, f64) {
+        self.query(|state| {
+            let location = state.pointer.current_location();
+            (location.x, location.y)
+        })
+    }
+
+    /// Mark the session locked or unlocked, as a lock screen would.
+    pub fn set_session_locked(&self, locked: bool) {
+        self.with_state(move |state| {
+            state.session_locked = locked;
+        });
+    }
+
     /// Return the title of the currently hovered window in expose, if any.
     pub fn expose_selected_title(&self) -> Option<String> {
         self.query(|state| {
@@ -547,7 +562,7 @@ fn run_headless_loop(
 
     state
         .workspaces
-        .set_screen_dimension(config.width, config.height);
+        .set_screen_dimension(config.width, config.height, config_screen_scale);
     state.workspaces.map_output(&output, (0, 0));
 
     let socket_name = state
@@ -1,9 +1,13 @@
-use std::{fs, process::Command, sync::atomic::Ordering};
+use std::{
+    fs,
+    process::{Command, Stdio},
+    sync::atomic::Ordering,
+};
 
-use brightness::blocking::Brightness;
 use freedesktop_desktop_entry::DesktopEntry;
 use smithay::{
     reexports::wayland_protocols::xdg::decoration::zv1::server::zxdg_toplevel_decoration_v1,
+    utils::IsAlive,
     wayland::{compositor::with_states, shell::xdg::XdgToplevelSurfaceData},
 };
 use tracing::{error, info, warn};
@@ -12,10 +16,11 @@ use crate::{
     audio::MediaController,
     config::{
         default_apps,
-        shortcuts::{BuiltinAction, ShortcutAction},
+        shortcuts::{BuiltinAction, ShortcutAction, WorkspaceTarget},
         Config,
     },
-    state::Backend,
+    state::{region_selection::RegionSelectionPurpose, Backend},
+    workspaces::{Direction, TileZone},
     Otto,
 };
 
@@ -29,43 +34,103 @@ pub enum KeyAction {
     VtSwitch(i32),
     /// run a command
     Run((String, Vec<String>)),
+    /// run a command detached from the compositor
+    Exec(Vec<String>),
     /// Switch the current screen
     Screen(usize),
     ScaleUp,
     ScaleDown,
     RotateOutput,
+    ToggleVrr,
+    ToggleNightLight,
+    ToggleFpsOverlay,
+    ToggleTestPattern,
+    ToggleReduceMotion,
+    ToggleDarkMode,
+    ToggleZoom,
+    ZoomIn,
+    ZoomOut,
     ToggleDecorations,
     ApplicationSwitchNext,
     ApplicationSwitchPrev,
     ApplicationSwitchQuit,
+    WindowSwitchNext,
+    WindowSwitchPrev,
     ToggleMaximize,
+    ToggleAlwaysOnTop,
     TileLeft,
     TileRight,
+    /// Snap the focused window into a screen zone, cycling widths on repeat
+    Snap(TileZone),
+    /// Focus the nearest window in a direction
+    FocusDirection(Direction),
+    /// Swap the focused tile with its neighbor in a direction
+    SwapWindow(Direction),
+    CycleWorkspaceLayout,
     CloseWindow,
     ApplicationSwitchNextWindow,
     ExposeShowDesktop,
     ExposeShowAll,
+    /// Type a character into the expose filter
+    ExposeFilterInput(char),
+    ExposeFilterBackspace,
+    /// Close expose focusing the selected window
+    ExposeConfirm,
+    /// Close expose keeping the current focus
+    ExposeCancel,
     WorkspaceNum(usize),
+    GoToWorkspace(WorkspaceTarget),
+    MoveWindowToWorkspace(WorkspaceTarget),
+    CreateWorkspace(Option<String>),
+    RenameWorkspace(Option<String>),
+    RemoveEmptyWorkspaces,
+    CycleKeyboardLayout,
     SceneSnapshot,
     BrightnessUp,
     BrightnessDown,
     VolumeUp,
     VolumeDown,
     VolumeMute,
+    ToggleMicMute,
     MediaPlayPause,
     MediaNext,
     MediaPrev,
     MediaStop,
+    /// Interactively select a region and save a screenshot of it
+    ScreenshotRegion,
+    /// Pick a source and record it to a file
+    StartRecording,
+    StopRecording,
+    RegionSelectConfirm,
+    RegionSelectCancel,
+    /// Show the clipboard history picker, or close it when shown
+    ClipboardHistory,
+    /// Move the picker selection by this many entries
+    ClipboardPickerStep(isize),
+    /// Copy the selected entry again and close the picker
+    ClipboardPickerConfirm,
+    ClipboardPickerCancel,
     /// Do nothing more
     None,
 }
 
 impl<BackendData: Backend> Otto<BackendData> {
     pub fn launch_program(&mut self, cmd: String, args: Vec<String>) {
+        let _ = self.launch_program_with_env(cmd, args, Vec::new());
+    }
+
+    /// `launch_program` with extra environment variables for the child,
+    /// returning whether it could be started.
+    pub fn launch_program_with_env(
+        &mut self,
+        cmd: String,
+        args: Vec<String>,
+        env: Vec<(String, String)>,
+    ) -> std::io::Result<()> {
         info!(program = %cmd, args = ?args, "Starting program");
 
         let mut command = Command::new(&cmd);
-        command.args(&args).envs(
+        command.args(&args).envs(env).envs(
             self.socket_name
                 .clone()
                 .map(|v| ("WAYLAND_DISPLAY", v))
@@ -84,6 +149,8 @@ impl<BackendData: Backend> Otto<BackendData> {
         // them dangling. PR_SET_PDEATHSIG is relative to the spawning thread, so
         // this relies on launch_program always being called from the main event
         // loop thread (which lives for the whole process).
+        // SAFETY: the closure runs in the forked child before exec and only
+        // calls async-signal-safe functions (prctl, getppid, _exit).
         #[cfg(target_os = "linux")]
         unsafe {
             use std::os::unix::process::CommandExt;
@@ -107,9 +174,64 @@ impl<BackendData: Backend> Otto<BackendData> {
             });
         }
 
-        if let Err(e) = command.spawn() {
+        command.spawn().map(|_| ()).inspect_err(|e| {
             error!(program = %cmd, err = %e, "Failed to start program");
+        })
+    }
+
+    /// Start `argv` detached from the compositor.
+    ///
+    /// Unlike `launch_program`, the child gets its own session and outlives
+    /// Otto, so scripts and launchers bound to shortcuts survive a restart.
+    /// `WAYLAND_DISPLAY` always points at this compositor, even when Otto
+    /// runs nested and inherited another one; `XDG_*` are inherited as is.
+    pub fn spawn_detached(&mut self, argv: Vec<String>) {
+        let Some((cmd, args)) = argv.split_first() else {
+            return;
+        };
+        info!(program = %cmd, args = ?args, "Spawning detached program");
+
+        let mut command = Command::new(cmd);
+        command
+            .args(args)
+            .stdin(Stdio::null())
+            .env_remove("WAYLAND_SOCKET");
+        match &self.socket_name {
+            Some(socket_name) => command.env("WAYLAND_DISPLAY", socket_name),
+            None => command.env_remove("WAYLAND_DISPLAY"),
+        };
+        #[cfg(feature = "xwayland")]
+        if let Some(xdisplay) = self.xdisplay {
+            command.env("DISPLAY", format!(":{}", xdisplay));
         }
+
+        // SAFETY: the closure runs in the forked child before exec and only
+        // calls setsid, which is async-signal-safe.
+        #[cfg(target_os = "linux")]
+        unsafe {
+            use std::os::unix::process::CommandExt;
+            command.pre_exec(|| {
+                if libc::setsid() < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+
+        match command.spawn() {
+            Ok(child) => self.detached_children.push(child),
+            Err(e) => error!(program = %cmd, err = %e, "Failed to spawn program"),
+        }
+        // Also catches exits whose SIGCHLD went to another thread
+        self.reap_detached_children();
+    }
+
+    /// Wait for the detached children that exited, so they don't linger as
+    /// zombies. Only these children are waited for: other code keeps the exit
+    /// status of the processes it runs.
+    pub fn reap_detached_children(&mut self) {
+        self.detached_children
+            .retain_mut(|child| matches!(child.try_wait(), Ok(None)));
     }
 
     pub fn autostart(&mut self) {
@@ -214,6 +336,10 @@ impl<BackendData: Backend> Otto<BackendData> {
                 self.launch_program(cmd, args);
             }
 
+            KeyAction::Exec(argv) => {
+                self.spawn_detached(argv);
+            }
+
             KeyAction::ToggleDecorations => {
                 for element in self.workspaces.spaces_elements() {
                     #[allow(irrefutable_let_patterns)]
@@ -264,6 +390,22 @@ impl<BackendData: Backend> Otto<BackendData> {
                 }
             }
 
+            KeyAction::ScreenshotRegion => {
+                self.start_region_selection(RegionSelectionPurpose::Screenshot);
+            }
+            KeyAction::RegionSelectConfirm => self.confirm_region_selection(),
+            KeyAction::RegionSelectCancel => self.cancel_region_selection(),
+            KeyAction::ClipboardHistory => self.toggle_clipboard_picker(),
+            KeyAction::ClipboardPickerStep(steps) => {
+                self.workspaces.clipboard_picker.select_step(steps)
+            }
+            KeyAction::ClipboardPickerConfirm => self.confirm_clipboard_picker(),
+            KeyAction::ClipboardPickerCancel => self.workspaces.clipboard_picker.hide(),
+            KeyAction::ExposeFilterInput(c) => self.handle_expose_filter_input(c),
+            KeyAction::ExposeFilterBackspace => self.handle_expose_filter_backspace(),
+            KeyAction::ExposeConfirm => self.close_expose_show_all_and_focus_top(),
+            KeyAction::ExposeCancel => self.cancel_expose(),
+
             _ => unreachable!(
                 "Common key action handler encountered backend specific action {:?}",
                 action
@@ -291,16 +433,73 @@ impl<BackendData: Backend> Otto<BackendData> {
         self.workspaces.quit_appswitcher_app();
     }
 
+    /// Open the window switcher, or move its selection while it is shown
+    pub(crate) fn handle_window_switcher(&mut self, forward: bool) {
+        if self.workspaces.app_switcher.alive() {
+            return;
+        }
+        if self.workspaces.get_show_all() {
+            self.close_expose_show_all_and_focus_top();
+        }
+        if !self.workspaces.window_switcher.alive() && !self.workspaces.show_window_switcher() {
+            return;
+        }
+        if forward {
+            self.workspaces.window_switcher.next();
+        } else {
+            self.workspaces.window_switcher.previous();
+        }
+    }
+
+    /// Close the window switcher, raising and focusing the selected window
+    pub(crate) fn commit_window_switcher(&mut self) {
+        if self.workspaces.window_switcher.alive() {
+            self.workspaces.window_switcher.hide();
+            if let Some(wid) = self.workspaces.window_switcher.current_window() {
+                self.activate_window(&wid);
+            }
+        }
+        self.app_switcher_hold_modifiers = None;
+    }
+
+    /// Close the window switcher keeping the current focus
+    pub(crate) fn cancel_window_switcher(&mut self) {
+        if self.workspaces.window_switcher.alive() {
+            self.workspaces.window_switcher.hide();
+        }
+        self.app_switcher_hold_modifiers = None;
+    }
+
     pub(crate) fn handle_toggle_maximize(&mut self) {
         self.toggle_maximize_focused_window();
     }
 
+    pub(crate) fn handle_toggle_always_on_top(&mut self) {
+        self.toggle_always_on_top_focused_window();
+    }
+
     pub(crate) fn handle_tile_left(&mut self) {
-        self.tile_focused_window(crate::workspaces::TileZone::LeftHalf);
+        self.tile_focused_window(TileZone::LeftHalf);
     }
 
     pub(crate) fn handle_tile_right(&mut self) {
-        self.tile_focused_window(crate::workspaces::TileZone::RightHalf);
+        self.tile_focused_window(TileZone::RightHalf);
+    }
+
+    pub(crate) fn handle_snap(&mut self, zone: TileZone) {
+        self.snap_focused_window(zone);
+    }
+
+    pub(crate) fn handle_focus_direction(&mut self, direction: Direction) {
+        self.focus_window_in_direction(direction);
+    }
+
+    pub(crate) fn handle_swap_window(&mut self, direction: Direction) {
+        self.swap_window_in_direction(direction);
+    }
+
+    pub(crate) fn handle_cycle_workspace_layout(&mut self) {
+        self.cycle_workspace_layout();
     }
 
     pub(crate) fn handle_close_window(&mut self) {
@@ -340,18 +539,75 @@ impl<BackendData: Backend> Otto<BackendData> {
         }
     }
 
+    pub(crate) fn handle_expose_filter_input(&mut self, c: char) {
+        let workspace_index = self.workspaces.get_current_workspace_index();
+        if let Some(workspace) = self.workspaces.get_workspace_at(workspace_index) {
+            let mut filter = workspace.window_selector_view.filter();
+            filter.push(c);
+            workspace.window_selector_view.set_filter(&filter);
+        }
+    }
+
+    pub(crate) fn handle_expose_filter_backspace(&mut self) {
+        let workspace_index = self.workspaces.get_current_workspace_index();
+        if let Some(workspace) = self.workspaces.get_workspace_at(workspace_index) {
+            let mut filter = workspace.window_selector_view.filter();
+            filter.pop();
+            workspace.window_selector_view.set_filter(&filter);
+        }
+    }
+
     pub(crate) fn handle_workspace_num(&mut self, n: usize) {
         self.set_current_workspace_index(n);
     }
 
-    pub(crate) fn handle_brightness_up(&mut self) {
-        if let Some(level) = adjust_brightness(10) {
-            self.workspaces.osd.show_brightness(level);
+    pub(crate) fn handle_go_to_workspace(&mut self, target: WorkspaceTarget) {
+        self.go_to_workspace(&target);
+    }
+
+    pub(crate) fn handle_move_window_to_workspace(&mut self, target: WorkspaceTarget) {
+        self.move_focused_window_to_workspace(&target);
+    }
+
+    pub(crate) fn handle_create_workspace(&mut self, name: Option<String>) {
+        let (index, _) = self.workspaces.create_workspace(name);
+        self.set_current_workspace_index(index);
+    }
+
+    pub(crate) fn handle_rename_workspace(&mut self, name: Option<String>) {
+        let index = self.workspaces.get_current_workspace_index();
+        self.workspaces.rename_workspace(index, name);
+    }
+
+    pub(crate) fn handle_remove_empty_workspaces(&mut self) {
+        self.workspaces.remove_empty_workspaces();
+    }
+
+    pub(crate) fn handle_cycle_keyboard_layout(&mut self) {
+        if let Some(name) = self.cycle_keyboard_layout() {
+            self.workspaces.osd.show_keyboard_layout(name);
         }
     }
 
+    pub(crate) fn handle_brightness_up(&mut self) {
+        self.step_backlight(1);
+    }
+
     pub(crate) fn handle_brightness_down(&mut self) {
-        if let Some(level) = adjust_brightness(-10) {
+        self.step_backlight(-1);
+    }
+
+    /// Move the backlight one `[backlight] step` up or down, within
+    /// `min_percent` and 100, and show the new level
+    fn step_backlight(&mut self, direction: i32) {
+        if self.backend_data.backlight().is_none() {
+            info!(
+                "Backlight control is not supported on the {} backend",
+                self.backend_data.backend_name()
+            );
+            return;
+        }
+        if let Some(level) = self.backend_data.step_backlight(direction) {
             self.workspaces.osd.show_brightness(level);
         }
     }
@@ -361,7 +617,7 @@ impl<BackendData: Backend> Otto<BackendData> {
             if let Err(e) = audio_mgr.increase_volume(5) {
                 error!("Failed to increase volume: {}", e);
             } else {
-                let volume = (audio_mgr.get_state().volume.min(100) * 20 / 100) as u8; // Scale to 0-20
+                let volume = audio_mgr.get_state().volume;
                 self.workspaces.osd.show_volume(volume);
 
                 // Play volume change sound
@@ -377,7 +633,7 @@ impl<BackendData: Backend> Otto<BackendData> {
             if let Err(e) = audio_mgr.decrease_volume(5) {
                 error!("Failed to decrease volume: {}", e);
             } else {
-                let volume = (audio_mgr.get_state().volume.min(100) * 20 / 100) as u8; // Scale to 0-20
+                let volume = audio_mgr.get_state().volume;
                 self.workspaces.osd.show_volume(volume);
 
                 // Play volume change sound
@@ -398,73 +654,56 @@ impl<BackendData: Backend> Otto<BackendData> {
                     0
                 } else {
                     println!("Volume after unmuting: {}", state.volume);
-                    (state.volume.min(100) * 20 / 100) as u8
+                    state.volume
                 };
                 self.workspaces.osd.show_volume(volume);
             }
         }
     }
 
+    pub(crate) fn handle_mic_mute_toggle(&mut self) {
+        if let Some(audio_mgr) = &self.audio_manager {
+            let muted = !audio_mgr.source_muted();
+            if let Err(e) = audio_mgr.set_source_mute(muted) {
+                error!("Failed to toggle microphone mute: {}", e);
+                return;
+            }
+            // Full bar when live, muted icon otherwise
+            self.workspaces
+                .osd
+                .show_microphone(if muted { 0 } else { u8::MAX });
+            let show_indicator = Config::with(|c| c.audio.show_mic_indicator);
+            self.workspaces
+                .mic_indicator
+                .set_visible(show_indicator && muted);
+        }
+    }
+
     pub(crate) fn handle_media_play_pause(&mut self) {
-        if let Err(e) = MediaController::play_pause() {
+        if let Err(e) = MediaController::play_pause(None) {
             error!("Failed to toggle media play/pause: {}", e);
         }
     }
 
     pub(crate) fn handle_media_next(&mut self) {
-        if let Err(e) = MediaController::next() {
+        if let Err(e) = MediaController::next(None) {
             error!("Failed to skip to next track: {}", e);
         }
     }
 
     pub(crate) fn handle_media_prev(&mut self) {
-        if let Err(e) = MediaController::previous() {
+        if let Err(e) = MediaController::previous(None) {
             error!("Failed to skip to previous track: {}", e);
         }
     }
 
     pub(crate) fn handle_media_stop(&mut self) {
-        if let Err(e) = MediaController::stop() {
+        if let Err(e) = MediaController::stop(None) {
             error!("Failed to stop media playback: {}", e);
         }
     }
 }
 
-fn adjust_brightness(delta: i32) -> Option<u8> {
-    let mut result_level = None;
-
-    for device in brightness::blocking::brightness_devices() {
-        match device {
-            Ok(device) => {
-                if let Ok(device_name) = device.device_name() {
-                    if let Ok(current) = device.get() {
-                        let new_value = (current as i32 + delta).clamp(0, 100) as u32;
-
-                        tracing::trace!(
-                            device = %device_name,
-                            current = current,
-                            delta = delta,
-                            new = new_value,
-                            "Adjusting brightness"
-                        );
-
-                        if let Err(e) = device.set(new_value) {
-                            error!(device = %device_name, error = %e, "Failed to set brightness");
-                        }
-                        // Store result (scaled to 0-20)
-                        result_level = Some((new_value.min(100) * 20 / 100) as u8);
-                    }
-                }
-            }
-            Err(e) => {
-                error!(error = %e, "Failed to get brightness device");
-            }
-        }
-    }
-
-    result_level
-}
-
 pub fn resolve_shortcut_action(config: &Config, action: &ShortcutAction) -> Option<KeyAction> {
     match action {
         ShortcutAction::Builtin(builtin) => match builtin {
@@ -473,13 +712,41 @@ pub fn resolve_shortcut_action(config: &Config, action: &ShortcutAction) -> Opti
             BuiltinAction::ScaleUp => Some(KeyAction::ScaleUp),
             BuiltinAction::ScaleDown => Some(KeyAction::ScaleDown),
             BuiltinAction::RotateOutput => Some(KeyAction::RotateOutput),
+            BuiltinAction::ToggleVrr => Some(KeyAction::ToggleVrr),
+            BuiltinAction::ToggleNightLight => Some(KeyAction::ToggleNightLight),
+            BuiltinAction::ToggleFpsOverlay => Some(KeyAction::ToggleFpsOverlay),
+            BuiltinAction::ToggleTestPattern => Some(KeyAction::ToggleTestPattern),
+            BuiltinAction::ToggleReduceMotion => Some(KeyAction::ToggleReduceMotion),
+            BuiltinAction::ToggleDarkMode => Some(KeyAction::ToggleDarkMode),
+            BuiltinAction::ToggleZoom => Some(KeyAction::ToggleZoom),
+            BuiltinAction::ZoomIn => Some(KeyAction::ZoomIn),
+            BuiltinAction::ZoomOut => Some(KeyAction::ZoomOut),
             BuiltinAction::ToggleDecorations => Some(KeyAction::ToggleDecorations),
             BuiltinAction::ApplicationSwitchNext => Some(KeyAction::ApplicationSwitchNext),
             BuiltinAction::ApplicationSwitchPrev => Some(KeyAction::ApplicationSwitchPrev),
             BuiltinAction::ApplicationSwitchQuit => Some(KeyAction::ApplicationSwitchQuit),
+            BuiltinAction::WindowSwitchNext => Some(KeyAction::WindowSwitchNext),
+            BuiltinAction::WindowSwitchPrev => Some(KeyAction::WindowSwitchPrev),
             BuiltinAction::ToggleMaximizeWindow => Some(KeyAction::ToggleMaximize),
+            BuiltinAction::ToggleAlwaysOnTop => Some(KeyAction::ToggleAlwaysOnTop),
             BuiltinAction::TileWindowLeft => Some(KeyAction::TileLeft),
             BuiltinAction::TileWindowRight => Some(KeyAction::TileRight),
+            BuiltinAction::SnapLeft => Some(KeyAction::Snap(TileZone::LeftHalf)),
+            BuiltinAction::SnapRight => Some(KeyAction::Snap(TileZone::RightHalf)),
+            BuiltinAction::SnapUp => Some(KeyAction::Snap(TileZone::Maximize)),
+            BuiltinAction::SnapTopLeft => Some(KeyAction::Snap(TileZone::TopLeft)),
+            BuiltinAction::SnapTopRight => Some(KeyAction::Snap(TileZone::TopRight)),
+            BuiltinAction::SnapBottomLeft => Some(KeyAction::Snap(TileZone::BottomLeft)),
+            BuiltinAction::SnapBottomRight => Some(KeyAction::Snap(TileZone::BottomRight)),
+            BuiltinAction::FocusLeft => Some(KeyAction::FocusDirection(Direction::Left)),
+            BuiltinAction::FocusRight => Some(KeyAction::FocusDirection(Direction::Right)),
+            BuiltinAction::FocusUp => Some(KeyAction::FocusDirection(Direction::Up)),
+            BuiltinAction::FocusDown => Some(KeyAction::FocusDirection(Direction::Down)),
+            BuiltinAction::SwapWindowLeft => Some(KeyAction::SwapWindow(Direction::Left)),
+            BuiltinAction::SwapWindowRight => Some(KeyAction::SwapWindow(Direction::Right)),
+            BuiltinAction::SwapWindowUp => Some(KeyAction::SwapWindow(Direction::Up)),
+            BuiltinAction::SwapWindowDown => Some(KeyAction::SwapWindow(Direction::Down)),
+            BuiltinAction::CycleWorkspaceLayout => Some(KeyAction::CycleWorkspaceLayout),
             BuiltinAction::CloseWindow => Some(KeyAction::CloseWindow),
             BuiltinAction::ApplicationSwitchNextWindow => {
                 Some(KeyAction::ApplicationSwitchNextWindow)
@@ -487,20 +754,42 @@ pub fn resolve_shortcut_action(config: &Config, action: &ShortcutAction) -> Opti
             BuiltinAction::ExposeShowDesktop => Some(KeyAction::ExposeShowDesktop),
             BuiltinAction::ExposeShowAll => Some(KeyAction::ExposeShowAll),
             BuiltinAction::WorkspaceNum { index } => Some(KeyAction::WorkspaceNum(*index)),
+            BuiltinAction::GoToWorkspace { target } => {
+                Some(KeyAction::GoToWorkspace(target.clone()))
+            }
+            BuiltinAction::MoveWindowToWorkspace { target } => {
+                Some(KeyAction::MoveWindowToWorkspace(target.clone()))
+            }
+            BuiltinAction::CreateWorkspace { name } => {
+                Some(KeyAction::CreateWorkspace(name.clone()))
+            }
+            BuiltinAction::RenameWorkspace { name } => {
+                Some(KeyAction::RenameWorkspace(name.clone()))
+            }
+            BuiltinAction::RemoveEmptyWorkspaces => Some(KeyAction::RemoveEmptyWorkspaces),
+            BuiltinAction::CycleKeyboardLayout => Some(KeyAction::CycleKeyboardLayout),
+            // Starts a pointer grab, handled by the pointer button path
+            BuiltinAction::MoveWindow => None,
             BuiltinAction::SceneSnapshot => Some(KeyAction::SceneSnapshot),
             BuiltinAction::BrightnessUp => Some(KeyAction::BrightnessUp),
             BuiltinAction::BrightnessDown => Some(KeyAction::BrightnessDown),
             BuiltinAction::VolumeUp => Some(KeyAction::VolumeUp),
             BuiltinAction::VolumeDown => Some(KeyAction::VolumeDown),
             BuiltinAction::VolumeMute => Some(KeyAction::VolumeMute),
+            BuiltinAction::ToggleMicMute => Some(KeyAction::ToggleMicMute),
             BuiltinAction::MediaPlayPause => Some(KeyAction::MediaPlayPause),
             BuiltinAction::MediaNext => Some(KeyAction::MediaNext),
             BuiltinAction::MediaPrev => Some(KeyAction::MediaPrev),
             BuiltinAction::MediaStop => Some(KeyAction::MediaStop),
+            BuiltinAction::ScreenshotRegion => Some(KeyAction::ScreenshotRegion),
+            BuiltinAction::StartRecording => Some(KeyAction::StartRecording),
+            BuiltinAction::StopRecording => Some(KeyAction::StopRecording),
+            BuiltinAction::ClipboardHistory => Some(KeyAction::ClipboardHistory),
         },
         ShortcutAction::RunCommand(run) => {
             Some(KeyAction::Run((run.cmd.clone(), run.args.clone())))
         }
+        ShortcutAction::Exec(argv) => Some(KeyAction::Exec(argv.clone())),
         ShortcutAction::OpenDefaultApp { role, fallback } => {
             match default_apps::resolve(role, fallback.as_deref(), config) {
                 Some((cmd, args)) => Some(KeyAction::Run((cmd, args))),
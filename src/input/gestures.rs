@@ -43,80 +43,7 @@ impl crate::Otto<crate::udev::UdevData> {
     ) {
         let pointer = self.pointer.clone();
         let delta = evt.delta();
-
-        match &mut self.swipe_gesture {
-            crate::state::SwipeGestureState::Detecting { accumulated } => {
-                accumulated.0 += delta.x;
-                accumulated.1 += delta.y;
-
-                let direction = crate::state::SwipeDirection::from_accumulated(
-                    accumulated.0.abs(),
-                    accumulated.1.abs(),
-                );
-
-                match direction {
-                    crate::state::SwipeDirection::Horizontal(_) => {
-                        // Detect the output under the pointer at gesture start
-                        let pointer_loc = pointer.current_location();
-                        let output_name = self
-                            .workspaces
-                            .output_under(pointer_loc)
-                            .next()
-                            .map(|o| o.name())
-                            .or_else(|| self.workspaces.primary_output().map(|o| o.name()))
-                            .unwrap_or_default();
-
-                        self.swipe_gesture = crate::state::SwipeGestureState::WorkspaceSwitching {
-                            velocity_samples: vec![delta.x],
-                            output_name: output_name.clone(),
-                        };
-                        self.workspaces
-                            .workspace_swipe_update(&output_name, delta.x as f32);
-                    }
-                    crate::state::SwipeDirection::Vertical(_) => {
-                        self.dismiss_all_popups();
-
-                        if !self.workspaces.get_show_all() {
-                            self.workspaces.expose_gesture_start();
-                        } else {
-                            self.workspaces.expose_gesture_close_start();
-                        }
-
-                        self.swipe_gesture = crate::state::SwipeGestureState::Expose {
-                            velocity_samples: vec![-delta.y],
-                        };
-                        // Apply the current frame's delta (not accumulated)
-                        let expose_delta =
-                            (-delta.y / crate::state::EXPOSE_DELTA_MULTIPLIER) as f32;
-                        self.workspaces.expose_update(expose_delta);
-                    }
-                    crate::state::SwipeDirection::Undetermined => {}
-                }
-            }
-            crate::state::SwipeGestureState::WorkspaceSwitching {
-                velocity_samples,
-                output_name,
-            } => {
-                velocity_samples.push(delta.x);
-                if velocity_samples.len() > crate::state::VELOCITY_SAMPLE_COUNT {
-                    velocity_samples.remove(0);
-                }
-                let name = output_name.clone();
-                self.workspaces
-                    .workspace_swipe_update(&name, delta.x as f32);
-            }
-            crate::state::SwipeGestureState::Expose { velocity_samples } => {
-                // Collect velocity samples for momentum-based spring animation
-                velocity_samples.push(-delta.y);
-                if velocity_samples.len() > crate::state::VELOCITY_SAMPLE_COUNT {
-                    velocity_samples.remove(0);
-                }
-
-                let expose_delta = (-delta.y / crate::state::EXPOSE_DELTA_MULTIPLIER) as f32;
-                self.workspaces.expose_update(expose_delta);
-            }
-            crate::state::SwipeGestureState::Idle => {}
-        }
+        self.gesture_swipe_update(delta.x, delta.y);
 
         pointer.gesture_swipe_update(
             self,
@@ -131,21 +58,7 @@ impl crate::Otto<crate::udev::UdevData> {
         let serial = SCOUNTER.next_serial();
         let pointer = self.pointer.clone();
 
-        match std::mem::replace(
-            &mut self.swipe_gesture,
-            crate::state::SwipeGestureState::Idle,
-        ) {
-            crate::state::SwipeGestureState::Expose { velocity_samples } => {
-                self.gesture_swipe_end_expose(velocity_samples);
-            }
-            crate::state::SwipeGestureState::WorkspaceSwitching {
-                velocity_samples,
-                output_name,
-            } => {
-                self.gesture_swipe_end_workspace(velocity_samples, output_name, evt.cancelled());
-            }
-            _ => {}
-        }
+        self.gesture_swipe_end(evt.cancelled());
 
         pointer.gesture_swipe_end(
             self,
@@ -273,7 +186,8 @@ impl<B: crate::state::Backend> crate::Otto<B> {
         };
     }
 
-    /// Simulate a swipe gesture update with raw deltas (no InputBackend needed).
+    /// Apply a swipe gesture update with raw deltas; also used to simulate
+    /// gestures without an InputBackend.
     pub fn gesture_swipe_update(&mut self, dx: f64, dy: f64) {
         let delta = smithay::utils::Point::<f64, smithay::utils::Logical>::from((dx, dy));
 
@@ -361,18 +275,19 @@ impl<B: crate::state::Backend> crate::Otto<B> {
         output_name: String,
         cancelled: bool,
     ) {
-        let velocity = if !cancelled && !velocity_samples.is_empty() {
-            velocity_samples.iter().sum::<f64>() / velocity_samples.len() as f64
-        } else {
+        let velocity = if velocity_samples.is_empty() {
             0.0
+        } else {
+            velocity_samples.iter().sum::<f64>() / velocity_samples.len() as f64
         };
-        let target_index = self
-            .workspaces
-            .workspace_swipe_end(&output_name, velocity as f32);
+        let target_index =
+            self.workspaces
+                .workspace_swipe_end(&output_name, velocity as f32, cancelled);
         self.focus_top_window_or_clear(target_index);
     }
 
-    /// Simulate ending a swipe gesture (no InputBackend needed).
+    /// End a swipe gesture; also used to simulate gestures without an
+    /// InputBackend.
     pub fn gesture_swipe_end(&mut self, cancelled: bool) {
         match std::mem::replace(
             &mut self.swipe_gesture,
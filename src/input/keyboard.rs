@@ -1,17 +1,32 @@
+use std::time::{Duration, Instant};
+
 use smithay::wayland::{
-    compositor::with_states, keyboard_shortcuts_inhibit::KeyboardShortcutsInhibitorSeat,
+    compositor::with_states,
+    keyboard_shortcuts_inhibit::{KeyboardShortcutsInhibitor, KeyboardShortcutsInhibitorSeat},
+    seat::WaylandFocus,
 };
 use smithay::{
-    backend::input::{Event, InputBackend, KeyState, KeyboardKeyEvent},
+    backend::input::{Event, InputBackend, KeyState, KeyboardKeyEvent, Keycode},
     desktop::layer_map_for_output,
     input::keyboard::{FilterResult, Keysym, ModifiersState},
+    reexports::{
+        calloop::timer::{TimeoutAction, Timer},
+        wayland_server::protocol::wl_surface::WlSurface,
+    },
     utils::{IsAlive, SERIAL_COUNTER as SCOUNTER},
     wayland::shell::wlr_layer::{
         KeyboardInteractivity, Layer as WlrLayer, LayerSurfaceCachedState,
     },
 };
 
-use crate::{config::Config, state::Backend, Otto};
+use crate::{
+    config::{
+        shortcuts::{match_sequence, sequence_id, SequenceMatch, ShortcutTrigger},
+        Config,
+    },
+    state::Backend,
+    Otto,
+};
 
 use super::actions::KeyAction;
 
@@ -45,11 +60,32 @@ pub fn app_switcher_hold_is_active(hold: Option<ModifiersState>, current: Modifi
     }
 }
 
+/// How long a partially typed shortcut sequence waits for its next chord.
+pub const SHORTCUT_SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// How soon the second press of `shortcuts_inhibit_escape` must follow the
+/// first to take shortcuts back from an inhibiting client.
+pub const SHORTCUTS_INHIBIT_ESCAPE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// What a key press does to the shortcut sequence being typed.
+#[derive(Debug)]
+pub enum ShortcutStep {
+    /// Not part of any shortcut, the key goes to the client
+    Forward,
+    /// The key completes a shortcut
+    Action(KeyAction),
+    /// The key starts or continues a multi-chord sequence and is swallowed
+    Prefix(ShortcutTrigger),
+    /// The key breaks off the pending sequence and is swallowed
+    Cancel,
+}
+
 pub fn process_keyboard_shortcut(
     config: &Config,
+    pending: &[ShortcutTrigger],
     modifiers: ModifiersState,
     keysym: Keysym,
-) -> Option<KeyAction> {
+) -> ShortcutStep {
     use smithay::input::keyboard::xkb::{self, keysyms::*};
 
     // Log the incoming key event for debugging
@@ -64,28 +100,37 @@ pub fn process_keyboard_shortcut(
         modifiers.logo
     );
 
-    if modifiers.ctrl && modifiers.alt && keysym == Keysym::BackSpace
+    if !pending.is_empty() {
+        // Holding a modifier for the next chord keeps the sequence alive
+        if keysym.is_modifier_key() {
+            return ShortcutStep::Forward;
+        }
+    } else if modifiers.ctrl && modifiers.alt && keysym == Keysym::BackSpace
         || modifiers.logo && keysym == Keysym::q
     {
         // ctrl+alt+backspace = quit
         // logo + q = quit
         tracing::info!("keyboard shortcut activated");
-        return Some(KeyAction::Quit);
-    }
-
-    if (KEY_XF86Switch_VT_1..=KEY_XF86Switch_VT_12).contains(&keysym.raw()) {
-        return Some(KeyAction::VtSwitch(
+        return ShortcutStep::Action(KeyAction::Quit);
+    } else if (KEY_XF86Switch_VT_1..=KEY_XF86Switch_VT_12).contains(&keysym.raw()) {
+        return ShortcutStep::Action(KeyAction::VtSwitch(
             (keysym.raw() - KEY_XF86Switch_VT_1 + 1) as i32,
         ));
     }
 
-    let result = config
-        .shortcut_bindings()
-        .iter()
-        .find(|binding| binding.trigger.matches(&modifiers, keysym))
-        .and_then(|binding| super::actions::resolve_shortcut_action(config, &binding.action));
-
-    result
+    let step = match match_sequence(config.shortcut_bindings(), pending, &modifiers, keysym) {
+        SequenceMatch::Complete(binding) => {
+            super::actions::resolve_shortcut_action(config, &binding.action)
+                .map(ShortcutStep::Action)
+        }
+        SequenceMatch::Prefix(chord) => Some(ShortcutStep::Prefix(chord)),
+        SequenceMatch::None => None,
+    };
+    match step {
+        Some(step) => step,
+        None if pending.is_empty() => ShortcutStep::Forward,
+        None => ShortcutStep::Cancel,
+    }
 }
 
 impl<BackendData: Backend> Otto<BackendData> {
@@ -93,10 +138,19 @@ impl<BackendData: Backend> Otto<BackendData> {
         &mut self,
         evt: B::KeyboardKeyEvent,
     ) -> KeyAction {
-        let keycode = evt.key_code();
-        let state = evt.state();
+        self.key_to_action(evt.key_code(), evt.state(), Event::time_msec(&evt))
+    }
+
+    /// Match a key of the physical seat against the compositor shortcuts
+    /// and grabs, and forward it to the focused client when nothing takes
+    /// it. Real and virtual keyboards both go through here.
+    pub(crate) fn key_to_action(
+        &mut self,
+        keycode: Keycode,
+        state: KeyState,
+        time: u32,
+    ) -> KeyAction {
         let serial = SCOUNTER.next_serial();
-        let time = Event::time_msec(&evt);
         let mut suppressed_keys = self.suppressed_keys.clone();
         let keyboard = self.seat.get_keyboard().unwrap();
         let mut updated_modifiers: Option<ModifiersState> = None;
@@ -126,15 +180,17 @@ impl<BackendData: Backend> Otto<BackendData> {
             }
         }
 
-        let inhibited = self
-            .workspaces
-            .element_under(self.pointer.current_location())
-            .and_then(|(window, _)| {
-                let surface = window.wl_surface()?;
-                self.seat.keyboard_shortcuts_inhibitor_for_surface(&surface)
-            })
-            .map(|inhibitor| inhibitor.is_active())
-            .unwrap_or(false);
+        let inhibited = self.shortcuts_inhibited();
+        let escape_armed = self
+            .shortcuts_inhibit_escape_at
+            .is_some_and(|at| at.elapsed() < SHORTCUTS_INHIBIT_ESCAPE_INTERVAL);
+        let mut escape_pressed = false;
+
+        let selecting_region = self.is_selecting_region();
+        let picking_clipboard = self.workspaces.clipboard_picker.alive();
+        let expose_open = self.workspaces.get_show_all();
+        let pending_shortcut = self.pending_shortcut.clone();
+        let mut next_pending_shortcut: Option<Vec<ShortcutTrigger>> = None;
 
         let action = keyboard
             .input(
@@ -146,27 +202,114 @@ impl<BackendData: Backend> Otto<BackendData> {
                 |_, modifiers, handle| {
                     let keysym = handle.modified_sym();
 
-                    let shortcut_action = Config::with(|config| {
+                    // The escape chord reaches the client, except for the
+                    // second press that ends the inhibitor
+                    if inhibited && matches!(state, KeyState::Pressed) {
+                        escape_pressed = Config::with(|config| {
+                            config
+                                .shortcuts_inhibit_escape_trigger()
+                                .is_some_and(|trigger| trigger.matches(modifiers, keysym))
+                        });
+                        if escape_pressed && escape_armed {
+                            updated_modifiers = Some(*modifiers);
+                            suppressed_keys.push(keysym);
+                            return FilterResult::Intercept(KeyAction::None);
+                        }
+                    }
+
+                    let shortcut_step = Config::with(|config| {
                         if matches!(state, KeyState::Pressed) && !inhibited {
-                            process_keyboard_shortcut(config, *modifiers, keysym)
+                            process_keyboard_shortcut(config, &pending_shortcut, *modifiers, keysym)
                         } else {
-                            None
+                            ShortcutStep::Forward
                         }
                     });
                     updated_modifiers = Some(*modifiers);
 
+                    // The region selector grabs the keyboard: only Escape and
+                    // Return do something, every other key is swallowed.
+                    if selecting_region {
+                        suppressed_keys.retain(|k| *k != keysym);
+                        let action = match (state, keysym) {
+                            (KeyState::Pressed, Keysym::Escape) => KeyAction::RegionSelectCancel,
+                            (KeyState::Pressed, Keysym::Return | Keysym::KP_Enter) => {
+                                KeyAction::RegionSelectConfirm
+                            }
+                            _ => KeyAction::None,
+                        };
+                        return FilterResult::Intercept(action);
+                    }
+
+                    // The clipboard picker grabs the keyboard the same way,
+                    // for its arrow keys.
+                    if picking_clipboard {
+                        suppressed_keys.retain(|k| *k != keysym);
+                        let action = match (state, keysym) {
+                            (KeyState::Pressed, Keysym::Escape) => KeyAction::ClipboardPickerCancel,
+                            (KeyState::Pressed, Keysym::Return | Keysym::KP_Enter) => {
+                                KeyAction::ClipboardPickerConfirm
+                            }
+                            (KeyState::Pressed, Keysym::Up) => KeyAction::ClipboardPickerStep(-1),
+                            (KeyState::Pressed, Keysym::Down) => KeyAction::ClipboardPickerStep(1),
+                            (KeyState::Pressed, Keysym::Prior) => {
+                                KeyAction::ClipboardPickerStep(-10)
+                            }
+                            (KeyState::Pressed, Keysym::Next) => KeyAction::ClipboardPickerStep(10),
+                            _ => KeyAction::None,
+                        };
+                        return FilterResult::Intercept(action);
+                    }
+
+                    // Open expose grabs the keyboard to filter the previews;
+                    // configured shortcuts keep working.
+                    if expose_open
+                        && matches!(state, KeyState::Pressed)
+                        && matches!(shortcut_step, ShortcutStep::Forward)
+                    {
+                        suppressed_keys.push(keysym);
+                        let action = match keysym {
+                            Keysym::Escape => KeyAction::ExposeCancel,
+                            Keysym::Return | Keysym::KP_Enter => KeyAction::ExposeConfirm,
+                            Keysym::BackSpace => KeyAction::ExposeFilterBackspace,
+                            _ => keysym
+                                .key_char()
+                                .filter(|c| !c.is_control())
+                                .filter(|_| !modifiers.ctrl && !modifiers.alt && !modifiers.logo)
+                                .map(KeyAction::ExposeFilterInput)
+                                .unwrap_or(KeyAction::None),
+                        };
+                        return FilterResult::Intercept(action);
+                    }
+
                     // If the key is pressed and triggered an action
                     // we will not forward the key to the client.
                     // Additionally add the key to the suppressed keys
                     // so that we can decide on a release if the key
                     // should be forwarded to the client or not.
+                    // Keys that are part of a sequence, or that break one
+                    // off, are swallowed the same way.
                     if let KeyState::Pressed = state {
-                        if let Some(action) = shortcut_action {
-                            suppressed_keys.push(keysym);
-                            FilterResult::Intercept(action)
-                        } else {
-                            FilterResult::Forward
-                        }
+                        let action = match shortcut_step {
+                            ShortcutStep::Forward => return FilterResult::Forward,
+                            ShortcutStep::Action(action) => {
+                                if !pending_shortcut.is_empty() {
+                                    next_pending_shortcut = Some(Vec::new());
+                                }
+                                action
+                            }
+                            ShortcutStep::Prefix(chord) => {
+                                let mut chords = pending_shortcut.clone();
+                                chords.push(chord);
+                                next_pending_shortcut = Some(chords);
+                                KeyAction::None
+                            }
+                            ShortcutStep::Cancel => {
+                                next_pending_shortcut = Some(Vec::new());
+                                KeyAction::None
+                            }
+                        };
+                        suppressed_keys.push(keysym);
+                        FilterResult::Intercept(action)
                     } else {
                         let suppressed = suppressed_keys.contains(&keysym);
                         if suppressed {
@@ -187,6 +330,8 @@ impl<BackendData: Backend> Otto<BackendData> {
                 KeyAction::ApplicationSwitchNext
                     | KeyAction::ApplicationSwitchPrev
                     | KeyAction::ApplicationSwitchNextWindow
+                    | KeyAction::WindowSwitchNext
+                    | KeyAction::WindowSwitchPrev
             )
         {
             if let Some(modifiers) = updated_modifiers {
@@ -202,16 +347,134 @@ impl<BackendData: Backend> Otto<BackendData> {
                 }
             }
         }
+        if KeyState::Released == state && self.workspaces.window_switcher.alive() {
+            if let Some(modifiers) = updated_modifiers {
+                if !app_switcher_hold_is_active(self.app_switcher_hold_modifiers, modifiers) {
+                    self.commit_window_switcher();
+                }
+            }
+        }
 
         // Update current modifiers state
         if let Some(modifiers) = updated_modifiers {
             self.current_modifiers = modifiers;
         }
 
+        // Shift toggles free-draw, so refresh the selector highlight
+        if selecting_region {
+            self.region_selection_motion(self.pointer.current_location());
+        }
+
+        if let Some(chords) = next_pending_shortcut {
+            self.set_pending_shortcut(chords);
+        }
+
+        if escape_pressed && escape_armed {
+            self.shortcuts_inhibit_escape_at = None;
+            self.break_shortcuts_inhibitor();
+        } else if escape_pressed {
+            self.shortcuts_inhibit_escape_at = Some(Instant::now());
+        } else if matches!(state, KeyState::Pressed) {
+            self.shortcuts_inhibit_escape_at = None;
+        }
+
         self.suppressed_keys = suppressed_keys;
         action
     }
 
+    /// Replace the partially typed shortcut sequence. A non-empty sequence
+    /// is shown in the OSD and dropped after [`SHORTCUT_SEQUENCE_TIMEOUT`].
+    fn set_pending_shortcut(&mut self, chords: Vec<ShortcutTrigger>) {
+        if let Some(token) = self.pending_shortcut_timeout.take() {
+            self.handle.remove(token);
+        }
+        if chords.is_empty() {
+            self.clear_pending_shortcut();
+            return;
+        }
+        self.workspaces
+            .osd
+            .show_shortcut_prefix(format!("{} …", sequence_id(&chords)));
+        self.pending_shortcut = chords;
+        self.pending_shortcut_timeout = self
+            .handle
+            .insert_source(
+                Timer::from_duration(SHORTCUT_SEQUENCE_TIMEOUT),
+                |_, _, otto| {
+                    otto.pending_shortcut_timeout = None;
+                    otto.clear_pending_shortcut();
+                    TimeoutAction::Drop
+                },
+            )
+            .ok();
+    }
+
+    fn clear_pending_shortcut(&mut self) {
+        if self.pending_shortcut.is_empty() {
+            return;
+        }
+        self.pending_shortcut.clear();
+        self.workspaces.osd.dismiss_shortcut_prefix();
+    }
+
+    /// Whether the focused surface has an active keyboard shortcuts
+    /// inhibitor, in which case compositor shortcuts are not applied.
+    pub(crate) fn shortcuts_inhibited(&self) -> bool {
+        self.focused_shortcuts_inhibitor()
+            .is_some_and(|inhibitor| inhibitor.is_active())
+    }
+
+    fn focused_shortcuts_inhibitor(&self) -> Option<KeyboardShortcutsInhibitor> {
+        let focus = self.seat.get_keyboard()?.current_focus()?;
+        let surface = focus.wl_surface()?;
+        self.seat.keyboard_shortcuts_inhibitor_for_surface(&surface)
+    }
+
+    /// Grant a new inhibitor if its surface has keyboard focus; otherwise it
+    /// is activated when the surface gets focus.
+    pub(crate) fn shortcuts_inhibitor_created(&mut self, inhibitor: KeyboardShortcutsInhibitor) {
+        let focused = self
+            .seat
+            .get_keyboard()
+            .and_then(|keyboard| keyboard.current_focus())
+            .and_then(|focus| focus.wl_surface().map(|surface| surface.into_owned()));
+        if focused.as_ref() == Some(inhibitor.wl_surface()) {
+            inhibitor.activate();
+            self.shortcuts_inhibit_surface = focused;
+        }
+    }
+
+    /// Move the active inhibitor along with keyboard focus: the surface
+    /// losing focus gives shortcuts back, the one gaining it takes them.
+    pub(crate) fn update_shortcuts_inhibitor_focus(&mut self, surface: Option<WlSurface>) {
+        if self.shortcuts_inhibit_surface == surface {
+            return;
+        }
+        if let Some(inhibitor) = self
+            .shortcuts_inhibit_surface
+            .take()
+            .and_then(|old| self.seat.keyboard_shortcuts_inhibitor_for_surface(&old))
+        {
+            inhibitor.inactivate();
+        }
+        if let Some(inhibitor) = surface
+            .as_ref()
+            .and_then(|new| self.seat.keyboard_shortcuts_inhibitor_for_surface(new))
+        {
+            inhibitor.activate();
+        }
+        self.shortcuts_inhibit_surface = surface;
+        self.shortcuts_inhibit_escape_at = None;
+    }
+
+    /// Take shortcuts back from the focused surface until it is focused again
+    fn break_shortcuts_inhibitor(&mut self) {
+        if let Some(inhibitor) = self.focused_shortcuts_inhibitor() {
+            tracing::info!("Keyboard shortcuts inhibitor released by the user");
+            inhibitor.inactivate();
+        }
+    }
+
     fn dismiss_app_switcher(&mut self) {
         if self.workspaces.app_switcher.alive() {
             self.workspaces.app_switcher.hide();
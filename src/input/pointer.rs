@@ -1,4 +1,12 @@
-use crate::{focus::PointerFocusTarget, shell::FullscreenSurface, state::Backend, Otto};
+use crate::{
+    config::shortcuts::{
+        find_pointer_binding, BuiltinAction, PointerInput, ScrollDirection, ShortcutAction,
+    },
+    focus::PointerFocusTarget,
+    shell::{FullscreenSurface, PointerMoveSurfaceGrab, WindowElement},
+    state::Backend,
+    Otto,
+};
 use layers::skia::Contains;
 use smithay::{
     backend::input::{
@@ -6,7 +14,7 @@ use smithay::{
         PointerButtonEvent,
     },
     desktop::{utils::under_from_surface_tree, WindowSurfaceType},
-    input::pointer::{AxisFrame, ButtonEvent, MotionEvent},
+    input::pointer::{AxisFrame, ButtonEvent, Focus, GrabStartData, MotionEvent},
     reexports::wayland_server::{protocol::wl_pointer, Resource},
     utils::{IsAlive, Logical, Point, Serial, SERIAL_COUNTER as SCOUNTER},
     wayland::{input_method::InputMethodSeat, shell::wlr_layer::Layer as WlrLayer},
@@ -54,12 +62,62 @@ use smithay::{
 
 use crate::config::Config;
 
-impl<BackendData: Backend> Otto<BackendData> {
-    pub(crate) fn on_pointer_button<B: InputBackend>(&mut self, evt: B::PointerButtonEvent) {
+use super::actions::{resolve_shortcut_action, KeyAction};
+
+/// Scroll distance of one wheel notch, in v120 units
+const SCROLL_BINDING_STEP: f64 = 120.0;
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    pub(crate) fn on_pointer_button<B: InputBackend>(
+        &mut self,
+        evt: B::PointerButtonEvent,
+    ) -> KeyAction {
+        self.pointer_button(
+            evt.button_code(),
+            wl_pointer::ButtonState::from(evt.state()),
+            evt.time_msec(),
+            true,
+        )
+    }
+
+    /// Press or release `button` on the seat pointer. With `bindings` off
+    /// (synthesized input) pointer shortcuts are not matched and the button
+    /// always reaches the client.
+    pub(crate) fn pointer_button(
+        &mut self,
+        button: u32,
+        state: wl_pointer::ButtonState,
+        time: u32,
+        bindings: bool,
+    ) -> KeyAction {
         let serial = SCOUNTER.next_serial();
-        let button = evt.button_code();
 
-        let state = wl_pointer::ButtonState::from(evt.state());
+        // The region selector owns the pointer while active; clients see nothing.
+        if self.is_selecting_region() {
+            self.region_selection_button(button, state == wl_pointer::ButtonState::Pressed);
+            return KeyAction::None;
+        }
+
+        // A bound button is consumed: neither its press nor its release
+        // reaches the client. MoveWindow instead hands the press to a move
+        // grab, which ends when the button is released.
+        let mut move_window = None;
+        if bindings && wl_pointer::ButtonState::Pressed == state {
+            match self.pointer_binding_action(PointerInput::Button(button)) {
+                Some(ShortcutAction::Builtin(BuiltinAction::MoveWindow)) => {
+                    move_window = self.window_to_move();
+                }
+                Some(action) => {
+                    self.suppressed_buttons.push(button);
+                    return Config::with(|config| resolve_shortcut_action(config, &action))
+                        .unwrap_or(KeyAction::None);
+                }
+                None => {}
+            }
+        } else if bindings && self.suppressed_buttons.contains(&button) {
+            self.suppressed_buttons.retain(|b| *b != button);
+            return KeyAction::None;
+        }
 
         if !self.workspaces.get_show_all() && wl_pointer::ButtonState::Pressed == state {
             self.focus_window_under_cursor(serial);
@@ -78,13 +136,17 @@ impl<BackendData: Backend> Otto<BackendData> {
                 .pointer_move(&(cx as f32, cy as f32).into(), None);
         }
 
+        if let Some(window) = move_window {
+            self.start_pointer_move(window, button, serial);
+        }
+
         pointer.button(
             self,
             &ButtonEvent {
                 button,
                 state: button_state,
                 serial,
-                time: evt.time_msec(),
+                time,
             },
         );
         pointer.frame(self);
@@ -96,6 +158,106 @@ impl<BackendData: Backend> Otto<BackendData> {
                 self.layers_engine.pointer_button_up();
             }
         }
+        KeyAction::None
+    }
+
+    /// Drag `window` with the pointer until `button` is released, as if the
+    /// client had asked for an interactive move.
+    fn start_pointer_move(&mut self, window: WindowElement, button: u32, serial: Serial) {
+        let Some(initial_window_location) = self.workspaces.element_location(&window) else {
+            return;
+        };
+        let pointer = self.pointer.clone();
+        let grab = PointerMoveSurfaceGrab {
+            start_data: GrabStartData {
+                focus: None,
+                button,
+                location: pointer.current_location(),
+            },
+            window,
+            initial_window_location,
+            active_zone: None,
+        };
+        pointer.set_grab(self, grab, serial, Focus::Clear);
+    }
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// The action bound to a pointer button or scroll direction with the
+    /// current modifiers, unless the focused window inhibits shortcuts.
+    fn pointer_binding_action(&self, input: PointerInput) -> Option<ShortcutAction> {
+        if self.shortcuts_inhibited() {
+            return None;
+        }
+        let modifiers = self.current_modifiers;
+        Config::with(|config| {
+            find_pointer_binding(config.pointer_bindings(), &modifiers, input)
+                .map(|binding| binding.action.clone())
+        })
+    }
+
+    /// The window under the cursor, if a MoveWindow binding can drag it.
+    /// Maximized and fullscreen windows stay put, as does everything in the
+    /// expose view.
+    fn window_to_move(&self) -> Option<WindowElement> {
+        if self.workspaces.get_show_all() {
+            return None;
+        }
+        let (window, _) = self
+            .workspaces
+            .element_under(self.pointer.current_location())?;
+        (!window.is_maximized() && !window.is_fullscreen()).then(|| window.clone())
+    }
+
+    /// Feed a scroll event to the scroll bindings.
+    ///
+    /// Returns `None` when no binding matches its direction, and the event
+    /// goes to the client. Otherwise the event is consumed and the bound
+    /// action fires once per wheel notch, or the equivalent touchpad
+    /// distance.
+    fn scroll_binding_action<B: InputBackend>(
+        &mut self,
+        evt: &B::PointerAxisEvent,
+    ) -> Option<KeyAction> {
+        let v120 = |axis| {
+            evt.amount_v120(axis)
+                .or_else(|| evt.amount(axis).map(|amount| amount * 120.0 / 15.0))
+                .unwrap_or(0.0)
+        };
+        let (dx, dy) = (v120(Axis::Horizontal), v120(Axis::Vertical));
+        let vertical = dy.abs() >= dx.abs();
+        let (delta, direction) = match (vertical, dx, dy) {
+            (true, _, dy) if dy < 0.0 => (dy, ScrollDirection::Up),
+            (true, _, dy) if dy > 0.0 => (dy, ScrollDirection::Down),
+            (false, dx, _) if dx < 0.0 => (dx, ScrollDirection::Left),
+            (false, dx, _) if dx > 0.0 => (dx, ScrollDirection::Right),
+            _ => return None,
+        };
+
+        let Some(action) = self.pointer_binding_action(PointerInput::Scroll(direction)) else {
+            self.scroll_binding_delta = (0.0, 0.0);
+            return None;
+        };
+
+        let accumulated = if vertical {
+            &mut self.scroll_binding_delta.1
+        } else {
+            &mut self.scroll_binding_delta.0
+        };
+        // Reversing direction starts over
+        if *accumulated * delta < 0.0 {
+            *accumulated = 0.0;
+        }
+        *accumulated += delta;
+        if accumulated.abs() < SCROLL_BINDING_STEP {
+            return Some(KeyAction::None);
+        }
+        *accumulated -= SCROLL_BINDING_STEP.copysign(delta);
+
+        Some(
+            Config::with(|config| resolve_shortcut_action(config, &action))
+                .unwrap_or(KeyAction::None),
+        )
     }
 
     /// Update the focus on the topmost surface under the cursor in the current workspace
@@ -277,11 +439,20 @@ impl<BackendData: Backend> Otto<BackendData> {
         let physical_pos = pos.to_physical(scale);
         let mut under = None;
 
+        // No client gets pointer focus while a region is being selected
+        if self.is_selecting_region() {
+            return None;
+        }
+
         // App switcher check
         if self.workspaces.app_switcher.alive() {
             let focus = self.workspaces.app_switcher.as_ref().clone().into();
             return Some((focus, (0.0, 0.0).into()));
         }
+        if self.workspaces.window_switcher.alive() {
+            let focus = self.workspaces.window_switcher.as_ref().clone().into();
+            return Some((focus, (0.0, 0.0).into()));
+        }
 
         // Workspace selector — skip when a window drag is active so the window selector
         // keeps receiving motion events and the dragged window keeps following the pointer.
@@ -405,16 +576,14 @@ impl<BackendData: Backend> Otto<BackendData> {
             }
         }
 
-        // Check dock
-        if under.is_none()
-            && self
+        // Check docks
+        if under.is_none() {
+            if let Some(dock) = self
                 .workspaces
-                .is_cursor_over_dock(physical_pos.x as f32, physical_pos.y as f32)
-        {
-            under = Some((
-                self.workspaces.dock.as_ref().clone().into(),
-                (0.0, 0.0).into(),
-            ));
+                .dock_under_cursor(physical_pos.x as f32, physical_pos.y as f32)
+            {
+                under = Some((dock.as_ref().clone().into(), (0.0, 0.0).into()));
+            }
         }
 
         // Check windows
@@ -474,7 +643,14 @@ impl<BackendData: Backend> Otto<BackendData> {
         under
     }
 
-    pub(crate) fn on_pointer_axis<B: InputBackend>(&mut self, evt: B::PointerAxisEvent) {
+    pub(crate) fn on_pointer_axis<B: InputBackend>(
+        &mut self,
+        evt: B::PointerAxisEvent,
+    ) -> KeyAction {
+        if let Some(action) = self.scroll_binding_action::<B>(&evt) {
+            return action;
+        }
+
         let scroll_speed = Config::with(|c| c.input.scroll_speed);
         let horizontal_amount = evt.amount(input::Axis::Horizontal).unwrap_or_else(|| {
             evt.amount_v120(input::Axis::Horizontal).unwrap_or(0.0) * 15.0 / 120.
@@ -516,10 +692,11 @@ impl<BackendData: Backend> Otto<BackendData> {
             pointer.axis(self, frame);
             pointer.frame(self);
         }
+        KeyAction::None
     }
 
-    /// Check if the pointer is in the dock hot zone (bottom edge of the primary output)
-    /// and show/hide the dock accordingly when autohide is enabled.
+    /// Check if the pointer is in a dock hot zone (bottom edge of each output with a dock)
+    /// and show/hide that dock accordingly when autohide is enabled.
     pub(crate) fn check_dock_hot_zone(&mut self, pos: (f64, f64)) {
         if !self.workspaces.dock.is_autohide_enabled() {
             return;
@@ -529,16 +706,18 @@ impl<BackendData: Backend> Otto<BackendData> {
             return;
         }
         let pos = layers::skia::Point::new(pos.0 as f32, pos.1 as f32);
-        let hot_zone = *self.workspaces.dock.cached_hot_zone.read().unwrap();
-        let dock_bounds = *self.workspaces.dock.cached_dock_bounds.read().unwrap();
-        if let Some(hot_zone) = hot_zone {
-            if hot_zone.contains(pos) && self.workspaces.dock.is_hidden() {
-                self.workspaces.dock.show_autohide();
+        for dock in self.workspaces.docks() {
+            let hot_zone = *dock.cached_hot_zone.read().unwrap();
+            let dock_bounds = *dock.cached_dock_bounds.read().unwrap();
+            if let Some(hot_zone) = hot_zone {
+                if hot_zone.contains(pos) && dock.is_hidden() {
+                    dock.show_autohide();
+                }
             }
-        }
-        if let Some(dock_bounds) = dock_bounds {
-            if !dock_bounds.contains(pos) && !self.workspaces.dock.is_hidden() {
-                self.workspaces.dock.schedule_autohide();
+            if let Some(dock_bounds) = dock_bounds {
+                if !dock_bounds.contains(pos) && !dock.is_hidden() {
+                    dock.schedule_autohide();
+                }
             }
         }
     }
@@ -585,6 +764,7 @@ impl<Backend: crate::state::Backend> Otto<Backend> {
             .pointer_move(&(pos.x as f32, pos.y as f32).into(), None);
 
         self.check_dock_hot_zone(self.last_pointer_location);
+        self.region_selection_motion(self.last_pointer_location.into());
     }
 }
 
@@ -713,6 +893,7 @@ impl crate::Otto<crate::udev::UdevData> {
             .pointer_move(&(pos.x as f32, pos.y as f32).into(), None);
 
         self.check_dock_hot_zone(self.last_pointer_location);
+        self.region_selection_motion(self.last_pointer_location.into());
 
         // Schedule a redraw to update the cursor position
         self.schedule_event_loop_dispatch();
@@ -741,7 +922,6 @@ impl crate::Otto<crate::udev::UdevData> {
         _dh: &smithay::reexports::wayland_server::DisplayHandle,
         evt: B::PointerMotionAbsoluteEvent,
     ) {
-        let serial = SCOUNTER.next_serial();
         let max_x = self.workspaces.outputs().fold(0, |acc, o| {
             acc + self.workspaces.output_geometry(o).unwrap().size.w
         });
@@ -760,10 +940,18 @@ impl crate::Otto<crate::udev::UdevData> {
             .size
             .h;
 
-        let mut pointer_location = (evt.x_transformed(max_x), evt.y_transformed(max_y)).into();
+        let pointer_location = (evt.x_transformed(max_x), evt.y_transformed(max_y)).into();
+        self.pointer_motion_to(pointer_location, evt.time_msec());
+    }
+}
 
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    /// Move the seat pointer to `location`, clamped to the outputs, and
+    /// update hover, the dock hot zone and the region selector
+    pub(crate) fn pointer_motion_to(&mut self, location: Point<f64, Logical>, time: u32) {
+        let serial = SCOUNTER.next_serial();
         // clamp to screen limits
-        pointer_location = self.clamp_coords(pointer_location);
+        let pointer_location = self.clamp_coords(location);
 
         let pointer = self.pointer.clone();
         let under = self.surface_under(pointer_location);
@@ -776,7 +964,7 @@ impl crate::Otto<crate::udev::UdevData> {
             &MotionEvent {
                 location: pointer_location,
                 serial,
-                time: evt.time_msec(),
+                time,
             },
         );
         pointer.frame(self);
@@ -789,6 +977,7 @@ impl crate::Otto<crate::udev::UdevData> {
             .pointer_move(&(pos.x as f32, pos.y as f32).into(), None);
 
         self.check_dock_hot_zone(self.last_pointer_location);
+        self.region_selection_motion(self.last_pointer_location.into());
 
         // Schedule a redraw to update the cursor position
         self.schedule_event_loop_dispatch();
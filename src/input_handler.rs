@@ -4,7 +4,10 @@
 //! to the appropriate handler modules (keyboard, pointer, gestures, tablet).
 
 use smithay::{
-    backend::input::{InputBackend, InputEvent, Switch, SwitchState, SwitchToggleEvent},
+    backend::input::{
+        InputBackend, InputEvent, KeyState, KeyboardKeyEvent, Switch, SwitchState,
+        SwitchToggleEvent,
+    },
     output::Scale,
     reexports::wayland_server::DisplayHandle,
     utils::Transform,
@@ -24,168 +27,276 @@ use smithay::{
     wayland::tablet_manager::{TabletDescriptor, TabletSeatTrait},
 };
 
+impl<Backend: crate::state::Backend + 'static> Otto<Backend> {
+    /// Key presses hide the physical seat cursor while typing; any pointer,
+    /// tablet or gesture input shows it again
+    fn update_cursor_visibility<B: InputBackend>(&mut self, event: &InputEvent<B>) {
+        let seat = self.seat_name.clone();
+        match event {
+            InputEvent::Keyboard { event, .. } if event.state() == KeyState::Pressed => {
+                self.hide_cursor_for_typing(&seat);
+            }
+            InputEvent::PointerMotion { .. }
+            | InputEvent::PointerMotionAbsolute { .. }
+            | InputEvent::PointerButton { .. }
+            | InputEvent::PointerAxis { .. }
+            | InputEvent::GestureSwipeBegin { .. }
+            | InputEvent::GesturePinchBegin { .. }
+            | InputEvent::GestureHoldBegin { .. }
+            | InputEvent::TabletToolAxis { .. }
+            | InputEvent::TabletToolProximity { .. }
+            | InputEvent::TabletToolTip { .. }
+            | InputEvent::TabletToolButton { .. } => self.cursor_pointer_input(&seat),
+            _ => {}
+        }
+    }
+}
+
 #[cfg(any(feature = "winit", feature = "x11"))]
-impl<Backend: crate::state::Backend> Otto<Backend> {
+impl<Backend: crate::state::Backend + 'static> Otto<Backend> {
     pub fn process_input_event_windowed<B: InputBackend>(
         &mut self,
         event: InputEvent<B>,
         output_name: &str,
     ) {
+        self.update_cursor_visibility(&event);
         match event {
-            InputEvent::Keyboard { event } => match self.keyboard_key_to_action::<B>(event) {
-                KeyAction::ScaleUp => {
-                    let output = self
-                        .workspaces
-                        .outputs()
-                        .find(|o| o.name() == output_name)
-                        .unwrap()
-                        .clone();
-
-                    let current_scale = output.current_scale().fractional_scale();
-                    let new_scale = current_scale + 0.25;
-                    output.change_current_state(
-                        None,
-                        None,
-                        Some(Scale::Fractional(new_scale)),
-                        None,
-                    );
-                    let current_location = self.pointer.current_location();
-
-                    crate::shell::fixup_positions(&mut self.workspaces, current_location);
-                    self.backend_data.reset_buffers(&output);
-                }
-
-                KeyAction::ScaleDown => {
-                    let output = self
-                        .workspaces
-                        .outputs()
-                        .find(|o| o.name() == output_name)
-                        .unwrap()
-                        .clone();
+            InputEvent::Keyboard { event } => {
+                let action = self.keyboard_key_to_action::<B>(event);
+                self.process_key_action_windowed(action, output_name);
+            }
 
-                    let current_scale = output.current_scale().fractional_scale();
-                    let new_scale = f64::max(1.0, current_scale - 0.25);
-                    output.change_current_state(
-                        None,
-                        None,
-                        Some(Scale::Fractional(new_scale)),
-                        None,
-                    );
-                    let current_location = self.pointer.current_location();
-                    crate::shell::fixup_positions(&mut self.workspaces, current_location);
-                    self.backend_data.reset_buffers(&output);
-                }
+            InputEvent::PointerMotionAbsolute { event } => {
+                let output = self
+                    .workspaces
+                    .outputs()
+                    .find(|o| o.name() == output_name)
+                    .unwrap()
+                    .clone();
+                self.on_pointer_move_absolute_windowed::<B>(event, &output)
+            }
+            InputEvent::PointerButton { event } => {
+                let action = self.on_pointer_button::<B>(event);
+                self.process_key_action_windowed(action, output_name);
+            }
+            InputEvent::PointerAxis { event } => {
+                let action = self.on_pointer_axis::<B>(event);
+                self.process_key_action_windowed(action, output_name);
+            }
+            _ => (), // other events are not handled (yet)
+        }
+    }
 
-                KeyAction::RotateOutput => {
-                    let output = self
-                        .workspaces
-                        .outputs()
-                        .find(|o| o.name() == output_name)
-                        .unwrap()
-                        .clone();
+    /// Run an action produced by a keyboard or pointer shortcut.
+    pub(crate) fn process_key_action_windowed(&mut self, action: KeyAction, output_name: &str) {
+        match action {
+            KeyAction::ScaleUp => {
+                let output = self
+                    .workspaces
+                    .outputs()
+                    .find(|o| o.name() == output_name)
+                    .unwrap()
+                    .clone();
 
-                    let current_transform = output.current_transform();
-                    let new_transform = match current_transform {
-                        Transform::Normal => Transform::_90,
-                        Transform::_90 => Transform::_180,
-                        Transform::_180 => Transform::_270,
-                        Transform::_270 => Transform::Normal,
-                        _ => Transform::Normal,
-                    };
-                    output.change_current_state(None, Some(new_transform), None, None);
-                    let current_location = self.pointer.current_location();
+                let current_scale = output.current_scale().fractional_scale();
+                let new_scale = current_scale + 0.25;
+                output.change_current_state(None, None, Some(Scale::Fractional(new_scale)), None);
+                let current_location = self.pointer.current_location();
 
-                    crate::shell::fixup_positions(&mut self.workspaces, current_location);
-                    self.backend_data.reset_buffers(&output);
-                }
-                KeyAction::ApplicationSwitchNext => {
-                    self.handle_app_switcher_next();
-                }
-                KeyAction::ApplicationSwitchPrev => {
-                    self.handle_app_switcher_prev();
-                }
-                KeyAction::ApplicationSwitchQuit => {
-                    self.handle_app_switcher_quit();
-                }
-                KeyAction::ToggleMaximize => {
-                    self.handle_toggle_maximize();
-                }
-                KeyAction::TileLeft => {
-                    self.handle_tile_left();
-                }
-                KeyAction::TileRight => {
-                    self.handle_tile_right();
-                }
-                KeyAction::CloseWindow => {
-                    self.handle_close_window();
-                }
-                KeyAction::ApplicationSwitchNextWindow => {
-                    self.handle_app_switcher_next_window();
-                }
-                KeyAction::ExposeShowDesktop => {
-                    self.handle_expose_show_desktop();
-                }
-                KeyAction::ExposeShowAll => {
-                    self.handle_expose_show_all();
-                }
-                KeyAction::WorkspaceNum(n) => {
-                    self.handle_workspace_num(n);
-                }
-                KeyAction::BrightnessUp => {
-                    self.handle_brightness_up();
-                }
-                KeyAction::BrightnessDown => {
-                    self.handle_brightness_down();
-                }
-                KeyAction::VolumeUp => {
-                    self.handle_volume_up();
-                }
-                KeyAction::VolumeDown => {
-                    self.handle_volume_down();
-                }
-                KeyAction::VolumeMute => {
-                    self.handle_volume_mute();
-                }
-                KeyAction::MediaPlayPause => {
-                    self.handle_media_play_pause();
-                }
-                KeyAction::MediaNext => {
-                    self.handle_media_next();
-                }
-                KeyAction::MediaPrev => {
-                    self.handle_media_prev();
-                }
-                KeyAction::MediaStop => {
-                    self.handle_media_stop();
-                }
+                crate::shell::fixup_positions(&mut self.workspaces, current_location);
+                self.backend_data.reset_buffers(&output);
+            }
 
-                action => match action {
-                    KeyAction::None
-                    | KeyAction::Quit
-                    | KeyAction::Run(_)
-                    | KeyAction::ToggleDecorations
-                    | KeyAction::SceneSnapshot => self.process_common_key_action(action),
+            KeyAction::ScaleDown => {
+                let output = self
+                    .workspaces
+                    .outputs()
+                    .find(|o| o.name() == output_name)
+                    .unwrap()
+                    .clone();
 
-                    _ => tracing::warn!(
-                        ?action,
-                        output_name,
-                        "Key action unsupported on output backend.",
-                    ),
-                },
-            },
+                let current_scale = output.current_scale().fractional_scale();
+                let new_scale = f64::max(1.0, current_scale - 0.25);
+                output.change_current_state(None, None, Some(Scale::Fractional(new_scale)), None);
+                let current_location = self.pointer.current_location();
+                crate::shell::fixup_positions(&mut self.workspaces, current_location);
+                self.backend_data.reset_buffers(&output);
+            }
 
-            InputEvent::PointerMotionAbsolute { event } => {
+            KeyAction::RotateOutput => {
                 let output = self
                     .workspaces
                     .outputs()
                     .find(|o| o.name() == output_name)
                     .unwrap()
                     .clone();
-                self.on_pointer_move_absolute_windowed::<B>(event, &output)
+
+                let current_transform = output.current_transform();
+                let new_transform = match current_transform {
+                    Transform::Normal => Transform::_90,
+                    Transform::_90 => Transform::_180,
+                    Transform::_180 => Transform::_270,
+                    Transform::_270 => Transform::Normal,
+                    _ => Transform::Normal,
+                };
+                output.change_current_state(None, Some(new_transform), None, None);
+                let current_location = self.pointer.current_location();
+
+                crate::shell::fixup_positions(&mut self.workspaces, current_location);
+                self.backend_data.reset_buffers(&output);
             }
-            InputEvent::PointerButton { event } => self.on_pointer_button::<B>(event),
-            InputEvent::PointerAxis { event } => self.on_pointer_axis::<B>(event),
-            _ => (), // other events are not handled (yet)
+            KeyAction::ApplicationSwitchNext => {
+                self.handle_app_switcher_next();
+            }
+            KeyAction::ApplicationSwitchPrev => {
+                self.handle_app_switcher_prev();
+            }
+            KeyAction::ApplicationSwitchQuit => {
+                self.handle_app_switcher_quit();
+            }
+            KeyAction::WindowSwitchNext => {
+                self.handle_window_switcher(true);
+            }
+            KeyAction::WindowSwitchPrev => {
+                self.handle_window_switcher(false);
+            }
+            KeyAction::ToggleMaximize => {
+                self.handle_toggle_maximize();
+            }
+            KeyAction::ToggleAlwaysOnTop => {
+                self.handle_toggle_always_on_top();
+            }
+            KeyAction::TileLeft => {
+                self.handle_tile_left();
+            }
+            KeyAction::TileRight => {
+                self.handle_tile_right();
+            }
+            KeyAction::Snap(zone) => {
+                self.handle_snap(zone);
+            }
+            KeyAction::FocusDirection(direction) => {
+                self.handle_focus_direction(direction);
+            }
+            KeyAction::SwapWindow(direction) => {
+                self.handle_swap_window(direction);
+            }
+            KeyAction::CycleWorkspaceLayout => {
+                self.handle_cycle_workspace_layout();
+            }
+            KeyAction::CloseWindow => {
+                self.handle_close_window();
+            }
+            KeyAction::ApplicationSwitchNextWindow => {
+                self.handle_app_switcher_next_window();
+            }
+            KeyAction::ExposeShowDesktop => {
+                self.handle_expose_show_desktop();
+            }
+            KeyAction::ExposeShowAll => {
+                self.handle_expose_show_all();
+            }
+            KeyAction::WorkspaceNum(n) => {
+                self.handle_workspace_num(n);
+            }
+            KeyAction::GoToWorkspace(target) => {
+                self.handle_go_to_workspace(target);
+            }
+            KeyAction::MoveWindowToWorkspace(target) => {
+                self.handle_move_window_to_workspace(target);
+            }
+            KeyAction::CreateWorkspace(name) => {
+                self.handle_create_workspace(name);
+            }
+            KeyAction::RenameWorkspace(name) => {
+                self.handle_rename_workspace(name);
+            }
+            KeyAction::RemoveEmptyWorkspaces => {
+                self.handle_remove_empty_workspaces();
+            }
+            KeyAction::CycleKeyboardLayout => {
+                self.handle_cycle_keyboard_layout();
+            }
+            KeyAction::BrightnessUp => {
+                self.handle_brightness_up();
+            }
+            KeyAction::BrightnessDown => {
+                self.handle_brightness_down();
+            }
+            KeyAction::VolumeUp => {
+                self.handle_volume_up();
+            }
+            KeyAction::VolumeDown => {
+                self.handle_volume_down();
+            }
+            KeyAction::VolumeMute => {
+                self.handle_volume_mute();
+            }
+            KeyAction::ToggleMicMute => {
+                self.handle_mic_mute_toggle();
+            }
+            KeyAction::ToggleNightLight => {
+                self.toggle_night_light();
+            }
+            KeyAction::ToggleFpsOverlay => {
+                self.toggle_fps_overlay();
+            }
+            KeyAction::ToggleTestPattern => {
+                self.toggle_test_pattern();
+            }
+            KeyAction::ToggleReduceMotion => {
+                self.toggle_reduce_motion();
+            }
+            KeyAction::ToggleDarkMode => {
+                self.toggle_dark_mode();
+            }
+            KeyAction::ToggleZoom => {
+                self.toggle_zoom();
+            }
+            KeyAction::ZoomIn => {
+                self.zoom_in();
+            }
+            KeyAction::ZoomOut => {
+                self.zoom_out();
+            }
+            KeyAction::MediaPlayPause => {
+                self.handle_media_play_pause();
+            }
+            KeyAction::MediaNext => {
+                self.handle_media_next();
+            }
+            KeyAction::MediaPrev => {
+                self.handle_media_prev();
+            }
+            KeyAction::MediaStop => {
+                self.handle_media_stop();
+            }
+
+            action => match action {
+                KeyAction::None
+                | KeyAction::Quit
+                | KeyAction::Run(_)
+                | KeyAction::Exec(_)
+                | KeyAction::ToggleDecorations
+                | KeyAction::SceneSnapshot
+                | KeyAction::ScreenshotRegion
+                | KeyAction::RegionSelectConfirm
+                | KeyAction::RegionSelectCancel
+                | KeyAction::ClipboardHistory
+                | KeyAction::ClipboardPickerStep(_)
+                | KeyAction::ClipboardPickerConfirm
+                | KeyAction::ClipboardPickerCancel
+                | KeyAction::ExposeFilterInput(_)
+                | KeyAction::ExposeFilterBackspace
+                | KeyAction::ExposeConfirm
+                | KeyAction::ExposeCancel => self.process_common_key_action(action),
+
+                _ => tracing::warn!(
+                    ?action,
+                    output_name,
+                    "Key action unsupported on output backend.",
+                ),
+            },
         }
     }
 }
@@ -197,228 +308,24 @@ impl Otto<UdevData> {
         dh: &DisplayHandle,
         event: InputEvent<B>,
     ) {
+        self.update_cursor_visibility(&event);
         match event {
-            InputEvent::Keyboard { event, .. } => match self.keyboard_key_to_action::<B>(event) {
-                #[cfg(feature = "udev")]
-                KeyAction::VtSwitch(vt) => {
-                    tracing::info!(to = vt, "Trying to switch vt");
-                    if let Err(err) = self.backend_data.session.change_vt(vt) {
-                        tracing::error!(vt, "Error switching vt: {}", err);
-                    }
-                }
-                KeyAction::Screen(num) => {
-                    let geometry = self
-                        .workspaces
-                        .outputs()
-                        .nth(num)
-                        .map(|o| self.workspaces.output_geometry(o).unwrap());
-
-                    if let Some(geometry) = geometry {
-                        let x = geometry.loc.x as f64 + geometry.size.w as f64 / 2.0;
-                        let y = geometry.size.h as f64 / 2.0;
-                        let location = (x, y).into();
-                        let pointer = self.pointer.clone();
-                        let under = self.surface_under(location);
-                        pointer.motion(
-                            self,
-                            under,
-                            &smithay::input::pointer::MotionEvent {
-                                location,
-                                serial: smithay::utils::SERIAL_COUNTER.next_serial(),
-                                time: 0,
-                            },
-                        );
-                        pointer.frame(self);
-                    }
-                }
-                KeyAction::ScaleUp => {
-                    let pos = self.pointer.current_location().to_i32_round();
-                    let output = self
-                        .workspaces
-                        .outputs()
-                        .find(|o| self.workspaces.output_geometry(o).unwrap().contains(pos))
-                        .cloned();
-
-                    if let Some(output) = output {
-                        let (output_location, scale) = (
-                            self.workspaces.output_geometry(&output).unwrap().loc,
-                            output.current_scale().fractional_scale(),
-                        );
-                        let new_scale = scale + 0.25;
-                        output.change_current_state(
-                            None,
-                            None,
-                            Some(Scale::Fractional(new_scale)),
-                            None,
-                        );
-
-                        let rescale = scale / new_scale;
-                        let output_location = output_location.to_f64();
-                        let mut pointer_output_location =
-                            self.pointer.current_location() - output_location;
-                        pointer_output_location.x *= rescale;
-                        pointer_output_location.y *= rescale;
-                        let pointer_location = output_location + pointer_output_location;
-                        crate::shell::fixup_positions(&mut self.workspaces, pointer_location);
-                        let pointer = self.pointer.clone();
-                        let under = self.surface_under(pointer_location);
-                        pointer.motion(
-                            self,
-                            under,
-                            &smithay::input::pointer::MotionEvent {
-                                location: pointer_location,
-                                serial: smithay::utils::SERIAL_COUNTER.next_serial(),
-                                time: 0,
-                            },
-                        );
-                        pointer.frame(self);
-                        self.backend_data.reset_buffers(&output);
-                    }
-                }
-                KeyAction::ScaleDown => {
-                    let pos = self.pointer.current_location().to_i32_round();
-                    let output = self
-                        .workspaces
-                        .outputs()
-                        .find(|o| self.workspaces.output_geometry(o).unwrap().contains(pos))
-                        .cloned();
-
-                    if let Some(output) = output {
-                        let (output_location, scale) = (
-                            self.workspaces.output_geometry(&output).unwrap().loc,
-                            output.current_scale().fractional_scale(),
-                        );
-                        let new_scale = f64::max(1.0, scale - 0.25);
-                        output.change_current_state(
-                            None,
-                            None,
-                            Some(Scale::Fractional(new_scale)),
-                            None,
-                        );
-
-                        let rescale = scale / new_scale;
-                        let output_location = output_location.to_f64();
-                        let mut pointer_output_location =
-                            self.pointer.current_location() - output_location;
-                        pointer_output_location.x *= rescale;
-                        pointer_output_location.y *= rescale;
-                        let pointer_location = output_location + pointer_output_location;
-
-                        crate::shell::fixup_positions(&mut self.workspaces, pointer_location);
-                        let pointer = self.pointer.clone();
-                        let under = self.surface_under(pointer_location);
-                        pointer.motion(
-                            self,
-                            under,
-                            &smithay::input::pointer::MotionEvent {
-                                location: pointer_location,
-                                serial: smithay::utils::SERIAL_COUNTER.next_serial(),
-                                time: 0,
-                            },
-                        );
-                        pointer.frame(self);
-                        self.backend_data.reset_buffers(&output);
-                    }
-                }
-                KeyAction::RotateOutput => {
-                    let pos = self.pointer.current_location().to_i32_round();
-                    let output = self
-                        .workspaces
-                        .outputs()
-                        .find(|o| self.workspaces.output_geometry(o).unwrap().contains(pos))
-                        .cloned();
-
-                    if let Some(output) = output {
-                        let current_transform = output.current_transform();
-                        let new_transform = match current_transform {
-                            Transform::Normal => Transform::_90,
-                            Transform::_90 => Transform::_180,
-                            Transform::_180 => Transform::_270,
-                            Transform::_270 => Transform::Normal,
-                            _ => Transform::Normal,
-                        };
-                        output.change_current_state(None, Some(new_transform), None, None);
-                        let current_location = self.pointer.current_location();
-                        crate::shell::fixup_positions(&mut self.workspaces, current_location);
-                        self.backend_data.reset_buffers(&output);
-                    }
-                }
-                KeyAction::ApplicationSwitchNext => {
-                    self.handle_app_switcher_next();
-                }
-                KeyAction::ApplicationSwitchPrev => {
-                    self.handle_app_switcher_prev();
-                }
-                KeyAction::ApplicationSwitchNextWindow => {
-                    self.handle_app_switcher_next_window();
-                }
-                KeyAction::ApplicationSwitchQuit => {
-                    self.handle_app_switcher_quit();
-                }
-                KeyAction::ToggleMaximize => {
-                    self.handle_toggle_maximize();
-                }
-                KeyAction::TileLeft => {
-                    self.handle_tile_left();
-                }
-                KeyAction::TileRight => {
-                    self.handle_tile_right();
-                }
-                KeyAction::CloseWindow => {
-                    self.handle_close_window();
-                }
-                KeyAction::ExposeShowDesktop => {
-                    self.handle_expose_show_desktop();
-                }
-                KeyAction::ExposeShowAll => {
-                    self.handle_expose_show_all();
-                }
-                KeyAction::WorkspaceNum(index) => {
-                    self.handle_workspace_num(index);
-                }
-                KeyAction::BrightnessUp => {
-                    self.handle_brightness_up();
-                }
-                KeyAction::BrightnessDown => {
-                    self.handle_brightness_down();
-                }
-                KeyAction::VolumeUp => {
-                    self.handle_volume_up();
-                }
-                KeyAction::VolumeDown => {
-                    self.handle_volume_down();
-                }
-                KeyAction::VolumeMute => {
-                    self.handle_volume_mute();
-                }
-                KeyAction::MediaPlayPause => {
-                    self.handle_media_play_pause();
-                }
-                KeyAction::MediaNext => {
-                    self.handle_media_next();
-                }
-                KeyAction::MediaPrev => {
-                    self.handle_media_prev();
-                }
-                KeyAction::MediaStop => {
-                    self.handle_media_stop();
-                }
-                action => match action {
-                    KeyAction::None
-                    | KeyAction::Quit
-                    | KeyAction::Run(_)
-                    | KeyAction::ToggleDecorations
-                    | KeyAction::SceneSnapshot => self.process_common_key_action(action),
-
-                    _ => unreachable!(),
-                },
-            },
+            InputEvent::Keyboard { event, .. } => {
+                let action = self.keyboard_key_to_action::<B>(event);
+                self.process_key_action(action);
+            }
             InputEvent::PointerMotion { event, .. } => self.on_pointer_move::<B>(dh, event),
             InputEvent::PointerMotionAbsolute { event, .. } => {
                 self.on_pointer_move_absolute::<B>(dh, event)
             }
-            InputEvent::PointerButton { event, .. } => self.on_pointer_button::<B>(event),
-            InputEvent::PointerAxis { event, .. } => self.on_pointer_axis::<B>(event),
+            InputEvent::PointerButton { event, .. } => {
+                let action = self.on_pointer_button::<B>(event);
+                self.process_key_action(action);
+            }
+            InputEvent::PointerAxis { event, .. } => {
+                let action = self.on_pointer_axis::<B>(event);
+                self.process_key_action(action);
+            }
             InputEvent::TabletToolAxis { event, .. } => self.on_tablet_tool_axis::<B>(event),
             InputEvent::TabletToolProximity { event, .. } => {
                 self.on_tablet_tool_proximity::<B>(dh, event)
@@ -475,4 +382,319 @@ impl Otto<UdevData> {
             }
         }
     }
+
+    /// Run an action produced by a keyboard or pointer shortcut.
+    pub(crate) fn process_key_action(&mut self, action: KeyAction) {
+        match action {
+            #[cfg(feature = "udev")]
+            KeyAction::VtSwitch(vt) => {
+                tracing::info!(to = vt, "Trying to switch vt");
+                if let Err(err) = self.backend_data.session.change_vt(vt) {
+                    tracing::error!(vt, "Error switching vt: {}", err);
+                }
+            }
+            KeyAction::Screen(num) => {
+                let geometry = self
+                    .workspaces
+                    .outputs()
+                    .nth(num)
+                    .map(|o| self.workspaces.output_geometry(o).unwrap());
+
+                if let Some(geometry) = geometry {
+                    let x = geometry.loc.x as f64 + geometry.size.w as f64 / 2.0;
+                    let y = geometry.size.h as f64 / 2.0;
+                    let location = (x, y).into();
+                    let pointer = self.pointer.clone();
+                    let under = self.surface_under(location);
+                    pointer.motion(
+                        self,
+                        under,
+                        &smithay::input::pointer::MotionEvent {
+                            location,
+                            serial: smithay::utils::SERIAL_COUNTER.next_serial(),
+                            time: 0,
+                        },
+                    );
+                    pointer.frame(self);
+                }
+            }
+            KeyAction::ScaleUp => {
+                let pos = self.pointer.current_location().to_i32_round();
+                let output = self
+                    .workspaces
+                    .outputs()
+                    .find(|o| self.workspaces.output_geometry(o).unwrap().contains(pos))
+                    .cloned();
+
+                if let Some(output) = output {
+                    let (output_location, scale) = (
+                        self.workspaces.output_geometry(&output).unwrap().loc,
+                        output.current_scale().fractional_scale(),
+                    );
+                    let new_scale = scale + 0.25;
+                    output.change_current_state(
+                        None,
+                        None,
+                        Some(Scale::Fractional(new_scale)),
+                        None,
+                    );
+
+                    let rescale = scale / new_scale;
+                    let output_location = output_location.to_f64();
+                    let mut pointer_output_location =
+                        self.pointer.current_location() - output_location;
+                    pointer_output_location.x *= rescale;
+                    pointer_output_location.y *= rescale;
+                    let pointer_location = output_location + pointer_output_location;
+                    crate::shell::fixup_positions(&mut self.workspaces, pointer_location);
+                    let pointer = self.pointer.clone();
+                    let under = self.surface_under(pointer_location);
+                    pointer.motion(
+                        self,
+                        under,
+                        &smithay::input::pointer::MotionEvent {
+                            location: pointer_location,
+                            serial: smithay::utils::SERIAL_COUNTER.next_serial(),
+                            time: 0,
+                        },
+                    );
+                    pointer.frame(self);
+                    self.backend_data.reset_buffers(&output);
+                }
+            }
+            KeyAction::ScaleDown => {
+                let pos = self.pointer.current_location().to_i32_round();
+                let output = self
+                    .workspaces
+                    .outputs()
+                    .find(|o| self.workspaces.output_geometry(o).unwrap().contains(pos))
+                    .cloned();
+
+                if let Some(output) = output {
+                    let (output_location, scale) = (
+                        self.workspaces.output_geometry(&output).unwrap().loc,
+                        output.current_scale().fractional_scale(),
+                    );
+                    let new_scale = f64::max(1.0, scale - 0.25);
+                    output.change_current_state(
+                        None,
+                        None,
+                        Some(Scale::Fractional(new_scale)),
+                        None,
+                    );
+
+                    let rescale = scale / new_scale;
+                    let output_location = output_location.to_f64();
+                    let mut pointer_output_location =
+                        self.pointer.current_location() - output_location;
+                    pointer_output_location.x *= rescale;
+                    pointer_output_location.y *= rescale;
+                    let pointer_location = output_location + pointer_output_location;
+
+                    crate::shell::fixup_positions(&mut self.workspaces, pointer_location);
+                    let pointer = self.pointer.clone();
+                    let under = self.surface_under(pointer_location);
+                    pointer.motion(
+                        self,
+                        under,
+                        &smithay::input::pointer::MotionEvent {
+                            location: pointer_location,
+                            serial: smithay::utils::SERIAL_COUNTER.next_serial(),
+                            time: 0,
+                        },
+                    );
+                    pointer.frame(self);
+                    self.backend_data.reset_buffers(&output);
+                }
+            }
+            KeyAction::RotateOutput => {
+                let pos = self.pointer.current_location().to_i32_round();
+                let output = self
+                    .workspaces
+                    .outputs()
+                    .find(|o| self.workspaces.output_geometry(o).unwrap().contains(pos))
+                    .cloned();
+
+                if let Some(output) = output {
+                    let current_transform = output.current_transform();
+                    let new_transform = match current_transform {
+                        Transform::Normal => Transform::_90,
+                        Transform::_90 => Transform::_180,
+                        Transform::_180 => Transform::_270,
+                        Transform::_270 => Transform::Normal,
+                        _ => Transform::Normal,
+                    };
+                    output.change_current_state(None, Some(new_transform), None, None);
+                    let current_location = self.pointer.current_location();
+                    crate::shell::fixup_positions(&mut self.workspaces, current_location);
+                    self.backend_data.reset_buffers(&output);
+                }
+            }
+            KeyAction::ToggleVrr => {
+                let pos = self.pointer.current_location().to_i32_round();
+                let output = self
+                    .workspaces
+                    .outputs()
+                    .find(|o| self.workspaces.output_geometry(o).unwrap().contains(pos))
+                    .cloned();
+
+                if let Some(output) = output {
+                    self.toggle_vrr(&output);
+                }
+            }
+            KeyAction::StartRecording => {
+                self.start_recording();
+            }
+            KeyAction::StopRecording => {
+                self.stop_recording();
+            }
+            KeyAction::ApplicationSwitchNext => {
+                self.handle_app_switcher_next();
+            }
+            KeyAction::ApplicationSwitchPrev => {
+                self.handle_app_switcher_prev();
+            }
+            KeyAction::ApplicationSwitchNextWindow => {
+                self.handle_app_switcher_next_window();
+            }
+            KeyAction::ApplicationSwitchQuit => {
+                self.handle_app_switcher_quit();
+            }
+            KeyAction::WindowSwitchNext => {
+                self.handle_window_switcher(true);
+            }
+            KeyAction::WindowSwitchPrev => {
+                self.handle_window_switcher(false);
+            }
+            KeyAction::ToggleMaximize => {
+                self.handle_toggle_maximize();
+            }
+            KeyAction::ToggleAlwaysOnTop => {
+                self.handle_toggle_always_on_top();
+            }
+            KeyAction::TileLeft => {
+                self.handle_tile_left();
+            }
+            KeyAction::TileRight => {
+                self.handle_tile_right();
+            }
+            KeyAction::Snap(zone) => {
+                self.handle_snap(zone);
+            }
+            KeyAction::FocusDirection(direction) => {
+                self.handle_focus_direction(direction);
+            }
+            KeyAction::SwapWindow(direction) => {
+                self.handle_swap_window(direction);
+            }
+            KeyAction::CycleWorkspaceLayout => {
+                self.handle_cycle_workspace_layout();
+            }
+            KeyAction::CloseWindow => {
+                self.handle_close_window();
+            }
+            KeyAction::ExposeShowDesktop => {
+                self.handle_expose_show_desktop();
+            }
+            KeyAction::ExposeShowAll => {
+                self.handle_expose_show_all();
+            }
+            KeyAction::WorkspaceNum(index) => {
+                self.handle_workspace_num(index);
+            }
+            KeyAction::GoToWorkspace(target) => {
+                self.handle_go_to_workspace(target);
+            }
+            KeyAction::MoveWindowToWorkspace(target) => {
+                self.handle_move_window_to_workspace(target);
+            }
+            KeyAction::CreateWorkspace(name) => {
+                self.handle_create_workspace(name);
+            }
+            KeyAction::RenameWorkspace(name) => {
+                self.handle_rename_workspace(name);
+            }
+            KeyAction::RemoveEmptyWorkspaces => {
+                self.handle_remove_empty_workspaces();
+            }
+            KeyAction::CycleKeyboardLayout => {
+                self.handle_cycle_keyboard_layout();
+            }
+            KeyAction::BrightnessUp => {
+                self.handle_brightness_up();
+            }
+            KeyAction::BrightnessDown => {
+                self.handle_brightness_down();
+            }
+            KeyAction::VolumeUp => {
+                self.handle_volume_up();
+            }
+            KeyAction::VolumeDown => {
+                self.handle_volume_down();
+            }
+            KeyAction::VolumeMute => {
+                self.handle_volume_mute();
+            }
+            KeyAction::ToggleMicMute => {
+                self.handle_mic_mute_toggle();
+            }
+            KeyAction::ToggleNightLight => {
+                self.toggle_night_light();
+            }
+            KeyAction::ToggleFpsOverlay => {
+                self.toggle_fps_overlay();
+            }
+            KeyAction::ToggleTestPattern => {
+                self.toggle_test_pattern();
+            }
+            KeyAction::ToggleReduceMotion => {
+                self.toggle_reduce_motion();
+            }
+            KeyAction::ToggleDarkMode => {
+                self.toggle_dark_mode();
+            }
+            KeyAction::ToggleZoom => {
+                self.toggle_zoom();
+            }
+            KeyAction::ZoomIn => {
+                self.zoom_in();
+            }
+            KeyAction::ZoomOut => {
+                self.zoom_out();
+            }
+            KeyAction::MediaPlayPause => {
+                self.handle_media_play_pause();
+            }
+            KeyAction::MediaNext => {
+                self.handle_media_next();
+            }
+            KeyAction::MediaPrev => {
+                self.handle_media_prev();
+            }
+            KeyAction::MediaStop => {
+                self.handle_media_stop();
+            }
+            action => match action {
+                KeyAction::None
+                | KeyAction::Quit
+                | KeyAction::Run(_)
+                | KeyAction::Exec(_)
+                | KeyAction::ToggleDecorations
+                | KeyAction::SceneSnapshot
+                | KeyAction::ScreenshotRegion
+                | KeyAction::RegionSelectConfirm
+                | KeyAction::RegionSelectCancel
+                | KeyAction::ClipboardHistory
+                | KeyAction::ClipboardPickerStep(_)
+                | KeyAction::ClipboardPickerConfirm
+                | KeyAction::ClipboardPickerCancel
+                | KeyAction::ExposeFilterInput(_)
+                | KeyAction::ExposeFilterBackspace
+                | KeyAction::ExposeConfirm
+                | KeyAction::ExposeCancel => self.process_common_key_action(action),
+
+                _ => unreachable!(),
+            },
+        }
+    }
 }
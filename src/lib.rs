@@ -11,6 +11,7 @@
 )]
 
 pub mod audio;
+pub mod compositor_service;
 #[cfg(any(feature = "udev", feature = "xwayland", feature = "headless"))]
 pub mod cursor;
 pub mod drawing;
@@ -21,6 +22,7 @@ pub mod input;
 pub mod input_handler;
 pub mod interactive_view;
 pub mod otto_dock;
+pub mod power;
 pub mod render;
 pub mod render_elements;
 #[cfg(feature = "metrics")]
@@ -41,6 +43,7 @@ pub mod winit;
 #[cfg(feature = "x11")]
 pub mod x11;
 
+pub use config::check::check_config_files;
 pub use state::{CalloopData, ClientState, Otto};
 mod workspaces;
 
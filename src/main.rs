@@ -1,10 +1,12 @@
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+
 static POSSIBLE_BACKENDS: &[&str] = &[
     #[cfg(feature = "winit")]
     "--winit      Run otto as a X11 or Wayland client using winit.",
     #[cfg(feature = "udev")]
     "--tty-udev   Run otto on a tty using udev (requires root or logind).",
     #[cfg(feature = "udev")]
-    "--probe      Probe available displays and resolutions, then exit.",
+    "--probe      Probe available displays and resolutions, then exit (--json for JSON).",
     #[cfg(feature = "x11")]
     "--x11        Run otto as an X11 client.",
 ];
@@ -18,6 +20,9 @@ fn print_help() {
     println!("OPTIONS:");
     println!("    -h, --help         Print this help message");
     println!("    --version          Print version information");
+    println!(
+        "    --check-config     Check the config files for errors and unknown keys, then exit"
+    );
     for b in POSSIBLE_BACKENDS {
         println!("    {}", b);
     }
@@ -47,6 +52,17 @@ async fn main() {
             print_help();
             return;
         }
+        Some("--check-config") => {
+            let issues = otto::check_config_files();
+            for issue in &issues {
+                eprintln!("{issue}");
+            }
+            if !issues.is_empty() {
+                std::process::exit(1);
+            }
+            println!("Config OK");
+            return;
+        }
         Some(other)
             if !other.starts_with("--winit")
                 && !other.starts_with("--tty-udev")
@@ -69,15 +85,23 @@ async fn main() {
         unsafe { std::env::set_var("OTTO_SYSTEMD_NOTIFY", "1") };
     }
 
+    // Keep stdout for the JSON document of `--probe --json`
+    let writer = if std::env::args().any(|a| a == "--json") {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
     if let Ok(env_filter) = tracing_subscriber::EnvFilter::try_from_default_env() {
         tracing_subscriber::fmt()
             .compact()
             .with_env_filter(env_filter)
+            .with_writer(writer)
             .init();
     } else {
         tracing_subscriber::fmt()
             .with_env_filter("info")
             .compact()
+            .with_writer(writer)
             .init();
     }
 
@@ -108,7 +132,7 @@ async fn main() {
         #[cfg(feature = "udev")]
         Some("--probe") => {
             tracing::info!("Probing available displays and resolutions");
-            otto::udev::probe_displays();
+            otto::udev::probe_displays(std::env::args().any(|a| a == "--json"));
         }
         #[cfg(feature = "x11")]
         Some("--x11") => {
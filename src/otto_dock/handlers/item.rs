@@ -53,30 +53,21 @@ impl<BackendData: Backend> Dispatch<OttoDockItemV1, DockItem, Otto<BackendData>>
                     dock_item.badge = text.clone();
                 }
 
-                // Update dock icon badge overlay (app-switcher mirrors reflect this automatically)
-                state.workspaces.dock.update_badge_for_app(&app_id, text);
+                super::set_app_badge(state, &app_id, text);
             }
 
             otto_dock_item_v1::Request::SetProgress { value } => {
                 let progress_value: f64 = value;
                 tracing::info!("set_progress: app_id={} value={}", app_id, progress_value);
 
-                let opt_value = if progress_value < 0.0 {
-                    None
-                } else {
-                    Some(progress_value.clamp(0.0, 1.0))
-                };
+                let opt_value = super::progress_value(progress_value);
 
                 // Persist
                 if let Some(dock_item) = state.otto_dock.dock_items.get_mut(&item.id()) {
                     dock_item.progress = opt_value;
                 }
 
-                // Update dock icon progress bar overlay (app-switcher mirrors reflect this automatically)
-                state
-                    .workspaces
-                    .dock
-                    .update_progress_for_app(&app_id, opt_value);
+                super::set_app_progress(state, &app_id, opt_value);
             }
         }
     }
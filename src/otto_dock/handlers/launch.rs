@@ -0,0 +1,96 @@
+use smithay::reexports::wayland_server::{Client, DataInit, Dispatch, DisplayHandle};
+
+use crate::{
+    otto_dock::protocol::gen::otto_dock_launch_v1::{self, OttoDockLaunchV1},
+    state::{Backend, Otto},
+    workspaces::{Application, ApplicationsInfo},
+};
+
+/// A `launch` request whose desktop entry is being looked up off the main
+/// loop; sent back to it through `OttoDockState::launch_sender`.
+pub struct PendingLaunch {
+    pub desktop_id: String,
+    pub app: Option<Application>,
+    pub args: Vec<String>,
+    pub launch: OttoDockLaunchV1,
+}
+
+impl<BackendData: Backend> Dispatch<OttoDockLaunchV1, (), Otto<BackendData>>
+    for crate::otto_dock::handlers::OttoDockState
+{
+    fn request(
+        _state: &mut Otto<BackendData>,
+        _client: &Client,
+        _launch: &OttoDockLaunchV1,
+        request: otto_dock_launch_v1::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, Otto<BackendData>>,
+    ) {
+        match request {
+            otto_dock_launch_v1::Request::Destroy => {
+                // The launched application is unaffected.
+            }
+        }
+    }
+}
+
+/// Look up `desktop_id` like the dock does and launch it once found.
+/// `args` are extra shell-quoted arguments appended to the Exec command.
+pub(crate) fn request_launch<BackendData: Backend>(
+    state: &mut Otto<BackendData>,
+    desktop_id: String,
+    args: Option<String>,
+    launch: OttoDockLaunchV1,
+) {
+    let args = match args.as_deref().map(shell_words::split).transpose() {
+        Ok(args) => args.unwrap_or_default(),
+        Err(err) => {
+            launch.failed(format!("invalid arguments: {err}"));
+            return;
+        }
+    };
+    let sender = state.otto_dock.launch_sender.clone();
+    tokio::spawn(async move {
+        let app = ApplicationsInfo::get_app_info_by_id(&desktop_id).await;
+        let pending = PendingLaunch {
+            desktop_id,
+            app,
+            args,
+            launch,
+        };
+        if let Err(err) = sender.send(pending) {
+            err.0.launch.failed("compositor is shutting down".into());
+        }
+    });
+}
+
+/// Start a looked-up application with a fresh xdg-activation token, so the
+/// window it maps takes the focus, and report the outcome to the client.
+pub(crate) fn start_launch<BackendData: Backend>(
+    state: &mut Otto<BackendData>,
+    pending: PendingLaunch,
+) {
+    let PendingLaunch {
+        desktop_id,
+        app,
+        args,
+        launch,
+    } = pending;
+    let Some((cmd, args)) = app.and_then(|app| app.command(&args)) else {
+        tracing::warn!("launch: no executable command for {}", desktop_id);
+        launch.failed(format!("no executable command for {desktop_id}"));
+        return;
+    };
+
+    let (token, _) = state.xdg_activation_state.create_external_token(None);
+    let token = token.to_string();
+    let env = vec![
+        ("XDG_ACTIVATION_TOKEN".to_string(), token.clone()),
+        ("DESKTOP_STARTUP_ID".to_string(), token.clone()),
+    ];
+    match state.launch_program_with_env(cmd, args, env) {
+        Ok(()) => launch.launched(token),
+        Err(err) => launch.failed(err.to_string()),
+    }
+}
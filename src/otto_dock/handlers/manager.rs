@@ -1,11 +1,18 @@
-use smithay::reexports::wayland_server::{
-    backend::ObjectId, Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch, New, Resource,
+use smithay::reexports::{
+    calloop::channel::Sender,
+    wayland_server::{
+        backend::ObjectId, Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch, New, Resource,
+    },
 };
 use std::collections::HashMap;
 
 use crate::{
+    otto_dock::handlers::launch::PendingLaunch,
     otto_dock::protocol::{
-        gen::otto_dock_manager_v1::{self, OttoDockManagerV1},
+        gen::{
+            otto_dock_launch_v1::OttoDockLaunchV1,
+            otto_dock_manager_v1::{self, OttoDockManagerV1},
+        },
         DockItem, OttoDockItemV1,
     },
     state::{Backend, Otto},
@@ -35,21 +42,25 @@ pub struct OttoDockState {
     pub dock_items: HashMap<ObjectId, DockItem>,
     /// Map from app_id to OttoDockItemV1 resource for sending events
     pub app_id_to_resource: HashMap<String, OttoDockItemV1>,
+    /// Hands looked-up `launch` requests back to the event loop
+    pub launch_sender: Sender<PendingLaunch>,
 }
 
 impl OttoDockState {
-    pub fn new<D>(display: &DisplayHandle) -> Self
+    pub fn new<D>(display: &DisplayHandle, launch_sender: Sender<PendingLaunch>) -> Self
     where
         D: GlobalDispatch<OttoDockManagerV1, ()>
             + Dispatch<OttoDockManagerV1, ()>
             + Dispatch<OttoDockItemV1, DockItem>
+            + Dispatch<OttoDockLaunchV1, ()>
             + 'static,
     {
-        display.create_global::<D, OttoDockManagerV1, ()>(1, ());
+        display.create_global::<D, OttoDockManagerV1, ()>(3, ());
 
         Self {
             dock_items: HashMap::new(),
             app_id_to_resource: HashMap::new(),
+            launch_sender,
         }
     }
 }
@@ -67,6 +78,10 @@ impl<BackendData: Backend> GlobalDispatch<OttoDockManagerV1, (), Otto<BackendDat
     ) {
         data_init.init(resource, ());
     }
+
+    fn can_view(client: Client, _global_data: &()) -> bool {
+        crate::state::is_privileged(&client)
+    }
 }
 
 impl<BackendData: Backend> Dispatch<OttoDockManagerV1, (), Otto<BackendData>> for OttoDockState {
@@ -107,6 +122,23 @@ impl<BackendData: Backend> Dispatch<OttoDockManagerV1, (), Otto<BackendData>> fo
             otto_dock_manager_v1::Request::Destroy => {
                 // Manager destroyed; existing dock items are unaffected.
             }
+            otto_dock_manager_v1::Request::SetBadge { app_id, text } => {
+                tracing::info!("set_badge: app_id={} text={:?}", app_id, text);
+                super::set_app_badge(state, &app_id, text);
+            }
+            otto_dock_manager_v1::Request::SetProgress { app_id, value } => {
+                tracing::info!("set_progress: app_id={} value={}", app_id, value);
+                super::set_app_progress(state, &app_id, super::progress_value(value));
+            }
+            otto_dock_manager_v1::Request::Launch {
+                id,
+                desktop_id,
+                args,
+            } => {
+                tracing::info!("launch: desktop_id={} args={:?}", desktop_id, args);
+                let launch = data_init.init(id, ());
+                super::launch::request_launch(state, desktop_id, args, launch);
+            }
         }
     }
 }
@@ -1,4 +1,47 @@
 pub mod item;
+pub mod launch;
 pub mod manager;
 
 pub use manager::OttoDockState;
+
+use crate::state::{Backend, Otto};
+
+/// Show `text` as the badge on the dock icon of `app_id`.
+/// Requests for apps the dock doesn't know are ignored.
+pub(crate) fn set_app_badge<BackendData: Backend>(
+    state: &mut Otto<BackendData>,
+    app_id: &str,
+    text: Option<String>,
+) {
+    if !state.workspaces.dock.has_app(app_id) {
+        tracing::warn!("set_badge: ignoring unknown app_id={}", app_id);
+        return;
+    }
+    // App-switcher mirrors and the docks of other outputs reflect this automatically
+    state.workspaces.dock.update_badge_for_app(app_id, text);
+}
+
+/// Show `value` as the progress bar on the dock icon of `app_id`, or hide
+/// it with `None`. Requests for apps the dock doesn't know are ignored.
+pub(crate) fn set_app_progress<BackendData: Backend>(
+    state: &mut Otto<BackendData>,
+    app_id: &str,
+    value: Option<f64>,
+) {
+    if !state.workspaces.dock.has_app(app_id) {
+        tracing::warn!("set_progress: ignoring unknown app_id={}", app_id);
+        return;
+    }
+    // App-switcher mirrors and the docks of other outputs reflect this automatically
+    state.workspaces.dock.update_progress_for_app(app_id, value);
+}
+
+/// Map a protocol progress value to the dock's: negative hides the bar,
+/// anything else is clamped to `0.0..=1.0`.
+pub(crate) fn progress_value(value: f64) -> Option<f64> {
+    if value < 0.0 {
+        None
+    } else {
+        Some(value.clamp(0.0, 1.0))
+    }
+}
@@ -18,6 +18,8 @@ pub enum DockItemType {
     #[default]
     AppElement,
     PlaceElement,
+    /// A folder or group of apps that opens in a popup
+    Stack,
 }
 
 /// Compositor-side dock item state
@@ -0,0 +1,163 @@
+//! Battery awareness through UPower.
+//!
+//! A background thread reads `OnBattery` from `org.freedesktop.UPower` and the
+//! charge of its display device (the aggregate of all batteries), then follows
+//! their `PropertiesChanged` signals on the system bus. Every change is sent
+//! to the main loop as a [`PowerState`], where `Otto::on_power_state_change`
+//! applies the `[power_management]` battery options. Without a system bus or
+//! UPower the thread logs once and exits: the compositor behaves as on AC.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use smithay::reexports::calloop::channel::Sender;
+use tracing::{debug, info};
+use zbus::blocking::{Connection, MessageIterator};
+use zbus::proxy::CacheProperties;
+use zbus::{proxy, MatchRule};
+
+const UPOWER_SERVICE: &str = "org.freedesktop.UPower";
+
+/// Set while the battery is low and `low_battery_reduce_motion` is on; read
+/// by [`crate::config::Config::reduce_motion`]
+static LOW_BATTERY_REDUCE_MOTION: AtomicBool = AtomicBool::new(false);
+
+/// Whether low battery currently turns animations off
+pub fn low_battery_reduce_motion() -> bool {
+    LOW_BATTERY_REDUCE_MOTION.load(Ordering::Relaxed)
+}
+
+pub(crate) fn set_low_battery_reduce_motion(active: bool) {
+    LOW_BATTERY_REDUCE_MOTION.store(active, Ordering::Relaxed);
+}
+
+/// Power source and charge as reported by UPower
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PowerState {
+    pub on_battery: bool,
+    /// Charge of all batteries, 0 - 100; `None` without a battery
+    pub percentage: Option<f64>,
+}
+
+impl PowerState {
+    /// Running on a battery charged to `threshold` percent or less
+    pub fn is_low(&self, threshold: f64) -> bool {
+        self.on_battery && self.percentage.is_some_and(|p| p <= threshold)
+    }
+}
+
+#[proxy(
+    interface = "org.freedesktop.UPower",
+    default_service = "org.freedesktop.UPower",
+    default_path = "/org/freedesktop/UPower"
+)]
+trait UPower {
+    #[zbus(property)]
+    fn on_battery(&self) -> zbus::Result<bool>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.UPower.Device",
+    default_service = "org.freedesktop.UPower",
+    default_path = "/org/freedesktop/UPower/devices/DisplayDevice"
+)]
+trait DisplayDevice {
+    #[zbus(property)]
+    fn is_present(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn percentage(&self) -> zbus::Result<f64>;
+}
+
+/// Start watching UPower; each change of [`PowerState`] is sent to `sender`,
+/// starting with the state at startup.
+pub fn watch_upower(sender: Sender<PowerState>) {
+    let spawned = std::thread::Builder::new()
+        .name("upower".into())
+        .spawn(move || {
+            if let Err(e) = run_watcher(&sender) {
+                info!("UPower unavailable, battery options disabled: {e}");
+            }
+        });
+    if let Err(e) = spawned {
+        info!("Failed to start the UPower watcher: {e}");
+    }
+}
+
+fn run_watcher(sender: &Sender<PowerState>) -> zbus::Result<()> {
+    let connection = Connection::system()?;
+    // Read the properties on every signal rather than racing the proxies'
+    // own cache updates
+    let upower = UPowerProxyBlocking::builder(&connection)
+        .cache_properties(CacheProperties::No)
+        .build()?;
+    let display_device = DisplayDeviceProxyBlocking::builder(&connection)
+        .cache_properties(CacheProperties::No)
+        .build()?;
+    // Fails when UPower is not installed, before subscribing to anything
+    let mut last = read_state(&upower, &display_device)?;
+    if sender.send(last).is_err() {
+        return Ok(());
+    }
+
+    let rule = MatchRule::builder()
+        .msg_type(zbus::message::Type::Signal)
+        .sender(UPOWER_SERVICE)?
+        .interface("org.freedesktop.DBus.Properties")?
+        .member("PropertiesChanged")?
+        .build();
+    for message in MessageIterator::for_match_rule(rule, &connection, None)? {
+        if let Err(e) = message {
+            debug!("UPower signal error: {e}");
+            continue;
+        }
+        let state = match read_state(&upower, &display_device) {
+            Ok(state) => state,
+            Err(e) => {
+                debug!("Failed to read UPower state: {e}");
+                continue;
+            }
+        };
+        if state == last {
+            continue;
+        }
+        last = state;
+        if sender.send(state).is_err() {
+            // The compositor is shutting down
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn read_state(
+    upower: &UPowerProxyBlocking<'_>,
+    display_device: &DisplayDeviceProxyBlocking<'_>,
+) -> zbus::Result<PowerState> {
+    let on_battery = upower.on_battery()?;
+    let percentage = match display_device.is_present() {
+        Ok(true) => display_device.percentage().ok(),
+        _ => None,
+    };
+    Ok(PowerState {
+        on_battery,
+        percentage,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn low_only_on_battery_below_threshold() {
+        let state = |on_battery, percentage| PowerState {
+            on_battery,
+            percentage,
+        };
+        assert!(state(true, Some(15.0)).is_low(20.0));
+        assert!(state(true, Some(20.0)).is_low(20.0));
+        assert!(!state(true, Some(21.0)).is_low(20.0));
+        assert!(!state(false, Some(5.0)).is_low(20.0));
+        assert!(!state(true, None).is_low(20.0));
+    }
+}
@@ -31,10 +31,41 @@ pub struct SceneElement {
     /// When set, render from this node instead of the global scene root.
     /// Used to render only a specific output's sub-tree (coordinates are output-local).
     pub output_root: Option<NodeRef>,
+    /// When set, the output sub-tree is scaled into a target of a different
+    /// size (mirroring one output onto another).
+    pub mirror: Option<SceneMirror>,
     #[cfg(feature = "perf-counters")]
     perf_stats: Rc<RefCell<ScenePerfStats>>,
 }
 
+/// Placement of a mirrored output sub-tree inside the target framebuffer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SceneMirror {
+    /// Uniform scale from source pixels to target pixels.
+    pub scale: f32,
+    pub offset_x_px: f32,
+    pub offset_y_px: f32,
+    pub width_px: i32,
+    pub height_px: i32,
+}
+
+impl SceneMirror {
+    /// Fit a source of `source_px` into `target_px`, preserving the aspect
+    /// ratio and centring it (letterbox / pillarbox bars on the sides).
+    pub fn letterbox(source_px: (i32, i32), target_px: (i32, i32)) -> Self {
+        let (source_w_px, source_h_px) = (source_px.0.max(1) as f32, source_px.1.max(1) as f32);
+        let (target_w_px, target_h_px) = (target_px.0 as f32, target_px.1 as f32);
+        let scale = (target_w_px / source_w_px).min(target_h_px / source_h_px);
+        Self {
+            scale,
+            offset_x_px: (target_w_px - source_w_px * scale) / 2.0,
+            offset_y_px: (target_h_px - source_h_px * scale) / 2.0,
+            width_px: target_px.0,
+            height_px: target_px.1,
+        }
+    }
+}
+
 impl SceneElement {
     pub fn with_engine(engine: Arc<Engine>) -> Self {
         Self {
@@ -45,6 +76,7 @@ impl SceneElement {
             size: (0.0, 0.0),
             damage: Rc::new(RefCell::new(DamageBag::new(5))),
             output_root: None,
+            mirror: None,
             #[cfg(feature = "perf-counters")]
             perf_stats: Rc::new(RefCell::new(ScenePerfStats::new())),
         }
@@ -56,6 +88,17 @@ impl SceneElement {
         clone.output_root = Some(layer.id);
         clone
     }
+
+    /// Return a clone of this element that draws its output sub-tree scaled
+    /// and positioned by `mirror`.
+    ///
+    /// Damage is not tracked across the transform: a mirrored element always
+    /// reports full damage.
+    pub fn mirrored(&self, mirror: SceneMirror) -> Self {
+        let mut clone = self.clone();
+        clone.mirror = Some(mirror);
+        clone
+    }
     #[profiling::function]
     pub fn update(&mut self) -> bool {
         let dt = self.last_update.elapsed().as_secs_f32();
@@ -207,6 +250,9 @@ impl Element for SceneElement {
     }
 
     fn geometry(&self, scale: Scale<f64>) -> Rectangle<i32, Physical> {
+        if let Some(mirror) = self.mirror {
+            return Rectangle::new((0, 0).into(), (mirror.width_px, mirror.height_px).into());
+        }
         if let Some(oid) = self.output_root {
             // Per-output element: geometry fills the output framebuffer from (0,0).
             let size = self
@@ -245,6 +291,9 @@ impl Element for SceneElement {
         }
 
         let full_damage = Rectangle::new((0, 0).into(), geometry_size);
+        if self.mirror.is_some() {
+            return DamageSet::from_slice(&[full_damage]);
+        }
         let damage = self.damage.borrow().damage_since(commit);
 
         match damage {
@@ -330,6 +379,12 @@ impl RenderElement<SkiaRenderer> for SceneElement {
             None
         };
 
+        // Mirroring: place the (scaled) source output inside the target.
+        if let Some(mirror) = self.mirror {
+            canvas.translate((mirror.offset_x_px, mirror.offset_y_px));
+            canvas.scale((mirror.scale, mirror.scale));
+        }
+
         // If rendering from an output sub-tree, translate so the output_layer's
         // scene-space position maps to (0,0) on the output framebuffer.
         if let Some(oid) = self.output_root {
@@ -357,7 +412,9 @@ impl RenderElement<SkiaRenderer> for SceneElement {
         // culling so that no layer is hidden by any rendering optimisation.
         // The canvas is already clipped to the damage region by Skia, so
         // correctness is preserved — only extra tree traversal is incurred.
-        let damage_ref = if occluded_ref.is_some() {
+        // Damage rects are in target space, which differs from scene space
+        // when mirroring.
+        let damage_ref = if occluded_ref.is_some() && self.mirror.is_none() {
             damage_region.as_ref()
         } else {
             None
@@ -384,3 +441,33 @@ impl RenderElement<SkiaRenderer> for SceneElement {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SceneMirror;
+
+    #[test]
+    fn letterbox_same_aspect_fills_target() {
+        let mirror = SceneMirror::letterbox((2880, 1800), (1440, 900));
+        assert_eq!(mirror.scale, 0.5);
+        assert_eq!((mirror.offset_x_px, mirror.offset_y_px), (0.0, 0.0));
+    }
+
+    #[test]
+    fn letterbox_wider_target_adds_side_bars() {
+        // 16:10 panel into a 16:9 stream
+        let mirror = SceneMirror::letterbox((2560, 1600), (1920, 1080));
+        assert_eq!(mirror.scale, 1080.0 / 1600.0);
+        assert_eq!(mirror.offset_y_px, 0.0);
+        assert_eq!(mirror.offset_x_px, (1920.0 - 2560.0 * mirror.scale) / 2.0);
+    }
+
+    #[test]
+    fn letterbox_taller_target_adds_top_and_bottom_bars() {
+        let mirror = SceneMirror::letterbox((1920, 1080), (1024, 768));
+        assert_eq!(mirror.scale, 1024.0 / 1920.0);
+        assert_eq!(mirror.offset_x_px, 0.0);
+        assert!(mirror.offset_y_px > 0.0);
+        assert_eq!((mirror.width_px, mirror.height_px), (1024, 768));
+    }
+}
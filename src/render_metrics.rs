@@ -1,7 +1,15 @@
+use serde::Serialize;
 use smithay::utils::{Physical, Rectangle};
+use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
+pub mod socket;
+
+/// Frames kept per output for frame time percentiles
+const FRAME_HISTORY: usize = 600;
+
 #[derive(Debug)]
 pub struct RenderMetrics {
     backend_name: &'static str,
@@ -10,7 +18,20 @@ pub struct RenderMetrics {
     total_pixels: AtomicU64,
     damaged_pixels: AtomicU64,
     damage_rect_count: AtomicU64,
+    tearing_frames: AtomicU64,
     last_log_time: std::sync::Mutex<Option<Instant>>,
+    /// Per-output statistics, kept until `reset_outputs`; unlike the totals
+    /// above they are not cleared by the periodic log
+    outputs: Mutex<HashMap<String, OutputMetrics>>,
+}
+
+#[derive(Debug, Default)]
+struct OutputMetrics {
+    /// Render time and end of the most recent frames, oldest first
+    recent_frames: VecDeque<(Duration, Instant)>,
+    frame_count: u64,
+    total_pixels: u64,
+    damaged_pixels: u64,
 }
 
 impl RenderMetrics {
@@ -22,18 +43,27 @@ impl RenderMetrics {
             total_pixels: AtomicU64::new(0),
             damaged_pixels: AtomicU64::new(0),
             damage_rect_count: AtomicU64::new(0),
+            tearing_frames: AtomicU64::new(0),
             last_log_time: std::sync::Mutex::new(None),
+            outputs: Mutex::new(HashMap::new()),
         }
     }
 
-    pub fn start_frame(&self) -> FrameTimer {
+    /// Time a frame of `output` until the returned timer is dropped
+    pub fn start_frame(&self, output: &str) -> FrameTimer {
         FrameTimer {
             start: Instant::now(),
+            output: output.to_string(),
             metrics: self,
         }
     }
 
-    pub fn record_damage(&self, output_size: (i32, i32), damage: &[Rectangle<i32, Physical>]) {
+    pub fn record_damage(
+        &self,
+        output: &str,
+        output_size: (i32, i32),
+        damage: &[Rectangle<i32, Physical>],
+    ) {
         let total = (output_size.0 * output_size.1) as u64;
         let damaged: u64 = damage
             .iter()
@@ -44,12 +74,30 @@ impl RenderMetrics {
         self.damaged_pixels.fetch_add(damaged, Ordering::Relaxed);
         self.damage_rect_count
             .fetch_add(damage.len() as u64, Ordering::Relaxed);
+
+        let mut outputs = self.outputs.lock().unwrap();
+        let stats = outputs.entry(output.to_string()).or_default();
+        stats.total_pixels += total;
+        stats.damaged_pixels += damaged;
     }
 
-    fn record_frame_time(&self, duration: Duration) {
+    /// Count a frame flipped without waiting for vblank
+    pub fn record_tearing_frame(&self) {
+        self.tearing_frames.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_frame_time(&self, output: &str, duration: Duration) {
         self.frame_count.fetch_add(1, Ordering::Relaxed);
         self.total_render_time_ns
             .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+
+        let mut outputs = self.outputs.lock().unwrap();
+        let stats = outputs.entry(output.to_string()).or_default();
+        if stats.recent_frames.len() == FRAME_HISTORY {
+            stats.recent_frames.pop_front();
+        }
+        stats.recent_frames.push_back((duration, Instant::now()));
+        stats.frame_count += 1;
     }
 
     pub fn maybe_log_stats(&self, force: bool) {
@@ -75,6 +123,7 @@ impl RenderMetrics {
         let total_pixels = self.total_pixels.load(Ordering::Relaxed);
         let damaged_pixels = self.damaged_pixels.load(Ordering::Relaxed);
         let damage_rect_count = self.damage_rect_count.load(Ordering::Relaxed);
+        let tearing_frames = self.tearing_frames.load(Ordering::Relaxed);
 
         let avg_render_ms = (total_render_ns as f64 / frame_count as f64) / 1_000_000.0;
         let damage_ratio = if total_pixels > 0 {
@@ -85,9 +134,10 @@ impl RenderMetrics {
         let avg_rects = damage_rect_count as f64 / frame_count as f64;
 
         tracing::info!(
-            "RENDER METRICS [{}]: {} frames, avg {:.2}ms/frame, damage {:.1}% ({}/{} px), avg {:.1} rects/frame",
+            "RENDER METRICS [{}]: {} frames ({} tearing), avg {:.2}ms/frame, damage {:.1}% ({}/{} px), avg {:.1} rects/frame",
             self.backend_name,
             frame_count,
+            tearing_frames,
             avg_render_ms,
             damage_ratio,
             damaged_pixels,
@@ -105,6 +155,69 @@ impl RenderMetrics {
         self.total_pixels.store(0, Ordering::Relaxed);
         self.damaged_pixels.store(0, Ordering::Relaxed);
         self.damage_rect_count.store(0, Ordering::Relaxed);
+        self.tearing_frames.store(0, Ordering::Relaxed);
+    }
+
+    /// Clear the per-output statistics
+    pub fn reset_outputs(&self) {
+        self.outputs.lock().unwrap().clear();
+    }
+
+    /// Render times of the last frames of `output`, oldest first, in ms
+    pub fn recent_frame_times(&self, output: &str) -> Vec<f64> {
+        self.outputs
+            .lock()
+            .unwrap()
+            .get(output)
+            .map(|stats| {
+                stats
+                    .recent_frames
+                    .iter()
+                    .map(|(duration, _)| duration.as_secs_f64() * 1000.0)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Per-output statistics, sorted by output name
+    pub fn output_stats(&self) -> Vec<OutputMetricsSnapshot> {
+        let now = Instant::now();
+        let outputs = self.outputs.lock().unwrap();
+        let mut snapshots: Vec<_> = outputs
+            .iter()
+            .map(|(name, stats)| {
+                let mut frame_times: Vec<f64> = stats
+                    .recent_frames
+                    .iter()
+                    .map(|(duration, _)| duration.as_secs_f64() * 1000.0)
+                    .collect();
+                frame_times.sort_by(f64::total_cmp);
+                let fps = stats
+                    .recent_frames
+                    .iter()
+                    .filter(|(_, end)| now.duration_since(*end) <= Duration::from_secs(1))
+                    .count() as u32;
+                OutputMetricsSnapshot {
+                    output: name.clone(),
+                    backend: self.backend_name,
+                    frame_count: stats.frame_count,
+                    fps,
+                    frame_time_ms: FrameTimePercentiles {
+                        p50: percentile(&frame_times, 50.0),
+                        p90: percentile(&frame_times, 90.0),
+                        p99: percentile(&frame_times, 99.0),
+                        max: frame_times.last().copied().unwrap_or(0.0),
+                    },
+                    damage_ratio: if stats.total_pixels > 0 {
+                        (stats.damaged_pixels as f64 / stats.total_pixels as f64) * 100.0
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect();
+        snapshots.sort_by(|a, b| a.output.cmp(&b.output));
+        snapshots
     }
 
     pub fn get_stats(&self) -> MetricsSnapshot {
@@ -139,16 +252,49 @@ impl RenderMetrics {
 
 pub struct FrameTimer<'a> {
     start: Instant,
+    output: String,
     metrics: &'a RenderMetrics,
 }
 
 impl Drop for FrameTimer<'_> {
     fn drop(&mut self) {
         let duration = self.start.elapsed();
-        self.metrics.record_frame_time(duration);
+        self.metrics.record_frame_time(&self.output, duration);
     }
 }
 
+/// Nearest-rank percentile of ascending `values`, 0 when empty
+fn percentile(values: &[f64], percent: f64) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let rank = ((percent / 100.0) * values.len() as f64).ceil() as usize;
+    values[rank.clamp(1, values.len()) - 1]
+}
+
+/// Statistics of one output since the last `reset_outputs`
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputMetricsSnapshot {
+    /// Connector name, e.g. "eDP-1"
+    pub output: String,
+    pub backend: &'static str,
+    pub frame_count: u64,
+    /// Frames rendered in the last second
+    pub fps: u32,
+    /// Render time of the last frames (up to 600)
+    pub frame_time_ms: FrameTimePercentiles,
+    /// Damaged share of the rendered pixels, in percent
+    pub damage_ratio: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FrameTimePercentiles {
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
 #[derive(Debug, Clone)]
 pub struct MetricsSnapshot {
     pub frame_count: u64,
@@ -173,3 +319,38 @@ impl MetricsSnapshot {
         println!("================\n");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let values: Vec<f64> = (1..=10).map(f64::from).collect();
+        assert_eq!(percentile(&values, 50.0), 5.0);
+        assert_eq!(percentile(&values, 90.0), 9.0);
+        assert_eq!(percentile(&values, 99.0), 10.0);
+        assert_eq!(percentile(&[], 50.0), 0.0);
+    }
+
+    #[test]
+    fn outputs_are_tracked_separately() {
+        let metrics = RenderMetrics::new("test");
+        drop(metrics.start_frame("DP-1"));
+        drop(metrics.start_frame("DP-1"));
+        drop(metrics.start_frame("eDP-1"));
+        let full = [Rectangle::new((0, 0).into(), (10, 10).into())];
+        metrics.record_damage("DP-1", (10, 20), &full);
+
+        let stats = metrics.output_stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].output, "DP-1");
+        assert_eq!(stats[0].frame_count, 2);
+        assert_eq!(stats[0].fps, 2);
+        assert_eq!(stats[0].damage_ratio, 50.0);
+        assert_eq!(stats[1].frame_count, 1);
+
+        metrics.reset_outputs();
+        assert!(metrics.output_stats().is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+//! Unix socket streaming render metrics to external tools (HUDs, profilers).
+//!
+//! The socket lives at `$XDG_RUNTIME_DIR/otto-metrics-<WAYLAND_DISPLAY>.sock`.
+//! Clients send one command per line and receive one JSON object per line:
+//!
+//! - `snapshot`: the current per-output statistics
+//! - `subscribe [interval_ms]`: a snapshot every interval (default 1000ms)
+//!   until the client disconnects
+//! - `reset`: clear all counters, answered with `{"ok":true}`
+//!
+//! A snapshot looks like
+//! `{"outputs":[{"output":"eDP-1","backend":"udev","frame_count":120,"fps":60,
+//! "frame_time_ms":{"p50":2.1,"p90":3.4,"p99":5.0,"max":6.2},"damage_ratio":12.5}]}`.
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+use tracing::{info, warn};
+
+use super::{OutputMetricsSnapshot, RenderMetrics};
+
+const DEFAULT_SUBSCRIBE_INTERVAL: Duration = Duration::from_millis(1000);
+const MIN_SUBSCRIBE_INTERVAL: Duration = Duration::from_millis(16);
+
+#[derive(Serialize)]
+struct Snapshot {
+    outputs: Vec<OutputMetricsSnapshot>,
+}
+
+#[derive(Serialize)]
+struct Reply {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Path of the metrics socket for the compositor on `socket_name`
+pub fn socket_path(socket_name: &str) -> Option<PathBuf> {
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")?;
+    Some(PathBuf::from(runtime_dir).join(format!("otto-metrics-{socket_name}.sock")))
+}
+
+/// Listen for metrics clients on a background thread
+pub fn start(metrics: Arc<RenderMetrics>, socket_name: &str) {
+    let Some(path) = socket_path(socket_name) else {
+        warn!("XDG_RUNTIME_DIR is not set, render metrics socket disabled");
+        return;
+    };
+    // A previous instance may have left its socket behind
+    let _ = std::fs::remove_file(&path);
+    let listener = match UnixListener::bind(&path) {
+        Ok(listener) => listener,
+        Err(err) => {
+            warn!(path = %path.display(), "Failed to bind render metrics socket: {}", err);
+            return;
+        }
+    };
+    info!(path = %path.display(), "Render metrics socket listening");
+
+    let spawned = std::thread::Builder::new()
+        .name("otto-metrics".into())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                let metrics = metrics.clone();
+                let _ = std::thread::Builder::new()
+                    .name("otto-metrics-client".into())
+                    .spawn(move || {
+                        if let Err(err) = serve_client(stream, &metrics) {
+                            tracing::debug!("Render metrics client disconnected: {}", err);
+                        }
+                    });
+            }
+        });
+    if let Err(err) = spawned {
+        warn!("Failed to start render metrics socket thread: {}", err);
+    }
+}
+
+fn serve_client(stream: UnixStream, metrics: &RenderMetrics) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        match parse_command(&line) {
+            Ok(Command::Snapshot) => write_line(&mut writer, &snapshot(metrics))?,
+            Ok(Command::Reset) => {
+                metrics.reset();
+                metrics.reset_outputs();
+                write_line(
+                    &mut writer,
+                    &Reply {
+                        ok: true,
+                        error: None,
+                    },
+                )?;
+            }
+            Ok(Command::Subscribe(interval)) => loop {
+                // Ends when the client goes away and the write fails
+                write_line(&mut writer, &snapshot(metrics))?;
+                std::thread::sleep(interval);
+            },
+            Err(error) => write_line(
+                &mut writer,
+                &Reply {
+                    ok: false,
+                    error: Some(error),
+                },
+            )?,
+        }
+    }
+    Ok(())
+}
+
+fn snapshot(metrics: &RenderMetrics) -> Snapshot {
+    Snapshot {
+        outputs: metrics.output_stats(),
+    }
+}
+
+fn write_line(writer: &mut UnixStream, value: &impl Serialize) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    writer.write_all(&line)
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Snapshot,
+    Reset,
+    Subscribe(Duration),
+}
+
+fn parse_command(line: &str) -> Result<Command, String> {
+    let mut words = line.split_whitespace();
+    let command = match words.next() {
+        Some("snapshot") => Command::Snapshot,
+        Some("reset") => Command::Reset,
+        Some("subscribe") => {
+            let interval = match words.next() {
+                Some(ms) => Duration::from_millis(
+                    ms.parse().map_err(|_| format!("invalid interval '{ms}'"))?,
+                ),
+                None => DEFAULT_SUBSCRIBE_INTERVAL,
+            };
+            Command::Subscribe(interval.max(MIN_SUBSCRIBE_INTERVAL))
+        }
+        Some(other) => return Err(format!("unknown command '{other}'")),
+        None => return Err("empty command".into()),
+    };
+    match words.next() {
+        Some(extra) => Err(format!("unexpected argument '{extra}'")),
+        None => Ok(command),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_are_parsed() {
+        assert_eq!(parse_command("snapshot"), Ok(Command::Snapshot));
+        assert_eq!(parse_command(" reset "), Ok(Command::Reset));
+        assert_eq!(
+            parse_command("subscribe"),
+            Ok(Command::Subscribe(DEFAULT_SUBSCRIBE_INTERVAL))
+        );
+        assert_eq!(
+            parse_command("subscribe 250"),
+            Ok(Command::Subscribe(Duration::from_millis(250)))
+        );
+        assert_eq!(
+            parse_command("subscribe 1"),
+            Ok(Command::Subscribe(MIN_SUBSCRIBE_INTERVAL))
+        );
+        assert!(parse_command("subscribe fast").is_err());
+        assert!(parse_command("snapshot now").is_err());
+        assert!(parse_command("").is_err());
+    }
+}
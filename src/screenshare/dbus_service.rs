@@ -8,12 +8,15 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use smithay::reexports::calloop::channel::Sender;
+use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
+use zbus::object_server::SignalContext;
 use zbus::zvariant::{ObjectPath, OwnedFd, OwnedObjectPath, OwnedValue, Value};
 use zbus::{interface, Connection};
 
-use super::CompositorCommand;
+use super::{CompositorCommand, PickedSource, WindowLookup};
+use crate::compositor_service::CompositorEvent;
 
 /// Global session counter for unique IDs.
 static SESSION_COUNTER: AtomicU64 = AtomicU64::new(1);
@@ -57,7 +60,7 @@ impl ScreenCastInterface {
     /// Creates a new screencast session.
     ///
     /// Properties may include:
-    /// - `cursor-mode`: u32 (0 = hidden, 1 = embedded, 2 = metadata)
+    /// - `cursor-mode`: u32 (1 = hidden, 2 = embedded, 4 = metadata)
     async fn create_session(
         &self,
         properties: HashMap<&str, Value<'_>>,
@@ -142,6 +145,108 @@ impl ScreenCastInterface {
         debug!("Received {} outputs: {:?}", connectors.len(), connectors);
         Ok(connectors)
     }
+
+    /// Lets the user interactively select a screen region.
+    ///
+    /// Returns `(x, y, width, height)` in global logical coordinates, or an
+    /// error if the user cancelled the selection.
+    async fn select_region(&self) -> zbus::fdo::Result<(i32, i32, i32, i32)> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        self.compositor_tx
+            .send(CompositorCommand::SelectRegion { response_tx: tx })
+            .map_err(|e| {
+                error!("Failed to send SelectRegion command: {}", e);
+                zbus::fdo::Error::Failed(format!("Channel send error: {e}"))
+            })?;
+
+        let region = rx
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Response channel error: {e}")))?
+            .ok_or_else(|| zbus::fdo::Error::Failed("Region selection cancelled".into()))?;
+
+        Ok((region.loc.x, region.loc.y, region.size.w, region.size.h))
+    }
+
+    /// Lets the user pick what to share: a window by clicking it, an output
+    /// by clicking outside any window, or a region by dragging.
+    ///
+    /// Properties may include:
+    /// - `types`: u32 bitmask of allowed sources (1 = monitor and region,
+    ///   2 = window), both by default
+    ///
+    /// Returns `source-type` (1 = monitor, 2 = window) and one of `output`
+    /// (connector), `window-id` (window handle) or `region` (`(x, y, width,
+    /// height)` in global logical coordinates), or nothing if the user
+    /// cancelled.
+    async fn pick_source(
+        &self,
+        properties: HashMap<&str, Value<'_>>,
+    ) -> zbus::fdo::Result<HashMap<String, OwnedValue>> {
+        let types = properties
+            .get("types")
+            .and_then(|v| u32::try_from(v).ok())
+            .unwrap_or(SOURCE_TYPE_MONITOR | SOURCE_TYPE_WINDOW);
+        let (outputs, windows) = (
+            types & SOURCE_TYPE_MONITOR != 0,
+            types & SOURCE_TYPE_WINDOW != 0,
+        );
+        if !outputs && !windows {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "Unsupported source types {types}"
+            )));
+        }
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.compositor_tx
+            .send(CompositorCommand::PickSource {
+                outputs,
+                windows,
+                response_tx: tx,
+            })
+            .map_err(|e| {
+                error!("Failed to send PickSource command: {}", e);
+                zbus::fdo::Error::Failed(format!("Channel send error: {e}"))
+            })?;
+
+        let source = rx
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Response channel error: {e}")))?;
+
+        let mut result = HashMap::new();
+        let Some(source) = source else {
+            return Ok(result);
+        };
+        let source_type = match source {
+            PickedSource::Output(connector) => {
+                result.insert("output".to_string(), owned_str(&connector));
+                SOURCE_TYPE_MONITOR
+            }
+            PickedSource::Window(handle) => {
+                result.insert("window-id".to_string(), owned_str(&handle));
+                SOURCE_TYPE_WINDOW
+            }
+            PickedSource::Region(rect) => {
+                let region = (rect.loc.x, rect.loc.y, rect.size.w, rect.size.h);
+                let value = OwnedValue::try_from(Value::new(region))
+                    .map_err(|e| zbus::fdo::Error::Failed(format!("Encoding error: {e}")))?;
+                result.insert("region".to_string(), value);
+                SOURCE_TYPE_MONITOR
+            }
+        };
+        result.insert("source-type".to_string(), OwnedValue::from(source_type));
+        Ok(result)
+    }
+}
+
+/// Source type bits of `PickSource`, as in the portal's `types` option.
+const SOURCE_TYPE_MONITOR: u32 = 1;
+const SOURCE_TYPE_WINDOW: u32 = 2;
+
+fn owned_str(value: &str) -> OwnedValue {
+    Value::from(value)
+        .try_into()
+        .expect("string values have no file descriptors")
 }
 
 /// Session D-Bus interface.
@@ -160,10 +265,23 @@ pub struct SessionInterface {
     streams: Arc<RwLock<HashMap<String, StreamState>>>,
 }
 
+/// What a stream captures.
+#[derive(Clone)]
+enum StreamSource {
+    /// The output named by the stream's connector.
+    Output,
+    /// A single window.
+    Window(WindowLookup),
+    /// A rectangle in global logical coordinates.
+    Region(smithay::utils::Rectangle<i32, smithay::utils::Logical>),
+}
+
 /// Internal state for a stream.
 #[derive(Clone)]
 struct StreamState {
+    /// Output connector, or the stream key of a window or region stream.
     connector: String,
+    source: StreamSource,
     cursor_mode: u32,
     node_id: Option<u32>,
     width: u32,
@@ -186,6 +304,46 @@ impl SessionInterface {
             streams: Arc::new(RwLock::new(HashMap::new())),
         }
     }
+
+    /// Store a window or region stream and export its D-Bus object.
+    async fn add_stream(
+        &self,
+        stream_id: u64,
+        stream: StreamState,
+    ) -> zbus::fdo::Result<OwnedObjectPath> {
+        let stream_path = format!("{}/stream/{stream_id}", self.session_path);
+        debug!(connector = %stream.connector, "Adding stream at {stream_path}");
+
+        self.streams
+            .write()
+            .await
+            .insert(stream_path.clone(), stream);
+
+        {
+            let mut sessions = self.sessions.write().await;
+            if let Some(session) = sessions.get_mut(&self.session_path) {
+                session.streams.push(stream_path.clone());
+            }
+        }
+
+        let stream_iface = StreamInterface::new(
+            stream_path.clone(),
+            self.compositor_tx.clone(),
+            self.streams.clone(),
+        );
+
+        let path = ObjectPath::try_from(stream_path.as_str())
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Invalid stream path: {e}")))?;
+
+        self.connection
+            .object_server()
+            .at(path, stream_iface)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to register stream: {e}")))?;
+
+        OwnedObjectPath::try_from(stream_path)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Invalid path: {e}")))
+    }
 }
 
 #[interface(name = "org.otto.ScreenCast.Session")]
@@ -232,6 +390,7 @@ impl SessionInterface {
                 stream_path.clone(),
                 StreamState {
                     connector: connector.to_string(),
+                    source: StreamSource::Output,
                     cursor_mode,
                     node_id: None,
                     width: output.width,
@@ -269,14 +428,97 @@ impl SessionInterface {
             .map_err(|e| zbus::fdo::Error::Failed(format!("Invalid path: {e}")))
     }
 
-    /// Starts recording a window (not implemented).
+    /// Starts recording a single window.
+    ///
+    /// Properties (one of `window-id` or `app-id` is required):
+    /// - `window-id`: string, the handle of the window, as returned by
+    ///   `PickSource` or `org.otto.Compositor.ListWindows`
+    /// - `app-id`: string, the app whose most recent window is captured
+    /// - `cursor-mode`: u32, as for `RecordMonitor`
     async fn record_window(
-        &self,
-        _properties: HashMap<&str, Value<'_>>,
+        &mut self,
+        properties: HashMap<&str, Value<'_>>,
     ) -> zbus::fdo::Result<OwnedObjectPath> {
-        Err(zbus::fdo::Error::NotSupported(
-            "Window recording not yet implemented".to_string(),
-        ))
+        let string = |key| {
+            properties
+                .get(key)
+                .and_then(|v| <&str>::try_from(v).ok())
+                .map(str::to_string)
+        };
+        let window = string("window-id")
+            .map(WindowLookup::Handle)
+            .or_else(|| string("app-id").map(WindowLookup::AppId))
+            .ok_or_else(|| {
+                zbus::fdo::Error::InvalidArgs("Missing window-id or app-id".to_string())
+            })?;
+        let cursor_mode = properties
+            .get("cursor-mode")
+            .and_then(|v| u32::try_from(v).ok())
+            .unwrap_or(1);
+
+        let stream_id = STREAM_COUNTER.fetch_add(1, Ordering::Relaxed);
+        info!(?window, cursor_mode, "Recording window");
+
+        // The window size is only known once the compositor starts the stream
+        self.add_stream(
+            stream_id,
+            StreamState {
+                connector: format!("window-{stream_id}"),
+                source: StreamSource::Window(window),
+                cursor_mode,
+                node_id: None,
+                width: 0,
+                height: 0,
+                started: false,
+            },
+        )
+        .await
+    }
+
+    /// Starts recording a rectangle of the screen, e.g. one returned by
+    /// `SelectRegion` or `PickSource`.
+    ///
+    /// The rectangle is in global logical coordinates. A region spanning
+    /// several outputs is cropped to the one holding most of it.
+    ///
+    /// Properties:
+    /// - `cursor-mode`: u32, as for `RecordMonitor`
+    async fn record_region(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        properties: HashMap<&str, Value<'_>>,
+    ) -> zbus::fdo::Result<OwnedObjectPath> {
+        if width <= 0 || height <= 0 {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "Empty region {width}x{height}"
+            )));
+        }
+        let cursor_mode = properties
+            .get("cursor-mode")
+            .and_then(|v| u32::try_from(v).ok())
+            .unwrap_or(1);
+        let region = smithay::utils::Rectangle::new((x, y).into(), (width, height).into());
+
+        let stream_id = STREAM_COUNTER.fetch_add(1, Ordering::Relaxed);
+        info!(?region, cursor_mode, "Recording region");
+
+        // The size in pixels is only known once the region is placed on an output
+        self.add_stream(
+            stream_id,
+            StreamState {
+                connector: format!("region-{stream_id}"),
+                source: StreamSource::Region(region),
+                cursor_mode,
+                node_id: None,
+                width: 0,
+                height: 0,
+                started: false,
+            },
+        )
+        .await
     }
 
     /// Starts all streams in the session.
@@ -297,14 +539,14 @@ impl SessionInterface {
         };
 
         for stream_path in stream_paths {
-            let (connector, cursor_mode) = {
+            let (connector, source, cursor_mode) = {
                 let streams = self.streams.read().await;
                 streams
                     .get(&stream_path)
-                    .map(|s| (s.connector.clone(), s.cursor_mode))
+                    .map(|s| (s.connector.clone(), s.source.clone(), s.cursor_mode))
                     .unwrap_or_else(|| {
                         // Skip if stream not found
-                        (String::new(), 0)
+                        (String::new(), StreamSource::Output, 0)
                     })
             };
 
@@ -312,6 +554,48 @@ impl SessionInterface {
                 continue;
             }
 
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let command = match source {
+                StreamSource::Output => None,
+                StreamSource::Window(window) => Some(CompositorCommand::StartWindowRecording {
+                    session_id: self.session_path.clone(),
+                    stream_key: connector.clone(),
+                    window,
+                    cursor_mode,
+                    response_tx: tx,
+                }),
+                StreamSource::Region(region) => Some(CompositorCommand::StartRegionRecording {
+                    session_id: self.session_path.clone(),
+                    stream_key: connector.clone(),
+                    region,
+                    cursor_mode,
+                    response_tx: tx,
+                }),
+            };
+            if let Some(command) = command {
+                self.compositor_tx.send(command).map_err(|e| {
+                    zbus::fdo::Error::Failed(format!("Failed to start recording: {e}"))
+                })?;
+
+                let (node_id, width, height) = rx
+                    .await
+                    .map_err(|e| zbus::fdo::Error::Failed(format!("Response channel error: {e}")))?
+                    .map_err(|e| {
+                        error!(%connector, %e, "Failed to start recording");
+                        zbus::fdo::Error::Failed(format!("Failed to start recording: {e}"))
+                    })?;
+
+                info!(%connector, node_id, "Recording started, got PipeWire node");
+                let mut streams = self.streams.write().await;
+                if let Some(stream) = streams.get_mut(&stream_path) {
+                    stream.started = true;
+                    stream.node_id = Some(node_id);
+                    stream.width = width;
+                    stream.height = height;
+                }
+                continue;
+            }
+
             // Create response channel for node_id
             let (tx, rx) = tokio::sync::oneshot::channel();
 
@@ -492,6 +776,11 @@ impl StreamInterface {
         Ok(result)
     }
 
+    /// The compositor ended the stream, e.g. because the captured window
+    /// closed. The stream object is removed right after.
+    #[zbus(signal)]
+    async fn closed(ctxt: &SignalContext<'_>) -> zbus::Result<()>;
+
     /// Returns static stream metadata.
     async fn metadata(&self) -> zbus::fdo::Result<HashMap<String, OwnedValue>> {
         let streams = self.streams.read().await;
@@ -515,44 +804,57 @@ impl StreamInterface {
     }
 }
 
-/// Compositor D-Bus interface for health checks and app management.
-pub struct CompositorInterface {
-    compositor_tx: Sender<CompositorCommand>,
-}
-
-impl CompositorInterface {
-    fn new(compositor_tx: Sender<CompositorCommand>) -> Self {
-        Self { compositor_tx }
-    }
-}
-
-#[interface(name = "org.otto.Compositor")]
-impl CompositorInterface {
-    /// Ping method for watchdog health checks.
-    ///
-    /// Returns "pong" if the compositor is responsive.
-    async fn ping(&self) -> zbus::fdo::Result<String> {
-        debug!("Ping received from watchdog");
-        Ok("pong".to_string())
-    }
+/// Marks the stream `stream_key` of the session at `session_path` stopped,
+/// emits its `Closed` signal and removes its object. The compositor already
+/// dropped the stream. Sessions without a D-Bus object (screen recordings)
+/// are ignored.
+pub(crate) async fn close_stream(
+    connection: &Connection,
+    session_path: &str,
+    stream_key: &str,
+) -> zbus::Result<()> {
+    let Ok(session) = connection
+        .object_server()
+        .interface::<_, SessionInterface>(session_path)
+        .await
+    else {
+        return Ok(());
+    };
+    let streams = session.get().await.streams.clone();
+    let Some(stream_path) = streams
+        .read()
+        .await
+        .iter()
+        .find(|(_, stream)| stream.connector == stream_key)
+        .map(|(path, _)| path.clone())
+    else {
+        return Ok(());
+    };
+    info!(%stream_path, "Stream closed by the compositor");
+
+    let stream = connection
+        .object_server()
+        .interface::<_, StreamInterface>(stream_path.as_str())
+        .await?;
+    StreamInterface::closed(stream.signal_context()).await?;
+    connection
+        .object_server()
+        .remove::<StreamInterface, _>(stream_path.as_str())
+        .await?;
 
-    /// Focus an application window by app_id.
-    ///
-    /// Sends a focus command to the compositor for the given app_id.
-    /// Returns true if the command was dispatched (not whether a window was found).
-    async fn focus_app(&self, app_id: &str) -> zbus::fdo::Result<bool> {
-        info!(app_id, "focus_app requested via D-Bus");
-        self.compositor_tx
-            .send(CompositorCommand::FocusApp {
-                app_id: app_id.to_string(),
-            })
-            .map_err(|e| zbus::fdo::Error::Failed(format!("channel send failed: {e}")))?;
-        Ok(true)
+    streams.write().await.remove(&stream_path);
+    let sessions = session.get().await.sessions.clone();
+    if let Some(session) = sessions.write().await.get_mut(session_path) {
+        session.streams.retain(|path| path != &stream_path);
     }
+    Ok(())
 }
 
 /// Starts the D-Bus service on the session bus.
-pub async fn run_dbus_service(compositor_tx: Sender<CompositorCommand>) -> zbus::Result<()> {
+pub async fn run_dbus_service(
+    compositor_tx: Sender<CompositorCommand>,
+    compositor_events: UnboundedReceiver<CompositorEvent>,
+) -> zbus::Result<()> {
     let connection = Connection::session().await?;
 
     let screencast = ScreenCastInterface::new(compositor_tx.clone(), connection.clone());
@@ -564,21 +866,16 @@ pub async fn run_dbus_service(compositor_tx: Sender<CompositorCommand>) -> zbus:
 
     connection.request_name("org.otto.ScreenCast").await?;
 
-    // Register the compositor interface (health + app management)
-    let compositor = CompositorInterface::new(compositor_tx);
-    connection
-        .object_server()
-        .at("/org/otto/Compositor", compositor)
-        .await?;
-
-    connection.request_name("org.otto.Compositor").await?;
+    // Register the compositor interface (health, queries and control)
+    crate::compositor_service::register_compositor_interface(&connection, compositor_tx).await?;
 
     // Register the Settings interface
     crate::settings_service::register_settings_interface(&connection).await?;
 
     info!("D-Bus service started at org.otto.ScreenCast");
 
-    // Keep the service running
+    // Forward compositor state changes as signals, then keep the service running
+    crate::compositor_service::emit_events(&connection, compositor_events).await;
     std::future::pending::<()>().await;
 
     Ok(())
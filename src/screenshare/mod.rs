@@ -36,11 +36,15 @@
 use std::collections::HashMap;
 
 mod dbus_service;
+pub(crate) use dbus_service::close_stream;
 mod pipewire_stream;
 
 pub use dbus_service::run_dbus_service;
 
-pub use pipewire_stream::{AvailableBuffer, BackendCapabilities, PipeWireStream, StreamConfig};
+pub use pipewire_stream::{
+    AvailableBuffer, BackendCapabilities, CursorBitmap, CursorMetadata, PipeWireStream,
+    StreamConfig, StreamTarget,
+};
 
 use smithay::reexports::calloop::channel::{
     channel, Event as ChannelEvent, Sender as ChannelSender,
@@ -71,6 +75,26 @@ pub struct ActiveStream {
     pub pipewire_stream: PipeWireStream,
 }
 
+/// How [`CompositorCommand::StartWindowRecording`] finds its window.
+#[derive(Debug, Clone)]
+pub enum WindowLookup {
+    /// The most recently mapped window of an app.
+    AppId(String),
+    /// A window by its `org.otto.Compositor` handle.
+    Handle(String),
+}
+
+/// A source chosen with the screencast source picker.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PickedSource {
+    /// A whole output, by connector name.
+    Output(String),
+    /// A window, by its `org.otto.Compositor` handle.
+    Window(String),
+    /// A rectangle in global logical coordinates.
+    Region(smithay::utils::Rectangle<i32, smithay::utils::Logical>),
+}
+
 /// Commands sent from the D-Bus service to the compositor main loop.
 #[derive(Debug)]
 pub enum CompositorCommand {
@@ -91,6 +115,29 @@ pub enum CompositorCommand {
         /// Response channel for the PipeWire node ID.
         response_tx: tokio::sync::oneshot::Sender<Result<u32, String>>,
     },
+    /// Start recording a single window.
+    ///
+    /// The stream is stored under `stream_key` in the session, so it can be
+    /// stopped with [`CompositorCommand::StopRecording`] like an output stream.
+    StartWindowRecording {
+        session_id: String,
+        stream_key: String,
+        window: WindowLookup,
+        cursor_mode: u32,
+        /// Response channel for the PipeWire node ID and the initial frame size.
+        response_tx: tokio::sync::oneshot::Sender<Result<(u32, u32, u32), String>>,
+    },
+    /// Start recording a region, cropped to the output holding most of it.
+    ///
+    /// Stored under `stream_key` like a window stream.
+    StartRegionRecording {
+        session_id: String,
+        stream_key: String,
+        region: smithay::utils::Rectangle<i32, smithay::utils::Logical>,
+        cursor_mode: u32,
+        /// Response channel for the PipeWire node ID and the frame size.
+        response_tx: tokio::sync::oneshot::Sender<Result<(u32, u32, u32), String>>,
+    },
     /// Stop recording on a specific output.
     StopRecording {
         session_id: String,
@@ -105,6 +152,107 @@ pub enum CompositorCommand {
     DestroySession { session_id: String },
     /// Focus an application by app_id (e.g. from notification click).
     FocusApp { app_id: String },
+    /// Run the interactive region selector; replies `None` when cancelled.
+    SelectRegion {
+        response_tx: tokio::sync::oneshot::Sender<
+            Option<smithay::utils::Rectangle<i32, smithay::utils::Logical>>,
+        >,
+    },
+    /// Run the source picker: clicking a window picks it, clicking elsewhere
+    /// picks the output under the pointer and dragging picks a region.
+    /// `outputs` allows outputs and regions, `windows` allows windows.
+    /// Replies `None` when cancelled.
+    PickSource {
+        outputs: bool,
+        windows: bool,
+        response_tx: tokio::sync::oneshot::Sender<Option<PickedSource>>,
+    },
+    /// List the windows of every output and workspace.
+    ListWindows {
+        response_tx: tokio::sync::oneshot::Sender<Vec<crate::compositor_service::WindowInfo>>,
+    },
+    /// List the workspaces of the primary output.
+    ListWorkspaces {
+        response_tx: tokio::sync::oneshot::Sender<Vec<crate::compositor_service::WorkspaceInfo>>,
+    },
+    /// Switch the focused output to a workspace; replies `false` when there
+    /// is no workspace at `index`.
+    SwitchWorkspace {
+        index: usize,
+        response_tx: tokio::sync::oneshot::Sender<bool>,
+    },
+    /// Focus a window by its D-Bus handle; replies `false` when not found.
+    FocusWindow {
+        id: String,
+        response_tx: tokio::sync::oneshot::Sender<bool>,
+    },
+    /// Ask a window to close by its D-Bus handle; replies `false` when not found.
+    CloseWindow {
+        id: String,
+        response_tx: tokio::sync::oneshot::Sender<bool>,
+    },
+    /// List the modes of an output as `(width, height, refresh_mhz)`.
+    ListOutputModes {
+        output: String,
+        response_tx: tokio::sync::oneshot::Sender<Result<Vec<(u32, u32, i32)>, String>>,
+    },
+    /// Switch an output to another mode; `refresh_hz` `None` picks the
+    /// fastest mode of that size.
+    SetOutputMode {
+        output: String,
+        width: u32,
+        height: u32,
+        refresh_hz: Option<f64>,
+        response_tx: tokio::sync::oneshot::Sender<Result<(), String>>,
+    },
+    /// Create a virtual output; replies with its PipeWire node id.
+    CreateVirtualOutput {
+        config: crate::config::VirtualOutputConfig,
+        response_tx: tokio::sync::oneshot::Sender<Result<u32, String>>,
+    },
+    /// Remove a virtual output; replies whether it existed.
+    RemoveVirtualOutput {
+        name: String,
+        response_tx: tokio::sync::oneshot::Sender<Result<bool, String>>,
+    },
+    /// Start an interactive region screenshot.
+    Screenshot,
+    /// Pick a source and record it to a video file.
+    StartScreenRecording,
+    /// Stop the screen recording.
+    StopScreenRecording,
+    /// Toggle night light.
+    ToggleNightLight,
+    /// Switch between the light and dark scheme.
+    ToggleDarkMode,
+    /// List the clipboard history, most recent first.
+    ListClipboardHistory {
+        response_tx:
+            tokio::sync::oneshot::Sender<Vec<crate::compositor_service::ClipboardEntryInfo>>,
+    },
+    /// Query the mime type and bytes of a clipboard history entry.
+    GetClipboardEntry {
+        id: u32,
+        response_tx: tokio::sync::oneshot::Sender<Option<(String, Vec<u8>)>>,
+    },
+    /// Make a clipboard history entry the clipboard selection again.
+    ApplyClipboardEntry {
+        id: u32,
+        response_tx: tokio::sync::oneshot::Sender<bool>,
+    },
+    /// Forget a clipboard history entry.
+    RemoveClipboardEntry {
+        id: u32,
+        response_tx: tokio::sync::oneshot::Sender<bool>,
+    },
+    /// Forget the whole clipboard history.
+    ClearClipboardHistory,
+    /// Open or close the clipboard history picker.
+    ShowClipboardHistory,
+    /// Query the power source and battery charge; `None` without UPower.
+    GetPowerState {
+        response_tx: tokio::sync::oneshot::Sender<Option<crate::power::PowerState>>,
+    },
 }
 
 /// Information about an available output.
@@ -131,6 +279,9 @@ pub struct StreamInfo {
 pub struct ScreenshareManager {
     /// Sender for commands from the D-Bus thread.
     pub command_sender: ChannelSender<CompositorCommand>,
+    /// Sender for state changes emitted as `org.otto.Compositor` signals.
+    pub event_sender:
+        tokio::sync::mpsc::UnboundedSender<crate::compositor_service::CompositorEvent>,
 }
 
 impl ScreenshareManager {
@@ -142,6 +293,7 @@ impl ScreenshareManager {
         loop_handle: &smithay::reexports::calloop::LoopHandle<'static, crate::state::Otto<B>>,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let (cmd_sender, cmd_receiver) = channel::<CompositorCommand>();
+        let (event_sender, event_receiver) = tokio::sync::mpsc::unbounded_channel();
 
         // Register the calloop channel to receive commands
         loop_handle
@@ -163,7 +315,9 @@ impl ScreenshareManager {
                     .expect("Failed to create tokio runtime for screenshare");
 
                 rt.block_on(async move {
-                    if let Err(e) = dbus_service::run_dbus_service(cmd_sender_clone).await {
+                    if let Err(e) =
+                        dbus_service::run_dbus_service(cmd_sender_clone, event_receiver).await
+                    {
                         tracing::error!("Screenshare D-Bus service failed: {}", e);
                     }
                 });
@@ -171,6 +325,7 @@ impl ScreenshareManager {
 
         Ok(Self {
             command_sender: cmd_sender,
+            event_sender,
         })
     }
 }
@@ -234,20 +389,15 @@ fn handle_screenshare_command<B: crate::state::Backend + 'static>(
                 cursor_mode
             );
 
-            // Find the output by connector name
-            let output = state
-                .workspaces
-                .outputs()
-                .find(|o| o.name() == output_connector);
-
-            let output = match output {
-                Some(o) => o.clone(),
-                None => {
-                    let _ =
-                        response_tx.send(Err(format!("Output not found: {}", output_connector)));
+            let source = match CaptureSource::output(state, &output_connector) {
+                Ok(source) => source,
+                Err(e) => {
+                    let _ = response_tx.send(Err(e));
                     return;
                 }
             };
+            // TODO: Make screenshare FPS cap configurable (e.g., config.screenshare.max_fps)
+            let config = source.stream_config(state, SCREENCAST_MAX_FPS);
 
             // Get the session and update cursor_mode
             let session = match state.screenshare_sessions.get_mut(&session_id) {
@@ -270,49 +420,7 @@ fn handle_screenshare_command<B: crate::state::Backend + 'static>(
                 return;
             }
 
-            // Get output dimensions for stream config
-            let (width, height, refresh_rate) = output
-                .current_mode()
-                .map(|m| (m.size.w as u32, m.size.h as u32, m.refresh as u32))
-                .unwrap_or((1920, 1080, 60000));
-
-            // Build backend capabilities
-            let gbm_device = state.backend_data.gbm_device();
-            let capabilities = if let Some(ref _gbm) = gbm_device {
-                use smithay::backend::allocator::Fourcc;
-
-                // For now, advertise ARGB8888 with common modifiers
-                // In production, we'd query the actual supported formats from the backend
-                let formats = vec![Fourcc::Argb8888];
-
-                // Common DRM modifiers - LINEAR and INVALID (for implicit modifier)
-                const DRM_FORMAT_MOD_LINEAR: i64 = 0;
-                const DRM_FORMAT_MOD_INVALID: i64 = 0x00ffffffffffffff_u64 as i64;
-                let modifiers = vec![DRM_FORMAT_MOD_INVALID, DRM_FORMAT_MOD_LINEAR];
-
-                pipewire_stream::BackendCapabilities {
-                    supports_dmabuf: true,
-                    formats,
-                    modifiers,
-                }
-            } else {
-                // Fallback to SHM
-                pipewire_stream::BackendCapabilities::default()
-            };
-
             // Create PipeWire stream
-            // TODO: Make screenshare FPS cap configurable (e.g., config.screenshare.max_fps)
-            // Chrome/WebRTC don't support >60fps, so we cap here for compatibility
-            let framerate_num = (refresh_rate / 1000).min(60); // Cap at 60fps for compatibility
-
-            let config = StreamConfig {
-                width,
-                height,
-                framerate_num,
-                framerate_denom: 1,
-                gbm_device,
-                capabilities,
-            };
             let mut pipewire_stream = PipeWireStream::new(config);
 
             // Start the PipeWire stream synchronously (spawns a thread and connects to PipeWire)
@@ -350,6 +458,148 @@ fn handle_screenshare_command<B: crate::state::Backend + 'static>(
             // Send success response with node_id
             let _ = response_tx.send(Ok(node_id));
         }
+        CompositorCommand::StartWindowRecording {
+            session_id,
+            stream_key,
+            window,
+            cursor_mode,
+            response_tx,
+        } => {
+            tracing::debug!(
+                "StartWindowRecording: session={}, window={:?}, cursor_mode={}",
+                session_id,
+                window,
+                cursor_mode
+            );
+
+            let found = match &window {
+                // Most recently mapped window of the app
+                WindowLookup::AppId(app_id) => state
+                    .workspaces
+                    .get_app_windows(app_id)
+                    .last()
+                    .and_then(|wid| state.workspaces.get_window_for_surface(wid))
+                    .cloned(),
+                WindowLookup::Handle(handle) => state.window_by_handle(handle),
+            };
+            let Some(window) = found else {
+                let _ = response_tx.send(Err(format!("No window found: {:?}", window)));
+                return;
+            };
+
+            let config =
+                CaptureSource::window(state, window).stream_config(state, SCREENCAST_MAX_FPS);
+            let (width, height) = (config.width, config.height);
+
+            let session = match state.screenshare_sessions.get_mut(&session_id) {
+                Some(s) => s,
+                None => {
+                    let _ = response_tx.send(Err(format!("Session not found: {}", session_id)));
+                    return;
+                }
+            };
+            session.cursor_mode = cursor_mode;
+
+            if session.streams.contains_key(&stream_key) {
+                let _ = response_tx.send(Err(format!("Already recording: {}", stream_key)));
+                return;
+            }
+
+            let mut pipewire_stream = PipeWireStream::new(config);
+            let node_id = match pipewire_stream.start_sync() {
+                Ok(id) => id,
+                Err(e) => {
+                    let _ =
+                        response_tx.send(Err(format!("Failed to start PipeWire stream: {}", e)));
+                    return;
+                }
+            };
+
+            tracing::debug!(
+                "Started window stream: session={}, key={}, node_id={}, {}x{}",
+                session_id,
+                stream_key,
+                node_id,
+                width,
+                height
+            );
+
+            session.streams.insert(
+                stream_key.clone(),
+                ActiveStream {
+                    output_connector: stream_key,
+                    pipewire_stream,
+                },
+            );
+
+            let _ = response_tx.send(Ok((node_id, width, height)));
+        }
+        CompositorCommand::StartRegionRecording {
+            session_id,
+            stream_key,
+            region,
+            cursor_mode,
+            response_tx,
+        } => {
+            tracing::debug!(
+                "StartRegionRecording: session={}, region={:?}, cursor_mode={}",
+                session_id,
+                region,
+                cursor_mode
+            );
+
+            let (output, source) = match CaptureSource::region(state, region) {
+                Ok(source) => source,
+                Err(e) => {
+                    let _ = response_tx.send(Err(e));
+                    return;
+                }
+            };
+            let config = source.stream_config(state, SCREENCAST_MAX_FPS);
+            let (width, height) = (config.width, config.height);
+
+            let session = match state.screenshare_sessions.get_mut(&session_id) {
+                Some(s) => s,
+                None => {
+                    let _ = response_tx.send(Err(format!("Session not found: {}", session_id)));
+                    return;
+                }
+            };
+            session.cursor_mode = cursor_mode;
+
+            if session.streams.contains_key(&stream_key) {
+                let _ = response_tx.send(Err(format!("Already recording: {}", stream_key)));
+                return;
+            }
+
+            let mut pipewire_stream = PipeWireStream::new(config);
+            let node_id = match pipewire_stream.start_sync() {
+                Ok(id) => id,
+                Err(e) => {
+                    let _ =
+                        response_tx.send(Err(format!("Failed to start PipeWire stream: {}", e)));
+                    return;
+                }
+            };
+
+            tracing::debug!(
+                "Started region stream: session={}, output={}, region={:?}, node_id={}",
+                session_id,
+                output,
+                region,
+                node_id
+            );
+
+            session.streams.insert(
+                stream_key.clone(),
+                ActiveStream {
+                    output_connector: stream_key,
+                    pipewire_stream,
+                },
+            );
+
+            let _ = response_tx.send(Ok((node_id, width, height)));
+        }
         CompositorCommand::StopRecording {
             session_id,
             output_connector,
@@ -413,6 +663,402 @@ fn handle_screenshare_command<B: crate::state::Backend + 'static>(
             tracing::info!("FocusApp: {}", app_id);
             state.focus_app(&app_id);
         }
+        CompositorCommand::SelectRegion { response_tx } => {
+            state.start_region_selection(
+                crate::state::region_selection::RegionSelectionPurpose::Reply(response_tx),
+            );
+        }
+        CompositorCommand::PickSource {
+            outputs,
+            windows,
+            response_tx,
+        } => {
+            state.start_region_selection(
+                crate::state::region_selection::RegionSelectionPurpose::PickSource {
+                    outputs,
+                    windows,
+                    response_tx,
+                },
+            );
+        }
+        CompositorCommand::ListWindows { response_tx } => {
+            let _ = response_tx.send(state.window_infos());
+        }
+        CompositorCommand::ListWorkspaces { response_tx } => {
+            let _ = response_tx.send(state.workspace_infos());
+        }
+        CompositorCommand::SwitchWorkspace { index, response_tx } => {
+            let count = state.workspaces.with_model(|m| m.workspaces.len());
+            if index < count {
+                state.set_current_workspace_index(index);
+            }
+            let _ = response_tx.send(index < count);
+        }
+        CompositorCommand::FocusWindow { id, response_tx } => {
+            let _ = response_tx.send(state.focus_window_by_handle(&id));
+        }
+        CompositorCommand::CloseWindow { id, response_tx } => {
+            let _ = response_tx.send(state.close_window_by_handle(&id));
+        }
+        CompositorCommand::ListOutputModes {
+            output,
+            response_tx,
+        } => {
+            let _ = response_tx.send(state.output_mode_list(&output));
+        }
+        CompositorCommand::SetOutputMode {
+            output,
+            width,
+            height,
+            refresh_hz,
+            response_tx,
+        } => {
+            let _ = response_tx.send(state.change_output_mode(&output, width, height, refresh_hz));
+        }
+        CompositorCommand::CreateVirtualOutput {
+            config,
+            response_tx,
+        } => {
+            state.request_virtual_output(crate::virtual_output::VirtualOutputCommand::Add {
+                config,
+                response_tx: Some(response_tx),
+            });
+        }
+        CompositorCommand::RemoveVirtualOutput { name, response_tx } => {
+            state.request_virtual_output(crate::virtual_output::VirtualOutputCommand::Remove {
+                name,
+                response_tx: Some(response_tx),
+            });
+        }
+        CompositorCommand::Screenshot => {
+            state.start_region_selection(
+                crate::state::region_selection::RegionSelectionPurpose::Screenshot,
+            );
+        }
+        CompositorCommand::StartScreenRecording => state.start_recording(),
+        CompositorCommand::StopScreenRecording => state.stop_recording(),
+        CompositorCommand::ToggleNightLight => state.toggle_night_light(),
+        CompositorCommand::ToggleDarkMode => state.toggle_dark_mode(),
+        CompositorCommand::ListClipboardHistory { response_tx } => {
+            let _ = response_tx.send(state.clipboard_history_infos());
+        }
+        CompositorCommand::GetClipboardEntry { id, response_tx } => {
+            let entry = state.clipboard_history.get(id).map(|entry| {
+                (
+                    entry.content.mime_type().to_string(),
+                    entry.content.bytes().to_vec(),
+                )
+            });
+            let _ = response_tx.send(entry);
+        }
+        CompositorCommand::ApplyClipboardEntry { id, response_tx } => {
+            let _ = response_tx.send(state.apply_clipboard_entry(id));
+        }
+        CompositorCommand::RemoveClipboardEntry { id, response_tx } => {
+            let _ = response_tx.send(state.remove_clipboard_entry(id));
+        }
+        CompositorCommand::ClearClipboardHistory => state.clear_clipboard_history(),
+        CompositorCommand::ShowClipboardHistory => state.toggle_clipboard_picker(),
+        CompositorCommand::GetPowerState { response_tx } => {
+            let _ = response_tx.send(state.power_state);
+        }
+    }
+}
+
+/// The output holding the largest part of `region`, and that part in the
+/// output's local physical pixels. `outputs` yields each output with its
+/// global logical geometry and scale.
+fn region_on_output<O>(
+    region: smithay::utils::Rectangle<i32, smithay::utils::Logical>,
+    outputs: impl IntoIterator<
+        Item = (
+            O,
+            smithay::utils::Rectangle<i32, smithay::utils::Logical>,
+            f64,
+        ),
+    >,
+) -> Option<(O, smithay::utils::Rectangle<i32, smithay::utils::Physical>)> {
+    outputs
+        .into_iter()
+        .filter_map(|(output, geometry, scale)| {
+            let mut part = geometry.intersection(region)?;
+            let covered = part.size.w as i64 * part.size.h as i64;
+            part.loc -= geometry.loc;
+            let area = part.to_physical_precise_round(scale);
+            (area.size.w > 0 && area.size.h > 0).then_some((covered, output, area))
+        })
+        .max_by_key(|(covered, _, _)| *covered)
+        .map(|(_, output, area)| (output, area))
+}
+
+/// Highest frame rate of screencast streams: Chrome/WebRTC don't support
+/// more than 60fps
+const SCREENCAST_MAX_FPS: u32 = 60;
+
+/// What a stream captures, with its size in physical pixels and the refresh
+/// rate of the output it is on, in mHz
+struct CaptureSource {
+    width: u32,
+    height: u32,
+    refresh_rate: u32,
+    target: StreamTarget,
+}
+
+impl CaptureSource {
+    /// The whole output called `name`
+    fn output<B: crate::state::Backend>(
+        state: &crate::state::Otto<B>,
+        name: &str,
+    ) -> Result<Self, String> {
+        let output = state
+            .workspaces
+            .outputs()
+            .find(|o| o.name() == name)
+            .ok_or_else(|| format!("Output not found: {name}"))?;
+        let (width, height, refresh_rate) = output
+            .current_mode()
+            .map(|m| (m.size.w as u32, m.size.h as u32, m.refresh as u32))
+            .unwrap_or((1920, 1080, 60000));
+        Ok(Self {
+            width,
+            height,
+            refresh_rate,
+            target: StreamTarget::Output,
+        })
+    }
+
+    /// `window` at its current size, at the scale of the output it is on
+    fn window<B: crate::state::Backend>(
+        state: &crate::state::Otto<B>,
+        window: crate::shell::WindowElement,
+    ) -> Self {
+        let scale = state
+            .workspaces
+            .outputs_for_element(&window)
+            .first()
+            .map(|o| o.current_scale().fractional_scale())
+            .unwrap_or(1.0);
+        let size_px = window
+            .geometry()
+            .size
+            .to_f64()
+            .to_physical(scale)
+            .to_i32_round();
+        Self {
+            width: size_px.w.max(1) as u32,
+            height: size_px.h.max(1) as u32,
+            refresh_rate: 60000,
+            target: StreamTarget::Window(window),
+        }
+    }
+
+    /// The part of `region`, in global logical coordinates, on the output
+    /// holding most of it, with the name of that output
+    fn region<B: crate::state::Backend>(
+        state: &crate::state::Otto<B>,
+        region: smithay::utils::Rectangle<i32, smithay::utils::Logical>,
+    ) -> Result<(String, Self), String> {
+        let outputs: Vec<_> = state
+            .workspaces
+            .outputs()
+            .filter_map(|output| {
+                let geometry = state.workspaces.output_geometry(output)?;
+                let scale = output.current_scale().fractional_scale();
+                Some((output.clone(), geometry, scale))
+            })
+            .collect();
+        let (output, area) = region_on_output(
+            region,
+            outputs
+                .iter()
+                .map(|(output, geometry, scale)| (output, *geometry, *scale)),
+        )
+        .ok_or("Region is outside every output")?;
+        let refresh_rate = output
+            .current_mode()
+            .map(|m| m.refresh as u32)
+            .unwrap_or(60000);
+        Ok((
+            output.name(),
+            Self {
+                width: area.size.w as u32,
+                height: area.size.h as u32,
+                refresh_rate,
+                target: StreamTarget::Region {
+                    output: output.name(),
+                    area,
+                },
+            },
+        ))
+    }
+
+    /// Stream settings capturing this source at the refresh rate of its
+    /// output, at most `max_fps`
+    fn stream_config<B: crate::state::Backend>(
+        self,
+        state: &crate::state::Otto<B>,
+        max_fps: u32,
+    ) -> StreamConfig {
+        let gbm_device = state.backend_data.gbm_device();
+        let capabilities = backend_capabilities(gbm_device.is_some());
+        StreamConfig {
+            width: self.width,
+            height: self.height,
+            framerate_num: (self.refresh_rate / 1000).clamp(1, max_fps.max(1)),
+            framerate_denom: 1,
+            gbm_device,
+            capabilities,
+            target: self.target,
+        }
+    }
+}
+
+/// Stream settings for a source picked with the source picker, at most
+/// `max_fps`, and the key to store the stream under in its session: the
+/// render loop finds output streams by connector name.
+pub fn picked_source_stream<B: crate::state::Backend>(
+    state: &crate::state::Otto<B>,
+    source: &PickedSource,
+    max_fps: u32,
+) -> Result<(String, StreamConfig), String> {
+    let (key, source) = match source {
+        PickedSource::Output(name) => (name.clone(), CaptureSource::output(state, name)?),
+        PickedSource::Window(handle) => {
+            let window = state
+                .window_by_handle(handle)
+                .ok_or_else(|| format!("No window found: {handle}"))?;
+            (handle.clone(), CaptureSource::window(state, window))
+        }
+        PickedSource::Region(region) => {
+            let (output, source) = CaptureSource::region(state, *region)?;
+            (format!("region-{output}"), source)
+        }
+    };
+    Ok((key, source.stream_config(state, max_fps)))
+}
+
+/// Cursor state for metadata cursor mode, in physical pixels relative to
+/// `area`: the captured output, or window, in global logical coordinates.
+///
+/// The bitmap is `seat`'s cursor from
+/// [`crate::cursor::CursorManager::get_render_cursor`] at `output`'s scale:
+/// named cursors use the themed xcursor frame, client cursor surfaces are read
+/// from their SHM buffer. A cursor outside `area` is reported without bitmap.
+pub fn cursor_metadata(
+    cursor_manager: &crate::cursor::CursorManager,
+    seat: &str,
+    output: &smithay::output::Output,
+    area: smithay::utils::Rectangle<i32, smithay::utils::Logical>,
+    pointer_location: smithay::utils::Point<f64, smithay::utils::Logical>,
+    time_millis: u32,
+) -> CursorMetadata {
+    use crate::cursor::RenderCursor;
+
+    let scale = output.current_scale().fractional_scale();
+    let local = (pointer_location - area.loc.to_f64())
+        .to_physical(scale)
+        .to_i32_round();
+    let position = (local.x, local.y);
+
+    if !area.to_f64().contains(pointer_location) {
+        return CursorMetadata {
+            position,
+            ..Default::default()
+        };
+    }
+
+    match cursor_manager.get_render_cursor(seat, scale) {
+        RenderCursor::Hidden => CursorMetadata {
+            position,
+            ..Default::default()
+        },
+        RenderCursor::Surface { hotspot, surface } => {
+            let hotspot = hotspot.to_f64().to_physical(scale).to_i32_round();
+            CursorMetadata {
+                position,
+                hotspot: (hotspot.x, hotspot.y),
+                bitmap: surface_cursor_bitmap(&surface),
+            }
+        }
+        RenderCursor::Named { cursor, .. } => {
+            let (_, image) = cursor.frame(time_millis);
+            CursorMetadata {
+                position,
+                hotspot: (image.xhot as i32, image.yhot as i32),
+                bitmap: Some(CursorBitmap {
+                    width: image.width,
+                    height: image.height,
+                    pixels: image.pixels_rgba.clone(),
+                }),
+            }
+        }
+    }
+}
+
+/// Read a client cursor surface's SHM buffer as an ARGB8888 bitmap.
+fn surface_cursor_bitmap(
+    surface: &smithay::reexports::wayland_server::protocol::wl_surface::WlSurface,
+) -> Option<CursorBitmap> {
+    use smithay::backend::renderer::utils::RendererSurfaceStateUserData;
+    use smithay::reexports::wayland_server::protocol::wl_shm;
+    use smithay::wayland::{compositor::with_states, shm};
+
+    let buffer = with_states(surface, |states| {
+        states
+            .data_map
+            .get::<RendererSurfaceStateUserData>()
+            .and_then(|data| data.lock().unwrap().buffer().cloned())
+    })?;
+
+    shm::with_buffer_contents(&buffer, |ptr, len, data| {
+        let opaque = match data.format {
+            wl_shm::Format::Argb8888 => false,
+            wl_shm::Format::Xrgb8888 => true,
+            _ => return None,
+        };
+        let (width, height) = (data.width as usize, data.height as usize);
+        let (offset, stride) = (data.offset as usize, data.stride as usize);
+        let src = unsafe { std::slice::from_raw_parts(ptr, len) };
+
+        let mut pixels = Vec::with_capacity(width * height * 4);
+        for row in 0..height {
+            let start = offset + row * stride;
+            pixels.extend_from_slice(src.get(start..start + width * 4)?);
+        }
+        if opaque {
+            pixels.chunks_exact_mut(4).for_each(|px| px[3] = 0xff);
+        }
+        Some(CursorBitmap {
+            width: width as u32,
+            height: height as u32,
+            pixels,
+        })
+    })
+    .ok()
+    .flatten()
+}
+
+/// Stream capabilities to advertise for the current backend.
+fn backend_capabilities(has_gbm_device: bool) -> BackendCapabilities {
+    if !has_gbm_device {
+        // Fallback to SHM
+        return BackendCapabilities::default();
+    }
+    use smithay::backend::allocator::Fourcc;
+
+    // For now, advertise ARGB8888 with common modifiers
+    // In production, we'd query the actual supported formats from the backend
+    let formats = vec![Fourcc::Argb8888];
+
+    // Common DRM modifiers - LINEAR and INVALID (for implicit modifier)
+    const DRM_FORMAT_MOD_LINEAR: i64 = 0;
+    const DRM_FORMAT_MOD_INVALID: i64 = 0x00ffffffffffffff_u64 as i64;
+    let modifiers = vec![DRM_FORMAT_MOD_INVALID, DRM_FORMAT_MOD_LINEAR];
+
+    BackendCapabilities {
+        supports_dmabuf: true,
+        formats,
+        modifiers,
     }
 }
 
@@ -482,3 +1128,86 @@ where
 
     Ok(())
 }
+
+/// Copy `area` of the compositor framebuffer to a PipeWire buffer of the
+/// area's size, with cursor rendering
+///
+/// Like [`fullscreen_to_dmabuf`] for region streams; cursor elements are in
+/// output coordinates and drawn shifted by the area's origin.
+pub fn region_to_dmabuf<R, E>(
+    renderer: &mut R,
+    dst_dmabuf: &mut smithay::backend::allocator::dmabuf::Dmabuf,
+    area: smithay::utils::Rectangle<i32, smithay::utils::Physical>,
+    cursor_elements: &[E],
+    scale: smithay::utils::Scale<f64>,
+) -> Result<(), String>
+where
+    R: smithay::backend::renderer::Renderer
+        + smithay::backend::renderer::Bind<smithay::backend::allocator::dmabuf::Dmabuf>,
+    R: BlitCurrentFrame,
+    E: smithay::backend::renderer::element::RenderElement<R>,
+{
+    let target = smithay::utils::Rectangle::from_size(area.size);
+    renderer
+        .blit_current_frame(dst_dmabuf, area, target)
+        .map_err(|e| format!("Blit failed: {:?}", e))?;
+
+    if !cursor_elements.is_empty() {
+        let mut dmabuf_fb = renderer
+            .bind(dst_dmabuf)
+            .map_err(|e| format!("Failed to bind dmabuf: {:?}", e))?;
+        let mut cursor_frame = renderer
+            .render(&mut dmabuf_fb, area.size, smithay::utils::Transform::Normal)
+            .map_err(|e| format!("Failed to create cursor frame: {:?}", e))?;
+
+        for element in cursor_elements.iter() {
+            let src = element.src();
+            let mut dst = element.geometry(scale);
+            dst.loc -= area.loc;
+            if let Some(mut damage) = target.intersection(dst) {
+                damage.loc -= dst.loc;
+                element
+                    .draw(&mut cursor_frame, src, dst, &[damage], &[])
+                    .map_err(|e| format!("Failed to draw cursor element: {:?}", e))?;
+            }
+        }
+
+        std::mem::drop(cursor_frame);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smithay::utils::Rectangle;
+
+    #[test]
+    fn region_goes_to_the_output_holding_most_of_it() {
+        let outputs = [
+            (
+                "left",
+                Rectangle::new((0, 0).into(), (1920, 1080).into()),
+                1.0,
+            ),
+            (
+                "right",
+                Rectangle::new((1920, 0).into(), (1280, 800).into()),
+                2.0,
+            ),
+        ];
+        let region = Rectangle::new((1800, 100).into(), (400, 200).into());
+        let (output, area) = region_on_output(region, outputs).unwrap();
+        assert_eq!(output, "right");
+        assert_eq!(area, Rectangle::new((0, 200).into(), (560, 400).into()));
+
+        let left = Rectangle::new((100, 50).into(), (300, 200).into());
+        let (output, area) = region_on_output(left, outputs).unwrap();
+        assert_eq!(output, "left");
+        assert_eq!(area, Rectangle::new((100, 50).into(), (300, 200).into()));
+
+        let outside = Rectangle::new((0, 2000).into(), (10, 10).into());
+        assert!(region_on_output(outside, outputs).is_none());
+    }
+}
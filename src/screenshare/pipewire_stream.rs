@@ -3,7 +3,7 @@
 //! Format negotiation-first approach: advertise capabilities based on backend,
 //! negotiate format, then route to appropriate buffer handling path.
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{
     atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
     Arc, Mutex,
@@ -17,6 +17,8 @@ use smithay::backend::allocator::{
 };
 use smithay::backend::drm::DrmDeviceFd;
 
+use crate::shell::WindowElement;
+
 /// Get current monotonic time in nanoseconds
 fn get_monotonic_time_ns() -> u64 {
     SystemTime::now()
@@ -36,6 +38,8 @@ pub struct BufferPool {
     pub to_queue: HashMap<i64, *mut pipewire::sys::pw_buffer>,
     /// Track last rendered buffer FD to detect buffer changes
     pub last_rendered_fd: Option<i64>,
+    /// Buffers queued only to carry cursor metadata (no new pixels)
+    pub cursor_only: HashSet<i64>,
 }
 
 // SAFETY: pw_buffer pointers are only accessed from PipeWire thread
@@ -70,6 +74,59 @@ impl Default for BackendCapabilities {
     }
 }
 
+/// Largest cursor bitmap (per side, in pixels) carried in `SPA_META_Cursor`.
+const CURSOR_META_MAX_SIZE: u32 = 256;
+
+/// Bytes reserved per buffer for cursor metadata, including the bitmap.
+fn cursor_meta_size() -> usize {
+    std::mem::size_of::<pipewire::spa::sys::spa_meta_cursor>()
+        + std::mem::size_of::<pipewire::spa::sys::spa_meta_bitmap>()
+        + (CURSOR_META_MAX_SIZE * CURSOR_META_MAX_SIZE * 4) as usize
+}
+
+/// Cursor image for metadata cursor mode, ARGB8888 (BGRA in memory).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CursorBitmap {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Cursor state sent as `SPA_META_Cursor` when a session uses metadata cursor
+/// mode, so consumers can draw the cursor themselves.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CursorMetadata {
+    /// Pointer (hotspot) position in stream pixels.
+    pub position: (i32, i32),
+    /// Hotspot offset within the bitmap, in pixels.
+    pub hotspot: (i32, i32),
+    /// Cursor image; `None` when the cursor is hidden or outside the stream.
+    pub bitmap: Option<CursorBitmap>,
+}
+
+#[derive(Default)]
+struct CursorState {
+    metadata: CursorMetadata,
+    /// The bitmap changed and hasn't been attached to a buffer yet.
+    bitmap_dirty: bool,
+}
+
+/// What a stream captures.
+#[derive(Debug, Clone, Default)]
+pub enum StreamTarget {
+    /// A whole output (physical or virtual), identified by the session's stream key.
+    #[default]
+    Output,
+    /// A single toplevel, rendered on its own at its current size.
+    Window(WindowElement),
+    /// Part of an output, cropped from its rendered frame; `area` is in
+    /// output-local physical pixels.
+    Region {
+        output: String,
+        area: smithay::utils::Rectangle<i32, smithay::utils::Physical>,
+    },
+}
+
 /// Configuration for a PipeWire stream.
 #[derive(Debug, Clone)]
 pub struct StreamConfig {
@@ -83,6 +140,8 @@ pub struct StreamConfig {
     pub capabilities: BackendCapabilities,
     /// GBM device (if backend supports DMA-BUF)
     pub gbm_device: Option<GbmDevice<DrmDeviceFd>>,
+    /// What the stream captures
+    pub target: StreamTarget,
 }
 
 impl Default for StreamConfig {
@@ -94,6 +153,7 @@ impl Default for StreamConfig {
             framerate_denom: 1,
             capabilities: BackendCapabilities::default(),
             gbm_device: None,
+            target: StreamTarget::Output,
         }
     }
 }
@@ -126,6 +186,10 @@ struct SharedState {
     frame_sequence: AtomicU64,
     /// Start time for calculating PTS (nanoseconds since CLOCK_MONOTONIC)
     start_time_ns: AtomicU64,
+    /// New frame size to renegotiate, picked up by the PipeWire thread
+    pending_size: Mutex<Option<(u32, u32)>>,
+    /// Cursor metadata attached to each queued buffer
+    cursor: Mutex<CursorState>,
 }
 
 // SAFETY: pw_stream pointer is only used to call pw_stream_trigger_process
@@ -158,6 +222,8 @@ impl PipeWireStream {
             stream_ptr: Arc::new(Mutex::new(None)),
             frame_sequence: AtomicU64::new(0),
             start_time_ns: AtomicU64::new(0),
+            pending_size: Mutex::new(None),
+            cursor: Mutex::new(CursorState::default()),
         });
 
         Self { shared, config }
@@ -179,7 +245,12 @@ impl PipeWireStream {
             self.config.capabilities.supports_dmabuf
         );
 
-        let config = self.config.clone();
+        // The PipeWire thread only needs the format; compositor objects such as
+        // the captured window stay on the main thread.
+        let config = StreamConfig {
+            target: StreamTarget::Output,
+            ..self.config.clone()
+        };
         let shared = self.shared.clone();
 
         // Channel for initialization result
@@ -236,6 +307,72 @@ impl PipeWireStream {
     pub fn increment_frame_sequence(&self) {
         self.shared.frame_sequence.fetch_add(1, Ordering::Relaxed);
     }
+
+    /// What this stream captures.
+    pub fn target(&self) -> &StreamTarget {
+        &self.config.target
+    }
+
+    /// Current frame size in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        (self.config.width, self.config.height)
+    }
+
+    /// Renegotiate the stream format for a new frame size.
+    ///
+    /// Buffers of the old size are dropped from the pool as PipeWire removes
+    /// them; new ones arrive once the consumer accepts the new format.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if (width, height) == self.size() {
+            return;
+        }
+        tracing::debug!("Renegotiating PipeWire stream size to {}x{}", width, height);
+        self.config.width = width;
+        self.config.height = height;
+        *self.shared.pending_size.lock().unwrap() = Some((width, height));
+    }
+
+    /// Update the cursor metadata attached to subsequent buffers.
+    ///
+    /// Returns `true` when anything changed, so callers can push a
+    /// cursor-only buffer on frames without pixel damage.
+    pub fn set_cursor(&self, cursor: CursorMetadata) -> bool {
+        let mut state = self.shared.cursor.lock().unwrap();
+        if state.metadata == cursor {
+            return false;
+        }
+        if state.metadata.bitmap != cursor.bitmap {
+            state.bitmap_dirty = true;
+        }
+        state.metadata = cursor;
+        true
+    }
+
+    /// Queue a buffer that only carries cursor metadata.
+    ///
+    /// Used when the pointer moved but nothing was rendered: the chunk is sent
+    /// with size 0 so consumers keep the previous frame's pixels.
+    pub fn queue_cursor_update(&self) {
+        {
+            let mut pool = self.shared.buffer_pool.lock().unwrap();
+            if let Some(buf) = pool.available.pop_front() {
+                pool.cursor_only.insert(buf.fd);
+                pool.to_queue.insert(buf.fd, buf.pw_buffer);
+            }
+        }
+        self.trigger_frame();
+    }
+
+    /// Ask the PipeWire thread to disconnect the stream and exit.
+    pub fn stop(&self) {
+        self.shared.should_stop.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for PipeWireStream {
+    fn drop(&mut self) {
+        self.stop();
+    }
 }
 
 /// PipeWire error types.
@@ -521,9 +658,22 @@ fn run_pipewire_thread(
         })
         .remove_buffer({
             let state = stream_state.clone();
+            let buffer_pool = shared.buffer_pool.clone();
             move |_stream, _user_data, buffer| unsafe {
                 let fd = (*(*buffer).buffer).datas.read().fd;
                 let removed = state.borrow_mut().dmabufs.remove(&fd);
+
+                // Forget the buffer on the main-thread side too, so a stale
+                // buffer (e.g. of the previous size) is never rendered into.
+                let mut pool = buffer_pool.lock().unwrap();
+                pool.dmabufs.remove(&fd);
+                pool.available.retain(|b| b.fd != fd);
+                pool.to_queue.remove(&fd);
+                pool.cursor_only.remove(&fd);
+                if pool.last_rendered_fd == Some(fd) {
+                    pool.last_rendered_fd = None;
+                }
+
                 if removed.is_some() {
                     tracing::debug!("Buffer removed fd={}", fd);
                 }
@@ -544,10 +694,16 @@ fn run_pipewire_thread(
                     let mut pool = buffer_pool.lock().unwrap();
                     let to_queue: Vec<_> = pool.to_queue.drain().collect();
                     for (fd, pw_buffer) in to_queue {
+                        let cursor_only = pool.cursor_only.remove(&fd);
                         unsafe {
                             let spa_buffer = (*pw_buffer).buffer;
                             let chunk = (*(*spa_buffer).datas).chunk;
-                            (*chunk).size = 1;
+                            (*chunk).size = if cursor_only { 0 } else { 1 };
+
+                            write_cursor_meta(
+                                spa_buffer,
+                                &mut shared_for_process.cursor.lock().unwrap(),
+                            );
 
                             // Set timestamp metadata
                             let meta_header = pipewire::spa::sys::spa_buffer_find_meta_data(
@@ -667,9 +823,36 @@ fn run_pipewire_thread(
     let loop_ref = mainloop.loop_();
     while !shared.should_stop.load(Ordering::SeqCst) {
         loop_ref.iterate(std::time::Duration::from_millis(16));
+
+        // Re-advertise the format when the captured content changed size
+        let pending_size = shared.pending_size.lock().unwrap().take();
+        if let Some((width, height)) = pending_size {
+            let resized = StreamConfig {
+                width,
+                height,
+                ..config.clone()
+            };
+            match build_format_params(&resized) {
+                Ok(bytes) => {
+                    let mut params: Vec<&pipewire::spa::pod::Pod> = bytes
+                        .iter()
+                        .map(|bytes| pipewire::spa::pod::Pod::from_bytes(bytes).unwrap())
+                        .collect();
+                    if let Err(e) = stream.update_params(&mut params) {
+                        tracing::error!("Failed to renegotiate stream size: {}", e);
+                    }
+                }
+                Err(e) => tracing::error!("Failed to build resized format params: {}", e),
+            }
+        }
     }
 
     tracing::debug!("PipeWire thread shutting down");
+    // Consumers see the stream go away (e.g. the shared window was closed)
+    if let Err(e) = stream.disconnect() {
+        tracing::warn!("Failed to disconnect PipeWire stream: {}", e);
+    }
+    *shared.stream_ptr.lock().unwrap() = None;
     Ok(())
 }
 
@@ -749,10 +932,25 @@ fn send_buffer_params(
         ),
     );
 
+    // Create Meta param for the cursor (metadata cursor mode), with room for the bitmap
+    let meta_cursor_param = pod::object!(
+        SpaTypes::ObjectParamMeta,
+        ParamType::Meta,
+        Property::new(
+            SPA_PARAM_META_type,
+            pod::Value::Id(pipewire::spa::utils::Id(SPA_META_Cursor))
+        ),
+        Property::new(
+            SPA_PARAM_META_size,
+            pod::Value::Int(cursor_meta_size() as i32)
+        ),
+    );
+
     // Serialize params
     let mut buf1 = Vec::new();
     let mut buf2 = Vec::new();
     let mut buf3 = Vec::new();
+    let mut buf4 = Vec::new();
     PodSerializer::serialize(Cursor::new(&mut buf1), &pod::Value::Object(buffers_param)).map_err(
         |e| PipeWireError::InitFailed(format!("Failed to serialize buffers param: {:?}", e)),
     )?;
@@ -771,13 +969,22 @@ fn send_buffer_params(
         PipeWireError::InitFailed(format!("Failed to serialize meta damage param: {:?}", e))
     })?;
 
+    PodSerializer::serialize(
+        Cursor::new(&mut buf4),
+        &pod::Value::Object(meta_cursor_param),
+    )
+    .map_err(|e| {
+        PipeWireError::InitFailed(format!("Failed to serialize meta cursor param: {:?}", e))
+    })?;
+
     let pod1 = pipewire::spa::pod::Pod::from_bytes(&buf1).unwrap();
     let pod2 = pipewire::spa::pod::Pod::from_bytes(&buf2).unwrap();
     let pod3 = pipewire::spa::pod::Pod::from_bytes(&buf3).unwrap();
-    let mut params = [pod1, pod2, pod3];
+    let pod4 = pipewire::spa::pod::Pod::from_bytes(&buf4).unwrap();
+    let mut params = [pod1, pod2, pod3, pod4];
 
     tracing::debug!(
-        "Updating stream params with Buffers (plane_count={}), Meta Header, and Meta VideoDamage",
+        "Updating stream params with Buffers (plane_count={}), Meta Header, Meta VideoDamage and Meta Cursor",
         plane_count
     );
 
@@ -788,6 +995,75 @@ fn send_buffer_params(
     Ok(())
 }
 
+/// Fill the buffer's `SPA_META_Cursor`, if the consumer negotiated it.
+///
+/// The bitmap is only attached when it changed since the last buffer;
+/// otherwise `bitmap_offset` is 0 and consumers keep the previous image.
+///
+/// # Safety
+/// `spa_buffer` must be a valid buffer owned by the stream.
+unsafe fn write_cursor_meta(
+    spa_buffer: *mut pipewire::spa::sys::spa_buffer,
+    state: &mut CursorState,
+) {
+    use pipewire::spa::sys::*;
+
+    let meta = spa_buffer_find_meta_data(
+        spa_buffer,
+        SPA_META_Cursor,
+        std::mem::size_of::<spa_meta_cursor>(),
+    ) as *mut spa_meta_cursor;
+    if meta.is_null() {
+        return;
+    }
+
+    let metadata = &state.metadata;
+    (*meta).id = 1;
+    (*meta).flags = 0;
+    (*meta).position.x = metadata.position.0;
+    (*meta).position.y = metadata.position.1;
+    (*meta).hotspot.x = metadata.hotspot.0;
+    (*meta).hotspot.y = metadata.hotspot.1;
+    (*meta).bitmap_offset = 0;
+
+    if !state.bitmap_dirty {
+        return;
+    }
+
+    // A hidden cursor is sent as an empty bitmap
+    let (width, height, pixels) = match &metadata.bitmap {
+        Some(bitmap)
+            if bitmap.width <= CURSOR_META_MAX_SIZE && bitmap.height <= CURSOR_META_MAX_SIZE =>
+        {
+            (bitmap.width, bitmap.height, bitmap.pixels.as_slice())
+        }
+        Some(bitmap) => {
+            tracing::debug!(
+                "Cursor bitmap {}x{} too large for metadata, skipping",
+                bitmap.width,
+                bitmap.height
+            );
+            (0, 0, &[][..])
+        }
+        None => (0, 0, &[][..]),
+    };
+
+    let bitmap_offset = std::mem::size_of::<spa_meta_cursor>();
+    (*meta).bitmap_offset = bitmap_offset as u32;
+    let bitmap = (meta as *mut u8).add(bitmap_offset) as *mut spa_meta_bitmap;
+    (*bitmap).format = SPA_VIDEO_FORMAT_BGRA;
+    (*bitmap).size.width = width;
+    (*bitmap).size.height = height;
+    (*bitmap).stride = (width * 4) as i32;
+    (*bitmap).offset = std::mem::size_of::<spa_meta_bitmap>() as u32;
+
+    let len = pixels.len().min((width * height * 4) as usize);
+    let dst = (bitmap as *mut u8).add((*bitmap).offset as usize);
+    std::ptr::copy_nonoverlapping(pixels.as_ptr(), dst, len);
+
+    state.bitmap_dirty = false;
+}
+
 /// Build format parameters based on backend capabilities.
 fn build_format_params(config: &StreamConfig) -> Result<Vec<Vec<u8>>, PipeWireError> {
     use pipewire::spa::param::format::{FormatProperties, MediaSubtype, MediaType};
@@ -1,11 +1,15 @@
 //! D-Bus service implementation for `org.otto.Settings`.
 //!
-//! Exposes compositor settings like theme color scheme to the portal backend.
+//! Exposes compositor settings like theme color scheme to the portal backend,
+//! and lets settings clients read and change them. Changes are written to the
+//! config file and applied by the compositor when it reloads it, so they show
+//! up within a moment, as if the file had been edited by hand. The interface
+//! is documented in `docs/developer/settings-dbus.md`.
 
 use tracing::info;
-use zbus::{interface, Connection};
+use zbus::{fdo, interface, Connection};
 
-use crate::config::Config;
+use crate::config::{Config, DockPosition};
 use crate::theme::ThemeScheme;
 
 /// The main Settings D-Bus interface.
@@ -21,11 +25,25 @@ impl SettingsInterface {
     /// - 0: No preference
     /// - 1: Prefer dark appearance
     /// - 2: Prefer light appearance
+    ///
+    /// An `Auto` scheme reports the one it currently shows.
     async fn get_color_scheme(&self) -> u32 {
-        Config::with(|config| match config.theme_scheme {
+        match crate::theme::active_scheme() {
             ThemeScheme::Dark => 1,
-            ThemeScheme::Light => 2,
-        })
+            _ => 2,
+        }
+    }
+
+    /// Returns the accent color as RGB, each channel from 0.0 to 1.0, as the
+    /// XDG settings portal `accent-color`.
+    async fn get_accent_color(&self) -> (f64, f64, f64) {
+        let color = crate::theme::accent_color().c4f();
+        (color.r as f64, color.g as f64, color.b as f64)
+    }
+
+    /// Returns the path of the theme file, empty when none is set.
+    async fn get_theme(&self) -> String {
+        Config::with(|config| config.theme_file.clone().unwrap_or_default())
     }
 
     /// Returns the configured icon theme name (e.g. "Adwaita", "Papirus").
@@ -34,6 +52,184 @@ impl SettingsInterface {
     async fn get_icon_theme(&self) -> String {
         Config::with(|config| config.icon_theme.clone().unwrap_or_default())
     }
+
+    /// Returns the global screen scale.
+    async fn get_screen_scale(&self) -> f64 {
+        Config::with(|config| config.screen_scale)
+    }
+
+    /// Returns the path of the wallpaper, empty when none is set.
+    async fn get_background_image(&self) -> String {
+        Config::with(|config| config.background_image.clone())
+    }
+
+    /// Returns whether tap-to-click is enabled on touchpads.
+    async fn get_tap_to_click(&self) -> bool {
+        Config::with(|config| config.input.tap_enabled)
+    }
+
+    /// Returns whether natural scrolling is enabled on touchpads.
+    async fn get_natural_scroll(&self) -> bool {
+        Config::with(|config| config.input.touchpad_natural_scroll_enabled)
+    }
+
+    /// Returns the pointer acceleration speed, from -1.0 to 1.0.
+    async fn get_pointer_accel_speed(&self) -> f64 {
+        Config::with(|config| config.input.pointer_accel_speed)
+    }
+
+    /// Returns the dock size multiplier, from 0.5 to 2.0.
+    async fn get_dock_size(&self) -> f64 {
+        Config::with(|config| config.dock.size)
+    }
+
+    /// Returns whether dock icons magnify under the pointer.
+    async fn get_dock_magnification(&self) -> bool {
+        Config::with(|config| config.dock.magnification)
+    }
+
+    /// Returns whether the dock hides when the pointer leaves it.
+    async fn get_dock_autohide(&self) -> bool {
+        Config::with(|config| config.dock.autohide)
+    }
+
+    /// Returns the screen edge of the dock: "bottom", "left" or "right".
+    async fn get_dock_position(&self) -> String {
+        Config::with(|config| dock_position_name(config.dock.position))
+    }
+
+    /// Sets the color scheme, using the values of `get_color_scheme`
+    /// (1: dark, 2: light).
+    async fn set_color_scheme(&self, scheme: u32) -> fdo::Result<()> {
+        let scheme = match scheme {
+            1 => ThemeScheme::Dark,
+            2 => ThemeScheme::Light,
+            other => {
+                return Err(fdo::Error::InvalidArgs(format!(
+                    "unsupported color scheme {other}"
+                )))
+            }
+        };
+        save(|config| config.theme_scheme = scheme)
+    }
+
+    /// Sets the accent color to a name ("blue", "purple", …) or a "#RRGGBB"
+    /// hex color. A theme file that sets its own accent keeps precedence.
+    async fn set_accent_color(&self, color: String) -> fdo::Result<()> {
+        let known = crate::theme::named_accent(&crate::theme::theme_colors(), &color)
+            .or_else(|| crate::theme::parse_color(&color))
+            .is_some();
+        if !known {
+            return Err(fdo::Error::InvalidArgs(format!(
+                "unsupported accent color {color}"
+            )));
+        }
+        save(|config| config.accent_color = color)
+    }
+
+    /// Sets the theme file to the one at `path`; an empty path goes back to
+    /// the built-in theme.
+    async fn set_theme(&self, path: String) -> fdo::Result<()> {
+        if path.is_empty() {
+            return save(|config| config.theme_file = None);
+        }
+        // Resolve `~` and relative paths as the compositor will load them
+        let file = Config::resolve_theme_file(&path)
+            .ok_or_else(|| fdo::Error::InvalidArgs(format!("cannot resolve theme {path}")))?;
+        let contents = std::fs::read_to_string(&file)
+            .map_err(|e| fdo::Error::InvalidArgs(format!("cannot read theme {path}: {e}")))?;
+        crate::theme::ThemeFile::parse(&file, &contents)
+            .map_err(|e| fdo::Error::InvalidArgs(format!("invalid theme {path}: {e}")))?;
+        save(|config| config.theme_file = Some(path))
+    }
+
+    /// Sets the global screen scale of outputs without a display profile scale.
+    async fn set_screen_scale(&self, scale: f64) -> fdo::Result<()> {
+        if !(scale.is_finite() && scale > 0.0) {
+            return Err(fdo::Error::InvalidArgs(format!("invalid scale {scale}")));
+        }
+        save(|config| config.screen_scale = scale)
+    }
+
+    /// Enables or disables tap-to-click on touchpads.
+    async fn set_tap_to_click(&self, enabled: bool) -> fdo::Result<()> {
+        save(|config| config.input.tap_enabled = enabled)
+    }
+
+    /// Enables or disables natural scrolling on touchpads.
+    async fn set_natural_scroll(&self, enabled: bool) -> fdo::Result<()> {
+        save(|config| config.input.touchpad_natural_scroll_enabled = enabled)
+    }
+
+    /// Sets the pointer acceleration speed, from -1.0 (slowest) to 1.0 (fastest).
+    async fn set_pointer_accel_speed(&self, speed: f64) -> fdo::Result<()> {
+        if !(-1.0..=1.0).contains(&speed) {
+            return Err(fdo::Error::InvalidArgs(format!(
+                "pointer acceleration speed {speed} out of range"
+            )));
+        }
+        save(|config| config.input.pointer_accel_speed = speed)
+    }
+
+    /// Sets the wallpaper to the image at `path`; an empty path removes it
+    /// and shows the background color.
+    async fn set_background_image(&self, path: String) -> fdo::Result<()> {
+        save_background_image(path)
+    }
+
+    /// Sets the dock size multiplier, from 0.5 to 2.0.
+    async fn set_dock_size(&self, size: f64) -> fdo::Result<()> {
+        if !(0.5..=2.0).contains(&size) {
+            return Err(fdo::Error::InvalidArgs(format!(
+                "dock size {size} out of range"
+            )));
+        }
+        save(|config| config.dock.size = size)
+    }
+
+    /// Enables or disables dock icon magnification.
+    async fn set_dock_magnification(&self, enabled: bool) -> fdo::Result<()> {
+        save(|config| config.dock.magnification = enabled)
+    }
+
+    /// Enables or disables hiding the dock when the pointer leaves it.
+    async fn set_dock_autohide(&self, enabled: bool) -> fdo::Result<()> {
+        save(|config| config.dock.autohide = enabled)
+    }
+
+    /// Moves the dock to the "bottom", "left" or "right" screen edge.
+    async fn set_dock_position(&self, position: String) -> fdo::Result<()> {
+        let position = parse_dock_position(&position).ok_or_else(|| {
+            fdo::Error::InvalidArgs(format!("unsupported dock position {position}"))
+        })?;
+        save(|config| config.dock.position = position)
+    }
+}
+
+/// Name of `position` on the bus: its serde name, as written in the config file.
+fn dock_position_name(position: DockPosition) -> String {
+    serde_json::to_value(position)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Parse a dock position name from the bus, as serde reads it from the config file.
+fn parse_dock_position(name: &str) -> Option<DockPosition> {
+    serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+}
+
+/// Saves `path` as the wallpaper; shared with `org.otto.Compositor.SetWallpaper`.
+pub(crate) fn save_background_image(path: String) -> fdo::Result<()> {
+    if !path.is_empty() && !std::path::Path::new(&path).is_file() {
+        return Err(fdo::Error::InvalidArgs(format!("no image at {path}")));
+    }
+    save(|config| config.background_image = path)
+}
+
+/// Saves the active config with `f` applied, reporting failures to the caller.
+fn save(f: impl FnOnce(&mut Config)) -> fdo::Result<()> {
+    Config::save_with(f).map_err(|e| fdo::Error::Failed(format!("failed to save config: {e}")))
 }
 
 /// Registers the Settings interface on the existing D-Bus connection.
@@ -51,3 +247,24 @@ pub async fn register_settings_interface(connection: &Connection) -> zbus::Resul
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dock_position_names_round_trip() {
+        for position in [
+            DockPosition::Bottom,
+            DockPosition::Left,
+            DockPosition::Right,
+        ] {
+            assert_eq!(
+                parse_dock_position(&dock_position_name(position)),
+                Some(position)
+            );
+        }
+        assert_eq!(dock_position_name(DockPosition::Left), "left");
+        assert_eq!(parse_dock_position("top"), None);
+    }
+}
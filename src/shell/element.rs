@@ -2,8 +2,8 @@ use std::{
     borrow::Cow,
     fs,
     sync::{
-        atomic::{AtomicBool, AtomicUsize},
-        Arc, OnceLock,
+        atomic::{AtomicBool, AtomicU32, AtomicUsize},
+        Arc, Mutex, OnceLock,
     },
     time::Duration,
 };
@@ -23,7 +23,11 @@ use smithay::{
     output::Output,
     reexports::{
         wayland_protocols::{
-            wp::presentation_time::server::wp_presentation_feedback,
+            wp::{
+                content_type::v1::server::wp_content_type_v1,
+                presentation_time::server::wp_presentation_feedback,
+                tearing_control::v1::server::wp_tearing_control_v1,
+            },
             xdg::shell::server::xdg_toplevel,
         },
         wayland_server::{backend::ObjectId, protocol::wl_surface::WlSurface, Resource},
@@ -32,14 +36,19 @@ use smithay::{
     utils::{user_data::UserDataMap, IsAlive, Logical, Physical, Point, Rectangle, Scale},
     wayland::{
         compositor::SurfaceData as WlSurfaceData,
+        content_type::ContentTypeSurfaceCachedState,
         dmabuf::DmabufFeedback,
         seat::WaylandFocus,
         shell::xdg::{ToplevelSurface, XdgToplevelSurfaceData},
+        tearing_control::TearingControlSurfaceCachedState,
     },
 };
 use wayland_server::DisplayHandle;
 
-use crate::{focus::PointerFocusTarget, state::Backend};
+use crate::{
+    config::window_rules::ContentType, focus::PointerFocusTarget, state::Backend,
+    workspaces::HomeOutput,
+};
 
 #[derive(Debug, Clone)]
 pub struct WindowElement(pub Arc<WindowElementInner>);
@@ -50,11 +59,21 @@ pub struct WindowElementInner {
     pub is_maximized: AtomicBool,
     pub is_minimized: AtomicBool,
     pub is_fullscreen: AtomicBool,
+    /// Excluded from tiling and snapping layouts (set by window rules)
+    pub is_floating: AtomicBool,
+    /// Stacked above normal windows (session-only toggle)
+    pub is_always_on_top: AtomicBool,
+    /// Opacity set by a window rule; not dimmed when inactive
+    pub has_rule_opacity: AtomicBool,
+    /// xkb layout index last active in this window (per-window layouts)
+    pub keyboard_layout: AtomicU32,
     pub app_id: String,
     pub base_layer: Layer,
     pub mirror_layer: Layer,
     pub workspace_index: AtomicUsize,
     pub fullscreen_workspace_index: AtomicUsize,
+    /// Output the window was on before it was unplugged
+    pub home_output: Mutex<Option<HomeOutput>>,
     /// Cached stable ID derived from the wl_surface on first call.
     /// Survives after the wl_surface is destroyed (e.g. on window close).
     cached_id: OnceLock<ObjectId>,
@@ -73,8 +92,13 @@ impl WindowElement {
             is_maximized: AtomicBool::new(false),
             is_minimized: AtomicBool::new(false),
             is_fullscreen: AtomicBool::new(false),
+            is_floating: AtomicBool::new(false),
+            is_always_on_top: AtomicBool::new(false),
+            has_rule_opacity: AtomicBool::new(false),
+            keyboard_layout: AtomicU32::new(0),
             workspace_index: AtomicUsize::new(0),
             fullscreen_workspace_index: AtomicUsize::new(0),
+            home_output: Mutex::new(None),
             app_id: "".to_string(),
             base_layer,
             mirror_layer,
@@ -248,9 +272,29 @@ impl WindowElement {
         }
     }
 
+    /// App id from the security context of a sandboxed client, see
+    /// [`crate::state::ClientState::sandbox_app_id`]
+    pub fn sandbox_app_id(&self, display_handle: &DisplayHandle) -> Option<String> {
+        let surface = self.wl_surface()?;
+        let client = display_handle.get_client(surface.id()).ok()?;
+        crate::state::sandbox_app_id(&client)
+    }
+
+    /// The app id policy decisions such as window rules rely on: the sandbox
+    /// app id for sandboxed clients, which they cannot spoof, else the
+    /// client's own
+    pub fn trusted_app_id(&self, display_handle: &DisplayHandle) -> String {
+        self.sandbox_app_id(display_handle)
+            .unwrap_or_else(|| self.xdg_app_id())
+    }
+
     /// Get the app_id to display in dock/app switcher
-    /// Uses PID resolution as fallback when XDG app_id is missing
+    /// Uses the sandbox app id for sandboxed clients, and PID resolution as
+    /// fallback when XDG app_id is missing
     pub fn display_app_id(&self, display_handle: &DisplayHandle) -> String {
+        if let Some(sandbox_app_id) = self.sandbox_app_id(display_handle) {
+            return sandbox_app_id;
+        }
         let raw_app_id = self.xdg_app_id();
 
         // If we have a valid XDG app_id, use it
@@ -364,6 +408,62 @@ impl WindowElement {
             .is_maximized
             .store(is_maximized, std::sync::atomic::Ordering::Relaxed);
     }
+
+    pub fn is_floating(&self) -> bool {
+        self.0
+            .is_floating
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    pub fn set_is_floating(&self, is_floating: bool) {
+        self.0
+            .is_floating
+            .store(is_floating, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn has_rule_opacity(&self) -> bool {
+        self.0
+            .has_rule_opacity
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    pub fn set_has_rule_opacity(&self, has_rule_opacity: bool) {
+        self.0
+            .has_rule_opacity
+            .store(has_rule_opacity, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn home_output(&self) -> Option<HomeOutput> {
+        self.0.home_output.lock().unwrap().clone()
+    }
+
+    pub fn set_home_output(&self, home_output: Option<HomeOutput>) {
+        *self.0.home_output.lock().unwrap() = home_output;
+    }
+
+    pub fn is_always_on_top(&self) -> bool {
+        self.0
+            .is_always_on_top
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    pub fn set_is_always_on_top(&self, always_on_top: bool) {
+        self.0
+            .is_always_on_top
+            .store(always_on_top, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn keyboard_layout(&self) -> u32 {
+        self.0
+            .keyboard_layout
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    pub fn set_keyboard_layout(&self, layout: u32) {
+        self.0
+            .keyboard_layout
+            .store(layout, std::sync::atomic::Ordering::Relaxed);
+    }
     pub fn set_workspace(&self, index: usize) {
         self.0
             .workspace_index
@@ -405,6 +505,42 @@ impl WindowElement {
             .unwrap_or(false)
     }
 
+    /// Whether the client asked for tearing (async) presentation through
+    /// `wp_tearing_control_v1`
+    pub fn wants_tearing(&self) -> bool {
+        self.wl_surface()
+            .map(|surface| {
+                smithay::wayland::compositor::with_states(&surface, |states| {
+                    let mut cached = states
+                        .cached_state
+                        .get::<TearingControlSurfaceCachedState>();
+                    matches!(
+                        cached.current().presentation_hint(),
+                        wp_tearing_control_v1::PresentationHint::Async
+                    )
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    /// Kind of content the client declared for the window through
+    /// `wp_content_type_v1`; `None` when it declared nothing
+    pub fn content_type(&self) -> ContentType {
+        self.wl_surface()
+            .map(|surface| {
+                smithay::wayland::compositor::with_states(&surface, |states| {
+                    let mut cached = states.cached_state.get::<ContentTypeSurfaceCachedState>();
+                    match cached.current().content_type() {
+                        wp_content_type_v1::Type::Photo => ContentType::Photo,
+                        wp_content_type_v1::Type::Video => ContentType::Video,
+                        wp_content_type_v1::Type::Game => ContentType::Game,
+                        _ => ContentType::None,
+                    }
+                })
+            })
+            .unwrap_or_default()
+    }
+
     pub fn xdg_title(&self) -> String {
         match self.underlying_surface() {
             WindowSurface::Wayland(_) => self
@@ -80,8 +80,8 @@ impl<B: Backend> PointerGrab<Otto<B>> for PointerMoveSurfaceGrab<B> {
         }
 
         // While Ctrl is held, preview the snap zone the pointer is over;
-        // release applies it (see `button`).
-        if state.current_modifiers.ctrl {
+        // release applies it (see `button`). Floating windows don't snap.
+        if state.current_modifiers.ctrl && !self.window.is_floating() {
             if let Some(output) = state
                 .workspaces
                 .outputs_for_element(&self.window)
@@ -36,7 +36,8 @@ use smithay::{
 };
 
 use crate::{
-    state::{Backend, Otto},
+    config::DisplayProfile,
+    state::{fractional_scale_handler::send_preferred_scale, Backend, Otto},
     workspaces::Workspaces,
     ClientState,
 };
@@ -44,6 +45,7 @@ use crate::{
 mod element;
 mod grabs;
 mod layer;
+mod single_pixel;
 pub(crate) mod ssd;
 #[cfg(feature = "xwayland")]
 mod x11;
@@ -52,6 +54,7 @@ mod xdg;
 pub use self::element::*;
 pub use self::grabs::*;
 pub use self::layer::*;
+pub use self::single_pixel::*;
 
 // the surface size is either output size
 // or the current workspace size
@@ -141,7 +144,10 @@ impl<BackendData: Backend> CompositorHandler for Otto<BackendData> {
 
     fn commit(&mut self, surface: &WlSurface) {
         on_commit_buffer_handler::<Self>(surface);
-        self.backend_data.early_import(surface);
+        // Single-pixel buffers are drawn as a solid color, nothing to import
+        if !has_single_pixel_buffer(surface) {
+            self.backend_data.early_import(surface);
+        }
 
         let sync = is_sync_subsurface(surface);
         let surface_id = surface.id();
@@ -205,10 +211,8 @@ impl<BackendData: Backend> CompositorHandler for Otto<BackendData> {
                         self.update_window_view(&window);
 
                         // Update foreign toplevel list only if title or app_id actually changed
-                        if let Some(handle) = root_id
-                            .or(Some(surface_id))
-                            .and_then(|id| self.foreign_toplevels.get(&id))
-                        {
+                        let window_id = root_id.unwrap_or(surface_id);
+                        if let Some(handle) = self.foreign_toplevels.get(&window_id) {
                             let title = window.xdg_title();
                             let app_id = window.xdg_app_id();
 
@@ -228,6 +232,8 @@ impl<BackendData: Backend> CompositorHandler for Otto<BackendData> {
                                 handle.send_done();
                             }
                         }
+                        // A commit also applies maximize and fullscreen changes
+                        self.refresh_foreign_toplevel(&window_id);
                     }
                 }
             }
@@ -286,12 +292,12 @@ impl<BackendData: Backend> CompositorHandler for Otto<BackendData> {
 }
 
 impl<BackendData: Backend> Otto<BackendData> {
-    fn update_layer_shell_surface(
+    pub(crate) fn update_layer_shell_surface(
         &mut self,
         surface_id: &smithay::reexports::wayland_server::backend::ObjectId,
     ) {
         // Extract needed data first to avoid borrow conflicts
-        let (geometry, wl_surface) = {
+        let (geometry, output_geo, wl_surface, scale_factor) = {
             let Some(layer_shell_surf) = self.layer_surfaces.get(surface_id) else {
                 return;
             };
@@ -303,11 +309,14 @@ impl<BackendData: Backend> Otto<BackendData> {
             let geometry = layer_shell_surf.compute_geometry(output_geo);
             let wl_surface = layer_shell_surf.layer_surface().wl_surface().clone();
 
-            (geometry, wl_surface)
+            (
+                geometry,
+                output_geo,
+                wl_surface,
+                output.current_scale().fractional_scale(),
+            )
         };
 
-        let scale_factor = crate::config::Config::with(|c| c.screen_scale);
-
         // Handle popups for this layer shell surface (e.g., waybar calendar)
         let layer_position = layers::types::Point {
             x: (geometry.loc.x as f64 * scale_factor) as f32,
@@ -317,6 +326,12 @@ impl<BackendData: Backend> Otto<BackendData> {
         use smithay::desktop::PopupManager;
 
         PopupManager::popups_for_surface(&wl_surface).for_each(|(popup, popup_offset)| {
+            let popup_offset = crate::state::input_method_handler::popup_offset_in_bounds(
+                &popup,
+                popup_offset,
+                geometry.loc,
+                output_geo,
+            );
             let offset: smithay::utils::Point<f64, smithay::utils::Physical> =
                 popup_offset.to_physical_precise_round(scale_factor);
             let popup_surface = popup.wl_surface();
@@ -482,12 +497,14 @@ impl<BackendData: Backend> Otto<BackendData> {
                 let gravity = style.map(|s| s.contents_gravity).unwrap_or_default();
                 let client_owns_size = style.map(|s| s.client_owns_size).unwrap_or(false);
                 let shared_gravity = style.map(|s| s.shared_gravity.clone());
+                let translation = style.map(|s| s.translation).unwrap_or_default();
                 crate::workspaces::utils::configure_surface_layer(
                     &surface_layer,
                     wvs,
                     gravity,
                     client_owns_size,
                     shared_gravity,
+                    translation,
                 );
 
                 // Set up parent-child relationship
@@ -712,6 +729,22 @@ fn ensure_initial_configure<Backend: crate::state::Backend>(
                     .initial_configure_sent
             });
             if !initial_configure_sent {
+                // app_id and title are only known from the first commit on
+                state.apply_window_rules(&window);
+                state.retile_workspace_of(&window);
+                state.refresh_foreign_toplevel(&window.id());
+                // Let the client size its first buffer for the output it opens on
+                let output = state
+                    .workspaces
+                    .outputs_for_element(&window)
+                    .into_iter()
+                    .next()
+                    .or_else(|| state.workspaces.outputs().next().cloned());
+                if let Some(output) = output {
+                    with_states(surface, |states| {
+                        send_preferred_scale(surface, states, &output);
+                    });
+                }
                 toplevel.send_configure();
             }
         }
@@ -793,13 +826,22 @@ fn ensure_initial_configure<Backend: crate::state::Backend>(
                 .layer_for_surface(surface, WindowSurfaceType::TOPLEVEL)
                 .unwrap();
 
+            with_states(surface, |states| {
+                send_preferred_scale(surface, states, &output);
+            });
             layer.layer_surface().send_configure();
         }
     };
 }
 
-pub fn fixup_positions(workspaces: &mut Workspaces, pointer_location: Point<f64, Logical>) {
-    // fixup outputs
+/// Lay the outputs out and bring windows back onto them. Returns the windows
+/// moved back to their home output.
+pub fn fixup_positions(
+    workspaces: &mut Workspaces,
+    pointer_location: Point<f64, Logical>,
+) -> Vec<smithay::reexports::wayland_server::backend::ObjectId> {
+    // fixup outputs: displays with a configured position stay there, the
+    // others are lined up left to right after them
     let mut offset = Point::<i32, Logical>::from((0, 0));
     for output in workspaces
         .outputs()
@@ -811,9 +853,22 @@ pub fn fixup_positions(workspaces: &mut Workspaces, pointer_location: Point<f64,
             .output_geometry(&output)
             .map(|geo| geo.size)
             .unwrap_or_else(|| Size::from((0, 0)));
-        workspaces.map_output(&output, offset);
+        let location = output
+            .user_data()
+            .get::<DisplayProfile>()
+            .and_then(|profile| profile.position)
+            .map(|p| Point::from((p.x, p.y)))
+            .unwrap_or(offset);
+        workspaces.map_output(&output, location);
         layer_map_for_output(&output).arrange();
-        offset.x += size.w;
+        offset.x = offset.x.max(location.x + size.w);
+    }
+    workspaces.sync_output_docks();
+
+    // windows go back to a display that was unplugged and is here again
+    let mut restored = Vec::new();
+    for output in workspaces.outputs().cloned().collect::<Vec<_>>() {
+        restored.extend(workspaces.restore_windows_to_home_output(&output));
     }
 
     // fixup windows
@@ -844,4 +899,5 @@ pub fn fixup_positions(workspaces: &mut Workspaces, pointer_location: Point<f64,
         let (_bounds, location) = workspaces.new_window_placement_at(pointer_location);
         workspaces.map_window(window, location, false, None);
     }
+    restored
 }
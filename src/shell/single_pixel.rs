@@ -0,0 +1,69 @@
+//! `wp_single_pixel_buffer_v1`: 1x1 buffers holding a single color, used by
+//! clients for solid backgrounds and by toolkits probing for the protocol.
+//!
+//! No texture is imported for them: the surface layer draws a rect of the
+//! buffer's color, stretched to the surface size by the viewport like any
+//! other buffer. Layer opacity and corner radius (surface style) apply to it
+//! as they do to textures.
+
+use smithay::{
+    backend::renderer::utils::{RendererSurfaceState, RendererSurfaceStateUserData},
+    reexports::wayland_server::protocol::wl_surface::WlSurface,
+    wayland::{compositor::with_states, single_pixel_buffer::get_single_pixel_buffer},
+};
+
+/// Color of the single-pixel buffer attached to the surface, as
+/// unpremultiplied RGBA in 0.0 - 1.0, or `None` for any other buffer
+pub fn single_pixel_color(surface: &RendererSurfaceState) -> Option<[f32; 4]> {
+    let buffer = surface.buffer()?;
+    let pixel = get_single_pixel_buffer(buffer).ok()?;
+    Some(unpremultiply([pixel.r, pixel.g, pixel.b, pixel.a]))
+}
+
+/// Whether the surface's current buffer is a single-pixel buffer
+pub fn has_single_pixel_buffer(surface: &WlSurface) -> bool {
+    with_states(surface, |states| {
+        states
+            .data_map
+            .get::<RendererSurfaceStateUserData>()
+            .is_some_and(|data| single_pixel_color(&data.lock().unwrap()).is_some())
+    })
+}
+
+/// Convert the protocol's premultiplied 32-bit channels to unpremultiplied
+/// floats
+fn unpremultiply([r, g, b, a]: [u32; 4]) -> [f32; 4] {
+    if a == 0 {
+        return [0.0, 0.0, 0.0, 0.0];
+    }
+    let channel = |value: u32| (value as f64 / a as f64).min(1.0) as f32;
+    [
+        channel(r),
+        channel(g),
+        channel(b),
+        (a as f64 / u32::MAX as f64) as f32,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels_are_unpremultiplied() {
+        assert_eq!(
+            unpremultiply([u32::MAX, 0, u32::MAX, u32::MAX]),
+            [1.0, 0.0, 1.0, 1.0]
+        );
+        let half = u32::MAX / 2;
+        let [r, g, b, a] = unpremultiply([half, half / 2, 0, half]);
+        assert_eq!((r, b), (1.0, 0.0));
+        assert!((g - 0.5).abs() < 1e-6, "green {g}");
+        assert!((a - 0.5).abs() < 1e-6, "alpha {a}");
+    }
+
+    #[test]
+    fn transparent_pixel_is_transparent_black() {
+        assert_eq!(unpremultiply([u32::MAX, 7, 0, 0]), [0.0; 4]);
+    }
+}
@@ -27,7 +27,7 @@ use smithay::{
 };
 use tracing::{error, trace};
 
-use crate::{focus::KeyboardFocusTarget, state::Backend, Otto};
+use crate::{config::Config, focus::KeyboardFocusTarget, state::Backend, Otto};
 
 use super::{
     FullscreenSurface, PointerMoveSurfaceGrab, PointerResizeSurfaceGrab, ResizeData, ResizeState,
@@ -165,8 +165,12 @@ impl<BackendData: Backend> XwmHandler for Otto<BackendData> {
                 window.title()
             );
             window.configure(old_geo).unwrap();
-            self.workspaces
-                .map_window(&elem, old_geo.loc, false, Some(Transition::ease_out(0.3)));
+            self.workspaces.map_window(
+                &elem,
+                old_geo.loc,
+                false,
+                Some(Config::transition_or_instant(Transition::ease_out(0.3))),
+            );
         }
     }
 
@@ -225,7 +229,7 @@ impl<BackendData: Backend> XwmHandler for Otto<BackendData> {
         self.workspaces
             .move_window_to_workspace(&elem, next_workspace_index, (0, 0));
 
-        let transition = Transition::ease_in_out_quad(1.4);
+        let transition = Config::transition_or_instant(Transition::ease_in_out_quad(1.4));
         self.workspaces
             .set_current_workspace_index(next_workspace_index, Some(transition));
 
@@ -287,7 +291,7 @@ impl<BackendData: Backend> XwmHandler for Otto<BackendData> {
             .map(|v| v.unmaximised_rect.loc)
             .unwrap_or_default();
 
-        let transition = Transition::ease_in_out_quad(1.4);
+        let transition = Config::transition_or_instant(Transition::ease_in_out_quad(1.4));
 
         self.workspaces
             .move_window_to_workspace(&elem, prev_workspace, restore_loc);
@@ -323,7 +327,8 @@ impl<BackendData: Backend> XwmHandler for Otto<BackendData> {
             let position = restore_loc.to_f64().to_physical(scale);
 
             let target_workspace = self.workspaces.get_workspace_at(prev_workspace);
-            let workspace_layer = target_workspace.map(|ws| ws.windows_layer.clone());
+            let workspace_layer =
+                target_workspace.map(|ws| ws.windows_container(elem.is_always_on_top()).clone());
 
             if let Err(e) = self
                 .workspaces
@@ -435,7 +440,12 @@ impl<BackendData: Backend> XwmHandler for Otto<BackendData> {
     ) {
         match selection {
             SelectionTarget::Clipboard => {
-                if let Err(err) = request_data_device_client_selection(&self.seat, mime_type, fd) {
+                // A clipboard history entry copied again
+                if let Some(Some(entry)) = current_data_device_selection_userdata(&self.seat) {
+                    self.send_clipboard_entry(entry, mime_type, fd);
+                } else if let Err(err) =
+                    request_data_device_client_selection(&self.seat, mime_type, fd)
+                {
                     error!(
                         ?err,
                         "Failed to request current wayland clipboard for Xwayland",
@@ -458,10 +468,16 @@ impl<BackendData: Backend> XwmHandler for Otto<BackendData> {
         // TODO check, that focused windows is X11 window before doing this
         match selection {
             SelectionTarget::Clipboard => {
-                set_data_device_selection(&self.display_handle, &self.seat, mime_types, ())
+                set_data_device_selection(
+                    &self.display_handle,
+                    &self.seat,
+                    mime_types.clone(),
+                    None,
+                );
+                self.record_clipboard_selection(&mime_types);
             }
             SelectionTarget::Primary => {
-                set_primary_selection(&self.display_handle, &self.seat, mime_types, ())
+                set_primary_selection(&self.display_handle, &self.seat, mime_types, None)
             }
         }
     }
@@ -520,8 +536,12 @@ impl<BackendData: Backend> Otto<BackendData> {
             .get::<OldGeometry>()
             .unwrap()
             .save(old_geo);
-        self.workspaces
-            .map_window(&elem, geometry.loc, false, Some(Transition::ease_out(0.3)));
+        self.workspaces.map_window(
+            &elem,
+            geometry.loc,
+            false,
+            Some(Config::transition_or_instant(Transition::ease_out(0.3))),
+        );
     }
 
     /// Snap an X11 window into a tiling target rectangle (logical pixels).
@@ -558,8 +578,12 @@ impl<BackendData: Backend> Otto<BackendData> {
 
         let _ = window.set_maximized(maximize);
         let _ = window.configure(target);
-        self.workspaces
-            .map_window(&elem, target.loc, false, Some(Transition::ease_out(0.3)));
+        self.workspaces.map_window(
+            &elem,
+            target.loc,
+            false,
+            Some(Config::transition_or_instant(Transition::ease_out(0.3))),
+        );
     }
 
     pub fn unmaximize_request_x11(&mut self, window: &X11Surface) {
@@ -583,8 +607,12 @@ impl<BackendData: Backend> Otto<BackendData> {
                 window.title()
             );
             window.configure(old_geo).unwrap();
-            self.workspaces
-                .map_window(&elem, old_geo.loc, false, Some(Transition::ease_out(0.3)));
+            self.workspaces.map_window(
+                &elem,
+                old_geo.loc,
+                false,
+                Some(Config::transition_or_instant(Transition::ease_out(0.3))),
+            );
         }
     }
 
@@ -20,13 +20,14 @@ use smithay::{
         compositor::{with_states, with_surface_tree_downward, TraversalAction},
         seat::WaylandFocus,
         shell::xdg::{
-            Configure, PopupSurface, PositionerState, ToplevelSurface, XdgShellHandler,
-            XdgShellState,
+            Configure, PopupSurface, PositionerState, SurfaceCachedState, ToplevelSurface,
+            XdgShellHandler, XdgShellState,
         },
     },
 };
 
 use crate::{
+    config::Config,
     focus::KeyboardFocusTarget,
     shell::TouchResizeSurfaceGrab,
     state::{Backend, Otto},
@@ -185,7 +186,7 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
         keyboard.set_focus(self, Some(window_element.into()), Serial::from(0));
 
         // Notify foreign toplevel watchers that the new window is activated
-        self.send_foreign_toplevel_state(&surface_id, true);
+        self.refresh_foreign_toplevel(&surface_id);
     }
 
     fn toplevel_destroyed(&mut self, toplevel: ToplevelSurface) {
@@ -218,7 +219,15 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
                 }
             }
         }
+        let tiling_workspace = self
+            .workspaces
+            .get_window_for_surface(&id)
+            .cloned()
+            .and_then(|window| self.tiling_workspace_of(&window));
         let removed_surface_ids = self.workspaces.unmap_window(&id);
+        if let Some((output, index)) = tiling_workspace {
+            self.retile_workspace(&output, index);
+        }
 
         // Notify foreign toplevel list that this toplevel is closed
         if let Some(handle) = self.foreign_toplevels.remove(&id) {
@@ -627,12 +636,13 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
             }
 
             window.set_fullscreen(true, next_workspace_index);
+            self.update_window_opacity(&window);
 
             let current_workspace_index = self.workspaces.get_current_workspace_index();
 
             let id = window.id();
             if let Some(view) = self.workspaces.get_window_view(&id) {
-                let transition = Transition::ease_in_out_quad(1.4);
+                let transition = Config::transition_or_instant(Transition::ease_in_out_quad(1.4));
 
                 // Fade out layer_shell_overlay when entering fullscreen
                 self.workspaces.set_fullscreen_overlay_visibility(true);
@@ -735,6 +745,7 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
             }
             if let Some(we) = self.workspaces.get_window_for_surface(&id).cloned() {
                 we.set_fullscreen(false, 0);
+                self.update_window_opacity(&we);
                 let Some(output) = self.workspaces.outputs_for_element(&we).first().cloned() else {
                     return;
                 };
@@ -743,7 +754,8 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
                 let position = view.unmaximised_rect.loc.to_f64().to_physical(scale);
 
                 if let Some(next_workspace) = self.workspaces.get_workspace_at(we.get_workspace()) {
-                    let transition = Transition::ease_in_out_quad(1.4);
+                    let transition =
+                        Config::transition_or_instant(Transition::ease_in_out_quad(1.4));
 
                     // Get the fullscreen workspace index before switching away from it
                     let fullscreen_workspace_index = self.workspaces.get_current_workspace_index();
@@ -810,7 +822,9 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
 
                     let surface_clone = surface.clone();
                     let restored_size = view.unmaximised_rect.size;
-                    let workspace_layer = next_workspace.windows_layer.clone();
+                    let workspace_layer = next_workspace
+                        .windows_container(we.is_always_on_top())
+                        .clone();
 
                     if let Err(e) = self
                         .workspaces
@@ -885,7 +899,7 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
             // Usable area = output minus exclusive zones minus (non-autohide) dock
             let new_geometry = self.usable_zone(&output);
 
-            let transition = Transition::ease_out(0.3);
+            let transition = Config::transition_or_instant(Transition::ease_out(0.3));
             let animation = self
                 .layers_engine
                 .add_animation_from_transition(&transition, false);
@@ -936,7 +950,7 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
                 .element_geometry(&window)
                 .unwrap_or(view.unmaximised_rect);
 
-            let transition = Transition::ease_out(0.3);
+            let transition = Config::transition_or_instant(Transition::ease_out(0.3));
             let animation = self
                 .layers_engine
                 .add_animation_from_transition(&transition, false);
@@ -983,29 +997,7 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
                 return;
             };
 
-            // Ignore duplicate minimize requests (e.g. rapid clicks while the
-            // genie animation is still running).
-            if window.is_minimised() {
-                surface.send_configure();
-                return;
-            }
-
-            let Some(current_element_geometry) = self.workspaces.element_geometry(&window) else {
-                surface.send_configure();
-                return;
-            };
-
-            if let Some(mut view) = self.workspaces.get_window_view(&id) {
-                view.unmaximised_rect = current_element_geometry;
-                self.workspaces.set_window_view(&id, view);
-            }
-
-            let next_focus = self.workspaces.minimize_window(&window);
-
-            match next_focus {
-                Some(wid) => self.set_keyboard_focus_on_surface(&wid),
-                None => self.clear_keyboard_focus(),
-            }
+            self.minimize_window(&window);
         }
 
         // The protocol demands us to always reply with a configure,
@@ -1271,9 +1263,14 @@ impl<BackendData: Backend> Otto<BackendData> {
     /// Snap `window` into the given tiling `zone` on its current output, animating
     /// size and position the same way maximize does. Records the pre-snap geometry
     /// in the window view so dragging the window off later restores it.
+    /// Floating windows are not snapped.
     pub fn apply_tile(&mut self, window: &WindowElement, zone: crate::workspaces::TileZone) {
         use crate::workspaces::TileZone;
 
+        if window.is_floating() {
+            return;
+        }
+
         let Some(output) = self
             .workspaces
             .outputs_for_element(window)
@@ -1291,7 +1288,25 @@ impl<BackendData: Backend> Otto<BackendData> {
 
         // Fresh exclusive zones, then derive the target rect for this zone.
         self.recalculate_exclusive_zones(&output);
-        let target = zone.target_rect(self.usable_zone(&output));
+        let (min_size, max_size) = match window.underlying_surface() {
+            WindowSurface::Wayland(toplevel) => with_states(toplevel.wl_surface(), |states| {
+                let mut guard = states.cached_state.get::<SurfaceCachedState>();
+                let data = guard.current();
+                (data.min_size, data.max_size)
+            }),
+            #[cfg(feature = "xwayland")]
+            WindowSurface::X11(x11) => (
+                x11.min_size().unwrap_or_default(),
+                x11.max_size().unwrap_or_default(),
+            ),
+            #[cfg(not(feature = "xwayland"))]
+            _ => Default::default(),
+        };
+        let target = zone.constrain_rect(
+            zone.target_rect(self.usable_zone(&output)),
+            min_size,
+            max_size,
+        );
 
         let Some(current_geometry) = self.workspaces.element_geometry(window) else {
             return;
@@ -1321,7 +1336,7 @@ impl<BackendData: Backend> Otto<BackendData> {
                     return;
                 };
 
-                let transition = Transition::ease_out(0.3);
+                let transition = Config::transition_or_instant(Transition::ease_out(0.3));
                 let animation = self
                     .layers_engine
                     .add_animation_from_transition(&transition, false);
@@ -1332,8 +1347,7 @@ impl<BackendData: Backend> Otto<BackendData> {
                 let new_height = target.size.h as f32;
 
                 let maximize = matches!(zone, TileZone::Maximize);
-                let tiled_left = matches!(zone, TileZone::LeftHalf);
-                let tiled_right = matches!(zone, TileZone::RightHalf);
+                let (tiled_left, tiled_right, tiled_top, tiled_bottom) = zone.tiled_edges();
 
                 let s = toplevel.clone();
                 self.layers_engine.on_animation_update(
@@ -1353,8 +1367,12 @@ impl<BackendData: Backend> Otto<BackendData> {
                                 if maximize {
                                     state.states.set(xdg_toplevel::State::Maximized);
                                 } else {
-                                    state.states.set(xdg_toplevel::State::TiledTop);
-                                    state.states.set(xdg_toplevel::State::TiledBottom);
+                                    if tiled_top {
+                                        state.states.set(xdg_toplevel::State::TiledTop);
+                                    }
+                                    if tiled_bottom {
+                                        state.states.set(xdg_toplevel::State::TiledBottom);
+                                    }
                                     if tiled_left {
                                         state.states.set(xdg_toplevel::State::TiledLeft);
                                     }
@@ -1400,6 +1418,27 @@ impl<BackendData: Backend> Otto<BackendData> {
         self.apply_tile(&window, zone);
     }
 
+    /// Snap the keyboard-focused window into `zone`. Repeating a half snap
+    /// cycles the window through half, third and two-thirds widths.
+    pub fn snap_focused_window(&mut self, zone: crate::workspaces::TileZone) {
+        let Some(window) = self
+            .seat
+            .get_keyboard()
+            .and_then(|keyboard| keyboard.current_focus())
+            .and_then(|focus| match focus {
+                KeyboardFocusTarget::Window(window) => Some(window),
+                _ => None,
+            })
+        else {
+            return;
+        };
+        let current = self
+            .workspaces
+            .get_window_view(&window.id())
+            .and_then(|view| view.tiled_zone);
+        self.apply_tile(&window, zone.cycle_from(current));
+    }
+
     pub(crate) fn unconstrain_popup(&self, popup: &PopupSurface) {
         let Ok(root) = find_popup_root_surface(&PopupKind::Xdg(popup.clone())) else {
             return;
@@ -3,7 +3,7 @@ use smithay::{
     reexports::wayland_server::backend::ObjectId, utils::SERIAL_COUNTER,
 };
 
-use crate::focus::KeyboardFocusTarget;
+use crate::{config::shortcuts::WorkspaceTarget, focus::KeyboardFocusTarget, shell::WindowElement};
 
 use super::{Backend, Otto};
 
@@ -53,6 +53,19 @@ impl<BackendData: Backend> Otto<BackendData> {
             }
         }
     }
+    /// Keep the focused window above normal windows, or return it to the normal stack
+    pub fn toggle_always_on_top_focused_window(&mut self) {
+        let Some(KeyboardFocusTarget::Window(window)) = self
+            .seat
+            .get_keyboard()
+            .and_then(|keyboard| keyboard.current_focus())
+        else {
+            return;
+        };
+        let on_top = !window.is_always_on_top();
+        self.workspaces.set_window_always_on_top(&window, on_top);
+    }
+
     /// Re-run maximize_request on every currently-maximized window so that the
     /// new usable geometry (e.g. dock just became visible) is applied immediately.
     pub fn remaximize_maximized_windows(&mut self) {
@@ -85,13 +98,18 @@ impl<BackendData: Backend> Otto<BackendData> {
     pub fn close_focused_window(&mut self) {
         if let Some(keyboard) = self.seat.get_keyboard() {
             if let Some(KeyboardFocusTarget::Window(window)) = keyboard.current_focus() {
-                match window.underlying_surface() {
-                    smithay::desktop::WindowSurface::Wayland(toplevel) => toplevel.send_close(),
-                    #[cfg(feature = "xwayland")]
-                    smithay::desktop::WindowSurface::X11(surface) => {
-                        let _ = surface.close();
-                    }
-                }
+                self.close_window(&window);
+            }
+        }
+    }
+
+    /// Ask `window` to close; the client may still refuse or prompt
+    pub fn close_window(&self, window: &WindowElement) {
+        match window.underlying_surface() {
+            smithay::desktop::WindowSurface::Wayland(toplevel) => toplevel.send_close(),
+            #[cfg(feature = "xwayland")]
+            smithay::desktop::WindowSurface::X11(surface) => {
+                let _ = surface.close();
             }
         }
     }
@@ -102,6 +120,43 @@ impl<BackendData: Backend> Otto<BackendData> {
         }
     }
 
+    /// Minimize `window` into the dock, remembering its geometry for the
+    /// restore, and move keyboard focus to the next window.
+    ///
+    /// Windows that are already minimized, or in the middle of the genie
+    /// animation, are left alone.
+    pub fn minimize_window(&mut self, window: &WindowElement) {
+        if window.is_minimised() {
+            return;
+        }
+        let Some(current_element_geometry) = self.workspaces.element_geometry(window) else {
+            return;
+        };
+        let id = window.id();
+        if let Some(mut view) = self.workspaces.get_window_view(&id) {
+            view.unmaximised_rect = current_element_geometry;
+            self.workspaces.set_window_view(&id, view);
+        }
+
+        match self.workspaces.minimize_window(window) {
+            Some(wid) => self.set_keyboard_focus_on_surface(&wid),
+            None => self.clear_keyboard_focus(),
+        }
+        self.refresh_foreign_toplevel(&id);
+    }
+
+    /// Whether one of the given windows has keyboard focus.
+    pub fn has_keyboard_focus_in(&self, windows: &[WindowElement]) -> bool {
+        match self
+            .seat
+            .get_keyboard()
+            .and_then(|keyboard| keyboard.current_focus())
+        {
+            Some(KeyboardFocusTarget::Window(focused)) => windows.contains(&focused),
+            _ => false,
+        }
+    }
+
     pub fn focus_app(&mut self, app_id: &str) -> bool {
         if let Some(wid) = self.workspaces.focus_app(app_id) {
             self.set_keyboard_focus_on_surface(&wid);
@@ -142,6 +197,69 @@ impl<BackendData: Backend> Otto<BackendData> {
         self.focus_top_window_or_clear(index);
     }
 
+    /// Position of the workspace `target` refers to. A name no workspace has
+    /// yet creates a new workspace with that name.
+    fn resolve_workspace_target(&mut self, target: &WorkspaceTarget) -> usize {
+        let current = self.workspaces.get_current_workspace_index();
+        match target {
+            WorkspaceTarget::Index(index) => *index,
+            WorkspaceTarget::Next => {
+                let count = self.workspaces.with_model(|m| m.workspaces.len());
+                (current + 1).min(count.saturating_sub(1))
+            }
+            WorkspaceTarget::Previous => current.saturating_sub(1),
+            WorkspaceTarget::Name(name) => self
+                .workspaces
+                .workspace_index_by_name(name)
+                .unwrap_or_else(|| self.workspaces.create_workspace(Some(name.clone())).0),
+        }
+    }
+
+    pub fn go_to_workspace(&mut self, target: &WorkspaceTarget) {
+        let index = self.resolve_workspace_target(target);
+        self.set_current_workspace_index(index);
+    }
+
+    /// Send the focused window to another workspace, keeping its position.
+    /// Focus moves to the next window of the current workspace.
+    pub fn move_focused_window_to_workspace(&mut self, target: &WorkspaceTarget) {
+        let Some(window) = self
+            .seat
+            .get_keyboard()
+            .and_then(|keyboard| keyboard.current_focus())
+            .and_then(|focus| match focus {
+                KeyboardFocusTarget::Window(window) => Some(window),
+                _ => None,
+            })
+        else {
+            return;
+        };
+        // Fullscreen windows own their workspace
+        if window.is_fullscreen() {
+            return;
+        }
+        let index = self.resolve_workspace_target(target);
+        let current = self.workspaces.get_current_workspace_index();
+        let count = self.workspaces.with_model(|m| m.workspaces.len());
+        if index == current || index >= count {
+            return;
+        }
+        let location = self
+            .workspaces
+            .element_location(&window)
+            .unwrap_or_default();
+        let source = self.tiling_workspace_of(&window);
+        self.workspaces
+            .move_window_to_workspace(&window, index, location);
+        if let Some((output, source_index)) = source {
+            self.retile_workspace(&output, source_index);
+        }
+        self.retile_workspace_of(&window);
+        self.workspaces.update_workspace_model();
+        self.focus_top_window_or_clear(current);
+        self.refresh_foreign_toplevel(&window.id());
+    }
+
     pub fn close_expose_show_all_and_focus_top(&mut self) {
         tracing::debug!("close_expose_show_all_and_focus_top");
         let was_open = self.workspaces.get_show_all();
@@ -171,22 +289,44 @@ impl<BackendData: Backend> Otto<BackendData> {
                     "close_expose_show_all_and_focus_top: no hover, focusing current workspace top"
                 );
                 self.focus_top_window_or_clear(workspace_index);
-                // expose_set_visible animates the dock position but never updates dock.active.
-                // When a window is clicked, focus_app_with_window → set_current_workspace_index
-                // → dock.show()/hide() fixes this. For the empty-space click path we must sync
-                // the active flag here so the dock becomes interactive after expose closes.
-                if !self.workspaces.dock.is_autohide_enabled() {
-                    let is_fullscreen = self
-                        .workspaces
-                        .get_workspace_at(workspace_index)
-                        .map(|w| w.get_fullscreen_mode())
-                        .unwrap_or(false);
-                    self.workspaces.dock.set_active_flag(!is_fullscreen);
-                }
+                // For the empty-space click path no window focus fixes the dock
+                self.sync_dock_active_after_expose(workspace_index);
             }
         }
     }
 
+    /// Close expose without changing the keyboard focus, restoring the
+    /// stacking order from before it opened
+    pub fn cancel_expose(&mut self) {
+        if !self.workspaces.get_show_all() {
+            return;
+        }
+        let workspace_index = self.workspaces.get_current_workspace_index();
+        if let Some(workspace) = self.workspaces.get_workspace_at(workspace_index) {
+            workspace.window_selector_view.clear_selection();
+        }
+        self.workspaces.expose_set_visible(false);
+        self.workspaces
+            .apply_window_selector_order_to_workspace(workspace_index);
+        self.sync_dock_active_after_expose(workspace_index);
+    }
+
+    /// expose_set_visible animates the dock position but never updates
+    /// dock.active. When a window gets focused, focus_app_with_window →
+    /// set_current_workspace_index → dock.show()/hide() fixes this; otherwise
+    /// the active flag must be synced so the dock becomes interactive after
+    /// expose closes.
+    fn sync_dock_active_after_expose(&mut self, workspace_index: usize) {
+        if !self.workspaces.dock.is_autohide_enabled() {
+            let is_fullscreen = self
+                .workspaces
+                .get_workspace_at(workspace_index)
+                .map(|w| w.get_fullscreen_mode())
+                .unwrap_or(false);
+            self.workspaces.dock.set_active_flag(!is_fullscreen);
+        }
+    }
+
     pub fn expose_end_with_velocity_and_focus_top(&mut self, raw_velocity: f32) {
         tracing::debug!(
             "expose_end_with_velocity_and_focus_top: velocity={}",
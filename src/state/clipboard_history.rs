@@ -0,0 +1,528 @@
+//! Clipboard history: keeps the last text and image selections copied by
+//! clients so they can be browsed and copied again, from the picker opened
+//! by the ClipboardHistory action or over `org.otto.Compositor`.
+//!
+//! Each new clipboard selection is read once through a pipe, like a paste.
+//! Copying an entry again offers it as a compositor-owned selection; its
+//! bytes are written by [`Otto::send_clipboard_entry`] when a client pastes.
+//! The history only lives in memory.
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, Read, Write},
+    os::fd::{FromRawFd, OwnedFd},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use smithay::{
+    reexports::calloop::{generic::Generic, Interest, Mode, PostAction},
+    utils::IsAlive,
+    wayland::selection::data_device::{
+        current_data_device_selection_userdata, request_data_device_client_selection,
+        set_data_device_selection,
+    },
+};
+use tracing::{debug, warn};
+
+use crate::{
+    compositor_service::ClipboardEntryInfo,
+    config::Config,
+    workspaces::{age_label, ClipboardPickerItem},
+};
+
+use super::{Backend, Otto};
+
+/// Offered by password managers (KeePassXC, KWallet) with secrets they copy
+const SENSITIVE_MIME_TYPE: &str = "x-kde-passwordManagerHint";
+
+/// Text mime types, in order of preference; a text entry offers all of them
+const TEXT_MIME_TYPES: [&str; 5] = [
+    "text/plain;charset=utf-8",
+    "UTF8_STRING",
+    "text/plain",
+    "STRING",
+    "TEXT",
+];
+
+/// Image mime types, in order of preference
+const IMAGE_MIME_TYPES: [&str; 4] = ["image/png", "image/jpeg", "image/webp", "image/bmp"];
+
+/// Selections larger than this are not recorded
+const MAX_ENTRY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Characters of text kept in a preview
+const PREVIEW_CHARS: usize = 120;
+
+/// Content of a recorded selection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardContent {
+    Text(String),
+    Image { mime_type: String, data: Vec<u8> },
+}
+
+impl ClipboardContent {
+    /// Decode what was read for `mime_type`; `None` for blank text
+    pub fn from_bytes(mime_type: &str, data: Vec<u8>) -> Option<Self> {
+        if mime_type.starts_with("image/") {
+            return (!data.is_empty()).then(|| Self::Image {
+                mime_type: mime_type.to_string(),
+                data,
+            });
+        }
+        let text = String::from_utf8_lossy(&data).into_owned();
+        (!text.trim().is_empty()).then_some(Self::Text(text))
+    }
+
+    /// Mime types offered when the entry is copied again
+    pub fn mime_types(&self) -> Vec<String> {
+        match self {
+            Self::Text(_) => TEXT_MIME_TYPES.iter().map(|m| m.to_string()).collect(),
+            Self::Image { mime_type, .. } => vec![mime_type.clone()],
+        }
+    }
+
+    /// The mime type reported for the entry
+    pub fn mime_type(&self) -> &str {
+        match self {
+            Self::Text(_) => TEXT_MIME_TYPES[0],
+            Self::Image { mime_type, .. } => mime_type,
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        match self {
+            Self::Text(text) => text.as_bytes(),
+            Self::Image { data, .. } => data,
+        }
+    }
+
+    /// One line of text for lists; empty for images
+    pub fn preview(&self) -> String {
+        match self {
+            Self::Text(text) => {
+                let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                match line.char_indices().nth(PREVIEW_CHARS) {
+                    Some((end, _)) => format!("{}…", &line[..end]),
+                    None => line,
+                }
+            }
+            Self::Image { .. } => String::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ClipboardEntry {
+    pub id: u32,
+    pub content: ClipboardContent,
+    /// When it was last copied
+    pub timestamp: SystemTime,
+}
+
+impl ClipboardEntry {
+    /// Seconds since the Unix epoch of `timestamp`
+    pub fn unix_time(&self) -> i64 {
+        self.timestamp
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0)
+    }
+}
+
+/// Recorded selections, most recent first
+#[derive(Debug)]
+pub struct ClipboardHistory {
+    entries: VecDeque<Arc<ClipboardEntry>>,
+    next_id: u32,
+    /// Bumped on every change, to know when to signal it
+    generation: u64,
+}
+
+impl Default for ClipboardHistory {
+    fn default() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            next_id: 1,
+            generation: 0,
+        }
+    }
+}
+
+impl ClipboardHistory {
+    pub fn entries(&self) -> impl Iterator<Item = &Arc<ClipboardEntry>> {
+        self.entries.iter()
+    }
+
+    pub fn get(&self, id: u32) -> Option<Arc<ClipboardEntry>> {
+        self.entries.iter().find(|e| e.id == id).cloned()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Record `content` as the most recent entry, keeping at most `max`.
+    /// Content already in the history moves to the front and keeps its id.
+    pub fn push(&mut self, content: ClipboardContent, timestamp: SystemTime, max: usize) -> u32 {
+        let id = match self.entries.iter().position(|e| e.content == content) {
+            Some(index) => self.entries.remove(index).map(|e| e.id).unwrap_or_default(),
+            None => {
+                let id = self.next_id;
+                self.next_id = self.next_id.wrapping_add(1).max(1);
+                id
+            }
+        };
+        self.entries.push_front(Arc::new(ClipboardEntry {
+            id,
+            content,
+            timestamp,
+        }));
+        self.truncate(max);
+        self.generation += 1;
+        id
+    }
+
+    /// Move an entry to the front, as when it is copied again
+    pub fn touch(&mut self, id: u32, timestamp: SystemTime) -> Option<Arc<ClipboardEntry>> {
+        let index = self.entries.iter().position(|e| e.id == id)?;
+        let entry = self.entries.remove(index)?;
+        let entry = Arc::new(ClipboardEntry {
+            id,
+            content: entry.content.clone(),
+            timestamp,
+        });
+        self.entries.push_front(entry.clone());
+        self.generation += 1;
+        Some(entry)
+    }
+
+    pub fn remove(&mut self, id: u32) -> bool {
+        let Some(index) = self.entries.iter().position(|e| e.id == id) else {
+            return false;
+        };
+        self.entries.remove(index);
+        self.generation += 1;
+        true
+    }
+
+    pub fn clear(&mut self) {
+        if !self.entries.is_empty() {
+            self.entries.clear();
+            self.generation += 1;
+        }
+    }
+
+    /// Drop the oldest entries beyond `max`
+    pub fn truncate(&mut self, max: usize) {
+        if self.entries.len() > max {
+            self.entries.truncate(max);
+            self.generation += 1;
+        }
+    }
+}
+
+/// The mime type to record a selection offering `mime_types` with, or
+/// `None` when it is sensitive or neither text nor an image
+pub fn recorded_mime_type(mime_types: &[String]) -> Option<String> {
+    if mime_types.iter().any(|m| m == SENSITIVE_MIME_TYPE) {
+        return None;
+    }
+    TEXT_MIME_TYPES
+        .iter()
+        .chain(IMAGE_MIME_TYPES.iter())
+        .find(|preferred| mime_types.iter().any(|m| m == *preferred))
+        .map(|m| m.to_string())
+}
+
+/// A pipe with a non-blocking read end; the write end goes to the client
+fn selection_pipe() -> io::Result<(File, OwnedFd)> {
+    let mut fds = [0; 2];
+    // Safety: pipe2 fills both fds on success, and we take ownership of them
+    unsafe {
+        if libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let read = File::from_raw_fd(fds[0]);
+        let write = OwnedFd::from_raw_fd(fds[1]);
+        if libc::fcntl(fds[0], libc::F_SETFL, libc::O_NONBLOCK) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok((read, write))
+    }
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Record a new clipboard selection offering `mime_types`, unless the
+    /// history is disabled or the selection is sensitive
+    pub fn record_clipboard_selection(&mut self, mime_types: &[String]) {
+        if !Config::with(|c| c.clipboard_history_enabled) {
+            return;
+        }
+        let Some(mime_type) = recorded_mime_type(mime_types) else {
+            debug!(?mime_types, "Clipboard selection not recorded");
+            return;
+        };
+        // The new selection is only current once its request is handled
+        self.handle.insert_idle(move |otto| {
+            if let Err(err) = otto.read_clipboard_selection(mime_type) {
+                warn!(?err, "Failed to read clipboard selection");
+            }
+        });
+    }
+
+    fn read_clipboard_selection(&mut self, mime_type: String) -> io::Result<()> {
+        let (read, write) = selection_pipe()?;
+        match current_data_device_selection_userdata(&self.seat) {
+            // Copied again from the history, already recorded
+            Some(Some(_)) => return Ok(()),
+            // Owned by an X11 client
+            Some(None) => {
+                #[cfg(feature = "xwayland")]
+                if let Some(xwm) = self.xwm.as_mut() {
+                    if let Err(err) = xwm.send_selection(
+                        smithay::wayland::selection::SelectionTarget::Clipboard,
+                        mime_type.clone(),
+                        write,
+                    ) {
+                        debug!(?err, "Failed to request the X11 clipboard");
+                        return Ok(());
+                    }
+                }
+                #[cfg(not(feature = "xwayland"))]
+                return Ok(());
+            }
+            None => {
+                if let Err(err) =
+                    request_data_device_client_selection(&self.seat, mime_type.clone(), write)
+                {
+                    debug!(?err, "Clipboard selection is gone");
+                    return Ok(());
+                }
+            }
+        }
+
+        let mut data = Vec::new();
+        let mut buffer = [0; 8192];
+        self.handle
+            .insert_source(
+                Generic::new(read, Interest::READ, Mode::Level),
+                move |_, read, otto| {
+                    // Safety: we don't drop the file
+                    let read = unsafe { read.get_mut() };
+                    loop {
+                        match read.read(&mut buffer) {
+                            Ok(0) => {
+                                let data = std::mem::take(&mut data);
+                                if let Some(content) =
+                                    ClipboardContent::from_bytes(&mime_type, data)
+                                {
+                                    otto.add_clipboard_entry(content);
+                                }
+                                return Ok(PostAction::Remove);
+                            }
+                            Ok(n) if data.len() + n > MAX_ENTRY_BYTES => {
+                                debug!("Clipboard selection too large to record");
+                                return Ok(PostAction::Remove);
+                            }
+                            Ok(n) => data.extend_from_slice(&buffer[..n]),
+                            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                                return Ok(PostAction::Continue);
+                            }
+                            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                            Err(err) => {
+                                warn!(?err, "Failed to read clipboard selection");
+                                return Ok(PostAction::Remove);
+                            }
+                        }
+                    }
+                },
+            )
+            .map_err(|err| io::Error::other(err.to_string()))?;
+        Ok(())
+    }
+
+    fn add_clipboard_entry(&mut self, content: ClipboardContent) {
+        let max = Config::with(|c| c.clipboard_history_size);
+        let id = self.clipboard_history.push(content, SystemTime::now(), max);
+        debug!(id, "Clipboard selection recorded");
+    }
+
+    /// Copy a history entry again, making it the clipboard selection.
+    /// Returns `false` for an unknown id.
+    pub fn apply_clipboard_entry(&mut self, id: u32) -> bool {
+        let Some(entry) = self.clipboard_history.touch(id, SystemTime::now()) else {
+            return false;
+        };
+        let mime_types = entry.content.mime_types();
+        set_data_device_selection(
+            &self.display_handle,
+            &self.seat,
+            mime_types.clone(),
+            Some(entry),
+        );
+        #[cfg(feature = "xwayland")]
+        if let Some(xwm) = self.xwm.as_mut() {
+            if let Err(err) = xwm.new_selection(
+                smithay::wayland::selection::SelectionTarget::Clipboard,
+                Some(mime_types),
+            ) {
+                warn!(?err, "Failed to set Xwayland clipboard");
+            }
+        }
+        true
+    }
+
+    /// Write a history entry to a client pasting it
+    pub fn send_clipboard_entry(&self, entry: Arc<ClipboardEntry>, mime_type: String, fd: OwnedFd) {
+        if !entry.content.mime_types().contains(&mime_type) {
+            return;
+        }
+        // Clients may read slowly; don't block the main loop on them
+        let _ = std::thread::Builder::new()
+            .name("clipboard-send".into())
+            .spawn(move || {
+                if let Err(err) = File::from(fd).write_all(entry.content.bytes()) {
+                    debug!(?err, "Failed to send clipboard entry");
+                }
+            });
+    }
+
+    /// The history as reported over D-Bus, most recent first
+    pub fn clipboard_history_infos(&self) -> Vec<ClipboardEntryInfo> {
+        self.clipboard_history
+            .entries()
+            .map(|entry| ClipboardEntryInfo {
+                id: entry.id,
+                mime_type: entry.content.mime_type().to_string(),
+                preview: entry.content.preview(),
+                timestamp: entry.unix_time(),
+            })
+            .collect()
+    }
+
+    /// Open the picker on the current history, or close it when shown
+    pub fn toggle_clipboard_picker(&mut self) {
+        if self.workspaces.clipboard_picker.alive() {
+            self.workspaces.clipboard_picker.hide();
+            return;
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let items = self
+            .clipboard_history
+            .entries()
+            .map(|entry| ClipboardPickerItem {
+                id: entry.id,
+                label: match &entry.content {
+                    ClipboardContent::Text(_) => entry.content.preview(),
+                    ClipboardContent::Image { mime_type, data } => format!(
+                        "Image ({}, {} KB)",
+                        mime_type.trim_start_matches("image/").to_uppercase(),
+                        data.len().div_ceil(1024)
+                    ),
+                },
+                detail: age_label(now - entry.unix_time()),
+            })
+            .collect();
+        self.workspaces.show_clipboard_picker(items);
+    }
+
+    /// Copy the entry selected in the picker and close it
+    pub fn confirm_clipboard_picker(&mut self) {
+        let picker = &self.workspaces.clipboard_picker;
+        let selected = picker.current_entry();
+        picker.hide();
+        if let Some(id) = selected {
+            self.apply_clipboard_entry(id);
+        }
+    }
+
+    pub fn remove_clipboard_entry(&mut self, id: u32) -> bool {
+        self.clipboard_history.remove(id)
+    }
+
+    pub fn clear_clipboard_history(&mut self) {
+        self.clipboard_history.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn text(s: &str) -> ClipboardContent {
+        ClipboardContent::Text(s.to_string())
+    }
+
+    #[test]
+    fn push_dedupes_and_keeps_the_id() {
+        let mut history = ClipboardHistory::default();
+        let now = SystemTime::now();
+        let first = history.push(text("one"), now, 10);
+        let second = history.push(text("two"), now, 10);
+        assert_ne!(first, second);
+
+        let later = now + Duration::from_secs(5);
+        assert_eq!(history.push(text("one"), later, 10), first);
+        let ids: Vec<u32> = history.entries().map(|e| e.id).collect();
+        assert_eq!(ids, vec![first, second]);
+        assert_eq!(history.get(first).unwrap().timestamp, later);
+    }
+
+    #[test]
+    fn push_drops_the_oldest_beyond_max() {
+        let mut history = ClipboardHistory::default();
+        let now = SystemTime::now();
+        let oldest = history.push(text("a"), now, 2);
+        history.push(text("b"), now, 2);
+        history.push(text("c"), now, 2);
+        assert_eq!(history.entries().count(), 2);
+        assert!(history.get(oldest).is_none());
+    }
+
+    #[test]
+    fn touch_moves_an_entry_to_the_front() {
+        let mut history = ClipboardHistory::default();
+        let now = SystemTime::now();
+        let first = history.push(text("a"), now, 10);
+        history.push(text("b"), now, 10);
+        assert!(history.touch(first, now).is_some());
+        assert_eq!(history.entries().next().unwrap().id, first);
+        assert!(history.touch(99, now).is_none());
+    }
+
+    #[test]
+    fn sensitive_selections_are_not_recorded() {
+        let offered =
+            |types: &[&str]| -> Vec<String> { types.iter().map(|t| t.to_string()).collect() };
+        assert_eq!(
+            recorded_mime_type(&offered(&["text/plain", "text/plain;charset=utf-8"])).as_deref(),
+            Some("text/plain;charset=utf-8")
+        );
+        assert_eq!(
+            recorded_mime_type(&offered(&["image/png", "text/uri-list"])).as_deref(),
+            Some("image/png")
+        );
+        assert_eq!(
+            recorded_mime_type(&offered(&["text/plain", SENSITIVE_MIME_TYPE])),
+            None
+        );
+        assert_eq!(recorded_mime_type(&offered(&["application/x-foo"])), None);
+    }
+
+    #[test]
+    fn preview_is_one_shortened_line() {
+        assert_eq!(text("  a\n b\tc ").preview(), "a b c");
+        let long = "x".repeat(PREVIEW_CHARS + 10);
+        assert_eq!(text(&long).preview().chars().count(), PREVIEW_CHARS + 1);
+        assert_eq!(
+            ClipboardContent::from_bytes("text/plain", b" \n".to_vec()),
+            None
+        );
+    }
+}
@@ -0,0 +1,284 @@
+//! Main-loop side of the `org.otto.Compositor` D-Bus interface: answers its
+//! queries and watches the state its signals report.
+
+use smithay::reexports::wayland_server::backend::ObjectId;
+
+use crate::{
+    compositor_service::{power_values, window_handle, CompositorEvent, WindowInfo, WorkspaceInfo},
+    focus::KeyboardFocusTarget,
+    power::PowerState,
+    shell::WindowElement,
+};
+
+use super::{Backend, Otto};
+
+/// The state last reported through compositor signals
+#[derive(Debug, Default)]
+pub struct ObservedState {
+    workspace: Option<usize>,
+    focus: Option<Option<ObjectId>>,
+    outputs: Option<Vec<String>>,
+    power: Option<PowerState>,
+    clipboard_history: Option<u64>,
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    fn focused_window(&self) -> Option<WindowElement> {
+        self.seat
+            .get_keyboard()
+            .and_then(|keyboard| keyboard.current_focus())
+            .and_then(|focus| match focus {
+                KeyboardFocusTarget::Window(window) => Some(window),
+                _ => None,
+            })
+    }
+
+    /// Every window, by output and workspace in stacking order, followed by
+    /// the minimized ones. Minimized windows report the output they were on
+    /// and that output's current workspace.
+    pub fn window_infos(&self) -> Vec<WindowInfo> {
+        let focused = self.focused_window().map(|w| w.id());
+        let info = |window: &WindowElement, output: String, workspace: usize| WindowInfo {
+            id: window_handle(&window.id()),
+            app_id: window.xdg_app_id(),
+            title: window.xdg_title(),
+            output,
+            workspace,
+            focused: focused.as_ref() == Some(&window.id()),
+            minimized: window.is_minimised(),
+        };
+
+        let mut windows = Vec::new();
+        let mut outputs: Vec<_> = self.workspaces.output_workspaces.iter().collect();
+        outputs.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (output, ows) in outputs {
+            for (index, space) in ows.spaces.iter().enumerate() {
+                windows.extend(space.elements().map(|w| info(w, output.clone(), index)));
+            }
+        }
+
+        let minimized = self.workspaces.with_model(|m| m.minimized_windows.clone());
+        for (wid, _) in minimized {
+            let Some(window) = self.workspaces.get_window_for_surface(&wid) else {
+                continue;
+            };
+            let output = self.workspaces.output_for_window(window);
+            let workspace = output
+                .as_ref()
+                .and_then(|o| self.workspaces.output_workspaces.get(&o.name()))
+                .map(|ows| ows.current_workspace)
+                .unwrap_or_default();
+            let output = output.map(|o| o.name()).unwrap_or_default();
+            windows.push(info(window, output, workspace));
+        }
+        windows
+    }
+
+    /// The workspaces of the primary output
+    pub fn workspace_infos(&self) -> Vec<WorkspaceInfo> {
+        let Some(ows) = self.workspaces.primary_output_workspaces() else {
+            return Vec::new();
+        };
+        ows.workspace_views
+            .iter()
+            .zip(ows.spaces.iter())
+            .enumerate()
+            .map(|(index, (view, space))| WorkspaceInfo {
+                index,
+                name: view.get_name().unwrap_or_default(),
+                windows: space.elements().count(),
+                current: index == ows.current_workspace,
+            })
+            .collect()
+    }
+
+    /// The window with the D-Bus `handle`, see [`window_handle`]
+    pub(crate) fn window_by_handle(&self, handle: &str) -> Option<WindowElement> {
+        self.workspaces
+            .windows_map
+            .iter()
+            .find(|(id, _)| window_handle(id) == handle)
+            .map(|(_, window)| window.clone())
+    }
+
+    /// Raise and focus the window with the D-Bus `handle`, restoring it when
+    /// minimized. Returns `false` when there is no such window.
+    pub fn focus_window_by_handle(&mut self, handle: &str) -> bool {
+        let Some(window) = self.window_by_handle(handle) else {
+            return false;
+        };
+        let wid = if window.is_minimised() {
+            self.workspaces.unminimize_window(&window.id())
+        } else {
+            Some(window.id())
+        };
+        if let Some(wid) = wid {
+            self.activate_window(&wid);
+        }
+        true
+    }
+
+    /// Ask the window with the D-Bus `handle` to close. Returns `false` when
+    /// there is no such window.
+    pub fn close_window_by_handle(&mut self, handle: &str) -> bool {
+        let Some(window) = self.window_by_handle(handle) else {
+            return false;
+        };
+        self.close_window(&window);
+        true
+    }
+
+    /// Queue a signal for every change of the current workspace, the focused
+    /// window, the set of outputs, the power state and the clipboard history
+    /// since the last call.
+    /// Called once per main loop iteration.
+    pub fn publish_compositor_events(&mut self) {
+        let Some(sender) = self
+            .screenshare_manager
+            .as_ref()
+            .map(|manager| manager.event_sender.clone())
+        else {
+            return;
+        };
+        let mut events = Vec::new();
+
+        let workspace = self.workspaces.get_current_workspace_index();
+        if self.compositor_observed.workspace != Some(workspace) {
+            self.compositor_observed.workspace = Some(workspace);
+            let name = self
+                .workspaces
+                .get_workspace_at(workspace)
+                .and_then(|w| w.get_name())
+                .unwrap_or_default();
+            events.push(CompositorEvent::WorkspaceChanged {
+                index: workspace,
+                name,
+            });
+        }
+
+        let focused = self.focused_window();
+        let focus = focused.as_ref().map(|w| w.id());
+        if self.compositor_observed.focus.as_ref() != Some(&focus) {
+            self.compositor_observed.focus = Some(focus);
+            events.push(match focused {
+                Some(window) => CompositorEvent::FocusChanged {
+                    id: window_handle(&window.id()),
+                    app_id: window.xdg_app_id(),
+                    title: window.xdg_title(),
+                },
+                None => CompositorEvent::FocusChanged {
+                    id: String::new(),
+                    app_id: String::new(),
+                    title: String::new(),
+                },
+            });
+        }
+
+        let outputs: Vec<String> = self.workspaces.outputs().map(|o| o.name()).collect();
+        if self.compositor_observed.outputs.as_ref() != Some(&outputs) {
+            self.compositor_observed.outputs = Some(outputs.clone());
+            events.push(CompositorEvent::OutputsChanged { outputs });
+        }
+
+        // Without UPower there is nothing to report
+        if let Some(state) = self.power_state {
+            if self.compositor_observed.power != Some(state) {
+                self.compositor_observed.power = Some(state);
+                let (on_battery, percentage, low) = power_values(&state);
+                events.push(CompositorEvent::PowerChanged {
+                    on_battery,
+                    percentage,
+                    low,
+                });
+            }
+        }
+
+        let clipboard_history = self.clipboard_history.generation();
+        if self.compositor_observed.clipboard_history != Some(clipboard_history) {
+            self.compositor_observed.clipboard_history = Some(clipboard_history);
+            events.push(CompositorEvent::ClipboardHistoryChanged);
+        }
+
+        for event in events {
+            let _ = sender.send(event);
+        }
+    }
+}
+
+/// Mode switching and virtual outputs are DRM features; other backends
+/// report them unsupported.
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    /// The modes of `output` as `(width, height, refresh_mhz)`
+    pub fn output_mode_list(&self, output: &str) -> Result<Vec<(u32, u32, i32)>, String> {
+        #[cfg(feature = "udev")]
+        {
+            use crate::udev::UdevData;
+            if let Some(state) = (self as &dyn std::any::Any).downcast_ref::<Otto<UdevData>>() {
+                return state.output_modes(output).map(|modes| {
+                    modes
+                        .iter()
+                        .map(crate::udev::output_mode::mode_summary)
+                        .map(|(w, h, refresh, _)| (w, h, refresh))
+                        .collect()
+                });
+            }
+        }
+        let _ = output;
+        Err(format!(
+            "the {} backend cannot change display modes",
+            self.backend_data.backend_name()
+        ))
+    }
+
+    /// Switch `output` to another mode, see `Otto<UdevData>::set_output_mode`
+    pub fn change_output_mode(
+        &mut self,
+        output: &str,
+        width: u32,
+        height: u32,
+        refresh_hz: Option<f64>,
+    ) -> Result<(), String> {
+        #[cfg(feature = "udev")]
+        {
+            use crate::udev::UdevData;
+            if let Some(state) = (self as &mut dyn std::any::Any).downcast_mut::<Otto<UdevData>>() {
+                return state.set_output_mode(output, width, height, refresh_hz);
+            }
+        }
+        let _ = (output, width, height, refresh_hz);
+        Err(format!(
+            "the {} backend cannot change display modes",
+            self.backend_data.backend_name()
+        ))
+    }
+
+    /// Hand a virtual output request to the udev backend's command channel
+    pub fn request_virtual_output(&mut self, command: crate::virtual_output::VirtualOutputCommand) {
+        #[cfg(feature = "udev")]
+        {
+            use crate::udev::UdevData;
+            if let Some(state) = (self as &mut dyn std::any::Any).downcast_mut::<Otto<UdevData>>() {
+                if let Some(sender) = state.backend_data.virtual_output_commands.as_ref() {
+                    let _ = sender.send(command);
+                    return;
+                }
+            }
+        }
+        let error = format!(
+            "the {} backend has no virtual outputs",
+            self.backend_data.backend_name()
+        );
+        match command {
+            crate::virtual_output::VirtualOutputCommand::Add { response_tx, .. } => {
+                if let Some(tx) = response_tx {
+                    let _ = tx.send(Err(error));
+                }
+            }
+            crate::virtual_output::VirtualOutputCommand::Remove { response_tx, .. } => {
+                if let Some(tx) = response_tx {
+                    let _ = tx.send(Err(error));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use std::{ffi::OsString, io};
+
+use inotify::{Inotify, WatchMask};
+use smithay::{
+    input::keyboard::XkbConfig,
+    output::{Output, Scale},
+    reexports::calloop::{generic::Generic, Interest, LoopHandle, Mode, PostAction},
+};
+use tracing::{debug, info, warn};
+
+use crate::config::{config_file_candidates, differs, Config, DisplayProfile};
+use crate::theme::ThemeScheme;
+
+use super::{Backend, Otto};
+
+/// Watch the directories holding the config files and the theme file, and
+/// reload the config whenever one of them is written, created, replaced or
+/// removed.
+///
+/// Directories are watched instead of the files themselves so that editors
+/// that save by renaming a temporary file, and files created after startup,
+/// are picked up as well.
+pub fn watch_config_files<BackendData: Backend + 'static>(
+    handle: &LoopHandle<'static, Otto<BackendData>>,
+) {
+    let mut inotify = match Inotify::init() {
+        Ok(inotify) => inotify,
+        Err(err) => {
+            warn!("Config hot-reload disabled, inotify unavailable: {}", err);
+            return;
+        }
+    };
+    let mut candidates = config_file_candidates();
+    // The theme file as configured at startup; a file moved to another
+    // directory later is only picked up by the next config change
+    candidates.extend(Config::with(|c| c.theme_file_path()));
+    let file_names: Vec<OsString> = candidates
+        .iter()
+        .filter_map(|path| path.file_name().map(|name| name.to_os_string()))
+        .collect();
+    let mask = WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO | WatchMask::CREATE | WatchMask::DELETE;
+    let mut watched = Vec::new();
+    for dir in candidates.iter().filter_map(|path| path.parent()) {
+        if watched.contains(&dir) || !dir.is_dir() {
+            continue;
+        }
+        match inotify.watches().add(dir, mask) {
+            Ok(_) => {
+                debug!("Watching {} for config changes", dir.display());
+                watched.push(dir);
+            }
+            Err(err) => warn!("Failed to watch {}: {}", dir.display(), err),
+        }
+    }
+    if watched.is_empty() {
+        return;
+    }
+
+    let mut buffer = [0; 4096];
+    handle
+        .insert_source(
+            Generic::new(inotify, Interest::READ, Mode::Level),
+            move |_, inotify, otto| {
+                let mut changed = false;
+                // Safety: we don't drop the inotify instance
+                let inotify = unsafe { inotify.get_mut() };
+                loop {
+                    match inotify.read_events(&mut buffer) {
+                        Ok(events) => {
+                            let mut any = false;
+                            for event in events {
+                                any = true;
+                                changed |= event.name.is_some_and(|name| {
+                                    file_names.iter().any(|f| f == name)
+                                        || Config::with(|c| c.theme_file_path())
+                                            .is_some_and(|path| path.file_name() == Some(name))
+                                });
+                            }
+                            if !any {
+                                break;
+                            }
+                        }
+                        Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                        Err(err) => {
+                            warn!("Failed to read config watch events: {}", err);
+                            break;
+                        }
+                    }
+                }
+                if changed {
+                    otto.reload_config();
+                }
+                Ok(PostAction::Continue)
+            },
+        )
+        .expect("Failed to register config file watcher");
+}
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    /// Re-read the config files and apply the settings that can change while
+    /// running. Settings that only take effect at startup are reported.
+    pub fn reload_config(&mut self) {
+        let Some(old) = Config::reload() else {
+            return;
+        };
+        let new = Config::current();
+        info!("Config reloaded");
+
+        for field in old.restart_required_changes(&new) {
+            warn!(
+                "Config setting `{}` changed, restart required to apply",
+                field
+            );
+        }
+
+        if let Some(keyboard) = self.seat.get_keyboard() {
+            if old.keyboard_repeat_rate != new.keyboard_repeat_rate
+                || old.keyboard_repeat_delay != new.keyboard_repeat_delay
+            {
+                keyboard.change_repeat_info(new.keyboard_repeat_rate, new.keyboard_repeat_delay);
+            }
+            if old.input.xkb_layout_string() != new.input.xkb_layout_string()
+                || old.input.xkb_variant != new.input.xkb_variant
+                || old.input.xkb_options != new.input.xkb_options
+            {
+                let layout = new.input.xkb_layout_string();
+                let variant = new.input.xkb_variant.clone().unwrap_or_default();
+                let options =
+                    (!new.input.xkb_options.is_empty()).then(|| new.input.xkb_options.join(","));
+                let xkb_config = XkbConfig {
+                    layout: &layout,
+                    variant: &variant,
+                    options,
+                    ..Default::default()
+                };
+                if let Err(err) = keyboard.set_xkb_config(self, xkb_config) {
+                    warn!("Failed to apply keyboard layout: {:?}", err);
+                }
+            }
+        }
+
+        if old.screen_scale != new.screen_scale {
+            self.apply_screen_scale(new.screen_scale);
+        }
+
+        if old.hide_cursor_idle_ms != new.hide_cursor_idle_ms {
+            self.update_cursor_idle_timer();
+        }
+
+        if old.inactive_opacity != new.inactive_opacity {
+            self.update_all_window_opacities();
+        }
+
+        if old.window_corner_radius != new.window_corner_radius
+            || differs(&old.window_border, &new.window_border)
+        {
+            self.update_all_window_frames();
+        }
+
+        // The theme file is re-read on every reload: it may be what changed
+        let theme_changed = crate::theme::reload_theme();
+        let auto_changed =
+            crate::theme::update_auto_scheme() && new.theme_scheme == ThemeScheme::Auto;
+        if theme_changed
+            || auto_changed
+            || old.theme_scheme != new.theme_scheme
+            || old.accent_color != new.accent_color
+        {
+            self.apply_theme();
+        }
+
+        if differs(&old.input, &new.input) {
+            self.backend_data.reload_input_config();
+        }
+
+        if differs(&old.dock, &new.dock) {
+            self.workspaces.apply_dock_config(new.dock.clone());
+        }
+
+        if old.cursor_theme != new.cursor_theme || old.cursor_size != new.cursor_size {
+            // Named cursors, including wp_cursor_shape_v1 shapes, are loaded
+            // lazily from the new theme on their next use
+            self.cursor_manager
+                .reload(&new.cursor_theme, new.cursor_size as u8);
+            self.cursor_texture_cache.clear();
+        }
+
+        if differs(&old.night_light, &new.night_light) {
+            self.update_night_light();
+        }
+
+        if !new.clipboard_history_enabled {
+            self.clear_clipboard_history();
+        } else if new.clipboard_history_size < old.clipboard_history_size {
+            self.clipboard_history.truncate(new.clipboard_history_size);
+        }
+
+        if differs(&old.power_management, &new.power_management) {
+            self.apply_power_policy();
+        }
+
+        if old.background_image != new.background_image
+            || old.background_color != new.background_color
+        {
+            self.workspaces.apply_background_config();
+        }
+
+        self.backend_data.request_redraw();
+    }
+
+    /// Give every output without a scale of its own in `[[displays]]` the
+    /// new global `screen_scale`
+    fn apply_screen_scale(&mut self, scale: f64) {
+        let outputs: Vec<Output> = self
+            .workspaces
+            .outputs()
+            .filter(|output| {
+                output
+                    .user_data()
+                    .get::<DisplayProfile>()
+                    .and_then(|profile| profile.scale)
+                    .is_none()
+            })
+            .cloned()
+            .collect();
+        for output in outputs {
+            output.change_current_state(None, None, Some(Scale::Fractional(scale)), None);
+            if self.workspaces.primary_output() == Some(&output) {
+                if let Some(mode) = output.current_mode() {
+                    let size = output.current_transform().transform_size(mode.size);
+                    self.workspaces.set_screen_dimension(size.w, size.h, scale);
+                }
+            }
+            self.backend_data.reset_buffers(&output);
+        }
+        crate::shell::fixup_positions(&mut self.workspaces, self.pointer.current_location());
+    }
+}
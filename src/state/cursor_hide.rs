@@ -0,0 +1,88 @@
+//! Hide seat cursors while typing (`hide_cursor_when_typing`) and after a
+//! while without pointer input (`hide_cursor_idle_ms`).
+//!
+//! Each seat's cursor is hidden and shown on its own: typing on the virtual
+//! seat never hides the physical cursor. Hiding only skips drawing the
+//! cursor; its image, pointer focus, constraints and locks are untouched,
+//! and any pointer input on the seat shows it again right away.
+//!
+//! The idle timer only runs while `hide_cursor_idle_ms` is set.
+
+use std::time::Duration;
+
+use smithay::reexports::calloop::{
+    timer::{TimeoutAction, Timer},
+    LoopHandle, RegistrationToken,
+};
+
+use crate::config::Config;
+
+use super::{Backend, Otto};
+
+/// How often the idle timeout is checked while no cursor is about to go idle
+pub const CURSOR_IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Register the timer hiding idle cursors; it drops itself once
+/// `hide_cursor_idle_ms` is turned off
+pub(crate) fn insert_cursor_idle_timer<BackendData: Backend + 'static>(
+    handle: &LoopHandle<'static, Otto<BackendData>>,
+) -> RegistrationToken {
+    handle
+        .insert_source(
+            Timer::from_duration(CURSOR_IDLE_CHECK_INTERVAL),
+            |_, _, otto| match otto.hide_idle_cursors() {
+                Some(next) => TimeoutAction::ToDuration(next),
+                None => {
+                    otto.cursor_idle_timer = None;
+                    TimeoutAction::Drop
+                }
+            },
+        )
+        .expect("Failed to register cursor idle timer")
+}
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    /// A key was pressed on `seat`: hide its cursor if `hide_cursor_when_typing`
+    pub(crate) fn hide_cursor_for_typing(&mut self, seat: &str) {
+        if Config::with(|c| c.hide_cursor_when_typing) && self.cursor_manager.hide_cursor(seat) {
+            self.backend_data.request_redraw();
+        }
+    }
+
+    /// The pointer of `seat` was used: show its cursor again
+    pub(crate) fn cursor_pointer_input(&mut self, seat: &str) {
+        if self.cursor_manager.pointer_input(seat) {
+            self.backend_data.request_redraw();
+        }
+    }
+
+    /// Start or stop the idle timer after `hide_cursor_idle_ms` changed
+    pub(crate) fn update_cursor_idle_timer(&mut self) {
+        let enabled = Config::with(|c| c.hide_cursor_idle_ms) > 0;
+        match (enabled, self.cursor_idle_timer.take()) {
+            (true, None) => self.cursor_idle_timer = Some(insert_cursor_idle_timer(&self.handle)),
+            (false, Some(token)) => self.handle.remove(token),
+            (_, token) => self.cursor_idle_timer = token,
+        }
+    }
+
+    /// Hide the cursors idle for `hide_cursor_idle_ms`, returning when to
+    /// check again; `None` when idle hiding is off
+    pub(crate) fn hide_idle_cursors(&mut self) -> Option<Duration> {
+        let idle_ms = Config::with(|c| c.hide_cursor_idle_ms);
+        if idle_ms == 0 {
+            return None;
+        }
+        let (changed, next) = self
+            .cursor_manager
+            .hide_idle_cursors(Duration::from_millis(idle_ms));
+        if changed {
+            self.backend_data.request_redraw();
+            self.schedule_event_loop_dispatch();
+        }
+        Some(
+            next.unwrap_or(CURSOR_IDLE_CHECK_INTERVAL)
+                .max(Duration::from_millis(10)),
+        )
+    }
+}
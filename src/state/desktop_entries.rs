@@ -0,0 +1,206 @@
+//! Refresh app names and icons when desktop entries are installed, changed or
+//! removed.
+//!
+//! The XDG application directories, plus the flatpak and snap export
+//! directories, are watched with inotify. A package install touches many
+//! files, so events only (re)arm a short timer and the app info cache is
+//! cleared once the directories have been quiet for `REFRESH_DELAY`. The
+//! docks then reload their bookmarks and running apps through the same
+//! observer channel workspace changes use.
+
+use std::{cell::Cell, io, path::PathBuf, rc::Rc, time::Duration};
+
+use inotify::{Inotify, WatchMask};
+use smithay::reexports::calloop::{
+    generic::Generic,
+    timer::{TimeoutAction, Timer},
+    Interest, LoopHandle, Mode, PostAction, RegistrationToken,
+};
+use tracing::{debug, info, warn};
+
+use crate::{utils::Observer, workspaces::ApplicationsInfo};
+
+use super::{Backend, Otto};
+
+/// How long the application directories must be quiet before refreshing
+const REFRESH_DELAY: Duration = Duration::from_secs(1);
+
+/// Watch the application directories and refresh the app info cache when a
+/// `.desktop` file in them changes.
+pub fn watch_desktop_entries<BackendData: Backend + 'static>(
+    handle: &LoopHandle<'static, Otto<BackendData>>,
+) {
+    let mut inotify = match Inotify::init() {
+        Ok(inotify) => inotify,
+        Err(err) => {
+            warn!(
+                "Desktop entry watching disabled, inotify unavailable: {}",
+                err
+            );
+            return;
+        }
+    };
+    let mask = WatchMask::CLOSE_WRITE
+        | WatchMask::MOVED_TO
+        | WatchMask::MOVED_FROM
+        | WatchMask::CREATE
+        | WatchMask::DELETE;
+    let dirs = application_dirs(
+        std::env::var("XDG_DATA_HOME").ok().as_deref(),
+        std::env::var("HOME").ok().as_deref(),
+        std::env::var("XDG_DATA_DIRS").ok().as_deref(),
+    );
+    let mut watching = false;
+    for dir in dirs.iter().filter(|dir| dir.is_dir()) {
+        match inotify.watches().add(dir, mask) {
+            Ok(_) => {
+                debug!("Watching {} for desktop entry changes", dir.display());
+                watching = true;
+            }
+            Err(err) => warn!("Failed to watch {}: {}", dir.display(), err),
+        }
+    }
+    if !watching {
+        return;
+    }
+
+    let pending: Rc<Cell<Option<RegistrationToken>>> = Rc::new(Cell::new(None));
+    let mut buffer = [0; 4096];
+    handle
+        .insert_source(
+            Generic::new(inotify, Interest::READ, Mode::Level),
+            move |_, inotify, otto| {
+                let mut changed = false;
+                // Safety: we don't drop the inotify instance
+                let inotify = unsafe { inotify.get_mut() };
+                loop {
+                    match inotify.read_events(&mut buffer) {
+                        Ok(events) => {
+                            let mut any = false;
+                            for event in events {
+                                any = true;
+                                changed |= event.name.is_some_and(|name| {
+                                    name.to_string_lossy().ends_with(".desktop")
+                                });
+                            }
+                            if !any {
+                                break;
+                            }
+                        }
+                        Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                        Err(err) => {
+                            warn!("Failed to read desktop entry watch events: {}", err);
+                            break;
+                        }
+                    }
+                }
+                if changed {
+                    // Restart the quiet period on every burst
+                    if let Some(token) = pending.take() {
+                        otto.handle.remove(token);
+                    }
+                    let pending_timer = pending.clone();
+                    let token = otto
+                        .handle
+                        .insert_source(Timer::from_duration(REFRESH_DELAY), move |_, _, otto| {
+                            pending_timer.set(None);
+                            otto.refresh_app_info();
+                            TimeoutAction::Drop
+                        })
+                        .ok();
+                    pending.set(token);
+                }
+                Ok(PostAction::Continue)
+            },
+        )
+        .expect("Failed to register desktop entry watcher");
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Forget the loaded app info and let every dock resolve its bookmarks
+    /// and running apps again, picking up new names and icons.
+    pub fn refresh_app_info(&mut self) {
+        info!("Desktop entries changed, refreshing app info");
+        let docks: Vec<_> = self.workspaces.docks().cloned().collect();
+        let model = self.workspaces.with_model(|model| model.clone());
+        tokio::spawn(async move {
+            ApplicationsInfo::clear_cache().await;
+            for dock in docks {
+                dock.load_configured_bookmarks();
+                dock.notify(&model);
+            }
+        });
+    }
+}
+
+/// Directories holding desktop entries, most important first: the user's
+/// data dir, `$XDG_DATA_DIRS` in order, then the flatpak and snap exports
+/// when they are not listed already.
+fn application_dirs(
+    data_home: Option<&str>,
+    home: Option<&str>,
+    data_dirs: Option<&str>,
+) -> Vec<PathBuf> {
+    let data_home = data_home
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.map(|home| PathBuf::from(home).join(".local/share")));
+    let data_dirs = data_dirs
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or("/usr/local/share:/usr/share");
+
+    let mut roots: Vec<PathBuf> = data_home.clone().into_iter().collect();
+    roots.extend(
+        data_dirs
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from),
+    );
+    roots.extend(data_home.map(|dir| dir.join("flatpak/exports/share")));
+    roots.push(PathBuf::from("/var/lib/flatpak/exports/share"));
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for dir in roots.into_iter().map(|root| root.join("applications")) {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    let snap = PathBuf::from("/var/lib/snapd/desktop/applications");
+    if !dirs.contains(&snap) {
+        dirs.push(snap);
+    }
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_dir_comes_first_and_exports_are_appended_once() {
+        let dirs = application_dirs(
+            None,
+            Some("/home/u"),
+            Some("/var/lib/flatpak/exports/share:/usr/share"),
+        );
+        let expected: Vec<PathBuf> = [
+            "/home/u/.local/share/applications",
+            "/var/lib/flatpak/exports/share/applications",
+            "/usr/share/applications",
+            "/home/u/.local/share/flatpak/exports/share/applications",
+            "/var/lib/snapd/desktop/applications",
+        ]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+        assert_eq!(dirs, expected);
+    }
+
+    #[test]
+    fn data_dirs_default_when_unset() {
+        let dirs = application_dirs(Some("/data"), None, None);
+        assert_eq!(dirs[0], PathBuf::from("/data/applications"));
+        assert_eq!(dirs[1], PathBuf::from("/usr/local/share/applications"));
+        assert_eq!(dirs[2], PathBuf::from("/usr/share/applications"));
+    }
+}
@@ -16,6 +16,8 @@ pub struct ForeignToplevelHandles {
     pub wlr: Option<WlrForeignToplevelHandle>,
     /// The output this toplevel is on (for late-joining managers)
     pub output: Option<Output>,
+    /// Last (activated, minimized, maximized, fullscreen) sent
+    state: Option<(bool, bool, bool, bool)>,
 }
 
 impl ForeignToplevelHandles {
@@ -24,6 +26,7 @@ impl ForeignToplevelHandles {
             ext: Some(ext),
             wlr: Some(wlr),
             output,
+            state: None,
         }
     }
 
@@ -75,9 +78,35 @@ impl ForeignToplevelHandles {
         }
     }
 
-    /// Broadcast window state (activated, minimized, maximized, fullscreen) via wlr protocol.
+    /// Move this toplevel to `output`, sending leave/enter when it changes
+    pub fn set_output(&mut self, output: Option<Output>) {
+        if self.output == output {
+            return;
+        }
+        if let Some(old) = self.output.take() {
+            self.send_output_leave(&old);
+        }
+        if let Some(new) = &output {
+            self.send_output_enter(new);
+        }
+        self.output = output;
+    }
+
+    /// Broadcast window state (activated, minimized, maximized, fullscreen) via wlr protocol,
+    /// if it changed since the last call.
     /// The ext-foreign-toplevel-list protocol does not carry state events.
-    pub fn send_state(&self, activated: bool, minimized: bool, maximized: bool, fullscreen: bool) {
+    pub fn send_state(
+        &mut self,
+        activated: bool,
+        minimized: bool,
+        maximized: bool,
+        fullscreen: bool,
+    ) {
+        let state = (activated, minimized, maximized, fullscreen);
+        if self.state == Some(state) {
+            return;
+        }
+        self.state = Some(state);
         if let Some(wlr) = &self.wlr {
             wlr.send_state(activated, minimized, maximized, fullscreen);
         }
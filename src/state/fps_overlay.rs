@@ -0,0 +1,77 @@
+//! Debug overlay showing the FPS and a frame time graph on every output,
+//! toggled at runtime with the ToggleFpsOverlay action.
+//!
+//! The values come from `render_metrics`, so the overlay needs a build with
+//! the `metrics` feature. While shown, it is refreshed a few times per
+//! second; an output's overlay is only redrawn when its values changed.
+
+#[cfg(feature = "metrics")]
+use std::time::Duration;
+
+#[cfg(feature = "metrics")]
+use smithay::reexports::calloop::timer::{TimeoutAction, Timer};
+use tracing::info;
+#[cfg(not(feature = "metrics"))]
+use tracing::warn;
+
+#[cfg(feature = "metrics")]
+use crate::workspaces::FpsOverlayStats;
+
+use super::{Backend, Otto};
+
+/// How often the overlay values are refreshed
+#[cfg(feature = "metrics")]
+const FPS_OVERLAY_REFRESH: Duration = Duration::from_millis(250);
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Show or hide the FPS overlay on every output
+    pub fn toggle_fps_overlay(&mut self) {
+        if self.workspaces.fps_overlay.is_visible() {
+            self.workspaces.fps_overlay.hide();
+            info!("FPS overlay hidden");
+            return;
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            self.workspaces.show_fps_overlay();
+            self.update_fps_overlay();
+            info!("FPS overlay shown");
+            let _ = self.handle.insert_source(
+                Timer::from_duration(FPS_OVERLAY_REFRESH),
+                |_, _, otto| {
+                    if !otto.workspaces.fps_overlay.is_visible() {
+                        return TimeoutAction::Drop;
+                    }
+                    otto.update_fps_overlay();
+                    TimeoutAction::ToDuration(FPS_OVERLAY_REFRESH)
+                },
+            );
+        }
+        #[cfg(not(feature = "metrics"))]
+        warn!("The FPS overlay needs a build with the `metrics` feature");
+    }
+
+    /// Push the latest metrics to the overlay of every output
+    #[cfg(feature = "metrics")]
+    fn update_fps_overlay(&mut self) {
+        // Follow outputs that were plugged in or removed since the last update
+        let shown = self.workspaces.fps_overlay.outputs().count();
+        let stale = shown != self.workspaces.outputs().count()
+            || self
+                .workspaces
+                .fps_overlay
+                .outputs()
+                .any(|name| !self.workspaces.outputs().any(|o| o.name() == *name));
+        if stale {
+            self.workspaces.show_fps_overlay();
+        }
+
+        for stats in self.render_metrics.output_stats() {
+            let frame_times = self.render_metrics.recent_frame_times(&stats.output);
+            self.workspaces
+                .fps_overlay
+                .update(&stats.output, FpsOverlayStats::new(stats.fps, &frame_times));
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use smithay::{
     delegate_fractional_scale,
     desktop::utils::surface_primary_scanout_output,
-    reexports::wayland_server::Resource,
+    output::Output,
+    reexports::wayland_server::{protocol::wl_surface::WlSurface, Resource},
     wayland::{
-        compositor::{get_parent, with_states},
+        compositor::{get_parent, send_surface_state, with_states, SurfaceData},
         fractional_scale::{with_fractional_scale, FractionalScaleHandler},
     },
 };
@@ -55,11 +56,28 @@ impl<BackendData: Backend> FractionalScaleHandler for Otto<BackendData> {
                 })
                 .or_else(|| self.workspaces.outputs().next().cloned());
             if let Some(output) = primary_scanout_output {
-                with_fractional_scale(states, |fractional_scale| {
-                    fractional_scale.set_preferred_scale(output.current_scale().fractional_scale());
-                });
+                send_preferred_scale(&surface, states, &output);
             }
         });
     }
 }
+
+/// Tell `surface` how to scale its buffers for `output`.
+///
+/// `wp_fractional_scale_v1` clients get the exact scale (e.g. 1.5) and use
+/// `wp_viewporter` to present the larger buffer at the logical size. Other
+/// clients get the scale rounded up through `wl_surface.preferred_buffer_scale`,
+/// along with the output transform. Both events are only sent on change.
+pub fn send_preferred_scale(surface: &WlSurface, states: &SurfaceData, output: &Output) {
+    let scale = output.current_scale();
+    with_fractional_scale(states, |fractional_scale| {
+        fractional_scale.set_preferred_scale(scale.fractional_scale());
+    });
+    send_surface_state(
+        surface,
+        states,
+        scale.integer_scale(),
+        output.current_transform(),
+    );
+}
 delegate_fractional_scale!(@<BackendData: Backend + 'static> Otto<BackendData>);
@@ -56,6 +56,13 @@ impl GammaControlManagerState {
         true
     }
 
+    /// Whether a client controls the gamma of `output`
+    pub fn has_control_for(&self, output: &Output) -> bool {
+        self.controls
+            .keys()
+            .any(|wl_output| Output::from_resource(wl_output).as_ref() == Some(output))
+    }
+
     /// Unregister gamma control for an output
     fn unregister_control(&mut self, control: &ZwlrGammaControlV1) {
         self.controls.retain(|_, v| v != control);
@@ -81,6 +88,10 @@ impl<BackendData: Backend> GlobalDispatch<ZwlrGammaControlManagerV1, (), Otto<Ba
     ) {
         data_init.init(resource, ());
     }
+
+    fn can_view(client: Client, _global_data: &()) -> bool {
+        crate::state::is_privileged(&client)
+    }
 }
 
 impl<BackendData: Backend> Dispatch<ZwlrGammaControlManagerV1, (), Otto<BackendData>>
@@ -222,17 +233,17 @@ impl<BackendData: Backend> Dispatch<ZwlrGammaControlV1, GammaControlState, Otto<
                 }
             }
             zwlr_gamma_control_v1::Request::Destroy => {
+                // Unregister first, so night light can take the output back
+                state.gamma_control_manager.unregister_control(resource);
+
                 // Find the smithay Output and reset gamma
                 let smithay_output = Output::from_resource(&data.output);
 
                 if let Some(output) = smithay_output {
-                    // Reset gamma to neutral
-                    let _ = state.reset_gamma(&output);
+                    // Reset gamma to neutral, or to the night light
+                    state.restore_output_gamma(&output);
                     debug!("Reset gamma for output");
                 }
-
-                // Unregister
-                state.gamma_control_manager.unregister_control(resource);
             }
         }
     }
@@ -243,14 +254,14 @@ impl<BackendData: Backend> Dispatch<ZwlrGammaControlV1, GammaControlState, Otto<
         resource: &ZwlrGammaControlV1,
         data: &GammaControlState,
     ) {
+        state.gamma_control_manager.unregister_control(resource);
+
         // Client disconnected, reset gamma
         let smithay_output = Output::from_resource(&data.output);
 
         if let Some(output) = smithay_output {
-            let _ = state.reset_gamma(&output);
+            state.restore_output_gamma(&output);
             debug!("Reset gamma on client disconnect");
         }
-
-        state.gamma_control_manager.unregister_control(resource);
     }
 }
@@ -0,0 +1,83 @@
+//! Inactive window dimming: windows without keyboard focus fade to
+//! `inactive_opacity`, the focused window back to full opacity.
+//!
+//! Only the opacity of the window layer changes, so its cached image is
+//! composited with a different alpha and no window content is redrawn.
+//! Fullscreen windows and windows whose opacity is set by a window rule are
+//! never dimmed. The dock and layer-shell surfaces are not windows and are
+//! left alone.
+
+use layers::prelude::Transition;
+use smithay::wayland::seat::WaylandFocus;
+
+use crate::{config::Config, focus::KeyboardFocusTarget, shell::WindowElement};
+
+use super::{Backend, Otto};
+
+/// Duration of the fade between active and inactive opacity, in seconds
+const OPACITY_TRANSITION: f32 = 0.25;
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Fade `window` to the opacity matching its focus and fullscreen state
+    pub fn update_window_opacity(&self, window: &WindowElement) {
+        let Some(view) = self.workspaces.get_window_view(&window.id()) else {
+            return;
+        };
+        // The minimize animations drive the window layer's opacity
+        if view.is_minimizing() || window.is_minimised() {
+            return;
+        }
+        let opacity = window_opacity(
+            self.is_keyboard_focused(window),
+            window.is_fullscreen(),
+            window.has_rule_opacity(),
+            Config::with(|c| c.inactive_opacity),
+        );
+        view.window_layer
+            .set_opacity(opacity, Some(Transition::ease_out_quad(OPACITY_TRANSITION)));
+    }
+
+    /// Re-apply the opacity of every window, after a focus or config change
+    pub fn update_all_window_opacities(&self) {
+        let windows: Vec<WindowElement> = self.workspaces.spaces_elements().cloned().collect();
+        for window in windows {
+            self.update_window_opacity(&window);
+        }
+    }
+
+    pub(super) fn is_keyboard_focused(&self, window: &WindowElement) -> bool {
+        let focus = self
+            .seat
+            .get_keyboard()
+            .and_then(|keyboard| keyboard.current_focus());
+        match focus {
+            Some(KeyboardFocusTarget::Window(focused)) => {
+                focused.wl_surface() == window.wl_surface()
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Opacity of a window layer; only inactive, non-fullscreen windows without
+/// a rule opacity are dimmed
+fn window_opacity(active: bool, fullscreen: bool, rule_opacity: bool, inactive: f32) -> f32 {
+    if active || fullscreen || rule_opacity {
+        1.0
+    } else {
+        inactive.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_plain_inactive_windows_are_dimmed() {
+        assert_eq!(window_opacity(false, false, false, 0.8), 0.8);
+        assert_eq!(window_opacity(true, false, false, 0.8), 1.0);
+        assert_eq!(window_opacity(false, true, false, 0.8), 1.0);
+        assert_eq!(window_opacity(false, false, true, 0.8), 1.0);
+    }
+}
@@ -1,9 +1,9 @@
 use smithay::{
     delegate_input_method_manager, delegate_pointer_constraints,
-    desktop::{PopupKind, PopupManager},
+    desktop::{find_popup_root_surface, PopupKind, PopupManager},
     input::pointer::PointerHandle,
-    reexports::wayland_server::protocol::wl_surface::WlSurface,
-    utils::Rectangle,
+    reexports::wayland_server::{protocol::wl_surface::WlSurface, Resource},
+    utils::{Logical, Point, Rectangle},
     wayland::{
         input_method::{InputMethodHandler, PopupSurface},
         pointer_constraints::{with_pointer_constraint, PointerConstraintsHandler},
@@ -21,7 +21,13 @@ impl<BackendData: Backend> InputMethodHandler for Otto<BackendData> {
         }
     }
 
-    fn popup_repositioned(&mut self, _: PopupSurface) {}
+    fn popup_repositioned(&mut self, surface: PopupSurface) {
+        // The text cursor moved. Like dismiss_popup this runs with the
+        // input_method mutex held, so rebuild the parent view once it is released
+        let popup = PopupKind::from(surface);
+        self.handle
+            .insert_idle(move |state| state.refresh_popup_parent(&popup));
+    }
 
     fn dismiss_popup(&mut self, surface: PopupSurface) {
         if let Some(parent) = surface.get_parent().map(|parent| parent.surface.clone()) {
@@ -33,18 +39,81 @@ impl<BackendData: Backend> InputMethodHandler for Otto<BackendData> {
         // Stale grabs are cleaned up proactively in xdg::grab and in popup_destroyed.
     }
 
-    fn parent_geometry(&self, parent: &WlSurface) -> Rectangle<i32, smithay::utils::Logical> {
+    fn parent_geometry(&self, parent: &WlSurface) -> Rectangle<i32, Logical> {
         self.workspaces
             .spaces_elements()
             .find_map(|window| {
                 (window.wl_surface().as_deref() == Some(parent)).then(|| window.geometry())
             })
+            .or_else(|| {
+                // Text fields in launchers and other layer shell surfaces
+                self.layer_surfaces
+                    .get(&parent.id())
+                    .map(|layer| Rectangle::new((0, 0).into(), layer.bbox().size))
+            })
             .unwrap_or_default()
     }
 }
 
 delegate_input_method_manager!(@<BackendData: Backend + 'static> Otto<BackendData>);
 
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Rebuild the view of the window or layer surface a popup belongs to
+    fn refresh_popup_parent(&mut self, popup: &PopupKind) {
+        let Ok(root) = find_popup_root_surface(popup) else {
+            return;
+        };
+        let root_id = root.id();
+        if self.layer_surfaces.contains_key(&root_id) {
+            self.update_layer_shell_surface(&root_id);
+        } else if let Some(window) = self.workspaces.get_window_for_surface(&root_id).cloned() {
+            self.update_window_view(&window);
+        }
+        self.backend_data.request_redraw();
+    }
+}
+
+/// Offset of a popup from its parent surface. Input method popups are kept
+/// inside `bounds`; other popups are positioned by the client.
+///
+/// `parent_location` and `bounds` are global logical coordinates.
+pub(crate) fn popup_offset_in_bounds(
+    popup: &PopupKind,
+    offset: Point<i32, Logical>,
+    parent_location: Point<i32, Logical>,
+    bounds: Rectangle<i32, Logical>,
+) -> Point<i32, Logical> {
+    let PopupKind::InputMethod(ime_popup) = popup else {
+        return offset;
+    };
+    let cursor = ime_popup.text_input_rectangle();
+    let cursor = Rectangle::new(parent_location + cursor.loc, cursor.size);
+    let rect = Rectangle::new(parent_location + offset, popup.geometry().size);
+    constrain_input_method_popup(rect, cursor, bounds) - parent_location
+}
+
+/// Location for an input method popup at `popup` so it stays inside
+/// `bounds`: flipped above the text `cursor` when it would run off the
+/// bottom, and slid left when it would run off the right edge
+fn constrain_input_method_popup(
+    popup: Rectangle<i32, Logical>,
+    cursor: Rectangle<i32, Logical>,
+    bounds: Rectangle<i32, Logical>,
+) -> Point<i32, Logical> {
+    let mut loc = popup.loc;
+    if loc.y + popup.size.h > bounds.loc.y + bounds.size.h {
+        let above = cursor.loc.y - popup.size.h;
+        if above >= bounds.loc.y {
+            loc.y = above;
+        }
+    }
+    loc.x = loc
+        .x
+        .min(bounds.loc.x + bounds.size.w - popup.size.w)
+        .max(bounds.loc.x);
+    loc
+}
+
 impl<BackendData: Backend> PointerConstraintsHandler for Otto<BackendData> {
     fn cursor_position_hint(
         &mut self,
@@ -68,3 +137,45 @@ impl<BackendData: Backend> PointerConstraintsHandler for Otto<BackendData> {
     }
 }
 delegate_pointer_constraints!(@<BackendData: Backend + 'static> Otto<BackendData>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, w: i32, h: i32) -> Rectangle<i32, Logical> {
+        Rectangle::new((x, y).into(), (w, h).into())
+    }
+
+    #[test]
+    fn input_method_popup_fits_below_cursor() {
+        let bounds = rect(0, 0, 1920, 1080);
+        let cursor = rect(100, 200, 2, 20);
+        let popup = rect(100, 220, 300, 40);
+        assert_eq!(
+            constrain_input_method_popup(popup, cursor, bounds),
+            (100, 220).into()
+        );
+    }
+
+    #[test]
+    fn input_method_popup_flips_above_cursor() {
+        let bounds = rect(0, 0, 1920, 1080);
+        let cursor = rect(100, 1050, 2, 20);
+        let popup = rect(100, 1070, 300, 40);
+        assert_eq!(
+            constrain_input_method_popup(popup, cursor, bounds),
+            (100, 1010).into()
+        );
+    }
+
+    #[test]
+    fn input_method_popup_slides_left_on_right_edge() {
+        let bounds = rect(1920, 0, 1280, 800);
+        let cursor = rect(3150, 100, 2, 20);
+        let popup = rect(3150, 120, 300, 40);
+        assert_eq!(
+            constrain_input_method_popup(popup, cursor, bounds),
+            (2900, 120).into()
+        );
+    }
+}
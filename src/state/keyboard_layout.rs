@@ -0,0 +1,61 @@
+use smithay::input::keyboard::Layout;
+
+use crate::{config::Config, focus::KeyboardFocusTarget, shell::WindowElement};
+
+use super::{Backend, Otto};
+
+impl<BackendData: Backend> Otto<BackendData> {
+    fn keyboard_focus_window(&self) -> Option<WindowElement> {
+        match self.seat.get_keyboard()?.current_focus()? {
+            KeyboardFocusTarget::Window(window) => Some(window),
+            _ => None,
+        }
+    }
+
+    /// Switch to the next configured keyboard layout, wrapping around, and
+    /// return its name.
+    ///
+    /// Key translation and the layout index sent to clients with the
+    /// modifiers both follow the new xkb group.
+    pub fn cycle_keyboard_layout(&mut self) -> Option<String> {
+        let keyboard = self.seat.get_keyboard()?;
+        let (layout, name) = keyboard.with_xkb_state(self, |mut context| {
+            context.cycle_next_layout();
+            let xkb = context.xkb().lock().unwrap();
+            let layout = xkb.active_layout();
+            (layout, xkb.layout_name(layout).to_string())
+        });
+        if Config::with(|c| c.input.per_window_layout) {
+            if let Some(window) = self.keyboard_focus_window() {
+                window.set_keyboard_layout(layout.0);
+            }
+        }
+        Some(name)
+    }
+
+    /// Save the active layout into the window that just lost keyboard focus
+    /// and switch to the one last used in the newly focused window.
+    ///
+    /// Focus moving to something other than a window (a layer surface, or
+    /// nothing) keeps the current layout.
+    pub fn restore_window_keyboard_layout(&mut self) {
+        let Some(keyboard) = self.seat.get_keyboard() else {
+            return;
+        };
+        let focused = self.keyboard_focus_window();
+        if focused == self.keyboard_layout_window {
+            return;
+        }
+        let previous = std::mem::replace(&mut self.keyboard_layout_window, focused.clone());
+        let restore = focused.map(|window| window.keyboard_layout());
+        keyboard.with_xkb_state(self, |mut context| {
+            let active = context.xkb().lock().unwrap().active_layout();
+            if let Some(previous) = &previous {
+                previous.set_keyboard_layout(active.0);
+            }
+            if let Some(layout) = restore.filter(|layout| *layout != active.0) {
+                context.set_layout(Layout(layout));
+            }
+        });
+    }
+}
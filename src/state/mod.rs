@@ -20,10 +20,12 @@ use smithay::{
         },
         utils::{RendererSurfaceState, RendererSurfaceStateUserData},
     },
-    delegate_compositor, delegate_cursor_shape, delegate_keyboard_shortcuts_inhibit,
-    delegate_layer_shell, delegate_output, delegate_pointer_gestures, delegate_presentation,
-    delegate_relative_pointer, delegate_shm, delegate_text_input_manager, delegate_viewporter,
-    delegate_virtual_keyboard_manager, delegate_xdg_foreign, delegate_xdg_shell,
+    delegate_compositor, delegate_content_type, delegate_cursor_shape,
+    delegate_keyboard_shortcuts_inhibit, delegate_layer_shell, delegate_output,
+    delegate_pointer_gestures, delegate_presentation, delegate_relative_pointer, delegate_shm,
+    delegate_single_pixel_buffer, delegate_tearing_control, delegate_text_input_manager,
+    delegate_viewporter, delegate_virtual_keyboard_manager, delegate_xdg_foreign,
+    delegate_xdg_shell,
     desktop::{
         utils::{
             surface_presentation_feedback_flags_from_states, surface_primary_scanout_output,
@@ -40,9 +42,10 @@ use smithay::{
     output::Output,
     reexports::{
         calloop::{
+            self,
             channel::{channel, Event as ChannelEvent, Sender as ChannelSender},
             generic::Generic,
-            Interest, LoopHandle, Mode, PostAction,
+            Interest, LoopHandle, Mode, PostAction, RegistrationToken,
         },
         wayland_protocols::xdg::shell::server::xdg_toplevel,
         wayland_server::{
@@ -53,13 +56,12 @@ use smithay::{
     },
     utils::{self, Clock, Monotonic, SERIAL_COUNTER},
     wayland::{
-        compositor::{
-            CompositorClientState, CompositorState, SurfaceAttributes, SurfaceData, TraversalAction,
-        },
+        compositor::{CompositorClientState, CompositorState, SurfaceData, TraversalAction},
+        content_type::ContentTypeState,
         cursor_shape::CursorShapeManagerState,
         dmabuf::DmabufFeedback,
         foreign_toplevel_list::ForeignToplevelListState,
-        fractional_scale::{with_fractional_scale, FractionalScaleManagerState},
+        fractional_scale::FractionalScaleManagerState,
         input_method::InputMethodManagerState,
         keyboard_shortcuts_inhibit::{
             KeyboardShortcutsInhibitHandler, KeyboardShortcutsInhibitState,
@@ -80,8 +82,10 @@ use smithay::{
             xdg::{decoration::XdgDecorationState, SurfaceCachedState, XdgShellState},
         },
         shm::{ShmHandler, ShmState},
+        single_pixel_buffer::SinglePixelBufferState,
         socket::ListeningSocketSource,
         tablet_manager::TabletManagerState,
+        tearing_control::TearingControlState,
         text_input::TextInputManagerState,
         viewporter::ViewporterState,
         virtual_keyboard::VirtualKeyboardManagerState,
@@ -95,7 +99,8 @@ use crate::cursor::Cursor;
 use crate::cursor::{CursorManager, CursorTextureCache};
 use crate::{
     audio::{AudioManager, SoundPlayer},
-    config::Config,
+    config::{shortcuts::ShortcutTrigger, Config},
+    input::actions::KeyAction,
     render_elements::scene_element::SceneElement,
     shell::{LayerShellSurface, WindowElement},
     skia_renderer::SkiaTextureImage,
@@ -118,6 +123,8 @@ pub struct CalloopData<BackendData: Backend + 'static> {
 pub struct ClientState {
     pub compositor_state: CompositorClientState,
     pub security_context: Option<SecurityContext>,
+    /// Binary the client runs, from its socket credentials
+    pub executable: Option<std::path::PathBuf>,
 }
 impl ClientData for ClientState {
     /// Notification that a client was initialized
@@ -126,6 +133,89 @@ impl ClientData for ClientState {
     fn disconnected(&self, _client_id: ClientId, _reason: DisconnectReason) {}
 }
 
+impl ClientState {
+    /// State of a client connecting on `stream`
+    pub fn for_stream(stream: &std::os::unix::net::UnixStream) -> Self {
+        Self {
+            executable: peer_executable(stream),
+            ..Self::default()
+        }
+    }
+
+    /// App id the sandbox engine attached to the client's security context,
+    /// e.g. the Flatpak id. Unlike the `xdg_toplevel` app id, the client
+    /// cannot choose it.
+    pub fn sandbox_app_id(&self) -> Option<&str> {
+        self.security_context.as_ref()?.app_id.as_deref()
+    }
+}
+
+/// Executable of the process at the other end of `stream`
+fn peer_executable(stream: &std::os::unix::net::UnixStream) -> Option<std::path::PathBuf> {
+    use std::os::fd::AsRawFd;
+    let mut credentials = libc::ucred {
+        pid: 0,
+        uid: 0,
+        gid: 0,
+    };
+    let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    // Safety: `credentials` and `len` outlive the call and match SO_PEERCRED
+    let result = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            &mut credentials as *mut libc::ucred as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if result != 0 || credentials.pid <= 0 {
+        return None;
+    }
+    std::fs::read_link(format!("/proc/{}/exe", credentials.pid)).ok()
+}
+
+/// Whether `client` connected through a security context (e.g. a Flatpak
+/// sandbox)
+pub fn is_sandboxed(client: &smithay::reexports::wayland_server::Client) -> bool {
+    client
+        .get_data::<ClientState>()
+        .is_some_and(|client_state| client_state.security_context.is_some())
+}
+
+/// Sandbox app id of `client`, see [`ClientState::sandbox_app_id`]
+pub fn sandbox_app_id(client: &smithay::reexports::wayland_server::Client) -> Option<String> {
+    client
+        .get_data::<ClientState>()
+        .and_then(|client_state| client_state.sandbox_app_id().map(str::to_string))
+}
+
+/// Whether `client` may see privileged globals, per `[security]`: those that
+/// read or drive other clients (input, screen contents, clipboard, windows)
+/// or restyle the desktop. Used as the registry filter of each of them, so
+/// other clients never learn they exist.
+pub fn is_privileged(client: &smithay::reexports::wayland_server::Client) -> bool {
+    // Clients Otto creates itself, such as XWayland, carry other data
+    let Some(client_state) = client.get_data::<ClientState>() else {
+        return true;
+    };
+    let allowed = Config::with(|c| {
+        c.security.allows(
+            client_state.security_context.is_some(),
+            client_state.sandbox_app_id(),
+            client_state.executable.as_deref(),
+        )
+    });
+    if !allowed {
+        tracing::trace!(
+            executable = ?client_state.executable,
+            app_id = ?client_state.sandbox_app_id(),
+            "Privileged global hidden from client"
+        );
+    }
+    allowed
+}
+
 /// Tracks reserved space on each edge of an output from layer shell exclusive zones
 #[derive(Debug, Clone, Default)]
 pub struct ExclusiveZones {
@@ -194,6 +284,9 @@ pub struct Otto<BackendData: Backend + 'static> {
     pub foreign_toplevel_list_state: ForeignToplevelListState,
     pub wlr_foreign_toplevel_state: wlr_foreign_toplevel::WlrForeignToplevelManagerState,
     pub cursor_shape_manager_state: CursorShapeManagerState,
+    pub tearing_control_state: TearingControlState,
+    pub content_type_state: ContentTypeState,
+    pub single_pixel_buffer_state: SinglePixelBufferState,
     pub virtual_keyboard_manager_state: VirtualKeyboardManagerState,
     pub screencopy_manager_state: screencopy::ScreencopyManagerState,
     pub pending_screencopy_frames: Vec<screencopy::PendingScreencopy>,
@@ -206,13 +299,36 @@ pub struct Otto<BackendData: Backend + 'static> {
 
     // input-related fields
     pub suppressed_keys: Vec<Keysym>,
+    /// Pointer buttons whose press triggered a binding; their release is
+    /// swallowed too
+    pub suppressed_buttons: Vec<u32>,
+    /// Scroll accumulated towards the next scroll binding step, horizontal
+    /// and vertical, in v120 units
+    pub scroll_binding_delta: (f64, f64),
     pub current_modifiers: ModifiersState,
     pub app_switcher_hold_modifiers: Option<ModifiersState>,
+    /// Window whose keyboard layout is active, with per-window layouts
+    pub keyboard_layout_window: Option<WindowElement>,
+    /// Chords of a multi-key shortcut typed so far
+    pub pending_shortcut: Vec<ShortcutTrigger>,
+    pub pending_shortcut_timeout: Option<RegistrationToken>,
+    /// Timer hiding idle cursors, registered while `hide_cursor_idle_ms` is set
+    pub cursor_idle_timer: Option<RegistrationToken>,
+    /// Focused surface whose shortcuts inhibitor was activated
+    pub shortcuts_inhibit_surface: Option<WlSurface>,
+    /// First press of the inhibitor escape chord, waiting for the second
+    pub shortcuts_inhibit_escape_at: Option<std::time::Instant>,
+    /// Whether a lock screen holds the session. Synthesized keyboard and
+    /// pointer input is dropped while it is set. No session lock protocol
+    /// sets it yet; only headless tests do.
+    pub session_locked: bool,
     pub cursor_status: Arc<Mutex<CursorImageStatus>>,
     pub cursor_manager: CursorManager,
     pub cursor_texture_cache: CursorTextureCache,
     pub seat_name: String,
     pub seat: Seat<Otto<BackendData>>,
+    /// Seat for synthesized input, see `state::virtual_seat`
+    pub virtual_seat: Option<Seat<Otto<BackendData>>>,
     pub clock: Clock<Monotonic>,
     pub pointer: PointerHandle<Otto<BackendData>>,
     /// Cached pointer location (logical) to avoid deadlock when accessing during button events
@@ -245,6 +361,14 @@ pub struct Otto<BackendData: Backend + 'static> {
     /// Currently applied gamma per output: (output_name, red_lut, green_lut, blue_lut)
     #[allow(clippy::type_complexity)]
     pub current_gamma: HashMap<String, (Vec<u16>, Vec<u16>, Vec<u16>)>,
+    /// Night light forced on or off by ToggleNightLight, until the schedule agrees
+    pub night_light_override: Option<bool>,
+    /// Night light temperature last applied per output name
+    pub night_light_applied: HashMap<String, u32>,
+    /// Full-screen magnifier level and region per output
+    pub magnifier: zoom::Magnifier,
+    /// Recent clipboard selections, most recent first
+    pub clipboard_history: clipboard_history::ClipboardHistory,
 
     #[cfg(feature = "xwayland")]
     pub xwm: Option<X11Wm>,
@@ -267,14 +391,28 @@ pub struct Otto<BackendData: Backend + 'static> {
 
     // power management
     pub is_lid_closed: bool,
+    /// Last state reported by UPower; `None` when UPower is unavailable
+    pub power_state: Option<crate::power::PowerState>,
+    /// Refresh rates and backlight levels changed to save battery
+    pub power_adjustments: power::PowerAdjustments,
 
     // screenshare
     pub screenshare_sessions: HashMap<String, crate::screenshare::ScreencastSession>,
     /// Manager for the screenshare D-Bus service (started lazily when needed).
     pub screenshare_manager: Option<crate::screenshare::ScreenshareManager>,
+    /// Screen recording to a file started by StartRecording, if any.
+    pub screen_recording: Option<screen_recording::ActiveRecording>,
+    /// State last reported through `org.otto.Compositor` signals.
+    pub compositor_observed: compositor_control::ObservedState,
 
     /// Virtual outputs defined in config, each streamed via PipeWire.
     pub virtual_outputs: Vec<crate::virtual_output::VirtualOutputState>,
+    /// Interactive region selection in progress (screenshots, region screenshare).
+    pub region_selection: Option<region_selection::ActiveRegionSelection>,
+    /// Region screenshot started once the selector is off the screen.
+    pub pending_screenshot: Option<region_selection::PendingScreenshot>,
+    /// Children started with `spawn_detached`, reaped on SIGCHLD.
+    pub detached_children: Vec<std::process::Child>,
 
     // foreign toplevel list - maps surface ObjectId to unified toplevel handles (both protocols)
     pub foreign_toplevels: HashMap<ObjectId, foreign_toplevel_shared::ForeignToplevelHandles>,
@@ -302,24 +440,44 @@ pub struct Otto<BackendData: Backend + 'static> {
 }
 
 pub mod app_management;
+pub mod clipboard_history;
+pub mod compositor_control;
+pub mod config_reload;
+pub mod cursor_hide;
 pub mod data_device_handler;
+pub mod desktop_entries;
 pub mod dnd_grab_handler;
 pub mod foreign_toplevel_list_handler;
 pub mod foreign_toplevel_shared;
+pub mod fps_overlay;
 pub mod fractional_scale_handler;
 pub mod gamma_control;
+pub mod inactive_opacity;
 pub mod input_method_handler;
+pub mod keyboard_layout;
+pub mod night_light;
+pub mod power;
+pub mod reduce_motion;
+pub mod region_selection;
+pub mod screen_recording;
 pub mod screencopy;
 pub mod seat_handler;
 pub mod security_context_handler;
 pub mod selection_handler;
+pub mod test_pattern;
+pub mod theme;
+pub mod tiling;
 pub mod virtual_keyboard_handler;
 pub mod virtual_pointer;
+pub mod virtual_seat;
+pub mod window_frame;
+pub mod window_rules;
 pub mod window_throttle;
 pub mod wlr_foreign_toplevel;
 pub mod xdg_activation_handler;
 pub mod xdg_decoration_handler;
 pub mod xwayland_handler;
+pub mod zoom;
 
 // Gesture constants
 pub const DIRECTION_THRESHOLD: f64 = 5.0;
@@ -394,8 +552,7 @@ impl<BackendData: Backend> KeyboardShortcutsInhibitHandler for Otto<BackendData>
     }
 
     fn new_inhibitor(&mut self, inhibitor: KeyboardShortcutsInhibitor) {
-        // Just grant the wish for everyone
-        inhibitor.activate();
+        self.shortcuts_inhibitor_created(inhibitor);
     }
 }
 
@@ -409,6 +566,9 @@ delegate_compositor!(@<BackendData: Backend + 'static> Otto<BackendData>);
 delegate_output!(@<BackendData: Backend + 'static> Otto<BackendData>);
 delegate_shm!(@<BackendData: Backend + 'static> Otto<BackendData>);
 delegate_cursor_shape!(@<BackendData: Backend + 'static> Otto<BackendData>);
+delegate_tearing_control!(@<BackendData: Backend + 'static> Otto<BackendData>);
+delegate_content_type!(@<BackendData: Backend + 'static> Otto<BackendData>);
+delegate_single_pixel_buffer!(@<BackendData: Backend + 'static> Otto<BackendData>);
 delegate_text_input_manager!(@<BackendData: Backend + 'static> Otto<BackendData>);
 delegate_keyboard_shortcuts_inhibit!(@<BackendData: Backend + 'static> Otto<BackendData>);
 delegate_virtual_keyboard_manager!(@<BackendData: Backend + 'static> Otto<BackendData>);
@@ -465,6 +625,10 @@ smithay::reexports::wayland_server::delegate_dispatch!(@<BackendData: Backend +
     crate::otto_dock::protocol::gen::otto_dock_item_v1::OttoDockItemV1: crate::otto_dock::protocol::DockItem
 ] => crate::otto_dock::handlers::OttoDockState);
 
+smithay::reexports::wayland_server::delegate_dispatch!(@<BackendData: Backend + 'static> Otto<BackendData>: [
+    crate::otto_dock::protocol::gen::otto_dock_launch_v1::OttoDockLaunchV1: ()
+] => crate::otto_dock::handlers::OttoDockState);
+
 smithay::reexports::wayland_server::delegate_global_dispatch!(@<BackendData: Backend + 'static> Otto<BackendData>: [
     smithay::reexports::wayland_protocols_wlr::screencopy::v1::server::zwlr_screencopy_manager_v1::ZwlrScreencopyManagerV1: ()
 ] => screencopy::ScreencopyManagerState);
@@ -494,9 +658,10 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
             let socket_name = source.socket_name().to_string_lossy().into_owned();
             handle
                 .insert_source(source, |client_stream, _, data| {
+                    let client_state = ClientState::for_stream(&client_stream);
                     if let Ok(_client) = data
                         .display_handle
-                        .insert_client(client_stream, Arc::new(ClientState::default()))
+                        .insert_client(client_stream, Arc::new(client_state))
                     {
                         // warn!("Error adding wayland client: {}", err);
                     };
@@ -571,13 +736,14 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
             .expect("Failed to insert loop wake channel");
 
         // init globals
-        let compositor_state = CompositorState::new::<Self>(&dh);
+        // v6 for wl_surface.preferred_buffer_scale/transform
+        let compositor_state = CompositorState::new_v6::<Self>(&dh);
         let data_device_state = DataDeviceState::new::<Self>(&dh);
         let layer_shell_state = WlrLayerShellState::new::<Self>(&dh);
         let output_manager_state = OutputManagerState::new_with_xdg_output::<Self>(&dh);
         let primary_selection_state = PrimarySelectionState::new::<Self>(&dh);
         let data_control_state =
-            DataControlState::new::<Self, _>(&dh, Some(&primary_selection_state), |_| true);
+            DataControlState::new::<Self, _>(&dh, Some(&primary_selection_state), is_privileged);
         let mut seat_state = SeatState::new();
         let shm_state = ShmState::new::<Self>(&dh, vec![]);
         let viewporter_state = ViewporterState::new::<Self>(&dh);
@@ -587,9 +753,9 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
         let presentation_state = PresentationState::new::<Self>(&dh, clock.id() as u32);
         let fractional_scale_manager_state = FractionalScaleManagerState::new::<Self>(&dh);
         TextInputManagerState::new::<Self>(&dh);
-        InputMethodManagerState::new::<Self, _>(&dh, |_client| true);
+        InputMethodManagerState::new::<Self, _>(&dh, is_privileged);
         let virtual_keyboard_manager_state =
-            VirtualKeyboardManagerState::new::<Self, _>(&dh, |_client| true);
+            VirtualKeyboardManagerState::new::<Self, _>(&dh, is_privileged);
         let screencopy_manager_state = screencopy::ScreencopyManagerState::new::<BackendData>(&dh);
         let virtual_pointer_manager_state =
             virtual_pointer::VirtualPointerManagerState::new::<BackendData>(&dh);
@@ -602,11 +768,7 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
             PointerGesturesState::new::<Self>(&dh);
         }
         TabletManagerState::new::<Self>(&dh);
-        SecurityContextState::new::<Self, _>(&dh, |client| {
-            client
-                .get_data::<ClientState>()
-                .is_none_or(|client_state| client_state.security_context.is_none())
-        });
+        SecurityContextState::new::<Self, _>(&dh, |client| !is_sandboxed(client));
         let xdg_foreign_state = XdgForeignState::new::<Self>(&dh);
         let foreign_toplevel_list_state = ForeignToplevelListState::new::<Self>(&dh);
         let wlr_foreign_toplevel_state =
@@ -623,7 +785,15 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
         crate::surface_style::create_style_manager_global::<BackendData>(&dh);
 
         // Create otto_dock protocol global
-        let otto_dock = crate::otto_dock::handlers::OttoDockState::new::<Self>(&dh);
+        let (launch_sender, launch_receiver) = channel();
+        handle
+            .insert_source(launch_receiver, |event, _, otto| {
+                if let ChannelEvent::Msg(pending) = event {
+                    crate::otto_dock::handlers::launch::start_launch(otto, pending);
+                }
+            })
+            .expect("Failed to register dock launch channel");
+        let otto_dock = crate::otto_dock::handlers::OttoDockState::new::<Self>(&dh, launch_sender);
 
         // init input
         let seat_name = backend_data.seat_name();
@@ -631,11 +801,12 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
 
         let cursor_status = Arc::new(Mutex::new(CursorImageStatus::default_named()));
         let (cursor_theme, cursor_size) = Config::with(|c| (c.cursor_theme.clone(), c.cursor_size));
-        let cursor_manager = CursorManager::new(&cursor_theme, cursor_size as u8);
+        let mut cursor_manager = CursorManager::new(&cursor_theme, cursor_size as u8);
+        cursor_manager.add_seat(&seat_name);
         let cursor_texture_cache = CursorTextureCache::default();
         let pointer = seat.add_pointer();
         let (layout, variant, options, repeat_delay, repeat_rate) = Config::with(|c| {
-            let layout = c.input.xkb_layout.clone().unwrap_or_default();
+            let layout = c.input.xkb_layout_string();
             let variant = c.input.xkb_variant.clone().unwrap_or_default();
             let options = if c.input.xkb_options.is_empty() {
                 None
@@ -653,14 +824,28 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
         let xkb_config = XkbConfig {
             layout: &layout,
             variant: &variant,
-            options,
+            options: options.clone(),
             ..Default::default()
         };
         seat.add_keyboard(xkb_config, repeat_delay, repeat_rate)
             .expect("Failed to initialize the keyboard");
+        let virtual_seat = virtual_seat::new_virtual_seat(
+            &mut seat_state,
+            &dh,
+            &seat_name,
+            XkbConfig {
+                layout: &layout,
+                variant: &variant,
+                options,
+                ..Default::default()
+            },
+        );
 
         let keyboard_shortcuts_inhibit_state = KeyboardShortcutsInhibitState::new::<Self>(&dh);
         let cursor_shape_manager_state = CursorShapeManagerState::new::<Self>(&dh);
+        let tearing_control_state = TearingControlState::new::<Self>(&dh);
+        let content_type_state = ContentTypeState::new::<Self>(&dh);
+        let single_pixel_buffer_state = SinglePixelBufferState::new::<Self>(&dh);
 
         #[cfg(feature = "xwayland")]
         let xwayland_shell_state = xwayland_shell::XWaylandShellState::new::<Self>(&dh.clone());
@@ -681,23 +866,73 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
             Workspaces::new(layers_engine.clone(), dh.clone());
         handle
             .insert_source(remove_workspace_receiver, |event, _, otto| {
-                if let ChannelEvent::Msg(index) = event {
+                if let ChannelEvent::Msg(removal) = event {
                     let pos = otto
                         .workspaces
-                        .with_model(|m| m.workspaces.iter().position(|w| w.index == index));
+                        .with_model(|m| m.workspaces.iter().position(|w| w.index == removal.index));
                     if let Some(pos) = pos {
                         otto.workspaces.remove_workspace_at(pos);
+                        if removal.persist {
+                            otto.workspaces.save_workspaces_config();
+                        }
                     }
                 }
             })
             .expect("Failed to register workspace remove channel");
 
+        match calloop::signals::Signals::new(&[calloop::signals::Signal::SIGCHLD]) {
+            Ok(signals) => {
+                handle
+                    .insert_source(signals, |_, _, otto| otto.reap_detached_children())
+                    .expect("Failed to register SIGCHLD source");
+            }
+            Err(e) => warn!(
+                "Failed to watch SIGCHLD, detached children are reaped lazily: {}",
+                e
+            ),
+        }
+
+        let audio_manager = AudioManager::new().ok();
+        if let Some(audio_manager) = &audio_manager {
+            let show_indicator = Config::with(|c| c.audio.show_mic_indicator);
+            workspaces
+                .mic_indicator
+                .set_visible(show_indicator && audio_manager.source_muted());
+        }
+        handle
+            .insert_source(calloop::timer::Timer::immediate(), |_, _, otto| {
+                otto.update_night_light();
+                otto.update_auto_theme_scheme();
+                calloop::timer::TimeoutAction::ToDuration(night_light::NIGHT_LIGHT_CHECK_INTERVAL)
+            })
+            .expect("Failed to register night light timer");
+        let cursor_idle_timer = (Config::with(|c| c.hide_cursor_idle_ms) > 0)
+            .then(|| cursor_hide::insert_cursor_idle_timer(&handle));
+        let (power_tx, power_rx) = calloop::channel::channel();
+        handle
+            .insert_source(power_rx, |event, _, otto| {
+                if let calloop::channel::Event::Msg(state) = event {
+                    otto.on_power_state_change(state);
+                }
+            })
+            .expect("Failed to register power state source");
+        crate::power::watch_upower(power_tx);
+
+        config_reload::watch_config_files(&handle);
+        desktop_entries::watch_desktop_entries(&handle);
+
         #[cfg(feature = "debugger")]
         layers_engine.start_debugger();
 
         // Get backend name before moving backend_data
         #[cfg(feature = "metrics")]
         let backend_name = backend_data.backend_name();
+        #[cfg(feature = "metrics")]
+        let render_metrics = Arc::new(crate::render_metrics::RenderMetrics::new(backend_name));
+        #[cfg(feature = "metrics")]
+        if let Some(socket_name) = socket_name.as_deref() {
+            crate::render_metrics::socket::start(render_metrics.clone(), socket_name);
+        }
 
         Otto {
             backend_data,
@@ -731,28 +966,45 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
             foreign_toplevel_list_state,
             wlr_foreign_toplevel_state,
             cursor_shape_manager_state,
+            tearing_control_state,
+            content_type_state,
+            single_pixel_buffer_state,
             virtual_keyboard_manager_state,
             screencopy_manager_state,
             pending_screencopy_frames: Vec::new(),
             virtual_pointer_manager_state,
             dnd_icon: None,
             suppressed_keys: Vec::new(),
+            suppressed_buttons: Vec::new(),
+            scroll_binding_delta: (0.0, 0.0),
             current_modifiers: ModifiersState::default(),
             app_switcher_hold_modifiers: None,
+            keyboard_layout_window: None,
+            pending_shortcut: Vec::new(),
+            pending_shortcut_timeout: None,
+            cursor_idle_timer,
+            shortcuts_inhibit_surface: None,
+            shortcuts_inhibit_escape_at: None,
+            session_locked: false,
             cursor_status,
             cursor_manager,
             cursor_texture_cache,
             seat_name,
             seat,
+            virtual_seat,
             pointer,
             last_pointer_location: (0.0, 0.0),
             cursor_physical_position: (0.0, 0.0),
             clock,
             gamma_control_manager,
-            audio_manager: AudioManager::new().ok(),
+            audio_manager,
             sound_player: SoundPlayer::new().ok(),
             gamma_transitions: HashMap::new(),
             current_gamma: HashMap::new(),
+            night_light_override: None,
+            night_light_applied: HashMap::new(),
+            magnifier: zoom::Magnifier::default(),
+            clipboard_history: Default::default(),
             #[cfg(feature = "xwayland")]
             xwayland_shell_state,
             #[cfg(feature = "xwayland")]
@@ -775,11 +1027,18 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
 
             // power management
             is_lid_closed: false,
+            power_state: None,
+            power_adjustments: Default::default(),
 
             // screenshare
             screenshare_sessions: HashMap::new(),
             screenshare_manager: None,
+            screen_recording: None,
+            compositor_observed: Default::default(),
             virtual_outputs: Vec::new(),
+            region_selection: None,
+            pending_screenshot: None,
+            detached_children: Vec::new(),
 
             // foreign toplevel list
             foreign_toplevels: HashMap::new(),
@@ -796,7 +1055,7 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
             dock_item_surfaces: HashMap::new(),
             // render metrics
             #[cfg(feature = "metrics")]
-            render_metrics: Arc::new(crate::render_metrics::RenderMetrics::new(backend_name)),
+            render_metrics,
         }
     }
 
@@ -886,16 +1145,9 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
         let mut usable_zone = zones.apply_to_output(output_geom);
 
         // When autohide is enabled the dock slides away, so tiled/maximized
-        // windows can use the full height; otherwise stop above the dock.
+        // windows can use the whole output; otherwise stop at the dock.
         if !self.workspaces.dock.is_autohide_enabled() {
-            let dock_geom = self.workspaces.get_dock_geometry();
-            if dock_geom.size.h > 0 {
-                let dock_top = dock_geom.loc.y;
-                let available_bottom = usable_zone.loc.y + usable_zone.size.h;
-                if dock_top < available_bottom {
-                    usable_zone.size.h = dock_top - usable_zone.loc.y;
-                }
-            }
+            usable_zone = self.workspaces.exclude_dock(output, usable_zone);
         }
 
         usable_zone
@@ -963,10 +1215,43 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
     }
     pub fn set_cursor(&mut self, image: &CursorImageStatus) {
         *self.cursor_status.lock().unwrap() = image.clone();
-        self.cursor_manager.set_cursor_image(image.clone());
+        self.cursor_manager
+            .set_cursor_image(&self.seat_name, image.clone());
         self.backend_data.set_cursor(image);
     }
 
+    /// Pointer location of every seat with a cursor on screen, physical
+    /// seat first
+    pub fn seat_cursors(&self) -> Vec<(String, Point<f64, utils::Logical>)> {
+        let mut cursors = vec![(self.seat_name.clone(), self.pointer.current_location())];
+        if let Some(seat) = self.virtual_seat.as_ref() {
+            let shown = self.cursor_manager.seats().any(|name| name == seat.name());
+            if let (true, Some(pointer)) = (shown, seat.get_pointer()) {
+                cursors.push((seat.name().to_string(), pointer.current_location()));
+            }
+        }
+        cursors
+    }
+
+    /// Size each seat cursor for the output under its pointer, easing
+    /// between scales unless `reduce_motion` is set; called before each frame
+    pub fn tick_cursor_scales(&mut self) {
+        let animate = !Config::reduce_motion();
+        for (seat, location) in self.seat_cursors() {
+            let scale = self
+                .workspaces
+                .output_under(location)
+                .next()
+                .map(|output| output.current_scale().fractional_scale());
+            if let Some(scale) = scale {
+                self.cursor_manager.set_output_scale(&seat, scale, animate);
+            }
+        }
+        if self.cursor_manager.is_scale_animating() {
+            self.backend_data.request_redraw();
+        }
+    }
+
     pub fn load_cursor_for_action(
         &mut self,
         action: smithay::reexports::wayland_server::protocol::wl_data_device_manager::DndAction,
@@ -1140,12 +1425,14 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
                     let gravity = style.map(|s| s.contents_gravity).unwrap_or_default();
                     let client_owns_size = style.map(|s| s.client_owns_size).unwrap_or(false);
                     let shared_gravity = style.map(|s| s.shared_gravity.clone());
+                    let translation = style.map(|s| s.translation).unwrap_or_default();
                     crate::workspaces::utils::configure_surface_layer(
                         &layer,
                         wvs,
                         gravity,
                         client_owns_size,
                         shared_gravity,
+                        translation,
                     );
 
                     // Build parent-child hierarchy
@@ -1218,36 +1505,45 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
             .unwrap_or_default()
             .to_f64()
             .to_physical(scale);
-        let mut surface_attributes = states.cached_state.get::<SurfaceAttributes>();
-        let surface_attributes = surface_attributes.current();
         if let Some(render_surface) = states.data_map.get::<RendererSurfaceStateUserData>() {
             let render_surface: std::sync::MutexGuard<RendererSurfaceState> =
                 render_surface.lock().unwrap();
 
             if let Some(view) = render_surface.view() {
+                let solid_color = crate::shell::single_pixel_color(&render_surface);
                 let mut texture_id = None;
-                if let Some(t) = self.backend_data.texture_for_surface(&render_surface) {
-                    texture_id = Some(t.tid);
-                    crate::textures_storage::set(&id, t);
+                if solid_color.is_none() {
+                    if let Some(t) = self.backend_data.texture_for_surface(&render_surface) {
+                        texture_id = Some(t.tid);
+                        crate::textures_storage::set(&id, t);
+                    }
                 }
+                // The viewport source (or the whole buffer) is in surface
+                // coordinates; crop the texture in buffer pixels
+                let buffer_src = view.src.to_buffer(
+                    render_surface.buffer_scale() as f64,
+                    render_surface.buffer_transform(),
+                    &render_surface.buffer_size().unwrap_or_default().to_f64(),
+                );
                 let wvs = WindowViewSurface {
                     parent_id, // Track parent for hierarchy
                     id: id.clone(),
                     log_offset_x: location.x as f32,
                     log_offset_y: location.y as f32,
 
-                    phy_src_x: view.src.loc.x as f32 * surface_attributes.buffer_scale as f32,
-                    phy_src_y: view.src.loc.y as f32 * surface_attributes.buffer_scale as f32,
-                    phy_src_w: view.src.size.w as f32 * surface_attributes.buffer_scale as f32,
-                    phy_src_h: view.src.size.h as f32 * surface_attributes.buffer_scale as f32,
+                    phy_src_x: buffer_src.loc.x as f32,
+                    phy_src_y: buffer_src.loc.y as f32,
+                    phy_src_w: buffer_src.size.w as f32,
+                    phy_src_h: buffer_src.size.h as f32,
 
                     phy_dst_x: view.offset.x as f32 * scale as f32 - surface_geometry.loc.x as f32,
                     phy_dst_y: view.offset.y as f32 * scale as f32 - surface_geometry.loc.y as f32,
                     phy_dst_w: view.dst.w as f32 * scale as f32,
                     phy_dst_h: view.dst.h as f32 * scale as f32,
                     texture_id,
+                    solid_color,
                     commit: render_surface.current_commit(),
-                    transform: surface_attributes.buffer_transform.into(),
+                    transform: render_surface.buffer_transform(),
                 };
                 return Some(wvs);
             }
@@ -1282,7 +1578,24 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
             let mut render_elements = VecDeque::new();
 
             // Collect popup surfaces and send them to the popup overlay layer
+            // Input method popups are kept on the window's output
+            let window_location = self.workspaces.element_location(window).unwrap_or_default();
+            let output_bounds = self
+                .workspaces
+                .outputs_for_element(window)
+                .first()
+                .and_then(|output| self.workspaces.output_geometry(output));
+
             PopupManager::popups_for_surface(&window_surface).for_each(|(popup, popup_offset)| {
+                let popup_offset = match output_bounds {
+                    Some(bounds) => input_method_handler::popup_offset_in_bounds(
+                        &popup,
+                        popup_offset,
+                        window_location,
+                        bounds,
+                    ),
+                    None => popup_offset,
+                };
                 let offset: smithay::utils::Point<f64, smithay::utils::Physical> =
                     popup_offset.to_physical_precise_round(scale_factor);
                 let popup_surface = popup.wl_surface();
@@ -1409,12 +1722,14 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
                     let gravity = style.map(|s| s.contents_gravity).unwrap_or_default();
                     let client_owns_size = style.map(|s| s.client_owns_size).unwrap_or(false);
                     let shared_gravity = style.map(|s| s.shared_gravity.clone());
+                    let translation = style.map(|s| s.translation).unwrap_or_default();
                     crate::workspaces::utils::configure_surface_layer(
                         &layer,
                         wvs,
                         gravity,
                         client_owns_size,
                         shared_gravity,
+                        translation,
                     );
 
                     // Set up parent-child relationship using layers_engine.
@@ -1446,6 +1761,18 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
                     active: false,
                 };
                 window_view.view_base.update_state(&model);
+                let output_scale = self
+                    .workspaces
+                    .output_for_window(window)
+                    .map(|output| output.current_scale().fractional_scale())
+                    .unwrap_or(scale_factor);
+                window_view.update_frame(
+                    window_geometry.size.w as f32,
+                    window_geometry.size.h as f32,
+                    output_scale as f32,
+                    window.is_maximized(),
+                    fullscreen,
+                );
 
                 // Directly add root surface layer to content layer without using LayerTreeBuilder
                 let content_layer = &window_view.content_layer;
@@ -1586,20 +1913,46 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
     //     }
     // }
 
-    pub fn send_foreign_toplevel_state(&self, wid: &ObjectId, activated: bool) {
-        if let Some(handles) = self.foreign_toplevels.get(wid) {
-            if let Some(window) = self.workspaces.get_window_for_surface(wid) {
-                let minimized = window.is_minimised();
-                let maximized = window
-                    .toplevel()
-                    .map(|t| {
-                        t.with_pending_state(|s| s.states.contains(xdg_toplevel::State::Maximized))
-                    })
-                    .unwrap_or(false);
-                let fullscreen = window.is_fullscreen();
-                handles.send_state(activated, minimized, maximized, fullscreen);
+    pub fn send_foreign_toplevel_state(&mut self, wid: &ObjectId, activated: bool) {
+        let Some(window) = self.workspaces.get_window_for_surface(wid) else {
+            return;
+        };
+        let minimized = window.is_minimised();
+        let maximized = window
+            .toplevel()
+            .map(|t| t.with_pending_state(|s| s.states.contains(xdg_toplevel::State::Maximized)))
+            .unwrap_or(false);
+        let fullscreen = window.is_fullscreen();
+        if let Some(handles) = self.foreign_toplevels.get_mut(wid) {
+            handles.send_state(activated, minimized, maximized, fullscreen);
+        }
+    }
+
+    /// Bring the foreign toplevel state and output of one window in line
+    /// with it, e.g. after it was mapped, committed a new state or moved to
+    /// another workspace. Only changes are sent.
+    pub fn refresh_foreign_toplevel(&mut self, wid: &ObjectId) {
+        if !self.foreign_toplevels.contains_key(wid) {
+            return;
+        }
+        let Some(window) = self.workspaces.get_window_for_surface(wid) else {
+            return;
+        };
+        // Minimized windows keep the output they were on
+        if let Some(output) = self.workspaces.output_for_window(window) {
+            if let Some(handles) = self.foreign_toplevels.get_mut(wid) {
+                handles.set_output(Some(output));
             }
         }
+        let activated = match self
+            .seat
+            .get_keyboard()
+            .and_then(|keyboard| keyboard.current_focus())
+        {
+            Some(crate::focus::KeyboardFocusTarget::Window(focused)) => focused.id() == *wid,
+            _ => false,
+        };
+        self.send_foreign_toplevel_state(wid, activated);
     }
 
     /// Inject pre-created surface layers into a View's cache
@@ -1808,6 +2161,27 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
         }
     }
 
+    /// Animate an output's gamma to a color temperature in Kelvin (udev backend only)
+    pub fn apply_color_temperature(
+        &mut self,
+        output: &Output,
+        temperature: u32,
+    ) -> Result<(), String> {
+        #[cfg(feature = "udev")]
+        {
+            let size = self
+                .get_gamma_size(output)
+                .ok_or("Failed to get gamma size")? as usize;
+            let (red, green, blue) = crate::udev::gamma::generate_gamma_lut(temperature, size);
+            self.apply_gamma(output, &red, &green, &blue)
+        }
+        #[cfg(not(feature = "udev"))]
+        {
+            let _ = (output, temperature);
+            Err("Gamma control not supported on this backend".to_string())
+        }
+    }
+
     /// Reset gamma to neutral for an output (udev backend only)
     pub fn reset_gamma(&mut self, output: &Output) -> Result<(), String> {
         #[cfg(feature = "udev")]
@@ -1912,9 +2286,7 @@ pub fn post_repaint<'a>(
             );
 
             if let Some(output) = primary_scanout_output {
-                with_fractional_scale(states, |fraction_scale| {
-                    fraction_scale.set_preferred_scale(output.current_scale().fractional_scale());
-                });
+                fractional_scale_handler::send_preferred_scale(surface, states, &output);
             }
         });
 
@@ -1950,9 +2322,7 @@ pub fn post_repaint<'a>(
             );
 
             if let Some(output) = primary_scanout_output {
-                with_fractional_scale(states, |fraction_scale| {
-                    fraction_scale.set_preferred_scale(output.current_scale().fractional_scale());
-                });
+                fractional_scale_handler::send_preferred_scale(surface, states, &output);
             }
         });
 
@@ -2030,6 +2400,29 @@ pub trait Backend {
     fn set_cursor(&mut self, image: &CursorImageStatus); //, renderer: &mut SkiaRenderer);
     fn renderer_context(&mut self) -> Option<layers::skia::gpu::DirectContext>;
     fn request_redraw(&mut self) {}
+    /// Re-apply the `[input]` config to connected input devices
+    fn reload_input_config(&mut self) {}
+    /// Backlight level of the internal panel, in percent; `None` when the
+    /// backend cannot control it
+    fn backlight(&self) -> Option<u32> {
+        None
+    }
+    /// Set the backlight of the internal panel, in percent. Returns `false`
+    /// when it was not changed.
+    fn set_backlight(&mut self, _percent: u32) -> bool {
+        false
+    }
+    /// Set the backlight without remembering it for the next session, for
+    /// dimming that is undone later. Returns `false` when it was not changed.
+    fn dim_backlight(&mut self, _percent: u32) -> bool {
+        false
+    }
+    /// Move the backlight one `[backlight] step` up or down, by at least one
+    /// hardware level. Returns the new level in percent, `None` when it was
+    /// not changed.
+    fn step_backlight(&mut self, _direction: i32) -> Option<u32> {
+        None
+    }
     /// Get GBM device for DMA-BUF screenshare (None for backends without DMA-BUF support)
     fn gbm_device(
         &self,
@@ -2051,4 +2444,12 @@ pub trait Backend {
     fn prefers_dmabuf_screenshare(&self) -> bool {
         false
     }
+    /// Run an action from a shortcut typed outside the backend's own input
+    /// events, e.g. on a virtual keyboard
+    fn run_key_action(state: &mut Otto<Self>, action: KeyAction)
+    where
+        Self: Sized + 'static,
+    {
+        state.process_common_key_action(action);
+    }
 }
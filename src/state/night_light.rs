@@ -0,0 +1,244 @@
+//! Night light: warms the color temperature of every output at night by
+//! replacing its gamma LUT (udev backend only).
+//!
+//! The night runs from `[night_light] start` to `end`, or from sunset to
+//! sunrise when a location is configured. The temperature ramps over
+//! `transition_minutes` at both ends; it is evaluated at startup, when an
+//! output is connected and then every minute, and each change is applied
+//! with the usual animated gamma transition.
+//! Outputs whose gamma is owned by a `wlr-gamma-control` client (wlsunset,
+//! gammastep) are left alone.
+
+use std::time::Duration;
+
+use chrono::{Datelike, Local, Timelike};
+use tracing::{info, warn};
+
+use crate::config::{Config, NightLightConfig};
+
+use super::{Backend, Otto};
+
+/// How often the schedule is re-evaluated
+pub const NIGHT_LIGHT_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Temperature of an unmodified gamma ramp, in Kelvin
+const NEUTRAL_TEMPERATURE: u32 = 6500;
+
+const MINUTES_PER_DAY: f64 = 24.0 * 60.0;
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Turn night light on or off until the schedule next changes
+    pub fn toggle_night_light(&mut self) {
+        let config = Config::with(|c| c.night_light.clone());
+        let active = self
+            .night_light_override
+            .unwrap_or_else(|| scheduled_temperature(&config) != NEUTRAL_TEMPERATURE);
+        self.night_light_override = Some(!active);
+        info!(enabled = !active, "Night light toggled");
+        if !self.update_night_light() {
+            warn!(
+                backend = self.backend_data.backend_name(),
+                "Night light is not supported: no output has a gamma LUT"
+            );
+        }
+    }
+
+    /// Apply the current night light temperature to every output that does
+    /// not already show it. Returns `false` when no output supports gamma.
+    pub fn update_night_light(&mut self) -> bool {
+        let config = Config::with(|c| c.night_light.clone());
+        let scheduled = scheduled_temperature(&config);
+        // A manual override lasts until the schedule agrees with it
+        if self.night_light_override == Some(scheduled != NEUTRAL_TEMPERATURE) {
+            self.night_light_override = None;
+        }
+        let temperature = match self.night_light_override {
+            Some(true) => config.temperature,
+            Some(false) => NEUTRAL_TEMPERATURE,
+            None => scheduled,
+        };
+
+        let outputs: Vec<_> = self.workspaces.outputs().cloned().collect();
+        let mut supported = false;
+        for output in outputs {
+            if self.gamma_control_manager.has_control_for(&output) {
+                continue;
+            }
+            if self.get_gamma_size(&output).unwrap_or(0) == 0 {
+                continue;
+            }
+            supported = true;
+            let name = output.name();
+            let current = self
+                .night_light_applied
+                .get(&name)
+                .copied()
+                .unwrap_or(NEUTRAL_TEMPERATURE);
+            if current == temperature {
+                continue;
+            }
+            self.night_light_applied.insert(name.clone(), temperature);
+            if let Err(err) = self.apply_color_temperature(&output, temperature) {
+                warn!(output = name, "Failed to apply night light: {}", err);
+            }
+        }
+        supported
+    }
+
+    /// Give an output's gamma back to night light after a gamma-control
+    /// client released it
+    pub fn restore_output_gamma(&mut self, output: &smithay::output::Output) {
+        let _ = self.reset_gamma(output);
+        self.night_light_applied.remove(&output.name());
+        self.update_night_light();
+    }
+}
+
+/// Local time as minutes since midnight
+fn local_minutes() -> f64 {
+    let now = Local::now();
+    now.num_seconds_from_midnight() as f64 / 60.0
+}
+
+/// The night, as (start, end) minutes since local midnight
+fn night_window(config: &NightLightConfig) -> Option<(f64, f64)> {
+    if let (Some(latitude), Some(longitude)) = (config.latitude, config.longitude) {
+        let now = Local::now();
+        let offset = now.offset().local_minus_utc() as f64 / 60.0;
+        if let Some((sunrise, sunset)) = sun_times(now.ordinal(), latitude, longitude) {
+            return Some((
+                (sunset + offset).rem_euclid(MINUTES_PER_DAY),
+                (sunrise + offset).rem_euclid(MINUTES_PER_DAY),
+            ));
+        }
+        // Polar day or night: fall back to the fixed times
+    }
+    Some((
+        parse_clock_time(&config.start)?,
+        parse_clock_time(&config.end)?,
+    ))
+}
+
+/// Whether the schedule is in its night now, enabled or not. The `Auto`
+/// theme scheme is dark then.
+pub fn is_night(config: &NightLightConfig) -> bool {
+    night_window(config)
+        .is_some_and(|(start, end)| night_strength(local_minutes(), start, end, 0.0) > 0.0)
+}
+
+/// Temperature the schedule asks for now
+fn scheduled_temperature(config: &NightLightConfig) -> u32 {
+    if !config.enabled {
+        return NEUTRAL_TEMPERATURE;
+    }
+    let Some((start, end)) = night_window(config) else {
+        warn!(
+            start = config.start,
+            end = config.end,
+            "Invalid night light times, expected HH:MM"
+        );
+        return NEUTRAL_TEMPERATURE;
+    };
+    let strength = night_strength(
+        local_minutes(),
+        start,
+        end,
+        config.transition_minutes as f64,
+    );
+    let night = config.temperature as f64;
+    let neutral = NEUTRAL_TEMPERATURE as f64;
+    (neutral + (night - neutral) * strength).round() as u32
+}
+
+/// How far into the night `now` is: 0 during the day, 1 in the middle of
+/// the night, ramping linearly over `transition` minutes after `start` and
+/// before `end`. All times are minutes since midnight; the night may span
+/// midnight.
+fn night_strength(now: f64, start: f64, end: f64, transition: f64) -> f64 {
+    let length = (end - start).rem_euclid(MINUTES_PER_DAY);
+    let elapsed = (now - start).rem_euclid(MINUTES_PER_DAY);
+    if elapsed >= length {
+        return 0.0;
+    }
+    if transition <= 0.0 {
+        return 1.0;
+    }
+    (elapsed / transition)
+        .min((length - elapsed) / transition)
+        .min(1.0)
+}
+
+/// Parse "HH:MM" into minutes since midnight
+fn parse_clock_time(time: &str) -> Option<f64> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+    (hours < 24 && minutes < 60).then_some((hours * 60 + minutes) as f64)
+}
+
+/// Sunrise and sunset as minutes since UTC midnight on `day_of_year`
+/// (NOAA approximation), or `None` during polar day or night
+fn sun_times(day_of_year: u32, latitude: f64, longitude: f64) -> Option<(f64, f64)> {
+    let g = 2.0 * std::f64::consts::PI / 365.0 * (day_of_year as f64 - 1.0);
+    let equation_of_time = 229.18
+        * (0.000075 + 0.001868 * g.cos()
+            - 0.032077 * g.sin()
+            - 0.014615 * (2.0 * g).cos()
+            - 0.040849 * (2.0 * g).sin());
+    let declination = 0.006918 - 0.399912 * g.cos() + 0.070257 * g.sin()
+        - 0.006758 * (2.0 * g).cos()
+        + 0.000907 * (2.0 * g).sin()
+        - 0.002697 * (3.0 * g).cos()
+        + 0.00148 * (3.0 * g).sin();
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = 90.833_f64.to_radians().cos() / (latitude.cos() * declination.cos())
+        - latitude.tan() * declination.tan();
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+    let sunrise = 720.0 - 4.0 * (longitude + hour_angle) - equation_of_time;
+    let sunset = 720.0 - 4.0 * (longitude - hour_angle) - equation_of_time;
+    Some((sunrise, sunset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strength_ramps_at_both_ends_across_midnight() {
+        let (start, end) = (20.0 * 60.0, 7.0 * 60.0);
+        assert_eq!(night_strength(12.0 * 60.0, start, end, 30.0), 0.0);
+        assert_eq!(night_strength(20.0 * 60.0 + 15.0, start, end, 30.0), 0.5);
+        assert_eq!(night_strength(2.0 * 60.0, start, end, 30.0), 1.0);
+        assert_eq!(night_strength(7.0 * 60.0 - 6.0, start, end, 30.0), 0.2);
+        assert_eq!(night_strength(7.0 * 60.0, start, end, 30.0), 0.0);
+    }
+
+    #[test]
+    fn strength_without_transition_switches() {
+        assert_eq!(night_strength(60.0, 0.0, 120.0, 0.0), 1.0);
+        assert_eq!(night_strength(180.0, 0.0, 120.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn clock_times_are_parsed() {
+        assert_eq!(parse_clock_time("07:30"), Some(450.0));
+        assert_eq!(parse_clock_time(" 23:59 "), Some(1439.0));
+        assert_eq!(parse_clock_time("24:00"), None);
+        assert_eq!(parse_clock_time("7pm"), None);
+    }
+
+    #[test]
+    fn sun_rises_near_six_at_the_equinox_on_the_equator() {
+        let (sunrise, sunset) = sun_times(80, 0.0, 0.0).unwrap();
+        assert!((sunrise - 360.0).abs() < 15.0, "sunrise {sunrise}");
+        assert!((sunset - 1080.0).abs() < 15.0, "sunset {sunset}");
+    }
+
+    #[test]
+    fn polar_summer_has_no_sunset() {
+        assert_eq!(sun_times(172, 80.0, 0.0), None);
+    }
+}
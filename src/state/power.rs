@@ -0,0 +1,210 @@
+//! Battery-driven behavior: applies the `[power_management]` battery options
+//! whenever UPower reports a new [`PowerState`] (see `crate::power`) or the
+//! config is reloaded.
+//!
+//! On battery, displays drop to `battery_refresh_hz`, which the render loop
+//! then follows at the next page flip. On low battery the backlight of the
+//! internal panel is dimmed and animations can be turned off. Everything the
+//! compositor changed is remembered and put back once the condition ends,
+//! unless the user changed it in the meantime.
+
+use std::collections::HashMap;
+
+use tracing::{debug, info};
+
+use crate::{
+    config::{Config, PowerManagementConfig},
+    power::PowerState,
+};
+
+use super::{Backend, Otto};
+
+/// Refresh rates below this distance, in Hz, count as the same
+const REFRESH_TOLERANCE_HZ: f64 = 0.5;
+
+/// What the compositor changed to save power, so it can be undone
+#[derive(Debug, Default)]
+pub struct PowerAdjustments {
+    /// Refresh rate, in Hz, of each display before `battery_refresh_hz`
+    lowered_refresh: HashMap<String, f64>,
+    /// Whether low-battery dimming is in effect
+    backlight_dimmed: bool,
+    /// Backlight level before low-battery dimming and the level it was
+    /// dimmed to; `None` when it was already at or below that level
+    backlight_before_dimming: Option<(u32, u32)>,
+}
+
+/// What the battery options ask for in a power state
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PowerPolicy {
+    pub refresh_hz: Option<f64>,
+    pub brightness: Option<u32>,
+    pub reduce_motion: bool,
+}
+
+pub(crate) fn power_policy(
+    state: Option<&PowerState>,
+    config: &PowerManagementConfig,
+) -> PowerPolicy {
+    let on_battery = state.is_some_and(|s| s.on_battery);
+    let low = state.is_some_and(|s| s.is_low(config.low_battery_percent as f64));
+    PowerPolicy {
+        refresh_hz: config.battery_refresh_hz.filter(|_| on_battery),
+        brightness: config.low_battery_brightness.filter(|_| low),
+        reduce_motion: low && config.low_battery_reduce_motion,
+    }
+}
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    /// Called with each state UPower reports, the first one at startup
+    pub fn on_power_state_change(&mut self, state: PowerState) {
+        info!(
+            on_battery = state.on_battery,
+            percentage = ?state.percentage,
+            "Power state changed"
+        );
+        self.power_state = Some(state);
+        self.apply_power_policy();
+    }
+
+    /// Bring refresh rates, backlight and animations in line with the power
+    /// state and the battery options
+    pub fn apply_power_policy(&mut self) {
+        let config = Config::with(|c| c.power_management.clone());
+        let policy = power_policy(self.power_state.as_ref(), &config);
+        self.apply_battery_refresh(policy.refresh_hz);
+        self.apply_battery_brightness(policy.brightness);
+        crate::power::set_low_battery_reduce_motion(policy.reduce_motion);
+    }
+
+    /// Switch every display to `refresh_hz`, or back to the rate it had
+    /// before when `None`. Night light is applied again on the displays that
+    /// switched, as a new mode may come with a reset gamma ramp.
+    fn apply_battery_refresh(&mut self, refresh_hz: Option<f64>) {
+        if refresh_hz.is_none() && self.power_adjustments.lowered_refresh.is_empty() {
+            return;
+        }
+        let outputs: Vec<_> = self.workspaces.outputs().cloned().collect();
+        let mut switched = false;
+        for output in outputs {
+            let name = output.name();
+            let Some(mode) = output.current_mode() else {
+                continue;
+            };
+            let current = mode.refresh as f64 / 1000.0;
+            let target = match refresh_hz {
+                Some(hz) => hz,
+                None => match self.power_adjustments.lowered_refresh.remove(&name) {
+                    Some(hz) => hz,
+                    None => continue,
+                },
+            };
+            if (current - target).abs() < REFRESH_TOLERANCE_HZ {
+                continue;
+            }
+            let (width, height) = (mode.size.w as u32, mode.size.h as u32);
+            match self.change_output_mode(&name, width, height, Some(target)) {
+                Ok(()) => {
+                    if refresh_hz.is_some() {
+                        self.power_adjustments
+                            .lowered_refresh
+                            .entry(name.clone())
+                            .or_insert(current);
+                    }
+                    self.night_light_applied.remove(&name);
+                    switched = true;
+                }
+                Err(e) => debug!(output = name, "Battery refresh rate not applied: {e}"),
+            }
+        }
+        if refresh_hz.is_none() {
+            self.power_adjustments.lowered_refresh.clear();
+        }
+        if switched {
+            self.update_night_light();
+        }
+    }
+
+    /// Dim the backlight to `level` once, when it becomes `Some` and the
+    /// backlight is brighter; restore it when it becomes `None`, unless the
+    /// user changed it in the meantime.
+    fn apply_battery_brightness(&mut self, level: Option<u32>) {
+        if level.is_some() == self.power_adjustments.backlight_dimmed {
+            return;
+        }
+        self.power_adjustments.backlight_dimmed = level.is_some();
+        let current = self.backend_data.backlight();
+        match level {
+            Some(level) => {
+                self.power_adjustments.backlight_before_dimming = None;
+                if let Some(current) = current.filter(|current| *current > level) {
+                    if self.backend_data.dim_backlight(level) {
+                        self.power_adjustments.backlight_before_dimming = Some((current, level));
+                    }
+                }
+            }
+            None => {
+                if let Some((before, dimmed)) =
+                    self.power_adjustments.backlight_before_dimming.take()
+                {
+                    if current == Some(dimmed) {
+                        self.backend_data.dim_backlight(before);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> PowerManagementConfig {
+        PowerManagementConfig {
+            battery_refresh_hz: Some(60.0),
+            low_battery_brightness: Some(30),
+            low_battery_reduce_motion: true,
+            ..Default::default()
+        }
+    }
+
+    fn state(on_battery: bool, percentage: f64) -> PowerState {
+        PowerState {
+            on_battery,
+            percentage: Some(percentage),
+        }
+    }
+
+    #[test]
+    fn policy_without_upower_changes_nothing() {
+        let policy = power_policy(None, &config());
+        assert_eq!(policy.refresh_hz, None);
+        assert_eq!(policy.brightness, None);
+        assert!(!policy.reduce_motion);
+    }
+
+    #[test]
+    fn policy_on_battery_lowers_refresh_only() {
+        let policy = power_policy(Some(&state(true, 80.0)), &config());
+        assert_eq!(policy.refresh_hz, Some(60.0));
+        assert_eq!(policy.brightness, None);
+        assert!(!policy.reduce_motion);
+    }
+
+    #[test]
+    fn policy_on_low_battery_saves_everything() {
+        let policy = power_policy(Some(&state(true, 10.0)), &config());
+        assert_eq!(
+            policy,
+            PowerPolicy {
+                refresh_hz: Some(60.0),
+                brightness: Some(30),
+                reduce_motion: true,
+            }
+        );
+        let charging = power_policy(Some(&state(false, 10.0)), &config());
+        assert_eq!(charging.refresh_hz, None);
+        assert_eq!(charging.brightness, None);
+    }
+}
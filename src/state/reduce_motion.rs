@@ -0,0 +1,26 @@
+//! Runtime toggle for `reduce_motion`, driven by the ToggleReduceMotion
+//! action.
+//!
+//! The new value is written to the config file, so it outlasts a restart,
+//! and applied right away through a config reload. Transitions read the
+//! setting when they start: animations already running finish as they were.
+
+use tracing::{info, warn};
+
+use crate::config::Config;
+
+use super::{Backend, Otto};
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    /// Turn `reduce_motion` on or off
+    pub fn toggle_reduce_motion(&mut self) {
+        let reduce_motion = !Config::with(|c| c.reduce_motion);
+        if let Err(e) = Config::save_with(|c| c.reduce_motion = reduce_motion) {
+            warn!("Failed to save reduce_motion: {e}");
+            return;
+        }
+        info!("Reduce motion {}", if reduce_motion { "on" } else { "off" });
+        // Don't wait for the config file watcher
+        self.reload_config();
+    }
+}
@@ -0,0 +1,287 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use smithay::utils::{Logical, Point, Rectangle};
+use tracing::{info, warn};
+
+use crate::{screenshare::PickedSource, workspaces::RegionSelection};
+
+use super::{Backend, Otto};
+
+/// Linux input event code of the left mouse button
+const BTN_LEFT: u32 = 0x110;
+/// Linux input event code of the right mouse button
+const BTN_RIGHT: u32 = 0x111;
+
+/// What a finished region selection is used for.
+pub enum RegionSelectionPurpose {
+    /// Save a screenshot of the region to the Pictures directory.
+    Screenshot,
+    /// Reply to a D-Bus caller (screenshare in region mode); `None` on cancel.
+    Reply(tokio::sync::oneshot::Sender<Option<Rectangle<i32, Logical>>>),
+    /// Pick a screencast source: a window with a click, an output with a
+    /// click outside any window, a region with a drag. `outputs` allows
+    /// outputs and regions, `windows` allows windows; `None` on cancel.
+    PickSource {
+        outputs: bool,
+        windows: bool,
+        response_tx: tokio::sync::oneshot::Sender<Option<PickedSource>>,
+    },
+    /// Pick a source like `PickSource`, outputs and windows allowed, and
+    /// record it to a file.
+    Record,
+}
+
+/// An in-progress interactive region selection.
+///
+/// While this is set on [`Otto::region_selection`] pointer focus is withheld
+/// from clients and the keyboard only reacts to Escape / Return.
+pub struct ActiveRegionSelection {
+    pub selection: RegionSelection,
+    pub purpose: RegionSelectionPurpose,
+}
+
+/// A region screenshot waiting for the selector to leave the screen.
+///
+/// The screenshot tool captures through wlr-screencopy, so it is started
+/// once a frame drawn after the selector was hidden has been presented.
+pub struct PendingScreenshot {
+    args: Vec<String>,
+    /// A frame without the selector was rendered
+    redrawn: bool,
+}
+
+/// Whether the source picker only accepts windows, so drags pick nothing.
+fn picks_windows_only(purpose: &RegionSelectionPurpose) -> bool {
+    matches!(
+        purpose,
+        RegionSelectionPurpose::PickSource { outputs: false, .. }
+    )
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Start an interactive region selection; replaces (cancels) any running one.
+    pub fn start_region_selection(&mut self, purpose: RegionSelectionPurpose) {
+        self.cancel_region_selection();
+
+        let location = self.pointer.current_location();
+        self.region_selection = Some(ActiveRegionSelection {
+            selection: RegionSelection::new(location),
+            purpose,
+        });
+        self.workspaces.show_region_selector();
+        self.region_selection_motion(location);
+    }
+
+    pub fn is_selecting_region(&self) -> bool {
+        self.region_selection.is_some()
+    }
+
+    /// Track the pointer; snaps to the window under it unless Shift is held.
+    /// The source picker snaps to the output under the pointer instead when
+    /// it is outside any window or windows are not allowed.
+    pub(crate) fn region_selection_motion(&mut self, location: Point<f64, Logical>) {
+        let Some(active) = self.region_selection.as_ref() else {
+            return;
+        };
+        let (snap_windows, snap_outputs) = match active.purpose {
+            RegionSelectionPurpose::PickSource {
+                outputs, windows, ..
+            } => (windows, outputs),
+            RegionSelectionPurpose::Record => (true, true),
+            _ => (true, false),
+        };
+        let window = self
+            .workspaces
+            .element_under(location)
+            .filter(|_| snap_windows)
+            .and_then(|(window, _)| self.workspaces.element_geometry(window));
+        let hovered = window.or_else(|| {
+            let output = self.workspaces.output_under(location).next()?;
+            self.workspaces
+                .output_geometry(output)
+                .filter(|_| snap_outputs)
+        });
+        let free_draw = self.current_modifiers.shift;
+
+        let Some(active) = self.region_selection.as_mut() else {
+            return;
+        };
+        active.selection.set_hovered_window(hovered);
+        active.selection.pointer_moved(location);
+        let current = active.selection.current(free_draw);
+        self.workspaces.region_selector.update(current);
+    }
+
+    /// Left button press anchors the rubber band, release completes the
+    /// selection; the right button cancels. Other buttons are ignored.
+    pub(crate) fn region_selection_button(&mut self, button: u32, pressed: bool) {
+        if button == BTN_RIGHT {
+            if pressed {
+                self.cancel_region_selection();
+            }
+            return;
+        }
+        if button != BTN_LEFT {
+            return;
+        }
+        let location = self.pointer.current_location();
+        let free_draw = self.current_modifiers.shift;
+        let Some(active) = self.region_selection.as_mut() else {
+            return;
+        };
+        if pressed {
+            active.selection.press(location);
+            return;
+        }
+        let dragged = active.selection.is_dragging();
+        let windows_only = picks_windows_only(&active.purpose);
+        // Regions are not windows, keep picking
+        let rect = active
+            .selection
+            .release(free_draw)
+            .filter(|_| !(dragged && windows_only));
+        if let Some(rect) = rect {
+            self.finish_region_selection(Some(rect), dragged);
+        } else {
+            let current = active.selection.current(free_draw);
+            self.workspaces.region_selector.update(current);
+        }
+    }
+
+    /// Confirm the currently highlighted region (Return key).
+    pub fn confirm_region_selection(&mut self) {
+        let free_draw = self.current_modifiers.shift;
+        let Some(active) = self.region_selection.as_ref() else {
+            return;
+        };
+        let dragged = active.selection.is_dragging();
+        if dragged && picks_windows_only(&active.purpose) {
+            return;
+        }
+        let Some(rect) = active.selection.current(free_draw) else {
+            return;
+        };
+        self.finish_region_selection(Some(rect), dragged);
+    }
+
+    pub fn cancel_region_selection(&mut self) {
+        if self.region_selection.is_some() {
+            self.finish_region_selection(None, false);
+        }
+    }
+
+    /// `dragged` tells a rubber band from a click on the highlighted window
+    /// or output, which only matters to the source picker.
+    fn finish_region_selection(&mut self, rect: Option<Rectangle<i32, Logical>>, dragged: bool) {
+        let Some(active) = self.region_selection.take() else {
+            return;
+        };
+        self.workspaces.region_selector.hide();
+
+        match active.purpose {
+            RegionSelectionPurpose::Screenshot => {
+                if let Some(rect) = rect {
+                    self.screenshot_region(rect);
+                }
+            }
+            RegionSelectionPurpose::Reply(response_tx) => {
+                let _ = response_tx.send(rect);
+            }
+            RegionSelectionPurpose::PickSource {
+                windows,
+                response_tx,
+                ..
+            } => {
+                let source = rect.and_then(|rect| self.picked_source(rect, dragged, windows));
+                let _ = response_tx.send(source);
+            }
+            RegionSelectionPurpose::Record => {
+                if let Some(source) = rect.and_then(|rect| self.picked_source(rect, dragged, true))
+                {
+                    self.start_recording_source(source);
+                }
+            }
+        }
+    }
+
+    /// What the picker selected: the region when dragged, otherwise the
+    /// window or output under the pointer.
+    fn picked_source(
+        &self,
+        rect: Rectangle<i32, Logical>,
+        dragged: bool,
+        windows: bool,
+    ) -> Option<PickedSource> {
+        if dragged {
+            return Some(PickedSource::Region(rect));
+        }
+        let location = self.pointer.current_location();
+        let window = self
+            .workspaces
+            .element_under(location)
+            .filter(|_| windows)
+            .map(|(window, _)| window.clone());
+        if let Some(window) = window {
+            let handle = crate::compositor_service::window_handle(&window.id());
+            return Some(PickedSource::Window(handle));
+        }
+        let output = self.workspaces.output_under(location).next()?;
+        Some(PickedSource::Output(output.name()))
+    }
+
+    fn screenshot_region(&mut self, rect: Rectangle<i32, Logical>) {
+        let Some(dir) = std::env::var_os("XDG_PICTURES_DIR")
+            .map(std::path::PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|h| std::path::Path::new(&h).join("Pictures"))
+            })
+        else {
+            warn!("Cannot save screenshot: neither XDG_PICTURES_DIR nor HOME is set");
+            return;
+        };
+        let _ = std::fs::create_dir_all(&dir);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let path = dir.join(format!("Screenshot-{timestamp}.png"));
+        let geometry = format!(
+            "{},{} {}x{}",
+            rect.loc.x, rect.loc.y, rect.size.w, rect.size.h
+        );
+        info!(geometry = %geometry, path = %path.display(), "Capturing region screenshot");
+
+        let args = vec![
+            "-g".to_string(),
+            geometry,
+            path.to_string_lossy().into_owned(),
+        ];
+        self.pending_screenshot = Some(PendingScreenshot {
+            args,
+            redrawn: false,
+        });
+        self.backend_data.request_redraw();
+    }
+
+    /// A frame was rendered; the selector is no longer part of it.
+    pub(crate) fn screenshot_frame_rendered(&mut self) {
+        if let Some(pending) = self.pending_screenshot.as_mut() {
+            pending.redrawn = true;
+        }
+    }
+
+    /// A frame reached the screen; start the pending screenshot if that
+    /// frame was drawn without the selector.
+    pub(crate) fn screenshot_frame_presented(&mut self) {
+        if !self
+            .pending_screenshot
+            .as_ref()
+            .is_some_and(|pending| pending.redrawn)
+        {
+            return;
+        }
+        if let Some(pending) = self.pending_screenshot.take() {
+            self.launch_program("grim".to_string(), pending.args);
+        }
+    }
+}
@@ -0,0 +1,307 @@
+//! Screen recording to a file, driven by the StartRecording and
+//! StopRecording actions.
+//!
+//! StartRecording opens the source picker. The picked output, window or
+//! region gets a PipeWire stream in a session of its own, so the render loop
+//! feeds it like a screencast, and `gst-launch-1.0` encodes that stream to a
+//! file in the recordings directory. StopRecording interrupts the pipeline,
+//! which finalizes the container before exiting; the stream is dropped once
+//! it has.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use smithay::reexports::calloop::timer::{TimeoutAction, Timer};
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{Config, RecordingFormat, ScreenRecordingConfig},
+    screenshare::{
+        picked_source_stream, ActiveStream, PickedSource, PipeWireStream, ScreencastSession,
+    },
+};
+
+use super::{region_selection::RegionSelectionPurpose, Backend, Otto};
+
+/// Key of the recording's session in `screenshare_sessions`
+pub(crate) const RECORDING_SESSION: &str = "screen-recording";
+/// How often the encoder is checked on
+const ENCODER_POLL: Duration = Duration::from_millis(250);
+/// How long the encoder may take to finalize the file before it is killed
+const FINALIZE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Cursor modes of `ScreencastSession::cursor_mode`
+const CURSOR_MODE_HIDDEN: u32 = 1;
+const CURSOR_MODE_EMBEDDED: u32 = 2;
+
+/// A screen recording in progress
+pub struct ActiveRecording {
+    pub path: PathBuf,
+    encoder: Child,
+    /// When StopRecording interrupted the encoder
+    stopping_since: Option<Instant>,
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Pick an output, window or region, then record it
+    pub fn start_recording(&mut self) {
+        if self.screen_recording.is_some() {
+            info!("A screen recording is already running");
+            return;
+        }
+        self.start_region_selection(RegionSelectionPurpose::Record);
+    }
+
+    /// Record `source` to a new file in the recordings directory
+    pub fn start_recording_source(&mut self, source: PickedSource) {
+        if self.screen_recording.is_some() {
+            return;
+        }
+        let config = Config::with(|c| c.screen_recording.clone());
+        let Some(dir) = Config::with(|c| c.recordings_dir()) else {
+            warn!("Cannot record: neither XDG_VIDEOS_DIR nor HOME is set");
+            return;
+        };
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            warn!("Cannot record to {}: {e}", dir.display());
+            return;
+        }
+
+        let (key, stream_config) = match picked_source_stream(self, &source, config.framerate) {
+            Ok(stream) => stream,
+            Err(e) => {
+                warn!("Cannot record {source:?}: {e}");
+                return;
+            }
+        };
+        let (width, height, framerate) = (
+            stream_config.width,
+            stream_config.height,
+            stream_config.framerate_num,
+        );
+        let mut pipewire_stream = PipeWireStream::new(stream_config);
+        let node_id = match pipewire_stream.start_sync() {
+            Ok(id) => id,
+            Err(e) => {
+                warn!("Failed to start the recording stream: {e}");
+                return;
+            }
+        };
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let path = dir.join(format!(
+            "Recording-{timestamp}.{}",
+            config.format.extension()
+        ));
+        info!(?source, path = %path.display(), "Starting screen recording");
+        let encoder = match Command::new("gst-launch-1.0")
+            .args(encoder_args(
+                node_id, width, height, framerate, &config, &path,
+            ))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .spawn()
+        {
+            Ok(encoder) => encoder,
+            Err(e) => {
+                error!("Failed to start gst-launch-1.0: {e}");
+                return;
+            }
+        };
+
+        let cursor_mode = if config.show_cursor {
+            CURSOR_MODE_EMBEDDED
+        } else {
+            CURSOR_MODE_HIDDEN
+        };
+        self.screenshare_sessions.insert(
+            RECORDING_SESSION.to_string(),
+            ScreencastSession {
+                session_id: RECORDING_SESSION.to_string(),
+                cursor_mode,
+                streams: HashMap::from([(
+                    key.clone(),
+                    ActiveStream {
+                        output_connector: key,
+                        pipewire_stream,
+                    },
+                )]),
+            },
+        );
+        self.screen_recording = Some(ActiveRecording {
+            path,
+            encoder,
+            stopping_since: None,
+        });
+        self.workspaces.recording_indicator.set_visible(true);
+
+        let _ = self
+            .handle
+            .insert_source(Timer::from_duration(ENCODER_POLL), |_, _, otto| {
+                if otto.poll_recording() {
+                    TimeoutAction::ToDuration(ENCODER_POLL)
+                } else {
+                    TimeoutAction::Drop
+                }
+            });
+    }
+
+    /// Stop the recording; the file is complete once the encoder exits
+    pub fn stop_recording(&mut self) {
+        let Some(recording) = self.screen_recording.as_mut() else {
+            info!("No screen recording to stop");
+            return;
+        };
+        if recording.stopping_since.is_some() {
+            return;
+        }
+        info!(path = %recording.path.display(), "Stopping screen recording");
+        // With -e, gst-launch turns SIGINT into an end of stream, which
+        // finalizes the container before it exits
+        // SAFETY: kill only sends a signal. The pid is our child's and is
+        // still unreaped, as poll_recording drops the recording as soon as
+        // it reaps the encoder, so no other process can have reused it.
+        unsafe {
+            libc::kill(recording.encoder.id() as libc::pid_t, libc::SIGINT);
+        }
+        recording.stopping_since = Some(Instant::now());
+        self.workspaces.recording_indicator.set_visible(false);
+    }
+
+    /// Check on the encoder; returns whether the recording is still running
+    fn poll_recording(&mut self) -> bool {
+        let Some(recording) = self.screen_recording.as_mut() else {
+            return false;
+        };
+        let stopping = recording.stopping_since.is_some();
+        match recording.encoder.try_wait() {
+            Ok(None) => {
+                if !recording
+                    .stopping_since
+                    .is_some_and(|since| since.elapsed() > FINALIZE_TIMEOUT)
+                {
+                    return true;
+                }
+                warn!(
+                    "The encoder did not finish {} in time, stopping it",
+                    recording.path.display()
+                );
+                let _ = recording.encoder.kill();
+                let _ = recording.encoder.wait();
+            }
+            Ok(Some(status)) if stopping && status.success() => {
+                info!(path = %recording.path.display(), "Screen recording saved");
+            }
+            Ok(Some(status)) => {
+                warn!(
+                    %status,
+                    path = %recording.path.display(),
+                    "The screen recording encoder exited"
+                );
+            }
+            Err(e) => warn!("Failed to check on the screen recording encoder: {e}"),
+        }
+
+        self.screen_recording = None;
+        // Dropping the stream stops its thread and disconnects it
+        self.screenshare_sessions.remove(RECORDING_SESSION);
+        self.workspaces.recording_indicator.set_visible(false);
+        false
+    }
+}
+
+/// Arguments of the `gst-launch-1.0` pipeline encoding PipeWire node
+/// `node_id` to `path`.
+///
+/// `framerate` is the rate the stream was set up with, which the output's
+/// refresh rate may have capped below `config.framerate`.
+///
+/// The frame size is rounded down to even numbers, as 4:2:0 H.264 and VP9
+/// need. A window that resizes later is scaled to fit, with borders.
+fn encoder_args(
+    node_id: u32,
+    width: u32,
+    height: u32,
+    framerate: u32,
+    config: &ScreenRecordingConfig,
+    path: &Path,
+) -> Vec<String> {
+    let (width, height) = ((width & !1).max(2), (height & !1).max(2));
+    let framerate = framerate.max(1);
+    let encoder = match config.format {
+        RecordingFormat::Mp4 => format!(
+            "x264enc bitrate={} speed-preset=veryfast key-int-max={} ! h264parse ! mp4mux",
+            config.bitrate_kbps,
+            framerate * 2
+        ),
+        RecordingFormat::Webm => format!(
+            "vp9enc target-bitrate={} deadline=1 cpu-used=8 ! webmmux",
+            config.bitrate_kbps as u64 * 1000
+        ),
+    };
+    // keepalive-time repeats the last frame while the screen is idle
+    let pipeline = format!(
+        "pipewiresrc path={node_id} do-timestamp=true keepalive-time=1000 \
+         ! videoconvert ! videoscale add-borders=true ! videorate \
+         ! video/x-raw,format=I420,width={width},height={height},framerate={framerate}/1 \
+         ! {encoder} ! filesink"
+    );
+
+    let mut args = vec!["-e".to_string()];
+    args.extend(pipeline.split_whitespace().map(str::to_string));
+    // gst-launch joins its arguments before parsing them, quote the path
+    args.push(format!("location=\"{}\"", path.display()));
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mp4_pipeline_encodes_h264() {
+        let config = ScreenRecordingConfig::default();
+        let args = encoder_args(42, 1921, 1081, 30, &config, Path::new("/v/My Videos/a.mp4"));
+        let pipeline = args.join(" ");
+        assert_eq!(args[0], "-e");
+        assert!(pipeline.starts_with("-e pipewiresrc path=42 "));
+        assert!(pipeline.contains("width=1920,height=1080,framerate=30/1"));
+        assert!(pipeline.contains("x264enc bitrate=8000 "));
+        assert!(pipeline.contains("! mp4mux ! filesink"));
+        assert_eq!(
+            args.last().map(String::as_str),
+            Some("location=\"/v/My Videos/a.mp4\"")
+        );
+    }
+
+    #[test]
+    fn webm_pipeline_encodes_vp9_in_bits_per_second() {
+        let config = ScreenRecordingConfig {
+            format: RecordingFormat::Webm,
+            framerate: 60,
+            bitrate_kbps: 4000,
+            ..Default::default()
+        };
+        let pipeline = encoder_args(7, 1, 1, 60, &config, Path::new("/v/a.webm")).join(" ");
+        assert!(pipeline.contains("width=2,height=2,framerate=60/1"));
+        assert!(pipeline.contains("vp9enc target-bitrate=4000000 "));
+        assert!(pipeline.contains("! webmmux ! filesink"));
+    }
+
+    #[test]
+    fn pipeline_runs_at_the_stream_framerate() {
+        let config = ScreenRecordingConfig {
+            framerate: 60,
+            ..Default::default()
+        };
+        let pipeline = encoder_args(7, 1280, 720, 24, &config, Path::new("/v/a.mp4")).join(" ");
+        assert!(pipeline.contains("framerate=24/1"));
+        assert!(pipeline.contains("key-int-max=48 "));
+    }
+}
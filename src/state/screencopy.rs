@@ -1,7 +1,7 @@
 use std::sync::Mutex;
 
 use smithay::{
-    backend::allocator::{dmabuf::Dmabuf, Fourcc},
+    backend::allocator::{dmabuf::Dmabuf, Buffer, Fourcc},
     output::Output,
     reexports::{
         wayland_protocols_wlr::screencopy::v1::server::{
@@ -14,7 +14,7 @@ use smithay::{
             Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch, New, Resource,
         },
     },
-    utils::{Physical, Rectangle, Size},
+    utils::{Logical, Physical, Rectangle, Size},
     wayland::{dmabuf::get_dmabuf, shm},
 };
 
@@ -50,7 +50,8 @@ impl ScreencopyManagerState {
 pub struct ScreencopyFrameData {
     pub output: Output,
     pub overlay_cursor: bool,
-    pub region: Option<Rectangle<i32, smithay::utils::Logical>>,
+    /// Part of the output's frame to copy, in physical pixels
+    pub area: Rectangle<i32, Physical>,
     pub width: u32,
     pub height: u32,
     pub stride: u32,
@@ -80,10 +81,23 @@ pub struct PendingScreencopy {
     pub frame: ZwlrScreencopyFrameV1,
     pub buffer: CaptureBuffer,
     pub output: Output,
-    pub region: Option<Rectangle<i32, smithay::utils::Logical>>,
+    pub area: Rectangle<i32, Physical>,
     pub width: u32,
     pub height: u32,
     pub stride: u32,
+    /// The cursor must be part of the copied frame
+    pub overlay_cursor: bool,
+    /// Sent with `copy_with_damage`: the frame waits for the next render
+    /// instead of forcing one, and `damage` events precede `ready`
+    pub with_damage: bool,
+}
+
+impl PendingScreencopy {
+    /// Plain `copy` wants the current contents right away, so the output
+    /// is redrawn even when nothing changed
+    pub fn forces_render(&self) -> bool {
+        !self.with_damage
+    }
 }
 
 fn find_output_for_wl<BackendData: Backend>(
@@ -115,6 +129,10 @@ where
     ) {
         data_init.init(resource, ());
     }
+
+    fn can_view(client: Client, _global_data: &()) -> bool {
+        crate::state::is_privileged(&client)
+    }
 }
 
 impl<BackendData> Dispatch<ZwlrScreencopyManagerV1, (), Otto<BackendData>>
@@ -177,26 +195,21 @@ fn init_frame<BackendData: Backend + 'static>(
     frame_new: New<ZwlrScreencopyFrameV1>,
     output: Output,
     overlay_cursor: bool,
-    region: Option<Rectangle<i32, smithay::utils::Logical>>,
+    region: Option<Rectangle<i32, Logical>>,
 ) where
     Otto<BackendData>: Dispatch<ZwlrScreencopyFrameV1, ScreencopyFrameData>,
 {
-    let mode = output.current_mode().unwrap();
-    let scale = output.current_scale().fractional_scale();
-    let (width, height) = if let Some(region) = region {
-        (
-            (region.size.w as f64 * scale) as u32,
-            (region.size.h as f64 * scale) as u32,
-        )
-    } else {
-        (mode.size.w as u32, mode.size.h as u32)
-    };
+    let area = output.current_mode().and_then(|mode| {
+        capture_area(region, mode.size, output.current_scale().fractional_scale())
+    });
+    let area_or_empty = area.unwrap_or_default();
+    let (width, height) = (area_or_empty.size.w as u32, area_or_empty.size.h as u32);
     let stride = width * 4;
 
     let frame_data = ScreencopyFrameData {
         output,
         overlay_cursor,
-        region,
+        area: area_or_empty,
         width,
         height,
         stride,
@@ -204,6 +217,12 @@ fn init_frame<BackendData: Backend + 'static>(
     };
     let frame = data_init.init(frame_new, frame_data);
 
+    // A region entirely outside the output has nothing to copy
+    if area.is_none() {
+        frame.failed();
+        return;
+    }
+
     frame.buffer(wl_shm::Format::Argb8888, width, height, stride);
     if frame.version() >= 3 {
         // Advertise dmabuf so capable clients (PipeWire portal, OBS,
@@ -228,32 +247,11 @@ where
         _data_init: &mut DataInit<'_, Otto<BackendData>>,
     ) {
         match request {
-            zwlr_screencopy_frame_v1::Request::Copy { buffer }
-            | zwlr_screencopy_frame_v1::Request::CopyWithDamage { buffer } => {
-                let mut frame_state = data.state.lock().unwrap();
-                if !matches!(*frame_state, FrameState::AwaitingCopy) {
-                    resource.failed();
-                    return;
-                }
-                *frame_state = FrameState::Copying;
-                drop(frame_state);
-
-                // Pick the GPU dmabuf path when the client gave us a
-                // dmabuf-backed buffer; otherwise fall back to legacy SHM.
-                let capture_buffer = match get_dmabuf(&buffer) {
-                    Ok(dmabuf) => CaptureBuffer::Dmabuf(dmabuf.clone()),
-                    Err(_) => CaptureBuffer::Shm(buffer),
-                };
-
-                state.pending_screencopy_frames.push(PendingScreencopy {
-                    frame: resource.clone(),
-                    buffer: capture_buffer,
-                    output: data.output.clone(),
-                    region: data.region,
-                    width: data.width,
-                    height: data.height,
-                    stride: data.stride,
-                });
+            zwlr_screencopy_frame_v1::Request::Copy { buffer } => {
+                queue_copy(state, resource, data, buffer, false);
+            }
+            zwlr_screencopy_frame_v1::Request::CopyWithDamage { buffer } => {
+                queue_copy(state, resource, data, buffer, true);
             }
             zwlr_screencopy_frame_v1::Request::Destroy => {}
             _ => {}
@@ -274,6 +272,114 @@ where
     }
 }
 
+fn queue_copy<BackendData: Backend>(
+    state: &mut Otto<BackendData>,
+    resource: &ZwlrScreencopyFrameV1,
+    data: &ScreencopyFrameData,
+    buffer: WlBuffer,
+    with_damage: bool,
+) {
+    let mut frame_state = data.state.lock().unwrap();
+    if !matches!(*frame_state, FrameState::AwaitingCopy) {
+        resource.post_error(
+            zwlr_screencopy_frame_v1::Error::AlreadyUsed,
+            "the frame has already been copied",
+        );
+        return;
+    }
+
+    // Pick the GPU dmabuf path when the client gave us a dmabuf-backed
+    // buffer; otherwise fall back to legacy SHM. Either way the buffer has
+    // to match what was advertised in `buffer` / `linux_dmabuf`.
+    let capture_buffer = match get_dmabuf(&buffer) {
+        Ok(dmabuf) => {
+            let size = dmabuf.size();
+            if dmabuf.format().code != Fourcc::Argb8888
+                || size.w as u32 != data.width
+                || size.h as u32 != data.height
+            {
+                resource.post_error(
+                    zwlr_screencopy_frame_v1::Error::InvalidBuffer,
+                    "dmabuf format or size does not match the frame",
+                );
+                return;
+            }
+            CaptureBuffer::Dmabuf(dmabuf.clone())
+        }
+        Err(_) => {
+            let matches = shm::with_buffer_contents(&buffer, |_, len, info| {
+                info.format == wl_shm::Format::Argb8888
+                    && info.width as u32 == data.width
+                    && info.height as u32 == data.height
+                    && info.stride as u32 == data.stride
+                    && info.offset as usize + data.stride as usize * data.height as usize <= len
+            })
+            .unwrap_or(false);
+            if !matches {
+                resource.post_error(
+                    zwlr_screencopy_frame_v1::Error::InvalidBuffer,
+                    "buffer format, size or stride does not match the frame",
+                );
+                return;
+            }
+            CaptureBuffer::Shm(buffer)
+        }
+    };
+    *frame_state = FrameState::Copying;
+    drop(frame_state);
+
+    let pending = PendingScreencopy {
+        frame: resource.clone(),
+        buffer: capture_buffer,
+        output: data.output.clone(),
+        area: data.area,
+        width: data.width,
+        height: data.height,
+        stride: data.stride,
+        overlay_cursor: data.overlay_cursor,
+        with_damage,
+    };
+    if pending.forces_render() {
+        state.backend_data.request_redraw();
+    }
+    state.pending_screencopy_frames.push(pending);
+}
+
+/// Physical part of an output's frame a capture copies: the whole mode, or
+/// the requested logical region scaled and clipped to it. `None` when the
+/// region misses the output.
+fn capture_area(
+    region: Option<Rectangle<i32, Logical>>,
+    mode_size: Size<i32, Physical>,
+    scale: f64,
+) -> Option<Rectangle<i32, Physical>> {
+    let full = Rectangle::from_size(mode_size);
+    let area = match region {
+        Some(region) => region
+            .to_f64()
+            .to_physical_precise_round(scale)
+            .intersection(full)?,
+        None => full,
+    };
+    (!area.is_empty()).then_some(area)
+}
+
+/// Damage of a rendered frame that falls in a capture, relative to the
+/// capture. Without damage information the whole capture is reported.
+fn capture_damage(
+    area: Rectangle<i32, Physical>,
+    damage: Option<&[Rectangle<i32, Physical>]>,
+) -> Vec<Rectangle<i32, Physical>> {
+    match damage {
+        Some(damage) => damage
+            .iter()
+            .filter_map(|rect| rect.intersection(area))
+            .map(|rect| Rectangle::new(rect.loc - area.loc, rect.size))
+            .collect(),
+        None => vec![Rectangle::from_size(area.size)],
+    }
+}
+
 /// Called from the render loop after the output has been rendered.
 /// Dmabuf clients ride the screenshare GPU blit path (zero CPU copy);
 /// SHM clients fall back to the legacy synchronous read_pixels path.
@@ -281,6 +387,7 @@ pub fn complete_screencopy_for_output(
     pending: &mut Vec<PendingScreencopy>,
     output: &Output,
     renderer: &mut UdevRenderer<'_>,
+    damage: Option<&[Rectangle<i32, Physical>]>,
 ) {
     let indices: Vec<usize> = pending
         .iter()
@@ -296,11 +403,21 @@ pub fn complete_screencopy_for_output(
     for i in indices.into_iter().rev() {
         let p = pending.remove(i);
         let success = match &p.buffer {
-            CaptureBuffer::Dmabuf(dmabuf) => copy_to_dmabuf(renderer, &p, dmabuf, output),
-            CaptureBuffer::Shm(buffer) => copy_to_shm(renderer, &p, buffer, output),
+            CaptureBuffer::Dmabuf(dmabuf) => copy_to_dmabuf(renderer, &p, dmabuf),
+            CaptureBuffer::Shm(buffer) => copy_to_shm(renderer, &p, buffer),
         };
 
         if success {
+            if p.with_damage {
+                for rect in capture_damage(p.area, damage) {
+                    p.frame.damage(
+                        rect.loc.x as u32,
+                        rect.loc.y as u32,
+                        rect.size.w as u32,
+                        rect.size.h as u32,
+                    );
+                }
+            }
             p.frame.flags(zwlr_screencopy_frame_v1::Flags::empty());
             let now = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -316,39 +433,10 @@ pub fn complete_screencopy_for_output(
     }
 }
 
-/// Compute the (source on framebuffer, destination on output buffer)
-/// rectangles for a capture, in physical pixels.
-fn capture_rects(
-    p: &PendingScreencopy,
-    output: &Output,
-) -> (Rectangle<i32, Physical>, Rectangle<i32, Physical>) {
-    let dst_size: Size<i32, Physical> = (p.width as i32, p.height as i32).into();
-    let dst = Rectangle::from_size(dst_size);
-    let src = if let Some(region) = p.region {
-        let scale = output.current_scale().fractional_scale();
-        Rectangle::new(
-            (
-                (region.loc.x as f64 * scale) as i32,
-                (region.loc.y as f64 * scale) as i32,
-            )
-                .into(),
-            dst_size,
-        )
-    } else {
-        dst
-    };
-    (src, dst)
-}
-
-fn copy_to_dmabuf(
-    renderer: &mut UdevRenderer<'_>,
-    p: &PendingScreencopy,
-    dmabuf: &Dmabuf,
-    output: &Output,
-) -> bool {
-    let (src, dst) = capture_rects(p, output);
+fn copy_to_dmabuf(renderer: &mut UdevRenderer<'_>, p: &PendingScreencopy, dmabuf: &Dmabuf) -> bool {
+    let dst = Rectangle::from_size(p.area.size);
     let mut dmabuf = dmabuf.clone();
-    match renderer.blit_current_frame(&mut dmabuf, src, dst) {
+    match renderer.blit_current_frame(&mut dmabuf, p.area, dst) {
         Ok(()) => true,
         Err(err) => {
             tracing::warn!(?err, "screencopy dmabuf blit failed");
@@ -357,36 +445,19 @@ fn copy_to_dmabuf(
     }
 }
 
-fn copy_to_shm(
-    renderer: &mut UdevRenderer<'_>,
-    p: &PendingScreencopy,
-    buffer: &WlBuffer,
-    output: &Output,
-) -> bool {
+fn copy_to_shm(renderer: &mut UdevRenderer<'_>, p: &PendingScreencopy, buffer: &WlBuffer) -> bool {
     let Some(skia_renderer) = renderer.as_mut().current_skia_renderer() else {
         return false;
     };
     let mut skia_surface = skia_renderer.surface.clone();
-    let scale = output.current_scale().fractional_scale();
 
     let result = shm::with_buffer_contents(buffer, |ptr, len, buf_data| {
-        if buf_data.format != wl_shm::Format::Argb8888 {
-            return false;
-        }
+        let offset = buf_data.offset as usize;
         let expected = p.stride as usize * p.height as usize;
-        if len < expected {
+        if offset + expected > len {
             return false;
         }
 
-        let x_off = p
-            .region
-            .map(|r| (r.loc.x as f64 * scale) as i32)
-            .unwrap_or(0);
-        let y_off = p
-            .region
-            .map(|r| (r.loc.y as f64 * scale) as i32)
-            .unwrap_or(0);
-
         let info = layers::skia::ImageInfo::new(
             (p.width as i32, p.height as i32),
             layers::skia::ColorType::BGRA8888,
@@ -394,10 +465,65 @@ fn copy_to_shm(
             None,
         );
 
-        let dst = unsafe { std::slice::from_raw_parts_mut(ptr as *mut u8, expected) };
+        let dst = unsafe { std::slice::from_raw_parts_mut((ptr as *mut u8).add(offset), expected) };
 
-        skia_surface.read_pixels(&info, dst, p.stride as usize, (x_off, y_off))
+        skia_surface.read_pixels(&info, dst, p.stride as usize, (p.area.loc.x, p.area.loc.y))
     });
 
     matches!(result, Ok(true))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regions_are_scaled_and_clipped_to_the_output() {
+        let mode = Size::from((2880, 1800));
+        assert_eq!(
+            capture_area(None, mode, 2.0),
+            Some(Rectangle::from_size(mode))
+        );
+        assert_eq!(
+            capture_area(
+                Some(Rectangle::new((10, 20).into(), (100, 50).into())),
+                mode,
+                1.5
+            ),
+            Some(Rectangle::new((15, 30).into(), (150, 75).into()))
+        );
+        assert_eq!(
+            capture_area(
+                Some(Rectangle::new((1400, 850).into(), (100, 100).into())),
+                mode,
+                2.0
+            ),
+            Some(Rectangle::new((2800, 1700).into(), (80, 100).into()))
+        );
+        assert_eq!(
+            capture_area(
+                Some(Rectangle::new((3000, 0).into(), (10, 10).into())),
+                mode,
+                1.0
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn damage_is_reported_relative_to_the_capture() {
+        let area = Rectangle::new((100, 100).into(), (200, 200).into());
+        assert_eq!(
+            capture_damage(area, None),
+            vec![Rectangle::from_size((200, 200).into())]
+        );
+        let damage = [
+            Rectangle::new((0, 0).into(), (50, 50).into()),
+            Rectangle::new((250, 150).into(), (100, 10).into()),
+        ];
+        assert_eq!(
+            capture_damage(area, Some(&damage)),
+            vec![Rectangle::new((150, 50).into(), (50, 10).into())]
+        );
+    }
+}
@@ -10,7 +10,10 @@ use smithay::{
     },
 };
 
-use crate::focus::{KeyboardFocusTarget, PointerFocusTarget};
+use crate::{
+    config::Config,
+    focus::{KeyboardFocusTarget, PointerFocusTarget},
+};
 
 use super::{Backend, Otto};
 
@@ -30,16 +33,43 @@ impl<BackendData: Backend> SeatHandler for Otto<BackendData> {
     ) {
         let dh = &self.display_handle;
 
-        let wl_surface = target.and_then(WaylandFocus::wl_surface);
+        let wl_surface = target
+            .and_then(WaylandFocus::wl_surface)
+            .map(|surface| surface.into_owned());
 
-        let focus = wl_surface.and_then(|s| dh.get_client(s.id()).ok());
+        let focus = wl_surface.as_ref().and_then(|s| dh.get_client(s.id()).ok());
         set_data_device_focus(dh, seat, focus.clone());
         set_primary_focus(dh, seat, focus);
+
+        // Everything below follows the physical seat only
+        if self.is_virtual_seat(seat) {
+            return;
+        }
+
+        self.update_shortcuts_inhibitor_focus(wl_surface);
+
+        // The keyboard is locked while its focus changes; switch layouts
+        // once it is released
+        if Config::with(|c| c.input.per_window_layout) {
+            self.handle
+                .insert_idle(|otto| otto.restore_window_keyboard_layout());
+        }
+
+        // Dim the windows that lost focus, also once the keyboard is released
+        if Config::with(|c| c.inactive_opacity < 1.0) {
+            self.handle
+                .insert_idle(|otto| otto.update_all_window_opacities());
+        }
     }
 
-    fn cursor_image(&mut self, _seat: &smithay::input::Seat<Self>, image: CursorImageStatus) {
-        *self.cursor_status.lock().unwrap() = image.clone();
-        self.cursor_manager.set_cursor_image(image);
+    fn cursor_image(&mut self, seat: &smithay::input::Seat<Self>, image: CursorImageStatus) {
+        self.cursor_manager
+            .set_cursor_image(seat.name(), image.clone());
+        // The backend cursor follows the physical seat only
+        if self.is_virtual_seat(seat) {
+            return;
+        }
+        *self.cursor_status.lock().unwrap() = image;
     }
     fn led_state_changed(
         &mut self,
@@ -54,7 +84,7 @@ impl<BackendData: Backend> TabletSeatHandler for Otto<BackendData> {
     fn tablet_tool_image(&mut self, _tool: &TabletToolDescriptor, image: CursorImageStatus) {
         let mut cursor_status = self.cursor_status.lock().unwrap();
         *cursor_status = image.clone();
-        self.cursor_manager.set_cursor_image(image);
+        self.cursor_manager.set_cursor_image(&self.seat_name, image);
     }
 }
 
@@ -19,7 +19,7 @@ impl<BackendData: Backend + 'static> SecurityContextHandler for Otto<BackendData
             .insert_source(source, move |client_stream, _, data| {
                 let client_state = ClientState {
                     security_context: Some(security_context.clone()),
-                    ..ClientState::default()
+                    ..ClientState::for_stream(&client_stream)
                 };
                 if let Err(err) = data
                     .display_handle
@@ -1,4 +1,4 @@
-use std::os::fd::OwnedFd;
+use std::{os::fd::OwnedFd, sync::Arc};
 
 use smithay::{
     delegate_primary_selection,
@@ -11,18 +11,23 @@ use smithay::{
 #[cfg(feature = "xwayland")]
 use tracing::warn;
 
-use super::{Backend, Otto};
+use super::{clipboard_history::ClipboardEntry, Backend, Otto};
 
 impl<BackendData: Backend> SelectionHandler for Otto<BackendData> {
-    type SelectionUserData = ();
+    /// A clipboard history entry when the compositor owns the selection
+    type SelectionUserData = Option<Arc<ClipboardEntry>>;
 
-    #[cfg(feature = "xwayland")]
     fn new_selection(
         &mut self,
         ty: SelectionTarget,
         source: Option<SelectionSource>,
         _seat: Seat<Self>,
     ) {
+        if let (SelectionTarget::Clipboard, Some(source)) = (ty, source.as_ref()) {
+            self.record_clipboard_selection(&source.mime_types());
+        }
+
+        #[cfg(feature = "xwayland")]
         if let Some(xwm) = self.xwm.as_mut() {
             if let Err(err) = xwm.new_selection(ty, source.map(|source| source.mime_types())) {
                 warn!(?err, ?ty, "Failed to set Xwayland selection");
@@ -30,20 +35,27 @@ impl<BackendData: Backend> SelectionHandler for Otto<BackendData> {
         }
     }
 
-    #[cfg(feature = "xwayland")]
     fn send_selection(
         &mut self,
         ty: SelectionTarget,
         mime_type: String,
         fd: OwnedFd,
         _seat: Seat<Self>,
-        _user_data: &(),
+        user_data: &Self::SelectionUserData,
     ) {
+        if let Some(entry) = user_data {
+            self.send_clipboard_entry(entry.clone(), mime_type, fd);
+            return;
+        }
+
+        #[cfg(feature = "xwayland")]
         if let Some(xwm) = self.xwm.as_mut() {
             if let Err(err) = xwm.send_selection(ty, mime_type, fd) {
                 warn!(?err, "Failed to send primary (X11 -> Wayland)");
             }
         }
+        #[cfg(not(feature = "xwayland"))]
+        let _ = (ty, mime_type, fd);
     }
 }
 
@@ -0,0 +1,22 @@
+//! Gradient test pattern on every output, toggled at runtime with the
+//! ToggleTestPattern action.
+//!
+//! It covers the whole screen, so banding in the ramps shows whether an
+//! output really renders in 10 bits per channel (`use_10bit_color`).
+
+use tracing::info;
+
+use super::{Backend, Otto};
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Show or hide the test pattern on every output
+    pub fn toggle_test_pattern(&mut self) {
+        if self.workspaces.test_pattern.is_visible() {
+            self.workspaces.test_pattern.hide();
+            info!("Test pattern hidden");
+        } else {
+            self.workspaces.show_test_pattern();
+            info!("Test pattern shown");
+        }
+    }
+}
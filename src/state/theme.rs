@@ -0,0 +1,48 @@
+//! Live theme changes: the ToggleDarkMode action, the `Auto` scheme and
+//! re-applying the theme colors to views that are already built.
+//!
+//! Views read `theme_colors()` while they build their layers, so a new
+//! scheme or theme file only shows once they are rebuilt. `apply_theme`
+//! does that for the dock, menus, switchers and window borders; views built
+//! each time they open pick the colors up on their own.
+
+use tracing::{info, warn};
+
+use crate::config::Config;
+use crate::theme::{self, ThemeScheme};
+
+use super::{Backend, Otto};
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    /// Switch to the scheme not shown now and save it; an `Auto` scheme is
+    /// replaced by the fixed one
+    pub fn toggle_dark_mode(&mut self) {
+        let scheme = match theme::active_scheme() {
+            ThemeScheme::Dark => ThemeScheme::Light,
+            _ => ThemeScheme::Dark,
+        };
+        if let Err(e) = Config::save_with(|c| c.theme_scheme = scheme) {
+            warn!("Failed to save theme_scheme: {e}");
+            return;
+        }
+        info!("Theme scheme {:?}", scheme);
+        // Don't wait for the config file watcher
+        self.reload_config();
+    }
+
+    /// Follow the night light schedule with an `Auto` scheme
+    pub fn update_auto_theme_scheme(&mut self) {
+        if theme::update_auto_scheme() && Config::with(|c| c.theme_scheme) == ThemeScheme::Auto {
+            info!("Theme scheme {:?}", theme::active_scheme());
+            self.apply_theme();
+        }
+    }
+
+    /// Rebuild the views that hold theme colors
+    pub fn apply_theme(&mut self) {
+        theme::bump_theme_generation();
+        self.workspaces.apply_theme();
+        self.update_all_window_frames();
+        self.backend_data.request_redraw();
+    }
+}
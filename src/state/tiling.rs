@@ -0,0 +1,341 @@
+use layers::prelude::{Interpolate, Transition};
+use smithay::{
+    desktop::WindowSurface,
+    output::Output,
+    reexports::{
+        wayland_protocols::xdg::shell::server::xdg_toplevel, wayland_server::backend::ObjectId,
+    },
+    utils::{Logical, Point, Rectangle},
+};
+
+use crate::{
+    config::Config,
+    focus::KeyboardFocusTarget,
+    shell::WindowElement,
+    workspaces::{inset, neighbor_in_direction, Direction, WorkspaceLayout},
+};
+
+use super::{Backend, Otto};
+
+fn center(rect: Rectangle<i32, Logical>) -> Point<i32, Logical> {
+    (rect.loc.x + rect.size.w / 2, rect.loc.y + rect.size.h / 2).into()
+}
+
+fn is_maximized(window: &WindowElement) -> bool {
+    window
+        .toplevel()
+        .map(|t| t.with_pending_state(|s| s.states.contains(xdg_toplevel::State::Maximized)))
+        .unwrap_or(false)
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    fn keyboard_focused_window(&self) -> Option<WindowElement> {
+        match self.seat.get_keyboard()?.current_focus()? {
+            KeyboardFocusTarget::Window(window) => Some(window),
+            _ => None,
+        }
+    }
+
+    /// Output and workspace index showing `window`: the space where the
+    /// window's center lies on that output.
+    pub fn tiling_workspace_of(&self, window: &WindowElement) -> Option<(Output, usize)> {
+        self.workspaces.outputs().find_map(|output| {
+            let ows = self.workspaces.output_workspaces.get(&output.name())?;
+            let output_geometry = self.workspaces.output_geometry(output)?;
+            ows.spaces
+                .iter()
+                .position(|space| {
+                    space
+                        .element_geometry(window)
+                        .is_some_and(|g| output_geometry.contains(center(g)))
+                })
+                .map(|index| (output.clone(), index))
+        })
+    }
+
+    /// Windows of workspace `index` on `output`, bottom to top, with their geometry.
+    fn workspace_windows(
+        &self,
+        output: &Output,
+        index: usize,
+    ) -> Vec<(WindowElement, Rectangle<i32, Logical>)> {
+        let Some(space) = self
+            .workspaces
+            .output_workspaces
+            .get(&output.name())
+            .and_then(|ows| ows.spaces.get(index))
+        else {
+            return Vec::new();
+        };
+        let Some(output_geometry) = self.workspaces.output_geometry(output) else {
+            return Vec::new();
+        };
+        space
+            .elements()
+            .filter_map(|w| {
+                let geometry = space.element_geometry(w)?;
+                output_geometry
+                    .contains(center(geometry))
+                    .then(|| (w.clone(), geometry))
+            })
+            .collect()
+    }
+
+    /// Re-arrange the tiled windows of workspace `index` on `output`.
+    ///
+    /// Windows that are new to the tile tree split the most recently raised
+    /// tile; windows that went away are dropped and their space re-flows.
+    /// Floating, maximized and fullscreen windows are left out of the grid.
+    pub fn retile_workspace(&mut self, output: &Output, index: usize) {
+        let Some(view) = self
+            .workspaces
+            .output_workspaces
+            .get(&output.name())
+            .and_then(|ows| ows.workspace_views.get(index).cloned())
+        else {
+            return;
+        };
+        let layout = view.layout();
+        if !layout.is_tiling() {
+            return;
+        }
+
+        let windows: Vec<(WindowElement, Rectangle<i32, Logical>)> = self
+            .workspace_windows(output, index)
+            .into_iter()
+            .filter(|(w, _)| !w.is_floating() && !w.is_fullscreen() && !is_maximized(w))
+            .collect();
+
+        self.recalculate_exclusive_zones(output);
+        let (gaps, outer_gap) = Config::with(|c| (c.tiling.gaps, c.tiling.outer_gap));
+        let area = inset(self.usable_zone(output), outer_gap);
+
+        let placements = view.with_tile_tree(|tree| {
+            tree.retain(|id| windows.iter().any(|(w, _)| w.id() == *id));
+            for (window, _) in &windows {
+                let id = window.id();
+                if !tree.contains(&id) {
+                    let focused = windows
+                        .iter()
+                        .rev()
+                        .map(|(w, _)| w.id())
+                        .find(|id| tree.contains(id));
+                    tree.insert(id, focused.as_ref(), area);
+                }
+            }
+            tree.layout(layout, area, gaps)
+        });
+
+        for (id, target) in placements {
+            if let Some((window, current)) = windows.iter().find(|(w, _)| w.id() == id) {
+                if *current != target {
+                    self.place_tiled_window(output, index, window, *current, target);
+                }
+            }
+        }
+        self.workspaces.raise_floating_windows(output, index);
+        self.workspaces.update_workspace_model();
+    }
+
+    /// Re-arrange the workspace showing `window`, if it is tiled.
+    pub fn retile_workspace_of(&mut self, window: &WindowElement) {
+        if let Some((output, index)) = self.tiling_workspace_of(window) {
+            self.retile_workspace(&output, index);
+        }
+    }
+
+    /// Configure `window` to fill `target`, animating from `current` the same
+    /// way tiling to a screen half does.
+    fn place_tiled_window(
+        &mut self,
+        output: &Output,
+        index: usize,
+        window: &WindowElement,
+        current: Rectangle<i32, Logical>,
+        target: Rectangle<i32, Logical>,
+    ) {
+        // Windows that have not drawn yet (just mapped) jump into place
+        let animate = current.size.w > 0 && current.size.h > 0;
+        let transition = animate.then(|| Config::transition_or_instant(Transition::ease_out(0.3)));
+
+        match window.underlying_surface() {
+            WindowSurface::Wayland(_) => {
+                let Some(toplevel) = window.toplevel().cloned() else {
+                    return;
+                };
+                let set_tiled = |state: &mut smithay::wayland::shell::xdg::ToplevelState| {
+                    state.states.set(xdg_toplevel::State::TiledLeft);
+                    state.states.set(xdg_toplevel::State::TiledRight);
+                    state.states.set(xdg_toplevel::State::TiledTop);
+                    state.states.set(xdg_toplevel::State::TiledBottom);
+                };
+                if let Some(transition) = transition.as_ref() {
+                    let animation = self
+                        .layers_engine
+                        .add_animation_from_transition(transition, false);
+                    let current_width = current.size.w as f32;
+                    let current_height = current.size.h as f32;
+                    let new_width = target.size.w as f32;
+                    let new_height = target.size.h as f32;
+                    let s = toplevel.clone();
+                    self.layers_engine.on_animation_update(
+                        animation,
+                        move |p: f32| {
+                            let width = current_width.interpolate(&new_width, p) as i32;
+                            let height = current_height.interpolate(&new_height, p) as i32;
+                            s.with_pending_state(|state| {
+                                if p >= 0.999 {
+                                    set_tiled(state);
+                                }
+                                state.size = Some((width, height).into());
+                            });
+                            s.send_configure();
+                        },
+                        false,
+                    );
+                    self.layers_engine.start_animation(animation, 0.0);
+                } else {
+                    toplevel.with_pending_state(|state| {
+                        set_tiled(state);
+                        state.size = Some(target.size);
+                    });
+                    toplevel.send_pending_configure();
+                }
+            }
+            #[cfg(feature = "xwayland")]
+            WindowSurface::X11(x11) => {
+                let _ = x11.configure(target);
+            }
+        }
+
+        self.workspaces
+            .place_window_in_workspace(output, index, window, target.loc, transition);
+    }
+
+    /// Switch the layout of the focused output's current workspace.
+    ///
+    /// Leaving a tiling layout keeps windows where they are and clears their
+    /// tiled state.
+    pub fn set_workspace_layout(&mut self, layout: WorkspaceLayout) {
+        let Some(output) = self.workspaces.focused_output().cloned() else {
+            return;
+        };
+        let Some((index, view)) = self
+            .workspaces
+            .output_workspaces
+            .get(&output.name())
+            .and_then(|ows| {
+                let index = ows.current_workspace;
+                Some((index, ows.workspace_views.get(index)?.clone()))
+            })
+        else {
+            return;
+        };
+        view.set_layout(layout);
+
+        if layout.is_tiling() {
+            self.retile_workspace(&output, index);
+            return;
+        }
+        let tiled = view.with_tile_tree(|tree| {
+            let leaves = tree.leaves();
+            tree.retain(|_| false);
+            leaves
+        });
+        for id in tiled {
+            if let Some(toplevel) = self
+                .workspaces
+                .get_window_for_surface(&id)
+                .and_then(|w| w.toplevel().cloned())
+            {
+                toplevel.with_pending_state(|state| {
+                    state.states.unset(xdg_toplevel::State::TiledLeft);
+                    state.states.unset(xdg_toplevel::State::TiledRight);
+                    state.states.unset(xdg_toplevel::State::TiledTop);
+                    state.states.unset(xdg_toplevel::State::TiledBottom);
+                });
+                toplevel.send_pending_configure();
+            }
+        }
+    }
+
+    /// Switch the focused output's current workspace to the next layout.
+    pub fn cycle_workspace_layout(&mut self) {
+        let Some(layout) = self.workspaces.focused_output().and_then(|output| {
+            let ows = self.workspaces.output_workspaces.get(&output.name())?;
+            Some(ows.workspace_views.get(ows.current_workspace)?.layout())
+        }) else {
+            return;
+        };
+        self.set_workspace_layout(layout.next());
+    }
+
+    /// Focus the closest window next to the focused one in `direction`.
+    pub fn focus_window_in_direction(&mut self, direction: Direction) {
+        let Some(focused) = self.keyboard_focused_window() else {
+            return;
+        };
+        let Some((output, index)) = self.tiling_workspace_of(&focused) else {
+            return;
+        };
+        let Some(target) = self.window_in_direction(&focused, &output, index, direction) else {
+            return;
+        };
+        self.workspaces.raise_element(&target, true, true);
+        self.set_keyboard_focus_on_surface(&target);
+    }
+
+    /// Exchange the focused tile with its neighbor in `direction`.
+    ///
+    /// Only tiling workspaces have tiles to swap; floating workspaces ignore it.
+    pub fn swap_window_in_direction(&mut self, direction: Direction) {
+        let Some(focused) = self.keyboard_focused_window() else {
+            return;
+        };
+        let Some((output, index)) = self.tiling_workspace_of(&focused) else {
+            return;
+        };
+        let Some(view) = self
+            .workspaces
+            .output_workspaces
+            .get(&output.name())
+            .and_then(|ows| ows.workspace_views.get(index).cloned())
+        else {
+            return;
+        };
+        if !view.layout().is_tiling() {
+            return;
+        }
+        let Some(target) = self.window_in_direction(&focused, &output, index, direction) else {
+            return;
+        };
+        let focused_id = focused.id();
+        let swapped = view.with_tile_tree(|tree| {
+            let both = tree.contains(&focused_id) && tree.contains(&target);
+            if both {
+                tree.swap(&focused_id, &target);
+            }
+            both
+        });
+        if swapped {
+            self.retile_workspace(&output, index);
+        }
+    }
+
+    fn window_in_direction(
+        &self,
+        from: &WindowElement,
+        output: &Output,
+        index: usize,
+        direction: Direction,
+    ) -> Option<ObjectId> {
+        let rects: Vec<(ObjectId, Rectangle<i32, Logical>)> = self
+            .workspace_windows(output, index)
+            .into_iter()
+            .map(|(w, geometry)| (w.id(), geometry))
+            .collect();
+        let from_id = from.id();
+        let from_rect = rects.iter().find(|(id, _)| *id == from_id)?.1;
+        neighbor_in_direction(&rects, from_rect, direction)
+    }
+}
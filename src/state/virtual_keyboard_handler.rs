@@ -1,21 +1,47 @@
 use smithay::backend::input::{KeyState, Keycode};
-use smithay::input::keyboard::KeyboardHandle;
+use smithay::input::keyboard::{FilterResult, KeyboardHandle};
+use smithay::utils::SERIAL_COUNTER;
 use smithay::wayland::virtual_keyboard::VirtualKeyboardHandler;
 use xkbcommon::xkb::ModMask;
 
 use crate::state::Backend;
 use crate::state::Otto;
 
-impl<BackendData: Backend> VirtualKeyboardHandler for Otto<BackendData> {
+impl<BackendData: Backend + 'static> VirtualKeyboardHandler for Otto<BackendData> {
     fn on_keyboard_event(
         &mut self,
-        _keycode: Keycode,
-        _state: KeyState,
-        _time: u32,
-        _keyboard: KeyboardHandle<Self>,
+        keycode: Keycode,
+        state: KeyState,
+        time: u32,
+        keyboard: KeyboardHandle<Self>,
     ) {
-        // Smithay's protocol handler already forwards events to focused clients
-        // No additional handling needed
+        // Nothing typed on a virtual keyboard reaches a locked session
+        if self.session_locked {
+            return;
+        }
+        // Typing hides the cursor of the keyboard's seat
+        let seat = self.keyboard_seat_name(&keyboard);
+        if state == KeyState::Pressed {
+            self.hide_cursor_for_typing(&seat);
+        }
+
+        // The virtual seat does not match compositor shortcuts
+        if seat != self.seat_name {
+            keyboard.input::<(), _>(
+                self,
+                keycode,
+                state,
+                SERIAL_COUNTER.next_serial(),
+                time,
+                |_, _, _| FilterResult::Forward,
+            );
+            return;
+        }
+
+        // Same path as a real key: shortcuts and grabs first, everything
+        // else reaches the focused client
+        let action = self.key_to_action(keycode, state, time);
+        BackendData::run_key_action(self, action);
     }
 
     fn on_keyboard_modifiers(
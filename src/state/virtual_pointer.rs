@@ -13,8 +13,8 @@
 //!
 //! - `motion` — relative displacement, applied to the current pointer
 //!   location.
-//! - `motion_absolute` — normalized absolute position mapped to the first
-//!   available output.
+//! - `motion_absolute` — normalized absolute position mapped to the output
+//!   given at creation, or to the first output.
 //! - `button` — wl_pointer button press/release.
 //! - `axis` — wl_pointer axis scroll.
 //! - `frame` — flush a coalesced event sequence.
@@ -22,17 +22,25 @@
 //!   `AxisFrame` and committed on `frame`.
 //! - `destroy` — drop the pointer; freeing state.
 //!
-//! Pointer constraints, locked pointers, and relative-motion reporting are
-//! not honored from synthesized events — those are real-pointer concerns.
+//! Motion and buttons go through the same `Otto` paths as a real pointer
+//! (clamping, hover, click-to-focus, region selection), but pointer
+//! shortcuts are not matched, so a synthesized click always reaches the
+//! client. Nothing is applied while `Otto::session_locked` is set. Pointer
+//! constraints, locked pointers, and relative-motion reporting are not
+//! honored from synthesized events — those are real-pointer concerns.
+//!
+//! Pointers created on the virtual seat (`[input] virtual_seat`) drive that
+//! seat's own pointer instead, see `state::virtual_seat`.
+//!
+//! Sandboxed clients (those connected through a security context) cannot
+//! see the global.
 
 use std::sync::Mutex;
 
 use smithay::{
-    backend::input::{Axis, ButtonState},
-    input::{
-        pointer::{AxisFrame, ButtonEvent, MotionEvent},
-        SeatHandler,
-    },
+    backend::input::Axis,
+    input::{pointer::AxisFrame, Seat, SeatHandler},
+    output::Output,
     reexports::{
         wayland_protocols_wlr::virtual_pointer::v1::server::{
             zwlr_virtual_pointer_manager_v1::{self, ZwlrVirtualPointerManagerV1},
@@ -40,11 +48,11 @@ use smithay::{
         },
         wayland_server::{
             backend::{ClientId, GlobalId},
-            protocol::wl_pointer,
+            protocol::{wl_pointer, wl_seat::WlSeat},
             Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch, New,
         },
     },
-    utils::{Point, SERIAL_COUNTER},
+    utils::Point,
 };
 
 use crate::state::Otto;
@@ -76,17 +84,23 @@ impl VirtualPointerManagerState {
 /// axis_discrete events until the client commits with `frame`.
 #[derive(Debug, Default)]
 pub struct VirtualPointerUserData {
+    /// Output that absolute motion is mapped to
+    output: Option<Output>,
+    /// Created on the virtual seat rather than the physical one
+    on_virtual_seat: bool,
     pending: Mutex<PendingFrame>,
 }
 
 #[derive(Debug, Default)]
 struct PendingFrame {
+    /// Time of the latest motion or axis event, in milliseconds
+    time: u32,
     /// Accumulated relative displacement (logical pixels) since the last frame.
     motion_rel: Option<(f64, f64)>,
     /// Absolute position (logical pixels) replacing any pending relative motion.
     motion_abs: Option<(f64, f64)>,
     /// Button events to flush.
-    buttons: Vec<(u32, u32, ButtonState)>,
+    buttons: Vec<(u32, u32, wl_pointer::ButtonState)>,
     /// Axis accumulator built via `AxisFrame::new`.
     axis: Option<AxisFrame>,
 }
@@ -108,6 +122,10 @@ where
     ) {
         data_init.init(resource, ());
     }
+
+    fn can_view(client: Client, _global_data: &()) -> bool {
+        crate::state::is_privileged(&client)
+    }
 }
 
 impl<BackendData> Dispatch<ZwlrVirtualPointerManagerV1, (), Otto<BackendData>>
@@ -117,7 +135,7 @@ where
     Otto<BackendData>: Dispatch<ZwlrVirtualPointerV1, VirtualPointerUserData>,
 {
     fn request(
-        _state: &mut Otto<BackendData>,
+        state: &mut Otto<BackendData>,
         _client: &Client,
         _resource: &ZwlrVirtualPointerManagerV1,
         request: zwlr_virtual_pointer_manager_v1::Request,
@@ -126,15 +144,28 @@ where
         data_init: &mut DataInit<'_, Otto<BackendData>>,
     ) {
         match request {
-            zwlr_virtual_pointer_manager_v1::Request::CreateVirtualPointer { seat: _, id } => {
-                data_init.init(id, VirtualPointerUserData::default());
+            zwlr_virtual_pointer_manager_v1::Request::CreateVirtualPointer { seat, id } => {
+                data_init.init(
+                    id,
+                    VirtualPointerUserData {
+                        on_virtual_seat: on_virtual_seat(state, seat.as_ref()),
+                        ..Default::default()
+                    },
+                );
             }
             zwlr_virtual_pointer_manager_v1::Request::CreateVirtualPointerWithOutput {
-                seat: _,
-                output: _,
+                seat,
+                output,
                 id,
             } => {
-                data_init.init(id, VirtualPointerUserData::default());
+                data_init.init(
+                    id,
+                    VirtualPointerUserData {
+                        output: output.as_ref().and_then(Output::from_resource),
+                        on_virtual_seat: on_virtual_seat(state, seat.as_ref()),
+                        ..Default::default()
+                    },
+                );
             }
             zwlr_virtual_pointer_manager_v1::Request::Destroy => {}
             _ => {}
@@ -142,6 +173,16 @@ where
     }
 }
 
+/// Whether the seat a pointer is created for is the virtual seat; no seat
+/// or any other seat means the physical one
+fn on_virtual_seat<BackendData: crate::state::Backend + 'static>(
+    state: &Otto<BackendData>,
+    seat: Option<&WlSeat>,
+) -> bool {
+    seat.and_then(Seat::<Otto<BackendData>>::from_resource)
+        .is_some_and(|seat| state.is_virtual_seat(&seat))
+}
+
 impl<BackendData> Dispatch<ZwlrVirtualPointerV1, VirtualPointerUserData, Otto<BackendData>>
     for VirtualPointerManagerState
 where
@@ -159,25 +200,31 @@ where
     ) {
         let mut pending = data.pending.lock().unwrap();
         match request {
-            zwlr_virtual_pointer_v1::Request::Motion { time: _, dx, dy } => {
+            zwlr_virtual_pointer_v1::Request::Motion { time, dx, dy } => {
                 // dx / dy are fixed-point values; into `f64` preserves precision.
                 let (ax, ay) = pending.motion_rel.unwrap_or((0.0, 0.0));
                 pending.motion_rel = Some((ax + dx, ay + dy));
+                pending.time = time;
             }
             zwlr_virtual_pointer_v1::Request::MotionAbsolute {
-                time: _,
+                time,
                 x,
                 y,
                 x_extent,
                 y_extent,
             } => {
                 // Map the normalized absolute position into logical-pixel
-                // coordinates using the first output's geometry. If we have
-                // no outputs (shouldn't happen in practice), drop the event.
+                // coordinates using the geometry of the pointer's output, or
+                // the first output. If we have no outputs (shouldn't happen
+                // in practice), drop the event.
                 if x_extent == 0 || y_extent == 0 {
                     return;
                 }
-                let Some(output) = state.workspaces.outputs().next().cloned() else {
+                let Some(output) = data
+                    .output
+                    .clone()
+                    .or_else(|| state.workspaces.outputs().next().cloned())
+                else {
                     return;
                 };
                 let Some(geo) = state.workspaces.output_geometry(&output) else {
@@ -189,24 +236,19 @@ where
                 let abs_y = geo.loc.y as f64 + ny * geo.size.h as f64;
                 pending.motion_rel = None;
                 pending.motion_abs = Some((abs_x, abs_y));
+                pending.time = time;
             }
             zwlr_virtual_pointer_v1::Request::Button {
                 time,
                 button,
                 state: btn_state,
             } => {
-                let btn_state = match btn_state.into_result() {
-                    Ok(wl_pointer::ButtonState::Pressed) => ButtonState::Pressed,
-                    Ok(wl_pointer::ButtonState::Released) => ButtonState::Released,
-                    _ => return,
+                let Ok(btn_state) = btn_state.into_result() else {
+                    return;
                 };
                 pending.buttons.push((time, button, btn_state));
             }
-            zwlr_virtual_pointer_v1::Request::Axis {
-                time: _,
-                axis,
-                value,
-            } => {
+            zwlr_virtual_pointer_v1::Request::Axis { time, axis, value } => {
                 let Ok(axis_kind) = axis.into_result() else {
                     return;
                 };
@@ -216,9 +258,10 @@ where
                     _ => return,
                 };
                 let frame = pending.axis.take().unwrap_or_else(|| {
-                    AxisFrame::new(0).source(smithay::backend::input::AxisSource::Wheel)
+                    AxisFrame::new(time).source(smithay::backend::input::AxisSource::Wheel)
                 });
                 pending.axis = Some(frame.value(smithay_axis, value));
+                pending.time = time;
             }
             zwlr_virtual_pointer_v1::Request::AxisSource { axis_source } => {
                 let Ok(src) = axis_source.into_result() else {
@@ -238,7 +281,7 @@ where
                 let frame = pending.axis.take().unwrap_or_else(|| AxisFrame::new(0));
                 pending.axis = Some(frame.source(smithay_src));
             }
-            zwlr_virtual_pointer_v1::Request::AxisStop { time: _, axis } => {
+            zwlr_virtual_pointer_v1::Request::AxisStop { time, axis } => {
                 let Ok(axis_kind) = axis.into_result() else {
                     return;
                 };
@@ -247,11 +290,12 @@ where
                     wl_pointer::Axis::HorizontalScroll => Axis::Horizontal,
                     _ => return,
                 };
-                let frame = pending.axis.take().unwrap_or_else(|| AxisFrame::new(0));
+                let frame = pending.axis.take().unwrap_or_else(|| AxisFrame::new(time));
                 pending.axis = Some(frame.stop(smithay_axis));
+                pending.time = time;
             }
             zwlr_virtual_pointer_v1::Request::AxisDiscrete {
-                time: _,
+                time,
                 axis,
                 value,
                 discrete,
@@ -264,12 +308,13 @@ where
                     wl_pointer::Axis::HorizontalScroll => Axis::Horizontal,
                     _ => return,
                 };
-                let frame = pending.axis.take().unwrap_or_else(|| AxisFrame::new(0));
+                let frame = pending.axis.take().unwrap_or_else(|| AxisFrame::new(time));
                 pending.axis = Some(
                     frame
                         .value(smithay_axis, value)
                         .v120(smithay_axis, discrete * 120),
                 );
+                pending.time = time;
             }
             zwlr_virtual_pointer_v1::Request::Frame => {
                 // Flush the accumulated events in order:
@@ -279,14 +324,35 @@ where
                 let motion_abs = pending.motion_abs.take();
                 let buttons = std::mem::take(&mut pending.buttons);
                 let axis = pending.axis.take();
+                let time = pending.time;
                 drop(pending);
 
+                // Nothing synthesized reaches a locked session
+                if state.session_locked {
+                    return;
+                }
+
+                if data.on_virtual_seat {
+                    let location = state
+                        .virtual_seat
+                        .as_ref()
+                        .and_then(Seat::get_pointer)
+                        .map(|pointer| pointer.current_location())
+                        .unwrap_or_default();
+                    if let Some((ax, ay)) = motion_abs {
+                        state.virtual_seat_motion_to(Point::from((ax, ay)), time);
+                    } else if let Some((dx, dy)) = motion_rel {
+                        state.virtual_seat_motion_to(location + Point::from((dx, dy)), time);
+                    }
+                    for (time, button, btn_state) in buttons {
+                        state.virtual_seat_button(button, btn_state, time);
+                    }
+                    state.virtual_seat_axis(axis);
+                    return;
+                }
+
                 let pointer = state.pointer.clone();
 
-                // Compute the new absolute location. We deliberately don't
-                // clamp to screen bounds for synthesized events — if the test
-                // harness wants to drive the cursor off-screen, that's its
-                // problem, and we save a dependency on `clamp_coords`.
                 let mut new_location = pointer.current_location();
                 if let Some((ax, ay)) = motion_abs {
                     new_location = Point::from((ax, ay));
@@ -295,43 +361,11 @@ where
                 }
 
                 if motion_rel.is_some() || motion_abs.is_some() {
-                    let under = state.surface_under(new_location);
-                    let serial = SERIAL_COUNTER.next_serial();
-                    pointer.motion(
-                        state,
-                        under,
-                        &MotionEvent {
-                            location: new_location,
-                            serial,
-                            time: 0,
-                        },
-                    );
+                    state.pointer_motion_to(new_location, time);
                 }
 
                 for (time, button, btn_state) in buttons {
-                    let serial = SERIAL_COUNTER.next_serial();
-                    // Mirror the click-to-focus / raise behavior that
-                    // `on_pointer_button` does for real libinput events, so
-                    // virtual-pointer clicks also focus the window under the
-                    // cursor. Without this, clicks would dispatch to whatever
-                    // surface already held pointer focus instead of the one
-                    // the test harness intended to click on.
-                    if btn_state == ButtonState::Pressed && !state.workspaces.get_show_all() {
-                        state.focus_window_under_cursor(serial);
-                    }
-                    pointer.button(
-                        state,
-                        &ButtonEvent {
-                            button,
-                            state: btn_state,
-                            serial,
-                            time,
-                        },
-                    );
-                    match btn_state {
-                        ButtonState::Pressed => state.layers_engine.pointer_button_down(),
-                        ButtonState::Released => state.layers_engine.pointer_button_up(),
-                    }
+                    state.pointer_button(button, btn_state, time, false);
                 }
 
                 if let Some(axis_frame) = axis {
@@ -339,6 +373,8 @@ where
                 }
 
                 pointer.frame(state);
+                let seat = state.seat_name.clone();
+                state.cursor_pointer_input(&seat);
             }
             zwlr_virtual_pointer_v1::Request::Destroy => {}
             _ => {}
@@ -0,0 +1,156 @@
+//! A second, named `wl_seat` for synthesized input, enabled by
+//! `[input] virtual_seat`.
+//!
+//! Virtual keyboards and pointers created on this seat drive their own
+//! pointer and keyboard focus, so automation does not move the physical
+//! cursor or steal the physical keyboard focus. Virtual keyboard events
+//! arrive on the keyboard of the seat the client picked and are forwarded
+//! by `state::virtual_keyboard_handler`; virtual pointers are routed here by
+//! `state::virtual_pointer`.
+//!
+//! Its cursor is drawn next to the physical one once its pointer has
+//! moved. The seat does not match compositor shortcuts, and outputs and
+//! window stacking are shared with the physical seat: clicking with the
+//! virtual pointer focuses a window for the virtual keyboard but does not
+//! raise or activate it.
+
+use smithay::{
+    input::{
+        keyboard::{KeyboardHandle, XkbConfig},
+        pointer::{AxisFrame, ButtonEvent, MotionEvent},
+        Seat, SeatState,
+    },
+    reexports::wayland_server::{protocol::wl_pointer, DisplayHandle},
+    utils::{Logical, Point, SERIAL_COUNTER},
+};
+use tracing::{info, warn};
+
+use crate::{config::Config, focus::KeyboardFocusTarget};
+
+use super::{Backend, Otto};
+
+/// Create the virtual seat named in the config, unless it is unset or
+/// clashes with the physical seat
+pub(super) fn new_virtual_seat<BackendData: Backend + 'static>(
+    seat_state: &mut SeatState<Otto<BackendData>>,
+    dh: &DisplayHandle,
+    physical_seat: &str,
+    xkb_config: XkbConfig<'_>,
+) -> Option<Seat<Otto<BackendData>>> {
+    let name = Config::with(|c| c.input.virtual_seat.clone())?;
+    if name.is_empty() || name == physical_seat {
+        warn!("Ignoring virtual seat name {name:?}: it must differ from {physical_seat:?}");
+        return None;
+    }
+    let (repeat_delay, repeat_rate) =
+        Config::with(|c| (c.keyboard_repeat_delay, c.keyboard_repeat_rate));
+
+    let mut seat = seat_state.new_wl_seat(dh, name.clone());
+    seat.add_pointer();
+    if let Err(err) = seat.add_keyboard(xkb_config, repeat_delay, repeat_rate) {
+        warn!("Failed to initialize the virtual seat keyboard: {err}");
+        return None;
+    }
+    info!("Virtual seat {name:?} created");
+    Some(seat)
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Whether `seat` is the virtual seat
+    pub(crate) fn is_virtual_seat(&self, seat: &Seat<Self>) -> bool {
+        self.virtual_seat.as_ref() == Some(seat)
+    }
+}
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    /// Move the virtual seat pointer to `location`, clamped to the outputs,
+    /// and update its hover. Its cursor is shown from the first motion on.
+    pub(crate) fn virtual_seat_motion_to(&mut self, location: Point<f64, Logical>, time: u32) {
+        let Some(seat) = self.virtual_seat.clone() else {
+            return;
+        };
+        let Some(pointer) = seat.get_pointer() else {
+            return;
+        };
+        self.cursor_manager.add_seat(seat.name());
+        let location = self.clamp_coords(location);
+        let under = self.surface_under(location);
+        pointer.motion(
+            self,
+            under,
+            &MotionEvent {
+                location,
+                serial: SERIAL_COUNTER.next_serial(),
+                time,
+            },
+        );
+
+        // Redraw to move its cursor
+        self.schedule_event_loop_dispatch();
+    }
+
+    /// Press or release `button` on the virtual seat pointer. A press gives
+    /// the virtual seat keyboard focus to the window under its pointer.
+    pub(crate) fn virtual_seat_button(
+        &mut self,
+        button: u32,
+        state: wl_pointer::ButtonState,
+        time: u32,
+    ) {
+        let Some(seat) = self.virtual_seat.clone() else {
+            return;
+        };
+        let (Some(pointer), Some(keyboard)) = (seat.get_pointer(), seat.get_keyboard()) else {
+            return;
+        };
+        let Ok(button_state) = state.try_into() else {
+            return;
+        };
+        let serial = SERIAL_COUNTER.next_serial();
+
+        if state == wl_pointer::ButtonState::Pressed
+            && !pointer.is_grabbed()
+            && !keyboard.is_grabbed()
+        {
+            let window = self
+                .workspaces
+                .element_under(pointer.current_location())
+                .map(|(window, _)| window.clone());
+            if let Some(window) = window {
+                keyboard.set_focus(self, Some(KeyboardFocusTarget::from(window)), serial);
+            }
+        }
+
+        pointer.button(
+            self,
+            &ButtonEvent {
+                button,
+                state: button_state,
+                serial,
+                time,
+            },
+        );
+    }
+
+    /// Scroll on the virtual seat pointer and end the pointer frame
+    pub(crate) fn virtual_seat_axis(&mut self, axis: Option<AxisFrame>) {
+        let Some(pointer) = self.virtual_seat.as_ref().and_then(Seat::get_pointer) else {
+            return;
+        };
+        if let Some(axis) = axis {
+            pointer.axis(self, axis);
+        }
+        pointer.frame(self);
+        if let Some(seat) = self.virtual_seat.clone() {
+            self.cursor_pointer_input(seat.name());
+        }
+    }
+
+    /// Name of the seat `keyboard` belongs to
+    pub(crate) fn keyboard_seat_name(&self, keyboard: &KeyboardHandle<Self>) -> String {
+        match self.virtual_seat.as_ref() {
+            Some(seat) if seat.get_keyboard().as_ref() == Some(keyboard) => seat.name().to_string(),
+            _ => self.seat_name.clone(),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Compositor-drawn window frames: `window_corner_radius` clips every
+//! window's content to a rounded rect and `[window_border]` draws a border
+//! around it, whatever the client set through surface style.
+//!
+//! Frames follow the window geometry on every commit through
+//! `update_window_view`; this re-applies them when the config changes.
+
+use crate::shell::WindowElement;
+
+use super::{Backend, Otto};
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Re-apply the corner radius and border of every window
+    pub fn update_all_window_frames(&mut self) {
+        let windows: Vec<WindowElement> = self.workspaces.spaces_elements().cloned().collect();
+        for window in windows {
+            self.update_window_view(&window);
+            if let Some(view) = self.workspaces.get_window_view(&window.id()) {
+                view.set_border_active(self.is_keyboard_focused(&window));
+            }
+        }
+    }
+}
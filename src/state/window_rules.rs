@@ -0,0 +1,138 @@
+use smithay::{
+    reexports::wayland_protocols::xdg::shell::server::xdg_toplevel,
+    utils::{Point, Rectangle},
+};
+use tracing::{info, warn};
+
+use crate::{
+    config::{window_rules::find_window_rule, Config},
+    shell::WindowElement,
+};
+
+use super::{Backend, Otto};
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Apply the first matching `[[window_rules]]` entry to a toplevel.
+    ///
+    /// Called on the first commit, right before the initial configure: app id
+    /// and title are known by then and the configure carries the rule's size
+    /// and state to the client. Sandboxed clients are matched by their
+    /// sandbox app id.
+    pub fn apply_window_rules(&mut self, window: &WindowElement) {
+        let app_id = window.trusted_app_id(&self.display_handle);
+        let title = window.xdg_title();
+        let content_type = window.content_type();
+        let Some(rule) = Config::with(|c| {
+            find_window_rule(&c.window_rules, &app_id, &title, content_type).cloned()
+        }) else {
+            return;
+        };
+        info!(
+            "Applying window rule to '{}' ({}): {:?}",
+            app_id, title, rule
+        );
+
+        if let Some(floating) = rule.floating {
+            window.set_is_floating(floating);
+        }
+
+        if let Some(always_on_top) = rule.always_on_top {
+            self.workspaces
+                .set_window_always_on_top(window, always_on_top);
+        }
+
+        if let Some(opacity) = rule.opacity {
+            window.set_has_rule_opacity(true);
+            if let Some(surface) = window.wl_surface() {
+                self.get_or_create_layer_for_surface(&surface)
+                    .set_opacity(opacity.clamp(0.0, 1.0), None);
+            }
+        }
+
+        // Same output new_toplevel picked, unless the rule names another one
+        let pointer_location = self.pointer.current_location();
+        let current_output = self
+            .workspaces
+            .output_under(pointer_location)
+            .next()
+            .or_else(|| self.workspaces.primary_output())
+            .cloned();
+        let rule_output = rule.output.as_ref().and_then(|name| {
+            let output = self
+                .workspaces
+                .outputs()
+                .find(|o| o.name() == *name)
+                .cloned();
+            if output.is_none() {
+                warn!("Window rule output '{}' not found", name);
+            }
+            output
+        });
+        let moves_output = rule_output.is_some() && rule_output != current_output;
+        let Some(output) = rule_output.or(current_output) else {
+            return;
+        };
+
+        let mut location = self
+            .workspaces
+            .element_geometry(window)
+            .map(|geometry| geometry.loc)
+            .unwrap_or_default();
+        if moves_output {
+            if let Some(geometry) = self.workspaces.output_geometry(&output) {
+                let half_size = Point::from((geometry.size.w / 2, geometry.size.h / 2));
+                let center = (geometry.loc + half_size).to_f64();
+                let (bounds, placement) = self.workspaces.new_window_placement_at(center);
+                location = placement;
+                if let Some(toplevel) = window.toplevel() {
+                    toplevel.with_pending_state(|state| state.bounds = Some(bounds.size));
+                }
+            }
+        }
+
+        let maximize = rule.maximized == Some(true);
+        if maximize {
+            if let Some(toplevel) = window.toplevel() {
+                self.recalculate_exclusive_zones(&output);
+                let usable = self.usable_zone(&output);
+                toplevel.with_pending_state(|state| {
+                    state.states.set(xdg_toplevel::State::Maximized);
+                    state.size = Some(usable.size);
+                });
+                // Unmaximizing hands the size back to the client
+                let id = window.id();
+                if let Some(mut view) = self.workspaces.get_window_view(&id) {
+                    view.unmaximised_rect = Rectangle::new(location, (0, 0).into());
+                    self.workspaces.set_window_view(&id, view);
+                }
+                location = usable.loc;
+            }
+        }
+
+        let num_workspaces = self.workspaces.with_model(|m| m.workspaces.len());
+        let current_workspace = self.workspaces.get_current_workspace_index();
+        match rule.workspace {
+            Some(index) if index >= num_workspaces => {
+                warn!(
+                    "Window rule workspace {} out of range ({} workspaces)",
+                    index, num_workspaces
+                );
+            }
+            Some(index) if moves_output => {
+                self.workspaces
+                    .move_window_to_output_workspace(&output, index, window, location);
+                return;
+            }
+            Some(index) if index != current_workspace => {
+                self.workspaces
+                    .move_window_to_workspace(window, index, location);
+                return;
+            }
+            _ => {}
+        }
+        if moves_output || maximize {
+            self.workspaces
+                .move_window_to_output(&output, window, location, true);
+        }
+    }
+}
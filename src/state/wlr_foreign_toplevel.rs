@@ -259,6 +259,10 @@ impl<BackendData: Backend> GlobalDispatch<ZwlrForeignToplevelManagerV1, (), Otto
             }
         }
     }
+
+    fn can_view(client: Client, _global_data: &()) -> bool {
+        crate::state::is_privileged(&client)
+    }
 }
 
 // Implement Dispatch for manager
@@ -326,7 +330,7 @@ impl<BackendData: Backend>
             }
             zwlr_foreign_toplevel_handle_v1::Request::SetMinimized => {
                 if let Some(window) = state.workspaces.get_window_for_surface(&window_id).cloned() {
-                    state.workspaces.minimize_window(&window);
+                    state.minimize_window(&window);
                 }
             }
             zwlr_foreign_toplevel_handle_v1::Request::UnsetMinimized => {
@@ -372,4 +376,16 @@ impl<BackendData: Backend>
             _ => {}
         }
     }
+
+    fn destroyed(
+        _state: &mut Otto<BackendData>,
+        _client: wayland_server::backend::ClientId,
+        resource: &ZwlrForeignToplevelHandleV1,
+        data: &Arc<Mutex<WlrToplevelData>>,
+    ) {
+        data.lock()
+            .unwrap()
+            .resources
+            .retain(|r| r.id() != resource.id());
+    }
 }
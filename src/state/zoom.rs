@@ -0,0 +1,312 @@
+//! Full-screen magnifier: scales the scene of every output around the
+//! pointer, driven by the ToggleZoom, ZoomIn and ZoomOut actions.
+//!
+//! The zoom is applied when the output's scene is drawn, so nothing in the
+//! layer tree changes and input keeps using scene coordinates. The cursor is
+//! drawn unscaled at its magnified position, so it stays sharp. The level
+//! eases towards its target every frame; the magnified region follows the
+//! pointer as configured by `[zoom] edge_mode`.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use smithay::{
+    output::Output,
+    utils::{Logical, Point},
+};
+use tracing::info;
+
+use crate::{
+    config::{Config, ZoomEdgeMode},
+    render_elements::scene_element::SceneMirror,
+};
+
+use super::{Backend, Otto};
+
+/// Levels closer than this to their target count as settled
+const LEVEL_EPSILON: f64 = 0.005;
+
+/// Magnified region of one output
+#[derive(Debug, Clone, Copy)]
+struct ZoomView {
+    /// Top-left of the region, in output-local logical coordinates
+    origin: (f64, f64),
+    /// Level the origin was computed for
+    level: f64,
+}
+
+#[derive(Debug)]
+pub struct Magnifier {
+    target: f64,
+    level: f64,
+    views: HashMap<String, ZoomView>,
+    last_tick: Option<Instant>,
+}
+
+impl Default for Magnifier {
+    fn default() -> Self {
+        Self {
+            target: 1.0,
+            level: 1.0,
+            views: HashMap::new(),
+            last_tick: None,
+        }
+    }
+}
+
+impl Magnifier {
+    /// Whether the scene is magnified or animating towards a zoom
+    pub fn is_active(&self) -> bool {
+        self.level > 1.0 || self.target > 1.0
+    }
+
+    fn set_target(&mut self, level: f64, max_level: f64) {
+        self.target = level.clamp(1.0, max_level.max(1.0));
+        self.last_tick = None;
+    }
+
+    /// Ease the level towards its target. Returns `true` while animating.
+    fn tick(&mut self, now: Instant, smoothing: Duration) -> bool {
+        let dt = self
+            .last_tick
+            .map(|last| now.saturating_duration_since(last))
+            .unwrap_or_default();
+        self.last_tick = Some(now);
+        self.level = ease_level(self.level, self.target, dt, smoothing);
+        if !self.is_active() {
+            self.views.clear();
+        }
+        self.level != self.target
+    }
+
+    /// Move the region of `output` to follow the pointer at `pointer`
+    /// (output-local), on an output of logical `size`
+    fn follow(&mut self, output: &str, pointer: (f64, f64), size: (f64, f64), mode: ZoomEdgeMode) {
+        let level = self.level;
+        let view = self.views.entry(output.to_string()).or_insert(ZoomView {
+            origin: (0.0, 0.0),
+            level: 1.0,
+        });
+        view.origin = (
+            follow_axis(view.origin.0, pointer.0, size.0, view.level, level, mode),
+            follow_axis(view.origin.1, pointer.1, size.1, view.level, level, mode),
+        );
+        view.level = level;
+    }
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    pub fn toggle_zoom(&mut self) {
+        let config = Config::with(|c| c.zoom.clone());
+        let level = if self.magnifier.target > 1.0 {
+            1.0
+        } else {
+            config.toggle_level
+        };
+        self.set_zoom(level);
+    }
+
+    pub fn zoom_in(&mut self) {
+        let config = Config::with(|c| c.zoom.clone());
+        self.set_zoom(self.magnifier.target * config.step.max(1.0));
+    }
+
+    pub fn zoom_out(&mut self) {
+        let config = Config::with(|c| c.zoom.clone());
+        let level = self.magnifier.target / config.step.max(1.0);
+        // Do not get stuck just above 1x after a few steps
+        self.set_zoom(if level < 1.0 + LEVEL_EPSILON {
+            1.0
+        } else {
+            level
+        });
+    }
+
+    fn set_zoom(&mut self, level: f64) {
+        let max_level = Config::with(|c| c.zoom.max_level);
+        self.magnifier.set_target(level, max_level);
+        info!(level = self.magnifier.target, "Zoom level changed");
+        self.backend_data.request_redraw();
+    }
+
+    /// Advance the zoom animation and move the magnified regions with the
+    /// pointer; called before each frame
+    pub fn tick_zoom(&mut self) {
+        if !self.magnifier.is_active() {
+            return;
+        }
+        let config = Config::with(|c| c.zoom.clone());
+        let smoothing = Duration::from_millis(config.smoothing_ms as u64);
+        let animating = self.magnifier.tick(Instant::now(), smoothing);
+        if !self.magnifier.is_active() {
+            // Back at 1x: draw one last unzoomed frame
+            self.backend_data.request_redraw();
+            return;
+        }
+
+        let pointer = self.pointer.current_location();
+        let outputs: Vec<Output> = self.workspaces.outputs().cloned().collect();
+        for output in outputs {
+            let Some(geometry) = self.workspaces.output_geometry(&output) else {
+                continue;
+            };
+            if !geometry.to_f64().contains(pointer)
+                && self.magnifier.views.contains_key(&output.name())
+            {
+                continue;
+            }
+            let local = pointer - geometry.loc.to_f64();
+            let local = (
+                local.x.clamp(0.0, geometry.size.w as f64),
+                local.y.clamp(0.0, geometry.size.h as f64),
+            );
+            self.magnifier.follow(
+                &output.name(),
+                local,
+                (geometry.size.w as f64, geometry.size.h as f64),
+                config.edge_mode,
+            );
+        }
+
+        if animating {
+            self.backend_data.request_redraw();
+        }
+    }
+
+    /// Transform drawing the magnified region of `output`, or `None` when
+    /// the scene is not magnified
+    pub fn zoom_scene_mirror(&self, output: &Output) -> Option<SceneMirror> {
+        let level = self.magnifier.level;
+        if level <= 1.0 {
+            return None;
+        }
+        let view = self.magnifier.views.get(&output.name())?;
+        let scale = output.current_scale().fractional_scale();
+        let size = output.current_mode()?.size;
+        Some(SceneMirror {
+            scale: level as f32,
+            offset_x_px: (-view.origin.0 * scale * level) as f32,
+            offset_y_px: (-view.origin.1 * scale * level) as f32,
+            width_px: size.w,
+            height_px: size.h,
+        })
+    }
+
+    /// Where the cursor at `pointer` is shown on `output` once magnified
+    pub fn zoom_cursor_location(
+        &self,
+        output: &Output,
+        pointer: Point<f64, Logical>,
+    ) -> Point<f64, Logical> {
+        let level = self.magnifier.level;
+        let (Some(view), Some(geometry)) = (
+            self.magnifier.views.get(&output.name()),
+            self.workspaces.output_geometry(output),
+        ) else {
+            return pointer;
+        };
+        if level <= 1.0 {
+            return pointer;
+        }
+        let origin = geometry.loc.to_f64();
+        let local = pointer - origin;
+        origin
+            + Point::from((
+                (local.x - view.origin.0) * level,
+                (local.y - view.origin.1) * level,
+            ))
+    }
+}
+
+/// Level after easing from `level` towards `target` for `dt`, with
+/// exponential smoothing of time constant `smoothing`
+fn ease_level(level: f64, target: f64, dt: Duration, smoothing: Duration) -> f64 {
+    if smoothing.is_zero() {
+        return target;
+    }
+    let remaining = (-dt.as_secs_f64() / smoothing.as_secs_f64()).exp();
+    let level = target + (level - target) * remaining;
+    if (level - target).abs() < LEVEL_EPSILON {
+        target
+    } else {
+        level
+    }
+}
+
+/// Origin of the magnified region along one axis, so that it follows
+/// `pointer` on an output of length `size`. In push mode the point under the
+/// pointer keeps its place on screen while the level changes from
+/// `old_level` to `level`.
+fn follow_axis(
+    origin: f64,
+    pointer: f64,
+    size: f64,
+    old_level: f64,
+    level: f64,
+    mode: ZoomEdgeMode,
+) -> f64 {
+    let visible = size / level;
+    let origin = match mode {
+        ZoomEdgeMode::Center => pointer - visible / 2.0,
+        ZoomEdgeMode::Push => {
+            let on_screen = (pointer - origin) * old_level;
+            (pointer - on_screen / level).clamp(pointer - visible, pointer)
+        }
+    };
+    origin.clamp(0.0, size - visible)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_eases_and_settles() {
+        let smoothing = Duration::from_millis(100);
+        let halfway = ease_level(1.0, 3.0, Duration::from_millis(100), smoothing);
+        assert!(halfway > 2.0 && halfway < 2.5, "level {halfway}");
+        assert_eq!(ease_level(2.999, 3.0, Duration::ZERO, smoothing), 3.0);
+        assert_eq!(ease_level(1.0, 3.0, Duration::ZERO, Duration::ZERO), 3.0);
+    }
+
+    #[test]
+    fn zooming_in_keeps_the_point_under_the_pointer() {
+        // From 1x, the pointer at 300 of 1000 stays at 300 on screen
+        let origin = follow_axis(0.0, 300.0, 1000.0, 1.0, 2.0, ZoomEdgeMode::Push);
+        assert_eq!(origin, 150.0);
+        assert_eq!((300.0 - origin) * 2.0, 300.0);
+    }
+
+    #[test]
+    fn push_moves_only_at_the_edges() {
+        // Region [100, 600) at 2x: moving inside it does not scroll
+        assert_eq!(
+            follow_axis(100.0, 400.0, 1000.0, 2.0, 2.0, ZoomEdgeMode::Push),
+            100.0
+        );
+        // Past the right edge the region is pushed along
+        assert_eq!(
+            follow_axis(100.0, 700.0, 1000.0, 2.0, 2.0, ZoomEdgeMode::Push),
+            200.0
+        );
+        // And never leaves the output
+        assert_eq!(
+            follow_axis(500.0, 1000.0, 1000.0, 2.0, 2.0, ZoomEdgeMode::Push),
+            500.0
+        );
+    }
+
+    #[test]
+    fn center_keeps_the_pointer_in_the_middle() {
+        assert_eq!(
+            follow_axis(0.0, 500.0, 1000.0, 2.0, 2.0, ZoomEdgeMode::Center),
+            250.0
+        );
+        assert_eq!(
+            follow_axis(0.0, 100.0, 1000.0, 2.0, 2.0, ZoomEdgeMode::Center),
+            0.0
+        );
+    }
+}
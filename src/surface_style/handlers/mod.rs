@@ -3,11 +3,13 @@ use smithay::reexports::wayland_server::{
 };
 
 use crate::{
+    config::Config,
     state::Backend,
     surface_style::gen::otto_surface_style_manager_v1::{self, OttoSurfaceStyleManagerV1},
     Otto,
 };
 use layers::prelude::{Spring, TimingFunction, Transition};
+use std::sync::{Arc, Mutex};
 
 use super::protocol::{SurfaceStyle, SurfaceStyleHandler};
 
@@ -67,12 +69,29 @@ fn commit_transaction<BackendData: Backend>(
     state: &mut Otto<BackendData>,
     txn_id: smithay::reexports::wayland_server::backend::ObjectId,
 ) {
-    let Some(txn) = state.style_transactions.get_mut(&txn_id) else {
+    // A committed transaction is never looked up again: the completion callback
+    // keeps its own handle on the protocol object, so drop it from the map now.
+    let Some(mut txn) = state.style_transactions.remove(&txn_id) else {
         return;
     };
 
     txn.committed = true;
 
+    for style in state.surfaces_style.values_mut().flatten() {
+        if let Some(translation) = txn.pending_translations.get(&style.wl_style.id()) {
+            style.translation = *translation;
+        }
+    }
+
+    let completion = txn.send_completion.then(|| {
+        let wl_txn = txn.wl_style_transaction.clone();
+        Completion::new(move || {
+            if wl_txn.is_alive() {
+                wl_txn.completed();
+            }
+        })
+    });
+
     // Use client-configured timing function, or create default from duration
     let mut transition = if let Some(mut trans) = txn.timing_function.take() {
         // Update timing function duration (timing functions are created with 0.0 duration)
@@ -135,68 +154,83 @@ fn commit_transaction<BackendData: Backend>(
         }
     }
 
-    // Schedule all accumulated changes together
-    if !txn.accumulated_changes.is_empty() {
-        if let Some(ref trans) = transition {
-            // Collect gravity info for diagnostics (before mutable borrow of state)
-            let gravities: Vec<_> = state
-                .surfaces_style
-                .values()
-                .flatten()
-                .map(|s| (s.wl_style.id(), s.contents_gravity))
-                .collect();
-            tracing::debug!(
-                "Committing animation: {} changes, duration={:?}s, delay={:?}s, timing={:?}, surface_gravities={:?}",
-                txn.accumulated_changes.len(),
-                txn.duration,
-                txn.delay,
-                trans.timing,
-                gravities,
-            );
-            // Create animation and store it in the transaction
-            let animation = state
-                .layers_engine
-                .add_animation_from_transition(trans, false);
-
-            txn.animation = Some(animation);
-
-            state
-                .layers_engine
-                .schedule_changes(&txn.accumulated_changes, animation);
-
-            // Add on_finish callback if completion event requested
-            if txn.send_completion {
-                let wl_txn = txn.wl_style_transaction.clone();
-                state.layers_engine.on_animation_finish(
-                    animation,
-                    move |_| {
-                        wl_txn.completed();
-                    },
-                    false,
-                );
-            }
+    // Without a duration the changes still go through a zero-length animation,
+    // so they apply on the next frame and completion has a single path.
+    // With reduce_motion the client's timing collapses to an instant change.
+    let trans = Config::transition_or_instant(transition.unwrap_or_else(|| Transition {
+        delay: txn.delay.unwrap_or(0.0),
+        ..Transition::linear(0.0)
+    }));
+
+    // Collect gravity info for diagnostics
+    let gravities: Vec<_> = state
+        .surfaces_style
+        .values()
+        .flatten()
+        .map(|s| (s.wl_style.id(), s.contents_gravity))
+        .collect();
+    tracing::debug!(
+        "Committing animation: {} changes, duration={:?}s, delay={:?}s, timing={:?}, surface_gravities={:?}",
+        txn.accumulated_changes.len(),
+        txn.duration,
+        txn.delay,
+        trans.timing,
+        gravities,
+    );
+    schedule_transaction(
+        &state.layers_engine,
+        &txn.accumulated_changes,
+        &trans,
+        completion,
+    );
+}
 
-            state.layers_engine.start_animation(animation, trans.delay);
-        } else {
-            tracing::debug!(
-                "Committing {} changes immediately (no animation)",
-                txn.accumulated_changes.len()
-            );
-            // No animation - send completion immediately if requested
-            if txn.send_completion {
-                if let Some(txn) = state.style_transactions.remove(&txn_id) {
-                    txn.wl_style_transaction.completed();
-                }
-            }
+/// Schedule `changes` as a single animation with `transition` and fire
+/// `completion` exactly once: right away when there is nothing to animate,
+/// otherwise when the animation finishes.
+fn schedule_transaction(
+    engine: &layers::engine::Engine,
+    changes: &[layers::engine::AnimatedNodeChange],
+    transition: &Transition,
+    completion: Option<Completion>,
+) {
+    if changes.is_empty() {
+        tracing::debug!("No changes, completing transaction immediately");
+        if let Some(completion) = completion {
+            completion.fire();
         }
-        // If no transition, changes were already applied immediately via set_* methods
-    } else {
-        // No changes - send completion immediately if requested
-        if txn.send_completion {
-            tracing::info!("No changes, sending completed event immediately");
-            if let Some(txn) = state.style_transactions.remove(&txn_id) {
-                txn.wl_style_transaction.completed();
-            }
+        return;
+    }
+
+    let animation = engine.add_animation_from_transition(transition, false);
+    engine.schedule_changes(changes, animation);
+
+    if let Some(completion) = completion {
+        engine.on_animation_finish(
+            animation,
+            move |_| {
+                completion.fire();
+            },
+            true,
+        );
+    }
+
+    engine.start_animation(animation, transition.delay);
+}
+
+/// Sends the `completed` event of a committed transaction exactly once,
+/// whichever path finishes it.
+struct Completion(Arc<Mutex<Option<Box<dyn FnOnce() + Send>>>>);
+
+impl Completion {
+    fn new(send: impl FnOnce() + Send + 'static) -> Self {
+        Self(Arc::new(Mutex::new(Some(Box::new(send)))))
+    }
+
+    fn fire(&self) {
+        let send = self.0.lock().unwrap().take();
+        if let Some(send) = send {
+            send();
         }
     }
 }
@@ -208,7 +242,7 @@ pub mod transactions;
 pub fn create_style_manager_global<BackendData: Backend + 'static>(
     display: &DisplayHandle,
 ) -> smithay::reexports::wayland_server::backend::GlobalId {
-    display.create_global::<Otto<BackendData>, OttoSurfaceStyleManagerV1, _>(1, ())
+    display.create_global::<Otto<BackendData>, OttoSurfaceStyleManagerV1, _>(2, ())
 }
 
 impl<BackendData: Backend> GlobalDispatch<OttoSurfaceStyleManagerV1, ()> for Otto<BackendData> {
@@ -222,6 +256,10 @@ impl<BackendData: Backend> GlobalDispatch<OttoSurfaceStyleManagerV1, ()> for Ott
     ) {
         data_init.init(resource, ());
     }
+
+    fn can_view(client: Client, _global_data: &()) -> bool {
+        crate::state::is_privileged(&client)
+    }
 }
 
 impl<BackendData: Backend> Dispatch<OttoSurfaceStyleManagerV1, ()> for Otto<BackendData> {
@@ -277,6 +315,7 @@ impl<BackendData: Backend> Dispatch<OttoSurfaceStyleManagerV1, ()> for Otto<Back
                     contents_gravity: crate::surface_style::ContentsGravity::default(),
                     shared_gravity: std::sync::Arc::new(std::sync::atomic::AtomicU8::new(0)),
                     client_owns_size: false,
+                    translation: (0.0, 0.0),
                 };
 
                 // Notify handler
@@ -298,7 +337,7 @@ impl<BackendData: Backend> Dispatch<OttoSurfaceStyleManagerV1, ()> for Otto<Back
                     spring_initial_velocity: 0.0,
                     send_completion: false,
                     accumulated_changes: Vec::new(),
-                    animation: None,
+                    pending_translations: Default::default(),
                     committed: false,
                 };
 
@@ -319,3 +358,92 @@ impl<BackendData: Backend> Dispatch<OttoSurfaceStyleManagerV1, ()> for Otto<Back
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn counting_completion() -> (Completion, Arc<AtomicUsize>) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = count.clone();
+        let completion = Completion::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        (completion, count)
+    }
+
+    #[test]
+    fn empty_transaction_completes_once() {
+        // Committing a transaction without changes fires right away; a later
+        // finish callback for the same transaction must not fire again.
+        let (completion, count) = counting_completion();
+        completion.fire();
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        completion.fire();
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn completion_waits_for_fire() {
+        let (completion, count) = counting_completion();
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+        drop(completion);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+    }
+
+    fn engine_with_layer() -> (Arc<layers::engine::Engine>, layers::prelude::Layer) {
+        let engine = layers::engine::Engine::create(500.0, 500.0);
+        let layer = engine.new_layer();
+        let _ = engine.add_layer(&layer);
+        (engine, layer)
+    }
+
+    #[test]
+    fn transaction_without_changes_completes_on_commit() {
+        let (engine, _layer) = engine_with_layer();
+        let (completion, count) = counting_completion();
+        schedule_transaction(&engine, &[], &Transition::linear(0.5), Some(completion));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        engine.update(1.0);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn transaction_without_duration_completes_on_next_frame() {
+        let (engine, layer) = engine_with_layer();
+        let (completion, count) = counting_completion();
+        let change = layer.change_position(layers::types::Point { x: 10.0, y: 20.0 });
+        schedule_transaction(
+            &engine,
+            &[change],
+            &Transition::linear(0.0),
+            Some(completion),
+        );
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+        engine.update(0.016);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        engine.update(0.016);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn animated_transaction_completes_when_it_finishes() {
+        let (engine, layer) = engine_with_layer();
+        let (completion, count) = counting_completion();
+        let change = layer.change_position(layers::types::Point { x: 10.0, y: 20.0 });
+        schedule_transaction(
+            &engine,
+            &[change],
+            &Transition::linear(0.5),
+            Some(completion),
+        );
+        engine.update(0.1);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+        engine.update(0.5);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        engine.update(0.5);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+}
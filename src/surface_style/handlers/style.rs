@@ -47,8 +47,15 @@ impl<BackendData: Backend> Dispatch<OttoSurfaceStyleV1, OttoLayerUserData> for O
 
         match request {
             otto_surface_style_v1::Request::SetPosition { x, y } => {
-                let x = wl_fixed_to_f32(x);
-                let y = wl_fixed_to_f32(y);
+                // Keep the translation set through set_transform_2d on top
+                // of the new position, as the compositor does on commit.
+                let translation = active_transaction
+                    .as_ref()
+                    .and_then(|txn_id| state.style_transactions.get(txn_id))
+                    .and_then(|txn| txn.pending_translations.get(&layer_id).copied())
+                    .unwrap_or(sstyle.translation);
+                let x = wl_fixed_to_f32(x) + translation.0;
+                let y = wl_fixed_to_f32(y) + translation.1;
 
                 if let Some(txn_id) = active_transaction {
                     // Accumulate change in transaction
@@ -99,6 +106,70 @@ impl<BackendData: Backend> Dispatch<OttoSurfaceStyleV1, OttoLayerUserData> for O
                 }
             }
 
+            otto_surface_style_v1::Request::SetRotation { angle } => {
+                let angle = wl_fixed_to_f32(angle);
+
+                if let Some(txn_id) = active_transaction {
+                    let change = sstyle.layer.change_rotation(angle);
+                    accumulate_change(state, txn_id, change);
+                } else {
+                    sstyle.layer.set_rotation(angle, None);
+                    trigger_window_update(state, &sstyle.surface.id());
+                }
+            }
+
+            otto_surface_style_v1::Request::SetTransform2d {
+                scale_x,
+                scale_y,
+                rotation,
+                translate_x,
+                translate_y,
+            } => {
+                let scale = layers::types::Point {
+                    x: wl_fixed_to_f32(scale_x),
+                    y: wl_fixed_to_f32(scale_y),
+                };
+                let rotation = wl_fixed_to_f32(rotation);
+                let translation = (wl_fixed_to_f32(translate_x), wl_fixed_to_f32(translate_y));
+
+                // Move the layer from its untranslated position by the new
+                // translation; the compositor adds the stored translation when it
+                // places the surface again on commit. A transaction stores it only
+                // once committed, so repeated or cancelled calls keep the base.
+                let current = sstyle.layer.render_position();
+                let position = layers::types::Point {
+                    x: current.x - sstyle.translation.0 + translation.0,
+                    y: current.y - sstyle.translation.1 + translation.1,
+                };
+                let surface_id = sstyle.surface.id();
+
+                if let Some(txn_id) = active_transaction {
+                    let layer = sstyle.layer.clone();
+                    let scale_change = layer.change_scale(scale);
+                    let rotation_change = layer.change_rotation(rotation);
+                    let position_change = layer.change_position(position);
+
+                    if let Some(txn) = state.style_transactions.get_mut(&txn_id) {
+                        txn.pending_translations
+                            .insert(layer_id.clone(), translation);
+                    }
+                    accumulate_change(state, txn_id.clone(), scale_change);
+                    accumulate_change(state, txn_id.clone(), rotation_change);
+                    accumulate_change(state, txn_id, position_change);
+                } else {
+                    if let Some(style_list) = state.surfaces_style.get_mut(&surface_id) {
+                        if let Some(s) = style_list.iter_mut().find(|l| l.wl_style.id() == layer_id)
+                        {
+                            s.translation = translation;
+                        }
+                    }
+                    sstyle.layer.set_scale(scale, None);
+                    sstyle.layer.set_rotation(rotation, None);
+                    sstyle.layer.set_position(position, None);
+                    trigger_window_update(state, &surface_id);
+                }
+            }
+
             otto_surface_style_v1::Request::SetAnchorPoint { x, y } => {
                 let x = wl_fixed_to_f32(x);
                 let y = wl_fixed_to_f32(y);
@@ -163,6 +234,32 @@ impl<BackendData: Backend> Dispatch<OttoSurfaceStyleV1, OttoLayerUserData> for O
                 }
             }
 
+            otto_surface_style_v1::Request::SetBlurRadius { radius } => {
+                let radius = wl_fixed_to_f32(radius).max(0.0);
+                let screen_scale = Config::with(|c| c.screen_scale) as f32;
+                let scaled_radius = radius * screen_scale;
+
+                if let Some(txn_id) = active_transaction {
+                    let change = sstyle.layer.change_background_blur_radius(scaled_radius);
+                    accumulate_change(state, txn_id, change);
+                } else {
+                    sstyle.layer.set_background_blur_radius(scaled_radius, None);
+                    trigger_window_update(state, &sstyle.surface.id());
+                }
+            }
+
+            otto_surface_style_v1::Request::SetBackdropSaturation { saturation } => {
+                let saturation = wl_fixed_to_f32(saturation).max(0.0);
+
+                if let Some(txn_id) = active_transaction {
+                    let change = sstyle.layer.change_background_saturation(saturation);
+                    accumulate_change(state, txn_id, change);
+                } else {
+                    sstyle.layer.set_background_saturation(saturation, None);
+                    trigger_window_update(state, &sstyle.surface.id());
+                }
+            }
+
             otto_surface_style_v1::Request::SetBorder {
                 width,
                 red,
@@ -60,14 +60,9 @@ impl<BackendData: Backend> Dispatch<OttoStyleTransactionV1, ()> for Otto<Backend
             }
 
             otto_style_transaction_v1::Request::Cancel => {
-                // Cancel the transaction - discard all pending changes without applying them
-                let transaction = state.style_transactions.remove(&txn_id);
-                if let Some(transaction) = transaction {
-                    if let Some(animation) = transaction.animation {
-                        state.layers_engine.cancel_animation(animation);
-                    }
-                }
-                // state.transactions.remove(&txn_id);
+                // Discard the pending changes of an uncommitted transaction. A committed
+                // one already left `style_transactions` and keeps animating.
+                state.style_transactions.remove(&txn_id);
             }
         }
     }
@@ -85,6 +85,10 @@ pub struct SurfaceStyle {
     /// When true, the client has called set_size at least once and now owns the layer bounds.
     /// The compositor will no longer override size/position from the buffer.
     pub client_owns_size: bool,
+
+    /// Offset from `set_transform_2d`, added to the layer position wherever it
+    /// comes from (compositor placement or `set_position`).
+    pub translation: (f32, f32),
 }
 
 impl PartialEq for SurfaceStyle {
@@ -122,7 +126,10 @@ pub struct StyleTransaction {
     /// Accumulated layer changes ready for scheduling
     pub accumulated_changes: Vec<layers::engine::AnimatedNodeChange>,
 
-    pub animation: Option<layers::engine::AnimationRef>,
+    /// `set_transform_2d` translations by surface style, stored on the
+    /// surface styles when the transaction commits
+    pub pending_translations:
+        std::collections::HashMap<wayland_server::backend::ObjectId, (f32, f32)>,
 
     /// Whether this transaction has already been committed (prevents re-use after commit)
     pub committed: bool,
@@ -140,7 +147,7 @@ impl Clone for StyleTransaction {
             spring_initial_velocity: self.spring_initial_velocity,
             send_completion: self.send_completion,
             accumulated_changes: self.accumulated_changes.clone(),
-            animation: self.animation,
+            pending_translations: self.pending_translations.clone(),
             committed: self.committed,
         }
     }
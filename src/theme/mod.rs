@@ -1,3 +1,7 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
 use layers::skia::{
     font_style::{Slant, Width},
     textlayout::TextStyle,
@@ -5,7 +9,9 @@ use layers::skia::{
 };
 use layers::types::Color;
 use once_cell::sync::Lazy;
+use otto_kit::components::context_menu::ContextMenuStyle;
 use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
 
 use crate::config::Config;
 
@@ -46,110 +52,312 @@ macro_rules! define_text_styles {
         }
     };
 }
-#[allow(unused)]
-pub struct ThemeColors {
-    pub accents_red: Color,
-    pub accents_orange: Color,
-    pub accents_yellow: Color,
-    pub accents_green: Color,
-    pub accents_mint: Color,
-    pub accents_teal: Color,
-    pub accents_cyan: Color,
-    pub accents_blue: Color,
-    pub accents_indigo: Color,
-    pub accents_purple: Color,
-    pub accents_pink: Color,
-    pub accents_gray: Color,
-    pub accents_brown: Color,
-    pub accents_vibrant_red: Color,
-    pub accents_vibrant_orange: Color,
-    pub accents_vibrant_yellow: Color,
-    pub accents_vibrant_green: Color,
-    pub accents_vibrant_mint: Color,
-    pub accents_vibrant_teal: Color,
-    pub accents_vibrant_cyan: Color,
-    pub accents_vibrant_blue: Color,
-    pub accents_vibrant_indigo: Color,
-    pub accents_vibrant_purple: Color,
-    pub accents_vibrant_pink: Color,
-    pub accents_vibrant_brown: Color,
-    pub accents_vibrant_gray: Color,
-    pub fills_primary: Color,
-    pub fills_secondary: Color,
-    pub fills_tertiary: Color,
-    pub fills_quaternary: Color,
-    pub fills_quinary: Color,
-    pub fills_vibrant_primary: Color,
-    pub fills_vibrant_secondary: Color,
-    pub fills_vibrant_tertiary: Color,
-    pub fills_vibrant_quaternary: Color,
-    pub fills_vibrant_quinary: Color,
-    pub text_primary: Color,
-    pub text_secondary: Color,
-    pub text_tertiary: Color,
-    pub text_quaternary: Color,
-    pub text_quinary: Color,
-    pub text_vibrant_primary: Color,
-    pub text_vibrant_secondary: Color,
-    pub text_vibrant_tertiary: Color,
-    pub text_vibrant_quaternary: Color,
-    pub text_vibrant_quinary: Color,
-    pub materials_ultrathick: Color,
-    pub materials_thick: Color,
-    pub materials_medium: Color,
-    pub materials_thin: Color,
-    pub materials_ultrathin: Color,
-    pub materials_highlight: Color,
-    pub materials_controls_menu: Color,
-    pub materials_controls_popover: Color,
-    pub materials_controls_title_bar: Color,
-    pub materials_controls_sidebar: Color,
-    pub materials_controls_selection_focused: Color,
-    pub materials_controls_selection_unfocused: Color,
-    pub materials_controls_header_view: Color,
-    pub materials_controls_tooltip: Color,
-    pub materials_controls_under_window_background: Color,
-    pub materials_controls_fullscreen: Color,
-    pub materials_controls_hud: Color,
-    pub shadow_color: Color,
+// Macro to define ThemeColors, with a lookup of its fields by name for
+// theme files
+macro_rules! define_theme_colors {
+    ($($name:ident),* $(,)?) => {
+        #[allow(unused)]
+        #[derive(Clone)]
+        pub struct ThemeColors {
+            $(pub $name: Color),*
+        }
+
+        impl ThemeColors {
+            /// The color stored in the field called `name`, e.g. "materials_medium"
+            pub fn color_mut(&mut self, name: &str) -> Option<&mut Color> {
+                match name {
+                    $(stringify!($name) => Some(&mut self.$name),)*
+                    _ => None,
+                }
+            }
+        }
+    };
 }
 
+define_theme_colors!(
+    accents_red,
+    accents_orange,
+    accents_yellow,
+    accents_green,
+    accents_mint,
+    accents_teal,
+    accents_cyan,
+    accents_blue,
+    accents_indigo,
+    accents_purple,
+    accents_pink,
+    accents_gray,
+    accents_brown,
+    accents_vibrant_red,
+    accents_vibrant_orange,
+    accents_vibrant_yellow,
+    accents_vibrant_green,
+    accents_vibrant_mint,
+    accents_vibrant_teal,
+    accents_vibrant_cyan,
+    accents_vibrant_blue,
+    accents_vibrant_indigo,
+    accents_vibrant_purple,
+    accents_vibrant_pink,
+    accents_vibrant_brown,
+    accents_vibrant_gray,
+    fills_primary,
+    fills_secondary,
+    fills_tertiary,
+    fills_quaternary,
+    fills_quinary,
+    fills_vibrant_primary,
+    fills_vibrant_secondary,
+    fills_vibrant_tertiary,
+    fills_vibrant_quaternary,
+    fills_vibrant_quinary,
+    text_primary,
+    text_secondary,
+    text_tertiary,
+    text_quaternary,
+    text_quinary,
+    text_vibrant_primary,
+    text_vibrant_secondary,
+    text_vibrant_tertiary,
+    text_vibrant_quaternary,
+    text_vibrant_quinary,
+    materials_ultrathick,
+    materials_thick,
+    materials_medium,
+    materials_thin,
+    materials_ultrathin,
+    materials_highlight,
+    materials_controls_menu,
+    materials_controls_popover,
+    materials_controls_title_bar,
+    materials_controls_sidebar,
+    materials_controls_selection_focused,
+    materials_controls_selection_unfocused,
+    materials_controls_header_view,
+    materials_controls_tooltip,
+    materials_controls_under_window_background,
+    materials_controls_fullscreen,
+    materials_controls_hud,
+    shadow_color,
+);
+
 mod colors_dark;
 mod colors_light;
 pub mod text_styles;
+mod theme_file;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub use theme_file::{parse_color, ThemeFile};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ThemeScheme {
     Light,
     Dark,
+    /// Dark during the night of the `[night_light]` schedule, light otherwise
+    Auto,
 }
 
-pub fn theme_colors() -> &'static Lazy<ThemeColors> {
-    Config::with(|c| match c.theme_scheme {
-        ThemeScheme::Light => &colors_light::COLORS,
-        ThemeScheme::Dark => &colors_dark::COLORS,
-    })
+/// The palettes and material settings in use: the built-in ones, with the
+/// overrides of the configured theme file
+pub struct Theme {
+    pub light: Arc<ThemeColors>,
+    pub dark: Arc<ThemeColors>,
+    /// Accent name or hex color; takes precedence over `accent_color`
+    pub accent_color: Option<String>,
+    /// Background blur radius of materials, in logical pixels; `None` keeps
+    /// the engine default
+    pub material_blur_radius: Option<f32>,
+    /// Path and contents of the theme file, to skip reloads that change nothing
+    source: Option<(PathBuf, String)>,
+}
+
+impl Theme {
+    fn builtin() -> Self {
+        Self {
+            light: Arc::new(colors_light::COLORS.clone()),
+            dark: Arc::new(colors_dark::COLORS.clone()),
+            accent_color: None,
+            material_blur_radius: None,
+            source: None,
+        }
+    }
+
+    /// The built-in theme with the overrides of the file at `path`. A file
+    /// that can't be read or parsed is reported and leaves the built-in
+    /// theme.
+    fn load(path: Option<PathBuf>) -> Self {
+        let Some(path) = path else {
+            return Self::builtin();
+        };
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                warn!("Failed to read theme file {}: {}", path.display(), err);
+                return Self::builtin();
+            }
+        };
+        let file = match ThemeFile::parse(&path, &contents) {
+            Ok(file) => file,
+            Err(err) => {
+                warn!("Invalid theme file {}: {}", path.display(), err);
+                return Self::builtin();
+            }
+        };
+        let mut light = colors_light::COLORS.clone();
+        let mut dark = colors_dark::COLORS.clone();
+        for (scheme, colors, overrides) in [
+            ("light", &mut light, &file.light),
+            ("dark", &mut dark, &file.dark),
+        ] {
+            for (name, value) in overrides {
+                match (colors.color_mut(name), parse_color(value)) {
+                    (Some(color), Some(value)) => *color = value,
+                    (None, _) => warn!("Theme file: unknown color `{scheme}.{name}`"),
+                    (_, None) => warn!("Theme file: invalid color `{scheme}.{name}` = {value:?}"),
+                }
+            }
+        }
+        info!("Loaded theme file {}", path.display());
+        Self {
+            light: Arc::new(light),
+            dark: Arc::new(dark),
+            accent_color: file.accent_color,
+            material_blur_radius: file.materials.blur_radius,
+            source: Some((path, contents)),
+        }
+    }
 }
 
-/// Get the configured accent color by name
+static THEME: Lazy<RwLock<Arc<Theme>>> = Lazy::new(|| {
+    let path = Config::with(|c| c.theme_file_path());
+    RwLock::new(Arc::new(Theme::load(path)))
+});
+
+/// Whether an `Auto` scheme is dark, updated by `update_auto_scheme`
+static AUTO_DARK: Lazy<AtomicBool> = Lazy::new(|| {
+    AtomicBool::new(Config::with(|c| {
+        crate::state::night_light::is_night(&c.night_light)
+    }))
+});
+
+/// Bumped each time the colors shown change
+static THEME_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Count the colors shown as changed. View states hash `theme_generation`,
+/// so their next update re-renders even when nothing else changed.
+pub fn bump_theme_generation() {
+    THEME_GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn theme_generation() -> u64 {
+    THEME_GENERATION.load(Ordering::Relaxed)
+}
+
+/// The active theme
+pub fn theme() -> Arc<Theme> {
+    THEME.read().unwrap().clone()
+}
+
+/// Re-read the configured theme file. Returns whether the theme changed.
+pub fn reload_theme() -> bool {
+    let path = Config::with(|c| c.theme_file_path());
+    let contents = path
+        .as_ref()
+        .and_then(|path| std::fs::read_to_string(path).ok());
+    let unchanged = {
+        let current = THEME.read().unwrap();
+        match (&current.source, &path, &contents) {
+            (Some((old_path, old)), Some(path), Some(contents)) => {
+                old_path == path && old == contents
+            }
+            (None, None, _) => true,
+            _ => false,
+        }
+    };
+    if unchanged {
+        return false;
+    }
+    *THEME.write().unwrap() = Arc::new(Theme::load(path));
+    true
+}
+
+/// Re-evaluate the `Auto` scheme against the night light schedule. Returns
+/// whether it switched between light and dark.
+pub fn update_auto_scheme() -> bool {
+    let night = Config::with(|c| crate::state::night_light::is_night(&c.night_light));
+    AUTO_DARK.swap(night, Ordering::Relaxed) != night
+}
+
+/// The scheme shown now: `Light` or `Dark`, with `Auto` resolved
+pub fn active_scheme() -> ThemeScheme {
+    match Config::with(|c| c.theme_scheme) {
+        ThemeScheme::Auto if AUTO_DARK.load(Ordering::Relaxed) => ThemeScheme::Dark,
+        ThemeScheme::Auto => ThemeScheme::Light,
+        scheme => scheme,
+    }
+}
+
+pub fn theme_colors() -> Arc<ThemeColors> {
+    let theme = theme();
+    match active_scheme() {
+        ThemeScheme::Dark => theme.dark.clone(),
+        _ => theme.light.clone(),
+    }
+}
+
+/// Get the accent color of the theme file, or the configured one, by name
+/// or hex value
 pub fn accent_color() -> Color {
     let colors = theme_colors();
-    Config::with(|c| {
-        match c.accent_color.as_str() {
-            "red" => colors.accents_red,
-            "orange" => colors.accents_orange,
-            "yellow" => colors.accents_yellow,
-            "green" => colors.accents_green,
-            "mint" => colors.accents_mint,
-            "teal" => colors.accents_teal,
-            "cyan" => colors.accents_cyan,
-            "blue" => colors.accents_blue,
-            "indigo" => colors.accents_indigo,
-            "purple" => colors.accents_purple,
-            "pink" => colors.accents_pink,
-            "gray" => colors.accents_gray,
-            "brown" => colors.accents_brown,
-            _ => colors.accents_blue, // default fallback
-        }
+    let name = theme()
+        .accent_color
+        .clone()
+        .unwrap_or_else(|| Config::with(|c| c.accent_color.clone()));
+    named_accent(&colors, &name)
+        .or_else(|| parse_color(&name))
+        .unwrap_or(colors.accents_blue) // default fallback
+}
+
+/// The accent of `colors` called `name` ("blue", "purple", …), `None` for
+/// names that are not accents
+pub fn named_accent(colors: &ThemeColors, name: &str) -> Option<Color> {
+    Some(match name {
+        "red" => colors.accents_red,
+        "orange" => colors.accents_orange,
+        "yellow" => colors.accents_yellow,
+        "green" => colors.accents_green,
+        "mint" => colors.accents_mint,
+        "teal" => colors.accents_teal,
+        "cyan" => colors.accents_cyan,
+        "blue" => colors.accents_blue,
+        "indigo" => colors.accents_indigo,
+        "purple" => colors.accents_purple,
+        "pink" => colors.accents_pink,
+        "gray" => colors.accents_gray,
+        "brown" => colors.accents_brown,
+        _ => return None,
     })
 }
+
+/// The otto-kit theme matching the scheme shown, with the accent color, for
+/// the components the compositor draws with otto-kit
+pub fn kit_theme() -> otto_kit::theme::Theme {
+    let theme = match active_scheme() {
+        ThemeScheme::Dark => otto_kit::theme::Theme::dark(),
+        _ => otto_kit::theme::Theme::light(),
+    };
+    theme.with_accent(accent_color().c4f().to_color())
+}
+
+/// Context menu style at `scale`, colored by `kit_theme`
+pub fn context_menu_style(scale: f32) -> ContextMenuStyle {
+    ContextMenuStyle::default_with_scale(scale).with_theme(kit_theme())
+}
+
+/// Give `layer` the background blur radius of the theme's materials, if it
+/// sets one
+pub fn apply_material_blur(layer: &layers::prelude::Layer) {
+    if let Some(radius) = theme().material_blur_radius {
+        let scale = Config::with(|c| c.screen_scale) as f32;
+        layer.set_background_blur_radius(radius * scale, None);
+    }
+}
@@ -0,0 +1,136 @@
+//! Theme files: overrides of the built-in palettes, the accent and the
+//! material blur, in TOML or JSON.
+//!
+//! ```toml
+//! accent_color = "purple"
+//!
+//! [materials]
+//! blur_radius = 40.0
+//!
+//! [light]
+//! materials_medium = "#F6F6F6B3"
+//!
+//! [dark]
+//! materials_medium = "#2A2A2AB3"
+//! text_primary = "#FFFFFFD9"
+//! ```
+//!
+//! Colors are named like the fields of `ThemeColors`; the ones a file leaves
+//! out keep their built-in value.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use layers::types::Color;
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeFile {
+    pub accent_color: Option<String>,
+    pub materials: MaterialsTheme,
+    pub light: BTreeMap<String, String>,
+    pub dark: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct MaterialsTheme {
+    /// Background blur radius, in logical pixels
+    pub blur_radius: Option<f32>,
+}
+
+#[derive(Debug, Error)]
+pub enum ThemeFileError {
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("blur radius {0} is negative")]
+    NegativeBlurRadius(f32),
+}
+
+impl ThemeFile {
+    /// Parse `contents` as JSON when `path` ends in `.json`, as TOML otherwise
+    pub fn parse(path: &Path, contents: &str) -> Result<Self, ThemeFileError> {
+        let file: Self = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(contents)?
+        } else {
+            toml::from_str(contents)?
+        };
+        if let Some(radius) = file.materials.blur_radius.filter(|r| *r < 0.0) {
+            return Err(ThemeFileError::NegativeBlurRadius(radius));
+        }
+        Ok(file)
+    }
+}
+
+/// Parse "#RRGGBB" or "#RRGGBBAA"; the leading '#' is optional
+pub fn parse_color(hex: &str) -> Option<Color> {
+    let hex = hex.trim().trim_start_matches('#');
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Color::new_rgba255(
+        channel(0)?,
+        channel(2)?,
+        channel(4)?,
+        alpha,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_toml_overrides() {
+        let file = ThemeFile::parse(
+            Path::new("theme.toml"),
+            r##"
+            accent_color = "purple"
+            [materials]
+            blur_radius = 40.0
+            [dark]
+            materials_medium = "#2A2A2AB3"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(file.accent_color.as_deref(), Some("purple"));
+        assert_eq!(file.materials.blur_radius, Some(40.0));
+        assert!(file.light.is_empty());
+        assert_eq!(file.dark["materials_medium"], "#2A2A2AB3");
+    }
+
+    #[test]
+    fn parses_json_by_extension() {
+        let file = ThemeFile::parse(
+            Path::new("/themes/nord.json"),
+            r##"{ "light": { "text_primary": "#2E3440" } }"##,
+        )
+        .unwrap();
+        assert_eq!(file.light["text_primary"], "#2E3440");
+        assert_eq!(file.materials.blur_radius, None);
+    }
+
+    #[test]
+    fn rejects_negative_blur_radius() {
+        let result = ThemeFile::parse(Path::new("t.toml"), "[materials]\nblur_radius = -1.0");
+        assert!(matches!(
+            result,
+            Err(ThemeFileError::NegativeBlurRadius(r)) if r == -1.0
+        ));
+    }
+
+    #[test]
+    fn parses_hex_colors() {
+        assert!(parse_color("#0A84FF").is_some());
+        assert!(parse_color("0A84FF80").is_some());
+        assert!(parse_color("#0A84F").is_none());
+        assert!(parse_color("#GGGGGG").is_none());
+        assert!(parse_color("blue").is_none());
+    }
+}
@@ -0,0 +1,270 @@
+// Backlight control for the internal panel
+//
+// Levels are read from and written to the panel's sysfs backlight under
+// /sys/class/backlight. Writing there needs root or a udev rule, so a denied
+// write goes through logind's Session.SetBrightness instead, which the
+// session owner may always call. Among several backlights the one of the
+// internal panel wins: firmware (ACPI) and platform ones drive it by
+// definition, raw ones only when they hang off an internal connector (eDP,
+// LVDS, DSI). Backlights of external displays, such as ddcci ones, are left
+// alone.
+//
+// The level is kept in percent of the device maximum, persisted under
+// $XDG_STATE_HOME/otto and restored at startup.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use tracing::{debug, info, warn};
+
+use crate::config::{Config, DisplayKind};
+
+const BACKLIGHT_CLASS: &str = "/sys/class/backlight";
+
+/// The sysfs backlight of the internal panel
+#[derive(Debug)]
+pub struct Backlight {
+    name: String,
+    path: PathBuf,
+    max: u32,
+}
+
+impl Backlight {
+    /// The backlight of the internal panel, if the machine has one
+    pub fn find() -> Option<Self> {
+        let entries = fs::read_dir(BACKLIGHT_CLASS).ok()?;
+        let backlight = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                let backlight_type = read_trimmed(&path.join("type")).ok()?;
+                let connector = linked_connector(&path);
+                let rank = internal_rank(&backlight_type, connector.as_deref())?;
+                let max = read_trimmed(&path.join("max_brightness"))
+                    .ok()?
+                    .parse()
+                    .ok()?;
+                let name = entry.file_name().to_string_lossy().into_owned();
+                Some((rank, Backlight { name, path, max }))
+            })
+            .filter(|(_, backlight)| backlight.max > 0)
+            // Ties go to the first name, e.g. acpi_video0 over acpi_video1
+            .max_by(|(rank_a, a), (rank_b, b)| rank_a.cmp(rank_b).then(b.name.cmp(&a.name)))
+            .map(|(_, backlight)| backlight);
+        match &backlight {
+            Some(backlight) => info!(device = backlight.name, "Using backlight"),
+            None => debug!("No internal panel backlight found"),
+        }
+        backlight
+    }
+
+    /// Current level, in percent
+    pub fn percent(&self) -> io::Result<u32> {
+        Ok(percent_from_raw(self.raw()?, self.max))
+    }
+
+    /// Set the level, in percent, and remember it for the next session
+    pub fn set_percent(&self, percent: u32) -> io::Result<()> {
+        self.apply_percent(percent)?;
+        store_level(percent);
+        Ok(())
+    }
+
+    /// Set the level, in percent, without remembering it, for temporary
+    /// dimming that is undone later
+    pub fn apply_percent(&self, percent: u32) -> io::Result<()> {
+        self.write_raw(raw_from_percent(percent, self.max))
+    }
+
+    /// Move the level `step` percent up or down, by at least one raw unit
+    /// and not below `min_percent`, and remember it. Returns the new level
+    /// in percent.
+    pub fn step(&self, direction: i32, step: u32, min_percent: u32) -> io::Result<u32> {
+        let raw = stepped_raw(self.raw()?, self.max, direction, step, min_percent);
+        self.write_raw(raw)?;
+        let percent = percent_from_raw(raw, self.max);
+        store_level(percent);
+        Ok(percent)
+    }
+
+    fn raw(&self) -> io::Result<u32> {
+        read_trimmed(&self.path.join("brightness"))?
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn write_raw(&self, raw: u32) -> io::Result<()> {
+        match fs::write(self.path.join("brightness"), raw.to_string()) {
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => self.set_through_logind(raw),
+            result => result,
+        }
+    }
+
+    fn set_through_logind(&self, raw: u32) -> io::Result<()> {
+        let to_io = io::Error::other;
+        let connection = zbus::blocking::Connection::system().map_err(to_io)?;
+        connection
+            .call_method(
+                Some("org.freedesktop.login1"),
+                "/org/freedesktop/login1/session/auto",
+                Some("org.freedesktop.login1.Session"),
+                "SetBrightness",
+                &("backlight", self.name.as_str(), raw),
+            )
+            .map_err(to_io)?;
+        Ok(())
+    }
+
+    /// Bring back the level of the previous session, if one was saved
+    pub fn restore(&self) {
+        let Some(percent) = load_level() else {
+            return;
+        };
+        let min = Config::with(|c| c.backlight.min_percent).min(100);
+        let percent = percent.clamp(min, 100);
+        if let Err(e) = self.apply_percent(percent) {
+            warn!(device = self.name, "Failed to restore backlight: {e}");
+        }
+    }
+}
+
+fn read_trimmed(path: &Path) -> io::Result<String> {
+    fs::read_to_string(path).map(|s| s.trim().to_string())
+}
+
+/// Name of the DRM connector the backlight belongs to, e.g. `eDP-1`, when
+/// the driver registered it under the connector's device
+fn linked_connector(backlight: &Path) -> Option<String> {
+    let device = fs::canonicalize(backlight).ok()?;
+    let parent = device.parent()?.file_name()?.to_str()?;
+    // Connector devices are named card<N>-<connector>
+    let (card, connector) = parent.split_once('-')?;
+    card.starts_with("card").then(|| connector.to_string())
+}
+
+fn connector_kind(connector: &str) -> DisplayKind {
+    if crate::utils::is_laptop_panel(connector) {
+        DisplayKind::Internal
+    } else {
+        DisplayKind::External
+    }
+}
+
+/// How well a backlight fits the internal panel, higher is better; `None`
+/// for backlights of external displays
+fn internal_rank(backlight_type: &str, connector: Option<&str>) -> Option<u8> {
+    let kind = connector.map(connector_kind);
+    if kind == Some(DisplayKind::External) {
+        return None;
+    }
+    match backlight_type {
+        "firmware" => Some(3),
+        "platform" => Some(2),
+        "raw" if kind == Some(DisplayKind::Internal) => Some(1),
+        // A raw backlight of unknown display, e.g. a ddcci monitor
+        _ => None,
+    }
+}
+
+fn percent_from_raw(raw: u32, max: u32) -> u32 {
+    ((raw.min(max) as u64 * 100 + max as u64 / 2) / max as u64) as u32
+}
+
+/// Raw level for `percent`, never 0: many panels turn fully off at 0
+fn raw_from_percent(percent: u32, max: u32) -> u32 {
+    (((percent.min(100) as u64 * max as u64 + 50) / 100) as u32).max(1)
+}
+
+/// Raw level `step` percent away from `raw` in `direction`. It moves at
+/// least one raw unit, so coarse backlights with fewer levels than steps
+/// still change, and stays between `min_percent` and `max`.
+fn stepped_raw(raw: u32, max: u32, direction: i32, step: u32, min_percent: u32) -> u32 {
+    let percent = percent_from_raw(raw, max) as i32 + direction * step as i32;
+    let target = raw_from_percent(percent.clamp(0, 100) as u32, max);
+    let target = match direction.signum() {
+        1 => target.max(raw + 1),
+        -1 => target.min(raw.saturating_sub(1)),
+        _ => target,
+    };
+    target.clamp(raw_from_percent(min_percent, max), max)
+}
+
+/// File holding the backlight level of the last session
+fn level_state_path() -> Option<PathBuf> {
+    std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/state")))
+        .map(|dir| dir.join("otto").join("backlight"))
+}
+
+fn load_level() -> Option<u32> {
+    let content = fs::read_to_string(level_state_path()?).ok()?;
+    content.trim().parse().ok()
+}
+
+fn store_level(percent: u32) {
+    let Some(path) = level_state_path() else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Err(e) = fs::write(&path, percent.to_string()) {
+        warn!("Failed to persist backlight level: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_rank_prefers_firmware_and_skips_external() {
+        assert_eq!(internal_rank("firmware", None), Some(3));
+        assert_eq!(internal_rank("platform", None), Some(2));
+        assert_eq!(internal_rank("raw", Some("eDP-1")), Some(1));
+        assert_eq!(internal_rank("raw", None), None);
+        assert_eq!(internal_rank("raw", Some("DP-2")), None);
+        assert_eq!(internal_rank("firmware", Some("HDMI-A-1")), None);
+    }
+
+    #[test]
+    fn percent_round_trips_through_raw_levels() {
+        assert_eq!(raw_from_percent(50, 1000), 500);
+        assert_eq!(raw_from_percent(100, 255), 255);
+        assert_eq!(raw_from_percent(120, 255), 255);
+        assert_eq!(percent_from_raw(128, 255), 50);
+        assert_eq!(percent_from_raw(7, 7), 100);
+        for percent in 0..=100 {
+            assert_eq!(
+                percent_from_raw(raw_from_percent(percent, 96000), 96000),
+                percent
+            );
+        }
+    }
+
+    #[test]
+    fn raw_level_never_reaches_zero() {
+        assert_eq!(raw_from_percent(0, 255), 1);
+        assert_eq!(raw_from_percent(1, 7), 1);
+    }
+
+    #[test]
+    fn steps_move_at_least_one_raw_unit() {
+        // 5% of 7 levels rounds to no change
+        assert_eq!(stepped_raw(3, 7, 1, 5, 1), 4);
+        assert_eq!(stepped_raw(3, 7, -1, 5, 1), 2);
+        assert_eq!(stepped_raw(500, 1000, 1, 5, 1), 550);
+        assert_eq!(stepped_raw(500, 1000, -1, 5, 1), 450);
+    }
+
+    #[test]
+    fn steps_stay_within_min_percent_and_max() {
+        assert_eq!(stepped_raw(7, 7, 1, 5, 1), 7);
+        assert_eq!(stepped_raw(1, 7, -1, 5, 1), 1);
+        assert_eq!(stepped_raw(60, 1000, -1, 5, 10), 100);
+        assert_eq!(stepped_raw(1, 1000, -1, 5, 0), 1);
+    }
+}
@@ -0,0 +1,81 @@
+// 10-bit color output
+//
+// With `use_10bit_color`, outputs whose connector accepts 10 bits per channel
+// are rendered and scanned out in a 10-bit format. The connector's `max bpc`
+// is raised to 10 so the extra precision reaches the display instead of being
+// dithered down on the link. The DRM compositor tries the formats in order
+// and falls back to the 8-bit ones when the planes reject the 10-bit ones.
+
+use smithay::{
+    backend::{allocator::Fourcc, drm::DrmDevice},
+    reexports::drm::control::{connector, property, Device as ControlDevice},
+};
+use tracing::{debug, info, warn};
+
+use crate::config::Config;
+
+use super::{
+    probe::connector_property,
+    types::{GbmDrmCompositor, SUPPORTED_FORMATS, SUPPORTED_FORMATS_8BIT_ONLY},
+};
+
+/// Bits per channel asked for on 10-bit outputs
+const TEN_BIT_BPC: u64 = 10;
+
+/// Formats to try for the output on `connector`, in preference order.
+///
+/// 10-bit formats come first when `use_10bit_color` is on and the connector
+/// accepts at least 10 bits per channel; its `max bpc` is then raised to 10.
+pub(super) fn output_color_formats(
+    drm: &DrmDevice,
+    connector: connector::Handle,
+    output_name: &str,
+) -> &'static [Fourcc] {
+    if !Config::with(|c| c.use_10bit_color) {
+        return SUPPORTED_FORMATS_8BIT_ONLY;
+    }
+
+    let Some((max_bpc, current)) = connector_property(drm, connector, "max bpc") else {
+        // Without the property the driver picks the link depth itself
+        debug!("{output_name} has no max bpc property, trying 10-bit formats");
+        return SUPPORTED_FORMATS;
+    };
+    let property::ValueType::UnsignedRange(_, max) = max_bpc.value_type() else {
+        return SUPPORTED_FORMATS;
+    };
+    if max < TEN_BIT_BPC {
+        info!("{output_name} supports at most {max} bits per channel, using 8-bit color");
+        return SUPPORTED_FORMATS_8BIT_ONLY;
+    }
+    if current < TEN_BIT_BPC {
+        if let Err(err) = drm.set_property(connector, max_bpc.handle(), TEN_BIT_BPC) {
+            warn!(
+                ?err,
+                "Failed to raise max bpc on {output_name}, the link may stay 8-bit"
+            );
+        }
+    }
+    SUPPORTED_FORMATS
+}
+
+/// Log the format the compositor settled on for the output, and whether it
+/// had to fall back from the 10-bit formats.
+pub(super) fn log_output_format(
+    compositor: &GbmDrmCompositor,
+    color_formats: &[Fourcc],
+    output_name: &str,
+) {
+    let format = compositor.format();
+    let wanted_10bit = color_formats.first().is_some_and(|f| is_10bit(*f));
+    if wanted_10bit && !is_10bit(format) {
+        warn!(
+            "{output_name} rejected the 10-bit formats, falling back to 8-bit color ({format:?})"
+        );
+    } else {
+        info!("{output_name} renders in {format:?}");
+    }
+}
+
+fn is_10bit(format: Fourcc) -> bool {
+    matches!(format, Fourcc::Abgr2101010 | Fourcc::Xbgr2101010)
+}
@@ -19,24 +19,28 @@ use smithay::{
                 connector::{self, SubPixel},
                 crtc, Device as ControlDevice, ModeTypeFlags,
             },
-            Device,
+            Device, DriverCapability,
         },
         rustix::fs::OFlags,
     },
-    utils::DeviceFd,
+    utils::{DeviceFd, Transform},
     wayland::drm_lease::DrmLeaseState,
 };
 use smithay_drm_extras::drm_scanner::DrmScanEvent;
 use tracing::{debug, error, info, warn};
 
-use crate::{config::Config, state::Otto};
+use crate::{
+    config::{window_rules::ContentType, Config},
+    state::Otto,
+    workspaces::DisplayIdentity,
+};
 
 use super::{
+    color_depth, edid,
     feedback::get_surface_dmabuf_feedback,
-    types::{
-        BackendData, DeviceAddError, GbmDrmCompositor, SurfaceData, UdevData, UdevOutputId,
-        SUPPORTED_FORMATS, SUPPORTED_FORMATS_8BIT_ONLY,
-    },
+    output_mode::{mode_summary, select_mode},
+    types::{BackendData, DeviceAddError, GbmDrmCompositor, SurfaceData, UdevData, UdevOutputId},
+    vrr::{self, GameVrr},
 };
 #[cfg(feature = "renderer_sync")]
 use smithay::backend::renderer::sync::SyncPoint;
@@ -151,8 +155,12 @@ impl Otto<UdevData> {
             }
         }
 
-        // fixup window coordinates
-        crate::shell::fixup_positions(&mut self.workspaces, self.pointer.current_location());
+        // fixup window coordinates; taskbars learn about windows back home
+        let restored =
+            crate::shell::fixup_positions(&mut self.workspaces, self.pointer.current_location());
+        for id in restored {
+            self.refresh_foreign_toplevel(&id);
+        }
     }
 
     /// Handles removal of a DRM device
@@ -242,12 +250,8 @@ impl Otto<UdevData> {
             })
             .unwrap_or(false);
 
-        // EDID info is no longer available in smithay-drm-extras
-        // Using connector info instead
-        let (make, model) = (
-            format!("{:?}", connector.interface()),
-            format!("{:?}", connector.interface()),
-        );
+        let identity = edid::read_identity(&device.drm, connector.handle());
+        let (make, model) = edid::vendor_and_model(identity.as_ref(), connector.interface());
 
         if non_desktop {
             info!(
@@ -272,6 +276,7 @@ impl Otto<UdevData> {
                 &output_name,
                 &make,
                 &model,
+                identity,
                 render_formats,
             );
         }
@@ -287,6 +292,7 @@ impl Otto<UdevData> {
         output_name: &str,
         make: &str,
         model: &str,
+        identity: Option<DisplayIdentity>,
         render_formats: smithay::backend::allocator::format::FormatSet,
     ) {
         let device_render_node = {
@@ -310,12 +316,19 @@ impl Otto<UdevData> {
         let mode_id = if let Some(ref profile) = config_profile {
             // Try to find matching resolution from config
             if let Some(desired_res) = profile.resolution {
-                connector
-                    .modes()
-                    .iter()
-                    .position(|mode| {
-                        let size = mode.size();
-                        size.0 as u32 == desired_res.width && size.1 as u32 == desired_res.height
+                // A configured refresh rate picks among the modes of that size
+                let candidates: Vec<_> = connector.modes().iter().map(mode_summary).collect();
+                profile
+                    .refresh_hz
+                    .and_then(|hz| {
+                        select_mode(&candidates, desired_res.width, desired_res.height, Some(hz))
+                    })
+                    .or_else(|| {
+                        connector.modes().iter().position(|mode| {
+                            let size = mode.size();
+                            size.0 as u32 == desired_res.width
+                                && size.1 as u32 == desired_res.height
+                        })
                     })
                     .or_else(|| {
                         warn!(
@@ -398,16 +411,34 @@ impl Otto<UdevData> {
                 subpixel,
                 make: make.to_string(),
                 model: model.to_string(),
-                serial_number: String::new(),
+                serial_number: identity
+                    .as_ref()
+                    .map(|identity| identity.serial.clone())
+                    .unwrap_or_default(),
             },
         );
+        if let Some(identity) = identity {
+            output.user_data().insert_if_missing(|| identity);
+        }
+
+        let screen_scale = config_profile
+            .as_ref()
+            .and_then(|p| p.scale)
+            .unwrap_or_else(|| Config::with(|c| c.screen_scale));
+        let transform: Transform = config_profile
+            .as_ref()
+            .and_then(|p| p.transform)
+            .map(Into::into)
+            .unwrap_or(Transform::Normal);
+        // Size of the output as laid out, after rotation
+        let mode_size = transform.transform_size(wl_mode.size);
 
         // FIXME handle multimonitor setup
         let root = self.scene_element.root_layer().unwrap();
-        let w = wl_mode.size.w as f32;
-        let h = wl_mode.size.h as f32;
+        let w = mode_size.w as f32;
+        let h = mode_size.h as f32;
         self.workspaces
-            .set_screen_dimension(wl_mode.size.w, wl_mode.size.h);
+            .set_screen_dimension(mode_size.w, mode_size.h, screen_scale);
         let scene_size = layers::types::Size::points(w, h);
         root.set_size(scene_size, None);
         self.scene_element.set_size(w, h);
@@ -418,15 +449,21 @@ impl Otto<UdevData> {
         let x = self.workspaces.outputs().fold(0, |acc, o| {
             acc + self.workspaces.output_geometry(o).unwrap().size.w
         });
-        let position = (x, 0).into();
+        let position = config_profile
+            .as_ref()
+            .and_then(|p| p.position)
+            .map(|p| (p.x, p.y).into())
+            .unwrap_or_else(|| (x, 0).into());
         output.set_preferred(wl_mode);
-        let screen_scale = Config::with(|c| c.screen_scale);
         output.change_current_state(
             Some(wl_mode),
-            None,
+            Some(transform),
             Some(smithay::output::Scale::Fractional(screen_scale)),
             Some(position),
         );
+        if let Some(profile) = config_profile.clone() {
+            output.user_data().insert_if_missing(|| profile);
+        }
 
         let is_primary = config_profile.as_ref().map(|p| p.primary).unwrap_or(false);
         self.workspaces
@@ -450,11 +487,18 @@ impl Otto<UdevData> {
             GbmBufferFlags::RENDERING | GbmBufferFlags::SCANOUT,
         );
 
-        let color_formats = if std::env::var("ANVIL_DISABLE_10BIT").is_ok() {
-            SUPPORTED_FORMATS_8BIT_ONLY
+        let async_page_flip_cap = if device.drm.is_atomic() {
+            DriverCapability::AtomicASyncPageFlip
         } else {
-            SUPPORTED_FORMATS
+            DriverCapability::ASyncPageFlip
         };
+        let supports_async_page_flip = device
+            .drm
+            .get_driver_capability(async_page_flip_cap)
+            .is_ok_and(|value| value == 1);
+
+        let color_formats =
+            color_depth::output_color_formats(&device.drm, connector.handle(), &output_name);
 
         let compositor = self.create_surface_compositor(
             node,
@@ -466,6 +510,7 @@ impl Otto<UdevData> {
         );
 
         if let Some(compositor) = compositor {
+            color_depth::log_output_format(&compositor, color_formats, &output_name);
             let dmabuf_feedback = get_surface_dmabuf_feedback(
                 self.backend_data.primary_gpu,
                 device_render_node,
@@ -473,7 +518,9 @@ impl Otto<UdevData> {
                 &compositor,
             );
 
-            let surface_data = SurfaceData {
+            let vrr_supported = vrr::connector_supports_vrr(&compositor, connector.handle());
+
+            let mut surface_data = SurfaceData {
                 dh: self.display_handle.clone(),
                 device_id: node,
                 render_node: device_render_node,
@@ -485,6 +532,13 @@ impl Otto<UdevData> {
                 fps_element,
                 dmabuf_feedback,
                 was_direct_scanout: false,
+                scanout_content_type: ContentType::None,
+                supports_async_page_flip,
+                tearing: false,
+                vrr_supported,
+                vrr: false,
+                game_vrr: GameVrr::default(),
+                awaiting_commit: false,
                 #[cfg(feature = "metrics")]
                 render_metrics: Some(self.render_metrics.clone()),
                 avg_render_time_us: 2000.0, // start with 2ms estimate
@@ -494,10 +548,16 @@ impl Otto<UdevData> {
                 pending_gpu_fence: SyncPoint::signaled(),
             };
 
+            if config_profile.as_ref().is_some_and(|profile| profile.vrr) {
+                surface_data.set_vrr(true, output_name);
+            }
+
             let device = self.backend_data.backends.get_mut(&node).unwrap();
             device.surfaces.insert(crtc, surface_data);
 
             self.schedule_initial_render(node, crtc, self.handle.clone());
+            // Warm the new output right away at night
+            self.update_night_light();
         }
     }
 
@@ -596,7 +656,12 @@ impl Otto<UdevData> {
                 .cloned();
 
             if let Some(output) = output {
-                self.workspaces.unmap_output(&output);
+                // A replugged display comes back with a neutral gamma ramp
+                self.night_light_applied.remove(&output.name());
+                // Taskbars learn about windows moved off the unplugged output
+                for id in self.workspaces.unmap_output(&output) {
+                    self.refresh_foreign_toplevel(&id);
+                }
             }
         }
     }
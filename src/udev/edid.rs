@@ -0,0 +1,148 @@
+// EDID identity of a connected display
+//
+// Reads the connector's EDID property blob and extracts the manufacturer,
+// monitor name and serial. Only the base block is parsed; extension blocks
+// carry nothing the compositor matches on.
+
+use smithay::reexports::drm::control::{connector, Device as ControlDevice};
+
+use crate::workspaces::DisplayIdentity;
+
+const HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+const BLOCK_LEN: usize = 128;
+/// Offsets of the four 18-byte descriptors in the base block
+const DESCRIPTORS: [usize; 4] = [54, 72, 90, 108];
+const TAG_SERIAL: u8 = 0xff;
+const TAG_NAME: u8 = 0xfc;
+
+/// Parsed identity of the display behind `handle`, if it has an EDID
+pub(super) fn read_identity(
+    drm: &impl ControlDevice,
+    handle: connector::Handle,
+) -> Option<DisplayIdentity> {
+    let (_, value) = drm
+        .get_properties(handle)
+        .ok()?
+        .into_iter()
+        .filter_map(|(property, value)| Some((drm.get_property(property).ok()?, value)))
+        .find(|(info, _)| info.name().to_str() == Ok("EDID"))?;
+    let blob = drm.get_property_blob(value).ok()?;
+    parse_identity(&blob)
+}
+
+/// Vendor and model that display profiles are matched against
+///
+/// Displays without an EDID are identified by the connector type.
+pub(super) fn vendor_and_model(
+    identity: Option<&DisplayIdentity>,
+    interface: connector::Interface,
+) -> (String, String) {
+    match identity {
+        Some(identity) => (identity.vendor.clone(), identity.model.clone()),
+        None => (format!("{interface:?}"), format!("{interface:?}")),
+    }
+}
+
+/// Vendor, model and serial from an EDID base block
+///
+/// The vendor is the three-letter PNP manufacturer ID. The model is the
+/// monitor name descriptor, or the product code when there is none. The
+/// serial is the serial descriptor, or the numeric serial when it is set.
+pub(super) fn parse_identity(edid: &[u8]) -> Option<DisplayIdentity> {
+    if edid.len() < BLOCK_LEN || edid[..HEADER.len()] != HEADER {
+        return None;
+    }
+
+    let id = u16::from_be_bytes([edid[8], edid[9]]);
+    let letter = |shift: u16| {
+        let code = ((id >> shift) & 0x1f) as u8;
+        (1..=26).contains(&code).then(|| (b'A' + code - 1) as char)
+    };
+    let vendor: String = [letter(10)?, letter(5)?, letter(0)?].iter().collect();
+
+    let product = u16::from_le_bytes([edid[10], edid[11]]);
+    let serial_number = u32::from_le_bytes([edid[12], edid[13], edid[14], edid[15]]);
+
+    let model = descriptor_text(edid, TAG_NAME).unwrap_or_else(|| format!("{vendor}{product:04X}"));
+    let serial = descriptor_text(edid, TAG_SERIAL)
+        .or_else(|| (serial_number != 0).then(|| serial_number.to_string()))
+        .unwrap_or_default();
+
+    Some(DisplayIdentity {
+        vendor,
+        model,
+        serial,
+    })
+}
+
+/// Text of the first display descriptor tagged `tag`
+fn descriptor_text(edid: &[u8], tag: u8) -> Option<String> {
+    DESCRIPTORS.iter().find_map(|&offset| {
+        let descriptor = &edid[offset..offset + 18];
+        // Display descriptors start with a zero pixel clock
+        if descriptor[..2] != [0, 0] || descriptor[3] != tag {
+            return None;
+        }
+        let text = &descriptor[5..];
+        let end = text.iter().position(|&b| b == b'\n').unwrap_or(text.len());
+        let text = String::from_utf8_lossy(&text[..end]).trim().to_string();
+        (!text.is_empty()).then_some(text)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edid(vendor: [u8; 2], serial: u32, descriptors: &[(u8, &str)]) -> Vec<u8> {
+        let mut edid = vec![0u8; BLOCK_LEN];
+        edid[..8].copy_from_slice(&HEADER);
+        edid[8..10].copy_from_slice(&vendor);
+        edid[10..12].copy_from_slice(&0xa0b1u16.to_le_bytes());
+        edid[12..16].copy_from_slice(&serial.to_le_bytes());
+        for (&offset, (tag, text)) in DESCRIPTORS.iter().zip(descriptors) {
+            edid[offset + 3] = *tag;
+            let mut field = [b' '; 13];
+            field[..text.len()].copy_from_slice(text.as_bytes());
+            if text.len() < field.len() {
+                field[text.len()] = b'\n';
+            }
+            edid[offset + 5..offset + 18].copy_from_slice(&field);
+        }
+        edid
+    }
+
+    // "DEL": D=4, E=5, L=12
+    const DELL: [u8; 2] = [0x10, 0xac];
+
+    #[test]
+    fn reads_vendor_name_and_serial_descriptors() {
+        let identity = parse_identity(&edid(
+            DELL,
+            1234,
+            &[(TAG_SERIAL, "ABC123"), (TAG_NAME, "DELL U2720Q")],
+        ))
+        .unwrap();
+        assert_eq!(identity.vendor, "DEL");
+        assert_eq!(identity.model, "DELL U2720Q");
+        assert_eq!(identity.serial, "ABC123");
+    }
+
+    #[test]
+    fn falls_back_to_the_product_code_and_numeric_serial() {
+        let identity = parse_identity(&edid(DELL, 1234, &[])).unwrap();
+        assert_eq!(identity.model, "DELA0B1");
+        assert_eq!(identity.serial, "1234");
+
+        let identity = parse_identity(&edid(DELL, 0, &[])).unwrap();
+        assert_eq!(identity.serial, "");
+    }
+
+    #[test]
+    fn rejects_truncated_or_headerless_blobs() {
+        let mut blob = edid(DELL, 0, &[]);
+        assert!(parse_identity(&blob[..100]).is_none());
+        blob[0] = 0xff;
+        assert!(parse_identity(&blob).is_none());
+    }
+}
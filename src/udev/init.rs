@@ -13,6 +13,7 @@ use smithay::{
     backend::{
         drm::{DrmNode, NodeType},
         egl::context::ContextPriority,
+        input::InputEvent,
         libinput::{LibinputInputBackend, LibinputSessionInterface},
         renderer::{
             multigpu::{gbm::GbmGlesBackend, GpuManager},
@@ -26,18 +27,20 @@ use smithay::{
 };
 use tracing::{error, info, warn};
 
-use crate::{
-    config::Config,
-    state::{Backend, Otto},
-};
+use crate::{config::Config, state::Otto};
 
 use super::{
+    backlight::Backlight,
     feedback::get_surface_dmabuf_feedback,
     types::{DeviceAddError, UdevData},
 };
 
-/// Configures all libinput devices based on Otto's configuration
-fn configure_libinput_devices(libinput: &mut Libinput, config: &Config) {
+/// Configures all libinput devices based on Otto's configuration,
+/// returning the configured devices
+fn configure_libinput_devices(
+    libinput: &mut Libinput,
+    config: &Config,
+) -> Vec<smithay::reexports::input::Device> {
     use smithay::reexports::input::{
         event::{DeviceEvent, EventTrait},
         Event,
@@ -46,16 +49,19 @@ fn configure_libinput_devices(libinput: &mut Libinput, config: &Config) {
     // Process initial devices
     libinput.dispatch().ok();
 
+    let mut devices = Vec::new();
     for event in libinput.by_ref() {
         if let Event::Device(DeviceEvent::Added(added_event)) = event {
             let mut device = added_event.device();
             apply_device_config(&mut device, config);
+            devices.push(device);
         }
     }
+    devices
 }
 
 /// Applies configuration to an individual input device
-fn apply_device_config(device: &mut smithay::reexports::input::Device, config: &Config) {
+pub(super) fn apply_device_config(device: &mut smithay::reexports::input::Device, config: &Config) {
     // Only configure pointer devices (touchpads)
     if !device.has_capability(smithay::reexports::input::DeviceCapability::Pointer) {
         return;
@@ -208,6 +214,7 @@ fn apply_device_config(device: &mut smithay::reexports::input::Device, config: &
 ///
 /// Initializes the session, GPU, input devices, and runs the main event loop.
 pub fn run_udev() {
+    Config::write_starter_config_on_first_run();
     let mut event_loop = EventLoop::try_new().unwrap();
     let display = Display::new().unwrap();
     let mut display_handle = display.handle();
@@ -264,7 +271,14 @@ pub fn run_udev() {
 
         context_id: None, // Will be set after device initialization
         render_requested: AtomicBool::new(false),
+        virtual_output_timer: None,
+        virtual_output_commands: None,
+        input_devices: Vec::new(),
+        backlight: Backlight::find(),
     };
+    if let Some(backlight) = &data.backlight {
+        backlight.restore();
+    }
     let mut state = Otto::init(display, event_loop.handle(), data, true);
 
     /*
@@ -287,9 +301,8 @@ pub fn run_udev() {
     libinput_context.udev_assign_seat(&state.seat_name).unwrap();
 
     // Configure input devices based on config
-    Config::with(|config| {
-        configure_libinput_devices(&mut libinput_context, config);
-    });
+    state.backend_data.input_devices =
+        Config::with(|config| configure_libinput_devices(&mut libinput_context, config));
 
     let libinput_backend = LibinputInputBackend::new(libinput_context.clone());
 
@@ -299,6 +312,17 @@ pub fn run_udev() {
     event_loop
         .handle()
         .insert_source(libinput_backend, move |event, _, data| {
+            match &event {
+                InputEvent::DeviceAdded { device } => {
+                    let mut device = device.clone();
+                    Config::with(|config| apply_device_config(&mut device, config));
+                    data.backend_data.input_devices.push(device);
+                }
+                InputEvent::DeviceRemoved { device } => {
+                    data.backend_data.input_devices.retain(|d| d != device);
+                }
+                _ => {}
+            }
             let dh = data.backend_data.dh.clone();
             data.process_input_event(&dh, event);
             // Input may move the cursor or trigger visual changes — request a render.
@@ -352,6 +376,10 @@ pub fn run_udev() {
                     }
                     handle.insert_idle(move |data| data.render(node, None));
                 }
+                // Whoever held the session meanwhile may have replaced the
+                // gamma ramps, so night light applies them again
+                data.night_light_applied.clear();
+                data.update_night_light();
             }
         })
         .unwrap();
@@ -527,84 +555,7 @@ pub fn run_udev() {
     /*
      * Create virtual outputs from config
      */
-    {
-        let vout_configs = crate::config::Config::with(|c| c.virtual_outputs.clone());
-        if !vout_configs.is_empty() {
-            let gbm_device = state.backend_data.gbm_device();
-            let format_modifiers = state
-                .backend_data
-                .get_format_modifiers(smithay::backend::allocator::Fourcc::Argb8888);
-
-            for vout_config in &vout_configs {
-                let output = crate::virtual_output::VirtualOutputState::build_output(vout_config);
-                let global = output.create_global::<Otto<UdevData>>(&display_handle);
-
-                let position: smithay::utils::Point<i32, smithay::utils::Logical> = vout_config
-                    .position
-                    .map(|p| (p.x, p.y).into())
-                    .unwrap_or_else(|| (0, 0).into());
-                state.workspaces.map_output(&output, position);
-
-                match crate::virtual_output::VirtualOutputState::start(
-                    output,
-                    global,
-                    vout_config,
-                    gbm_device.clone(),
-                    format_modifiers.clone(),
-                ) {
-                    Ok((vout_state, node_id)) => {
-                        tracing::info!(
-                            "Virtual output '{}' started (PipeWire node {}). \
-                             Connect with: pw-play --target {}",
-                            vout_config.name,
-                            node_id,
-                            node_id
-                        );
-                        state.virtual_outputs.push(vout_state);
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            "Failed to create virtual output '{}': {}",
-                            vout_config.name,
-                            e
-                        );
-                    }
-                }
-            }
-        }
-
-        // Add a calloop timer to drive virtual output rendering independently of physical VBlanks.
-        // This ensures frames keep flowing even when physical outputs are idle.
-        if !state.virtual_outputs.is_empty() {
-            let refresh_hz = state
-                .virtual_outputs
-                .iter()
-                .filter_map(|v| v.output.current_mode())
-                .map(|m| m.refresh as f64 / 1000.0)
-                .fold(f64::INFINITY, f64::min);
-            let refresh_hz = if refresh_hz.is_finite() {
-                refresh_hz
-            } else {
-                60.0
-            };
-            let interval = std::time::Duration::from_micros((1_000_000.0 / refresh_hz) as u64);
-
-            state
-                .handle
-                .insert_source(
-                    smithay::reexports::calloop::timer::Timer::from_duration(interval),
-                    move |_, _, data: &mut Otto<super::types::UdevData>| {
-                        data.render_virtual_outputs();
-                        smithay::reexports::calloop::timer::TimeoutAction::ToDuration(interval)
-                    },
-                )
-                .expect("failed to schedule virtual output render timer");
-            tracing::info!(
-                "Virtual output render timer started at {:.1} Hz",
-                refresh_hz
-            );
-        }
-    }
+    state.init_virtual_outputs();
 
     /*
      * And run our loop
@@ -674,6 +625,19 @@ pub fn run_udev() {
                     for node in device_nodes {
                         state.render(node, None);
                     }
+                } else {
+                    // VRR outputs waiting for a commit draw right away
+                    let mut awaiting = Vec::new();
+                    for (node, device) in state.backend_data.backends.iter_mut() {
+                        for (crtc, surface) in device.surfaces.iter_mut() {
+                            if std::mem::take(&mut surface.awaiting_commit) {
+                                awaiting.push((*node, *crtc));
+                            }
+                        }
+                    }
+                    for (node, crtc) in awaiting {
+                        state.render(node, Some(crtc));
+                    }
                 }
             }
             display_handle.flush_clients().unwrap();
@@ -3,15 +3,23 @@
 // This module implements the production backend for Otto using DRM/KMS for display
 // and libinput for input handling.
 
+pub mod backlight;
+pub mod color_depth;
 pub mod device;
+pub mod edid;
 pub mod feedback;
 pub mod gamma;
 pub mod init;
+pub mod output_mode;
+pub mod probe;
 pub mod render;
 pub mod types;
+pub mod virtual_outputs;
+pub mod vrr;
 
 // Re-export public API
 pub use init::run_udev;
+pub use probe::probe_displays;
 
 // Re-export public types
 pub use types::{
@@ -21,6 +29,8 @@ pub use types::{
 
 use crate::renderer::{SkiaTexture, SkiaTextureImage};
 use crate::{
+    config::Config,
+    input::actions::KeyAction,
     skia_renderer::SkiaRenderer,
     state::{Backend, Otto},
 };
@@ -30,24 +40,17 @@ use smithay::backend::renderer::ImportMem;
 use smithay::{
     backend::{
         allocator::dmabuf::Dmabuf,
-        drm::{DrmDevice, DrmDeviceFd, DrmNode},
+        drm::{DrmDeviceFd, DrmNode},
         renderer::{
             multigpu::{gbm::GbmGlesBackend, MultiTexture},
             utils::import_surface,
             ImportDma,
         },
-        session::{libseat::LibSeatSession, Session},
-        udev::UdevBackend,
     },
     delegate_dmabuf, delegate_drm_lease, delegate_drm_syncobj,
     input::pointer::CursorImageStatus,
     output::Output,
-    reexports::{
-        drm::control::{Device as ControlDevice, ModeTypeFlags},
-        rustix::fs::OFlags,
-        wayland_server::protocol::wl_surface,
-    },
-    utils::DeviceFd,
+    reexports::wayland_server::protocol::wl_surface,
     wayland::{
         compositor,
         dmabuf::{DmabufGlobal, DmabufHandler, DmabufState, ImportNotifier},
@@ -103,6 +106,51 @@ impl Backend for UdevData {
         "udev"
     }
 
+    fn reload_input_config(&mut self) {
+        Config::with(|config| {
+            for device in &mut self.input_devices {
+                init::apply_device_config(device, config);
+            }
+        });
+    }
+
+    fn backlight(&self) -> Option<u32> {
+        let backlight = self.backlight.as_ref()?;
+        backlight
+            .percent()
+            .map_err(|e| tracing::warn!("Failed to read backlight: {e}"))
+            .ok()
+    }
+
+    fn set_backlight(&mut self, percent: u32) -> bool {
+        let Some(backlight) = &self.backlight else {
+            return false;
+        };
+        backlight
+            .set_percent(percent)
+            .map_err(|e| tracing::warn!("Failed to set backlight: {e}"))
+            .is_ok()
+    }
+
+    fn dim_backlight(&mut self, percent: u32) -> bool {
+        let Some(backlight) = &self.backlight else {
+            return false;
+        };
+        backlight
+            .apply_percent(percent)
+            .map_err(|e| tracing::warn!("Failed to dim backlight: {e}"))
+            .is_ok()
+    }
+
+    fn step_backlight(&mut self, direction: i32) -> Option<u32> {
+        let backlight = self.backlight.as_ref()?;
+        let (step, min) = Config::with(|c| (c.backlight.step, c.backlight.min_percent));
+        backlight
+            .step(direction, step, min.min(100))
+            .map_err(|e| tracing::warn!("Failed to set backlight: {e}"))
+            .ok()
+    }
+
     fn reset_buffers(&mut self, output: &Output) {
         if let Some(id) = output.user_data().get::<UdevOutputId>() {
             if let Some(gpu) = self.backends.get_mut(&id.device_id) {
@@ -215,6 +263,9 @@ impl Backend for UdevData {
         // Udev backend supports DMA-BUF for zero-copy screenshare
         true
     }
+    fn run_key_action(state: &mut Otto<Self>, action: KeyAction) {
+        state.process_key_action(action);
+    }
 }
 
 impl DrmLeaseHandler for Otto<UdevData> {
@@ -295,137 +346,3 @@ impl DrmLeaseHandler for Otto<UdevData> {
 }
 
 delegate_drm_lease!(Otto<UdevData>);
-
-pub fn probe_displays() {
-    #[allow(clippy::disallowed_macros)]
-    {
-        println!("Probing available displays and resolutions...\n");
-    }
-
-    let (mut session, _notifier) = match LibSeatSession::new() {
-        Ok(ret) => ret,
-        Err(err) => {
-            tracing::error!("Could not initialize a session: {}", err);
-            #[allow(clippy::disallowed_macros)]
-            {
-                eprintln!("Error: Could not initialize session - {}", err);
-                eprintln!(
-                    "Note: This command may require root privileges or proper seat permissions."
-                );
-            }
-            return;
-        }
-    };
-
-    let udev_backend = match UdevBackend::new(session.seat()) {
-        Ok(ret) => ret,
-        Err(err) => {
-            tracing::error!("Failed to initialize udev backend: {:?}", err);
-            #[allow(clippy::disallowed_macros)]
-            {
-                eprintln!("Error: Failed to initialize udev backend - {:?}", err);
-            }
-            return;
-        }
-    };
-
-    let mut found_displays = false;
-
-    for (device_id, path) in udev_backend.device_list() {
-        let node = match DrmNode::from_dev_id(device_id) {
-            Ok(node) => node,
-            Err(err) => {
-                tracing::warn!("Failed to get DRM node for device {}: {}", device_id, err);
-                continue;
-            }
-        };
-
-        #[allow(clippy::disallowed_macros)]
-        {
-            println!("Device: {} ({:?})", node, path);
-        }
-
-        let fd = match session.open(
-            path,
-            OFlags::RDWR | OFlags::CLOEXEC | OFlags::NOCTTY | OFlags::NONBLOCK,
-        ) {
-            Ok(fd) => DrmDeviceFd::new(DeviceFd::from(fd)),
-            Err(err) => {
-                tracing::warn!("Failed to open DRM device: {}", err);
-                continue;
-            }
-        };
-
-        let (drm_device, _notifier) = match DrmDevice::new(fd.clone(), true) {
-            Ok(device) => device,
-            Err(err) => {
-                tracing::warn!("Failed to create DRM device: {:?}", err);
-                continue;
-            }
-        };
-
-        let res_handles = match drm_device.resource_handles() {
-            Ok(res) => res,
-            Err(err) => {
-                tracing::warn!("Failed to get resource handles: {:?}", err);
-                continue;
-            }
-        };
-
-        for connector_handle in res_handles.connectors() {
-            let connector_info = match drm_device.get_connector(*connector_handle, false) {
-                Ok(info) => info,
-                Err(err) => {
-                    tracing::warn!("Failed to get connector info: {:?}", err);
-                    continue;
-                }
-            };
-
-            if connector_info.state()
-                != smithay::reexports::drm::control::connector::State::Connected
-            {
-                continue;
-            }
-
-            found_displays = true;
-
-            let connector_name = format!(
-                "{}-{}",
-                connector_info.interface().as_str(),
-                connector_info.interface_id()
-            );
-
-            #[allow(clippy::disallowed_macros)]
-            {
-                println!("\n  Connector: {}", connector_name);
-                println!("  Status: Connected");
-                println!("  Available modes:");
-            }
-
-            for mode in connector_info.modes() {
-                let refresh = (mode.vrefresh() as f32) / 1000.0;
-                #[allow(clippy::disallowed_macros)]
-                {
-                    println!(
-                        "    {}x{} @ {:.2} Hz{}",
-                        mode.size().0,
-                        mode.size().1,
-                        refresh,
-                        if mode.mode_type().contains(ModeTypeFlags::PREFERRED) {
-                            " (preferred)"
-                        } else {
-                            ""
-                        }
-                    );
-                }
-            }
-        }
-    }
-
-    if !found_displays {
-        #[allow(clippy::disallowed_macros)]
-        {
-            println!("\nNo connected displays found.");
-        }
-    }
-}
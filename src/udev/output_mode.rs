@@ -0,0 +1,187 @@
+// Runtime mode switching for DRM outputs
+//
+// Changes the resolution or refresh rate of a connected display without a
+// restart, e.g. 60Hz on battery and 144Hz on AC. The DRM compositor of the
+// output switches to the new mode on its next page flip; a frame already in
+// flight still completes with the old one, so the render loop is left alone
+// and simply draws the next frame at the new size.
+
+use smithay::{
+    output::{Mode as WlMode, Output},
+    reexports::drm::control::{Mode, ModeTypeFlags},
+};
+use tracing::info;
+
+use crate::state::{Backend, Otto};
+
+use super::types::{UdevData, UdevOutputId};
+
+/// How far a requested refresh rate may be from a mode's, in mHz, for the
+/// mode to match; covers rates like 59.94Hz requested as 60
+const REFRESH_TOLERANCE_MHZ: i32 = 500;
+
+impl Otto<UdevData> {
+    fn udev_output(&self, output_name: &str) -> Result<Output, String> {
+        self.workspaces
+            .outputs()
+            .find(|o| o.name() == output_name && o.user_data().get::<UdevOutputId>().is_some())
+            .cloned()
+            .ok_or_else(|| format!("no display named {output_name}"))
+    }
+
+    /// The modes the connector of `output_name` supports
+    pub fn output_modes(&self, output_name: &str) -> Result<Vec<Mode>, String> {
+        let output = self.udev_output(output_name)?;
+        let id = output.user_data().get::<UdevOutputId>().unwrap();
+        let device = self
+            .backend_data
+            .backends
+            .get(&id.device_id)
+            .ok_or_else(|| format!("the device of {output_name} is gone"))?;
+        device
+            .drm_scanner
+            .crtcs()
+            .find(|(_, crtc)| *crtc == id.crtc)
+            .map(|(connector, _)| connector.modes().to_vec())
+            .ok_or_else(|| format!("no connector drives {output_name}"))
+    }
+
+    /// Switch `output_name` to `width`x`height` at `refresh_hz`, or at the
+    /// highest rate of that size when `refresh_hz` is `None`, and lay out the
+    /// outputs again for the new size.
+    pub fn set_output_mode(
+        &mut self,
+        output_name: &str,
+        width: u32,
+        height: u32,
+        refresh_hz: Option<f64>,
+    ) -> Result<(), String> {
+        let output = self.udev_output(output_name)?;
+        let modes = self.output_modes(output_name)?;
+        let candidates: Vec<_> = modes.iter().map(mode_summary).collect();
+        let drm_mode = select_mode(&candidates, width, height, refresh_hz)
+            .map(|index| modes[index])
+            .ok_or_else(|| {
+                let available: Vec<_> = candidates
+                    .iter()
+                    .map(|(w, h, refresh, _)| format!("{w}x{h}@{:.2}", *refresh as f64 / 1000.0))
+                    .collect();
+                let requested = match refresh_hz {
+                    Some(hz) => format!("{width}x{height}@{hz}"),
+                    None => format!("{width}x{height}"),
+                };
+                format!(
+                    "{output_name} does not support {requested}; available: {}",
+                    available.join(", ")
+                )
+            })?;
+
+        let id = output.user_data().get::<UdevOutputId>().unwrap();
+        let surface = self
+            .backend_data
+            .backends
+            .get_mut(&id.device_id)
+            .and_then(|device| device.surfaces.get_mut(&id.crtc))
+            .ok_or_else(|| format!("{output_name} is not active"))?;
+        surface
+            .compositor
+            .use_mode(drm_mode)
+            .map_err(|err| format!("failed to switch {output_name} to the new mode: {err}"))?;
+        // The next frame is a full redraw at the new size
+        surface.prefetched_scene_damage = None;
+        surface.was_direct_scanout = false;
+
+        let wl_mode = WlMode::from(drm_mode);
+        info!(
+            "Switched {} to {}x{} @ {:.2}Hz",
+            output_name,
+            wl_mode.size.w,
+            wl_mode.size.h,
+            wl_mode.refresh as f64 / 1000.0
+        );
+        output.change_current_state(Some(wl_mode), None, None, None);
+
+        if self.workspaces.primary_output() == Some(&output) {
+            let size = output.current_transform().transform_size(wl_mode.size);
+            let scale = output.current_scale().fractional_scale();
+            self.workspaces.set_screen_dimension(size.w, size.h, scale);
+            let (w, h) = (size.w as f32, size.h as f32);
+            if let Some(root) = self.scene_element.root_layer() {
+                root.set_size(layers::types::Size::points(w, h), None);
+            }
+            self.scene_element.set_size(w, h);
+            self.layers_engine.scene_set_size(w, h);
+        }
+        crate::shell::fixup_positions(&mut self.workspaces, self.pointer.current_location());
+        self.backend_data.request_redraw();
+        Ok(())
+    }
+}
+
+/// Width, height, refresh in mHz and whether the mode is preferred
+pub(crate) fn mode_summary(mode: &Mode) -> (u32, u32, i32, bool) {
+    let (w, h) = mode.size();
+    let refresh = WlMode::from(*mode).refresh;
+    let preferred = mode.mode_type().contains(ModeTypeFlags::PREFERRED);
+    (w as u32, h as u32, refresh, preferred)
+}
+
+/// Index of the mode of `width`x`height` closest to `refresh_hz`, within
+/// [`REFRESH_TOLERANCE_MHZ`]. Without a rate the fastest mode of that size
+/// wins, the preferred one on a tie.
+pub(super) fn select_mode(
+    modes: &[(u32, u32, i32, bool)],
+    width: u32,
+    height: u32,
+    refresh_hz: Option<f64>,
+) -> Option<usize> {
+    let sized = modes
+        .iter()
+        .enumerate()
+        .filter(|(_, (w, h, _, _))| *w == width && *h == height);
+    match refresh_hz {
+        Some(hz) => {
+            let target = (hz * 1000.0).round() as i32;
+            sized
+                .map(|(index, (_, _, refresh, _))| (index, (refresh - target).abs()))
+                .filter(|(_, distance)| *distance <= REFRESH_TOLERANCE_MHZ)
+                .min_by_key(|(_, distance)| *distance)
+                .map(|(index, _)| index)
+        }
+        None => sized
+            .max_by_key(|(_, (_, _, refresh, preferred))| (*refresh, *preferred))
+            .map(|(index, _)| index),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODES: &[(u32, u32, i32, bool)] = &[
+        (2560, 1440, 59951, true),
+        (2560, 1440, 143912, false),
+        (2560, 1440, 120000, false),
+        (1920, 1080, 60000, false),
+    ];
+
+    #[test]
+    fn select_mode_matches_nearby_refresh() {
+        assert_eq!(select_mode(MODES, 2560, 1440, Some(60.0)), Some(0));
+        assert_eq!(select_mode(MODES, 2560, 1440, Some(144.0)), Some(1));
+        assert_eq!(select_mode(MODES, 2560, 1440, Some(120.0)), Some(2));
+    }
+
+    #[test]
+    fn select_mode_rejects_unsupported_modes() {
+        assert_eq!(select_mode(MODES, 2560, 1440, Some(75.0)), None);
+        assert_eq!(select_mode(MODES, 3840, 2160, None), None);
+        assert_eq!(select_mode(MODES, 1920, 1080, Some(144.0)), None);
+    }
+
+    #[test]
+    fn select_mode_defaults_to_fastest() {
+        assert_eq!(select_mode(MODES, 2560, 1440, None), Some(1));
+        assert_eq!(select_mode(MODES, 1920, 1080, None), Some(3));
+    }
+}
@@ -0,0 +1,262 @@
+// Display probing for `otto --probe`
+//
+// Lists the connected displays of every DRM device with their modes and
+// capabilities, either as text or, with `--json`, as a JSON array for
+// installers and config generators. Each entry carries the connector, vendor
+// and model that `[[displays.generic]]` matchers are checked against, plus a
+// ready-made `match` object for a profile of exactly that display.
+
+use serde::Serialize;
+use smithay::{
+    backend::{
+        drm::{DrmDevice, DrmDeviceFd},
+        session::{libseat::LibSeatSession, Session},
+        udev::UdevBackend,
+    },
+    reexports::{
+        drm::control::{connector, property, Device as ControlDevice, Mode, ModeTypeFlags},
+        rustix::fs::OFlags,
+    },
+    utils::DeviceFd,
+};
+
+use crate::config::{DisplayKind, DisplayMatcher};
+
+use super::edid;
+
+/// A connected display, as printed by `--probe --json`
+#[derive(Debug, Serialize)]
+struct ProbedDisplay {
+    device: String,
+    connector: String,
+    kind: DisplayKind,
+    vendor: String,
+    model: String,
+    physical_size_mm: Option<PhysicalSize>,
+    modes: Vec<ProbedMode>,
+    current_mode: Option<ProbedMode>,
+    vrr_capable: bool,
+    /// Highest bits per color channel the connector accepts
+    max_bpc: Option<u64>,
+    supports_10bit: bool,
+    /// Matcher for a `[[displays.generic]]` profile of this display
+    #[serde(rename = "match")]
+    matcher: DisplayMatcher,
+}
+
+#[derive(Debug, Serialize)]
+struct PhysicalSize {
+    width: u32,
+    height: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct ProbedMode {
+    width: u16,
+    height: u16,
+    refresh_hz: u32,
+    preferred: bool,
+}
+
+impl From<&Mode> for ProbedMode {
+    fn from(mode: &Mode) -> Self {
+        Self {
+            width: mode.size().0,
+            height: mode.size().1,
+            refresh_hz: mode.vrefresh(),
+            preferred: mode.mode_type().contains(ModeTypeFlags::PREFERRED),
+        }
+    }
+}
+
+/// Print the connected displays and their modes, as text or as JSON
+pub fn probe_displays(json: bool) {
+    if !json {
+        #[allow(clippy::disallowed_macros)]
+        {
+            println!("Probing available displays and resolutions...\n");
+        }
+    }
+
+    let (mut session, _notifier) = match LibSeatSession::new() {
+        Ok(ret) => ret,
+        Err(err) => {
+            tracing::error!("Could not initialize a session: {}", err);
+            #[allow(clippy::disallowed_macros)]
+            {
+                eprintln!("Error: Could not initialize session - {}", err);
+                eprintln!(
+                    "Note: This command may require root privileges or proper seat permissions."
+                );
+            }
+            return;
+        }
+    };
+
+    let udev_backend = match UdevBackend::new(session.seat()) {
+        Ok(ret) => ret,
+        Err(err) => {
+            tracing::error!("Failed to initialize udev backend: {:?}", err);
+            #[allow(clippy::disallowed_macros)]
+            {
+                eprintln!("Error: Failed to initialize udev backend - {:?}", err);
+            }
+            return;
+        }
+    };
+
+    let mut displays = Vec::new();
+    for (device_id, path) in udev_backend.device_list() {
+        let fd = match session.open(
+            path,
+            OFlags::RDWR | OFlags::CLOEXEC | OFlags::NOCTTY | OFlags::NONBLOCK,
+        ) {
+            Ok(fd) => DrmDeviceFd::new(DeviceFd::from(fd)),
+            Err(err) => {
+                tracing::warn!("Failed to open DRM device {}: {}", device_id, err);
+                continue;
+            }
+        };
+
+        let (drm_device, _notifier) = match DrmDevice::new(fd, true) {
+            Ok(device) => device,
+            Err(err) => {
+                tracing::warn!("Failed to create DRM device: {:?}", err);
+                continue;
+            }
+        };
+
+        displays.extend(probe_device(&drm_device, &path.display().to_string()));
+    }
+
+    if json {
+        match serde_json::to_string_pretty(&displays) {
+            #[allow(clippy::disallowed_macros)]
+            Ok(output) => println!("{output}"),
+            Err(err) => tracing::error!("Failed to serialize displays: {}", err),
+        }
+    } else {
+        print_displays(&displays);
+    }
+}
+
+/// Connected displays of one DRM device
+fn probe_device(drm_device: &DrmDevice, device: &str) -> Vec<ProbedDisplay> {
+    let res_handles = match drm_device.resource_handles() {
+        Ok(res) => res,
+        Err(err) => {
+            tracing::warn!("Failed to get resource handles: {:?}", err);
+            return Vec::new();
+        }
+    };
+
+    res_handles
+        .connectors()
+        .iter()
+        .filter_map(|handle| {
+            let info = match drm_device.get_connector(*handle, false) {
+                Ok(info) => info,
+                Err(err) => {
+                    tracing::warn!("Failed to get connector info: {:?}", err);
+                    return None;
+                }
+            };
+            (info.state() == connector::State::Connected)
+                .then(|| probe_connector(drm_device, device, &info))
+        })
+        .collect()
+}
+
+fn probe_connector(drm_device: &DrmDevice, device: &str, info: &connector::Info) -> ProbedDisplay {
+    let connector = format!("{}-{}", info.interface().as_str(), info.interface_id());
+    // Same identity the udev backend matches display profiles against
+    let identity = edid::read_identity(drm_device, info.handle());
+    let (vendor, model) = edid::vendor_and_model(identity.as_ref(), info.interface());
+    let kind = if crate::utils::is_laptop_panel(&connector) {
+        DisplayKind::Internal
+    } else {
+        DisplayKind::External
+    };
+
+    let current_mode = info
+        .current_encoder()
+        .and_then(|encoder| drm_device.get_encoder(encoder).ok()?.crtc())
+        .and_then(|crtc| drm_device.get_crtc(crtc).ok()?.mode())
+        .map(|mode| ProbedMode::from(&mode));
+
+    let vrr_capable = connector_property(drm_device, info.handle(), "vrr_capable")
+        .and_then(|(property, value)| property.value_type().convert_value(value).as_boolean())
+        .unwrap_or(false);
+    let max_bpc =
+        connector_property(drm_device, info.handle(), "max bpc").and_then(|(property, _)| {
+            match property.value_type() {
+                property::ValueType::UnsignedRange(_, max) => Some(max),
+                _ => None,
+            }
+        });
+
+    ProbedDisplay {
+        device: device.to_string(),
+        matcher: DisplayMatcher {
+            connector: Some(connector.clone()),
+            vendor: Some(vendor.clone()),
+            model: Some(model.clone()),
+            // The udev backend does not report a kind when matching
+            ..Default::default()
+        },
+        connector,
+        kind,
+        vendor,
+        model,
+        physical_size_mm: info
+            .size()
+            .map(|(width, height)| PhysicalSize { width, height }),
+        modes: info.modes().iter().map(ProbedMode::from).collect(),
+        current_mode,
+        vrr_capable,
+        max_bpc,
+        supports_10bit: max_bpc.is_some_and(|bpc| bpc >= 10),
+    }
+}
+
+/// Info and current value of the connector property called `name`
+pub(super) fn connector_property(
+    drm_device: &DrmDevice,
+    handle: connector::Handle,
+    name: &str,
+) -> Option<(property::Info, property::RawValue)> {
+    drm_device
+        .get_properties(handle)
+        .ok()?
+        .into_iter()
+        .filter_map(|(handle, value)| Some((drm_device.get_property(handle).ok()?, value)))
+        .find(|(info, _)| info.name().to_str() == Ok(name))
+}
+
+#[allow(clippy::disallowed_macros)]
+fn print_displays(displays: &[ProbedDisplay]) {
+    if displays.is_empty() {
+        println!("\nNo connected displays found.");
+        return;
+    }
+
+    let mut device = None;
+    for display in displays {
+        if device != Some(&display.device) {
+            println!("Device: {}", display.device);
+            device = Some(&display.device);
+        }
+        println!("\n  Connector: {}", display.connector);
+        println!("  Status: Connected");
+        println!("  Available modes:");
+        for mode in &display.modes {
+            println!(
+                "    {}x{} @ {} Hz{}",
+                mode.width,
+                mode.height,
+                mode.refresh_hz,
+                if mode.preferred { " (preferred)" } else { "" }
+            );
+        }
+    }
+}
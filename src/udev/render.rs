@@ -12,12 +12,16 @@ use std::{
 };
 
 use crate::{
-    config::Config,
-    cursor::{CursorManager, CursorTextureCache},
+    config::{window_rules::ContentType, Config},
+    cursor::{CursorManager, CursorTextureCache, XCursor},
     drawing::*,
     render::*,
     render_elements::workspace_render_elements::WorkspaceRenderElements,
-    render_elements::{output_render_elements::OutputRenderElements, scene_element::SceneElement},
+    render_elements::{
+        output_render_elements::OutputRenderElements,
+        scene_element::{SceneElement, SceneMirror},
+    },
+    screenshare::StreamTarget,
     shell::{WindowElement, WindowRenderElement},
     state::{post_repaint, take_presentation_feedback, SurfaceDmabufFeedback},
 };
@@ -27,7 +31,7 @@ use smithay::{
         drm::{DrmAccessError, DrmError, DrmEventMetadata, DrmNode},
         renderer::{
             damage::OutputDamageTracker,
-            element::{AsRenderElements, Kind},
+            element::{AsRenderElements, Kind, Wrap},
             Bind,
         },
         SwapBuffersError,
@@ -42,7 +46,9 @@ use smithay::{
         wayland_protocols::wp::presentation_time::server::wp_presentation_feedback,
         wayland_server::protocol::wl_surface,
     },
-    utils::{Clock, IsAlive, Logical, Monotonic, Physical, Point, Rectangle, Scale},
+    utils::{
+        Clock, IsAlive, Logical, Monotonic, Physical, Point, Rectangle, Scale, Size, Transform,
+    },
     wayland::presentation::Refresh,
 };
 use tracing::{debug, trace, warn};
@@ -72,6 +78,9 @@ impl Otto<UdevData> {
     ) {
         profiling::scope!("frame_finish", &format!("{crtc:?}"));
 
+        // A queued frame is now on screen
+        self.screenshot_frame_presented();
+
         let device_backend = match self.backend_data.backends.get_mut(&dev_id) {
             Some(backend) => backend,
             None => {
@@ -113,7 +122,7 @@ impl Otto<UdevData> {
                             .map(|metadata| metadata.sequence)
                             .unwrap_or(0);
 
-                        let (clock, flags) = if let Some(tp) = tp {
+                        let (clock, mut flags) = if let Some(tp) = tp {
                             (
                                 tp.into(),
                                 wp_presentation_feedback::Kind::Vsync
@@ -123,6 +132,10 @@ impl Otto<UdevData> {
                         } else {
                             (self.clock.now(), wp_presentation_feedback::Kind::Vsync)
                         };
+                        // A tearing flip was not synchronized to the vertical blank
+                        if surface.tearing {
+                            flags.remove(wp_presentation_feedback::Kind::Vsync);
+                        }
 
                         feedback.presented(
                             clock,
@@ -193,6 +206,13 @@ impl Otto<UdevData> {
             let scene_has_damage = self.scene_element.update();
             surface.prefetched_scene_damage = Some(scene_has_damage);
 
+            // With VRR on, a directly scanned out fullscreen window sets the
+            // pace: the next frame is drawn when it commits, not on a timer
+            if surface.vrr && surface.was_direct_scanout {
+                surface.awaiting_commit = true;
+                return;
+            }
+
             // ── Frame-pipeline Phase 2: schedule the draw at the deadline ─────
             //
             // We want to submit the next page flip as close to the upcoming
@@ -206,8 +226,13 @@ impl Otto<UdevData> {
             // For multi-GPU paths a buffer copy is needed after rendering; we
             // have no reliable estimate for the copy duration, so we fire
             // immediately and accept the slightly-wider timing window.
+            //
+            // A scanned out video does not need the low latency either: it
+            // is drawn right away too, so a slow frame has the whole period
+            // to finish instead of missing the flip.
             let is_multi_gpu = self.backend_data.primary_gpu != surface.render_node;
-            let timer = if is_multi_gpu {
+            let relaxed_pacing = surface.scanout_content_type == ContentType::Video;
+            let timer = if is_multi_gpu || relaxed_pacing {
                 Timer::immediate()
             } else {
                 // output_refresh is in millihertz (mHz); convert to µs/frame.
@@ -254,6 +279,7 @@ impl Otto<UdevData> {
         // Render virtual outputs once per primary GPU cycle
         if node == self.backend_data.primary_gpu {
             self.render_virtual_outputs();
+            self.render_window_streams();
         }
     }
 
@@ -263,6 +289,8 @@ impl Otto<UdevData> {
 
         // Tick gamma transitions before rendering
         self.tick_gamma_transitions();
+        self.tick_zoom();
+        self.tick_cursor_scales();
 
         // ── Frame-pipeline: consume pre-computed scene update ─────────────────
         //
@@ -364,8 +392,18 @@ impl Otto<UdevData> {
         // - Current workspace must be in fullscreen mode and not animating
         // - Disable during expose gesture
         // - Disable during workspace swipe gesture
-        let allow_direct_scanout =
-            self.workspaces.is_fullscreen_and_stable() && !self.swipe_gesture.is_active();
+        // - Disable while the magnifier is zoomed in
+        // - Disable while a screencopy client waits for this output, since
+        //   frames are copied out of the composited framebuffer
+        // - Disable while the microphone or recording badge is on this output
+        let allow_direct_scanout = self.workspaces.is_fullscreen_and_stable()
+            && !self.workspaces.overlay_badges_on(&output)
+            && !self.swipe_gesture.is_active()
+            && !self.magnifier.is_active()
+            && !self
+                .pending_screencopy_frames
+                .iter()
+                .any(|p| p.output == output);
 
         // Only fetch the fullscreen window if direct scanout is allowed
         let fullscreen_window = if allow_direct_scanout {
@@ -381,6 +419,11 @@ impl Otto<UdevData> {
             .get(&output.name())
             .map(|ows| self.scene_element.for_output_layer(&ows.output_layer))
             .unwrap_or_else(|| self.scene_element.clone());
+        // The magnifier scales the scene; the cursor is moved to match below
+        let output_scene_element = match self.zoom_scene_mirror(&output) {
+            Some(zoom) => output_scene_element.mirrored(zoom),
+            None => output_scene_element,
+        };
 
         // Classify every window into its visibility state so post_repaint can
         // pick a per-window frame-callback throttle. `occluded_ids` is empty
@@ -395,12 +438,17 @@ impl Otto<UdevData> {
             expose_active,
         );
 
+        let cursors: Vec<_> = self
+            .seat_cursors()
+            .into_iter()
+            .map(|(seat, location)| (seat, self.zoom_cursor_location(&output, location)))
+            .collect();
         let result = render_surface(
             surface,
             &mut renderer,
             &all_window_elements,
             &output,
-            self.pointer.current_location(),
+            &cursors,
             &self.cursor_manager,
             &self.cursor_texture_cache,
             self.dnd_icon.as_ref(),
@@ -456,7 +504,7 @@ impl Otto<UdevData> {
 
         // Render to screenshare buffers if rendering succeeded
         if let Ok(outcome) = &result {
-            if outcome.rendered && !self.screenshare_sessions.is_empty() {
+            if !self.screenshare_sessions.is_empty() {
                 let scale = Scale::from(output.current_scale().fractional_scale());
 
                 // Get the source framebuffer that was just rendered to
@@ -465,9 +513,27 @@ impl Otto<UdevData> {
                     // Check if we should render cursor for this session
                     // CURSOR_MODE_HIDDEN (1) = don't render cursor
                     // CURSOR_MODE_EMBEDDED (2) = render cursor into video
-                    // CURSOR_MODE_METADATA (4) = send cursor as metadata (not in video) - NOT IMPLEMENTED, treat as hidden
+                    // CURSOR_MODE_METADATA (4) = send cursor as SPA_META_Cursor, not in video
                     const CURSOR_MODE_EMBEDDED: u32 = 2;
-                    let should_render_cursor = session.cursor_mode == CURSOR_MODE_EMBEDDED;
+                    const CURSOR_MODE_METADATA: u32 = 4;
+                    let should_render_cursor =
+                        outcome.rendered && session.cursor_mode == CURSOR_MODE_EMBEDDED;
+                    let cursor_metadata =
+                        (session.cursor_mode == CURSOR_MODE_METADATA).then(|| {
+                            crate::screenshare::cursor_metadata(
+                                &self.cursor_manager,
+                                &self.seat_name,
+                                &output,
+                                self.workspaces.output_geometry(&output).unwrap_or_default(),
+                                self.pointer.current_location(),
+                                self.clock.now().as_millis(),
+                            )
+                        });
+
+                    // Without new pixels only metadata sessions have anything to send
+                    if !outcome.rendered && cursor_metadata.is_none() {
+                        continue;
+                    }
 
                     tracing::trace!(
                         "Screenshare session {}: cursor_mode={}, should_render={}",
@@ -488,65 +554,16 @@ impl Otto<UdevData> {
                             .contains(pointer_location.to_physical(scale));
 
                         if pointer_in_output {
-                            use crate::cursor::RenderCursor;
-                            use smithay::backend::renderer::element::memory::MemoryRenderBufferRenderElement;
-                            use smithay::backend::renderer::element::surface::render_elements_from_surface_tree;
-
-                            let mut elements = Vec::new();
-
-                            match self
-                                .cursor_manager
-                                .get_render_cursor(output_scale.round() as i32)
-                            {
-                                RenderCursor::Hidden => {}
-                                RenderCursor::Surface { hotspot, surface } => {
-                                    let cursor_pos_scaled = (pointer_location.to_physical(scale)
-                                        - hotspot.to_f64().to_physical(scale))
-                                    .to_i32_round();
-                                    let cursor_elems: Vec<WorkspaceRenderElements<_>> =
-                                        render_elements_from_surface_tree(
-                                            &mut renderer,
-                                            &surface,
-                                            cursor_pos_scaled,
-                                            scale,
-                                            1.0,
-                                            Kind::Cursor,
-                                        );
-                                    elements.extend(cursor_elems);
-                                }
-                                RenderCursor::Named {
-                                    icon,
-                                    scale: _,
-                                    cursor,
-                                } => {
-                                    let elapsed_millis = self.clock.now().as_millis();
-                                    let (idx, image) = cursor.frame(elapsed_millis);
-                                    let texture = self.cursor_texture_cache.get(
-                                        icon,
-                                        output_scale.round() as i32,
-                                        &cursor,
-                                        idx,
-                                    );
-                                    let hotspot_physical =
-                                        Point::from((image.xhot as f64, image.yhot as f64));
-                                    let cursor_pos_scaled: Point<i32, Physical> =
-                                        (pointer_location.to_physical(scale) - hotspot_physical)
-                                            .to_i32_round();
-                                    let elem = MemoryRenderBufferRenderElement::from_buffer(
-                                        &mut renderer,
-                                        cursor_pos_scaled.to_f64(),
-                                        &texture,
-                                        None,
-                                        None,
-                                        None,
-                                        Kind::Cursor,
-                                    )
-                                    .expect("Failed to create cursor render element");
-                                    elements.push(WorkspaceRenderElements::from(elem));
-                                }
-                            }
-
-                            elements
+                            cursor_elements(
+                                &mut renderer,
+                                self.cursor_manager
+                                    .get_render_cursor(&self.seat_name, output_scale),
+                                pointer_location.to_physical(scale),
+                                scale,
+                                output_scale,
+                                &self.cursor_texture_cache,
+                                &self.clock,
+                            )
                         } else {
                             Vec::new()
                         }
@@ -555,63 +572,98 @@ impl Otto<UdevData> {
                     };
 
                     for (connector, stream) in &session.streams {
-                        if connector == &output.name() {
-                            let buffer_pool = stream.pipewire_stream.buffer_pool();
-                            let mut pool = buffer_pool.lock().unwrap();
-
-                            if let Some(available) = pool.available.pop_front() {
-                                let size = output
-                                    .current_mode()
-                                    .map(|m| m.size)
-                                    .unwrap_or_else(|| (1920, 1080).into());
-
-                                // Force full frame for first render (when last_rendered_fd is None)
-                                let is_first_frame = pool.last_rendered_fd.is_none();
-                                let buffer_changed = pool.last_rendered_fd != Some(available.fd);
-
-                                pool.last_rendered_fd = Some(available.fd);
-
-                                // Use damage only if not first frame and same buffer
-                                let damage_to_use = if is_first_frame || buffer_changed {
-                                    None // Full frame for first render or buffer change
-                                } else {
-                                    outcome.damage.as_deref()
-                                };
-
-                                if is_first_frame {
-                                    tracing::debug!(
-                                        "First frame for stream on {}, forcing full blit",
-                                        connector
-                                    );
-                                }
+                        // Region streams crop `area` out of their output's frame
+                        let area = match stream.pipewire_stream.target() {
+                            StreamTarget::Output if connector == &output.name() => None,
+                            StreamTarget::Region {
+                                output: region_output,
+                                area,
+                            } if region_output == &output.name() => Some(*area),
+                            _ => continue,
+                        };
+                        let cursor_changed = cursor_metadata.as_ref().is_some_and(|m| {
+                            let mut m = m.clone();
+                            if let Some(area) = area {
+                                m.position.0 -= area.loc.x;
+                                m.position.1 -= area.loc.y;
+                            }
+                            stream.pipewire_stream.set_cursor(m)
+                        });
+                        if !outcome.rendered {
+                            // Keep the cursor moving on frames with no pixel damage
+                            if cursor_changed {
+                                stream.pipewire_stream.queue_cursor_update();
+                            }
+                            continue;
+                        }
+
+                        let buffer_pool = stream.pipewire_stream.buffer_pool();
+                        let mut pool = buffer_pool.lock().unwrap();
+
+                        if let Some(available) = pool.available.pop_front() {
+                            let size = output
+                                .current_mode()
+                                .map(|m| m.size)
+                                .unwrap_or_else(|| (1920, 1080).into());
+
+                            // Force full frame for first render (when last_rendered_fd is None)
+                            let is_first_frame = pool.last_rendered_fd.is_none();
+                            let buffer_changed = pool.last_rendered_fd != Some(available.fd);
 
-                                // Blit from source framebuffer and render cursor on top
-                                let blit_result = crate::screenshare::fullscreen_to_dmabuf(
+                            pool.last_rendered_fd = Some(available.fd);
+
+                            // Use damage only if not first frame and same buffer
+                            let damage_to_use = if is_first_frame || buffer_changed {
+                                None // Full frame for first render or buffer change
+                            } else {
+                                outcome.damage.as_deref()
+                            };
+
+                            if is_first_frame {
+                                tracing::debug!(
+                                    "First frame for stream on {}, forcing full blit",
+                                    connector
+                                );
+                            }
+
+                            // Blit from source framebuffer and render cursor on top
+                            let blit_result = match area {
+                                None => crate::screenshare::fullscreen_to_dmabuf(
                                     &mut renderer,
                                     &mut available.dmabuf.clone(),
                                     size,
                                     damage_to_use,
                                     &cursor_elements,
                                     scale,
-                                );
-
-                                if let Err(e) = blit_result {
-                                    tracing::debug!("Screenshare blit failed: {}", e);
-                                } else {
-                                    // Only increment sequence on successful blit
-                                    stream.pipewire_stream.increment_frame_sequence();
-                                }
+                                ),
+                                Some(area) => crate::screenshare::region_to_dmabuf(
+                                    &mut renderer,
+                                    &mut available.dmabuf.clone(),
+                                    area,
+                                    &cursor_elements,
+                                    scale,
+                                ),
+                            };
 
-                                pool.to_queue.insert(available.fd, available.pw_buffer);
-                                drop(pool);
-                                // Trigger to queue the buffer we just rendered
-                                stream.pipewire_stream.trigger_frame();
+                            if let Err(e) = blit_result {
+                                tracing::debug!("Screenshare blit failed: {}", e);
                             } else {
-                                // No buffer available - trigger to dequeue any released buffers
-                                drop(pool);
-                                stream.pipewire_stream.trigger_frame();
-                                tracing::trace!("No available buffers for screenshare on {}, triggering dequeue", connector);
+                                // Only increment sequence on successful blit
+                                stream.pipewire_stream.increment_frame_sequence();
                             }
+
+                            pool.to_queue.insert(available.fd, available.pw_buffer);
+                            drop(pool);
+                            // Trigger to queue the buffer we just rendered
+                            stream.pipewire_stream.trigger_frame();
+                        } else {
+                            // No buffer available - trigger to dequeue any released buffers
+                            drop(pool);
+                            stream.pipewire_stream.trigger_frame();
+                            tracing::trace!(
+                                "No available buffers for screenshare on {}, triggering dequeue",
+                                connector
+                            );
                         }
                     }
                 } // Close for session loop
@@ -621,6 +673,7 @@ impl Otto<UdevData> {
         {
             self.workspaces.refresh_space();
             self.popups.cleanup();
+            self.publish_compositor_events();
             self.update_dnd();
         }
 
@@ -628,6 +681,9 @@ impl Otto<UdevData> {
         let render_time_us = start.elapsed().as_micros() as f32;
         let has_animations = self.scene_element.has_pending_animations();
         let was_rendered = result.as_ref().map(|o| o.rendered).unwrap_or(false);
+        if was_rendered {
+            self.screenshot_frame_rendered();
+        }
         if let Some(device) = self.backend_data.backends.get_mut(&node) {
             if let Some(surface) = device.surfaces.get_mut(&crtc) {
                 surface.avg_render_time_us =
@@ -763,97 +819,107 @@ impl Otto<UdevData> {
             let output_clone = self.virtual_outputs[i].output.clone();
             let output_name = output_clone.name();
 
-            // Per-output scene element — renders only this output's sub-tree
-            let output_scene_element = self
-                .workspaces
-                .output_workspaces
-                .get(&output_name)
-                .map(|ows| scene_element.for_output_layer(&ows.output_layer))
-                .unwrap_or_else(|| scene_element.clone());
-
-            // Build cursor elements if pointer is over this output
-            let scale = Scale::from(output_clone.current_scale().fractional_scale());
             let output_mode_size = output_clone
                 .current_mode()
                 .map(|m| m.size)
                 .unwrap_or_default();
             let output_geometry = Rectangle::new((0, 0).into(), output_mode_size);
+
+            // Output whose scene sub-tree is shown: this one, or the mirror
+            // source (`None` if the source is not connected; frames are blank).
+            let (scene_output, mirror) = match self.virtual_outputs[i].mirror_of.as_deref() {
+                None => (Some(output_clone.clone()), None),
+                Some(source_name) => {
+                    let source = self
+                        .workspaces
+                        .outputs()
+                        .find(|o| o.name() == source_name)
+                        .cloned();
+                    let mirror = source.as_ref().map(|source| {
+                        let source_size_px = source
+                            .current_mode()
+                            .map(|m| source.current_transform().transform_size(m.size))
+                            .unwrap_or_default();
+                        SceneMirror::letterbox(
+                            (source_size_px.w, source_size_px.h),
+                            (output_mode_size.w, output_mode_size.h),
+                        )
+                    });
+                    (source, mirror)
+                }
+            };
+
+            // Per-output scene element — renders only the sub-tree of `scene_output`
+            let output_scene_element = match (&scene_output, mirror) {
+                (Some(source), Some(mirror)) => self
+                    .workspaces
+                    .output_workspaces
+                    .get(&source.name())
+                    .map(|ows| {
+                        scene_element
+                            .for_output_layer(&ows.output_layer)
+                            .mirrored(mirror)
+                    }),
+                (Some(_), None) => Some(
+                    self.workspaces
+                        .output_workspaces
+                        .get(&output_name)
+                        .map(|ows| scene_element.for_output_layer(&ows.output_layer))
+                        .unwrap_or_else(|| scene_element.clone()),
+                ),
+                (None, _) => None,
+            };
+
+            // Pointer position in this output's framebuffer, if the cursor
+            // should be drawn on it
+            let scale = Scale::from(output_clone.current_scale().fractional_scale());
             let pointer_location = self.pointer.current_location();
-            // Virtual output's logical position in the scene
-            let vout_geo = self.workspaces.output_geometry(&output_clone);
-            let local_pointer: Point<f64, Logical> = vout_geo
-                .map(|geo| {
-                    (
-                        pointer_location.x - geo.loc.x as f64,
-                        pointer_location.y - geo.loc.y as f64,
-                    )
-                        .into()
-                })
-                .unwrap_or(pointer_location);
-            let pointer_in_output = output_geometry
-                .to_f64()
-                .contains(local_pointer.to_physical(scale));
+            let pointer_px: Option<Point<f64, Physical>> =
+                scene_output.as_ref().and_then(|source| {
+                    // Logical position of the shown output in the scene
+                    let source_geo = self.workspaces.output_geometry(source);
+                    let source_scale = source.current_scale().fractional_scale();
+                    let local_pointer: Point<f64, Logical> = source_geo
+                        .map(|geo| {
+                            (
+                                pointer_location.x - geo.loc.x as f64,
+                                pointer_location.y - geo.loc.y as f64,
+                            )
+                                .into()
+                        })
+                        .unwrap_or(pointer_location);
+                    let pointer_px = local_pointer.to_physical(source_scale);
+                    match mirror {
+                        None => Some(pointer_px),
+                        Some(mirror) => source_geo
+                            .filter(|geo| geo.to_f64().contains(pointer_location))
+                            .map(|_| {
+                                (
+                                    pointer_px.x * mirror.scale as f64 + mirror.offset_x_px as f64,
+                                    pointer_px.y * mirror.scale as f64 + mirror.offset_y_px as f64,
+                                )
+                                    .into()
+                            }),
+                    }
+                });
+            let pointer_px = pointer_px.filter(|p| output_geometry.to_f64().contains(*p));
 
             // Helper closure — builds fresh cursor elements (can't clone render elements)
             let build_cursor_elements = |renderer: &mut _| -> Vec<WorkspaceRenderElements<_>> {
-                if !pointer_in_output {
+                let Some(pointer_px) = pointer_px else {
                     return Vec::new();
-                }
-                use crate::cursor::RenderCursor;
-                use smithay::backend::renderer::element::memory::MemoryRenderBufferRenderElement;
-                use smithay::backend::renderer::element::surface::render_elements_from_surface_tree;
+                };
                 let output_scale = output_clone.current_scale().fractional_scale();
-                let mut elems = Vec::new();
-                match self
-                    .cursor_manager
-                    .get_render_cursor(output_scale.round() as i32)
-                {
-                    RenderCursor::Hidden => {}
-                    RenderCursor::Surface { hotspot, surface } => {
-                        let cursor_pos_scaled = (local_pointer.to_physical(scale)
-                            - hotspot.to_f64().to_physical(scale))
-                        .to_i32_round();
-                        let cursor_elems: Vec<WorkspaceRenderElements<_>> =
-                            render_elements_from_surface_tree(
-                                renderer,
-                                &surface,
-                                cursor_pos_scaled,
-                                scale,
-                                1.0,
-                                Kind::Cursor,
-                            );
-                        elems.extend(cursor_elems);
-                    }
-                    RenderCursor::Named {
-                        icon,
-                        scale: _,
-                        cursor,
-                    } => {
-                        let elapsed_millis = self.clock.now().as_millis();
-                        let (idx, image) = cursor.frame(elapsed_millis);
-                        let texture = self.cursor_texture_cache.get(
-                            icon,
-                            output_scale.round() as i32,
-                            &cursor,
-                            idx,
-                        );
-                        let hotspot_physical = Point::from((image.xhot as f64, image.yhot as f64));
-                        let cursor_pos_scaled: Point<i32, Physical> =
-                            (local_pointer.to_physical(scale) - hotspot_physical).to_i32_round();
-                        if let Ok(elem) = MemoryRenderBufferRenderElement::from_buffer(
-                            renderer,
-                            cursor_pos_scaled.to_f64(),
-                            &texture,
-                            None,
-                            None,
-                            None,
-                            Kind::Cursor,
-                        ) {
-                            elems.push(WorkspaceRenderElements::from(elem));
-                        }
-                    }
-                }
-                elems
+                cursor_elements(
+                    renderer,
+                    self.cursor_manager
+                        .get_render_cursor(&self.seat_name, output_scale),
+                    pointer_px,
+                    scale,
+                    output_scale,
+                    &self.cursor_texture_cache,
+                    &self.clock,
+                )
             };
 
             // --- Render into this virtual output's own PipeWire stream ---
@@ -872,8 +938,11 @@ impl Otto<UdevData> {
                     match renderer.bind(&mut dmabuf) {
                         Ok(mut framebuffer) => {
                             let mut elements = build_cursor_elements(&mut renderer);
-                            elements
-                                .push(WorkspaceRenderElements::Scene(output_scene_element.clone()));
+                            elements.extend(
+                                output_scene_element
+                                    .clone()
+                                    .map(WorkspaceRenderElements::Scene),
+                            );
                             let _ = crate::render::render_output(
                                 &output_clone,
                                 &all_window_elements,
@@ -913,9 +982,11 @@ impl Otto<UdevData> {
                             match renderer.bind(&mut ss_dmabuf) {
                                 Ok(mut fb) => {
                                     let mut ss_elements = build_cursor_elements(&mut renderer);
-                                    ss_elements.push(WorkspaceRenderElements::Scene(
-                                        output_scene_element.clone(),
-                                    ));
+                                    ss_elements.extend(
+                                        output_scene_element
+                                            .clone()
+                                            .map(WorkspaceRenderElements::Scene),
+                                    );
                                     let _ = crate::render::render_output(
                                         &output_clone,
                                         &all_window_elements,
@@ -944,6 +1015,176 @@ impl Otto<UdevData> {
             }
         }
     }
+
+    /// Render single-window screenshare streams.
+    ///
+    /// Each stream receives only its window's surface tree, drawn at the
+    /// window's current size on the primary GPU. When the window resizes the
+    /// stream format is renegotiated; when it is closed the stream is dropped,
+    /// which disconnects it on the PipeWire side, and its D-Bus object emits
+    /// `Closed`. The pointer is drawn in, or sent as metadata, while it is
+    /// over the window, as the session's cursor mode asks.
+    pub(super) fn render_window_streams(&mut self) {
+        const CURSOR_MODE_EMBEDDED: u32 = 2;
+        const CURSOR_MODE_METADATA: u32 = 4;
+
+        let primary_gpu = self.backend_data.primary_gpu;
+        let pointer_location = self.pointer.current_location();
+        let mut closed = Vec::new();
+
+        for (session_id, session) in self.screenshare_sessions.iter_mut() {
+            for (key, stream) in session.streams.iter_mut() {
+                let StreamTarget::Window(window) = stream.pipewire_stream.target().clone() else {
+                    continue;
+                };
+                if !window.alive() {
+                    closed.push((session_id.clone(), key.clone()));
+                    continue;
+                }
+
+                let output = self
+                    .workspaces
+                    .outputs_for_element(&window)
+                    .first()
+                    .cloned();
+                let scale = output
+                    .as_ref()
+                    .map(|o| o.current_scale().fractional_scale())
+                    .unwrap_or(1.0);
+                let geometry = window.geometry();
+                // Where the window is on screen; `None` off the current workspace
+                let window_area = self.workspaces.element_geometry(&window);
+                let size_px = geometry.size.to_f64().to_physical(scale).to_i32_round();
+                if size_px.w <= 0 || size_px.h <= 0 {
+                    continue;
+                }
+                let size = (size_px.w as u32, size_px.h as u32);
+                if size != stream.pipewire_stream.size() {
+                    // New buffers arrive once the consumer accepts the new format
+                    stream.pipewire_stream.resize(size.0, size.1);
+                    continue;
+                }
+
+                let pool_arc = stream.pipewire_stream.buffer_pool();
+                let maybe_buf = {
+                    let mut pool = pool_arc.lock().unwrap();
+                    pool.available.pop_front().inspect(|buf| {
+                        pool.to_queue.insert(buf.fd, buf.pw_buffer);
+                    })
+                };
+                let Some(available) = maybe_buf else {
+                    stream.pipewire_stream.trigger_frame();
+                    trace!("render_window_streams: no buffer for '{key}'");
+                    continue;
+                };
+
+                if session.cursor_mode == CURSOR_MODE_METADATA {
+                    if let (Some(output), Some(area)) = (output.as_ref(), window_area) {
+                        stream
+                            .pipewire_stream
+                            .set_cursor(crate::screenshare::cursor_metadata(
+                                &self.cursor_manager,
+                                &self.seat_name,
+                                output,
+                                area,
+                                pointer_location,
+                                self.clock.now().as_millis(),
+                            ));
+                    }
+                }
+
+                let mut renderer = match self.backend_data.gpus.single_renderer(&primary_gpu) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        warn!("render_window_streams: failed to get renderer: {e}");
+                        continue;
+                    }
+                };
+                // The pointer over the window, in stream pixels
+                let cursor_location = window_area
+                    .filter(|_| session.cursor_mode == CURSOR_MODE_EMBEDDED)
+                    .filter(|area| area.to_f64().contains(pointer_location))
+                    .map(|area| (pointer_location - area.loc.to_f64()).to_physical(scale));
+                let mut elements: Vec<OutputRenderElements<'_, _, WindowRenderElement<_>>> =
+                    match cursor_location {
+                        Some(location) => cursor_elements(
+                            &mut renderer,
+                            self.cursor_manager
+                                .get_render_cursor(&self.seat_name, scale),
+                            location,
+                            Scale::from(scale),
+                            scale,
+                            &self.cursor_texture_cache,
+                            &self.clock,
+                        )
+                        .into_iter()
+                        .map(OutputRenderElements::Workspace)
+                        .collect(),
+                        None => Vec::new(),
+                    };
+                let mut dmabuf = available.dmabuf.clone();
+                match renderer.bind(&mut dmabuf) {
+                    Ok(mut framebuffer) => {
+                        // Place the window geometry (not the CSD shadow) at the origin
+                        let location =
+                            Point::<i32, Logical>::from((-geometry.loc.x, -geometry.loc.y))
+                                .to_physical_precise_round(scale);
+                        elements.extend(
+                            AsRenderElements::render_elements::<WindowRenderElement<_>>(
+                                &window,
+                                &mut renderer,
+                                location,
+                                Scale::from(scale),
+                                1.0,
+                            )
+                            .into_iter()
+                            .map(|e| OutputRenderElements::Window(Wrap::from(e))),
+                        );
+                        let mut damage_tracker =
+                            OutputDamageTracker::new(size_px, scale, Transform::Normal);
+                        match damage_tracker.render_output(
+                            &mut renderer,
+                            &mut framebuffer,
+                            0,
+                            &elements,
+                            [0.0, 0.0, 0.0, 0.0],
+                        ) {
+                            Ok(_) => stream.pipewire_stream.increment_frame_sequence(),
+                            Err(e) => warn!("render_window_streams: render failed: {e:?}"),
+                        }
+                    }
+                    Err(e) => {
+                        warn!("render_window_streams: bind failed for '{key}': {e}");
+                    }
+                }
+                stream.pipewire_stream.trigger_frame();
+            }
+        }
+
+        let events = self
+            .screenshare_manager
+            .as_ref()
+            .map(|manager| manager.event_sender.clone());
+        for (session_id, key) in closed {
+            if session_id == crate::state::screen_recording::RECORDING_SESSION {
+                // Let the encoder finalize the file; poll_recording drops the
+                // stream once it exits
+                self.stop_recording();
+                continue;
+            }
+            if let Some(session) = self.screenshare_sessions.get_mut(&session_id) {
+                debug!("Shared window closed, ending stream {key} of {session_id}");
+                // Dropping the stream stops its thread and disconnects it
+                session.streams.remove(&key);
+            }
+            if let Some(events) = events.as_ref() {
+                let _ = events.send(crate::compositor_service::CompositorEvent::StreamClosed {
+                    session_id,
+                    stream_key: key,
+                });
+            }
+        }
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -953,7 +1194,7 @@ pub(super) fn render_surface<'a>(
     renderer: &mut UdevRenderer<'a>,
     window_elements: &[&WindowElement],
     output: &Output,
-    pointer_location: Point<f64, Logical>,
+    cursors: &[(String, Point<f64, Logical>)],
     cursor_manager: &CursorManager,
     cursor_texture_cache: &CursorTextureCache,
     dnd_icon: Option<&wl_surface::WlSurface>,
@@ -972,7 +1213,7 @@ pub(super) fn render_surface<'a>(
     let _frame_timer = surface
         .render_metrics
         .as_ref()
-        .map(|m: &Arc<_>| m.start_frame());
+        .map(|m: &Arc<_>| m.start_frame(&output.name()));
 
     let output_geometry = Rectangle::new((0, 0).into(), output.current_mode().unwrap().size);
     let scale = Scale::from(output.current_scale().fractional_scale());
@@ -982,56 +1223,33 @@ pub(super) fn render_surface<'a>(
     let output_scale = output.current_scale().fractional_scale();
     let dnd_needs_draw = dnd_icon.map(|surface| surface.alive()).unwrap_or(false);
 
-    let pointer_in_output = output_geometry
-        .to_f64()
-        .contains(pointer_location.to_physical(scale));
-
-    if pointer_in_output {
-        use crate::cursor::RenderCursor;
-        use smithay::backend::renderer::element::surface::render_elements_from_surface_tree;
-
-        match cursor_manager.get_render_cursor(output_scale.round() as i32) {
-            RenderCursor::Hidden => {}
-            RenderCursor::Surface { hotspot, surface } => {
-                let cursor_pos_scaled = (pointer_location.to_physical(scale)
-                    - hotspot.to_f64().to_physical(scale))
-                .to_i32_round();
-                let elements: Vec<WorkspaceRenderElements<_>> = render_elements_from_surface_tree(
-                    renderer,
-                    &surface,
-                    cursor_pos_scaled,
-                    scale,
-                    1.0,
-                    Kind::Cursor,
-                );
-                workspace_render_elements.extend(elements);
-            }
-            RenderCursor::Named {
-                icon,
-                scale: _,
-                cursor,
-            } => {
-                let elapsed_millis = clock.now().as_millis();
-                let (idx, image) = cursor.frame(elapsed_millis);
-                let texture =
-                    cursor_texture_cache.get(icon, output_scale.round() as i32, &cursor, idx);
-                use smithay::backend::renderer::element::memory::MemoryRenderBufferRenderElement;
-                let hotspot_physical = Point::from((image.xhot as f64, image.yhot as f64));
-                let cursor_pos_scaled: Point<i32, Physical> =
-                    (pointer_location.to_physical(scale) - hotspot_physical).to_i32_round();
-                let elem = MemoryRenderBufferRenderElement::from_buffer(
-                    renderer,
-                    cursor_pos_scaled.to_f64(),
-                    &texture,
-                    None,
-                    None,
-                    None,
-                    Kind::Cursor,
-                )
-                .expect("Failed to create cursor render element");
-                workspace_render_elements.push(WorkspaceRenderElements::from(elem));
-            }
+    let screencopy_forced = pending_screencopy
+        .iter()
+        .any(|p| p.output == *output && p.forces_render());
+    let screencopy_cursor = pending_screencopy
+        .iter()
+        .any(|p| p.output == *output && p.overlay_cursor);
+
+    // Every seat with a cursor on this output draws it; the physical seat
+    // comes first so it is the one that gets the hardware cursor plane
+    let mut pointer_in_output = false;
+    for (seat, pointer_location) in cursors {
+        let location = pointer_location.to_physical(scale);
+        if !output_geometry.to_f64().contains(location) {
+            continue;
         }
+        pointer_in_output = true;
+        let cursor_scale = cursor_manager.cursor_scale(seat).unwrap_or(output_scale);
+        let cursor = cursor_manager.get_render_cursor(seat, cursor_scale);
+        workspace_render_elements.extend(cursor_elements(
+            renderer,
+            cursor,
+            location,
+            scale,
+            output_scale,
+            cursor_texture_cache,
+            clock,
+        ));
     }
 
     #[cfg(feature = "fps_ticker")]
@@ -1041,6 +1259,9 @@ pub(super) fn render_surface<'a>(
         workspace_render_elements.push(WorkspaceRenderElements::Fps(element.clone()));
     }
 
+    // Any render satisfies a VRR frame waiting for a commit
+    surface.awaiting_commit = false;
+
     // Track direct scanout mode transitions
     let is_direct_scanout = fullscreen_window.is_some();
     let mode_changed = is_direct_scanout != surface.was_direct_scanout;
@@ -1052,6 +1273,29 @@ pub(super) fn render_surface<'a>(
         surface.compositor.reset_buffers();
     }
 
+    // Tearing is only ever allowed for a fullscreen window on the direct
+    // scanout path; everything else keeps waiting for vblank
+    let tearing = fullscreen_window.is_some_and(|window| window.wants_tearing())
+        && surface.supports_async_page_flip
+        && Config::with(|c| c.allow_tearing);
+    if tearing != surface.tearing {
+        debug!("Tearing page flips {}", if tearing { "on" } else { "off" });
+    }
+
+    let content_type = fullscreen_window
+        .map(|window| window.content_type())
+        .unwrap_or_default();
+    if content_type != surface.scanout_content_type {
+        debug!(?content_type, "Scanned out content type changed");
+        surface.scanout_content_type = content_type;
+    }
+
+    surface.update_game_vrr(
+        fullscreen_window.map(|window| window.id()),
+        content_type == ContentType::Game,
+        &output.name(),
+    );
+
     // If fullscreen_window is Some, direct scanout is allowed (checked by caller)
     let (output_elements, clear_color, should_draw) =
         if let Some(fullscreen_win) = fullscreen_window {
@@ -1088,11 +1332,13 @@ pub(super) fn render_surface<'a>(
             // When nothing actually changed, render_frame returns is_empty=true
             // and no page flip occurs, so this is cheap in the idle case.
             let cursor_needs_draw = pointer_in_output;
-            // Screencopy never forces a render: pending capture frames piggyback on
+            // `copy_with_damage` frames never force a render: they piggyback on
             // the next render that happens for some other reason (scene damage,
             // cursor, DND). This avoids a 120 Hz capture loop when a client keeps
-            // a frame request outstanding.
-            let should_draw = scene_has_damage || dnd_needs_draw || cursor_needs_draw;
+            // a frame request outstanding. A plain `copy` wants the current
+            // contents, so it draws even on a static screen.
+            let should_draw =
+                scene_has_damage || dnd_needs_draw || cursor_needs_draw || screencopy_forced;
             if !should_draw {
                 return Ok(RenderOutcome::skipped());
             }
@@ -1116,15 +1362,26 @@ pub(super) fn render_surface<'a>(
         return Ok(RenderOutcome::skipped());
     }
 
+    let mut frame_flags = smithay::backend::drm::compositor::FrameFlags::ALLOW_CURSOR_PLANE_SCANOUT
+        | smithay::backend::drm::compositor::FrameFlags::ALLOW_PRIMARY_PLANE_SCANOUT_ANY;
+    if tearing {
+        frame_flags |= smithay::backend::drm::compositor::FrameFlags::ALLOW_TEARING;
+    }
+    // A capture with the cursor copies it out of the framebuffer, so keep
+    // it off the hardware cursor plane for this frame
+    if screencopy_cursor {
+        frame_flags
+            .remove(smithay::backend::drm::compositor::FrameFlags::ALLOW_CURSOR_PLANE_SCANOUT);
+    }
+    // Without damage the compositor would skip the frame and leave a plain
+    // `copy` waiting; start from fresh buffers so it is fully redrawn
+    if screencopy_forced {
+        surface.compositor.reset_buffers();
+    }
+
     let render_frame_result = surface
         .compositor
-        .render_frame(
-            renderer,
-            &output_elements,
-            clear_color,
-            smithay::backend::drm::compositor::FrameFlags::ALLOW_CURSOR_PLANE_SCANOUT
-                | smithay::backend::drm::compositor::FrameFlags::ALLOW_PRIMARY_PLANE_SCANOUT_ANY,
-        )
+        .render_frame(renderer, &output_elements, clear_color, frame_flags)
         .map_err(|err| match err {
             smithay::backend::drm::compositor::RenderFrameError::PrepareFrame(err) => err.into(),
             smithay::backend::drm::compositor::RenderFrameError::RenderFrame(
@@ -1163,7 +1420,9 @@ pub(super) fn render_surface<'a>(
 
         if let Some(ref damage_rects) = damage {
             // Have actual damage information
-            metrics.as_ref().record_damage(output_size, damage_rects);
+            metrics
+                .as_ref()
+                .record_damage(&output.name(), output_size, damage_rects);
         } else if rendered {
             // No damage info available (DRM compositor mode), but frame was rendered
             // Record full frame as damage as approximation
@@ -1171,7 +1430,12 @@ pub(super) fn render_surface<'a>(
                 (0, 0).into(),
                 (mode.size.w, mode.size.h).into(),
             )];
-            metrics.as_ref().record_damage(output_size, &full_screen);
+            metrics
+                .as_ref()
+                .record_damage(&output.name(), output_size, &full_screen);
+        }
+        if rendered && tearing {
+            metrics.as_ref().record_tearing_frame();
         }
     }
 
@@ -1209,6 +1473,7 @@ pub(super) fn render_surface<'a>(
                 pending_screencopy,
                 output,
                 renderer,
+                damage.as_deref(),
             );
         }
 
@@ -1217,6 +1482,7 @@ pub(super) fn render_surface<'a>(
         surface
             .compositor
             .queue_frame(Some(output_presentation_feedback))?;
+        surface.tearing = tearing;
     }
 
     Ok(RenderOutcome {
@@ -1225,6 +1491,65 @@ pub(super) fn render_surface<'a>(
     })
 }
 
+/// Render elements for `cursor` with its hotspot at `location`, in
+/// output-local physical pixels
+fn cursor_elements<'a>(
+    renderer: &mut UdevRenderer<'a>,
+    cursor: crate::cursor::RenderCursor,
+    location: Point<f64, Physical>,
+    scale: Scale<f64>,
+    output_scale: f64,
+    cursor_texture_cache: &CursorTextureCache,
+    clock: &Clock<Monotonic>,
+) -> Vec<WorkspaceRenderElements<'a, UdevRenderer<'a>>> {
+    use crate::cursor::RenderCursor;
+    use smithay::backend::renderer::element::{
+        memory::MemoryRenderBufferRenderElement, surface::render_elements_from_surface_tree,
+    };
+
+    match cursor {
+        RenderCursor::Hidden => Vec::new(),
+        RenderCursor::Surface { hotspot, surface } => {
+            let cursor_pos_scaled = (location - hotspot.to_f64().to_physical(scale)).to_i32_round();
+            render_elements_from_surface_tree(
+                renderer,
+                &surface,
+                cursor_pos_scaled,
+                scale,
+                1.0,
+                Kind::Cursor,
+            )
+        }
+        RenderCursor::Named {
+            icon,
+            scale: xcursor_scale,
+            size,
+            cursor,
+        } => {
+            let elapsed_millis = clock.now().as_millis();
+            let (idx, image) = cursor.frame(elapsed_millis);
+            let texture = cursor_texture_cache.get(icon, xcursor_scale, &cursor, idx);
+            // Scale the themed image to exactly `size`, hotspot included
+            let factor = XCursor::image_scale(image, size);
+            let hotspot = XCursor::hotspot(image).to_f64().upscale(factor);
+            let image_size: Size<f64, Physical> =
+                (image.width as f64 * factor, image.height as f64 * factor).into();
+            let cursor_pos_scaled: Point<i32, Physical> = (location - hotspot).to_i32_round();
+            let elem = MemoryRenderBufferRenderElement::from_buffer(
+                renderer,
+                cursor_pos_scaled.to_f64(),
+                &texture,
+                None,
+                None,
+                Some(image_size.to_logical(output_scale).to_i32_round()),
+                Kind::Cursor,
+            )
+            .expect("Failed to create cursor render element");
+            vec![WorkspaceRenderElements::from(elem)]
+        }
+    }
+}
+
 pub(super) fn initial_render(
     surface: &mut SurfaceData,
     renderer: &mut UdevRenderer<'_>,
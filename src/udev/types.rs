@@ -35,13 +35,22 @@ use smithay::{
 };
 use smithay_drm_extras::drm_scanner::DrmScanner;
 
+use crate::config::window_rules::ContentType;
 use crate::skia_renderer::SkiaRenderer;
 
+use super::vrr::GameVrr;
+
 // Supported pixel formats for rendering, in preference order.
 // Argb8888 maps to GL_BGRA_EXT which is Skia's native kN32 (BGRA8888) — no
-// channel swizzle needed.  Abgr2101010 is the only 10-bit format with a GL
-// mapping in smithay; Argb2101010 has none and is omitted.
-pub const SUPPORTED_FORMATS: &[Fourcc] = &[Fourcc::Abgr2101010, Fourcc::Argb8888, Fourcc::Abgr8888];
+// channel swizzle needed.  Abgr2101010 and Xbgr2101010 are the only 10-bit
+// formats with a GL mapping in smithay (GL_RGB10_A2, Skia's RGBA1010102);
+// the Argb/Xrgb variants have none and are omitted.
+pub const SUPPORTED_FORMATS: &[Fourcc] = &[
+    Fourcc::Abgr2101010,
+    Fourcc::Xbgr2101010,
+    Fourcc::Argb8888,
+    Fourcc::Abgr8888,
+];
 
 pub const SUPPORTED_FORMATS_8BIT_ONLY: &[Fourcc] = &[Fourcc::Argb8888, Fourcc::Abgr8888];
 
@@ -83,6 +92,16 @@ pub struct UdevData {
     pub context_id: Option<ContextId<MultiTexture>>,
     /// Flag set by `request_redraw` to trigger a render on next loop iteration.
     pub(super) render_requested: AtomicBool,
+    /// Timer driving virtual output rendering; present while any virtual output exists.
+    pub(super) virtual_output_timer: Option<RegistrationToken>,
+    /// Sender for runtime virtual output add/remove requests.
+    pub virtual_output_commands: Option<
+        smithay::reexports::calloop::channel::Sender<crate::virtual_output::VirtualOutputCommand>,
+    >,
+    /// Connected libinput devices, kept to re-apply input config on reload.
+    pub(super) input_devices: Vec<smithay::reexports::input::Device>,
+    /// Backlight of the internal panel, if any
+    pub(super) backlight: Option<super::backlight::Backlight>,
 }
 
 /// Per-device backend data
@@ -114,6 +133,22 @@ pub struct SurfaceData {
     /// Track whether we were in direct scanout mode on the previous frame
     /// Used to reset buffers when transitioning between modes
     pub(super) was_direct_scanout: bool,
+    /// Content type of the window scanned out on the previous frame, `None`
+    /// when the desktop was composited
+    pub(super) scanout_content_type: ContentType,
+    /// Whether the driver can flip without waiting for vblank
+    pub(super) supports_async_page_flip: bool,
+    /// Whether the frame waiting for its page flip was submitted with tearing
+    pub(super) tearing: bool,
+    /// Whether the connector advertises variable refresh rate
+    pub(super) vrr_supported: bool,
+    /// Whether VRR is currently on
+    pub(super) vrr: bool,
+    /// VRR for a scanned out fullscreen game (`game_vrr`)
+    pub(super) game_vrr: GameVrr,
+    /// With VRR on during direct scanout, the next frame is drawn when a
+    /// redraw is requested (e.g. a client commit) instead of on a timer
+    pub(super) awaiting_commit: bool,
     /// Rendering metrics
     #[cfg(feature = "metrics")]
     pub(super) render_metrics: Option<Arc<crate::render_metrics::RenderMetrics>>,
@@ -0,0 +1,225 @@
+// Runtime management of virtual outputs
+//
+// Virtual outputs are headless outputs whose frames are streamed over PipeWire.
+// They are created from config at startup and can be added or removed later
+// through a calloop channel (see `VirtualOutputCommand`), which the
+// `CreateVirtualOutput` and `RemoveVirtualOutput` D-Bus methods feed.
+
+use std::time::Duration;
+
+use smithay::reexports::calloop::{
+    channel::{channel, Event as ChannelEvent},
+    timer::{TimeoutAction, Timer},
+};
+use tracing::{error, info, warn};
+
+use crate::{
+    config::VirtualOutputConfig,
+    state::{Backend, Otto},
+    virtual_output::{VirtualOutputCommand, VirtualOutputState},
+};
+
+use super::types::UdevData;
+
+/// How long a removed output's global stays disabled before it is destroyed.
+///
+/// Gives clients time to see the global removal and release their `wl_output`
+/// before the resource goes away.
+const GLOBAL_REMOVAL_DELAY: Duration = Duration::from_secs(5);
+
+impl Otto<UdevData> {
+    /// Register the virtual output command channel and create the outputs from config.
+    pub(super) fn init_virtual_outputs(&mut self) {
+        let (sender, receiver) = channel::<VirtualOutputCommand>();
+        match self.handle.insert_source(receiver, |event, _, state| {
+            if let ChannelEvent::Msg(cmd) = event {
+                state.handle_virtual_output_command(cmd);
+            }
+        }) {
+            Ok(_) => self.backend_data.virtual_output_commands = Some(sender),
+            Err(e) => warn!("Failed to insert virtual output channel: {}", e),
+        }
+
+        let vout_configs = crate::config::Config::with(|c| c.virtual_outputs.clone());
+        for vout_config in vout_configs {
+            let name = vout_config.name.clone();
+            if let Err(e) = self.add_virtual_output(vout_config) {
+                error!("Failed to create virtual output '{}': {}", name, e);
+            }
+        }
+    }
+
+    fn handle_virtual_output_command(&mut self, cmd: VirtualOutputCommand) {
+        match cmd {
+            VirtualOutputCommand::Add {
+                config,
+                response_tx,
+            } => {
+                let name = config.name.clone();
+                let result = self.add_virtual_output(config);
+                if let Err(e) = &result {
+                    error!("Failed to create virtual output '{}': {}", name, e);
+                }
+                if let Some(tx) = response_tx {
+                    let _ = tx.send(result);
+                }
+            }
+            VirtualOutputCommand::Remove { name, response_tx } => {
+                let removed = self.remove_virtual_output(&name);
+                if let Some(tx) = response_tx {
+                    let _ = tx.send(Ok(removed));
+                }
+            }
+        }
+    }
+
+    /// Create a virtual output, map it into the layout and start its PipeWire stream.
+    ///
+    /// Returns the PipeWire node ID that clients connect to.
+    pub fn add_virtual_output(&mut self, config: VirtualOutputConfig) -> Result<u32, String> {
+        if self.workspaces.outputs().any(|o| o.name() == config.name)
+            || self
+                .virtual_outputs
+                .iter()
+                .any(|v| v.output.name() == config.name)
+        {
+            return Err(format!("an output named '{}' already exists", config.name));
+        }
+
+        let gbm_device = self.backend_data.gbm_device();
+        let format_modifiers = self
+            .backend_data
+            .get_format_modifiers(smithay::backend::allocator::Fourcc::Argb8888);
+
+        let output = VirtualOutputState::build_output(&config);
+        // A mirror only shows another output's content: it is neither part of
+        // the layout nor advertised to clients.
+        let mirror = config.mirror_of.is_some();
+        let global = (!mirror).then(|| {
+            let position: smithay::utils::Point<i32, smithay::utils::Logical> = config
+                .position
+                .map(|p| (p.x, p.y).into())
+                .unwrap_or_else(|| (0, 0).into());
+            self.workspaces.map_output(&output, position);
+            output.create_global::<Otto<UdevData>>(&self.display_handle)
+        });
+
+        match VirtualOutputState::start(
+            output.clone(),
+            global.clone(),
+            &config,
+            gbm_device,
+            format_modifiers,
+        ) {
+            Ok((vout_state, node_id)) => {
+                info!(
+                    "Virtual output '{}' started (PipeWire node {}). \
+                     Connect with: pw-play --target {}",
+                    config.name, node_id, node_id
+                );
+                self.virtual_outputs.push(vout_state);
+                self.restart_virtual_output_timer();
+                if !mirror {
+                    crate::shell::fixup_positions(
+                        &mut self.workspaces,
+                        self.pointer.current_location(),
+                    );
+                }
+                Ok(node_id)
+            }
+            Err(e) => {
+                if let Some(global) = global {
+                    self.workspaces.unmap_output(&output);
+                    self.display_handle.remove_global::<Otto<UdevData>>(global);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Destroy a virtual output: stop its stream, unmap it and withdraw its global.
+    ///
+    /// Returns `false` if no virtual output with that name exists.
+    pub fn remove_virtual_output(&mut self, name: &str) -> bool {
+        let Some(index) = self
+            .virtual_outputs
+            .iter()
+            .position(|v| v.output.name() == name)
+        else {
+            return false;
+        };
+        let vout_state = self.virtual_outputs.remove(index);
+
+        // Mirrors were never mapped or advertised.
+        if let Some(global) = vout_state.global.clone() {
+            // Disable first and destroy later: clients may still be bound to
+            // the global and must get the chance to release it.
+            self.display_handle
+                .disable_global::<Otto<UdevData>>(global.clone());
+            let _ = self.handle.insert_source(
+                Timer::from_duration(GLOBAL_REMOVAL_DELAY),
+                move |_, _, state| {
+                    state
+                        .display_handle
+                        .remove_global::<Otto<UdevData>>(global.clone());
+                    TimeoutAction::Drop
+                },
+            );
+
+            let moved = self.workspaces.unmap_output(&vout_state.output);
+            for id in moved {
+                self.refresh_foreign_toplevel(&id);
+            }
+            crate::shell::fixup_positions(&mut self.workspaces, self.pointer.current_location());
+        }
+        // Dropping the state stops the PipeWire stream.
+        drop(vout_state);
+
+        self.restart_virtual_output_timer();
+        info!("Virtual output '{}' removed", name);
+        true
+    }
+
+    /// (Re)start the timer driving virtual output rendering at the lowest
+    /// refresh rate among the current virtual outputs, or stop it if none remain.
+    ///
+    /// The timer keeps frames flowing even when physical outputs are idle.
+    fn restart_virtual_output_timer(&mut self) {
+        if let Some(token) = self.backend_data.virtual_output_timer.take() {
+            self.handle.remove(token);
+        }
+        if self.virtual_outputs.is_empty() {
+            return;
+        }
+
+        let refresh_hz = self
+            .virtual_outputs
+            .iter()
+            .filter_map(|v| v.output.current_mode())
+            .map(|m| m.refresh as f64 / 1000.0)
+            .fold(f64::INFINITY, f64::min);
+        let refresh_hz = if refresh_hz.is_finite() {
+            refresh_hz
+        } else {
+            60.0
+        };
+        let interval = Duration::from_micros((1_000_000.0 / refresh_hz) as u64);
+
+        match self.handle.insert_source(
+            Timer::from_duration(interval),
+            move |_, _, data: &mut Otto<UdevData>| {
+                data.render_virtual_outputs();
+                TimeoutAction::ToDuration(interval)
+            },
+        ) {
+            Ok(token) => {
+                self.backend_data.virtual_output_timer = Some(token);
+                info!(
+                    "Virtual output render timer started at {:.1} Hz",
+                    refresh_hz
+                );
+            }
+            Err(e) => error!("Failed to schedule virtual output render timer: {}", e),
+        }
+    }
+}
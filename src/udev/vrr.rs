@@ -0,0 +1,130 @@
+// Variable refresh rate (adaptive sync)
+//
+// Sets VRR_ENABLED on an output's CRTC through the DRM compositor. While VRR
+// is on and a fullscreen window is scanned out directly, frames are paced to
+// the client's commits instead of the fixed refresh timer (see
+// `frame_finish` and the udev event loop).
+
+use smithay::{
+    backend::drm::VrrSupport,
+    output::Output,
+    reexports::{drm::control::connector, wayland_server::backend::ObjectId},
+};
+use tracing::{info, warn};
+
+use crate::{config::Config, state::Otto};
+
+use super::types::{GbmDrmCompositor, SurfaceData, UdevData, UdevOutputId};
+
+/// Whether the connector can turn on VRR
+pub(super) fn connector_supports_vrr(
+    compositor: &GbmDrmCompositor,
+    connector: connector::Handle,
+) -> bool {
+    matches!(
+        compositor.vrr_supported(connector),
+        Ok(VrrSupport::Supported | VrrSupport::RequiresModeset)
+    )
+}
+
+/// `game_vrr` state of an output. A user toggle or a failed attempt keeps
+/// VRR as it is until another window is scanned out.
+#[derive(Debug, Default)]
+pub(super) struct GameVrr {
+    /// VRR was turned on for the scanned out game and goes off with it
+    active: bool,
+    /// The user toggled VRR since the scanned out window changed
+    overridden: bool,
+    /// Turning VRR on failed for the scanned out window
+    failed: bool,
+    /// Surface of the window scanned out on the previous frame
+    scanout: Option<ObjectId>,
+}
+
+impl SurfaceData {
+    /// Turn VRR on while `scanout` is a game and `game_vrr` is set, and off
+    /// again once it is gone. An output whose VRR is already on is left as
+    /// it is.
+    pub(super) fn update_game_vrr(
+        &mut self,
+        scanout: Option<ObjectId>,
+        is_game: bool,
+        output_name: &str,
+    ) {
+        if self.game_vrr.scanout != scanout {
+            self.game_vrr.scanout = scanout;
+            self.game_vrr.overridden = false;
+            self.game_vrr.failed = false;
+        }
+        let wants_vrr = is_game
+            && self.vrr_supported
+            && !self.game_vrr.overridden
+            && !self.game_vrr.failed
+            && Config::with(|c| c.game_vrr);
+        if wants_vrr && !self.vrr {
+            self.game_vrr.active = self.set_vrr(true, output_name);
+            self.game_vrr.failed = !self.game_vrr.active;
+        } else if !wants_vrr && self.game_vrr.active {
+            self.set_vrr(false, output_name);
+            self.game_vrr.active = false;
+        }
+    }
+
+    /// Turn VRR on or off. Returns whether VRR is on afterwards.
+    pub(super) fn set_vrr(&mut self, enabled: bool, output_name: &str) -> bool {
+        if enabled && !self.vrr_supported {
+            warn!("{output_name} does not advertise VRR, keeping a fixed refresh rate");
+            self.vrr = false;
+            return false;
+        }
+        match self.compositor.use_vrr(enabled) {
+            Ok(()) => {
+                info!(
+                    "VRR {} on {output_name}",
+                    if enabled { "enabled" } else { "disabled" }
+                );
+                self.vrr = enabled;
+            }
+            Err(err) => {
+                warn!(?err, "Failed to change VRR on {output_name}, disabling it");
+                if self.vrr && self.compositor.use_vrr(false).is_err() {
+                    warn!("Failed to disable VRR on {output_name}");
+                }
+                self.vrr = false;
+            }
+        }
+        // A frame waiting for a commit would otherwise wait forever without
+        // VRR; callers outside the render loop must draw the output again
+        self.awaiting_commit = false;
+        self.vrr
+    }
+}
+
+impl Otto<UdevData> {
+    /// Flip VRR on the output. Returns whether VRR is on afterwards.
+    pub fn toggle_vrr(&mut self, output: &Output) -> bool {
+        let Some(id) = output.user_data().get::<UdevOutputId>() else {
+            return false;
+        };
+        let (node, crtc) = (id.device_id, id.crtc);
+        let Some(surface) = self
+            .backend_data
+            .backends
+            .get_mut(&id.device_id)
+            .and_then(|device| device.surfaces.get_mut(&id.crtc))
+        else {
+            return false;
+        };
+        let enabled = !surface.vrr;
+        // The user takes over from `game_vrr` until another window is
+        // scanned out
+        surface.game_vrr.active = false;
+        surface.game_vrr.overridden = true;
+        let vrr = surface.set_vrr(enabled, &output.name());
+        // Draw now at the new pacing, instead of waiting for a commit that
+        // may never come
+        self.handle
+            .insert_idle(move |data| data.render(node, Some(crtc)));
+        vrr
+    }
+}
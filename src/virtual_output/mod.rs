@@ -20,15 +20,18 @@ use smithay::{
 
 use crate::{
     config::VirtualOutputConfig,
-    screenshare::{BackendCapabilities, PipeWireStream, StreamConfig},
+    screenshare::{BackendCapabilities, PipeWireStream, StreamConfig, StreamTarget},
 };
 
 /// Runtime state for one virtual output.
 pub struct VirtualOutputState {
     /// The Smithay output (Wayland global, workspace mapping).
     pub output: Output,
-    /// Global handle — must be kept alive for the Wayland global to exist.
-    pub _global: GlobalId,
+    /// Global handle, withdrawn when the output is removed.
+    /// Mirrors are not advertised to clients and have none.
+    pub global: Option<GlobalId>,
+    /// Name of the output whose content this output mirrors, if any.
+    pub mirror_of: Option<String>,
     /// PipeWire stream receiving rendered frames.
     pub pipewire_stream: PipeWireStream,
     /// Damage tracker for this output (always renders full frames, age=0).
@@ -39,7 +42,7 @@ impl VirtualOutputState {
     /// Build an `Output` from config (without registering a Wayland global yet).
     ///
     /// The caller is responsible for calling `output.create_global::<D>()` and
-    /// storing the returned `GlobalId` in `_global`, then calling `finish()`.
+    /// passing the returned `GlobalId` to `start()`.
     pub fn build_output(config: &VirtualOutputConfig) -> Output {
         let output = Output::new(
             config.name.clone(),
@@ -84,7 +87,7 @@ impl VirtualOutputState {
     /// Returns the state and the PipeWire node ID that clients connect to.
     pub fn start(
         output: Output,
-        global: GlobalId,
+        global: Option<GlobalId>,
         config: &VirtualOutputConfig,
         gbm_device: Option<GbmDevice<DrmDeviceFd>>,
         format_modifiers: Vec<u64>,
@@ -108,6 +111,7 @@ impl VirtualOutputState {
             framerate_denom: 1,
             capabilities,
             gbm_device,
+            target: StreamTarget::Output,
         };
 
         let mut pipewire_stream = PipeWireStream::new(stream_config);
@@ -117,7 +121,8 @@ impl VirtualOutputState {
 
         let state = Self {
             output,
-            _global: global,
+            global,
+            mirror_of: config.mirror_of.clone(),
             pipewire_stream,
             damage_tracker,
         };
@@ -125,3 +130,19 @@ impl VirtualOutputState {
         Ok((state, node_id))
     }
 }
+
+/// Requests to create or destroy virtual outputs while the compositor is running.
+///
+/// Sent over the calloop channel whose sender is kept in the udev backend data.
+pub enum VirtualOutputCommand {
+    /// Create a virtual output; replies with the PipeWire node ID.
+    Add {
+        config: VirtualOutputConfig,
+        response_tx: Option<tokio::sync::oneshot::Sender<Result<u32, String>>>,
+    },
+    /// Destroy the virtual output with the given name; replies whether it existed.
+    Remove {
+        name: String,
+        response_tx: Option<tokio::sync::oneshot::Sender<Result<bool, String>>>,
+    },
+}
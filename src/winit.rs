@@ -53,6 +53,7 @@ use tracing::{error, info, warn};
 
 use crate::{
     config::{Config, DisplayDescriptor, DisplayKind, DisplayResolution, WINIT_DISPLAY_ID},
+    input::actions::KeyAction,
     render::*,
     render_elements::workspace_render_elements::WorkspaceRenderElements,
     renderer::SkiaTexture,
@@ -216,9 +217,13 @@ impl Backend for WinitData {
     fn request_redraw(&mut self) {
         self.full_redraw = self.full_redraw.max(2);
     }
+    fn run_key_action(state: &mut Otto<Self>, action: KeyAction) {
+        state.process_key_action_windowed(action, OUTPUT_NAME);
+    }
 }
 
 pub fn run_winit() {
+    Config::write_starter_config_on_first_run();
     let mut event_loop = EventLoop::try_new().unwrap();
     let display = Display::new().unwrap();
     let mut display_handle = display.handle();
@@ -369,7 +374,11 @@ pub fn run_winit() {
 
     // Set initial screen dimensions before mapping output so update_workspaces_layout
     // can compute the correct scene size.
-    state.workspaces.set_screen_dimension(size.w, size.h);
+    state.workspaces.set_screen_dimension(
+        size.w,
+        size.h,
+        output.current_scale().fractional_scale(),
+    );
     state.workspaces.map_output(&output, (0, 0));
 
     #[cfg(feature = "xwayland")]
@@ -425,7 +434,9 @@ pub fn run_winit() {
                 crate::shell::fixup_positions(&mut state.workspaces, pointer_location);
                 // set_screen_dimension triggers update_workspaces_layout which resizes
                 // the scene root to cover all outputs' physical extents.
-                state.workspaces.set_screen_dimension(size.w, size.h);
+                state
+                    .workspaces
+                    .set_screen_dimension(size.w, size.h, config_screen_scale);
             }
             WinitEvent::Input(event) => state.process_input_event_windowed(event, OUTPUT_NAME),
             _ => (),
@@ -436,6 +447,7 @@ pub fn run_winit() {
             break;
         }
 
+        state.tick_zoom();
         let scene_has_damage = state.scene_element.update();
         let mut needs_redraw_soon;
         let pointer_active;
@@ -453,7 +465,7 @@ pub fn run_winit() {
                     *cursor_guard = CursorImageStatus::default_named();
                     state
                         .cursor_manager
-                        .set_cursor_image(CursorImageStatus::default_named());
+                        .set_cursor_image(&state.seat_name, CursorImageStatus::default_named());
                 }
             }
 
@@ -492,7 +504,7 @@ pub fn run_winit() {
             if should_draw {
                 // Start frame timing
                 #[cfg(feature = "metrics")]
-                let _frame_timer = state.render_metrics.start_frame();
+                let _frame_timer = state.render_metrics.start_frame(&output.name());
 
                 #[cfg(feature = "debug")]
                 {
@@ -571,6 +583,10 @@ pub fn run_winit() {
                         .get(&output.name())
                         .map(|ows| state.scene_element.for_output_layer(&ows.output_layer))
                         .unwrap_or_else(|| state.scene_element.clone());
+                    let scene_element = match state.zoom_scene_mirror(&output) {
+                        Some(zoom) => scene_element.mirrored(zoom),
+                        None => scene_element,
+                    };
                     elements.push(WorkspaceRenderElements::Scene(scene_element));
 
                     render_output(
@@ -599,7 +615,11 @@ pub fn run_winit() {
                             {
                                 let mode = output.current_mode().unwrap();
                                 let output_size = (mode.size.w, mode.size.h);
-                                state.render_metrics.record_damage(output_size, damage);
+                                state.render_metrics.record_damage(
+                                    &output.name(),
+                                    output_size,
+                                    damage,
+                                );
                             }
 
                             match backend.submit(Some(damage)) {
@@ -661,6 +681,8 @@ pub fn run_winit() {
                         }
 
                         if has_rendered {
+                            state.screenshot_frame_rendered();
+                            state.screenshot_frame_presented();
                             let all_window_elements: Vec<&WindowElement> =
                                 state.workspaces.spaces_elements().collect();
                             let mut output_presentation_feedback = take_presentation_feedback(
@@ -730,6 +752,7 @@ pub fn run_winit() {
         } else {
             state.workspaces.refresh_space();
             state.popups.cleanup();
+            state.publish_compositor_events();
             display_handle.flush_clients().unwrap();
         }
     }
@@ -39,6 +39,10 @@ pub struct AppIconsManager {
     /// `render_node_tree` can produce output for mirror followers.
     pub container: Layer,
     entries: RwLock<HashMap<String, AppIconEntry>>,
+    /// Latest badge text per app, kept so a stack created later starts with it.
+    badges: RwLock<HashMap<String, String>>,
+    /// Latest progress value per app, kept so a stack created later starts with it.
+    progress: RwLock<HashMap<String, f64>>,
 }
 
 impl std::fmt::Debug for AppIconsManager {
@@ -62,6 +66,8 @@ impl AppIconsManager {
             engine,
             container,
             entries: RwLock::new(HashMap::new()),
+            badges: RwLock::new(HashMap::new()),
+            progress: RwLock::new(HashMap::new()),
         }
     }
 
@@ -136,6 +142,15 @@ impl AppIconsManager {
                 icon_id,
             },
         );
+        // Apply a badge or progress set before the app had an icon
+        let badge = self.badges.read().unwrap().get(app_id).cloned();
+        if badge.is_some() {
+            self.update_badge(app_id, badge);
+        }
+        let progress = self.progress.read().unwrap().get(app_id).copied();
+        if progress.is_some() {
+            self.update_progress(app_id, progress);
+        }
         stack
     }
 
@@ -161,7 +176,16 @@ impl AppIconsManager {
     }
 
     /// Show or hide the badge on the dock/switcher icon for `app_id`.
+    /// The value is kept for apps without a stack yet and shown once it is created.
     pub fn update_badge(&self, app_id: &str, text: Option<String>) {
+        match text.as_ref().filter(|t| !t.is_empty()) {
+            Some(t) => self
+                .badges
+                .write()
+                .unwrap()
+                .insert(app_id.to_string(), t.clone()),
+            None => self.badges.write().unwrap().remove(app_id),
+        };
         let entries = self.entries.read().unwrap();
         if let Some(entry) = entries.get(app_id) {
             match text {
@@ -181,7 +205,12 @@ impl AppIconsManager {
     }
 
     /// Show or hide the progress bar on the dock/switcher icon for `app_id`.
+    /// The value is kept for apps without a stack yet and shown once it is created.
     pub fn update_progress(&self, app_id: &str, value: Option<f64>) {
+        match value.filter(|v| *v >= 0.0) {
+            Some(v) => self.progress.write().unwrap().insert(app_id.to_string(), v),
+            None => self.progress.write().unwrap().remove(app_id),
+        };
         let entries = self.entries.read().unwrap();
         if let Some(entry) = entries.get(app_id) {
             match value {
@@ -21,6 +21,7 @@ impl Hash for AppSwitcherModel {
         for node in &self.icon_stacks {
             node.map(|n| n.0).hash(state);
         }
+        crate::theme::theme_generation().hash(state);
     }
 }
 
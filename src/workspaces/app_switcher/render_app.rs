@@ -116,7 +116,9 @@ pub fn render_appswitcher_panel(
         })
         .size((
             Size::points(w, h),
-            Some(layers::engine::animation::Transition::spring(0.4, 0.0)),
+            Some(crate::config::Config::transition_or_instant(
+                layers::engine::animation::Transition::spring(0.4, 0.0),
+            )),
         ))
         .border_corner_radius((BorderRadius::new_single(h / 8.0), None))
         .layout_style(taffy::Style {
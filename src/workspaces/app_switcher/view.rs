@@ -20,6 +20,7 @@ use smithay::utils::IsAlive;
 use tokio::sync::mpsc;
 
 use crate::{
+    config::Config,
     interactive_view::ViewInteractions,
     utils::Observer,
     workspaces::{
@@ -111,10 +112,10 @@ impl AppSwitcherView {
         self.wrap_layer.set_hidden(false);
         self.wrap_layer.set_opacity(
             1.0,
-            Some(Transition {
+            Some(Config::transition_or_instant(Transition {
                 delay: 0.05,
                 timing: TimingFunction::ease_out_quad(0.1),
-            }),
+            })),
         );
     }
 
@@ -132,18 +133,17 @@ impl AppSwitcherView {
         self.wrap_layer.set_hidden(false);
         self.wrap_layer.set_opacity(
             1.0,
-            Some(Transition {
+            Some(Config::transition_or_instant(Transition {
                 delay: 0.0,
                 timing: TimingFunction::ease_out_quad(0.1),
-            }),
+            })),
         );
     }
 
     pub fn hide(&self) -> layers::engine::TransactionRef {
         self.active.store(false, Ordering::Relaxed);
-        let tr = self
-            .wrap_layer
-            .set_opacity(0.0, Some(Transition::ease_in_quad(0.05)));
+        let transition = Config::transition_or_instant(Transition::ease_in_quad(0.05));
+        let tr = self.wrap_layer.set_opacity(0.0, Some(transition));
         tr.on_finish(
             move |l: &layers::prelude::Layer, _p: f32| {
                 l.set_hidden(true);
@@ -35,6 +35,25 @@ impl Application {
         }
     }
 
+    /// A dock item that is not backed by a desktop entry, such as a stack,
+    /// shown as `name` with the themed icon `icon_name`.
+    pub fn placeholder(identifier: &str, name: &str, icon_name: &str) -> Self {
+        let icon_path = find_icon_with_theme(icon_name, 512, 1);
+        let icon = icon_path
+            .as_ref()
+            .and_then(|icon_path| image_from_path(icon_path, (512, 512)));
+        Self {
+            identifier: identifier.to_string(),
+            match_id: identifier.to_string(),
+            icon_path,
+            icon,
+            picture: None,
+            override_name: Some(name.to_string()),
+            desktop_file_id: None,
+            app_info: None,
+        }
+    }
+
     pub fn desktop_name(&self) -> Option<String> {
         if let Some(name) = &self.override_name {
             return Some(name.clone());
@@ -133,6 +152,14 @@ impl ApplicationsInfo {
         app
     }
 
+    /// Forget every loaded app, so the next lookup reads its desktop entry
+    /// and icon again.
+    pub async fn clear_cache() {
+        applications_info().write().await.clear();
+        otto_kit::desktop_entry::clear_cache();
+        otto_kit::icon_lookup::clear_cache();
+    }
+
     async fn load_app_info(app_id: &str) -> Option<Application> {
         tracing::trace!(app_id = %app_id, "[load_app_info] start");
 
@@ -63,6 +63,15 @@ impl BackgroundView {
             ..self.view.get_state()
         });
     }
+
+    /// Replace both the wallpaper and the color drawn when there is none
+    pub fn set_background(&self, image: Option<skia::Image>, fallback_color: skia::Color4f) {
+        self.view.update_state(&BackgroundViewState {
+            image,
+            fallback_color,
+            ..self.view.get_state()
+        });
+    }
 }
 
 // static mut COUNTER: f32 = 1.0;
@@ -0,0 +1,307 @@
+use std::hash::{Hash, Hasher};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use layers::{
+    engine::{
+        animation::{TimingFunction, Transition},
+        Engine,
+    },
+    prelude::*,
+    taffy::style::Style,
+    types::{BlendMode, BorderRadius, Size},
+};
+use smithay::utils::IsAlive;
+
+use crate::{config::Config, theme::theme_colors, workspaces::utils::FONT_CACHE};
+
+/// Width of the panel, in logical pixels, unless the output is narrower.
+const PANEL_WIDTH: f32 = 560.0;
+/// Height of one entry, in logical pixels.
+const ROW_HEIGHT: f32 = 36.0;
+/// Space around the entries, in logical pixels.
+const PADDING: f32 = 8.0;
+/// Size of the entry text, in logical pixels.
+const TEXT_SIZE: f32 = 14.0;
+/// Entries shown at once; the list scrolls to keep the selection visible.
+const VISIBLE_ROWS: usize = 10;
+
+/// An entry of the clipboard history as listed in the picker.
+#[derive(Debug, Clone, Hash)]
+pub struct ClipboardPickerItem {
+    pub id: u32,
+    pub label: String,
+    /// Shown on the right, e.g. how long ago it was copied.
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ClipboardPickerModel {
+    /// Most recent first.
+    pub items: Vec<ClipboardPickerItem>,
+    pub current: usize,
+    /// The physical width of the output the picker is shown on.
+    pub width: i32,
+    /// Scale of the output the picker is shown on.
+    pub scale: f32,
+}
+
+impl Hash for ClipboardPickerModel {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.items.hash(state);
+        self.current.hash(state);
+        self.width.hash(state);
+        self.scale.to_bits().hash(state);
+        crate::theme::theme_generation().hash(state);
+    }
+}
+
+/// First row to draw so that `current` is visible in a window of `rows`.
+fn first_visible_row(current: usize, len: usize, rows: usize) -> usize {
+    if len <= rows {
+        return 0;
+    }
+    current.saturating_sub(rows / 2).min(len - rows)
+}
+
+/// How long ago something was copied, for the picker.
+pub fn age_label(seconds: i64) -> String {
+    match seconds {
+        s if s < 60 => "Just now".to_string(),
+        s if s < 3600 => format!("{} min ago", s / 60),
+        s if s < 86400 => format!("{} h ago", s / 3600),
+        s => format!("{} d ago", s / 86400),
+    }
+}
+
+/// Keyboard-driven list of recent clipboard entries, centred on the
+/// primary output. Opened by the ClipboardHistory action.
+#[derive(Debug, Clone)]
+pub struct ClipboardPickerView {
+    pub wrap_layer: Layer,
+    pub view: View<ClipboardPickerModel>,
+    active: Arc<AtomicBool>,
+}
+
+impl IsAlive for ClipboardPickerView {
+    fn alive(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+}
+
+impl ClipboardPickerView {
+    pub fn new(layers_engine: Arc<Engine>) -> Self {
+        let wrap = layers_engine.new_layer();
+        wrap.set_key("clipboard_picker");
+        wrap.set_size(Size::percent(1.0, 1.0), None);
+        wrap.set_layout_style(Style {
+            position: taffy::Position::Absolute,
+            display: taffy::Display::Flex,
+            justify_content: Some(taffy::JustifyContent::Center),
+            align_items: Some(taffy::AlignItems::Center),
+            ..Default::default()
+        });
+        wrap.set_opacity(0.0, None);
+        wrap.set_pointer_events(false);
+        wrap.set_hidden(true);
+
+        let view_layer = layers_engine.new_layer();
+        let _ = wrap.add_sublayer(&view_layer);
+
+        let view = View::new(
+            "clipboard_picker_view",
+            ClipboardPickerModel::default(),
+            render_clipboard_picker,
+        );
+        view.set_layer(view_layer);
+
+        Self {
+            wrap_layer: wrap,
+            view,
+            active: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Show `items`, most recent first, with the first one selected.
+    pub fn show(&self, items: Vec<ClipboardPickerItem>, width: i32, scale: f32) {
+        self.view.update_state(&ClipboardPickerModel {
+            items,
+            current: 0,
+            width,
+            scale,
+        });
+        self.active.store(true, Ordering::Relaxed);
+        self.wrap_layer.set_hidden(false);
+        let transition = Config::transition_or_instant(Transition::ease_out_quad(0.1));
+        self.wrap_layer.set_opacity(1.0, Some(transition));
+    }
+
+    pub fn hide(&self) {
+        self.active.store(false, Ordering::Relaxed);
+        let transition = Config::transition_or_instant(Transition::ease_in_quad(0.05));
+        self.wrap_layer
+            .set_opacity(0.0, Some(transition))
+            .on_finish(
+                |l: &Layer, _p: f32| {
+                    l.set_hidden(true);
+                },
+                true,
+            );
+    }
+
+    /// Move the selection, stopping at the first and last entries.
+    pub fn select_step(&self, steps: isize) {
+        let state = self.view.get_state();
+        if state.items.is_empty() {
+            return;
+        }
+        let current =
+            (state.current as isize + steps).clamp(0, state.items.len() as isize - 1) as usize;
+        self.view
+            .update_state(&ClipboardPickerModel { current, ..state });
+    }
+
+    /// Id of the selected entry.
+    pub fn current_entry(&self) -> Option<u32> {
+        let state = self.view.get_state();
+        state.items.get(state.current).map(|item| item.id)
+    }
+}
+
+fn render_clipboard_picker(
+    state: &ClipboardPickerModel,
+    _view: &View<ClipboardPickerModel>,
+) -> LayerTree {
+    let scale = state.scale;
+    let padding = PADDING * scale;
+    let row_height = ROW_HEIGHT * scale;
+    let text_size = TEXT_SIZE * scale;
+    let width = (PANEL_WIDTH * scale).min(state.width as f32 - padding * 4.0);
+    let rows = state.items.len().clamp(1, VISIBLE_ROWS);
+    let height = rows as f32 * row_height + padding * 2.0;
+
+    let first = first_visible_row(state.current, state.items.len(), VISIBLE_ROWS);
+    let items: Vec<ClipboardPickerItem> = state
+        .items
+        .iter()
+        .skip(first)
+        .take(VISIBLE_ROWS)
+        .cloned()
+        .collect();
+    let selected = state.current.checked_sub(first);
+
+    let draw = move |canvas: &layers::skia::Canvas, w: f32, h: f32| -> layers::skia::Rect {
+        let font_family = Config::with(|c| c.font_family.clone());
+        let font = FONT_CACHE.with(|fc| {
+            fc.make_font_with_fallback(font_family, layers::skia::FontStyle::normal(), text_size)
+        });
+        let baseline = (row_height + text_size * 0.7) / 2.0;
+
+        if items.is_empty() {
+            let mut paint = layers::skia::Paint::new(theme_colors().text_secondary.c4f(), None);
+            paint.set_anti_alias(true);
+            let label = "Clipboard history is empty";
+            let text_width = font.measure_str(label, Some(&paint)).0;
+            canvas.draw_str(
+                label,
+                ((w - text_width) / 2.0, padding + baseline),
+                &font,
+                &paint,
+            );
+            return layers::skia::Rect::from_xywh(0.0, 0.0, w, h);
+        }
+
+        for (index, item) in items.iter().enumerate() {
+            let top = padding + index as f32 * row_height;
+            let is_selected = selected == Some(index);
+            if is_selected {
+                let mut paint = layers::skia::Paint::new(theme_colors().fills_primary.c4f(), None);
+                paint.set_anti_alias(true);
+                let rect =
+                    layers::skia::Rect::from_xywh(padding, top, w - padding * 2.0, row_height);
+                canvas.draw_round_rect(rect, padding * 0.75, padding * 0.75, &paint);
+            }
+
+            let detail_color = if is_selected {
+                theme_colors().text_primary
+            } else {
+                theme_colors().text_secondary
+            };
+            let mut detail_paint = layers::skia::Paint::new(detail_color.c4f(), None);
+            detail_paint.set_anti_alias(true);
+            let detail_width = font.measure_str(&item.detail, Some(&detail_paint)).0;
+            let detail_x = w - padding * 2.5 - detail_width;
+            canvas.draw_str(
+                &item.detail,
+                (detail_x, top + baseline),
+                &font,
+                &detail_paint,
+            );
+
+            let mut label_paint = layers::skia::Paint::new(theme_colors().text_primary.c4f(), None);
+            label_paint.set_anti_alias(true);
+            canvas.save();
+            canvas.clip_rect(
+                layers::skia::Rect::from_xywh(
+                    padding,
+                    top,
+                    (detail_x - padding * 2.5).max(0.0),
+                    row_height,
+                ),
+                None,
+                Some(true),
+            );
+            canvas.draw_str(
+                &item.label,
+                (padding * 2.5, top + baseline),
+                &font,
+                &label_paint,
+            );
+            canvas.restore();
+        }
+
+        layers::skia::Rect::from_xywh(0.0, 0.0, w, h)
+    };
+
+    LayerTreeBuilder::with_key("clipboard_picker_panel")
+        .blend_mode(BlendMode::BackgroundBlur)
+        .background_color(theme_colors().materials_medium)
+        .size((
+            Size::points(width, height),
+            Some(Config::transition_or_instant(Transition {
+                delay: 0.0,
+                timing: TimingFunction::ease_out_quad(0.1),
+            })),
+        ))
+        .border_corner_radius((BorderRadius::new_single(padding * 1.5), None))
+        .shadow_color(theme_colors().shadow_color)
+        .shadow_offset(((0.0, 0.0).into(), None))
+        .shadow_radius((20.0, None))
+        .content(Some(draw))
+        .pointer_events(false)
+        .build()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_selection_stays_visible() {
+        assert_eq!(first_visible_row(3, 5, 10), 0);
+        assert_eq!(first_visible_row(2, 30, 10), 0);
+        assert_eq!(first_visible_row(12, 30, 10), 7);
+        assert_eq!(first_visible_row(29, 30, 10), 20);
+    }
+
+    #[test]
+    fn ages_are_rounded_down() {
+        assert_eq!(age_label(5), "Just now");
+        assert_eq!(age_label(125), "2 min ago");
+        assert_eq!(age_label(7200), "2 h ago");
+        assert_eq!(age_label(90000), "1 d ago");
+    }
+}
@@ -0,0 +1,304 @@
+use layers::{
+    prelude::{taffy, Layer, Point, Transition},
+    types::Size,
+    view::{BuildLayerTree, LayerTreeBuilder},
+};
+
+use crate::{
+    config::{DockBookmark, DockPosition},
+    workspaces::Application,
+};
+
+use super::{
+    model::order_bookmarks,
+    view::{DockView, BASE_ICON_SIZE},
+};
+
+/// Distance in logical pixels the pointer travels before a press on an icon
+/// turns into a drag.
+const DRAG_THRESHOLD: f32 = 8.0;
+
+/// An app icon pressed in the dock, and its drag once the pointer moves.
+#[derive(Debug, Clone)]
+pub(super) struct IconDrag {
+    match_id: String,
+    /// Physical pointer position of the press.
+    start: (f32, f32),
+    /// Launchers before the drag, restored when it is cancelled.
+    saved_launchers: Vec<Application>,
+    /// Copy of the icon following the pointer, once the drag started.
+    floating: Option<(Layer, f32)>,
+    /// The pointer is far enough from the dock to drop the bookmark.
+    off_dock: bool,
+}
+
+impl DockView {
+    /// Remember the app icon under a button press, so that moving the
+    /// pointer can start dragging it. `pos` is in physical pixels.
+    pub(super) fn press_icon(&self, match_id: &str, pos: (f32, f32)) {
+        *self.icon_drag.write().unwrap() = Some(IconDrag {
+            match_id: match_id.to_string(),
+            start: pos,
+            saved_launchers: self.get_state().launchers,
+            floating: None,
+            off_dock: false,
+        });
+    }
+
+    /// Move the dragged icon with the pointer and preview where it would be
+    /// dropped. Returns `true` while a drag is in progress.
+    pub(super) fn drag_icon_motion(&self, pos: (f32, f32)) -> bool {
+        let Some(mut drag) = self.icon_drag.read().unwrap().clone() else {
+            return false;
+        };
+        if drag.floating.is_none() {
+            let scale = self.scale();
+            let distance = (pos.0 - drag.start.0).hypot(pos.1 - drag.start.1);
+            if distance < DRAG_THRESHOLD * scale {
+                return false;
+            }
+            let Some(floating) = self.start_icon_drag(&drag.match_id) else {
+                *self.icon_drag.write().unwrap() = None;
+                return false;
+            };
+            drag.floating = Some(floating);
+        }
+        let Some((floating, _)) = drag.floating.as_ref() else {
+            return false;
+        };
+
+        let wrap_bounds = self.wrap_layer.render_bounds_transformed();
+        floating.set_position(
+            Point::new(pos.0 - wrap_bounds.x(), pos.1 - wrap_bounds.y()),
+            None,
+        );
+
+        // Off the dock: more than a bar depth away from the screen edge, or
+        // past either end of it.
+        let bar = self.bar_layer.render_bounds_transformed();
+        let off_dock = match self.position() {
+            DockPosition::Bottom => {
+                pos.1 < bar.top - bar.height()
+                    || pos.0 < bar.left - bar.height()
+                    || pos.0 > bar.right + bar.height()
+            }
+            DockPosition::Left => {
+                pos.0 > bar.right + bar.width()
+                    || pos.1 < bar.top - bar.width()
+                    || pos.1 > bar.bottom + bar.width()
+            }
+            DockPosition::Right => {
+                pos.0 < bar.left - bar.width()
+                    || pos.1 < bar.top - bar.width()
+                    || pos.1 > bar.bottom + bar.width()
+            }
+        };
+        if off_dock != drag.off_dock {
+            let opacity = if off_dock { 0.5 } else { 1.0 };
+            floating.set_opacity(opacity, Transition::ease_out_quad(0.15));
+            drag.off_dock = off_dock;
+        }
+        if !off_dock {
+            self.preview_icon_drop(&drag, self.along_dock(pos));
+        }
+
+        *self.icon_drag.write().unwrap() = Some(drag);
+        true
+    }
+
+    /// Finish a press on an app icon. When the icon was dragged, save the new
+    /// launcher order, add or remove the bookmark, and return `true` so the
+    /// release is not handled as a click.
+    pub(super) fn release_icon<Backend: crate::state::Backend>(
+        &self,
+        state: &mut crate::Otto<Backend>,
+    ) -> bool {
+        let Some(drag) = self.icon_drag.write().unwrap().take() else {
+            return false;
+        };
+        let Some((floating, floating_scale)) = drag.floating else {
+            return false;
+        };
+        self.dragging
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+
+        let match_id = drag.match_id;
+        let bookmarked = self.bookmark_config_for(&match_id).is_some();
+        let mut dock_state = self.get_state();
+        if drag.off_dock {
+            if bookmarked {
+                self.update_dock_config(|d| {
+                    d.bookmarks.retain(|b| {
+                        b.desktop_id
+                            .strip_suffix(".desktop")
+                            .unwrap_or(&b.desktop_id)
+                            != match_id
+                    });
+                });
+                dock_state.launchers = drag.saved_launchers;
+                dock_state.launchers.retain(|a| a.match_id != match_id);
+                poof(&floating, floating_scale);
+                tracing::info!("Removed '{}' from dock bookmarks", match_id);
+            } else {
+                dock_state.launchers = drag.saved_launchers;
+                floating.remove();
+            }
+            self.update_state(&dock_state);
+        } else {
+            if dock_state.launcher_index(&match_id).is_some() {
+                let order: Vec<String> = dock_state
+                    .launchers
+                    .iter()
+                    .map(|a| a.match_id.clone())
+                    .collect();
+                self.update_dock_config(|d| {
+                    if !bookmarked {
+                        d.bookmarks.push(DockBookmark {
+                            desktop_id: match_id.clone(),
+                            label: None,
+                            exec_args: vec![],
+                        });
+                    }
+                    order_bookmarks(&mut d.bookmarks, &order);
+                });
+                if !bookmarked {
+                    tracing::info!("Added '{}' to dock bookmarks", match_id);
+                }
+            }
+            floating.remove();
+        }
+
+        // Show the icon in its slot again, unless the app left the dock.
+        let shown = self
+            .get_state()
+            .display_entries()
+            .iter()
+            .any(|(app, _)| app.match_id == match_id);
+        if shown {
+            if let Some(entry) = self.app_layers.read().unwrap().get(&match_id) {
+                entry.icon_mirror.set_opacity(1.0, None);
+            }
+        }
+        state.workspaces.sync_docks_from(self);
+        true
+    }
+
+    /// Lift the icon of `match_id` out of its slot into a copy that follows
+    /// the pointer. Returns the copy and its scale.
+    fn start_icon_drag(&self, match_id: &str) -> Option<(Layer, f32)> {
+        let (icon_mirror, icon_bounds) = {
+            let app_layers = self.app_layers.read().unwrap();
+            let entry = app_layers.get(match_id)?;
+            (
+                entry.icon_mirror.clone(),
+                entry.icon_scaler.render_bounds_transformed(),
+            )
+        };
+        let icon_stack = self.get_icon_stack_for_app(match_id)?;
+
+        let floating = self.layers_engine.new_layer();
+        let tree = LayerTreeBuilder::default()
+            .key(format!("dock_drag_icon_{}", match_id))
+            .layout_style(taffy::Style {
+                position: taffy::Position::Absolute,
+                ..Default::default()
+            })
+            .size(Size::points(BASE_ICON_SIZE, BASE_ICON_SIZE))
+            .anchor_point(Point::new(0.5, 0.5))
+            .replicate_node(Some(icon_stack.id()))
+            .pointer_events(false)
+            .build()
+            .unwrap();
+        floating.build_layer_tree(&tree);
+        let scale = icon_bounds.width() / BASE_ICON_SIZE;
+        floating.set_scale(Point::new(scale, scale), None);
+        let _ = self.wrap_layer.add_sublayer(&floating);
+
+        icon_mirror.set_opacity(0.0, None);
+        self.dragging
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        self.clear_pressed();
+        self.set_active_label(None);
+        Some((floating, scale))
+    }
+
+    /// Move the dragged app to the launcher slot at `along`, the pointer
+    /// coordinate along the dock (physical pixels).
+    /// A running app that is not bookmarked yet joins the launchers while it
+    /// is over them, and goes back to the running apps past them.
+    fn preview_icon_drop(&self, drag: &IconDrag, along: f32) {
+        let vertical = self.position().is_vertical();
+        let mut dock_state = self.get_state();
+        let slot = {
+            let app_layers = self.app_layers.read().unwrap();
+            dock_state
+                .display_entries()
+                .iter()
+                .filter(|(app, _)| app.match_id != drag.match_id)
+                .filter_map(|(app, _)| app_layers.get(&app.match_id))
+                .filter(|entry| {
+                    let bounds = entry.layer.render_bounds_transformed();
+                    let center = if vertical {
+                        bounds.center_y()
+                    } else {
+                        bounds.center_x()
+                    };
+                    center < along
+                })
+                .count()
+        };
+        let other_launchers = dock_state
+            .launchers
+            .iter()
+            .filter(|a| a.match_id != drag.match_id)
+            .count();
+        let was_launcher = drag
+            .saved_launchers
+            .iter()
+            .any(|a| a.match_id == drag.match_id);
+
+        if slot > other_launchers && !was_launcher {
+            if dock_state.launcher_index(&drag.match_id).is_none() {
+                return;
+            }
+            dock_state.launchers.retain(|a| a.match_id != drag.match_id);
+        } else {
+            let slot = slot.min(other_launchers);
+            if dock_state.launcher_index(&drag.match_id) == Some(slot) {
+                return;
+            }
+            let Some(app) = dock_state
+                .launchers
+                .iter()
+                .chain(dock_state.running_apps.iter())
+                .find(|a| a.match_id == drag.match_id)
+                .cloned()
+            else {
+                return;
+            };
+            dock_state.place_launcher(app, slot);
+        }
+
+        // Collapse the slot so it opens up again at its new position.
+        if let Some(entry) = self.app_layers.read().unwrap().get(&drag.match_id) {
+            let height = entry.layer.render_bounds_transformed().height();
+            entry.layer.set_size(Size::points(0.0, height), None);
+        }
+        self.update_state(&dock_state);
+    }
+}
+
+/// Blow the dragged icon up and fade it out, then drop it.
+fn poof(layer: &Layer, scale: f32) {
+    let transition = Transition::ease_out_quad(0.25);
+    layer.set_scale(
+        Point::new(scale * 1.6, scale * 1.6),
+        Some(transition.clone()),
+    );
+    layer.set_opacity(0.0, Some(transition)).on_finish(
+        |l: &Layer, _| {
+            l.remove();
+        },
+        true,
+    );
+}
@@ -7,13 +7,14 @@ use smithay::{
 };
 
 use crate::{
-    config::Config,
+    config::{StackDisplay, StackSource},
     interactive_view::{InteractiveView, ViewInteractions},
+    shell::WindowElement,
 };
 
 use tracing::warn;
 
-use super::DockView;
+use super::{previews::WindowThumbnail, stack::StackMove, DockView};
 
 // Dock view interactions
 impl<Backend: crate::state::Backend> ViewInteractions<Backend> for DockView {
@@ -26,13 +27,20 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for DockView {
     fn on_motion(
         &self,
         _seat: &smithay::input::Seat<crate::Otto<Backend>>,
-        _data: &mut crate::Otto<Backend>,
+        data: &mut crate::Otto<Backend>,
         event: &smithay::input::pointer::MotionEvent,
     ) {
+        let scale = self.scale() as f64;
+        let physical = (
+            (event.location.x * scale) as f32,
+            (event.location.y * scale) as f32,
+        );
+        if self.drag_icon_motion(physical) {
+            return;
+        }
         if self.dragging.load(std::sync::atomic::Ordering::SeqCst) {
             return;
         }
-        let scale = Config::with(|c| c.screen_scale);
         if let Some(menu) = self
             .context_menu
             .read()
@@ -51,8 +59,14 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for DockView {
             menu_state.select_at_depth(0, item_index);
             menu.view.update_state(&menu_state);
         }
+        self.stack_hover(physical.0, physical.1);
 
-        self.update_magnification_position((event.location.x * scale) as f32);
+        // Over the window previews the dock keeps its magnification.
+        if self.update_window_previews(data) {
+            return;
+        }
+
+        self.update_magnification_position(self.along_dock(physical));
 
         // Update label visibility: show tooltip for the hovered dock item only.
         // Skip while a context menu is open.
@@ -63,6 +77,7 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for DockView {
     fn on_leave(&self, _serial: smithay::utils::Serial, _time: u32) {
         self.demagnify_elements();
         self.set_active_label(None);
+        self.leave_previews();
         // Autohide is managed exclusively by check_dock_hot_zone via cached_dock_bounds.
     }
     fn on_enter(&self, _event: &smithay::input::pointer::MotionEvent) {
@@ -75,25 +90,85 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for DockView {
         state: &mut crate::Otto<Backend>,
         event: &smithay::input::pointer::ButtonEvent,
     ) {
+        const BTN_LEFT: u32 = 0x110; // 272
         const BTN_RIGHT: u32 = 0x111; // 273
 
         match event.state {
             ButtonState::Pressed => {
                 if let Some(layer_id) = state.layers_engine.current_hover() {
+                    if !self.is_preview_layer(&layer_id) {
+                        self.hide_previews();
+                    }
                     if let Some((target, label)) = self.darkening_target_for_hover(&layer_id) {
                         self.darken_pressed(&target);
                         if let Some(l) = label {
                             l.set_opacity(1.0, Some(Transition::ease_in_quad(0.05)));
                         }
                     }
+                    // A left press on an app icon may turn into a drag.
+                    // Stacks stay in place.
+                    if event.button == BTN_LEFT && !self.has_menu_open() {
+                        if let Some((identifier, match_id)) = self.get_app_from_layer(&layer_id) {
+                            if !self.is_stack(&identifier) {
+                                let scale = self.scale() as f64;
+                                let (x, y) = state.last_pointer_location;
+                                self.press_icon(
+                                    &match_id,
+                                    ((x * scale) as f32, (y * scale) as f32),
+                                );
+                            }
+                        }
+                    }
                 }
             }
             ButtonState::Released => {
+                // A dragged icon was dropped, not clicked.
+                if self.release_icon(state) {
+                    self.clear_pressed();
+                    return;
+                }
+
+                // A click on a window preview focuses that window.
+                if let Some(wid) = state
+                    .layers_engine
+                    .current_hover()
+                    .and_then(|layer_id| self.get_preview_window_from_layer(&layer_id))
+                {
+                    self.hide_previews();
+                    state.activate_window(&wid);
+                    self.clear_pressed();
+                    return;
+                }
+
+                // If a stack is open, a click opens the entry under the pointer.
+                // A click on a stack icon toggles stacks below; anywhere else
+                // it closes the open one.
+                if self.has_stack_open() {
+                    let scale = self.scale() as f64;
+                    let (x, y) = state.last_pointer_location;
+                    if let Some(target) =
+                        self.stack_entry_at((x * scale) as f32, (y * scale) as f32)
+                    {
+                        self.open_stack_entry(target, state);
+                        self.clear_pressed();
+                        return;
+                    }
+                    let on_stack_icon = state
+                        .layers_engine
+                        .current_hover()
+                        .and_then(|layer_id| self.get_app_from_layer(&layer_id))
+                        .is_some_and(|(identifier, _)| self.is_stack(&identifier));
+                    if !on_stack_icon {
+                        self.close_stack();
+                        self.clear_pressed();
+                        return;
+                    }
+                }
+
                 // If context menu is open, forward the click to it
                 {
-                    use crate::config::Config;
                     use otto_kit::components::context_menu::ContextMenuRenderer;
-                    let scale = Config::with(|c| c.screen_scale) as f32;
+                    let scale = self.scale();
                     let menu_lock = self.context_menu.read().unwrap();
                     if let Some(menu) = menu_lock.as_ref().filter(|m| m.is_active()) {
                         let menu_state = menu.view.get_state();
@@ -141,7 +216,7 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for DockView {
                 if let Some(layer_id) = state.layers_engine.current_hover() {
                     // Right-click on the dock handle → settings menu
                     if event.button == BTN_RIGHT && self.is_handle_layer(&layer_id) {
-                        state.workspaces.dock.open_handle_context_menu();
+                        self.open_handle_context_menu();
                         let view = InteractiveView {
                             view: Box::new(self.clone()),
                         };
@@ -169,10 +244,7 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for DockView {
 
                                 let pos = state.last_pointer_location;
                                 let pos = layers::prelude::Point::new(pos.0 as f32, pos.1 as f32);
-                                state
-                                    .workspaces
-                                    .dock
-                                    .open_context_menu(pos, identifier.clone());
+                                self.open_context_menu(pos, identifier.clone());
                                 let view = InteractiveView {
                                     view: Box::new(self.clone()),
                                 };
@@ -183,9 +255,27 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for DockView {
                                         event.serial,
                                     );
                                 }
+                            } else if self.is_stack(&identifier) {
+                                // Left-click on a stack: open or close its popup
+                                // and take the keyboard for arrow navigation.
+                                self.toggle_stack(&identifier);
+                                if self.has_stack_open() {
+                                    let view = InteractiveView {
+                                        view: Box::new(self.clone()),
+                                    };
+                                    if let Some(keyboard) = seat.get_keyboard() {
+                                        keyboard.set_focus(
+                                            state,
+                                            Some(crate::focus::KeyboardFocusTarget::View(view)),
+                                            event.serial,
+                                        );
+                                    }
+                                }
                             } else {
                                 // Normal left-click: focus or launch app
-                                if !state.focus_app(&identifier) {
+                                if !self.toggle_app_windows(&identifier, state)
+                                    && !state.focus_app(&identifier)
+                                {
                                     if let Some(bookmark) = self.bookmark_config_for(&match_id) {
                                         if let Some(app) = self.bookmark_application(&match_id) {
                                             if let Some((cmd, args)) =
@@ -230,6 +320,18 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for DockView {
             return;
         }
 
+        if self.has_stack_open() {
+            match event.modified_sym() {
+                Keysym::Left => self.stack_navigate(StackMove::Left),
+                Keysym::Right => self.stack_navigate(StackMove::Right),
+                Keysym::Up => self.stack_navigate(StackMove::Up),
+                Keysym::Down => self.stack_navigate(StackMove::Down),
+                Keysym::Escape => self.close_stack(),
+                _ => {}
+            }
+            return;
+        }
+
         enum MenuAction {
             None,
             Navigate,
@@ -277,6 +379,14 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for DockView {
         if key_state != KeyState::Released {
             return;
         }
+        if self.has_stack_open() {
+            if matches!(event.modified_sym(), Keysym::Return | Keysym::KP_Enter) {
+                if let Some(target) = self.selected_stack_entry() {
+                    self.open_stack_entry(target, data);
+                }
+            }
+            return;
+        }
         let (idx, depth, action_id) = {
             let menu_lock = self.context_menu.read().unwrap();
             let Some(menu) = menu_lock.as_ref().filter(|m| m.is_active()) else {
@@ -317,10 +427,90 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for DockView {
 
     fn on_keyboard_leave(&self) {
         self.close_context_menu();
+        self.close_stack();
     }
 }
 
 impl DockView {
+    /// Show or hide the window previews for the layer under the pointer.
+    /// Returns `true` while the pointer is over the previews.
+    fn update_window_previews<Backend: crate::state::Backend>(
+        &self,
+        state: &crate::Otto<Backend>,
+    ) -> bool {
+        if self.has_menu_open() || self.icon_drag.read().unwrap().is_some() {
+            return false;
+        }
+        let Some(layer_id) = state.layers_engine.current_hover() else {
+            self.leave_previews();
+            return false;
+        };
+        if self.is_preview_layer(&layer_id) {
+            self.hover_previews();
+            return true;
+        }
+        match self.get_app_from_layer(&layer_id) {
+            Some((identifier, match_id)) => {
+                let thumbnails = state
+                    .workspaces
+                    .get_app_windows(&identifier)
+                    .iter()
+                    .filter_map(|wid| state.workspaces.get_window_for_surface(wid))
+                    .filter(|window| !window.is_minimised())
+                    .map(WindowThumbnail::new)
+                    .collect();
+                self.hover_app_previews(&match_id, thumbnails);
+            }
+            None => self.leave_previews(),
+        }
+        false
+    }
+
+    /// With `minimize_on_click`, minimize every window of the app when one of
+    /// them has keyboard focus, and restore them when all are minimized.
+    ///
+    /// Returns `false` when the click should focus or launch the app instead.
+    fn toggle_app_windows<Backend: crate::state::Backend>(
+        &self,
+        identifier: &str,
+        state: &mut crate::Otto<Backend>,
+    ) -> bool {
+        if !self.dock_config.read().unwrap().minimize_on_click {
+            return false;
+        }
+        let windows: Vec<WindowElement> = state
+            .workspaces
+            .get_app_windows(identifier)
+            .iter()
+            .filter_map(|wid| state.workspaces.get_window_for_surface(wid).cloned())
+            .collect();
+        if windows.is_empty() {
+            return false;
+        }
+
+        if windows.iter().all(|window| window.is_minimised()) {
+            let mut focus = None;
+            for window in &windows {
+                if let Some(wid) = state.workspaces.unminimize_window(&window.id()) {
+                    focus = Some(wid);
+                }
+            }
+            if let Some(wid) = focus {
+                state.set_keyboard_focus_on_surface(&wid);
+            }
+            return true;
+        }
+
+        if state.has_keyboard_focus_in(&windows) {
+            for window in &windows {
+                state.minimize_window(window);
+            }
+            return true;
+        }
+
+        false
+    }
+
     /// Execute the named context-menu action for the given app identifier.
     pub(super) fn execute_context_menu_action<Backend: crate::state::Backend>(
         &self,
@@ -330,6 +520,9 @@ impl DockView {
     ) {
         tracing::info!("Context menu action '{}' for app '{}'", action_id, app_id);
         match action_id {
+            "open" if self.is_stack(app_id) => {
+                self.toggle_stack(app_id);
+            }
             "open" | "new_window" => {
                 // Focus if running, otherwise launch
                 if self.is_app_running(app_id) {
@@ -364,6 +557,7 @@ impl DockView {
                         if !dock_state.launchers.iter().any(|a| a.match_id == match_id) {
                             dock_state.launchers.push(app);
                             self.update_state(&dock_state);
+                            state.workspaces.sync_docks_from(self);
                         }
                         tracing::info!("Added '{}' to dock bookmarks", match_id);
                     }
@@ -383,15 +577,41 @@ impl DockView {
                     let mut dock_state = self.get_state();
                     dock_state.launchers.retain(|a| a.match_id != match_id);
                     self.update_state(&dock_state);
+                    state.workspaces.sync_docks_from(self);
                     tracing::info!("Removed '{}' from dock bookmarks", app_id);
                 }
             }
             "quit" => {
                 state.workspaces.quit_app(app_id);
             }
+            "stack_grid" | "stack_fan" => {
+                let display = if action_id == "stack_fan" {
+                    StackDisplay::Fan
+                } else {
+                    StackDisplay::Grid
+                };
+                if let Some(stack) = self.stack_config_for(app_id) {
+                    self.update_dock_config(|d| {
+                        if let Some(s) = d.stacks.iter_mut().find(|s| s.name == stack.name) {
+                            s.display = display;
+                        }
+                    });
+                }
+            }
+            "stack_open_folder" => {
+                if let Some(StackSource::Folder(folder)) =
+                    self.stack_config_for(app_id).map(|stack| stack.source())
+                {
+                    state.launch_program(
+                        "xdg-open".to_string(),
+                        vec![folder.to_string_lossy().into_owned()],
+                    );
+                }
+            }
             "toggle_autohide" => {
                 let autohide = self.dock_config.read().unwrap().autohide;
                 self.update_dock_config(|d| d.autohide = !autohide);
+                state.workspaces.sync_docks_from(self);
                 tracing::info!(
                     "Dock auto-hide {}",
                     if !autohide { "enabled" } else { "disabled" }
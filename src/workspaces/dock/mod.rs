@@ -1,7 +1,11 @@
+mod drag;
 mod interactions;
 mod model;
+mod previews;
 mod render;
+mod stack;
 mod view;
+pub(crate) use model::exclude_dock;
 pub(crate) use render::{
     draw_app_icon, draw_badge, draw_progress, setup_badge_layer, setup_progress_layer,
 };
@@ -1,14 +1,31 @@
 use std::hash::{Hash, Hasher};
 
-use smithay::reexports::wayland_server::backend::ObjectId;
+use smithay::{
+    reexports::wayland_server::backend::ObjectId,
+    utils::{Logical, Rectangle},
+};
 
-use crate::workspaces::Application;
+use crate::{
+    config::{DockBookmark, DockPosition},
+    otto_dock::DockItemType,
+    workspaces::Application,
+};
+
+/// Prefix of the identifier of a stack item, followed by the stack name.
+pub const STACK_ID_PREFIX: &str = "stack:";
+
+/// Identifier of the dock item for the stack called `name`.
+pub fn stack_identifier(name: &str) -> String {
+    format!("{STACK_ID_PREFIX}{name}")
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct DockModel {
     pub launchers: Vec<Application>,
     pub running_apps: Vec<Application>,
     pub minimized_windows: Vec<(ObjectId, String)>,
+    /// Stack items, shown after the apps.
+    pub stacks: Vec<Application>,
     pub width: i32,
     pub focus: f32,
 }
@@ -18,6 +35,7 @@ impl Hash for DockModel {
         self.launchers.hash(state);
         self.running_apps.hash(state);
         self.minimized_windows.hash(state);
+        self.stacks.hash(state);
         self.width.hash(state);
     }
 }
@@ -33,7 +51,7 @@ impl DockModel {
     /// Merge launchers with running apps into a display list.
     /// Each entry is `(app, is_running)`. Launchers matched by `match_id`
     /// to a running app get `is_running = true`. Running apps not in
-    /// launchers are appended at the end.
+    /// launchers are appended, followed by the stacks.
     pub fn display_entries(&self) -> Vec<(Application, bool)> {
         let mut entries: Vec<(Application, bool)> = self
             .launchers
@@ -57,9 +75,99 @@ impl DockModel {
                 entries.push((running.clone(), true));
             }
         }
+        entries.extend(self.stacks.iter().map(|stack| (stack.clone(), false)));
 
         entries
     }
+
+    /// Kind of the dock item with `identifier`.
+    pub fn item_type(&self, identifier: &str) -> DockItemType {
+        if self
+            .stacks
+            .iter()
+            .any(|stack| stack.identifier == identifier)
+        {
+            DockItemType::Stack
+        } else {
+            DockItemType::AppElement
+        }
+    }
+
+    /// Put `app` at `index` among the launchers, moving it if it is one
+    /// already. The index is clamped to the end of the launchers.
+    pub fn place_launcher(&mut self, app: Application, index: usize) {
+        self.launchers
+            .retain(|launcher| launcher.match_id != app.match_id);
+        let index = index.min(self.launchers.len());
+        self.launchers.insert(index, app);
+    }
+
+    /// Position of `match_id` among the launchers.
+    pub fn launcher_index(&self, match_id: &str) -> Option<usize> {
+        self.launchers
+            .iter()
+            .position(|launcher| launcher.match_id == match_id)
+    }
+
+    /// Whether `app_id`, an identifier or `match_id`, is a launcher or a
+    /// running app.
+    pub fn has_app(&self, app_id: &str) -> bool {
+        self.launchers
+            .iter()
+            .chain(self.running_apps.iter())
+            .any(|app| app.identifier == app_id || app.match_id == app_id)
+    }
+}
+
+/// Sort `bookmarks` to follow `order`, a list of launcher `match_id`s.
+/// Bookmarks missing from `order` keep their relative order at the end.
+pub fn order_bookmarks(bookmarks: &mut [DockBookmark], order: &[String]) {
+    bookmarks.sort_by_key(|bookmark| {
+        let id = bookmark
+            .desktop_id
+            .strip_suffix(".desktop")
+            .unwrap_or(&bookmark.desktop_id);
+        order
+            .iter()
+            .position(|match_id| match_id == id)
+            .unwrap_or(order.len())
+    });
+}
+
+/// Shrink `usable` so it stops at the inner edge of a dock with geometry
+/// `dock` on the `position` edge. An empty dock, or one outside `usable`,
+/// leaves it unchanged.
+pub fn exclude_dock(
+    usable: Rectangle<i32, Logical>,
+    dock: Rectangle<i32, Logical>,
+    position: DockPosition,
+) -> Rectangle<i32, Logical> {
+    if dock.size.w <= 0 || dock.size.h <= 0 {
+        return usable;
+    }
+    let mut usable = usable;
+    match position {
+        DockPosition::Bottom => {
+            let dock_top = dock.loc.y;
+            if dock_top < usable.loc.y + usable.size.h {
+                usable.size.h = dock_top - usable.loc.y;
+            }
+        }
+        DockPosition::Left => {
+            let dock_right = dock.loc.x + dock.size.w;
+            if dock_right > usable.loc.x {
+                usable.size.w -= dock_right - usable.loc.x;
+                usable.loc.x = dock_right;
+            }
+        }
+        DockPosition::Right => {
+            let dock_left = dock.loc.x;
+            if dock_left < usable.loc.x + usable.size.w {
+                usable.size.w = dock_left - usable.loc.x;
+            }
+        }
+    }
+    usable
 }
 
 #[cfg(test)]
@@ -127,6 +235,58 @@ mod tests {
         assert!(entries[3].1, "chromium running");
     }
 
+    fn ids(apps: &[Application]) -> Vec<&str> {
+        apps.iter().map(|app| app.match_id.as_str()).collect()
+    }
+
+    #[test]
+    fn place_launcher_moves_and_inserts() {
+        let mut model = DockModel {
+            launchers: vec![make_app("firefox"), make_app("terminal"), make_app("files")],
+            ..DockModel::new()
+        };
+        model.place_launcher(make_app("files"), 0);
+        assert_eq!(ids(&model.launchers), ["files", "firefox", "terminal"]);
+
+        model.place_launcher(make_app("files"), 10);
+        assert_eq!(ids(&model.launchers), ["firefox", "terminal", "files"]);
+
+        model.place_launcher(make_app("spotify"), 1);
+        assert_eq!(
+            ids(&model.launchers),
+            ["firefox", "spotify", "terminal", "files"]
+        );
+        assert_eq!(model.launcher_index("terminal"), Some(2));
+        assert_eq!(model.launcher_index("chromium"), None);
+    }
+
+    #[test]
+    fn bookmarks_follow_launcher_order() {
+        let bookmark = |id: &str| DockBookmark {
+            desktop_id: id.to_string(),
+            label: None,
+            exec_args: Vec::new(),
+        };
+        let mut bookmarks = vec![
+            bookmark("firefox.desktop"),
+            bookmark("missing.desktop"),
+            bookmark("terminal"),
+            bookmark("files.desktop"),
+        ];
+        let order = ["files", "firefox", "terminal"].map(String::from);
+        order_bookmarks(&mut bookmarks, &order);
+        let ids: Vec<&str> = bookmarks.iter().map(|b| b.desktop_id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "files.desktop",
+                "firefox.desktop",
+                "terminal",
+                "missing.desktop"
+            ]
+        );
+    }
+
     #[test]
     fn override_name_preserved_from_launcher() {
         let mut launcher = make_app("firefox");
@@ -144,4 +304,65 @@ mod tests {
         );
         assert!(entries[0].1);
     }
+
+    #[test]
+    fn stacks_follow_the_apps() {
+        let stack = make_app(&stack_identifier("Downloads"));
+        let model = DockModel {
+            launchers: vec![make_app("firefox")],
+            running_apps: vec![make_app("spotify")],
+            stacks: vec![stack],
+            ..DockModel::new()
+        };
+        let entries = model.display_entries();
+        let ids: Vec<&str> = entries
+            .iter()
+            .map(|(app, _)| app.match_id.as_str())
+            .collect();
+        assert_eq!(ids, ["firefox", "spotify", "stack:Downloads"]);
+        assert!(!entries[2].1, "stacks are never running");
+        assert_eq!(model.item_type("stack:Downloads"), DockItemType::Stack);
+        assert_eq!(model.item_type("firefox"), DockItemType::AppElement);
+    }
+
+    #[test]
+    fn has_app_matches_launchers_and_running_apps() {
+        let mut running = make_app("org.gnome.Nautilus");
+        running.match_id = "nautilus".to_string();
+        let model = DockModel {
+            launchers: vec![make_app("firefox")],
+            running_apps: vec![running],
+            ..DockModel::new()
+        };
+        assert!(model.has_app("firefox"));
+        assert!(model.has_app("org.gnome.Nautilus"));
+        assert!(model.has_app("nautilus"));
+        assert!(!model.has_app("spotify"));
+    }
+
+    #[test]
+    fn dock_is_excluded_from_its_edge() {
+        let usable = Rectangle::new((0, 30).into(), (1920, 1050).into());
+
+        let bottom = Rectangle::new((600, 980).into(), (720, 100).into());
+        assert_eq!(
+            exclude_dock(usable, bottom, DockPosition::Bottom),
+            Rectangle::new((0, 30).into(), (1920, 950).into())
+        );
+
+        let left = Rectangle::new((0, 200).into(), (100, 600).into());
+        assert_eq!(
+            exclude_dock(usable, left, DockPosition::Left),
+            Rectangle::new((100, 30).into(), (1820, 1050).into())
+        );
+
+        let right = Rectangle::new((1820, 200).into(), (100, 600).into());
+        assert_eq!(
+            exclude_dock(usable, right, DockPosition::Right),
+            Rectangle::new((0, 30).into(), (1820, 1050).into())
+        );
+
+        let hidden = Rectangle::new((0, 0).into(), (0, 0).into());
+        assert_eq!(exclude_dock(usable, hidden, DockPosition::Left), usable);
+    }
 }
@@ -0,0 +1,306 @@
+use std::time::Duration;
+
+use layers::{
+    engine::NodeRef,
+    prelude::*,
+    types::{BlendMode, Size},
+};
+use smithay::reexports::wayland_server::backend::ObjectId;
+
+use crate::{config::DockPosition, shell::WindowElement, theme::theme_colors};
+
+use super::view::DockView;
+
+/// Time the pointer rests on a running app before its previews appear.
+const PREVIEW_DELAY: Duration = Duration::from_millis(500);
+/// Time the previews stay up after the pointer left the icon and the popup.
+const PREVIEW_GRACE: Duration = Duration::from_millis(300);
+/// Largest thumbnail, in logical pixels.
+const THUMBNAIL_MAX_WIDTH: f32 = 200.0;
+const THUMBNAIL_MAX_HEIGHT: f32 = 130.0;
+/// Space around and between the thumbnails, in logical pixels.
+const PREVIEW_PADDING: f32 = 10.0;
+/// Space between the popup and the top of the dock, in logical pixels.
+const PREVIEW_MARGIN: f32 = 12.0;
+
+/// A window shown in the previews, mirrored from its scene layer.
+#[derive(Debug, Clone)]
+pub(super) struct WindowThumbnail {
+    wid: ObjectId,
+    node: NodeRef,
+    /// Physical size of the window layer.
+    size: (f32, f32),
+}
+
+impl WindowThumbnail {
+    pub(super) fn new(window: &WindowElement) -> Self {
+        let size = window.base_layer().render_size();
+        Self {
+            wid: window.id(),
+            node: window.base_layer().id(),
+            size: (size.x.max(1.0), size.y.max(1.0)),
+        }
+    }
+}
+
+/// The preview popup shown above an app icon.
+#[derive(Debug, Clone)]
+struct PreviewPopup {
+    layer: Layer,
+    thumbnails: Vec<(ObjectId, Layer)>,
+}
+
+/// The app the previews are shown or about to be shown for.
+#[derive(Debug, Default)]
+pub(super) struct PreviewsState {
+    target: Option<String>,
+    popup: Option<PreviewPopup>,
+    /// Bumped on every hover change; a pending timer only fires when the
+    /// generation it was started with is still current.
+    generation: u64,
+}
+
+impl DockView {
+    /// The pointer rests on the icon of a running app. Show its previews after
+    /// a delay, or right away when the previews of another app are up.
+    pub(super) fn hover_app_previews(&self, match_id: &str, thumbnails: Vec<WindowThumbnail>) {
+        if thumbnails.is_empty() {
+            self.leave_previews();
+            return;
+        }
+        let mut previews = self.previews.write().unwrap();
+        if previews.target.as_deref() == Some(match_id) {
+            // Cancel a pending hide; a pending show keeps running.
+            if previews.popup.is_some() {
+                previews.generation += 1;
+            }
+            return;
+        }
+        previews.target = Some(match_id.to_string());
+        previews.generation += 1;
+        if previews.popup.is_some() {
+            drop(previews);
+            self.show_previews(match_id, &thumbnails);
+            return;
+        }
+        let generation = previews.generation;
+        drop(previews);
+
+        let dock = self.clone();
+        let match_id = match_id.to_string();
+        tokio::spawn(async move {
+            tokio::time::sleep(PREVIEW_DELAY).await;
+            if dock.previews.read().unwrap().generation == generation {
+                dock.show_previews(&match_id, &thumbnails);
+            }
+        });
+    }
+
+    /// The pointer is over the preview popup: keep it up.
+    pub(super) fn hover_previews(&self) {
+        let mut previews = self.previews.write().unwrap();
+        if previews.popup.is_some() {
+            previews.generation += 1;
+        }
+    }
+
+    /// The pointer left the app icon or the popup. Hide the previews after a
+    /// grace period, and drop a show that is still pending.
+    pub(super) fn leave_previews(&self) {
+        let mut previews = self.previews.write().unwrap();
+        if previews.target.is_none() {
+            return;
+        }
+        previews.generation += 1;
+        if previews.popup.is_none() {
+            previews.target = None;
+            return;
+        }
+        let generation = previews.generation;
+        drop(previews);
+
+        let dock = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(PREVIEW_GRACE).await;
+            if dock.previews.read().unwrap().generation == generation {
+                dock.hide_previews();
+            }
+        });
+    }
+
+    /// Remove the previews right away.
+    pub fn hide_previews(&self) {
+        let popup = {
+            let mut previews = self.previews.write().unwrap();
+            previews.generation += 1;
+            previews.target = None;
+            previews.popup.take()
+        };
+        if let Some(popup) = popup {
+            popup
+                .layer
+                .set_opacity(0.0, Some(Transition::ease_out_quad(0.15)))
+                .on_finish(
+                    |l: &Layer, _| {
+                        l.remove();
+                    },
+                    true,
+                );
+        }
+    }
+
+    /// The window of the thumbnail owning `layer`, if any.
+    pub(super) fn get_preview_window_from_layer(&self, layer: &NodeRef) -> Option<ObjectId> {
+        let previews = self.previews.read().unwrap();
+        previews
+            .popup
+            .as_ref()?
+            .thumbnails
+            .iter()
+            .find(|(_, l)| l.id() == *layer)
+            .map(|(wid, _)| wid.clone())
+    }
+
+    /// Whether `layer` is the preview popup or one of its thumbnails.
+    pub(super) fn is_preview_layer(&self, layer: &NodeRef) -> bool {
+        let previews = self.previews.read().unwrap();
+        previews.popup.as_ref().is_some_and(|popup| {
+            popup.layer.id() == *layer || popup.thumbnails.iter().any(|(_, l)| l.id() == *layer)
+        })
+    }
+
+    /// Physical bounds of the preview popup, while it is shown.
+    pub(super) fn previews_bounds(&self) -> Option<layers::skia::Rect> {
+        let previews = self.previews.read().unwrap();
+        previews
+            .popup
+            .as_ref()
+            .map(|popup| popup.layer.render_bounds_transformed())
+    }
+
+    /// Build the popup of `match_id` above its icon, replacing the one shown.
+    fn show_previews(&self, match_id: &str, thumbnails: &[WindowThumbnail]) {
+        let Some(icon_bounds) = self
+            .app_layers
+            .read()
+            .unwrap()
+            .get(match_id)
+            .filter(|entry| entry.running)
+            .map(|entry| entry.layer.render_bounds_transformed())
+        else {
+            self.hide_previews();
+            return;
+        };
+        if let Some(old) = self.previews.write().unwrap().popup.take() {
+            old.layer.remove();
+        }
+
+        let scale = self.scale();
+        let padding = PREVIEW_PADDING * scale;
+        let sizes: Vec<(f32, f32, f32)> = thumbnails
+            .iter()
+            .map(|thumbnail| {
+                let (w, h) = thumbnail.size;
+                let fit = (THUMBNAIL_MAX_WIDTH * scale / w).min(THUMBNAIL_MAX_HEIGHT * scale / h);
+                (w * fit, h * fit, fit)
+            })
+            .collect();
+        let content_height = sizes.iter().fold(0.0_f32, |max, (_, h, _)| max.max(*h));
+        let width = sizes.iter().map(|(w, _, _)| w + padding).sum::<f32>() + padding;
+        let height = content_height + padding * 2.0;
+
+        let mut x = padding;
+        let children: Vec<(ObjectId, LayerTree)> = thumbnails
+            .iter()
+            .zip(sizes.iter())
+            .map(|(thumbnail, (w, h, fit))| {
+                let position = Point::new(x, padding + (content_height - h) / 2.0);
+                x += w + padding;
+                let tree = LayerTreeBuilder::default()
+                    .key(format!("dock_preview_{:?}", thumbnail.wid))
+                    .layout_style(taffy::Style {
+                        position: taffy::Position::Absolute,
+                        ..Default::default()
+                    })
+                    .position(position)
+                    .size(Size::points(*w, *h))
+                    .children(vec![LayerTreeBuilder::default()
+                        .key(format!("dock_preview_mirror_{:?}", thumbnail.wid))
+                        .layout_style(taffy::Style {
+                            position: taffy::Position::Absolute,
+                            ..Default::default()
+                        })
+                        .size(Size::points(thumbnail.size.0, thumbnail.size.1))
+                        .scale(Point::new(*fit, *fit))
+                        .replicate_node(Some(thumbnail.node))
+                        .picture_cached(false)
+                        .pointer_events(false)
+                        .build()
+                        .unwrap()])
+                    .build()
+                    .unwrap();
+                (thumbnail.wid.clone(), tree)
+            })
+            .collect();
+
+        // Centre the popup on the icon, beside the bar and kept inside the
+        // dock's output.
+        let wrap_bounds = self.wrap_layer.render_bounds_transformed();
+        let bar_bounds = self.bar_layer.render_bounds_transformed();
+        let margin = PREVIEW_MARGIN * scale;
+        let (left, top) = match self.position() {
+            DockPosition::Bottom => (
+                (icon_bounds.center_x() - width / 2.0 - wrap_bounds.x())
+                    .min(wrap_bounds.width() - width)
+                    .max(0.0),
+                bar_bounds.top - wrap_bounds.y() - height - margin,
+            ),
+            DockPosition::Left | DockPosition::Right => {
+                let left = if self.position() == DockPosition::Left {
+                    bar_bounds.right - wrap_bounds.x() + margin
+                } else {
+                    bar_bounds.left - wrap_bounds.x() - width - margin
+                };
+                let top = (icon_bounds.center_y() - height / 2.0 - wrap_bounds.y())
+                    .min(wrap_bounds.height() - height)
+                    .max(0.0);
+                (left, top)
+            }
+        };
+
+        let layer = self.layers_engine.new_layer();
+        let tree = LayerTreeBuilder::default()
+            .key(format!("dock_previews_{}", match_id))
+            .layout_style(taffy::Style {
+                position: taffy::Position::Absolute,
+                ..Default::default()
+            })
+            .position(Point::new(left, top))
+            .size(Size::points(width, height))
+            .blend_mode(BlendMode::BackgroundBlur)
+            .background_color(theme_colors().materials_medium)
+            .border_corner_radius(BorderRadius::new_single(padding))
+            .border_width((1.0 * scale, None))
+            .border_color(theme_colors().materials_highlight)
+            .shadow_color(theme_colors().shadow_color)
+            .shadow_offset(((0.0, 0.0).into(), None))
+            .shadow_radius((20.0, None))
+            .opacity((0.0, None))
+            .build()
+            .unwrap();
+        layer.build_layer_tree(&tree);
+        let thumbnails = children
+            .into_iter()
+            .map(|(wid, tree)| {
+                let thumbnail = self.layers_engine.new_layer();
+                thumbnail.build_layer_tree(&tree);
+                let _ = layer.add_sublayer(&thumbnail);
+                (wid, thumbnail)
+            })
+            .collect();
+        let _ = self.wrap_layer.add_sublayer(&layer);
+        layer.set_opacity(1.0, Some(Transition::ease_out_quad(0.15)));
+
+        self.previews.write().unwrap().popup = Some(PreviewPopup { layer, thumbnails });
+    }
+}
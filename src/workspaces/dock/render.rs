@@ -3,7 +3,7 @@ use layers::{prelude::*, types::Size};
 use taffy::LengthPercentageAuto;
 
 use crate::{
-    config::Config,
+    config::{Config, DockPosition},
     theme::theme_colors,
     workspaces::{
         utils::{draw_balloon_rect, FONT_CACHE},
@@ -243,8 +243,9 @@ pub fn setup_miniwindow_icon(layer: &Layer, inner_layer: &Layer, icon_width: f32
     inner_layer.build_layer_tree(&inner_tree);
 }
 
-pub fn setup_label(new_layer: &Layer, label_text: String) {
-    let _draw_scale = Config::with(|config| config.screen_scale as f32);
+/// Set up the tooltip of a dock item: a balloon above the icon pointing down
+/// at it on a bottom dock, a plain pill beside the icon on a side dock.
+pub fn setup_label(new_layer: &Layer, label_text: String, position: DockPosition) {
     let text_size = 26.0;
     let font_family = Config::with(|config| config.font_family.clone());
     let font = FONT_CACHE.with(|font_cache| {
@@ -260,7 +261,7 @@ pub fn setup_label(new_layer: &Layer, label_text: String) {
     let text_bounds = font.measure_str(label_text, Some(&paint));
 
     let text_bounds = text_bounds.1;
-    let arrow_height = 20.0;
+    let arrow_height = if position.is_vertical() { 0.0 } else { 20.0 };
     let text_padding_h = 30.0;
     let text_padding_v = 14.0;
     let safe_margin = 100.0;
@@ -296,17 +297,9 @@ pub fn setup_label(new_layer: &Layer, label_text: String) {
         // Paint for the tooltip background
         let mut paint = layers::skia::Paint::default();
 
-        // choose colors according to theme scheme so tooltip looks correct in dark mode
-        let (bg_col, text_col) = Config::with(|c| match c.theme_scheme {
-            crate::theme::ThemeScheme::Light => (
-                layers::skia::Color4f::new(157.0 / 255.0, 157.0 / 255.0, 157.0 / 255.0, 1.0),
-                theme_colors().text_primary.c4f(),
-            ),
-            crate::theme::ThemeScheme::Dark => (
-                layers::skia::Color4f::new(157.0 / 255.0, 157.0 / 255.0, 157.0 / 255.0, 1.0),
-                theme_colors().text_primary.c4f(),
-            ),
-        });
+        // The same gray in both schemes; the text follows the active palette
+        let bg_col = layers::skia::Color4f::new(157.0 / 255.0, 157.0 / 255.0, 157.0 / 255.0, 1.0);
+        let text_col = theme_colors().text_primary.c4f();
         paint.set_color4f(bg_col, None);
         paint.set_anti_alias(true);
 
@@ -322,6 +315,47 @@ pub fn setup_label(new_layer: &Layer, label_text: String) {
         canvas.draw_str(text.as_str(), (text_x, text_y), &font, &text_paint);
         layers::skia::Rect::from_xywh(0.0, 0.0, w, h)
     };
+    // Anchor the balloon on the icon edge facing the screen, then pull it
+    // back by the safe margin so the gap to the icon stays 10 points.
+    let auto = LengthPercentageAuto::Auto;
+    let (inset, offset) = match position {
+        DockPosition::Bottom => (
+            taffy::geometry::Rect {
+                top: auto,
+                right: auto,
+                bottom: auto,
+                left: LengthPercentageAuto::Percent(0.5),
+            },
+            Point {
+                x: -label_size_width / 2.0,
+                y: -label_size_height - 10.0 + safe_margin,
+            },
+        ),
+        DockPosition::Left => (
+            taffy::geometry::Rect {
+                top: LengthPercentageAuto::Percent(0.5),
+                right: auto,
+                bottom: auto,
+                left: LengthPercentageAuto::Percent(1.0),
+            },
+            Point {
+                x: 10.0 - safe_margin,
+                y: -label_size_height / 2.0,
+            },
+        ),
+        DockPosition::Right => (
+            taffy::geometry::Rect {
+                top: LengthPercentageAuto::Percent(0.5),
+                right: LengthPercentageAuto::Percent(1.0),
+                bottom: auto,
+                left: auto,
+            },
+            Point {
+                x: safe_margin - 10.0,
+                y: -label_size_height / 2.0,
+            },
+        ),
+    };
     let label_tree = LayerTreeBuilder::default()
         .key(format!("{}_label", new_layer.key()))
         .shape(layers::prelude::Shape::from_path(&arrow_path))
@@ -332,12 +366,7 @@ pub fn setup_label(new_layer: &Layer, label_text: String) {
                 width: taffy::style::Dimension::Length(label_size_width),
                 height: taffy::style::Dimension::Length(label_size_height),
             },
-            inset: taffy::geometry::Rect::<LengthPercentageAuto> {
-                top: LengthPercentageAuto::Auto,
-                right: LengthPercentageAuto::Auto,
-                bottom: LengthPercentageAuto::Auto,
-                left: LengthPercentageAuto::Percent(0.5),
-            },
+            inset,
             ..Default::default()
         })
         .size(Size {
@@ -345,10 +374,7 @@ pub fn setup_label(new_layer: &Layer, label_text: String) {
             height: taffy::Dimension::Length(label_size_height),
         })
         .background_color(theme_colors().materials_ultrathick)
-        .position(Point {
-            x: -label_size_width / 2.0,
-            y: -label_size_height - 10.0 + safe_margin,
-        })
+        .position(offset)
         .shadow_color(theme_colors().shadow_color)
         .shadow_offset(((0.0, 0.0).into(), None))
         .shadow_radius((20.0, None))
@@ -0,0 +1,849 @@
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{atomic::AtomicBool, Arc},
+    time::SystemTime,
+};
+
+use layers::{
+    engine::animation::{Spring, TimingFunction, Transition},
+    prelude::{taffy, BorderRadius, Layer, LayerTree, LayerTreeBuilder, Point, View},
+    skia,
+    taffy::style::Style,
+    types::{BlendMode, Size},
+};
+
+use crate::{
+    config::{Config, DockPosition, DockStack, StackDisplay, StackSource},
+    theme::theme_colors,
+    utils::{find_icon_with_theme, image_from_path},
+    workspaces::{utils::FONT_CACHE, Application, ApplicationsInfo},
+};
+
+use super::{model::STACK_ID_PREFIX, view::DockView};
+
+/// Size of a grid cell, icon and label, in logical pixels.
+const GRID_CELL: f32 = 84.0;
+const GRID_ICON: f32 = 52.0;
+const GRID_MAX_COLUMNS: usize = 5;
+/// Height of a fan row and size of its icon, in logical pixels.
+const FAN_ROW: f32 = 52.0;
+const FAN_ICON: f32 = 44.0;
+const FAN_LABEL_WIDTH: f32 = 180.0;
+/// How far the top of the fan leans to the right, in logical pixels.
+const FAN_CURVE: f32 = 36.0;
+const FAN_MAX_ENTRIES: usize = 12;
+/// Space around the entries, in logical pixels.
+const POPUP_PADDING: f32 = 12.0;
+/// Space between the popup and the top of the icon, in logical pixels.
+const POPUP_MARGIN: f32 = 10.0;
+/// Most entries listed from a folder, newest first.
+const FOLDER_LIMIT: usize = 40;
+const LABEL_FONT_SIZE: f32 = 12.0;
+
+/// What an entry of a stack opens.
+#[derive(Debug, Clone)]
+pub(super) enum StackTarget {
+    File(PathBuf),
+    App(Application),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct StackEntry {
+    label: String,
+    icon: Option<skia::Image>,
+    target: StackTarget,
+}
+
+impl Hash for StackEntry {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.label.hash(state);
+        match &self.target {
+            StackTarget::File(path) => path.hash(state),
+            StackTarget::App(app) => app.hash(state),
+        }
+        if let Some(icon) = self.icon.as_ref() {
+            icon.unique_id().hash(state)
+        }
+    }
+}
+
+/// Entries and keyboard selection of the open stack.
+#[derive(Debug, Clone, Default)]
+pub(super) struct StackState {
+    stack_id: String,
+    display: StackDisplay,
+    entries: Vec<StackEntry>,
+    selected: Option<usize>,
+    /// Scale of the dock's output when the stack was opened
+    scale: f32,
+}
+
+impl Hash for StackState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.stack_id.hash(state);
+        self.display.hash(state);
+        self.entries.hash(state);
+        self.selected.hash(state);
+        self.scale.to_bits().hash(state);
+    }
+}
+
+impl StackState {
+    fn visible_count(&self) -> usize {
+        visible_count(self.entries.len(), self.display)
+    }
+}
+
+/// Arrow key moves through the entries of a stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum StackMove {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// The popup listing the entries of a stack, anchored beside its icon.
+///
+/// Built like the dock context menu: a `View<StackState>` mounted in its own
+/// container on the dock's `wrap_layer`, faded in and out on show and hide.
+#[derive(Debug, Clone)]
+pub(super) struct StackPopup {
+    wrap_layer: Layer,
+    view_layer: Layer,
+    view: View<StackState>,
+    active: Arc<AtomicBool>,
+}
+
+impl StackPopup {
+    fn new(base_layer: &Layer) -> Self {
+        let layers_engine = base_layer.engine.clone();
+        let wrap = layers_engine.new_layer();
+        wrap.set_key("dock_stack_container");
+        wrap.set_size(Size::percent(1.0, 1.0), None);
+        wrap.set_layout_style(Style {
+            position: taffy::style::Position::Absolute,
+            display: taffy::style::Display::Flex,
+            justify_content: Some(taffy::JustifyContent::FlexStart),
+            align_items: Some(taffy::AlignItems::FlexStart),
+            ..Default::default()
+        });
+        let view_layer = layers_engine.new_layer();
+        let _ = layers_engine.add_layer(&wrap);
+        let _ = wrap.add_sublayer(&view_layer);
+
+        view_layer.set_anchor_point((0.5, 1.0), None);
+        let view = View::new(
+            "dock_stack_inner",
+            StackState::default(),
+            Box::new(render_stack),
+        );
+        view.mount_layer(view_layer.clone());
+
+        let _ = base_layer.add_sublayer(&wrap);
+        Self {
+            wrap_layer: wrap,
+            view_layer,
+            view,
+            active: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn show_at(&self, x: f32, y: f32, anchor: (f32, f32)) {
+        let scale = self.view.get_state().scale;
+        self.view_layer.set_anchor_point(anchor, None);
+        self.view_layer.set_position(
+            Point {
+                x: x * scale,
+                y: y * scale,
+            },
+            None,
+        );
+        self.active
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        self.wrap_layer.set_opacity(0.0, None);
+        self.wrap_layer.set_hidden(false);
+        self.wrap_layer.set_opacity(
+            1.0,
+            Some(Config::transition_or_instant(Transition {
+                delay: 0.0,
+                timing: TimingFunction::Spring(Spring::with_duration_and_bounce(0.1, 0.0)),
+            })),
+        );
+    }
+
+    fn hide(&self) {
+        self.active
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+        self.wrap_layer
+            .set_opacity(
+                0.0,
+                Some(Config::transition_or_instant(Transition {
+                    delay: 0.0,
+                    timing: TimingFunction::Spring(Spring::with_duration_and_bounce(0.10, 0.0)),
+                })),
+            )
+            .on_finish(
+                |l: &Layer, _p: f32| {
+                    l.set_hidden(true);
+                },
+                true,
+            );
+    }
+
+    fn is_active(&self) -> bool {
+        self.active.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Popup-local logical position of the physical point `(x, y)`.
+    fn local_point(&self, x: f32, y: f32) -> (f32, f32) {
+        let scale = self.view.get_state().scale;
+        let bounds = self.view_layer.render_bounds_transformed();
+        ((x - bounds.left) / scale, (y - bounds.top) / scale)
+    }
+}
+
+impl DockView {
+    /// Whether `identifier` is the dock item of a stack.
+    pub fn is_stack(&self, identifier: &str) -> bool {
+        self.get_state().item_type(identifier) == crate::otto_dock::DockItemType::Stack
+    }
+
+    /// The configured stack behind the dock item `identifier`.
+    pub(super) fn stack_config_for(&self, identifier: &str) -> Option<DockStack> {
+        let name = identifier.strip_prefix(STACK_ID_PREFIX)?;
+        self.dock_config
+            .read()
+            .unwrap()
+            .stacks
+            .iter()
+            .find(|stack| stack.name == name)
+            .cloned()
+    }
+
+    /// Open the popup of the stack `identifier` above its icon, or close it
+    /// when it is already open. Its entries are loaded in the background.
+    pub(super) fn toggle_stack(&self, identifier: &str) {
+        let open_id = self
+            .stack_popup
+            .read()
+            .unwrap()
+            .as_ref()
+            .filter(|popup| popup.is_active())
+            .map(|popup| popup.view.get_state().stack_id);
+        if open_id.is_some() {
+            self.close_stack();
+            if open_id.as_deref() == Some(identifier) {
+                return;
+            }
+        }
+        let Some(stack) = self.stack_config_for(identifier) else {
+            tracing::warn!("stack {} is not configured", identifier);
+            return;
+        };
+        let Some(pos) = self.popup_anchor_for(identifier, POPUP_MARGIN) else {
+            return;
+        };
+
+        self.set_active_label(None);
+        let mut popup_lock = self.stack_popup.write().unwrap();
+        let popup = popup_lock.get_or_insert_with(|| StackPopup::new(&self.wrap_layer));
+        // A fan rises from the icon, so side docks show every stack as a grid
+        let display = if self.position().is_vertical() {
+            StackDisplay::Grid
+        } else {
+            stack.display
+        };
+        popup.view.update_state(&StackState {
+            stack_id: identifier.to_string(),
+            display,
+            entries: Vec::new(),
+            selected: None,
+            scale: self.scale(),
+        });
+        popup.show_at(pos.x, pos.y, self.popup_anchor_point());
+        let view = popup.view.clone();
+        drop(popup_lock);
+
+        let stack_id = identifier.to_string();
+        tokio::spawn(async move {
+            let entries = load_entries(stack.source()).await;
+            let state = view.get_state();
+            // The stack may have been closed or replaced meanwhile.
+            if state.stack_id == stack_id {
+                view.update_state(&StackState { entries, ..state });
+            }
+        });
+    }
+
+    /// Logical position, relative to `wrap_layer`, of the point `margin`
+    /// logical pixels away from the center of the icon `identifier`, on the
+    /// side facing away from the screen edge.
+    pub(super) fn popup_anchor_for(&self, identifier: &str, margin: f32) -> Option<Point> {
+        let app_layers = self.app_layers.read().unwrap();
+        let entry = app_layers.values().find(|e| e.identifier == identifier)?;
+        Some(self.popup_anchor_beside(entry.layer.render_bounds_transformed(), margin))
+    }
+
+    /// Logical position, relative to `wrap_layer`, of the point `margin`
+    /// logical pixels off the inner edge of `bounds` (physical), centered
+    /// along the dock.
+    pub(super) fn popup_anchor_beside(&self, bounds: skia::Rect, margin: f32) -> Point {
+        let scale = self.scale();
+        let wrap_bounds = self.wrap_layer.render_bounds_transformed();
+        let x = (bounds.center_x() - wrap_bounds.x()) / scale;
+        let y = (bounds.center_y() - wrap_bounds.y()) / scale;
+        match self.position() {
+            DockPosition::Bottom => Point::new(x, (bounds.top - wrap_bounds.y()) / scale - margin),
+            DockPosition::Left => Point::new((bounds.right - wrap_bounds.x()) / scale + margin, y),
+            DockPosition::Right => Point::new((bounds.left - wrap_bounds.x()) / scale - margin, y),
+        }
+    }
+
+    /// Point of a popup, as a fraction of its size, that goes on the
+    /// position from `popup_anchor_for`: the edge facing the dock.
+    pub(super) fn popup_anchor_point(&self) -> (f32, f32) {
+        match self.position() {
+            DockPosition::Bottom => (0.5, 1.0),
+            DockPosition::Left => (0.0, 0.5),
+            DockPosition::Right => (1.0, 0.5),
+        }
+    }
+
+    /// Hide the open stack and let the dock magnify under the pointer again.
+    pub fn close_stack(&self) {
+        let was_open = self.has_stack_open();
+        if let Some(popup) = self.stack_popup.read().unwrap().as_ref() {
+            popup.hide();
+        }
+        if was_open {
+            self.magnify_elements_animated();
+        }
+    }
+
+    pub fn has_stack_open(&self) -> bool {
+        self.stack_popup
+            .read()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|popup| popup.is_active())
+    }
+
+    /// Physical bounds of the open stack popup.
+    pub(super) fn stack_bounds(&self) -> Option<skia::Rect> {
+        self.stack_popup
+            .read()
+            .unwrap()
+            .as_ref()
+            .filter(|popup| popup.is_active())
+            .map(|popup| popup.view_layer.render_bounds_transformed())
+    }
+
+    /// Select the entry under the physical point `(x, y)`, if any.
+    pub(super) fn stack_hover(&self, x: f32, y: f32) {
+        let popup_lock = self.stack_popup.read().unwrap();
+        let Some(popup) = popup_lock.as_ref().filter(|popup| popup.is_active()) else {
+            return;
+        };
+        let (x, y) = popup.local_point(x, y);
+        let state = popup.view.get_state();
+        let selected = hit_test(x, y, state.visible_count(), state.display);
+        if selected != state.selected {
+            popup.view.update_state(&StackState { selected, ..state });
+        }
+    }
+
+    /// The entry under the physical point `(x, y)`, if any.
+    pub(super) fn stack_entry_at(&self, x: f32, y: f32) -> Option<StackTarget> {
+        let popup_lock = self.stack_popup.read().unwrap();
+        let popup = popup_lock.as_ref().filter(|popup| popup.is_active())?;
+        let (x, y) = popup.local_point(x, y);
+        let state = popup.view.get_state();
+        let index = hit_test(x, y, state.visible_count(), state.display)?;
+        Some(state.entries[index].target.clone())
+    }
+
+    /// Move the keyboard selection of the open stack.
+    pub(super) fn stack_navigate(&self, direction: StackMove) {
+        let popup_lock = self.stack_popup.read().unwrap();
+        let Some(popup) = popup_lock.as_ref().filter(|popup| popup.is_active()) else {
+            return;
+        };
+        let state = popup.view.get_state();
+        let selected = move_selection(
+            state.selected,
+            state.visible_count(),
+            state.display,
+            direction,
+        );
+        popup.view.update_state(&StackState { selected, ..state });
+    }
+
+    /// The selected entry of the open stack.
+    pub(super) fn selected_stack_entry(&self) -> Option<StackTarget> {
+        let popup_lock = self.stack_popup.read().unwrap();
+        let popup = popup_lock.as_ref().filter(|popup| popup.is_active())?;
+        let state = popup.view.get_state();
+        state
+            .selected
+            .and_then(|index| state.entries.get(index))
+            .map(|entry| entry.target.clone())
+    }
+
+    /// Open a file with its default application, or launch an app, then
+    /// close the stack.
+    pub(super) fn open_stack_entry<Backend: crate::state::Backend>(
+        &self,
+        target: StackTarget,
+        state: &mut crate::Otto<Backend>,
+    ) {
+        match target {
+            StackTarget::File(path) => {
+                state.launch_program(
+                    "xdg-open".to_string(),
+                    vec![path.to_string_lossy().into_owned()],
+                );
+            }
+            StackTarget::App(app) => {
+                if !state.focus_app(&app.identifier) {
+                    if let Some((cmd, args)) = app.command(&[]) {
+                        state.launch_program(cmd, args);
+                    } else {
+                        tracing::warn!("stack app {} has no executable command", app.identifier);
+                    }
+                }
+            }
+        }
+        self.close_stack();
+    }
+}
+
+/// Load the entries of a stack: the newest entries of a folder, or the
+/// apps of a group in their configured order.
+async fn load_entries(source: StackSource) -> Vec<StackEntry> {
+    match source {
+        StackSource::Folder(folder) => folder_entries(&folder),
+        StackSource::Apps(ids) => {
+            let mut entries = Vec::new();
+            for id in ids {
+                let id = id.strip_suffix(".desktop").unwrap_or(&id).to_string();
+                match ApplicationsInfo::get_app_info_by_id(id.clone()).await {
+                    Some(app) => entries.push(StackEntry {
+                        label: app.desktop_name().unwrap_or_else(|| id.clone()),
+                        icon: app.icon.clone(),
+                        target: StackTarget::App(app),
+                    }),
+                    None => tracing::warn!("stack app not found: {}", id),
+                }
+            }
+            entries
+        }
+    }
+}
+
+fn folder_entries(folder: &Path) -> Vec<StackEntry> {
+    let read_dir = match std::fs::read_dir(folder) {
+        Ok(read_dir) => read_dir,
+        Err(err) => {
+            tracing::warn!("Failed to read stack folder {}: {}", folder.display(), err);
+            return Vec::new();
+        }
+    };
+    let mut files: Vec<(PathBuf, bool, SystemTime)> = read_dir
+        .filter_map(Result::ok)
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            Some((entry.path(), metadata.is_dir(), modified))
+        })
+        .collect();
+    files.sort_by(|a, b| b.2.cmp(&a.2));
+    files.truncate(FOLDER_LIMIT);
+
+    let mut icons: HashMap<&'static str, Option<skia::Image>> = HashMap::new();
+    files
+        .into_iter()
+        .map(|(path, is_dir, _)| {
+            let icon_name = file_icon_name(&path, is_dir);
+            // Pictures are their own icon.
+            let icon = (icon_name == "image-x-generic")
+                .then(|| image_from_path(&path.to_string_lossy(), (256, 256)))
+                .flatten()
+                .or_else(|| {
+                    icons
+                        .entry(icon_name)
+                        .or_insert_with(|| {
+                            find_icon_with_theme(icon_name, 128, 1)
+                                .and_then(|icon_path| image_from_path(&icon_path, (128, 128)))
+                        })
+                        .clone()
+                });
+            StackEntry {
+                label: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                icon,
+                target: StackTarget::File(path),
+            }
+        })
+        .collect()
+}
+
+/// Generic icon name for a file, from its extension.
+fn file_icon_name(path: &Path, is_dir: bool) -> &'static str {
+    if is_dir {
+        return "folder";
+    }
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg" | "bmp" => "image-x-generic",
+        "mp4" | "mkv" | "webm" | "mov" | "avi" => "video-x-generic",
+        "mp3" | "ogg" | "flac" | "wav" | "opus" | "m4a" => "audio-x-generic",
+        "pdf" => "application-pdf",
+        "zip" | "tar" | "gz" | "xz" | "bz2" | "zst" | "7z" | "rar" => "package-x-generic",
+        "html" | "htm" => "text-html",
+        "desktop" | "sh" | "appimage" => "application-x-executable",
+        _ => "text-x-generic",
+    }
+}
+
+/// Entries a stack shows at once: all of them in a grid, the first few in
+/// a fan.
+fn visible_count(count: usize, display: StackDisplay) -> usize {
+    match display {
+        StackDisplay::Grid => count,
+        StackDisplay::Fan => count.min(FAN_MAX_ENTRIES),
+    }
+}
+
+/// Columns of a grid of `count` entries: as square as possible, but no
+/// wider than `GRID_MAX_COLUMNS`.
+fn grid_columns(count: usize) -> usize {
+    ((count as f32).sqrt().ceil() as usize).clamp(1, GRID_MAX_COLUMNS)
+}
+
+/// Size of the popup showing `count` entries, in logical pixels.
+fn popup_size(count: usize, display: StackDisplay) -> (f32, f32) {
+    match display {
+        StackDisplay::Grid => {
+            let columns = grid_columns(count);
+            let rows = count.div_ceil(columns).max(1);
+            (
+                columns as f32 * GRID_CELL + 2.0 * POPUP_PADDING,
+                rows as f32 * GRID_CELL + 2.0 * POPUP_PADDING,
+            )
+        }
+        StackDisplay::Fan => (
+            FAN_LABEL_WIDTH + FAN_ICON + FAN_CURVE + 2.0 * POPUP_PADDING,
+            count.max(1) as f32 * FAN_ROW + 2.0 * POPUP_PADDING,
+        ),
+    }
+}
+
+/// Cell of entry `index` of `count`, in logical pixels from the top-left of
+/// the popup. Grids fill rows from the top; fans start at the bottom, next
+/// to the dock, and lean further right the higher they go.
+fn cell_rect(index: usize, count: usize, display: StackDisplay) -> skia::Rect {
+    match display {
+        StackDisplay::Grid => {
+            let columns = grid_columns(count);
+            skia::Rect::from_xywh(
+                POPUP_PADDING + (index % columns) as f32 * GRID_CELL,
+                POPUP_PADDING + (index / columns) as f32 * GRID_CELL,
+                GRID_CELL,
+                GRID_CELL,
+            )
+        }
+        StackDisplay::Fan => {
+            let (_, height) = popup_size(count, display);
+            let t = index as f32 / count.saturating_sub(1).max(1) as f32;
+            skia::Rect::from_xywh(
+                POPUP_PADDING + FAN_CURVE * t * t,
+                height - POPUP_PADDING - (index + 1) as f32 * FAN_ROW,
+                FAN_LABEL_WIDTH + FAN_ICON,
+                FAN_ROW,
+            )
+        }
+    }
+}
+
+/// Entry under the popup-local logical point `(x, y)`.
+fn hit_test(x: f32, y: f32, count: usize, display: StackDisplay) -> Option<usize> {
+    (0..count).find(|index| {
+        let cell = cell_rect(*index, count, display);
+        x >= cell.left && x < cell.right && y >= cell.top && y < cell.bottom
+    })
+}
+
+/// Selection after an arrow key. The first key selects the entry closest
+/// to the dock's icon; moves stop at the edges.
+fn move_selection(
+    selected: Option<usize>,
+    count: usize,
+    display: StackDisplay,
+    direction: StackMove,
+) -> Option<usize> {
+    if count == 0 {
+        return None;
+    }
+    let Some(index) = selected.filter(|index| *index < count) else {
+        return Some(match display {
+            // The last row sits right above the icon.
+            StackDisplay::Grid => (count - 1) / grid_columns(count) * grid_columns(count),
+            StackDisplay::Fan => 0,
+        });
+    };
+    let next = match display {
+        StackDisplay::Grid => {
+            let columns = grid_columns(count);
+            let last_row = (count - 1) / columns;
+            match direction {
+                StackMove::Left if index % columns > 0 => index - 1,
+                StackMove::Right if index % columns + 1 < columns && index + 1 < count => index + 1,
+                StackMove::Up if index >= columns => index - columns,
+                // Down from above a short last row lands on its last entry.
+                StackMove::Down if index / columns < last_row => (index + columns).min(count - 1),
+                _ => index,
+            }
+        }
+        StackDisplay::Fan => match direction {
+            StackMove::Up if index + 1 < count => index + 1,
+            StackMove::Down if index > 0 => index - 1,
+            _ => index,
+        },
+    };
+    Some(next)
+}
+
+/// Shorten `text` with an ellipsis until it fits `max_width`.
+fn fit_label(font: &skia::Font, text: &str, max_width: f32) -> String {
+    if font.measure_str(text, None).0 <= max_width {
+        return text.to_string();
+    }
+    let mut chars: Vec<char> = text.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let candidate: String = chars.iter().collect::<String>() + "…";
+        if font.measure_str(&candidate, None).0 <= max_width {
+            return candidate;
+        }
+    }
+    "…".to_string()
+}
+
+/// Render function for `View<StackState>`
+///
+/// A single layer drawing the entries: a blurred panel for grids, bare
+/// label pills for fans.
+fn render_stack(state: &StackState, _view: &View<StackState>) -> LayerTree {
+    let draw_scale = state.scale;
+    let count = state.visible_count();
+    let display = state.display;
+    let (width, height) = popup_size(count, display);
+    let entries: Vec<StackEntry> = state.entries.iter().take(count).cloned().collect();
+    let selected = state.selected;
+    let font_family = Config::with(|c| c.font_family.clone());
+
+    let draw_fn = move |canvas: &skia::Canvas, w: f32, h: f32| {
+        canvas.save();
+        canvas.scale((draw_scale, draw_scale));
+        let font_style = skia::FontStyle::new(
+            skia::font_style::Weight::MEDIUM,
+            skia::font_style::Width::NORMAL,
+            skia::font_style::Slant::Upright,
+        );
+        let font = FONT_CACHE
+            .with(|fc| fc.make_font_with_fallback(&font_family, font_style, LABEL_FONT_SIZE));
+        let mut text_paint = skia::Paint::new(theme_colors().text_primary.c4f(), None);
+        text_paint.set_anti_alias(true);
+        let mut highlight =
+            skia::Paint::new(crate::theme::accent_color().opacity(0.35).c4f(), None);
+        highlight.set_anti_alias(true);
+        let mut pill = skia::Paint::new(theme_colors().materials_thick.c4f(), None);
+        pill.set_anti_alias(true);
+        let mut icon_paint = skia::Paint::default();
+        icon_paint.set_anti_alias(true);
+        let sampling = skia::SamplingOptions::from(skia::CubicResampler::catmull_rom());
+
+        for (index, entry) in entries.iter().enumerate() {
+            let cell = cell_rect(index, count, display);
+            let (icon_rect, label_width) = match display {
+                StackDisplay::Grid => (
+                    skia::Rect::from_xywh(
+                        cell.left + (GRID_CELL - GRID_ICON) / 2.0,
+                        cell.top + 6.0,
+                        GRID_ICON,
+                        GRID_ICON,
+                    ),
+                    GRID_CELL - 8.0,
+                ),
+                StackDisplay::Fan => (
+                    skia::Rect::from_xywh(
+                        cell.right - FAN_ICON,
+                        cell.top + (FAN_ROW - FAN_ICON) / 2.0,
+                        FAN_ICON,
+                        FAN_ICON,
+                    ),
+                    FAN_LABEL_WIDTH - 24.0,
+                ),
+            };
+            if selected == Some(index) {
+                let rrect = skia::RRect::new_rect_xy(cell.with_inset((2.0, 2.0)), 8.0, 8.0);
+                canvas.draw_rrect(rrect, &highlight);
+            }
+            if let Some(icon) = entry.icon.as_ref() {
+                canvas.draw_image_rect_with_sampling_options(
+                    icon,
+                    None,
+                    icon_rect,
+                    sampling,
+                    &icon_paint,
+                );
+            }
+
+            let label = fit_label(&font, &entry.label, label_width);
+            let label_size = font.measure_str(&label, Some(&text_paint)).1;
+            match display {
+                StackDisplay::Grid => {
+                    let x = cell.left + (GRID_CELL - label_size.width()) / 2.0;
+                    let y = icon_rect.bottom + 6.0 + LABEL_FONT_SIZE;
+                    canvas.draw_str(&label, (x, y), &font, &text_paint);
+                }
+                StackDisplay::Fan => {
+                    // Labels sit in a pill to the left of the icon.
+                    let pill_rect = skia::Rect::from_xywh(
+                        icon_rect.left - 8.0 - label_size.width() - 16.0,
+                        cell.top + (FAN_ROW - LABEL_FONT_SIZE * 2.0) / 2.0,
+                        label_size.width() + 16.0,
+                        LABEL_FONT_SIZE * 2.0,
+                    );
+                    let rrect =
+                        skia::RRect::new_rect_xy(pill_rect, LABEL_FONT_SIZE, LABEL_FONT_SIZE);
+                    canvas.draw_rrect(rrect, &pill);
+                    canvas.draw_str(
+                        &label,
+                        (pill_rect.left + 8.0, pill_rect.top + LABEL_FONT_SIZE * 1.4),
+                        &font,
+                        &text_paint,
+                    );
+                }
+            }
+        }
+        canvas.restore();
+        skia::Rect::from_xywh(0.0, 0.0, w, h)
+    };
+
+    let builder = LayerTreeBuilder::default()
+        .key("dock_stack")
+        .size(Size::points(width * draw_scale, height * draw_scale))
+        .content(Some(draw_fn));
+    let builder = match display {
+        StackDisplay::Grid => builder
+            .border_corner_radius(BorderRadius::new_single(14.0 * draw_scale))
+            .blend_mode(BlendMode::BackgroundBlur)
+            .background_color(theme_colors().materials_medium)
+            .shadow_color(theme_colors().shadow_color)
+            .shadow_offset(((0.0, 4.0 * draw_scale).into(), None))
+            .shadow_radius((16.0 * draw_scale, None)),
+        StackDisplay::Fan => builder,
+    };
+    builder.build().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grids_are_as_square_as_allowed() {
+        assert_eq!(grid_columns(0), 1);
+        assert_eq!(grid_columns(1), 1);
+        assert_eq!(grid_columns(4), 2);
+        assert_eq!(grid_columns(7), 3);
+        assert_eq!(grid_columns(40), GRID_MAX_COLUMNS);
+    }
+
+    #[test]
+    fn first_move_selects_the_entry_next_to_the_icon() {
+        // 7 entries in 3 columns: the last row starts at 6.
+        assert_eq!(
+            move_selection(None, 7, StackDisplay::Grid, StackMove::Up),
+            Some(6)
+        );
+        assert_eq!(
+            move_selection(None, 7, StackDisplay::Fan, StackMove::Up),
+            Some(0)
+        );
+        assert_eq!(
+            move_selection(None, 0, StackDisplay::Grid, StackMove::Up),
+            None
+        );
+    }
+
+    #[test]
+    fn grid_moves_stop_at_the_edges() {
+        let grid =
+            |selected, direction| move_selection(Some(selected), 7, StackDisplay::Grid, direction);
+        assert_eq!(grid(0, StackMove::Left), Some(0));
+        assert_eq!(grid(0, StackMove::Right), Some(1));
+        assert_eq!(grid(2, StackMove::Right), Some(2));
+        assert_eq!(grid(4, StackMove::Up), Some(1));
+        assert_eq!(grid(1, StackMove::Up), Some(1));
+        assert_eq!(grid(6, StackMove::Right), Some(6));
+        assert_eq!(grid(6, StackMove::Down), Some(6));
+        // Down from above the short last row lands on its last entry.
+        assert_eq!(grid(5, StackMove::Down), Some(6));
+    }
+
+    #[test]
+    fn fan_moves_go_up_from_the_dock() {
+        let fan =
+            |selected, direction| move_selection(Some(selected), 3, StackDisplay::Fan, direction);
+        assert_eq!(fan(0, StackMove::Up), Some(1));
+        assert_eq!(fan(2, StackMove::Up), Some(2));
+        assert_eq!(fan(1, StackMove::Down), Some(0));
+        assert_eq!(fan(0, StackMove::Down), Some(0));
+    }
+
+    #[test]
+    fn hit_test_finds_cells() {
+        let center = |rect: skia::Rect| (rect.center_x(), rect.center_y());
+        for display in [StackDisplay::Grid, StackDisplay::Fan] {
+            for index in 0..5 {
+                let (x, y) = center(cell_rect(index, 5, display));
+                assert_eq!(hit_test(x, y, 5, display), Some(index));
+            }
+            assert_eq!(hit_test(1.0, 1.0, 5, display), None);
+        }
+        // The first fan entry sits at the bottom of the popup.
+        let (_, height) = popup_size(5, StackDisplay::Fan);
+        assert_eq!(
+            cell_rect(0, 5, StackDisplay::Fan).bottom,
+            height - POPUP_PADDING
+        );
+    }
+
+    #[test]
+    fn file_icons_follow_the_extension() {
+        assert_eq!(file_icon_name(Path::new("/d"), true), "folder");
+        assert_eq!(
+            file_icon_name(Path::new("a/photo.JPG"), false),
+            "image-x-generic"
+        );
+        assert_eq!(
+            file_icon_name(Path::new("a/report.pdf"), false),
+            "application-pdf"
+        );
+        assert_eq!(
+            file_icon_name(Path::new("a/notes"), false),
+            "text-x-generic"
+        );
+    }
+}
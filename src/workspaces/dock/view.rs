@@ -10,17 +10,17 @@ use layers::{
         AnimationRef, Engine, NodeRef, TransactionRef,
     },
     prelude::{taffy, Layer, Point, Spring, TimingFunction},
-    skia,
+    skia::{self, Contains},
     taffy::{prelude::FromLength, style::Style},
     types::{BlendMode, Size},
     view::{BuildLayerTree, LayerTreeBuilder},
 };
-use otto_kit::prelude::{ContextMenuStyle, MenuItem};
+use otto_kit::prelude::MenuItem;
 use smithay::{reexports::wayland_server::backend::ObjectId, utils::IsAlive};
 use tokio::sync::mpsc;
 
 use crate::{
-    config::{Config, DockBookmark},
+    config::{Config, DockBookmark, DockPosition, DockStack, StackDisplay},
     shell::WindowElement,
     theme::theme_colors,
     utils::{parse_hex_color, Observer},
@@ -31,8 +31,11 @@ use crate::{
 };
 
 use super::{
-    model::DockModel,
+    drag::IconDrag,
+    model::{stack_identifier, DockModel},
+    previews::PreviewsState,
     render::{setup_app_icon, setup_label, setup_miniwindow_icon},
+    stack::StackPopup,
 };
 
 pub const BASE_ICON_SIZE: f32 = 300.0;
@@ -55,7 +58,7 @@ type MiniWindowLayers = (Layer, Layer, Layer, Option<u32>);
 
 #[derive(Debug, Clone)]
 pub struct DockView {
-    layers_engine: Arc<Engine>,
+    pub(super) layers_engine: Arc<Engine>,
     // layers
     pub wrap_layer: layers::prelude::Layer,
     pub view_layer: layers::prelude::Layer,
@@ -65,6 +68,8 @@ pub struct DockView {
     dock_windows_container: layers::prelude::Layer,
 
     pub(super) app_layers: Arc<RwLock<HashMap<String, AppLayerEntry>>>,
+    /// `match_id`s of the app layers in the order they sit in `dock_apps_container`.
+    app_layers_order: Arc<RwLock<Vec<String>>>,
     miniwindow_layers: Arc<RwLock<HashMap<ObjectId, MiniWindowLayers>>>,
     state: Arc<RwLock<DockModel>>,
     active: Arc<AtomicBool>,
@@ -84,6 +89,15 @@ pub struct DockView {
     magnification_enabled: Arc<AtomicBool>,
     /// Physical screen dimensions, kept in sync by the compositor via `set_screen_size`.
     screen_size: Arc<RwLock<(i32, i32)>>,
+    /// Scale of the dock's output, set along with the screen size.
+    scale: Arc<RwLock<f32>>,
+    /// Logical position of the dock's output in the global space, set via `set_screen_origin`.
+    screen_origin: Arc<RwLock<(i32, i32)>>,
+    /// Only the primary dock collects minimized windows; the docks on the
+    /// other outputs show launchers and running apps.
+    shows_minimized_windows: bool,
+    /// Set by `destroy` so the notification task stops.
+    destroyed: Arc<AtomicBool>,
     /// Pre-computed autohide hot-zone rect, rebuilt by `render_dock` every time the dock
     /// layout changes. `check_dock_hot_zone` reads this without doing any computation.
     pub cached_hot_zone: Arc<RwLock<Option<skia::Rect>>>,
@@ -100,6 +114,15 @@ pub struct DockView {
     /// `match_id`. The flag stays `true` while bouncing; setting it `false` (or
     /// removing the entry) stops the bounce loop once a window appears.
     bouncing: Arc<RwLock<HashMap<String, Arc<AtomicBool>>>>,
+    /// The app icon under a pressed button, and its drag once it starts moving.
+    pub(super) icon_drag: Arc<RwLock<Option<IconDrag>>>,
+    /// Window previews of the hovered running app.
+    pub(super) previews: Arc<RwLock<PreviewsState>>,
+    /// Popup of the open stack, created the first time a stack opens.
+    pub(super) stack_popup: Arc<RwLock<Option<StackPopup>>>,
+    /// Screen edge the layers were last laid out for; labels and running
+    /// dots are rebuilt when the configured position changes.
+    layout_position: Arc<RwLock<DockPosition>>,
 }
 impl PartialEq for DockView {
     fn eq(&self, other: &Self) -> bool {
@@ -109,6 +132,7 @@ impl PartialEq for DockView {
 impl IsAlive for DockView {
     fn alive(&self) -> bool {
         self.active.load(std::sync::atomic::Ordering::Relaxed)
+            && !self.destroyed.load(std::sync::atomic::Ordering::Relaxed)
     }
 }
 
@@ -119,19 +143,23 @@ impl IsAlive for DockView {
 /// ```diagram
 /// DockView
 /// └── wrap_layer: `dock`
-///     └── view_layer `dock-view`
-///         ├── bar_layer `dock-bar`
-///         ├── dock_apps_container `dock_app_container`
-///         │   ├── App
-///         │   │   ├── Icon
-///         │   │   └── Label
-///         │   └── App
-///         │       ├── Icon
-///         │       └── Label
-///         ├── dock_handle `dock_handle`
-///         └── dock_windows_container `dock_windows_container`
-///             ├── miniwindow
-///             └── miniwindow
+///     ├── view_layer `dock-view`
+///     │   ├── bar_layer `dock-bar`
+///     │   ├── dock_apps_container `dock_app_container`
+///     │   │   ├── App
+///     │   │   │   ├── Icon
+///     │   │   │   └── Label
+///     │   │   └── App
+///     │   │       ├── Icon
+///     │   │       └── Label
+///     │   ├── dock_handle `dock_handle`
+///     │   └── dock_windows_container `dock_windows_container`
+///     │       ├── miniwindow
+///     │       └── miniwindow
+///     ├── previews `dock_previews_<app>`
+///     │   └── thumbnail
+///     └── stack `dock_stack_container`
+///         └── entries `dock_stack`
 /// ```
 ///
 ///
@@ -143,8 +171,49 @@ impl DockView {
     }
 
     pub fn new(layers_engine: Arc<Engine>, app_icons_manager: Arc<AppIconsManager>) -> Self {
-        let draw_scale = Config::with(|config| config.screen_scale) as f32 * 0.8;
-        let dock_size_multiplier = Config::with(|config| config.dock.size.clamp(0.5, 2.0)) as f32;
+        let dock_config = Config::with(|c| c.dock.clone());
+        let magnification_enabled = Arc::new(AtomicBool::new(dock_config.magnification));
+        let dock = Self::build(
+            layers_engine,
+            app_icons_manager,
+            Arc::new(RwLock::new(dock_config)),
+            magnification_enabled,
+            true,
+        );
+        dock.load_configured_bookmarks();
+
+        dock
+    }
+
+    /// Create a dock for another output. It shares the config, the
+    /// magnification toggle and the app icons with `self`, starts with the
+    /// same launchers and running apps, and never shows minimized windows.
+    pub fn new_sibling(&self) -> Self {
+        let dock = Self::build(
+            self.layers_engine.clone(),
+            self.app_icons_manager.clone(),
+            self.dock_config.clone(),
+            self.magnification_enabled.clone(),
+            false,
+        );
+        dock.update_state(&DockModel {
+            minimized_windows: Vec::new(),
+            ..self.get_state()
+        });
+
+        dock
+    }
+
+    fn build(
+        layers_engine: Arc<Engine>,
+        app_icons_manager: Arc<AppIconsManager>,
+        dock_config: Arc<RwLock<crate::config::DockConfig>>,
+        magnification_enabled: Arc<AtomicBool>,
+        shows_minimized_windows: bool,
+    ) -> Self {
+        let scale = Config::with(|config| config.screen_scale) as f32;
+        let draw_scale = scale * 0.8;
+        let dock_size_multiplier = dock_config.read().unwrap().size.clamp(0.5, 2.0) as f32;
         let base_icon_size = 95.0;
         let scaled_icon_size = base_icon_size * dock_size_multiplier * draw_scale;
 
@@ -200,6 +269,7 @@ impl DockView {
             .unwrap();
 
         bar_layer.build_layer_tree(&bar_tree);
+        crate::theme::apply_material_blur(&bar_layer);
 
         let dock_apps_container = layers_engine.new_layer();
         let _ = view_layer.add_sublayer(&dock_apps_container);
@@ -242,15 +312,17 @@ impl DockView {
             .content(Some(move |canvas: &skia::Canvas, w, h| {
                 let paint = layers::skia::Paint::new(theme_colors().text_tertiary.c4f(), None);
 
+                // The line runs across the bar: upright on a bottom dock,
+                // flat on a side dock where the handle is wider than tall.
                 let line_width: f32 = 3.0 * draw_scale;
-                let margin_h = (w - line_width) / 2.0;
-                let margin_v = 18.0 * draw_scale * dock_size_multiplier;
-                let rect = layers::skia::Rect::from_xywh(
-                    margin_h,
-                    margin_v,
-                    w - 2.0 * margin_h,
-                    h - 2.0 * margin_v,
-                );
+                let margin = 18.0 * draw_scale * dock_size_multiplier;
+                let rect = if h >= w {
+                    let margin_h = (w - line_width) / 2.0;
+                    layers::skia::Rect::from_xywh(margin_h, margin, line_width, h - 2.0 * margin)
+                } else {
+                    let margin_v = (h - line_width) / 2.0;
+                    layers::skia::Rect::from_xywh(margin, margin_v, w - 2.0 * margin, line_width)
+                };
                 let rrect = layers::skia::RRect::new_rect_xy(rect, 3.0, 3.0);
                 canvas.draw_rrect(rrect, &paint);
                 skia::Rect::from_xywh(0.0, 0.0, w, h)
@@ -287,6 +359,7 @@ impl DockView {
 
         let mut initial_state = DockModel::new();
         initial_state.width = 1000;
+        let layout_position = dock_config.read().unwrap().position;
 
         let (notify_tx, notify_rx) = mpsc::channel(5);
         let dock = Self {
@@ -299,6 +372,7 @@ impl DockView {
             dock_apps_container,
             dock_windows_container,
             app_layers: Arc::new(RwLock::new(HashMap::new())),
+            app_layers_order: Arc::new(RwLock::new(Vec::new())),
             miniwindow_layers: Arc::new(RwLock::new(HashMap::new())),
             state: Arc::new(RwLock::new(initial_state)),
             active: Arc::new(AtomicBool::new(true)),
@@ -308,17 +382,23 @@ impl DockView {
             dragging: Arc::new(AtomicBool::new(false)),
             context_menu: Arc::new(RwLock::new(None)),
             context_menu_app_id: Arc::new(RwLock::new(None)),
-            dock_config: Arc::new(RwLock::new(Config::with(|c| c.dock.clone()))),
-            magnification_enabled: Arc::new(AtomicBool::new(Config::with(|c| {
-                c.dock.magnification
-            }))),
+            dock_config,
+            magnification_enabled,
             screen_size: Arc::new(RwLock::new((0, 0))),
+            scale: Arc::new(RwLock::new(scale)),
+            screen_origin: Arc::new(RwLock::new((0, 0))),
+            shows_minimized_windows,
+            destroyed: Arc::new(AtomicBool::new(false)),
             cached_hot_zone: Arc::new(RwLock::new(None)),
             cached_dock_bounds: Arc::new(RwLock::new(None)),
             active_label: Arc::new(RwLock::new(None)),
             last_layout_animation: Arc::new(RwLock::new(None)),
             pressed_layer: Arc::new(RwLock::new(None)),
             bouncing: Arc::new(RwLock::new(HashMap::new())),
+            icon_drag: Arc::new(RwLock::new(None)),
+            previews: Arc::new(RwLock::new(PreviewsState::default())),
+            stack_popup: Arc::new(RwLock::new(None)),
+            layout_position: Arc::new(RwLock::new(layout_position)),
             app_icons_manager,
         };
         // Sync AtomicBool from dock_config (single source)
@@ -328,15 +408,21 @@ impl DockView {
         );
         dock.render_dock();
         dock.notification_handler(notify_rx);
-        dock.load_configured_bookmarks();
 
         dock
     }
-    fn load_configured_bookmarks(&self) {
-        let bookmarks = self.dock_config.read().unwrap().bookmarks.clone();
-        if bookmarks.is_empty() {
+
+    /// Resolve the configured bookmarks into launchers, and the configured
+    /// stacks into their dock items, in the background.
+    pub fn load_configured_bookmarks(&self) {
+        let (bookmarks, stacks) = {
+            let config = self.dock_config.read().unwrap();
+            (config.bookmarks.clone(), config.stacks.clone())
+        };
+        if bookmarks.is_empty() && stacks.is_empty() {
             let mut state = self.get_state();
             state.launchers.clear();
+            state.stacks.clear();
             self.update_state(&state);
             return;
         }
@@ -359,8 +445,20 @@ impl DockView {
                 }
             }
 
+            let stacks = stacks
+                .iter()
+                .map(|stack| {
+                    Application::placeholder(
+                        &stack_identifier(&stack.name),
+                        &stack.name,
+                        stack.icon.as_deref().unwrap_or("folder"),
+                    )
+                })
+                .collect();
+
             let mut state = dock.get_state();
             state.launchers = launchers;
+            state.stacks = stacks;
             dock.update_state(&state);
         });
     }
@@ -387,7 +485,8 @@ impl DockView {
         tracing::debug!("dock: hide");
         self.active
             .store(false, std::sync::atomic::Ordering::Relaxed);
-        self.view_layer.set_position((0.0, 250.0), transition)
+        self.view_layer
+            .set_position(self.hidden_offset(), transition)
     }
     pub fn show(&self, transition: Option<Transition>) -> TransactionRef {
         if self.dock_config.read().unwrap().autohide {
@@ -395,18 +494,40 @@ impl DockView {
             // Mark active=false so is_hidden() returns true and the hot zone can trigger it.
             self.active
                 .store(false, std::sync::atomic::Ordering::Relaxed);
-            return self.view_layer.set_position((0.0, 250.0), None);
+            return self.view_layer.set_position(self.hidden_offset(), None);
         }
         tracing::debug!("dock: show");
         self.active
             .store(true, std::sync::atomic::Ordering::Relaxed);
         self.view_layer.set_position((0.0, 0.0), transition)
     }
+    /// Screen edge the dock is configured on.
+    pub fn position(&self) -> DockPosition {
+        self.dock_config.read().unwrap().position
+    }
+    /// The coordinate of `pos` along the dock: x on the bottom, y on the sides.
+    pub(super) fn along_dock(&self, pos: (f32, f32)) -> f32 {
+        if self.position().is_vertical() {
+            pos.1
+        } else {
+            pos.0
+        }
+    }
+    /// Offset of `view_layer` that slides the dock off its screen edge.
+    fn hidden_offset(&self) -> (f32, f32) {
+        edge_offset(self.position(), 1.0)
+    }
+    /// Slide the dock `fraction` of the way off its screen edge: 0.0 rests
+    /// in place, 1.0 is fully hidden.
+    pub fn slide_off_edge(&self, fraction: f32, transition: Option<Transition>) {
+        self.view_layer
+            .set_position(edge_offset(self.position(), fraction), transition);
+    }
     fn display_entries(&self, state: &DockModel) -> Vec<(Application, bool)> {
         state.display_entries()
     }
     fn render_elements_layers(&self, available_icon_width: f32, icon_size: f32) {
-        let draw_scale = Config::with(|config| config.screen_scale) as f32 * 0.8;
+        let draw_scale = self.scale() * 0.8;
         let dock_size_multiplier = self.dock_config.read().unwrap().size.clamp(0.5, 2.0) as f32;
         let icon_color_filter = {
             let dock_config = self.dock_config.read().unwrap();
@@ -450,24 +571,64 @@ impl DockView {
 
         // Calculate bar height using helper function
         let bar_height = Self::calculate_bar_height(icon_size, draw_scale * dock_size_multiplier);
-        let padding_top = 4.0 * draw_scale;
-        let padding_bottom = 4.0 * draw_scale;
+        let padding_cross = 4.0 * draw_scale;
+        let padding_main = available_icon_width * 10.0 / 95.0;
+
+        let position = self.position();
+        let vertical = position.is_vertical();
+        let relayout = {
+            let mut laid_out = self.layout_position.write().unwrap();
+            let changed = *laid_out != position;
+            *laid_out = position;
+            changed
+        };
+
+        // Stick the dock to its edge, centered along it
+        let (justify_content, align_items) = match position {
+            DockPosition::Bottom => (taffy::JustifyContent::Center, taffy::AlignItems::FlexEnd),
+            DockPosition::Left => (taffy::JustifyContent::FlexStart, taffy::AlignItems::Center),
+            DockPosition::Right => (taffy::JustifyContent::FlexEnd, taffy::AlignItems::Center),
+        };
+        self.wrap_layer.set_layout_style(Style {
+            position: layers::taffy::style::Position::Absolute,
+            display: layers::taffy::style::Display::Flex,
+            justify_content: Some(justify_content),
+            align_items: Some(align_items),
+            justify_items: Some(taffy::JustifyItems::Center),
+            ..Default::default()
+        });
 
         // Update view layer padding to match current icon size
+        let (flex_direction, padding) = if vertical {
+            (
+                taffy::FlexDirection::Column,
+                taffy::Rect {
+                    top: taffy::length(padding_main),
+                    bottom: taffy::length(padding_main),
+                    right: taffy::length(padding_cross),
+                    left: taffy::length(padding_cross),
+                },
+            )
+        } else {
+            (
+                taffy::FlexDirection::Row,
+                taffy::Rect {
+                    top: taffy::length(padding_cross),
+                    bottom: taffy::length(padding_cross),
+                    right: taffy::length(padding_main),
+                    left: taffy::length(padding_main),
+                },
+            )
+        };
         self.view_layer.set_layout_style(taffy::Style {
             position: taffy::Position::Relative,
             display: taffy::Display::Flex,
-            flex_direction: taffy::FlexDirection::Row,
+            flex_direction,
             justify_content: Some(taffy::JustifyContent::Center),
             justify_items: Some(taffy::JustifyItems::Center),
             align_items: Some(taffy::AlignItems::Center),
             gap: taffy::Size::<taffy::LengthPercentage>::from_length(0.0),
-            padding: taffy::Rect {
-                top: taffy::length(padding_top),
-                bottom: taffy::length(padding_bottom),
-                right: taffy::length(available_icon_width * 10.0 / 95.0),
-                left: taffy::length(available_icon_width * 10.0 / 95.0),
-            },
+            padding,
             ..Default::default()
         });
 
@@ -475,18 +636,34 @@ impl DockView {
             .set_border_corner_radius(bar_height / 3.5, None);
 
         self.resize_handle.set_size(
-            Size {
-                width: taffy::length(25.0 * draw_scale),
-                height: taffy::Dimension::Length(bar_height),
-            },
+            cross_size(
+                vertical,
+                taffy::length(25.0 * draw_scale),
+                taffy::Dimension::Length(bar_height),
+            ),
             None,
         );
 
         self.bar_layer.set_size(
-            Size {
-                width: taffy::percent(1.0),
-                height: taffy::Dimension::Length(bar_height),
-            },
+            cross_size(
+                vertical,
+                taffy::percent(1.0),
+                taffy::Dimension::Length(bar_height),
+            ),
+            None,
+        );
+
+        let container_style = elements_container_style(position, draw_scale);
+        self.dock_apps_container
+            .set_layout_style(container_style.clone());
+        self.dock_windows_container
+            .set_layout_style(container_style);
+        self.dock_windows_container.set_size(
+            cross_size(
+                vertical,
+                taffy::Dimension::Auto,
+                taffy::Dimension::Length(icon_size),
+            ),
             None,
         );
 
@@ -518,6 +695,13 @@ impl DockView {
                     entry.running = *running;
                     entry.dot_layer.set_hidden(!*running);
 
+                    if relayout {
+                        setup_label(&entry.label_layer, app_name, position);
+                        let (dot_style, dot_size) = dot_layout(position, 5.0 * draw_scale);
+                        entry.dot_layer.set_layout_style(dot_style);
+                        entry.dot_layer.set_size(dot_size, None);
+                    }
+
                     previous_app_layers.retain(|l| l.id() != layer.id());
                 }
                 Entry::Vacant(vac) => {
@@ -595,31 +779,20 @@ impl DockView {
                     icon_mirror.set_color_filter(icon_color_filter.clone());
 
                     let label_layer = self.layers_engine.new_layer();
-                    setup_label(&label_layer, app_name);
+                    setup_label(&label_layer, app_name, position);
 
-                    // Running indicator dot — absolute-positioned at bottom center,
-                    // rendered on top of the icon because it's the last child.
+                    // Running indicator dot — absolute-positioned between the icon and
+                    // the screen edge, rendered on top of the icon because it's the last child.
                     let dot_layer = self.layers_engine.new_layer();
                     let dot_radius = 2.0 * draw_scale;
                     let dot_height = 5.0 * draw_scale;
                     {
                         use layers::view::BuildLayerTree;
+                        let (dot_style, dot_size) = dot_layout(position, dot_height);
                         let dot_tree = layers::view::LayerTreeBuilder::default()
                             .key("_dot")
-                            .layout_style(taffy::Style {
-                                position: taffy::Position::Absolute,
-                                inset: taffy::Rect {
-                                    left: taffy::length(0.0),
-                                    right: taffy::length(0.0),
-                                    top: taffy::LengthPercentageAuto::Auto,
-                                    bottom: taffy::length(0.0),
-                                },
-                                ..Default::default()
-                            })
-                            .size(Size {
-                                width: taffy::Dimension::Percent(1.0),
-                                height: taffy::Dimension::Length(dot_height),
-                            })
+                            .layout_style(dot_style)
+                            .size(dot_size)
                             .pointer_events(false)
                             .build()
                             .unwrap();
@@ -657,6 +830,22 @@ impl DockView {
             }
         }
 
+        // Keep the icons in display order; launchers can be reordered by dragging.
+        let order: Vec<String> = display_apps
+            .iter()
+            .map(|(app, _)| app.match_id.clone())
+            .collect();
+        let mut layers_order = self.app_layers_order.write().unwrap();
+        if *layers_order != order {
+            for match_id in &order {
+                if let Some(entry) = apps_layers_map.get(match_id) {
+                    let _ = self.dock_apps_container.add_sublayer(&entry.layer);
+                }
+            }
+            *layers_order = order;
+        }
+        drop(layers_order);
+
         let mut previous_miniwindows = self.get_miniwin_layers();
         let mut miniwindows_layers_map = self.miniwindow_layers.write().unwrap();
         {
@@ -673,13 +862,15 @@ impl DockView {
                         setup_miniwindow_icon(&new_layer, &inner_layer, available_icon_width);
                         let _ = new_layer.add_sublayer(&inner_layer);
 
-                        setup_label(&label_layer, title.clone());
+                        setup_label(&label_layer, title.clone(), position);
                         let _ = new_layer.add_sublayer(&label_layer);
 
                         (new_layer, inner_layer, label_layer, None)
                     });
 
-                let _label = label.clone();
+                if relayout {
+                    setup_label(label, title.clone(), position);
+                }
                 layer.remove_all_pointer_handlers();
 
                 previous_miniwindows.retain(|l| l.id() != layer.id());
@@ -690,20 +881,29 @@ impl DockView {
 
         // App layers
         for layer in previous_app_layers {
-            let animation = self
-                .layers_engine
-                .add_animation_from_transition(&Transition::ease_out_quad(0.3), false);
-            let mut changes = vec![
-                layer.change_opacity(0.0),
-                layer.change_size(layers::types::Size::points(0.0, app_height)),
-            ];
+            let animation = self.layers_engine.add_animation_from_transition(
+                &Config::transition_or_instant(Transition::ease_out_quad(0.3)),
+                false,
+            );
+            // Collapse along the dock so the neighbours close the gap
+            let collapsed = if vertical {
+                layers::types::Size::points(app_height, 0.0)
+            } else {
+                layers::types::Size::points(0.0, app_height)
+            };
+            let mut changes = vec![layer.change_opacity(0.0), layer.change_size(collapsed)];
             if let Some(entry) = apps_layers_map
                 .values()
                 .find(|entry| entry.layer.id() == layer.id())
             {
+                let anchor = match position {
+                    DockPosition::Bottom => Point::new(0.0, 1.0),
+                    DockPosition::Left => Point::new(0.0, 0.0),
+                    DockPosition::Right => Point::new(1.0, 0.0),
+                };
                 entry
                     .icon_scaler
-                    .set_anchor_point_preserving_position(Point::new(0.0, 1.0));
+                    .set_anchor_point_preserving_position(anchor);
                 changes.push(entry.icon_scaler.change_scale(Point::new(0.1, 0.1)));
             }
 
@@ -722,10 +922,18 @@ impl DockView {
 
         // Mini window layers
         for layer in previous_miniwindows {
-            layer.set_opacity(0.0, Transition::ease_out_quad(0.2));
+            layer.set_opacity(
+                0.0,
+                Config::transition_or_instant(Transition::ease_out_quad(0.2)),
+            );
+            let collapsed = if vertical {
+                layers::types::Size::points(miniwindow_height, 0.0)
+            } else {
+                layers::types::Size::points(0.0, miniwindow_height)
+            };
             layer.set_size(
-                layers::types::Size::points(0.0, miniwindow_height),
-                Transition::ease_out_quad(0.3),
+                collapsed,
+                Config::transition_or_instant(Transition::ease_out_quad(0.3)),
             );
 
             miniwindows_layers_map.retain(|_k, (v, ..)| v.id() != layer.id());
@@ -741,8 +949,15 @@ impl DockView {
     }
     pub fn available_icon_size(&self) -> (f32, f32) {
         let state = self.get_state();
-        let draw_scale = Config::with(|config| config.screen_scale) as f32 * 0.8;
-        let available_width = state.width as f32 - 20.0 * draw_scale;
+        let draw_scale = self.scale() * 0.8;
+        // A side dock runs along the screen height
+        let screen_h = self.screen_size.read().unwrap().1;
+        let dock_length = if self.position().is_vertical() && screen_h > 0 {
+            screen_h
+        } else {
+            state.width
+        };
+        let available_width = dock_length as f32 - 20.0 * draw_scale;
         let base_icon_size = 95.0;
         let dock_size_multiplier = self.dock_config.read().unwrap().size.clamp(0.5, 2.0) as f32;
         let icon_size: f32 = base_icon_size * dock_size_multiplier * draw_scale;
@@ -778,39 +993,53 @@ impl DockView {
         } else {
             Some(0.0_f64)
         };
-        self.magnify_elements_with_scale(scale_override, Some(Transition::spring(0.5, 0.1)));
+        self.magnify_elements_with_scale(
+            scale_override,
+            Some(Config::transition_or_instant(Transition::spring(0.5, 0.1))),
+        );
 
         // Recompute and cache the autohide hot zone from the new dock dimensions.
-        let screen_scale = Config::with(|c| c.screen_scale) as f32;
+        let screen_scale = self.scale();
         let bar_h = Self::calculate_bar_height(icon_size, 1.0) / screen_scale;
         let bar_h = bar_h / 2.0;
-        let (screen_w, screen_h) = *self.screen_size.read().unwrap();
-        // println!("screen x=0, w={}, h={} scale={}", screen_w, screen_h, screen_scale);
-
-        let screen_h = screen_h as f32 / screen_scale;
-        let screen_w = screen_w as f32 / screen_scale;
+        let screen = output_area(
+            *self.screen_origin.read().unwrap(),
+            *self.screen_size.read().unwrap(),
+            screen_scale,
+        );
+        let (screen_w, screen_h) = (screen.width(), screen.height());
+        let position = self.position();
         *self.cached_hot_zone.write().unwrap() = if screen_w > 0.0 && screen_h > 0.0 {
-            // Hot zone is a thin strip at the very bottom: 30% of the dock height.
+            // Hot zone is a thin strip along the dock edge: 30% of the dock depth.
             let hot_zone_h = (bar_h * 2.0) * 0.3;
-            Some(skia::Rect::from_xywh(
-                0.0,
-                screen_h - hot_zone_h,
-                screen_w,
-                hot_zone_h,
-            ))
+            Some(edge_strip(position, screen, hot_zone_h))
         } else {
             None
         };
         *self.cached_dock_bounds.write().unwrap() = if screen_w > 0.0 && screen_h > 0.0 {
-            // Span from dock top (screen_h - bar height) to screen bottom,
-            // outset 40 pts horizontally.
-            let dock_h = bar_h * 2.0;
-            Some(skia::Rect::from_xywh(
-                -40.0,
-                screen_h - dock_h,
-                screen_w + 80.0,
-                dock_h + 80.0,
-            ))
+            // Span the dock depth from the screen edge, outset 40 pts along
+            // the edge and 80 pts past it.
+            let dock = edge_strip(position, screen, bar_h * 2.0);
+            Some(match position {
+                DockPosition::Bottom => skia::Rect::from_ltrb(
+                    dock.left - 40.0,
+                    dock.top,
+                    dock.right + 40.0,
+                    dock.bottom + 80.0,
+                ),
+                DockPosition::Left => skia::Rect::from_ltrb(
+                    dock.left - 80.0,
+                    dock.top - 40.0,
+                    dock.right,
+                    dock.bottom + 40.0,
+                ),
+                DockPosition::Right => skia::Rect::from_ltrb(
+                    dock.left,
+                    dock.top - 40.0,
+                    dock.right + 80.0,
+                    dock.bottom + 40.0,
+                ),
+            })
         } else {
             None
         };
@@ -832,6 +1061,9 @@ impl DockView {
             loop {
                 // dock updates don't need to be instantanious
                 tokio::time::sleep(Duration::from_secs_f32(0.5)).await;
+                if dock.destroyed.load(std::sync::atomic::Ordering::Relaxed) {
+                    break;
+                }
 
                 let event = {
                     let mut latest_event_lock = latest_event.write().await;
@@ -851,7 +1083,11 @@ impl DockView {
                         }
                     }
 
-                    let minimized_windows = workspace.minimized_windows.clone();
+                    let minimized_windows = if dock.shows_minimized_windows {
+                        workspace.minimized_windows.clone()
+                    } else {
+                        Vec::new()
+                    };
 
                     let state = dock.get_state();
 
@@ -888,6 +1124,19 @@ impl DockView {
             .map(|(match_id, entry)| (entry.identifier.clone(), match_id.clone()))
     }
 
+    /// Whether the physical point `(x, y)` is over the dock, its window
+    /// previews or an open stack.
+    pub fn contains_point(&self, x: f32, y: f32) -> bool {
+        let point = skia::Point::new(x, y);
+        self.view_layer.render_bounds_transformed().contains(point)
+            || self
+                .previews_bounds()
+                .is_some_and(|bounds| bounds.contains(point))
+            || self
+                .stack_bounds()
+                .is_some_and(|bounds| bounds.contains(point))
+    }
+
     pub fn is_handle_layer(&self, layer: &NodeRef) -> bool {
         self.resize_handle.id() == *layer
     }
@@ -1040,7 +1289,7 @@ impl DockView {
     /// Returns the resting icon size used for miniwindow drawers when
     /// magnification is at rest (same formula as `magnify_elements_with_scale`).
     pub fn miniwindow_icon_size(&self) -> f32 {
-        let draw_scale = Config::with(|config| config.screen_scale) as f32 * 0.8;
+        let draw_scale = self.scale() * 0.8;
         let dock_size_multiplier = self.dock_config.read().unwrap().size.clamp(0.5, 2.0) as f32;
         let base_icon_size = 80.0;
         base_icon_size * dock_size_multiplier * draw_scale
@@ -1054,7 +1303,8 @@ impl DockView {
 
     // Magnify elements
     fn magnify_elements(&self) {
-        self.magnify_elements_with_scale(None, Some(Transition::spring(0.005, 0.0)));
+        let transition = Config::transition_or_instant(Transition::spring(0.005, 0.0));
+        self.magnify_elements_with_scale(None, Some(transition));
     }
 
     fn magnify_elements_with_scale(
@@ -1068,13 +1318,20 @@ impl DockView {
         if scale_override.is_none() && !magnification_enabled {
             return;
         }
+        let position = self.position();
+        let vertical = position.is_vertical();
+        // Magnification runs along the dock: x on the bottom, y on the sides.
+        // `start`/`end`/`length` read a rect along that axis.
+        let start = |r: &skia::Rect| if vertical { r.top } else { r.left };
+        let end = |r: &skia::Rect| if vertical { r.bottom } else { r.right };
+        let length = |r: &skia::Rect| if vertical { r.height() } else { r.width() };
         let pos = *self.magnification_position.read().unwrap();
         let bounds = self.view_layer.render_bounds_transformed();
-        let pos = pos - bounds.x();
+        let pos = pos - start(&bounds);
         let state = self.get_state();
         let display_apps = self.display_entries(&state);
 
-        let draw_scale = Config::with(|config| config.screen_scale) as f32 * 0.8;
+        let draw_scale = self.scale() * 0.8;
         let dock_size_multiplier = self.dock_config.read().unwrap().size.clamp(0.5, 2.0) as f32;
         let base_icon_size = 80.0;
         let icon_size: f32 = base_icon_size * dock_size_multiplier * draw_scale;
@@ -1084,11 +1341,11 @@ impl DockView {
         // We need to skip the handle gap so that icon_pos maps to actual cursor positions.
         let apps_bounds = self.dock_apps_container.render_bounds_transformed();
         let windows_bounds = self.dock_windows_container.render_bounds_transformed();
-        let elements_start = apps_bounds.x() - bounds.x();
-        let handle_gap = (windows_bounds.x() - apps_bounds.right()).max(0.0);
-        let elements_width = (apps_bounds.width() + windows_bounds.width()).max(1.0);
+        let elements_start = start(&apps_bounds) - start(&bounds);
+        let handle_gap = (start(&windows_bounds) - end(&apps_bounds)).max(0.0);
+        let elements_width = (length(&apps_bounds) + length(&windows_bounds)).max(1.0);
         // Subtract the handle gap for any cursor position that is past the apps container.
-        let pos_in_elements = if pos - elements_start > apps_bounds.width() {
+        let pos_in_elements = if pos - elements_start > length(&apps_bounds) {
             pos - elements_start - handle_gap
         } else {
             pos - elements_start
@@ -1107,13 +1364,14 @@ impl DockView {
             scale_override.unwrap_or_else(|| self.dock_config.read().unwrap().genie_scale);
         let genie_span = self.dock_config.read().unwrap().genie_span;
         {
-            let draw_scale = Config::with(|config| config.screen_scale) as f32 * 0.8;
+            let draw_scale = self.scale() * 0.8;
             let dot_area_height = 3.0 * draw_scale;
             let container_height = icon_size + dot_area_height;
-            let change = self.dock_apps_container.change_size(Size {
-                width: taffy::Dimension::Auto,
-                height: taffy::Dimension::Length(container_height),
-            });
+            let change = self.dock_apps_container.change_size(cross_size(
+                vertical,
+                taffy::Dimension::Auto,
+                taffy::Dimension::Length(container_height),
+            ));
             changes.push(change);
             let position_change = self
                 .dock_apps_container
@@ -1129,11 +1387,16 @@ impl DockView {
                         1.0 + magnify_function(focus - icon_pos, genie_span) * genie_scale;
                     let focused_icon_size = icon_size * icon_focus as f32;
 
-                    // Width = focused icon size (for magnification);
-                    // Height = icon + dot area so the running indicator dot
-                    // sits below the icon inside the container.
+                    // Along the dock = focused icon size (for magnification);
+                    // across = icon + dot area so the running indicator dot
+                    // sits between the icon and the screen edge.
                     let app_height = focused_icon_size + dot_area_height;
-                    let change = layer.change_size(Size::points(focused_icon_size, app_height));
+                    let size = if vertical {
+                        Size::points(app_height, focused_icon_size)
+                    } else {
+                        Size::points(focused_icon_size, app_height)
+                    };
+                    let change = layer.change_size(size);
                     changes.push(change);
 
                     let change = entry
@@ -1144,8 +1407,14 @@ impl DockView {
                     // to focused_icon_size. badge and progress scale with it as children.
                     let scaler = (focused_icon_size * ICON_SCALER_FILL) / BASE_ICON_SIZE;
 
+                    // On a left dock the dot area comes first, so the icon shifts past it
+                    let icon_offset = if position == DockPosition::Left {
+                        dot_area_height
+                    } else {
+                        0.0
+                    };
                     let scaler_change_position = entry.icon_scaler.change_position(Point {
-                        x: focused_icon_size / 2.0,
+                        x: icon_offset + focused_icon_size / 2.0,
                         y: focused_icon_size / 2.0,
                     });
                     changes.push(scaler_change_position);
@@ -1187,14 +1456,52 @@ impl DockView {
         }
     }
     /// Update the physical screen dimensions so `render_dock` can compute a correct hot zone.
-    pub fn set_screen_size(&self, w: i32, h: i32) {
+    pub fn set_screen_size(&self, w: i32, h: i32, scale: f64) {
         *self.screen_size.write().unwrap() = (w, h);
+        *self.scale.write().unwrap() = scale as f32;
+    }
+
+    /// Scale of the dock's output
+    pub(super) fn scale(&self) -> f32 {
+        *self.scale.read().unwrap()
+    }
+
+    /// Update the logical position of the dock's output, so the hot zone and
+    /// the dock bounds follow the output in the global space.
+    pub fn set_screen_origin(&self, x: i32, y: i32) {
+        *self.screen_origin.write().unwrap() = (x, y);
+        self.render_dock();
+    }
+
+    /// Detach the dock from the scene and stop its background updates.
+    pub fn destroy(&self) {
+        self.destroyed
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        self.close_context_menu();
+        self.close_stack();
+        self.hide_previews();
+        self.wrap_layer.remove();
+    }
+
+    /// Copy the launchers of `source`, a dock sharing this dock's config,
+    /// after they were changed from its context menu.
+    pub fn sync_launchers_from(&self, source: &DockView) {
+        if self == source {
+            return;
+        }
+        self.update_state(&DockModel {
+            launchers: source.get_state().launchers,
+            ..self.get_state()
+        });
     }
 
     /// Start bouncing the icon for `match_id` to signal that a launch is in progress.
     /// The icon keeps hopping until [`Self::stop_bounce`] is called (a window appeared)
     /// or a safety cap is reached. No-op if the app is already running or already bouncing.
     pub fn start_bounce(&self, match_id: &str) {
+        if Config::reduce_motion() {
+            return;
+        }
         // Capacity guard: only bounce launchers that aren't already running, and
         // grab the container layer to animate.
         let layer = {
@@ -1234,9 +1541,10 @@ impl DockView {
             // Settle immediately; this also cancels any in-flight hop animation
             // because it targets the same position value.
             if let Some(entry) = self.app_layers.read().unwrap().get(match_id) {
-                entry
-                    .layer
-                    .set_position(Point::new(0.0, 0.0), Some(Transition::spring(0.3, 0.2)));
+                entry.layer.set_position(
+                    Point::new(0.0, 0.0),
+                    Some(Config::transition_or_instant(Transition::spring(0.3, 0.2))),
+                );
             }
         }
     }
@@ -1252,7 +1560,10 @@ impl DockView {
         match_id: String,
     ) {
         if remaining == 0 || !flag.load(std::sync::atomic::Ordering::Relaxed) {
-            layer.set_position(Point::new(0.0, 0.0), Some(Transition::spring(0.3, 0.2)));
+            layer.set_position(
+                Point::new(0.0, 0.0),
+                Some(Config::transition_or_instant(Transition::spring(0.3, 0.2))),
+            );
             bouncing.write().unwrap().remove(&match_id);
             return;
         }
@@ -1279,7 +1590,7 @@ impl DockView {
     /// Keyframe timing for a single launch-bounce hop. `progress` is the fraction of the
     /// target offset applied: a tall hop, a short rebound, then a brief pause at rest.
     fn bounce_transition() -> Transition {
-        Transition {
+        Config::transition_or_instant(Transition {
             delay: 0.0,
             timing: TimingFunction::keyframes(vec![
                 KeyframeSegment {
@@ -1313,18 +1624,26 @@ impl DockView {
                     end_progress: 0.0,
                 },
             ]),
-        }
+        })
     }
 
     pub(super) fn magnify_elements_animated(&self) {
-        self.magnify_elements_with_scale(None, Some(Transition::spring(0.2, 0.1)));
+        self.magnify_elements_with_scale(
+            None,
+            Some(Config::transition_or_instant(Transition::spring(0.2, 0.1))),
+        );
     }
 
     pub(super) fn demagnify_elements(&self) {
         *self.magnification_position.write().unwrap() = -500.0;
-        self.magnify_elements_with_scale(Some(0.0), Some(Transition::spring(0.2, 0.1)));
+        self.magnify_elements_with_scale(
+            Some(0.0),
+            Some(Config::transition_or_instant(Transition::spring(0.2, 0.1))),
+        );
     }
 
+    /// Magnify the icons around `pos`, the pointer coordinate along the dock
+    /// in physical pixels.
     pub fn update_magnification_position(&self, pos: f32) {
         *self.magnification_position.write().unwrap() = pos;
         if self.has_menu_open() {
@@ -1367,6 +1686,11 @@ impl DockView {
         self.app_icons_manager.update_badge(app_id, text);
     }
 
+    /// Whether `app_id` is bookmarked or running in the dock.
+    pub fn has_app(&self, app_id: &str) -> bool {
+        self.state.read().unwrap().has_app(app_id)
+    }
+
     /// Update the progress bar shown on the dock icon for `app_id`.
     /// Pass `None` or a negative value to hide the progress bar.
     pub fn update_progress_for_app(&self, app_id: &str, value: Option<f64>) {
@@ -1375,13 +1699,7 @@ impl DockView {
 
     /// Open the dock-settings context menu anchored to the handle.
     pub fn open_handle_context_menu(&self) {
-        let scale = Config::with(|c| c.screen_scale) as f32;
-        let handle_bounds = self.resize_handle.render_bounds_transformed();
-        let wrap_bounds = self.wrap_layer.render_bounds_transformed();
-        let pos = Point::new(
-            (handle_bounds.x() + handle_bounds.width() / 2.0 - wrap_bounds.x()) / scale,
-            (handle_bounds.y() - wrap_bounds.y()) / scale,
-        );
+        let pos = self.popup_anchor_beside(self.resize_handle.render_bounds_transformed(), 0.0);
 
         let autohide = self.dock_config.read().unwrap().autohide;
         let magnification = self.dock_config.read().unwrap().magnification;
@@ -1404,12 +1722,13 @@ impl DockView {
         let mut context_menu_lock = self.context_menu.write().unwrap();
         if context_menu_lock.is_none() {
             let menu = ContextMenuView::new(&self.wrap_layer, items.clone());
-            let s = Config::with(|c| c.screen_scale) as f32;
-            menu.set_style(ContextMenuStyle::default_with_scale(s));
+            menu.set_style(crate::theme::context_menu_style(self.scale()));
             *context_menu_lock = Some(menu);
         }
         if let Some(menu) = context_menu_lock.as_ref() {
             menu.set_items(items);
+            let (anchor_x, anchor_y) = self.popup_anchor_point();
+            menu.set_anchor_point(anchor_x, anchor_y);
             menu.show_at(pos.x, pos.y);
         }
         drop(context_menu_lock);
@@ -1440,6 +1759,9 @@ impl DockView {
     /// Build context-menu items for the given app `identifier`,
     /// reflecting its current running and bookmarked state.
     pub fn build_context_menu_items(&self, identifier: &str) -> Vec<MenuItem> {
+        if let Some(stack) = self.stack_config_for(identifier) {
+            return Self::build_stack_menu_items(&stack);
+        }
         let running = self.is_app_running(identifier);
         let match_id = self.match_id_for(identifier);
         let bookmarked = match_id
@@ -1480,26 +1802,33 @@ impl DockView {
         items
     }
 
-    pub fn open_context_menu(&self, _pos: Point, app_id: String) {
-        // Compute position from the app icon layer to anchor the menu above it
-        let scale = Config::with(|c| c.screen_scale) as f32;
-        let menu_pos = {
-            let app_layers = self.app_layers.read().unwrap();
-            let entry = app_layers.values().find(|e| e.identifier == app_id);
-            if let Some(e) = entry {
-                let icon_bounds = e.layer.render_bounds_transformed();
-                let wrap_bounds = self.wrap_layer.render_bounds_transformed();
-                // Center the menu horizontally over the icon;
-                // anchor point (0.5, 1.0) means the bottom-center lands at (x, y),
-                // so y = top-edge of the icon relative to the wrap_layer (in logical px).
-                Point::new(
-                    (icon_bounds.x() + icon_bounds.width() / 2.0 - wrap_bounds.x()) / scale,
-                    (icon_bounds.y() - wrap_bounds.y()) / scale - 10.0 * scale, // nudge up above the icon
-                )
+    /// Context-menu items of a stack: open it, switch its display mode and,
+    /// for folders, open the folder itself.
+    fn build_stack_menu_items(stack: &DockStack) -> Vec<MenuItem> {
+        let check = |label: &str, display: StackDisplay| {
+            if stack.display == display {
+                format!("✓ {label}")
             } else {
-                _pos
+                label.to_string()
             }
         };
+        let mut items = vec![
+            MenuItem::action("Open").with_action_id("open"),
+            MenuItem::separator(),
+            MenuItem::action(check("Grid", StackDisplay::Grid)).with_action_id("stack_grid"),
+            MenuItem::action(check("Fan", StackDisplay::Fan)).with_action_id("stack_fan"),
+        ];
+        if stack.folder.is_some() {
+            items.push(MenuItem::separator());
+            items.push(MenuItem::action("Open Folder").with_action_id("stack_open_folder"));
+        }
+        items
+    }
+
+    pub fn open_context_menu(&self, _pos: Point, app_id: String) {
+        // Anchor the menu on the inner side of the app icon, nudged off it
+        let scale = self.scale();
+        let menu_pos = self.popup_anchor_for(&app_id, 10.0 * scale).unwrap_or(_pos);
 
         // Hide any visible tooltip before showing the context menu.
         self.set_active_label(None);
@@ -1513,8 +1842,7 @@ impl DockView {
         } else {
             let items = self.build_context_menu_items(&app_id);
             let menu = ContextMenuView::new(&self.wrap_layer, items);
-            let scale = Config::with(|c| c.screen_scale) as f32;
-            menu.set_style(ContextMenuStyle::default_with_scale(scale));
+            menu.set_style(crate::theme::context_menu_style(self.scale()));
             *context_menu_lock = Some(menu);
         }
 
@@ -1522,6 +1850,8 @@ impl DockView {
             // Refresh items in case the menu was reused (app state may have changed)
             let items = self.build_context_menu_items(&app_id);
             menu.set_items(items);
+            let (anchor_x, anchor_y) = self.popup_anchor_point();
+            menu.set_anchor_point(anchor_x, anchor_y);
             menu.show_at(menu_pos.x, menu_pos.y);
         }
         drop(context_menu_lock);
@@ -1549,11 +1879,12 @@ impl DockView {
         }
     }
 
+    /// Whether a context menu or a stack is open over the dock.
     pub fn has_menu_open(&self) -> bool {
         if let Some(menu) = self.context_menu.read().unwrap().as_ref() {
-            menu.is_active()
+            menu.is_active() || self.has_stack_open()
         } else {
-            false
+            self.has_stack_open()
         }
     }
 
@@ -1567,6 +1898,50 @@ impl DockView {
         }
     }
 
+    /// Replace the in-memory dock config after a config file reload, without
+    /// writing it back, and re-render with the new size, colors and position.
+    pub fn apply_config(&self, config: crate::config::DockConfig) {
+        let was_autohide = self.is_autohide_enabled();
+        *self.dock_config.write().unwrap() = config;
+        self.config_changed(was_autohide);
+    }
+
+    /// Re-apply the theme colors to the bar and re-render the icons
+    pub fn apply_theme(&self) {
+        let colors = theme_colors();
+        self.bar_layer
+            .set_background_color(colors.materials_medium, None);
+        self.bar_layer
+            .set_border_color(colors.materials_highlight, None);
+        self.bar_layer.set_shadow_color(colors.shadow_color, None);
+        crate::theme::apply_material_blur(&self.bar_layer);
+        // An open menu takes the new colors and accent right away
+        if let Some(menu) = self.context_menu.read().unwrap().as_ref() {
+            menu.set_style(crate::theme::context_menu_style(self.scale()));
+        }
+        self.render_dock();
+    }
+
+    /// Re-render after the config shared with a sibling dock was replaced
+    /// through that dock's `apply_config`.
+    pub fn config_changed(&self, was_autohide: bool) {
+        let (autohide, magnification) = {
+            let config = self.dock_config.read().unwrap();
+            (config.autohide, config.magnification)
+        };
+        self.set_magnification_enabled(magnification);
+        self.render_dock();
+        // Keep a hidden dock off the edge it was moved to
+        if self.is_hidden() {
+            self.view_layer.set_position(self.hidden_offset(), None);
+        }
+        if autohide && !was_autohide {
+            self.schedule_autohide();
+        } else if !autohide && was_autohide {
+            self.show(None);
+        }
+    }
+
     /// Persist the current in-memory dock config to the writable config file.
     pub(super) fn save_config(&self) {
         crate::config::save_dock_config(&self.dock_config.read().unwrap());
@@ -1589,11 +1964,11 @@ impl DockView {
             .store(false, std::sync::atomic::Ordering::Relaxed);
         self.view_layer
             .set_position(
-                (0.0, 250.0),
-                Some(Transition {
+                self.hidden_offset(),
+                Some(Config::transition_or_instant(Transition {
                     timing: TimingFunction::Spring(Spring::with_duration_and_bounce(0.5, 0.0)),
                     delay: 0.4,
-                }),
+                })),
             )
             .on_finish(
                 |l: &Layer, _| {
@@ -1621,10 +1996,10 @@ impl DockView {
             .store(true, std::sync::atomic::Ordering::Relaxed);
         Some(self.view_layer.set_position(
             (0.0, 0.0),
-            Some(Transition {
+            Some(Config::transition_or_instant(Transition {
                 timing: TimingFunction::Spring(Spring::with_duration_and_bounce(0.5, 0.2)),
                 delay: 0.0,
-            }),
+            })),
         ))
     }
 
@@ -1662,3 +2037,162 @@ pub fn magnify_function(x: impl Into<f64>, genie_span: f64) -> f64 {
     let x = x.into();
     E.powf(-genie_span * x.powi(2))
 }
+
+/// Size with `main` along the dock and `cross` across it.
+fn cross_size(vertical: bool, main: taffy::Dimension, cross: taffy::Dimension) -> Size {
+    if vertical {
+        Size {
+            width: cross,
+            height: main,
+        }
+    } else {
+        Size {
+            width: main,
+            height: cross,
+        }
+    }
+}
+
+/// Flex style of the apps and minimized windows containers: items flow
+/// along the dock and line up on the side facing the screen edge.
+fn elements_container_style(position: DockPosition, draw_scale: f32) -> taffy::Style {
+    let (flex_direction, align_items, min_size) = match position {
+        DockPosition::Bottom => (
+            taffy::FlexDirection::Row,
+            taffy::AlignItems::FlexEnd,
+            taffy::Size {
+                width: taffy::Dimension::Length(20.0 * draw_scale),
+                height: taffy::Dimension::Length(0.0),
+            },
+        ),
+        DockPosition::Left | DockPosition::Right => (
+            taffy::FlexDirection::Column,
+            if position == DockPosition::Left {
+                taffy::AlignItems::FlexStart
+            } else {
+                taffy::AlignItems::FlexEnd
+            },
+            taffy::Size {
+                width: taffy::Dimension::Length(0.0),
+                height: taffy::Dimension::Length(20.0 * draw_scale),
+            },
+        ),
+    };
+    taffy::Style {
+        display: taffy::Display::Flex,
+        flex_direction,
+        justify_content: Some(taffy::JustifyContent::FlexEnd),
+        justify_items: Some(taffy::JustifyItems::FlexEnd),
+        align_items: Some(align_items),
+        gap: taffy::Size::<taffy::LengthPercentage>::from_length(0.0),
+        min_size,
+        ..Default::default()
+    }
+}
+
+/// Style and size of the running dot: a strip `dot_height` deep along the
+/// side of the app item that faces the screen edge.
+fn dot_layout(position: DockPosition, dot_height: f32) -> (taffy::Style, Size) {
+    let auto = taffy::LengthPercentageAuto::Auto;
+    let zero = taffy::length(0.0);
+    let inset = match position {
+        DockPosition::Bottom => taffy::Rect {
+            left: zero,
+            right: zero,
+            top: auto,
+            bottom: zero,
+        },
+        DockPosition::Left => taffy::Rect {
+            left: zero,
+            right: auto,
+            top: zero,
+            bottom: zero,
+        },
+        DockPosition::Right => taffy::Rect {
+            left: auto,
+            right: zero,
+            top: zero,
+            bottom: zero,
+        },
+    };
+    let style = taffy::Style {
+        position: taffy::Position::Absolute,
+        inset,
+        ..Default::default()
+    };
+    let size = cross_size(
+        position.is_vertical(),
+        taffy::Dimension::Percent(1.0),
+        taffy::Dimension::Length(dot_height),
+    );
+    (style, size)
+}
+
+/// Offset of a dock on the `position` edge, `fraction` of the way off it.
+fn edge_offset(position: DockPosition, fraction: f32) -> (f32, f32) {
+    let distance = 250.0 * fraction.clamp(0.0, 1.0);
+    match position {
+        DockPosition::Bottom => (0.0, distance),
+        DockPosition::Left => (-distance, 0.0),
+        DockPosition::Right => (distance, 0.0),
+    }
+}
+
+/// Logical area of an output at `origin` with a mode of `size` physical
+/// pixels shown at `scale`.
+fn output_area(origin: (i32, i32), size: (i32, i32), scale: f32) -> skia::Rect {
+    skia::Rect::from_xywh(
+        origin.0 as f32,
+        origin.1 as f32,
+        size.0 as f32 / scale,
+        size.1 as f32 / scale,
+    )
+}
+
+/// The strip `depth` deep along the `position` edge of `area`.
+fn edge_strip(position: DockPosition, area: skia::Rect, depth: f32) -> skia::Rect {
+    match position {
+        DockPosition::Bottom => {
+            skia::Rect::from_ltrb(area.left, area.bottom - depth, area.right, area.bottom)
+        }
+        DockPosition::Left => {
+            skia::Rect::from_ltrb(area.left, area.top, area.left + depth, area.bottom)
+        }
+        DockPosition::Right => {
+            skia::Rect::from_ltrb(area.right - depth, area.top, area.right, area.bottom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dock_edges_follow_each_output() {
+        // A 2x output to the right of a 1920x1080 one
+        let area = output_area((1920, 0), (2560, 1440), 2.0);
+        assert_eq!(area, skia::Rect::from_xywh(1920.0, 0.0, 1280.0, 720.0));
+
+        assert_eq!(
+            edge_strip(DockPosition::Bottom, area, 20.0),
+            skia::Rect::from_ltrb(1920.0, 700.0, 3200.0, 720.0)
+        );
+        assert_eq!(
+            edge_strip(DockPosition::Left, area, 20.0),
+            skia::Rect::from_ltrb(1920.0, 0.0, 1940.0, 720.0)
+        );
+        assert_eq!(
+            edge_strip(DockPosition::Right, area, 20.0),
+            skia::Rect::from_ltrb(3180.0, 0.0, 3200.0, 720.0)
+        );
+    }
+
+    #[test]
+    fn docks_slide_off_their_own_edge() {
+        assert_eq!(edge_offset(DockPosition::Bottom, 0.0), (0.0, 0.0));
+        assert_eq!(edge_offset(DockPosition::Bottom, 1.0), (0.0, 250.0));
+        assert_eq!(edge_offset(DockPosition::Left, 0.5), (-125.0, 0.0));
+        assert_eq!(edge_offset(DockPosition::Right, 2.0), (250.0, 0.0));
+    }
+}
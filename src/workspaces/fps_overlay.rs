@@ -0,0 +1,217 @@
+use layers::{engine::Engine, prelude::*, skia, types::Size};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{config::Config, workspaces::utils::FONT_CACHE};
+
+/// Overlay size, in points
+const OVERLAY_WIDTH: f32 = 180.0;
+const OVERLAY_HEIGHT: f32 = 72.0;
+/// Distance from the top-left corner of the output, in points
+const OVERLAY_MARGIN: f32 = 12.0;
+/// Frames shown in the graph
+const GRAPH_FRAMES: usize = 120;
+/// Frame time at the top of the graph, in ms (two frames at 60Hz)
+const GRAPH_MAX_MS: f32 = 33.3;
+/// Frame time of a 60Hz frame, drawn as a guide line
+const FRAME_BUDGET_MS: f32 = 16.7;
+
+/// Values shown by one output's overlay
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FpsOverlayStats {
+    pub fps: u32,
+    /// Render time of the last frames, oldest first, rounded to 0.1ms
+    pub frame_times_ms: Vec<f32>,
+}
+
+impl FpsOverlayStats {
+    /// Keep the last `GRAPH_FRAMES` frame times, rounded so sub-0.1ms jitter
+    /// does not count as a change
+    pub fn new(fps: u32, frame_times_ms: &[f64]) -> Self {
+        let start = frame_times_ms.len().saturating_sub(GRAPH_FRAMES);
+        Self {
+            fps,
+            frame_times_ms: frame_times_ms[start..]
+                .iter()
+                .map(|ms| (*ms as f32 * 10.0).round() / 10.0)
+                .collect(),
+        }
+    }
+}
+
+/// Output the overlay is attached to
+pub struct FpsOverlayOutput {
+    pub name: String,
+    pub scale: f64,
+    /// The output's container layer; the overlay is attached on top of it.
+    pub output_layer: Layer,
+}
+
+struct OutputFpsOverlay {
+    layer: Layer,
+    stats: Option<FpsOverlayStats>,
+}
+
+/// Debug overlay with the FPS and a frame time graph in the top-left corner
+/// of each output.
+///
+/// Each output gets a small non-interactive layer attached last to its
+/// output layer, so it draws above windows and every other overlay; the
+/// cursor is composited on top of the scene. The layer is only redrawn
+/// when its values change.
+pub struct FpsOverlayView {
+    layers_engine: Arc<Engine>,
+    overlays: HashMap<String, OutputFpsOverlay>,
+}
+
+impl FpsOverlayView {
+    pub fn new(layers_engine: Arc<Engine>) -> Self {
+        Self {
+            layers_engine,
+            overlays: HashMap::new(),
+        }
+    }
+
+    /// Attach a fresh overlay on top of every given output.
+    pub fn show(&mut self, outputs: Vec<FpsOverlayOutput>) {
+        self.hide();
+        for output in outputs {
+            let scale = output.scale as f32;
+            let layer = self.layers_engine.new_layer();
+            layer.set_key(format!("fps_overlay_{}", output.name));
+            layer.set_layout_style(taffy::style::Style {
+                position: taffy::style::Position::Absolute,
+                ..Default::default()
+            });
+            layer.set_position((OVERLAY_MARGIN * scale, OVERLAY_MARGIN * scale), None);
+            layer.set_size(
+                Size::points(OVERLAY_WIDTH * scale, OVERLAY_HEIGHT * scale),
+                None,
+            );
+            layer.set_pointer_events(false);
+            let _ = output.output_layer.add_sublayer(&layer);
+            self.overlays
+                .insert(output.name, OutputFpsOverlay { layer, stats: None });
+        }
+    }
+
+    /// Show `stats` on the overlay of `output`; nothing is redrawn when they
+    /// did not change
+    pub fn update(&mut self, output: &str, stats: FpsOverlayStats) {
+        let Some(overlay) = self.overlays.get_mut(output) else {
+            return;
+        };
+        if overlay.stats.as_ref() == Some(&stats) {
+            return;
+        }
+        overlay.stats = Some(stats.clone());
+        overlay
+            .layer
+            .set_draw_content(move |canvas: &skia::Canvas, w: f32, h: f32| {
+                draw_overlay(canvas, w, h, &stats);
+                skia::Rect::from_xywh(0.0, 0.0, w, h)
+            });
+    }
+
+    /// Names of the outputs showing the overlay
+    pub fn outputs(&self) -> impl Iterator<Item = &String> {
+        self.overlays.keys()
+    }
+
+    /// Detach all overlays from the scene.
+    pub fn hide(&mut self) {
+        for (_, overlay) in self.overlays.drain() {
+            overlay.layer.remove();
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        !self.overlays.is_empty()
+    }
+}
+
+fn draw_overlay(canvas: &skia::Canvas, w: f32, h: f32, stats: &FpsOverlayStats) {
+    let mut background = skia::Paint::new(skia::Color4f::new(0.0, 0.0, 0.0, 0.6), None);
+    background.set_anti_alias(true);
+    let radius = h * 0.12;
+    canvas.draw_round_rect(skia::Rect::from_wh(w, h), radius, radius, &background);
+
+    let padding = h * 0.1;
+    let text_size = h * 0.22;
+    let font_family = Config::with(|c| c.font_family.clone());
+    let font = FONT_CACHE.with(|font_cache| {
+        font_cache.make_font_with_fallback(font_family, skia::FontStyle::bold(), text_size)
+    });
+    let mut text_paint = skia::Paint::new(skia::Color4f::new(1.0, 1.0, 1.0, 0.95), None);
+    text_paint.set_anti_alias(true);
+    let last = stats.frame_times_ms.last().copied().unwrap_or(0.0);
+    canvas.draw_str(
+        format!("{} FPS  {last:.1} ms", stats.fps),
+        (padding, padding + text_size),
+        &font,
+        &text_paint,
+    );
+
+    let graph = skia::Rect::from_xywh(
+        padding,
+        padding * 2.0 + text_size,
+        w - padding * 2.0,
+        h - padding * 3.0 - text_size,
+    );
+    let mut guide = skia::Paint::new(skia::Color4f::new(1.0, 1.0, 1.0, 0.3), None);
+    guide.set_stroke_width(1.0);
+    let budget_y = graph_y(FRAME_BUDGET_MS, graph);
+    canvas.draw_line((graph.left, budget_y), (graph.right, budget_y), &guide);
+
+    let mut bar = skia::Paint::new(skia::Color4f::new(0.3, 0.85, 0.4, 0.9), None);
+    let mut slow_bar = skia::Paint::new(skia::Color4f::new(0.95, 0.3, 0.25, 0.9), None);
+    bar.set_anti_alias(true);
+    slow_bar.set_anti_alias(true);
+    let bar_width = graph.width() / GRAPH_FRAMES as f32;
+    // Newest frame on the right
+    let offset = GRAPH_FRAMES.saturating_sub(stats.frame_times_ms.len());
+    for (i, ms) in stats.frame_times_ms.iter().enumerate() {
+        let x = graph.left + (offset + i) as f32 * bar_width;
+        let paint = if *ms > FRAME_BUDGET_MS {
+            &slow_bar
+        } else {
+            &bar
+        };
+        canvas.draw_rect(
+            skia::Rect::new(x, graph_y(*ms, graph), x + bar_width.max(1.0), graph.bottom),
+            paint,
+        );
+    }
+}
+
+/// Height in `graph` of a frame taking `ms`, clamped to the graph
+fn graph_y(ms: f32, graph: skia::Rect) -> f32 {
+    let ratio = (ms / GRAPH_MAX_MS).clamp(0.0, 1.0);
+    graph.bottom - ratio * graph.height()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_keep_the_last_frames_rounded() {
+        let times: Vec<f64> = (0..200).map(|i| i as f64 + 0.04).collect();
+        let stats = FpsOverlayStats::new(60, &times);
+        assert_eq!(stats.frame_times_ms.len(), GRAPH_FRAMES);
+        assert_eq!(stats.frame_times_ms.first(), Some(&80.0));
+        assert_eq!(stats.frame_times_ms.last(), Some(&199.0));
+        assert_eq!(
+            FpsOverlayStats::new(60, &[2.01, 2.02]),
+            FpsOverlayStats::new(60, &[2.02, 2.03])
+        );
+    }
+
+    #[test]
+    fn graph_is_clamped() {
+        let graph = skia::Rect::from_xywh(0.0, 10.0, 100.0, 50.0);
+        assert_eq!(graph_y(0.0, graph), 60.0);
+        assert_eq!(graph_y(GRAPH_MAX_MS, graph), 10.0);
+        assert_eq!(graph_y(100.0, graph), 10.0);
+    }
+}
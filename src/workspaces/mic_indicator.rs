@@ -0,0 +1,120 @@
+use layers::{engine::Engine, prelude::*, skia, types::Size};
+use std::sync::Arc;
+
+use crate::utils::resource_image;
+
+/// Indicator diameter, in points
+const INDICATOR_SIZE: f32 = 28.0;
+/// Distance from the top-right corner of the output, in points
+const INDICATOR_MARGIN: f32 = 12.0;
+
+/// Small always-on-top badge shown in the top-right corner while the
+/// microphone is muted.
+///
+/// A red disc with a crossed-out microphone icon inside a full-screen,
+/// non-interactive `wrap_layer` attached to the overlay tree.
+pub struct MicIndicatorView {
+    pub wrap_layer: Layer,
+    pub indicator_layer: Layer,
+}
+
+impl MicIndicatorView {
+    pub fn new(layers_engine: Arc<Engine>) -> Self {
+        let wrap = layers_engine.new_layer();
+        wrap.set_key("mic_indicator_container");
+        wrap.set_size(Size::percent(1.0, 1.0), None);
+        wrap.set_pointer_events(false);
+        wrap.set_hidden(true);
+
+        let indicator = layers_engine.new_layer();
+        indicator.set_key("mic_indicator");
+        indicator.set_pointer_events(false);
+        indicator.set_opacity(0.0, None);
+
+        let icon = resource_image("mic-mute.svg", "microphone-sensitivity-muted-symbolic");
+        indicator.set_draw_content(move |canvas: &skia::Canvas, w: f32, h: f32| {
+            let mut paint = skia::Paint::new(skia::Color4f::new(0.92, 0.23, 0.2, 0.95), None);
+            paint.set_anti_alias(true);
+            canvas.draw_circle((w / 2.0, h / 2.0), w.min(h) / 2.0, &paint);
+
+            if let Some(icon) = &icon {
+                let icon_size = w.min(h) * 0.6;
+                let mut icon_paint = skia::Paint::default();
+                icon_paint.set_color_filter(skia::color_filters::blend(
+                    skia::Color::WHITE,
+                    skia::BlendMode::SrcIn,
+                ));
+                icon_paint.set_anti_alias(true);
+                canvas.draw_image_rect_with_sampling_options(
+                    icon,
+                    None,
+                    skia::Rect::from_xywh(
+                        (w - icon_size) / 2.0,
+                        (h - icon_size) / 2.0,
+                        icon_size,
+                        icon_size,
+                    ),
+                    skia::CubicResampler::catmull_rom(),
+                    &icon_paint,
+                );
+            }
+            skia::Rect::from_xywh(0.0, 0.0, w, h)
+        });
+        let _ = wrap.add_sublayer(&indicator);
+
+        let view = Self {
+            wrap_layer: wrap,
+            indicator_layer: indicator,
+        };
+        view.set_scale(crate::config::Config::with(|c| c.screen_scale) as f32);
+        view
+    }
+
+    /// Size the badge for the output it is shown on
+    pub fn set_scale(&self, scale: f32) {
+        self.wrap_layer.set_layout_style(taffy::style::Style {
+            position: taffy::style::Position::Absolute,
+            display: taffy::style::Display::Flex,
+            justify_content: Some(taffy::JustifyContent::FlexEnd),
+            align_items: Some(taffy::AlignItems::FlexStart),
+            padding: taffy::Rect {
+                top: taffy::length(INDICATOR_MARGIN * scale),
+                bottom: taffy::length(0.0),
+                left: taffy::length(0.0),
+                right: taffy::length(INDICATOR_MARGIN * scale),
+            },
+            ..Default::default()
+        });
+        self.indicator_layer.set_size(
+            Size::points(INDICATOR_SIZE * scale, INDICATOR_SIZE * scale),
+            None,
+        );
+    }
+
+    /// Fade the indicator in or out
+    pub fn set_visible(&self, visible: bool) {
+        if visible {
+            self.wrap_layer.set_hidden(false);
+            self.indicator_layer
+                .set_opacity(1.0, Some(Transition::ease_out_quad(0.2)));
+        } else {
+            let w = self.wrap_layer.clone();
+            self.indicator_layer
+                .set_opacity(0.0, Some(Transition::ease_out_quad(0.2)))
+                .on_finish(
+                    move |l: &Layer, _| {
+                        // Muted again while fading out
+                        if l.opacity() == 0.0 {
+                            w.set_hidden(true);
+                        }
+                    },
+                    true,
+                );
+        }
+    }
+
+    /// Whether the indicator is currently shown
+    pub fn is_visible(&self) -> bool {
+        !self.wrap_layer.hidden()
+    }
+}
@@ -6,11 +6,10 @@ use std::{
     },
 };
 
-use apps_info::Application;
 use layers::{
     engine::{Engine, TransactionRef},
     prelude::{taffy, Interpolate, Layer, Spring, TimingFunction, Transition},
-    skia::{self, Contains},
+    skia,
     types::Size,
 };
 use smithay::{
@@ -29,10 +28,18 @@ use workspace::WorkspaceView;
 mod app_icons_manager;
 mod app_switcher;
 mod background;
+mod clipboard_picker;
 mod dnd_view;
 mod dock;
+mod fps_overlay;
+mod mic_indicator;
 mod osd;
+mod output_home;
 mod popup_overlay;
+mod recording_indicator;
+mod region_selector;
+mod test_pattern;
+mod tiling;
 mod tiling_overlay;
 pub mod workspace;
 
@@ -40,31 +47,50 @@ pub mod utils;
 
 mod apps_info;
 mod window_selector;
+mod window_switcher;
 mod window_view;
 mod workspace_selector;
 
 pub use background::BackgroundView;
+pub use clipboard_picker::{age_label, ClipboardPickerItem, ClipboardPickerView};
 pub use window_selector::{WindowSelectorView, WindowSelectorWindow};
+pub use window_switcher::{WindowSwitcherItem, WindowSwitcherView};
 pub use window_view::{WindowView, WindowViewBaseModel, WindowViewSurface};
 
 pub use app_icons_manager::AppIconsManager;
 pub use app_switcher::AppSwitcherView;
-pub use apps_info::ApplicationsInfo;
+pub use apps_info::{Application, ApplicationsInfo};
 pub use dnd_view::DndView;
 pub use dock::DockView;
+pub use fps_overlay::{FpsOverlayOutput, FpsOverlayStats, FpsOverlayView};
+pub use mic_indicator::MicIndicatorView;
 pub use osd::OsdView;
+pub use output_home::{DisplayIdentity, HomeOutput};
 pub use popup_overlay::PopupOverlayView;
+pub use recording_indicator::RecordingIndicatorView;
+pub use region_selector::{RegionOutput, RegionSelection, RegionSelectorView};
+pub use test_pattern::{TestPatternOutput, TestPatternView};
+pub use tiling::{inset, neighbor_in_direction, Direction, TileTree, WorkspaceLayout};
 pub use tiling_overlay::{zone_from_pointer, TileZone, TilingOverlayView};
 pub use workspace::WORKSPACE_SPACING;
 pub use workspace_selector::{WorkspaceSelectorView, WORKSPACE_SELECTOR_PREVIEW_WIDTH};
 
 use crate::{
-    config::Config,
+    config::{window_rules::ContentType, Config, DockOutputs},
     shell::WindowElement,
     utils::{natural_layout::LayoutRect, Observable, Observer},
 };
 
 /// Per-output workspace set: each output has its own independent workspaces.
+/// A workspace removal scheduled to run once an animation ends
+pub struct WorkspaceRemoval {
+    /// Model index of the workspace (`WorkspaceView::index`)
+    pub index: usize,
+    /// Whether to update the `[workspaces]` config; transient fullscreen
+    /// workspaces are not saved
+    pub persist: bool,
+}
+
 pub struct OutputWorkspaces {
     pub current_workspace: usize,
     pub spaces: Vec<Space<WindowElement>>,
@@ -125,12 +151,21 @@ pub struct Workspaces {
     // views
     pub workspace_selector_view: Arc<WorkspaceSelectorView>,
     pub dock: Arc<DockView>,
+    /// Docks of the non-primary outputs, keyed by output name
+    output_docks: HashMap<String, Arc<DockView>>,
     pub app_switcher: Arc<AppSwitcherView>,
+    pub window_switcher: Arc<WindowSwitcherView>,
+    pub clipboard_picker: ClipboardPickerView,
     pub window_views: Arc<RwLock<HashMap<ObjectId, WindowView>>>,
     pub dnd_view: DndView,
     pub popup_overlay: PopupOverlayView,
     pub osd: OsdView,
+    pub mic_indicator: MicIndicatorView,
+    pub recording_indicator: RecordingIndicatorView,
     pub tiling_overlay: TilingOverlayView,
+    pub region_selector: RegionSelectorView,
+    pub fps_overlay: FpsOverlayView,
+    pub test_pattern: TestPatternView,
     pub app_icons_manager: Arc<AppIconsManager>,
 
     // gestures states
@@ -153,7 +188,7 @@ pub struct Workspaces {
     expose_layer: Layer,
     observers: Vec<Weak<dyn Observer<WorkspacesModel>>>,
     expose_dragged_window: Arc<std::sync::Mutex<Option<ObjectId>>>,
-    remove_workspace_sender: CalloopSender<usize>,
+    remove_workspace_sender: CalloopSender<WorkspaceRemoval>,
 }
 
 /// # Workspaces Layer Structure
@@ -184,8 +219,9 @@ pub struct Workspaces {
 /// │   │   │   ├── window_selector_view_1
 /// │   │   ├── expose_view
 /// │   │   ├── app_switcher
+/// │   │   ├── window_switcher
 /// │   │
-/// │   ├── dock (primary only)
+/// │   ├── dock (one per output, see `sync_output_docks`)
 /// │   ├── layer_shell_top (primary only)
 /// │   ├── popup_overlay (primary only)
 /// │   ├── layer_shell_overlay (primary only)
@@ -240,7 +276,10 @@ impl Workspaces {
     pub fn new(
         layers_engine: Arc<Engine>,
         display_handle: DisplayHandle,
-    ) -> (Self, smithay::reexports::calloop::channel::Channel<usize>) {
+    ) -> (
+        Self,
+        smithay::reexports::calloop::channel::Channel<WorkspaceRemoval>,
+    ) {
         let model = WorkspacesModel::default();
 
         let expose_layer = layers_engine.new_layer();
@@ -296,6 +335,8 @@ impl Workspaces {
 
         let app_switcher = AppSwitcherView::new(layers_engine.clone(), app_icons_manager.clone());
         let app_switcher = Arc::new(app_switcher);
+        let window_switcher = Arc::new(WindowSwitcherView::new(layers_engine.clone()));
+        let clipboard_picker = ClipboardPickerView::new(layers_engine.clone());
 
         let workspace_selector_layer = layers_engine.new_layer();
         workspace_selector_layer.set_pointer_events(false);
@@ -316,7 +357,7 @@ impl Workspaces {
         layer_shell_overlay.set_hidden(true);
         // attached to output_layer in map_output_with_primary
 
-        let (remove_workspace_sender, remove_receiver) = channel::<usize>();
+        let (remove_workspace_sender, remove_receiver) = channel::<WorkspaceRemoval>();
         let workspace_selector_view = WorkspaceSelectorView::new(
             layers_engine.clone(),
             workspace_selector_layer.clone(),
@@ -327,9 +368,23 @@ impl Workspaces {
         // Create OSD view; attach it to overlay_layer in map_output_with_primary
         let osd = OsdView::new(layers_engine.clone());
 
+        // Muted-microphone badge; attached to overlay_layer in map_output_with_primary
+        let mic_indicator = MicIndicatorView::new(layers_engine.clone());
+        // Screen recording badge; attached next to the microphone badge
+        let recording_indicator = RecordingIndicatorView::new(layers_engine.clone());
+
         // Window-tiling drop-zone overlay; attached to overlay_layer in map_output_with_primary
         let tiling_overlay = TilingOverlayView::new(layers_engine.clone());
 
+        // Region selector overlay; attached per output on demand
+        let region_selector = RegionSelectorView::new(layers_engine.clone());
+
+        // Debug FPS overlay; attached per output on demand
+        let fps_overlay = FpsOverlayView::new(layers_engine.clone());
+
+        // Gradient test pattern; attached per output on demand
+        let test_pattern = TestPatternView::new(layers_engine.clone());
+
         let mut workspaces = Self {
             // layer,
             output_workspaces: HashMap::new(),
@@ -339,12 +394,20 @@ impl Workspaces {
             windows_map: HashMap::new(),
             expose_layer,
             app_switcher: app_switcher.clone(),
+            window_switcher,
+            clipboard_picker,
             workspace_selector_view: workspace_selector_view.clone(),
             dock: dock.clone(),
+            output_docks: HashMap::new(),
             dnd_view,
             popup_overlay,
             osd,
+            mic_indicator,
+            recording_indicator,
             tiling_overlay,
+            region_selector,
+            fps_overlay,
+            test_pattern,
             app_icons_manager,
             overlay_layer,
             layer_shell_top,
@@ -446,9 +509,8 @@ impl Workspaces {
         let idx = ows.current_workspace;
         ows.spaces.get_mut(idx)
     }
-    /// Set the workspace screen physical size
-    pub fn set_screen_dimension(&self, width: i32, height: i32) {
-        let scale = Config::with(|c| c.screen_scale);
+    /// Set the workspace screen physical size and the scale it is shown at
+    pub fn set_screen_dimension(&self, width: i32, height: i32, scale: f64) {
         let current_workspace = self.with_model_mut(|model| {
             model.width = width;
             model.height = height;
@@ -460,7 +522,17 @@ impl Workspaces {
 
         self.update_workspaces_layout();
         self.scroll_to_workspace_index(current_workspace, Some(Transition::ease_out_quad(0.0)));
-        self.dock.set_screen_size(width, height);
+        self.dock.set_screen_size(width, height, scale);
+    }
+
+    /// Scale of the primary output, where the dock, expose, the workspace
+    /// selector, the switchers and the clipboard picker are shown; the global
+    /// `screen_scale` until one is mapped.
+    pub fn primary_output_scale(&self) -> f64 {
+        self.primary_output
+            .as_ref()
+            .map(|o| o.current_scale().fractional_scale())
+            .unwrap_or_else(|| Config::with(|c| c.screen_scale))
     }
 
     pub fn get_logical_rect(&self) -> smithay::utils::Rectangle<i32, smithay::utils::Logical> {
@@ -567,7 +639,7 @@ impl Workspaces {
     /// - The workspace is not animating (not scrolling between workspaces)
     /// - The fullscreen window is not animating
     /// - Not in expose/show-all mode
-    /// - App switcher is not visible
+    /// - App switcher and window switcher are not visible
     /// - The workspace has exactly one window (the fullscreen window only)
     pub fn is_fullscreen_and_stable(&self) -> bool {
         // Check if expose mode is active
@@ -575,13 +647,19 @@ impl Workspaces {
             return false;
         }
 
-        // Check if app switcher is visible
-        if self.app_switcher.alive() {
+        // Check if app switcher or window switcher is visible
+        if self.app_switcher.alive() || self.window_switcher.alive() {
             return false;
         }
 
-        // Check if OSD (volume/brightness) is visible
-        if self.osd.is_visible() {
+        // Check if OSD (volume/brightness) is visible; a fullscreen game
+        // keeps direct scanout instead, a flip back to composition would
+        // stutter it for a transient popup
+        if self.osd.is_visible()
+            && self
+                .get_fullscreen_window()
+                .is_none_or(|window| window.content_type() != ContentType::Game)
+        {
             return false;
         }
 
@@ -617,6 +695,14 @@ impl Workspaces {
         true
     }
 
+    /// Whether the muted-microphone or recording badge is shown on `output`.
+    /// The badges must stay visible over fullscreen apps, so direct scanout
+    /// is disabled on that output only.
+    pub fn overlay_badges_on(&self, output: &Output) -> bool {
+        self.primary_output.as_ref() == Some(output)
+            && (self.mic_indicator.is_visible() || self.recording_indicator.is_visible())
+    }
+
     /// Get the fullscreen window from the current workspace, if any.
     /// Returns Some(WindowElement) if the current workspace is in fullscreen mode
     /// and has a fullscreen window.
@@ -813,6 +899,7 @@ impl Workspaces {
                 // Clear any leftover selection/state and force a fresh layout recalculation.
                 workspace_view.window_selector_view.take_pre_close_hovered();
                 workspace_view.window_selector_view.clear_selection();
+                workspace_view.window_selector_view.clear_filter();
                 workspace_view.window_selector_view.invalidate_layout();
             }
             // Compute layout and trigger a view render so the selection overlay is ready
@@ -849,6 +936,7 @@ impl Workspaces {
                     .set_opacity(0.0, None);
                 workspace_view.window_selector_view.save_pre_close_hovered();
                 workspace_view.window_selector_view.clear_selection();
+                workspace_view.window_selector_view.clear_filter();
             }
         }
     }
@@ -937,10 +1025,10 @@ impl Workspaces {
             spring_velocity, // initial velocity from gesture
         );
 
-        let transition = Transition {
+        let transition = Config::transition_or_instant(Transition {
             delay: 0.0,
             timing: TimingFunction::Spring(spring),
-        };
+        });
 
         let current_workspace = self.get_current_workspace_index();
         // Use current delta so the spring animation can transition FROM current state TO target state
@@ -999,10 +1087,10 @@ impl Workspaces {
 
         // Create smooth spring transition (zero velocity for keyboard shortcuts)
         let spring = Spring::with_duration_and_bounce(0.3, 0.1);
-        let transition = Transition {
+        let transition = Config::transition_or_instant(Transition {
             delay: 0.0,
             timing: TimingFunction::Spring(spring),
-        };
+        });
 
         let current_workspace = self.get_current_workspace_index();
         let delta_normalized = if show { 1.0 } else { 0.0 };
@@ -1012,6 +1100,7 @@ impl Workspaces {
         for i in 0..num_workspaces {
             if let Some(workspace_view) = self.get_workspace_at(i) {
                 workspace_view.window_selector_view.clear_selection();
+                workspace_view.window_selector_view.clear_filter();
             }
         }
 
@@ -1075,10 +1164,10 @@ impl Workspaces {
         let delta_normalized = new_gesture as f32 / 1000.0;
 
         let transition = if animated {
-            Some(Transition {
+            Some(Config::transition_or_instant(Transition {
                 delay: 0.0,
                 timing: TimingFunction::Spring(Spring::with_duration_and_bounce(0.3, 0.1)),
-            })
+            }))
         } else {
             None
         };
@@ -1112,7 +1201,7 @@ impl Workspaces {
             .primary_workspaces_layer()
             .map(|l| l.render_size_transformed())
             .unwrap_or_default();
-        let scale = Config::with(|c| c.screen_scale);
+        let scale = self.primary_output_scale();
         let screen_size_w = size.x;
         let screen_size_h = size.y - padding_top - padding_bottom - workspace_selector_height;
 
@@ -1158,6 +1247,7 @@ impl Workspaces {
                                     bbox.size.h as f32,
                                 ),
                                 title: window.xdg_title().to_string(),
+                                app_id: window.xdg_app_id(),
                             });
                         }
                     }
@@ -1218,6 +1308,7 @@ impl Workspaces {
         show_all: bool,
         transition: Option<Transition>,
     ) {
+        let transition = transition.map(Config::transition_or_instant);
         let velocity = if let Some(Transition {
             timing: TimingFunction::Spring(spring),
             ..
@@ -1263,7 +1354,7 @@ impl Workspaces {
 
         // Hide popup overlay when entering expose mode
         self.popup_overlay.set_hidden(is_gesture_ongoing);
-        let scale = Config::with(|c| c.screen_scale);
+        let scale = self.primary_output_scale();
 
         let offset_y = 200.0;
         let mut changes = Vec::new();
@@ -1483,10 +1574,10 @@ impl Workspaces {
                             for ol in &all_window_selector_views {
                                 if show_all {
                                     tracing::debug!("wsv: on_finish(open) → opacity=1.0 fade");
-                                    let fade_in = Transition {
+                                    let fade_in = Config::transition_or_instant(Transition {
                                         delay: 0.05,
                                         timing: TimingFunction::ease_in_out(0.2),
-                                    };
+                                    });
                                     ol.set_opacity(1.0, Some(fade_in));
                                 } else {
                                     tracing::debug!("wsv: on_finish(close) → opacity=0");
@@ -1551,29 +1642,33 @@ impl Workspaces {
             if !is_current_workspace {
                 return;
             }
-            // When autohide is on and the dock is visible, animate it down with the expose
-            // gesture (same interpolation as the non-autohide path). The hot zone is suppressed
-            // during expose (see check_dock_hot_zone).
-            if self.dock.is_autohide_enabled() && !self.dock.is_hidden() {
-                let dock_y = 0.0_f32.interpolate(&250.0, delta);
-                self.dock
-                    .view_layer
-                    .set_position((0.0, dock_y.clamp(0.0, 250.0)), transition);
-                if end_gesture && show_all {
-                    self.dock.schedule_autohide();
-                }
-            } else if !self.dock.is_autohide_enabled() {
-                let mut start_position = 0.0;
-                let mut end_position = 250.0;
-                // Only keep dock hidden in fullscreen mode when NOT in expose mode
-                // During expose mode, we want the dock to animate normally
-                if current_workspace.get_fullscreen_mode() {
-                    start_position = 250.0;
-                    end_position = 250.0;
+            // Every output's dock follows the gesture off its own edge. When
+            // autohide is on and the dock is visible, animate it with the expose
+            // gesture (same interpolation as the non-autohide path). The hot zone
+            // is suppressed during expose (see check_dock_hot_zone).
+            for output in &self.outputs {
+                let Some(dock) = self.dock_for_output(output) else {
+                    continue;
+                };
+                let fullscreen = if self.primary_output.as_ref() == Some(output) {
+                    current_workspace.get_fullscreen_mode()
+                } else {
+                    self.output_workspaces
+                        .get(&output.name())
+                        .and_then(|ows| ows.workspace_views.get(ows.current_workspace))
+                        .is_some_and(|w| w.get_fullscreen_mode())
+                };
+                if dock.is_autohide_enabled() && !dock.is_hidden() {
+                    dock.slide_off_edge(0.0_f32.interpolate(&1.0, delta), transition.clone());
+                    if end_gesture && show_all {
+                        dock.schedule_autohide();
+                    }
+                } else if !dock.is_autohide_enabled() {
+                    // Only keep dock hidden in fullscreen mode when NOT in expose mode
+                    // During expose mode, we want the dock to animate normally
+                    let start: f32 = if fullscreen { 1.0 } else { 0.0 };
+                    dock.slide_off_edge(start.interpolate(&1.0, delta), transition.clone());
                 }
-                let dock_y = start_position.interpolate(&end_position, delta);
-                let dock_y = dock_y.clamp(0.0, 250.0);
-                self.dock.view_layer.set_position((0.0, dock_y), transition);
             }
 
             if let Some(anim_ref) = animation {
@@ -1587,7 +1682,9 @@ impl Workspaces {
     /// When exiting fullscreen (is_fullscreen=false), shows and fades in both layers
     pub fn set_fullscreen_overlay_visibility(&self, is_fullscreen: bool) {
         let target_opacity = if is_fullscreen { 0.0 } else { 1.0 };
-        let transition = Some(Transition::ease_in_out_quad(1.4));
+        let transition = Some(Config::transition_or_instant(Transition::ease_in_out_quad(
+            1.4,
+        )));
 
         if !is_fullscreen {
             // Unhide before fading in so the animation is visible
@@ -1663,7 +1760,7 @@ impl Workspaces {
             .store(new_gesture, std::sync::atomic::Ordering::Relaxed);
 
         // Use same spring transition as expose_show_all for consistency
-        let mut transition = Some(Transition::spring(0.5, 0.1));
+        let mut transition = Some(Config::transition_or_instant(Transition::spring(0.5, 0.1)));
         if !end_gesture {
             transition = None;
         }
@@ -1673,7 +1770,7 @@ impl Workspaces {
             .primary_workspaces_layer()
             .map(|l| l.render_size_transformed())
             .unwrap_or_default();
-        let scale = Config::with(|c| c.screen_scale);
+        let scale = self.primary_output_scale();
         let screen_center_x = size.x / 2.0;
         let screen_center_y = size.y / 2.0;
 
@@ -1811,10 +1908,10 @@ impl Workspaces {
             .expose_gesture_active
             .load(std::sync::atomic::Ordering::Relaxed);
         if self.get_show_all() && relayout && !gesture_active {
-            let transition = Transition {
+            let transition = Config::transition_or_instant(Transition {
                 delay: 0.0,
                 timing: TimingFunction::Spring(Spring::with_duration_and_bounce(0.3, 0.1)),
-            };
+            });
             self.expose_show_all_end(workspace_index, 1.0, true, Some(transition));
         }
     }
@@ -2034,10 +2131,10 @@ impl Workspaces {
         let ctx = self.build_unminimize_context(wid)?;
 
         if workspace_for_window != current_workspace_index {
-            if let Some(tr) = self.set_current_workspace_index(
-                workspace_for_window,
-                Some(Transition::ease_out_quad(0.2)),
-            ) {
+            let transition = Config::transition_or_instant(Transition::ease_out_quad(0.2));
+            if let Some(tr) =
+                self.set_current_workspace_index(workspace_for_window, Some(transition))
+            {
                 let ctx_clone = ctx.clone();
                 tr.on_finish(
                     move |_: &Layer, _: f32| {
@@ -2058,7 +2155,7 @@ impl Workspaces {
     }
 
     fn build_unminimize_context(&self, wid: &ObjectId) -> Option<UnminimizeContext> {
-        let scale = Config::with(|c| c.screen_scale) as f32;
+        let scale = self.primary_output_scale() as f32;
         let primary_ows = self.primary_output_workspaces()?;
         let (index, space) = primary_ows
             .spaces
@@ -2115,18 +2212,7 @@ impl Workspaces {
                 let mut adjusted = Rectangle::new(geo.loc + zone.loc, zone.size);
 
                 // Account for the dock geometry (internal compositor UI, not layer-shell)
-                let dock_geom = self.get_dock_geometry();
-                if dock_geom.size.h > 0 {
-                    let dock_top = dock_geom.loc.y;
-                    let available_bottom = adjusted.loc.y + adjusted.size.h;
-
-                    // If dock is in the usable area, reduce height to stop above dock
-                    if dock_top < available_bottom {
-                        adjusted.size.h = dock_top - adjusted.loc.y;
-                    }
-                }
-
-                Some(adjusted)
+                Some(self.exclude_dock(&o, adjusted))
             })
             .unwrap_or_else(|| Rectangle::new((0, 0).into(), (800, 800).into()));
 
@@ -2289,6 +2375,112 @@ impl Workspaces {
         self.expose_update_if_needed();
     }
 
+    /// Remove the window from every output's spaces and map it onto
+    /// `output`'s current workspace at `location`.
+    pub fn move_window_to_output(
+        &mut self,
+        output: &Output,
+        window_element: &WindowElement,
+        location: impl Into<smithay::utils::Point<i32, smithay::utils::Logical>>,
+        activate: bool,
+    ) {
+        // Moved on purpose: the new output is its home now
+        window_element.set_home_output(None);
+        for ows in self.output_workspaces.values_mut() {
+            for space in ows.spaces.iter_mut() {
+                space.unmap_elem(window_element);
+            }
+        }
+        self.map_window_for_output(output, window_element, location, activate, None);
+    }
+
+    /// Remove the window from every output's spaces and map it onto
+    /// workspace `index` of `output` at `location`.
+    pub fn move_window_to_output_workspace(
+        &mut self,
+        output: &Output,
+        index: usize,
+        window_element: &WindowElement,
+        location: impl Into<smithay::utils::Point<i32, smithay::utils::Logical>>,
+    ) {
+        let location = location.into();
+        window_element.set_home_output(None);
+        let id = window_element.id();
+        for ows in self.output_workspaces.values_mut() {
+            for (i, space) in ows.spaces.iter_mut().enumerate() {
+                if space.elements().any(|e| e.id() == id) {
+                    space.unmap_elem(window_element);
+                    if let Some(view) = ows.workspace_views.get(i) {
+                        view.unmap_window_internal(&id);
+                    }
+                }
+            }
+        }
+        let Some(ows) = self.output_workspaces.get_mut(&output.name()) else {
+            return;
+        };
+        let Some(space) = ows.spaces.get_mut(index) else {
+            return;
+        };
+        space.map_element(window_element.clone(), location, false);
+        if let Some(view) = ows.workspace_views.get(index) {
+            view.map_window(window_element, location, None);
+        }
+        self.refresh_space();
+        self.expose_update_if_needed_workspace(index);
+    }
+
+    /// Move a window within workspace `index` of `output` without changing
+    /// the stacking order of that workspace.
+    pub fn place_window_in_workspace(
+        &mut self,
+        output: &Output,
+        index: usize,
+        window_element: &WindowElement,
+        location: impl Into<smithay::utils::Point<i32, smithay::utils::Logical>>,
+        transition: Option<Transition>,
+    ) {
+        let location = location.into();
+        let Some(ows) = self.output_workspaces.get_mut(&output.name()) else {
+            return;
+        };
+        let Some(space) = ows.spaces.get_mut(index) else {
+            return;
+        };
+        // map_element raises the element: replay the previous order afterwards
+        let order: Vec<WindowElement> = space.elements().cloned().collect();
+        space.map_element(window_element.clone(), location, false);
+        for element in &order {
+            space.raise_element(element, false);
+        }
+        if let Some(view) = ows.workspace_views.get(index) {
+            view.map_window(window_element, location, transition);
+        }
+    }
+
+    /// Raise the floating windows of workspace `index` of `output` above the
+    /// tiled ones, keeping always-on-top windows uppermost.
+    pub fn raise_floating_windows(&mut self, output: &Output, index: usize) {
+        let Some(ows) = self.output_workspaces.get_mut(&output.name()) else {
+            return;
+        };
+        let Some(space) = ows.spaces.get_mut(index) else {
+            return;
+        };
+        let floating: Vec<WindowElement> = space
+            .elements()
+            .filter(|e| e.is_floating())
+            .cloned()
+            .collect();
+        for element in &floating {
+            space.raise_element(element, false);
+            if let Some(view) = ows.workspace_views.get(index) {
+                view.raise_window_to_front(&element.id());
+            }
+        }
+        restack_always_on_top(space, None);
+    }
+
     /// remove a WindowElement from the workspace model,
     /// remove the window layer from the scene,
     /// Returns the surface IDs from removed popups that need cleanup
@@ -2321,6 +2513,7 @@ impl Workspaces {
             }
         });
         self.windows_map.remove(window_id);
+        self.window_switcher.remove_window(window_id);
         // Remove debug texture snapshot for this surface
         crate::textures_storage::remove(window_id);
         let removed_surface_ids = self.remove_window_view(window_id);
@@ -2336,33 +2529,166 @@ impl Workspaces {
         // Return the surface IDs so the compositor can clean up surface_layers and sc_layers
         removed_surface_ids
     }
-    /// Return if the current coordinates are over the dock
+    /// Return the dock under the given physical coordinates, or the dock
+    /// with an open context menu
+    pub fn dock_under_cursor(&self, x: f32, y: f32) -> Option<Arc<DockView>> {
+        self.docks()
+            .find(|dock| dock.has_menu_open())
+            .or_else(|| {
+                self.docks()
+                    .find(|dock| dock.alive() && dock.contains_point(x, y))
+            })
+            .cloned()
+    }
+
+    /// Return if the current coordinates are over a dock
     pub fn is_cursor_over_dock(&self, x: f32, y: f32) -> bool {
-        self.dock.alive()
-            && self
-                .dock
-                .view_layer
-                .render_bounds_transformed()
-                .contains(skia::Point::new(x, y))
-            || self.dock.has_menu_open()
+        self.dock_under_cursor(x, y).is_some()
+    }
+
+    /// The primary dock followed by the docks of the other outputs
+    pub fn docks(&self) -> impl Iterator<Item = &Arc<DockView>> {
+        std::iter::once(&self.dock).chain(self.output_docks.values())
+    }
+
+    /// The dock shown on `output`, if any
+    pub fn dock_for_output(&self, output: &Output) -> Option<&Arc<DockView>> {
+        if self.primary_output.as_ref() == Some(output) {
+            Some(&self.dock)
+        } else {
+            self.output_docks.get(&output.name())
+        }
+    }
+
+    /// Create a dock for every mapped non-primary output that has none, drop
+    /// the docks of outputs that are gone, and keep each dock's screen
+    /// geometry in sync with its output.
+    ///
+    /// With `dock.outputs = "primary"` only the primary dock is kept.
+    pub fn sync_output_docks(&mut self) {
+        let all_outputs = Config::with(|c| c.dock.outputs == DockOutputs::All);
+        let primary_name = self.primary_output_name();
+        let wanted: Vec<Output> = self
+            .outputs
+            .iter()
+            .filter(|o| all_outputs && Some(o.name()) != primary_name)
+            .filter(|o| self.output_workspaces.contains_key(&o.name()))
+            .cloned()
+            .collect();
+
+        let stale: Vec<String> = self
+            .output_docks
+            .keys()
+            .filter(|name| !wanted.iter().any(|o| o.name() == **name))
+            .cloned()
+            .collect();
+        for name in stale {
+            if let Some(dock) = self.output_docks.remove(&name) {
+                dock.destroy();
+            }
+        }
+
+        for output in &wanted {
+            if self.output_docks.contains_key(&output.name()) {
+                continue;
+            }
+            let Some(ows) = self.output_workspaces.get(&output.name()) else {
+                continue;
+            };
+            let dock = Arc::new(self.dock.new_sibling());
+            let _ = ows.output_layer.add_sublayer(&dock.wrap_layer);
+            let fullscreen = ows
+                .workspace_views
+                .get(ows.current_workspace)
+                .is_some_and(|w| w.get_fullscreen_mode());
+            if fullscreen {
+                dock.hide(None);
+            } else {
+                dock.show(None);
+            }
+            self.add_listener(dock.clone());
+            self.output_docks.insert(output.name(), dock);
+        }
+
+        for output in self.outputs.clone() {
+            let Some(dock) = self.dock_for_output(&output) else {
+                continue;
+            };
+            if let Some(mode) = output.current_mode() {
+                dock.set_screen_size(
+                    mode.size.w,
+                    mode.size.h,
+                    output.current_scale().fractional_scale(),
+                );
+            }
+            if let Some(geo) = self.output_geometry(&output) {
+                dock.set_screen_origin(geo.loc.x, geo.loc.y);
+            }
+        }
+    }
+
+    /// Apply a reloaded dock config to every dock, and create or drop the
+    /// per-output docks when `outputs` changed.
+    pub fn apply_dock_config(&mut self, config: crate::config::DockConfig) {
+        let was_autohide = self.dock.is_autohide_enabled();
+        self.dock.apply_config(config);
+        for dock in self.output_docks.values() {
+            dock.config_changed(was_autohide);
+        }
+        self.sync_output_docks();
+    }
+
+    /// Bring the other docks in line with a change made from the context
+    /// menu of `source`: its launchers and the shared autohide setting.
+    pub fn sync_docks_from(&self, source: &DockView) {
+        let autohide = source.is_autohide_enabled();
+        for output in &self.outputs {
+            let Some(dock) = self.dock_for_output(output) else {
+                continue;
+            };
+            if dock.as_ref() == source {
+                continue;
+            }
+            dock.sync_launchers_from(source);
+            let fullscreen = self
+                .output_workspaces
+                .get(&output.name())
+                .and_then(|ows| ows.workspace_views.get(ows.current_workspace))
+                .is_some_and(|w| w.get_fullscreen_mode());
+            if !autohide && dock.is_hidden() && !fullscreen && !self.get_show_all() {
+                dock.show(None);
+            }
+        }
     }
 
     /// Return the actual rendered height of the dock in logical pixels
     pub fn get_dock_height(&self) -> i32 {
         if self.dock.alive() {
             let bounds = self.dock.bar_layer.render_bounds_transformed();
-            let scale = Config::with(|c| c.screen_scale);
+            let scale = self.primary_output_scale();
             (bounds.height() / scale as f32).ceil() as i32
         } else {
             0
         }
     }
 
-    /// Return the actual rendered geometry of the dock in logical coordinates
-    pub fn get_dock_geometry(&self) -> Rectangle<i32, smithay::utils::Logical> {
-        if self.dock.alive() {
-            let bounds = self.dock.bar_layer.render_bounds_transformed();
-            let scale = Config::with(|c| c.screen_scale) as f32;
+    /// Shrink `zone` so it stops at the dock on `output`, on whichever
+    /// screen edge the dock sits.
+    pub fn exclude_dock(
+        &self,
+        output: &Output,
+        zone: Rectangle<i32, smithay::utils::Logical>,
+    ) -> Rectangle<i32, smithay::utils::Logical> {
+        dock::exclude_dock(zone, self.get_dock_geometry(output), self.dock.position())
+    }
+
+    /// Return the actual rendered geometry of the dock on `output` in
+    /// logical coordinates, empty when the output has no visible dock
+    pub fn get_dock_geometry(&self, output: &Output) -> Rectangle<i32, smithay::utils::Logical> {
+        let dock = self.dock_for_output(output).filter(|dock| dock.alive());
+        if let Some(dock) = dock {
+            let bounds = dock.bar_layer.render_bounds_transformed();
+            let scale = output.current_scale().fractional_scale() as f32;
             Rectangle::new(
                 (
                     ((bounds.x() / scale) as i32),
@@ -2641,10 +2967,16 @@ impl Workspaces {
                     .and_then(|s| s.elements().last())
                     .map(|w| w.id());
 
-                // Raise on all outputs' spaces at same index
+                // Raise on all outputs' spaces at same index. In fullscreen mode
+                // the fullscreen window stays above always-on-top windows.
+                let workspace = self.with_model(|m| m.workspaces[index].clone());
+                let keep_on_top = !workspace.get_fullscreen_mode();
                 for ows in self.output_workspaces.values_mut() {
                     if let Some(space) = ows.spaces.get_mut(index) {
                         space.raise_element(window, activate);
+                        if keep_on_top {
+                            restack_always_on_top(space, Some(window));
+                        }
                     }
                 }
 
@@ -2667,7 +2999,6 @@ impl Workspaces {
                     self.popup_overlay.show_popups_for_window(window_id);
                 }
 
-                let workspace = self.with_model(|m| m.workspaces[index].clone());
                 workspace.raise_window_to_front(window_id);
                 if update {
                     self.update_workspace_model();
@@ -2678,6 +3009,30 @@ impl Workspaces {
         }
     }
 
+    /// Keep a window above normal windows across focus changes, or return it
+    /// to the normal stack
+    pub fn set_window_always_on_top(&mut self, window: &WindowElement, on_top: bool) {
+        window.set_is_always_on_top(on_top);
+        // A fullscreen window keeps covering the output; the flag applies when
+        // it leaves fullscreen and is remapped on its workspace
+        if window.is_fullscreen() {
+            return;
+        }
+        let id = window.id();
+        for ows in self.output_workspaces.values_mut() {
+            for view in ows.workspace_views.iter() {
+                view.set_window_on_top(&id, on_top);
+            }
+            for space in ows.spaces.iter_mut() {
+                if space.elements().any(|e| e == window) {
+                    space.raise_element(window, false);
+                    restack_always_on_top(space, Some(window));
+                }
+            }
+        }
+        self.update_workspace_model();
+    }
+
     /// Raise all the windows of a given app
     /// returns the window id of the last window raised, if any
     fn raise_app_elements(
@@ -2910,6 +3265,62 @@ impl Workspaces {
         self.outputs.iter()
     }
 
+    /// Show the region selector overlay on every mapped output.
+    pub fn show_region_selector(&mut self) {
+        let outputs = self
+            .outputs
+            .iter()
+            .filter_map(|output| {
+                let geometry = self.output_geometry(output)?;
+                let ows = self.output_workspaces.get(&output.name())?;
+                Some(RegionOutput {
+                    name: output.name(),
+                    geometry,
+                    scale: output.current_scale().fractional_scale(),
+                    output_layer: ows.output_layer.clone(),
+                })
+            })
+            .collect();
+        self.region_selector.show(outputs);
+    }
+
+    /// Show the FPS overlay on every mapped output.
+    pub fn show_fps_overlay(&mut self) {
+        let outputs = self
+            .outputs
+            .iter()
+            .filter_map(|output| {
+                let ows = self.output_workspaces.get(&output.name())?;
+                Some(FpsOverlayOutput {
+                    name: output.name(),
+                    scale: output.current_scale().fractional_scale(),
+                    output_layer: ows.output_layer.clone(),
+                })
+            })
+            .collect();
+        self.fps_overlay.show(outputs);
+    }
+
+    /// Show the gradient test pattern on every mapped output.
+    pub fn show_test_pattern(&mut self) {
+        let outputs = self
+            .outputs
+            .iter()
+            .filter_map(|output| {
+                let geometry = self.output_geometry(output)?;
+                let ows = self.output_workspaces.get(&output.name())?;
+                let scale = output.current_scale().fractional_scale() as f32;
+                Some(TestPatternOutput {
+                    name: output.name(),
+                    width: geometry.size.w as f32 * scale,
+                    height: geometry.size.h as f32 * scale,
+                    output_layer: ows.output_layer.clone(),
+                })
+            })
+            .collect();
+        self.test_pattern.show(outputs);
+    }
+
     /// Attach a new output to every workspace
     pub fn map_output(
         &mut self,
@@ -2969,14 +3380,17 @@ impl Workspaces {
             self.primary_output = Some(output.clone());
         }
 
-        // Count workspaces from primary output (default 2 if no primary yet)
-        let n_workspaces = self
+        // Count workspaces from primary output (from config if no primary yet)
+        let primary_views = self
             .primary_output_name()
             .as_ref()
             .and_then(|n| self.output_workspaces.get(n))
-            .map(|ows| ows.workspace_views.len())
-            .unwrap_or(0)
-            .max(2);
+            .map(|ows| ows.workspace_views.clone());
+        let n_workspaces = primary_views
+            .as_ref()
+            .map(|views| views.len())
+            .unwrap_or_else(|| Config::with(|c| c.workspaces.count))
+            .max(1);
 
         // Physical size from output mode
         let (phys_w, phys_h) = output
@@ -3040,7 +3454,8 @@ impl Workspaces {
         // Attach layers to output_layer in z-order (bottom to top):
         // workspaces → expose → overlay (dnd, osd) →
         // dock (primary) → layer_shell_top → workspace_selector →
-        // layer_shell_overlay → app_switcher (primary) → popup_overlay (primary)
+        // layer_shell_overlay → app_switcher, window_switcher, clipboard_picker (primary) →
+        // popup_overlay (primary)
         let _ = output_layer.add_sublayer(&workspaces_layer);
 
         // Create a per-output expose layer
@@ -3066,6 +3481,14 @@ impl Workspaces {
                 .overlay_layer
                 .add_sublayer(&self.tiling_overlay.wrap_layer);
             let _ = self.overlay_layer.add_sublayer(&self.osd.wrap_layer);
+            let _ = self
+                .overlay_layer
+                .add_sublayer(&self.mic_indicator.wrap_layer);
+            let _ = self
+                .overlay_layer
+                .add_sublayer(&self.recording_indicator.wrap_layer);
+            self.mic_indicator.set_scale(scale);
+            self.recording_indicator.set_scale(scale);
             // App icons manager lives at the root — sibling of output layers, never rendered
             // on any output, but present in the scene so its subtree gets laid out.
             if let Some(root) = self
@@ -3079,6 +3502,8 @@ impl Workspaces {
             let _ = output_layer.add_sublayer(&self.layer_shell_top);
             let _ = output_layer.add_sublayer(&self.workspace_selector_view.layer.clone());
             let _ = output_layer.add_sublayer(&self.app_switcher.wrap_layer.clone());
+            let _ = output_layer.add_sublayer(&self.window_switcher.wrap_layer.clone());
+            let _ = output_layer.add_sublayer(&self.clipboard_picker.wrap_layer.clone());
             let _ = output_layer.add_sublayer(&self.popup_overlay.layer.clone());
             let _ = output_layer.add_sublayer(&self.layer_shell_overlay);
             let _ = output_layer.add_sublayer(&self.overlay_layer);
@@ -3102,6 +3527,11 @@ impl Workspaces {
                 &layer_shell_background,
             ));
             let _ = expose_layer.add_sublayer(&workspace.window_selector_view.window_selector_root);
+            let name = match &primary_views {
+                Some(views) => views.get(i).and_then(|w| w.get_name()),
+                None => Config::with(|c| c.workspaces.name(i)),
+            };
+            workspace.set_name(name);
             workspace_views.push(workspace);
         }
 
@@ -3138,15 +3568,87 @@ impl Workspaces {
             .or(self.primary_output.as_ref())
     }
 
-    /// Detach an output from every workspace
-    pub fn unmap_output(&mut self, output: &Output) {
+    /// Detach an output from every workspace. Returns the windows moved to
+    /// another output.
+    pub fn unmap_output(&mut self, output: &Output) -> Vec<ObjectId> {
         self.outputs.retain(|o| o != output);
         if self.primary_output.as_ref() == Some(output) {
             self.primary_output = self.outputs.first().cloned();
         }
         // Remove the output's workspace set (dropping workspaces_layer removes it from scene)
-        self.output_workspaces.remove(&output.name());
+        let moved = match self.output_workspaces.remove(&output.name()) {
+            Some(ows) => self.move_windows_from_unplugged_output(output, &ows),
+            None => Vec::new(),
+        };
         self.sync_model_from_primary();
+        moved
+    }
+
+    /// Move the windows of an unplugged output to the same workspace of the
+    /// primary output, remembering the output as their home. Returns the
+    /// moved windows.
+    fn move_windows_from_unplugged_output(
+        &mut self,
+        output: &Output,
+        ows: &OutputWorkspaces,
+    ) -> Vec<ObjectId> {
+        let target = self
+            .primary_output
+            .clone()
+            .and_then(|target| Some((self.output_geometry(&target)?, target)));
+        let mut moved = Vec::new();
+        for (index, space) in ows.spaces.iter().enumerate() {
+            let origin = space.output_geometry(output).unwrap_or_default().loc;
+            for window in space.elements() {
+                let offset = space.element_location(window).unwrap_or_default() - origin;
+                // A window already away from home keeps its first home
+                if window.home_output().is_none() {
+                    window.set_home_output(Some(HomeOutput::new(output, index, offset)));
+                }
+                let Some((geometry, target)) = &target else {
+                    continue;
+                };
+                let location = geometry.loc + output_home::clamp_offset(offset, geometry.size);
+                let workspace = index.min(self.last_workspace_index(target));
+                self.place_window_in_workspace(target, workspace, window, location, None);
+                moved.push(window.id());
+            }
+        }
+        moved
+    }
+
+    fn last_workspace_index(&self, output: &Output) -> usize {
+        self.output_workspaces
+            .get(&output.name())
+            .map_or(0, |ows| ows.spaces.len().saturating_sub(1))
+    }
+
+    /// Move the windows whose home is `output` back to it, on the workspace
+    /// and at the position they had there. Returns the moved windows.
+    pub fn restore_windows_to_home_output(&mut self, output: &Output) -> Vec<ObjectId> {
+        let Some(geometry) = self.output_geometry(output) else {
+            return Vec::new();
+        };
+        let windows: Vec<(WindowElement, HomeOutput)> = self
+            .windows_map
+            .values()
+            .filter_map(|window| {
+                let home = window.home_output().filter(|home| home.matches(output))?;
+                Some((window.clone(), home))
+            })
+            .collect();
+        for (window, home) in &windows {
+            window.set_home_output(None);
+            for ows in self.output_workspaces.values_mut() {
+                for space in ows.spaces.iter_mut() {
+                    space.unmap_elem(window);
+                }
+            }
+            let location = geometry.loc + output_home::clamp_offset(home.offset, geometry.size);
+            let workspace = home.workspace.min(self.last_workspace_index(output));
+            self.place_window_in_workspace(output, workspace, window, location, None);
+        }
+        windows.iter().map(|(window, _)| window.id()).collect()
     }
 
     /// Suspend an output without destroying its workspace data.
@@ -3227,6 +3729,134 @@ impl Workspaces {
         })
     }
 
+    /// Re-read the wallpaper and background color from the config and apply
+    /// them to every workspace on every output.
+    pub fn apply_background_config(&self) {
+        let (path, color) = Config::with(|c| {
+            (
+                c.background_image.clone(),
+                crate::utils::parse_hex_color(&c.background_color),
+            )
+        });
+        let image = crate::utils::image_from_path(&path, (2048, 2048));
+        if image.is_none() && !path.is_empty() {
+            tracing::warn!("Failed to load background image from path: {}", path);
+        }
+        for ows in self.output_workspaces.values() {
+            for workspace in &ows.workspace_views {
+                workspace
+                    .background_view
+                    .set_background(image.clone(), color);
+            }
+        }
+    }
+
+    /// Rebuild the views that hold theme colors after the scheme, accent or
+    /// theme file changed. Context menus and dock previews are built when
+    /// they open and need nothing.
+    pub fn apply_theme(&self) {
+        self.dock.apply_theme();
+        for dock in self.output_docks.values() {
+            dock.apply_theme();
+        }
+        self.app_switcher
+            .view
+            .update_state(&self.app_switcher.view.get_state());
+        self.window_switcher
+            .view
+            .update_state(&self.window_switcher.view.get_state());
+        self.clipboard_picker
+            .view
+            .update_state(&self.clipboard_picker.view.get_state());
+        self.osd.view.update_state(&self.osd.view.get_state());
+        self.workspace_selector_view
+            .view
+            .update_state(&self.workspace_selector_view.view.get_state());
+        for ows in self.output_workspaces.values() {
+            for workspace in &ows.workspace_views {
+                let selector = &workspace.window_selector_view;
+                selector.view.update_state(&selector.view.get_state());
+            }
+        }
+    }
+
+    /// Add a workspace after the last one, optionally named, and persist the
+    /// workspace list.
+    pub fn create_workspace(&mut self, name: Option<String>) -> (usize, Arc<WorkspaceView>) {
+        let (index, workspace) = self.add_workspace();
+        self.rename_workspace(index, name);
+        (index, workspace)
+    }
+
+    /// Set or clear the name of the workspace at `index` on every output.
+    pub fn rename_workspace(&mut self, index: usize, name: Option<String>) {
+        let name = name.filter(|name| !name.is_empty());
+        for ows in self.output_workspaces.values() {
+            if let Some(workspace) = ows.workspace_views.get(index) {
+                workspace.set_name(name.clone());
+            }
+        }
+        self.with_model(|m| self.notify_observers(m));
+        self.save_workspaces_config();
+    }
+
+    /// Position of the workspace named `name`. Fullscreen workspaces, which
+    /// are named after their app, are not considered.
+    pub fn workspace_index_by_name(&self, name: &str) -> Option<usize> {
+        self.primary_output_workspaces()?
+            .workspace_views
+            .iter()
+            .position(|w| !w.get_fullscreen_mode() && w.get_name().as_deref() == Some(name))
+    }
+
+    /// Remove every workspace without windows, except the current one and
+    /// named ones. At least one workspace is always kept.
+    pub fn remove_empty_workspaces(&mut self) {
+        let current = self.get_current_workspace_index();
+        let Some(ows) = self.primary_output_workspaces() else {
+            return;
+        };
+        let empty: Vec<usize> = (0..ows.workspace_views.len())
+            .filter(|&i| {
+                i != current
+                    && ows.workspace_views[i].get_name().is_none()
+                    && self
+                        .output_workspaces
+                        .values()
+                        .all(|o| o.spaces.get(i).is_none_or(|s| s.elements().count() == 0))
+            })
+            .collect();
+        // Highest first, so the remaining indices stay valid
+        for index in empty.into_iter().rev() {
+            self.remove_workspace_at(index);
+        }
+        self.save_workspaces_config();
+    }
+
+    /// Write the workspace count and names to the `[workspaces]` config section.
+    /// Fullscreen workspaces are transient and left out.
+    pub fn save_workspaces_config(&self) {
+        let Some(ows) = self.primary_output_workspaces() else {
+            return;
+        };
+        let workspaces: Vec<&Arc<WorkspaceView>> = ows
+            .workspace_views
+            .iter()
+            .filter(|w| !w.get_fullscreen_mode())
+            .collect();
+        let mut names: Vec<String> = workspaces
+            .iter()
+            .map(|w| w.get_name().unwrap_or_default())
+            .collect();
+        while names.last().is_some_and(|name| name.is_empty()) {
+            names.pop();
+        }
+        crate::config::save_workspaces_config(&crate::config::WorkspacesConfig {
+            count: workspaces.len(),
+            names,
+        });
+    }
+
     pub fn get_next_free_workspace(&mut self) -> (usize, Arc<WorkspaceView>) {
         let current_workspace = self.get_current_workspace_index();
         let num_spaces = self
@@ -3235,8 +3865,14 @@ impl Workspaces {
             .unwrap_or(0);
         if current_workspace < num_spaces.saturating_sub(1) {
             for i in current_workspace + 1..num_spaces {
+                // Named workspaces are kept for the user, even when empty
                 let is_empty = self
                     .primary_output_workspaces()
+                    .filter(|ows| {
+                        ows.workspace_views
+                            .get(i)
+                            .is_some_and(|w| w.get_name().is_none())
+                    })
                     .and_then(|ows| ows.spaces.get(i))
                     .map(|s| s.elements().count() == 0)
                     .unwrap_or(false);
@@ -3256,7 +3892,10 @@ impl Workspaces {
             let sender = self.remove_workspace_sender.clone();
             transaction.on_finish(
                 move |_: &Layer, _: f32| {
-                    let _ = sender.send(index);
+                    let _ = sender.send(WorkspaceRemoval {
+                        index,
+                        persist: false,
+                    });
                 },
                 true,
             );
@@ -3279,12 +3918,15 @@ impl Workspaces {
 
             if n < m.workspaces.len() {
                 m.workspaces.remove(n);
-                if m.current_workspace >= m.workspaces.len() {
-                    m.current_workspace = m.workspaces.len() - 1;
+                if m.current_workspace > n || m.current_workspace >= m.workspaces.len() {
+                    m.current_workspace -= 1;
                 }
             }
             m.clone()
         });
+        // Windows of the removed workspace go to its left neighbor, or to the
+        // right one when removing the first workspace
+        let target_workspace = n.saturating_sub(1);
 
         if n < num_spaces {
             if let Some(ws) = self.get_workspace_at(n) {
@@ -3326,7 +3968,11 @@ impl Workspaces {
                 if n < ows.workspace_views.len() {
                     ows.workspace_views.remove(n);
                 }
-                // Clamp current_workspace so it never exceeds the new spaces length.
+                // Keep current_workspace on the same workspace, and never past the
+                // new spaces length.
+                if ows.current_workspace > n && ows.current_workspace > 0 {
+                    ows.current_workspace -= 1;
+                }
                 if !ows.spaces.is_empty() && ows.current_workspace >= ows.spaces.len() {
                     ows.current_workspace = ows.spaces.len() - 1;
                 }
@@ -3334,18 +3980,18 @@ impl Workspaces {
 
             for (e, location) in windows_to_move {
                 if e.is_fullscreen() {
-                    e.set_fullscreen(false, workspace_model.current_workspace);
-                    if let Some(ws) = self.get_workspace_at(workspace_model.current_workspace) {
+                    e.set_fullscreen(false, target_workspace);
+                    if let Some(ws) = self.get_workspace_at(target_workspace) {
                         ws.set_fullscreen_mode(false);
                         ws.set_fullscreen_animating(false);
                         ws.set_name(None);
                     }
                 }
-                self.move_window_to_workspace(&e, workspace_model.current_workspace, location);
+                self.move_window_to_workspace(&e, target_workspace, location);
             }
 
             if self.get_show_all() {
-                self.expose_update_if_needed_workspace(workspace_model.current_workspace);
+                self.expose_update_if_needed_workspace(target_workspace);
             }
         }
         self.update_workspaces_layout();
@@ -3381,6 +4027,34 @@ impl Workspaces {
         self.with_model(|m| m.current_workspace)
     }
 
+    /// Show the window switcher with the non-minimised windows of the current
+    /// workspace, topmost first. Returns false when there is no window.
+    pub fn show_window_switcher(&self) -> bool {
+        let Some(space) = self.space() else {
+            return false;
+        };
+        let windows: Vec<WindowSwitcherItem> = space
+            .elements()
+            .rev()
+            .filter(|window| !window.is_minimised())
+            .map(WindowSwitcherItem::new)
+            .collect();
+        if windows.is_empty() {
+            return false;
+        }
+        let width = self.with_model(|m| m.width);
+        let scale = self.primary_output_scale() as f32;
+        self.window_switcher.show(windows, width, scale);
+        true
+    }
+
+    /// Show the clipboard picker with `items`, most recent first.
+    pub fn show_clipboard_picker(&self, items: Vec<ClipboardPickerItem>) {
+        let width = self.with_model(|m| m.width);
+        let scale = self.primary_output_scale() as f32;
+        self.clipboard_picker.show(items, width, scale);
+    }
+
     /// Get the top (non-minimized) window of a workspace, or None if the workspace is empty.
     pub fn get_top_window_of_workspace(&self, workspace_index: usize) -> Option<ObjectId> {
         let pows = self.primary_output_workspaces()?;
@@ -3473,16 +4147,21 @@ impl Workspaces {
         self.update_workspace_model();
 
         // Control dock visibility based on target workspace fullscreen state.
-        let resolved_transition = transition.clone().unwrap_or(Transition {
-            delay: 0.0,
-            timing: TimingFunction::Spring(Spring::with_duration_and_bounce(1.0, 0.1)),
-        });
+        let resolved_transition =
+            Config::transition_or_instant(transition.clone().unwrap_or(Transition {
+                delay: 0.0,
+                timing: TimingFunction::Spring(Spring::with_duration_and_bounce(1.0, 0.1)),
+            }));
         if !self.get_show_all() {
-            if let Some(workspace) = self.get_workspace_at(i) {
+            let workspace = self
+                .output_workspaces
+                .get(&name)
+                .and_then(|ows| ows.workspace_views.get(i));
+            if let (Some(workspace), Some(dock)) = (workspace, self.dock_for_output(output)) {
                 if workspace.get_fullscreen_mode() {
-                    self.dock.hide(Some(resolved_transition.clone()));
-                } else if !self.dock.is_autohide_enabled() {
-                    self.dock.show(Some(resolved_transition.clone()));
+                    dock.hide(Some(resolved_transition.clone()));
+                } else if !dock.is_autohide_enabled() {
+                    dock.show(Some(resolved_transition.clone()));
                 }
             }
         }
@@ -3512,10 +4191,10 @@ impl Workspaces {
         // Scroll only this output's layer
         let workspace_gap_px = WORKSPACE_SPACING * scale;
         let offset = i as f32 * (workspace_width + workspace_gap_px);
-        let transition = transition.unwrap_or(Transition {
+        let transition = Config::transition_or_instant(transition.unwrap_or(Transition {
             delay: 0.0,
             timing: TimingFunction::Spring(Spring::with_duration_and_bounce(1.0, 0.1)),
-        });
+        }));
         self.apply_scroll_offset_filtered(offset, Some(transition), Some(&name.clone()))
     }
 
@@ -3547,10 +4226,10 @@ impl Workspaces {
         i: usize,
         transition: Option<Transition>,
     ) -> Option<TransactionRef> {
-        let transition = transition.unwrap_or(Transition {
+        let transition = Config::transition_or_instant(transition.unwrap_or(Transition {
             delay: 0.0,
             timing: TimingFunction::Spring(Spring::with_duration_and_bounce(1.0, 0.1)),
-        });
+        }));
         let x = 0.0_f32;
         if let Some(workspace) = self.get_workspace_at(i) {
             // Control dock visibility based on workspace fullscreen state
@@ -3560,12 +4239,25 @@ impl Workspaces {
             // - expose mode or expose transitions (let expose system control position)
             // - fullscreen animations (let fullscreen transition complete smoothly)
             if !self.get_show_all() {
-                if workspace.get_fullscreen_mode() {
-                    self.dock.hide(Some(transition.clone()));
-                } else if !self.dock.is_autohide_enabled() {
-                    // With autohide on, dock visibility is managed by the hot zone /
-                    // show_autohide(); calling show() would hide it (see DockView::show).
-                    self.dock.show(Some(transition.clone()));
+                for output in &self.outputs {
+                    let Some(dock) = self.dock_for_output(output) else {
+                        continue;
+                    };
+                    let fullscreen = if self.primary_output.as_ref() == Some(output) {
+                        workspace.get_fullscreen_mode()
+                    } else {
+                        self.output_workspaces
+                            .get(&output.name())
+                            .and_then(|ows| ows.workspace_views.get(i))
+                            .is_some_and(|w| w.get_fullscreen_mode())
+                    };
+                    if fullscreen {
+                        dock.hide(Some(transition.clone()));
+                    } else if !dock.is_autohide_enabled() {
+                        // With autohide on, dock visibility is managed by the hot zone /
+                        // show_autohide(); calling show() would hide it (see DockView::show).
+                        dock.show(Some(transition.clone()));
+                    }
                 }
             }
 
@@ -3672,7 +4364,8 @@ impl Workspaces {
         let current_offset = -current_pos.x;
 
         const SWIPE_DAMPENING: f32 = 0.6;
-        let physical_delta = delta_x * scale * SWIPE_DAMPENING;
+        let sensitivity = Config::with(|c| c.input.workspace_swipe_sensitivity) as f32;
+        let physical_delta = delta_x * scale * SWIPE_DAMPENING * sensitivity;
         let workspace_gap_px = WORKSPACE_SPACING * scale;
         let max_offset = (num_workspaces - 1) as f32 * (workspace_width + workspace_gap_px);
 
@@ -3720,10 +4413,16 @@ impl Workspaces {
         }
     }
 
-    /// End workspace swipe gesture and snap to nearest workspace.
-    /// Uses velocity to determine target workspace for natural momentum-based snapping.
+    /// End workspace swipe gesture and snap to the target workspace.
+    /// A fling moves one workspace in its direction; a slow swipe switches only
+    /// past the configured threshold. A cancelled swipe snaps back.
     /// Returns the target workspace index.
-    pub fn workspace_swipe_end(&mut self, output_name: &str, velocity: f32) -> usize {
+    pub fn workspace_swipe_end(
+        &mut self,
+        output_name: &str,
+        velocity: f32,
+        cancelled: bool,
+    ) -> usize {
         let output = self
             .outputs
             .iter()
@@ -3771,26 +4470,26 @@ impl Workspaces {
             .unwrap_or_default();
         let current_offset = -current_pos.x;
 
-        let physical_velocity = velocity * scale;
-        const VELOCITY_THRESHOLD: f32 = 15.0;
-
         let workspace_gap_px = WORKSPACE_SPACING * scale;
         let progress = current_offset / (workspace_width + workspace_gap_px);
+        let threshold = Config::with(|c| c.input.workspace_swipe_threshold) as f32;
 
-        let target_index = if physical_velocity.abs() > VELOCITY_THRESHOLD {
-            if physical_velocity > 0.0 {
-                current_index.saturating_sub(1)
-            } else {
-                (current_index + 1).min(num_workspaces - 1)
-            }
+        let target_index = if cancelled {
+            current_index
         } else {
-            (progress.round() as usize).min(num_workspaces - 1)
+            swipe_target_index(
+                current_index,
+                num_workspaces,
+                progress,
+                velocity * scale,
+                threshold,
+            )
         };
 
-        let transition = Transition {
+        let transition = Config::transition_or_instant(Transition {
             delay: 0.0,
             timing: TimingFunction::Spring(Spring::with_duration_and_bounce(0.5, 0.05)),
-        };
+        });
 
         if let Some(output) = output {
             let _ = self.set_workspace_for_output(&output, target_index, Some(transition));
@@ -3829,6 +4528,7 @@ impl Workspaces {
         if !offset.is_finite() {
             return None;
         }
+        let transition = transition.map(Config::transition_or_instant);
         if let Some(transition) = &transition {
             // Mark as animating
             self.is_animating
@@ -3921,6 +4621,17 @@ impl Workspaces {
         })
     }
 
+    /// Output of the workspace holding `element`, whether or not that
+    /// workspace is shown
+    pub fn output_for_window(&self, element: &WindowElement) -> Option<Output> {
+        let (name, _) = self.output_workspaces.iter().find(|(_, ows)| {
+            ows.spaces
+                .iter()
+                .any(|space| space.elements().any(|e| e.id() == element.id()))
+        })?;
+        self.outputs.iter().find(|o| o.name() == *name).cloned()
+    }
+
     pub fn element_geometry(
         &self,
         we: &WindowElement,
@@ -4079,7 +4790,9 @@ impl UnminimizeContext {
                 return;
             }
 
-            let windows_layer_ref = workspace.windows_layer.clone();
+            let windows_layer_ref = workspace
+                .windows_container(window.is_always_on_top())
+                .clone();
             let expose_windows_ref = expose_layer.clone();
             let layer_ref = view.window_layer.clone();
             let mirror_ref = view.mirror_layer.clone();
@@ -4098,10 +4811,10 @@ impl UnminimizeContext {
             drawer
                 .set_size(
                     Size::points(0.0, 130.0),
-                    Transition {
+                    Config::transition_or_instant(Transition {
                         delay: 0.2,
                         timing: TimingFunction::ease_out_quad(0.3),
-                    },
+                    }),
                 )
                 .on_start(
                     move |_layer: &Layer, _| {
@@ -4144,3 +4857,74 @@ impl Observable<WorkspacesModel> for Workspaces {
         Box::new(self.observers.iter().cloned())
     }
 }
+
+/// Raise the always-on-top windows of a space above the others, keeping
+/// `top` uppermost if it is one of them.
+fn restack_always_on_top(space: &mut Space<WindowElement>, top: Option<&WindowElement>) {
+    let on_top: Vec<WindowElement> = space
+        .elements()
+        .filter(|e| e.is_always_on_top() && Some(*e) != top)
+        .cloned()
+        .collect();
+    for element in &on_top {
+        space.raise_element(element, false);
+    }
+    if let Some(top) = top.filter(|top| top.is_always_on_top()) {
+        space.raise_element(top, false);
+    }
+}
+
+/// Workspace a swipe ending at `progress` (in workspaces from the first one)
+/// with `velocity` (physical pixels per event, positive towards the previous
+/// workspace) settles on.
+fn swipe_target_index(
+    current_index: usize,
+    num_workspaces: usize,
+    progress: f32,
+    velocity: f32,
+    threshold: f32,
+) -> usize {
+    const VELOCITY_THRESHOLD: f32 = 15.0;
+
+    let last = num_workspaces.saturating_sub(1);
+    if velocity.abs() > VELOCITY_THRESHOLD {
+        return if velocity > 0.0 {
+            current_index.saturating_sub(1)
+        } else {
+            (current_index + 1).min(last)
+        };
+    }
+    // Each workspace crossed by more than `threshold` counts
+    let moved = progress - current_index as f32;
+    let steps = (moved.abs() + 1.0 - threshold).floor().max(0.0) as usize;
+    if moved > 0.0 {
+        (current_index + steps).min(last)
+    } else {
+        current_index.saturating_sub(steps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_swipes_switch_past_the_threshold() {
+        assert_eq!(swipe_target_index(1, 3, 1.2, 0.0, 0.3), 1);
+        assert_eq!(swipe_target_index(1, 3, 1.35, 0.0, 0.3), 2);
+        assert_eq!(swipe_target_index(1, 3, 0.75, 0.0, 0.3), 1);
+        assert_eq!(swipe_target_index(1, 3, 0.6, 0.0, 0.3), 0);
+        // Default threshold rounds to the nearest workspace
+        assert_eq!(swipe_target_index(0, 4, 1.6, 0.0, 0.5), 2);
+    }
+
+    #[test]
+    fn flings_move_one_workspace_and_stop_at_the_edges() {
+        assert_eq!(swipe_target_index(1, 3, 1.05, -30.0, 0.5), 2);
+        assert_eq!(swipe_target_index(1, 3, 0.95, 30.0, 0.5), 0);
+        assert_eq!(swipe_target_index(0, 3, -0.2, 30.0, 0.5), 0);
+        assert_eq!(swipe_target_index(2, 3, 2.2, -30.0, 0.5), 2);
+        // Rubber-banding past the last workspace snaps back
+        assert_eq!(swipe_target_index(2, 3, 2.3, 0.0, 0.5), 2);
+    }
+}
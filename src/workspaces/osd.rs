@@ -1,15 +1,35 @@
 use layers::{engine::Engine, prelude::*, skia, types::Size};
 use std::hash::{Hash, Hasher};
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 
-use crate::{theme::theme_colors, utils::resource_image};
+use crate::{
+    config::Config, theme::theme_colors, utils::resource_image, workspaces::utils::FONT_CACHE,
+};
 
 const PROGRESSBAR_STEPS: usize = 16;
+/// Seconds the OSD stays fully visible before fading out (~1.5s on screen in total)
+const OSD_HOLD_SECS: f32 = 1.0;
+/// Distance of the OSD from the bottom edge of the output, in points
+const OSD_BOTTOM_MARGIN: f32 = 140.0;
+
+/// Progress bar steps lit for `percent`; any non-zero value lights at least
+/// one, so only 0 reads as muted
+fn progress_level(percent: u32) -> u8 {
+    (percent.min(100) as usize * PROGRESSBAR_STEPS).div_ceil(100) as u8
+}
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum OsdType {
     Brightness,
     Volume,
+    Microphone,
+    /// Name of the keyboard layout just switched to, shown in place of the level
+    KeyboardLayout,
+    /// Chords typed so far of a multi-key shortcut
+    ShortcutPrefix,
     // Future: Keyboard backlight, etc.
 }
 
@@ -19,9 +39,12 @@ pub struct OsdViewState {
     pub osd_type: OsdType,
     pub level: u8,        // 0-PROGRESSBAR_STEPS
     pub max_level: usize, // Number of squares/bars
+    pub label: String,
     brightness_icon: Option<skia::Image>,
     audio_icon: Option<skia::Image>,
     audio_mute_icon: Option<skia::Image>,
+    mic_icon: Option<skia::Image>,
+    mic_mute_icon: Option<skia::Image>,
 }
 
 impl Hash for OsdViewState {
@@ -31,7 +54,9 @@ impl Hash for OsdViewState {
         std::mem::discriminant(&self.osd_type).hash(state);
         self.level.hash(state);
         self.max_level.hash(state);
+        self.label.hash(state);
         // Note: We don't hash the images as they're loaded once and don't change
+        crate::theme::theme_generation().hash(state);
     }
 }
 
@@ -42,6 +67,10 @@ pub struct OsdView {
     brightness_icon: Option<skia::Image>,
     audio_icon: Option<skia::Image>,
     audio_mute_icon: Option<skia::Image>,
+    mic_icon: Option<skia::Image>,
+    mic_mute_icon: Option<skia::Image>,
+    /// Bumped on every show; stale fade-out callbacks compare against it
+    generation: Arc<AtomicU64>,
 }
 
 impl OsdView {
@@ -71,15 +100,20 @@ impl OsdView {
         let brightness_icon = resource_image("brightness.svg", "display-brightness-symbolic");
         let audio_icon = resource_image("audio.svg", "audio-volume-high-symbolic");
         let audio_mute_icon = resource_image("audio-mute.svg", "audio-volume-muted-symbolic");
+        let mic_icon = resource_image("mic.svg", "audio-input-microphone-symbolic");
+        let mic_mute_icon = resource_image("mic-mute.svg", "microphone-sensitivity-muted-symbolic");
 
         let state = OsdViewState {
             visible: false,
             osd_type: OsdType::Brightness,
             level: PROGRESSBAR_STEPS as u8,
             max_level: PROGRESSBAR_STEPS,
+            label: String::new(),
             brightness_icon: brightness_icon.clone(),
             audio_icon: audio_icon.clone(),
             audio_mute_icon: audio_mute_icon.clone(),
+            mic_icon: mic_icon.clone(),
+            mic_mute_icon: mic_mute_icon.clone(),
         };
 
         let view = View::new("osd_view".to_string(), state, Box::new(view_osd));
@@ -93,39 +127,76 @@ impl OsdView {
             brightness_icon,
             audio_icon,
             audio_mute_icon,
+            mic_icon,
+            mic_mute_icon,
+            generation: Arc::new(AtomicU64::new(0)),
         }
     }
 
-    /// Show brightness indicator
-    pub fn show_brightness(&self, level: u8) {
-        self.view.update_state(&OsdViewState {
-            visible: true,
-            osd_type: OsdType::Brightness,
-            level: level.min(PROGRESSBAR_STEPS as u8),
-            max_level: PROGRESSBAR_STEPS,
-            brightness_icon: self.brightness_icon.clone(),
-            audio_icon: self.audio_icon.clone(),
-            audio_mute_icon: self.audio_mute_icon.clone(),
-        });
-        self.pulse();
+    /// Show brightness indicator, `percent` from 0 to 100
+    pub fn show_brightness(&self, percent: u32) {
+        self.show(OsdType::Brightness, progress_level(percent));
+    }
+
+    /// Show volume indicator, `percent` from 0 to 100 (0 shows the muted icon)
+    pub fn show_volume(&self, percent: u32) {
+        self.show(OsdType::Volume, progress_level(percent));
+    }
+
+    /// Show microphone input level indicator, `percent` from 0 to 100 (0
+    /// shows the muted icon)
+    pub fn show_microphone(&self, percent: u32) {
+        self.show(OsdType::Microphone, progress_level(percent));
+    }
+
+    /// Show the name of the active keyboard layout
+    pub fn show_keyboard_layout(&self, name: String) {
+        self.show_with_label(OsdType::KeyboardLayout, 0, name);
+    }
+
+    /// Show the pending chords of a multi-key shortcut
+    pub fn show_shortcut_prefix(&self, prefix: String) {
+        self.show_with_label(OsdType::ShortcutPrefix, 0, prefix);
     }
 
-    /// Show volume indicator
-    pub fn show_volume(&self, level: u8) {
+    /// Quickly fade out the shortcut prefix once the sequence completes or
+    /// is cancelled. Any other OSD being shown is left alone.
+    pub fn dismiss_shortcut_prefix(&self) {
+        if self.is_visible() && self.view.get_state().osd_type == OsdType::ShortcutPrefix {
+            self.fade_out(0.0, 0.15);
+        }
+    }
+
+    fn show(&self, osd_type: OsdType, level: u8) {
+        self.show_with_label(osd_type, level, String::new());
+    }
+
+    fn show_with_label(&self, osd_type: OsdType, level: u8, label: String) {
         self.view.update_state(&OsdViewState {
             visible: true,
-            osd_type: OsdType::Volume,
+            osd_type,
             level: level.min(PROGRESSBAR_STEPS as u8),
             max_level: PROGRESSBAR_STEPS,
+            label,
             brightness_icon: self.brightness_icon.clone(),
             audio_icon: self.audio_icon.clone(),
             audio_mute_icon: self.audio_mute_icon.clone(),
+            mic_icon: self.mic_icon.clone(),
+            mic_mute_icon: self.mic_mute_icon.clone(),
         });
         self.pulse();
     }
+
+    /// Fade the OSD in, hold it and fade it out.
+    ///
+    /// Pulsing again while visible restarts the hold instead of stacking a
+    /// second fade-out: callbacks of earlier pulses see a newer generation
+    /// and do nothing.
     pub fn pulse(&self) {
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
         self.wrap_layer.set_hidden(false);
         let w = self.wrap_layer.clone();
+        let current = self.generation.clone();
         self.view_layer
             .set_opacity(
                 1.0,
@@ -136,18 +207,24 @@ impl OsdView {
             )
             .on_finish(
                 move |l: &Layer, _| {
+                    if current.load(Ordering::Relaxed) != generation {
+                        return;
+                    }
                     let w = w.clone();
+                    let current = current.clone();
 
                     l.set_opacity(
                         0.0,
                         Some(Transition {
-                            delay: 0.5,
+                            delay: OSD_HOLD_SECS,
                             timing: TimingFunction::ease_out_quad(0.3),
                         }),
                     )
                     .on_finish(
                         move |_l: &Layer, _| {
-                            w.set_hidden(true);
+                            if current.load(Ordering::Relaxed) == generation {
+                                w.set_hidden(true);
+                            }
                         },
                         true,
                     );
@@ -162,19 +239,27 @@ impl OsdView {
 
     /// Hide the OSD
     pub fn hide(&self) {
+        self.fade_out(1.0, 0.4);
+    }
+
+    fn fade_out(&self, delay: f32, duration: f32) {
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        let current = self.generation.clone();
         let w = self.wrap_layer.clone();
 
         self.view_layer
             .set_opacity(
                 0.0,
                 Some(Transition {
-                    delay: 1.0,
-                    timing: TimingFunction::ease_out_quad(0.4),
+                    delay,
+                    timing: TimingFunction::ease_out_quad(duration),
                 }),
             )
             .on_finish(
                 move |_l: &Layer, _| {
-                    w.set_hidden(true);
+                    if current.load(Ordering::Relaxed) == generation {
+                        w.set_hidden(true);
+                    }
                 },
                 true,
             );
@@ -184,18 +269,54 @@ impl OsdView {
 pub fn view_osd(state: &OsdViewState, _view: &View<OsdViewState>) -> LayerTree {
     let level = state.level;
     let max_level = state.max_level;
+    let label = matches!(
+        state.osd_type,
+        OsdType::KeyboardLayout | OsdType::ShortcutPrefix
+    )
+    .then(|| state.label.clone());
 
     // Select icon based on OSD type from state
     let icon_image = match (&state.osd_type, level) {
         (OsdType::Brightness, _) => state.brightness_icon.clone(),
         (OsdType::Volume, 0) => state.audio_mute_icon.clone(),
         (OsdType::Volume, _) => state.audio_icon.clone(),
+        (OsdType::Microphone, 0) => state.mic_mute_icon.clone(),
+        (OsdType::Microphone, _) => state.mic_icon.clone(),
+        (OsdType::KeyboardLayout | OsdType::ShortcutPrefix, _) => None,
     };
 
     // Combined draw function for icon and progress
     let draw_osd_content = move |canvas: &skia::Canvas, w: f32, h: f32| {
         let text_color = theme_colors().text_secondary.c4f();
 
+        if let Some(label) = &label {
+            let font_family = Config::with(|c| c.font_family.clone());
+            let font_style = skia::FontStyle::new(
+                skia::font_style::Weight::SEMI_BOLD,
+                skia::font_style::Width::NORMAL,
+                skia::font_style::Slant::Upright,
+            );
+            let mut text_size = h * 0.18;
+            let mut font = FONT_CACHE.with(|font_cache| {
+                font_cache.make_font_with_fallback(font_family.clone(), font_style, text_size)
+            });
+            let mut paint = skia::Paint::new(text_color, None);
+            paint.set_anti_alias(true);
+            let (_, mut bounds) = font.measure_str(label, Some(&paint));
+            // Shrink long layout names to fit
+            if bounds.width() > w * 0.85 {
+                text_size *= w * 0.85 / bounds.width();
+                font = FONT_CACHE.with(|font_cache| {
+                    font_cache.make_font_with_fallback(font_family, font_style, text_size)
+                });
+                bounds = font.measure_str(label, Some(&paint)).1;
+            }
+            let x = (w - bounds.width()) / 2.0 - bounds.left;
+            let y = (h - bounds.height()) / 2.0 - bounds.top;
+            canvas.draw_str(label, (x, y), &font, &paint);
+            return skia::Rect::from_xywh(0.0, 0.0, w, h);
+        }
+
         // Icon dimensions and position (centered at top)
         let icon_size = h * 0.55;
         let icon_y = h * 0.16;
@@ -262,7 +383,14 @@ pub fn view_osd(state: &OsdViewState, _view: &View<OsdViewState>) -> LayerTree {
         .layout_style(taffy::style::Style {
             display: taffy::style::Display::Flex,
             justify_content: Some(taffy::JustifyContent::Center),
-            align_items: Some(taffy::AlignItems::Center),
+            // centered horizontally, above the dock
+            align_items: Some(taffy::AlignItems::FlexEnd),
+            padding: taffy::Rect {
+                top: taffy::length(0.0),
+                bottom: taffy::length(OSD_BOTTOM_MARGIN * scale_factor),
+                left: taffy::length(0.0),
+                right: taffy::length(0.0),
+            },
             ..Default::default()
         })
         .pointer_events(false)
@@ -285,3 +413,18 @@ pub fn view_osd(state: &OsdViewState, _view: &View<OsdViewState>) -> LayerTree {
         .build()
         .unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_maps_onto_the_progress_bar() {
+        assert_eq!(progress_level(0), 0);
+        assert_eq!(progress_level(1), 1);
+        assert_eq!(progress_level(50), 8);
+        assert_eq!(progress_level(80), 13);
+        assert_eq!(progress_level(100), PROGRESSBAR_STEPS as u8);
+        assert_eq!(progress_level(150), PROGRESSBAR_STEPS as u8);
+    }
+}
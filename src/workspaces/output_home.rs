@@ -0,0 +1,125 @@
+//! Home outputs: the display a window was on when that display was unplugged.
+//!
+//! The windows of an unplugged output move to the primary output and keep a
+//! [`HomeOutput`]. When a display matching it is plugged in again, they go
+//! back to the same workspace and relative position on it.
+
+use smithay::{
+    output::Output,
+    utils::{Logical, Point, Size},
+};
+
+/// Pixels of a window kept on its output when its offset is clamped
+const MIN_VISIBLE: i32 = 64;
+
+/// Vendor, model and serial of a display, read from its EDID
+///
+/// Backends that read the EDID store it in the output's user data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayIdentity {
+    pub vendor: String,
+    pub model: String,
+    pub serial: String,
+}
+
+/// Where a window was before its output went away
+#[derive(Debug, Clone)]
+pub struct HomeOutput {
+    /// EDID identity of the display, when the backend knew it
+    identity: Option<DisplayIdentity>,
+    /// Connector the display was on, matched when the identity is unknown
+    connector: String,
+    /// Workspace index on that output
+    pub workspace: usize,
+    /// Window location relative to the output origin
+    pub offset: Point<i32, Logical>,
+}
+
+impl HomeOutput {
+    pub fn new(output: &Output, workspace: usize, offset: Point<i32, Logical>) -> Self {
+        Self {
+            identity: output.user_data().get::<DisplayIdentity>().cloned(),
+            connector: output.name(),
+            workspace,
+            offset,
+        }
+    }
+
+    /// Whether `output` is the display this home was recorded for
+    pub fn matches(&self, output: &Output) -> bool {
+        self.matches_display(&output.name(), output.user_data().get::<DisplayIdentity>())
+    }
+
+    /// The same EDID identity on any connector, or the same connector when
+    /// either side has no identity
+    fn matches_display(&self, connector: &str, identity: Option<&DisplayIdentity>) -> bool {
+        match (&self.identity, identity) {
+            (Some(home), Some(identity)) => home == identity,
+            _ => self.connector == connector,
+        }
+    }
+}
+
+/// `offset` moved so that a window placed at it keeps at least
+/// `MIN_VISIBLE` pixels on an output of `size`
+pub fn clamp_offset(offset: Point<i32, Logical>, size: Size<i32, Logical>) -> Point<i32, Logical> {
+    let clamp = |value: i32, extent: i32| value.clamp(0, (extent - MIN_VISIBLE).max(0));
+    Point::from((clamp(offset.x, size.w), clamp(offset.y, size.h)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(vendor: &str, model: &str, serial: &str) -> DisplayIdentity {
+        DisplayIdentity {
+            vendor: vendor.to_string(),
+            model: model.to_string(),
+            serial: serial.to_string(),
+        }
+    }
+
+    fn home(connector: &str, identity: Option<DisplayIdentity>) -> HomeOutput {
+        HomeOutput {
+            identity,
+            connector: connector.to_string(),
+            workspace: 0,
+            offset: Point::from((0, 0)),
+        }
+    }
+
+    #[test]
+    fn identity_matches_on_any_connector() {
+        let dell = identity("DEL", "DELL U2720Q", "ABC123");
+        let home = home("DP-1", Some(dell.clone()));
+        assert!(home.matches_display("DP-1", Some(&dell)));
+        assert!(home.matches_display("DP-2", Some(&dell)));
+        assert!(!home.matches_display("DP-1", Some(&identity("DEL", "DELL U2720Q", "XYZ789"))));
+        assert!(!home.matches_display("DP-1", Some(&identity("GSM", "LG HDR 4K", ""))));
+    }
+
+    #[test]
+    fn unknown_identity_matches_on_the_connector() {
+        let home_without = home("HDMI-A-1", None);
+        let lg = identity("GSM", "LG HDR 4K", "");
+        assert!(home_without.matches_display("HDMI-A-1", Some(&lg)));
+        assert!(!home_without.matches_display("HDMI-A-2", Some(&lg)));
+
+        let home_with = home("HDMI-A-1", Some(lg));
+        assert!(home_with.matches_display("HDMI-A-1", None));
+        assert!(!home_with.matches_display("HDMI-A-2", None));
+    }
+
+    #[test]
+    fn offsets_are_kept_on_the_output() {
+        let size = Size::from((1920, 1080));
+        assert_eq!(
+            clamp_offset(Point::from((100, 200)), size),
+            Point::from((100, 200))
+        );
+        assert_eq!(
+            clamp_offset(Point::from((3000, -50)), size),
+            Point::from((1856, 0))
+        );
+    }
+}
@@ -146,6 +146,7 @@ impl PopupOverlayView {
                 crate::surface_style::ContentsGravity::Resize,
                 false,
                 None,
+                (0.0, 0.0),
             );
 
             if let Some(ref parent_id) = wvs.parent_id {
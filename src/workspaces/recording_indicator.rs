@@ -0,0 +1,115 @@
+use layers::{engine::Engine, prelude::*, skia, types::Size};
+use std::sync::Arc;
+
+use crate::{config::Config, workspaces::utils::FONT_CACHE};
+
+/// Indicator size, in points
+const INDICATOR_WIDTH: f32 = 64.0;
+const INDICATOR_HEIGHT: f32 = 24.0;
+/// Distance from the top edge of the output, in points
+const INDICATOR_MARGIN: f32 = 12.0;
+
+/// Red "REC" pill shown at the top center of the primary output while a
+/// screen recording is running.
+///
+/// Built like the microphone indicator: a full-screen, non-interactive
+/// `wrap_layer` attached to the overlay tree holds the pill.
+pub struct RecordingIndicatorView {
+    pub wrap_layer: Layer,
+    pub indicator_layer: Layer,
+}
+
+impl RecordingIndicatorView {
+    pub fn new(layers_engine: Arc<Engine>) -> Self {
+        let wrap = layers_engine.new_layer();
+        wrap.set_key("recording_indicator_container");
+        wrap.set_size(Size::percent(1.0, 1.0), None);
+        wrap.set_pointer_events(false);
+        wrap.set_hidden(true);
+
+        let indicator = layers_engine.new_layer();
+        indicator.set_key("recording_indicator");
+        indicator.set_pointer_events(false);
+        indicator.set_opacity(0.0, None);
+        indicator.set_draw_content(move |canvas: &skia::Canvas, w: f32, h: f32| {
+            let mut background = skia::Paint::new(skia::Color4f::new(0.92, 0.23, 0.2, 0.95), None);
+            background.set_anti_alias(true);
+            canvas.draw_round_rect(skia::Rect::from_wh(w, h), h / 2.0, h / 2.0, &background);
+
+            let mut white = skia::Paint::new(skia::Color4f::new(1.0, 1.0, 1.0, 1.0), None);
+            white.set_anti_alias(true);
+            let dot_radius = h * 0.18;
+            let dot_x = h * 0.55;
+            canvas.draw_circle((dot_x, h / 2.0), dot_radius, &white);
+
+            let text_size = h * 0.5;
+            let font_family = Config::with(|c| c.font_family.clone());
+            let font = FONT_CACHE.with(|font_cache| {
+                font_cache.make_font_with_fallback(font_family, skia::FontStyle::bold(), text_size)
+            });
+            canvas.draw_str(
+                "REC",
+                (dot_x + dot_radius * 2.0, h / 2.0 + text_size * 0.36),
+                &font,
+                &white,
+            );
+            skia::Rect::from_xywh(0.0, 0.0, w, h)
+        });
+        let _ = wrap.add_sublayer(&indicator);
+
+        let view = Self {
+            wrap_layer: wrap,
+            indicator_layer: indicator,
+        };
+        view.set_scale(Config::with(|c| c.screen_scale) as f32);
+        view
+    }
+
+    /// Size the pill for the output it is shown on
+    pub fn set_scale(&self, scale: f32) {
+        self.wrap_layer.set_layout_style(taffy::style::Style {
+            position: taffy::style::Position::Absolute,
+            display: taffy::style::Display::Flex,
+            justify_content: Some(taffy::JustifyContent::Center),
+            align_items: Some(taffy::AlignItems::FlexStart),
+            padding: taffy::Rect {
+                top: taffy::length(INDICATOR_MARGIN * scale),
+                bottom: taffy::length(0.0),
+                left: taffy::length(0.0),
+                right: taffy::length(0.0),
+            },
+            ..Default::default()
+        });
+        self.indicator_layer.set_size(
+            Size::points(INDICATOR_WIDTH * scale, INDICATOR_HEIGHT * scale),
+            None,
+        );
+    }
+
+    /// Fade the indicator in or out
+    pub fn set_visible(&self, visible: bool) {
+        if visible {
+            self.wrap_layer.set_hidden(false);
+            self.indicator_layer
+                .set_opacity(1.0, Some(Transition::ease_out_quad(0.2)));
+        } else {
+            let w = self.wrap_layer.clone();
+            self.indicator_layer
+                .set_opacity(0.0, Some(Transition::ease_out_quad(0.2)))
+                .on_finish(
+                    move |l: &Layer, _| {
+                        // Recording again while fading out
+                        if l.opacity() == 0.0 {
+                            w.set_hidden(true);
+                        }
+                    },
+                    true,
+                );
+        }
+    }
+
+    /// Whether the indicator is currently shown
+    pub fn is_visible(&self) -> bool {
+        !self.wrap_layer.hidden()
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use layers::{
+    engine::Engine,
+    prelude::*,
+    types::{Point as LayerPoint, Size},
+};
+use smithay::utils::{Logical, Point, Rectangle};
+
+/// Minimum pointer travel (logical pixels) before a press turns into a drag.
+const DRAG_THRESHOLD: f64 = 4.0;
+
+/// Pointer-driven selection state for the region selector.
+///
+/// All coordinates are global logical pixels, so a rubber band can freely
+/// span several outputs. The view layer converts to per-output physical
+/// pixels when laying out the overlay.
+#[derive(Debug, Clone, Default)]
+pub struct RegionSelection {
+    anchor: Option<Point<f64, Logical>>,
+    cursor: Point<f64, Logical>,
+    hovered_window: Option<Rectangle<i32, Logical>>,
+    dragging: bool,
+}
+
+impl RegionSelection {
+    pub fn new(cursor: Point<f64, Logical>) -> Self {
+        Self {
+            cursor,
+            ..Default::default()
+        }
+    }
+
+    /// Window bounds under the pointer, used for snapping when not dragging.
+    pub fn set_hovered_window(&mut self, geometry: Option<Rectangle<i32, Logical>>) {
+        self.hovered_window = geometry;
+    }
+
+    pub fn pointer_moved(&mut self, location: Point<f64, Logical>) {
+        self.cursor = location;
+        if let Some(anchor) = self.anchor {
+            let dx = location.x - anchor.x;
+            let dy = location.y - anchor.y;
+            if dx.abs() > DRAG_THRESHOLD || dy.abs() > DRAG_THRESHOLD {
+                self.dragging = true;
+            }
+        }
+    }
+
+    pub fn press(&mut self, location: Point<f64, Logical>) {
+        self.cursor = location;
+        self.anchor = Some(location);
+        self.dragging = false;
+    }
+
+    /// Finish a press/release cycle.
+    ///
+    /// Returns the selected rectangle, or `None` when the click didn't produce
+    /// a usable selection (in which case the selector keeps running).
+    pub fn release(&mut self, free_draw: bool) -> Option<Rectangle<i32, Logical>> {
+        let selection = self.current(free_draw);
+        self.anchor = None;
+        self.dragging = false;
+        selection.filter(|rect| rect.size.w > 0 && rect.size.h > 0)
+    }
+
+    /// The rectangle currently highlighted by the overlay.
+    ///
+    /// While dragging this is the rubber band; otherwise it snaps to the window
+    /// under the pointer unless `free_draw` (Shift) is held.
+    pub fn current(&self, free_draw: bool) -> Option<Rectangle<i32, Logical>> {
+        match self.anchor {
+            Some(anchor) if self.dragging => Some(rect_from_points(anchor, self.cursor)),
+            _ if free_draw => None,
+            _ => self.hovered_window,
+        }
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+}
+
+/// Normalized rectangle spanning two corner points, in either drag direction.
+fn rect_from_points(a: Point<f64, Logical>, b: Point<f64, Logical>) -> Rectangle<i32, Logical> {
+    let x0 = a.x.min(b.x).round() as i32;
+    let y0 = a.y.min(b.y).round() as i32;
+    let x1 = a.x.max(b.x).round() as i32;
+    let y1 = a.y.max(b.y).round() as i32;
+    Rectangle::new((x0, y0).into(), (x1 - x0, y1 - y0).into())
+}
+
+/// The four dimming rectangles surrounding `hole` inside an output of
+/// `width_px` x `height_px`, as `(x, y, w, h)` in physical pixels
+/// (top, bottom, left, right). Without a hole the top rect covers everything.
+fn dim_rects(width_px: f32, height_px: f32, hole: Option<[f32; 4]>) -> [[f32; 4]; 4] {
+    let Some([hx, hy, hw, hh]) = hole else {
+        return [
+            [0.0, 0.0, width_px, height_px],
+            [0.0; 4],
+            [0.0; 4],
+            [0.0; 4],
+        ];
+    };
+    let bottom_y = hy + hh;
+    let right_x = hx + hw;
+    [
+        [0.0, 0.0, width_px, hy],
+        [0.0, bottom_y, width_px, (height_px - bottom_y).max(0.0)],
+        [0.0, hy, hx, hh],
+        [right_x, hy, (width_px - right_x).max(0.0), hh],
+    ]
+}
+
+/// Output description used to lay out the overlay.
+pub struct RegionOutput {
+    pub name: String,
+    /// Output geometry in global logical pixels.
+    pub geometry: Rectangle<i32, Logical>,
+    pub scale: f64,
+    /// The output's container layer; the overlay is attached on top of it.
+    pub output_layer: Layer,
+}
+
+struct OutputOverlay {
+    container: Layer,
+    dims: [Layer; 4],
+    frame: Layer,
+    geometry: Rectangle<i32, Logical>,
+    scale: f64,
+}
+
+/// Compositor-drawn overlay for interactive region selection.
+///
+/// Each output gets a full-size, non-interactive container with four dimming
+/// rectangles framing the selection and a bordered rubber-band rectangle, so a
+/// selection spanning two monitors is drawn on both. Modeled on
+/// [`crate::workspaces::TilingOverlayView`].
+pub struct RegionSelectorView {
+    layers_engine: Arc<Engine>,
+    overlays: HashMap<String, OutputOverlay>,
+}
+
+impl RegionSelectorView {
+    pub fn new(layers_engine: Arc<Engine>) -> Self {
+        Self {
+            layers_engine,
+            overlays: HashMap::new(),
+        }
+    }
+
+    /// Attach a fresh overlay on top of every given output.
+    pub fn show(&mut self, outputs: Vec<RegionOutput>) {
+        self.hide();
+        for output in outputs {
+            let container = self.layers_engine.new_layer();
+            container.set_key(format!("region_selector_{}", output.name));
+            container.set_layout_style(taffy::style::Style {
+                position: taffy::style::Position::Absolute,
+                ..Default::default()
+            });
+            container.set_size(Size::percent(1.0, 1.0), None);
+            container.set_pointer_events(false);
+
+            let dims = std::array::from_fn(|i| {
+                let dim = self.layers_engine.new_layer();
+                dim.set_key(format!("region_selector_dim_{}_{i}", output.name));
+                dim.set_layout_style(taffy::style::Style {
+                    position: taffy::style::Position::Absolute,
+                    ..Default::default()
+                });
+                dim.set_pointer_events(false);
+                dim.set_background_color(
+                    PaintColor::Solid {
+                        color: Color::new_rgba(0.0, 0.0, 0.0, 0.4),
+                    },
+                    None,
+                );
+                let _ = container.add_sublayer(&dim);
+                dim
+            });
+
+            let scale = output.scale as f32;
+            let frame = self.layers_engine.new_layer();
+            frame.set_key(format!("region_selector_frame_{}", output.name));
+            frame.set_layout_style(taffy::style::Style {
+                position: taffy::style::Position::Absolute,
+                ..Default::default()
+            });
+            frame.set_pointer_events(false);
+            frame.set_border_width(2.0 * scale, None);
+            frame.set_border_color(
+                PaintColor::Solid {
+                    color: Color::new_rgba(1.0, 1.0, 1.0, 0.9),
+                },
+                None,
+            );
+            frame.set_opacity(0.0, None);
+            let _ = container.add_sublayer(&frame);
+
+            let _ = output.output_layer.add_sublayer(&container);
+            self.overlays.insert(
+                output.name,
+                OutputOverlay {
+                    container,
+                    dims,
+                    frame,
+                    geometry: output.geometry,
+                    scale: output.scale,
+                },
+            );
+        }
+        self.update(None);
+    }
+
+    /// Re-layout every output overlay around `selection` (global logical pixels).
+    pub fn update(&self, selection: Option<Rectangle<i32, Logical>>) {
+        for overlay in self.overlays.values() {
+            let scale = overlay.scale as f32;
+            let width_px = overlay.geometry.size.w as f32 * scale;
+            let height_px = overlay.geometry.size.h as f32 * scale;
+
+            let hole = selection
+                .and_then(|rect| rect.intersection(overlay.geometry))
+                .map(|local| {
+                    [
+                        (local.loc.x - overlay.geometry.loc.x) as f32 * scale,
+                        (local.loc.y - overlay.geometry.loc.y) as f32 * scale,
+                        local.size.w as f32 * scale,
+                        local.size.h as f32 * scale,
+                    ]
+                });
+
+            for (layer, [x, y, w, h]) in overlay
+                .dims
+                .iter()
+                .zip(dim_rects(width_px, height_px, hole))
+            {
+                layer.set_position(LayerPoint { x, y }, None);
+                layer.set_size(Size::points(w, h), None);
+            }
+
+            match hole {
+                Some([x, y, w, h]) => {
+                    overlay.frame.set_position(LayerPoint { x, y }, None);
+                    overlay.frame.set_size(Size::points(w, h), None);
+                    overlay.frame.set_opacity(1.0, None);
+                }
+                None => {
+                    overlay.frame.set_opacity(0.0, None);
+                }
+            }
+        }
+    }
+
+    /// Detach all overlays from the scene.
+    pub fn hide(&mut self) {
+        for (_, overlay) in self.overlays.drain() {
+            overlay.container.remove();
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        !self.overlays.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drag_normalizes_in_any_direction() {
+        let mut selection = RegionSelection::new((0.0, 0.0).into());
+        selection.press((300.0, 200.0).into());
+        selection.pointer_moved((100.0, 50.0).into());
+        let rect = selection.release(false).expect("drag produces a selection");
+        assert_eq!(rect, Rectangle::new((100, 50).into(), (200, 150).into()));
+    }
+
+    #[test]
+    fn click_snaps_to_hovered_window_unless_free_draw() {
+        let window = Rectangle::new((10, 10).into(), (400, 300).into());
+        let mut selection = RegionSelection::new((50.0, 50.0).into());
+        selection.set_hovered_window(Some(window));
+
+        selection.press((50.0, 50.0).into());
+        assert_eq!(selection.release(false), Some(window));
+
+        selection.press((50.0, 50.0).into());
+        assert_eq!(selection.release(true), None);
+    }
+
+    #[test]
+    fn small_jitter_does_not_start_a_drag() {
+        let mut selection = RegionSelection::new((0.0, 0.0).into());
+        selection.press((100.0, 100.0).into());
+        selection.pointer_moved((102.0, 101.0).into());
+        assert!(!selection.is_dragging());
+    }
+
+    #[test]
+    fn dim_rects_frame_the_hole() {
+        let [top, bottom, left, right] =
+            dim_rects(1000.0, 800.0, Some([100.0, 200.0, 300.0, 400.0]));
+        assert_eq!(top, [0.0, 0.0, 1000.0, 200.0]);
+        assert_eq!(bottom, [0.0, 600.0, 1000.0, 200.0]);
+        assert_eq!(left, [0.0, 200.0, 100.0, 400.0]);
+        assert_eq!(right, [400.0, 200.0, 600.0, 400.0]);
+    }
+}
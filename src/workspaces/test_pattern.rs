@@ -0,0 +1,116 @@
+use layers::{engine::Engine, prelude::*, skia, types::Size};
+use std::sync::Arc;
+
+/// Steps of the reference ramps, one per 8-bit level
+const STEPS_8BIT: usize = 256;
+/// Ramp colors, top to bottom: gray, red, green, blue
+const RAMPS: [[f32; 3]; 4] = [
+    [1.0, 1.0, 1.0],
+    [1.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0],
+    [0.0, 0.0, 1.0],
+];
+
+/// Output the test pattern is attached to
+pub struct TestPatternOutput {
+    pub name: String,
+    pub width: f32,
+    pub height: f32,
+    /// The output's container layer; the pattern is attached on top of it.
+    pub output_layer: Layer,
+}
+
+/// Full-screen gradient test pattern, to check 10-bit output for banding.
+///
+/// Each ramp is drawn twice: quantized to the 256 levels of 8-bit color on
+/// top, and as a smooth gradient below. On an 8-bit output both halves band
+/// the same way; on a 10-bit one the lower half has four times as many, much
+/// narrower, bands. Gradients are drawn without dithering so the bands show.
+pub struct TestPatternView {
+    layers_engine: Arc<Engine>,
+    layers: Vec<Layer>,
+}
+
+impl TestPatternView {
+    pub fn new(layers_engine: Arc<Engine>) -> Self {
+        Self {
+            layers_engine,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Attach a fresh pattern on top of every given output.
+    pub fn show(&mut self, outputs: Vec<TestPatternOutput>) {
+        self.hide();
+        for output in outputs {
+            let layer = self.layers_engine.new_layer();
+            layer.set_key(format!("test_pattern_{}", output.name));
+            layer.set_layout_style(taffy::style::Style {
+                position: taffy::style::Position::Absolute,
+                ..Default::default()
+            });
+            layer.set_position((0.0, 0.0), None);
+            layer.set_size(Size::points(output.width, output.height), None);
+            layer.set_pointer_events(false);
+            // Draw straight into the output buffer, at its color depth
+            layer.set_image_cached(false);
+            layer.set_draw_content(|canvas: &skia::Canvas, w: f32, h: f32| {
+                draw_pattern(canvas, w, h);
+                skia::Rect::from_xywh(0.0, 0.0, w, h)
+            });
+            let _ = output.output_layer.add_sublayer(&layer);
+            self.layers.push(layer);
+        }
+    }
+
+    /// Detach the pattern from every output.
+    pub fn hide(&mut self) {
+        for layer in self.layers.drain(..) {
+            layer.remove();
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        !self.layers.is_empty()
+    }
+}
+
+fn draw_pattern(canvas: &skia::Canvas, w: f32, h: f32) {
+    canvas.draw_color(skia::Color::BLACK, skia::BlendMode::Src);
+    let band_height = h / RAMPS.len() as f32;
+    for (i, [r, g, b]) in RAMPS.into_iter().enumerate() {
+        let top = i as f32 * band_height;
+        let half = band_height / 2.0;
+
+        // 8-bit reference: one flat rect per level
+        let step_width = w / STEPS_8BIT as f32;
+        let mut step = skia::Paint::default();
+        for level in 0..STEPS_8BIT {
+            let v = level as f32 / (STEPS_8BIT - 1) as f32;
+            step.set_color4f(skia::Color4f::new(r * v, g * v, b * v, 1.0), None);
+            let x = level as f32 * step_width;
+            canvas.draw_rect(skia::Rect::new(x, top, x + step_width, top + half), &step);
+        }
+
+        // Smooth ramp, as precise as the output format allows
+        let colors = [
+            skia::Color::BLACK,
+            skia::Color4f::new(r, g, b, 1.0).to_color(),
+        ];
+        let shader = skia::Shader::linear_gradient(
+            ((0.0, 0.0), (w, 0.0)),
+            colors.as_slice(),
+            None,
+            skia::TileMode::Clamp,
+            None,
+            None,
+        );
+        let mut ramp = skia::Paint::default();
+        ramp.set_shader(shader);
+        ramp.set_dither(false);
+        canvas.draw_rect(
+            skia::Rect::new(0.0, top + half, w, top + band_height),
+            &ramp,
+        );
+    }
+}
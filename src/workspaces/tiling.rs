@@ -0,0 +1,450 @@
+use serde::{Deserialize, Serialize};
+use smithay::utils::{Logical, Point, Rectangle};
+
+/// How a workspace arranges its windows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorkspaceLayout {
+    /// Windows keep the position and size they are given
+    #[default]
+    Floating,
+    /// Each new window splits the focused tile along its longer side
+    TileBsp,
+    /// Equal-width columns, in tile order
+    TileColumns,
+}
+
+impl WorkspaceLayout {
+    pub fn is_tiling(self) -> bool {
+        self != WorkspaceLayout::Floating
+    }
+
+    /// The layout after this one, wrapping back to `Floating`
+    pub fn next(self) -> Self {
+        match self {
+            WorkspaceLayout::Floating => WorkspaceLayout::TileBsp,
+            WorkspaceLayout::TileBsp => WorkspaceLayout::TileColumns,
+            WorkspaceLayout::TileColumns => WorkspaceLayout::Floating,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SplitAxis {
+    /// Children side by side
+    Horizontal,
+    /// Children stacked
+    Vertical,
+}
+
+#[derive(Debug, Clone)]
+enum TileNode<T> {
+    Leaf(T),
+    Split {
+        axis: SplitAxis,
+        first: Box<TileNode<T>>,
+        second: Box<TileNode<T>>,
+    },
+}
+
+/// Binary space partition of a workspace's tiled windows.
+///
+/// The in-order sequence of leaves is the tile order, used directly by the
+/// column layout, so both layouts share the same tree.
+#[derive(Debug, Clone)]
+pub struct TileTree<T> {
+    root: Option<TileNode<T>>,
+}
+
+impl<T> Default for TileTree<T> {
+    fn default() -> Self {
+        Self { root: None }
+    }
+}
+
+impl<T: Clone + PartialEq> TileTree<T> {
+    pub fn contains(&self, id: &T) -> bool {
+        self.leaves().contains(id)
+    }
+
+    /// Tiles in order (left to right, top to bottom for BSP)
+    pub fn leaves(&self) -> Vec<T> {
+        fn collect<T: Clone>(node: &TileNode<T>, out: &mut Vec<T>) {
+            match node {
+                TileNode::Leaf(id) => out.push(id.clone()),
+                TileNode::Split { first, second, .. } => {
+                    collect(first, out);
+                    collect(second, out);
+                }
+            }
+        }
+        let mut out = Vec::new();
+        if let Some(root) = &self.root {
+            collect(root, &mut out);
+        }
+        out
+    }
+
+    /// Add a tile by splitting `focused` (or the last tile) along its longer side.
+    /// The new tile takes the right/bottom half.
+    pub fn insert(&mut self, id: T, focused: Option<&T>, area: Rectangle<i32, Logical>) {
+        if self.root.is_none() {
+            self.root = Some(TileNode::Leaf(id));
+            return;
+        }
+        let target = focused
+            .filter(|f| self.leaves().contains(*f))
+            .cloned()
+            .or_else(|| self.leaves().last().cloned());
+        let Some(target) = target else {
+            return;
+        };
+        let axis = self
+            .layout(WorkspaceLayout::TileBsp, area, 0)
+            .into_iter()
+            .find(|(leaf, _)| *leaf == target)
+            .map(|(_, rect)| {
+                if rect.size.w >= rect.size.h {
+                    SplitAxis::Horizontal
+                } else {
+                    SplitAxis::Vertical
+                }
+            })
+            .unwrap_or(SplitAxis::Horizontal);
+
+        fn split<T: Clone + PartialEq>(
+            node: &mut TileNode<T>,
+            target: &T,
+            id: T,
+            axis: SplitAxis,
+        ) -> Option<T> {
+            match node {
+                TileNode::Leaf(leaf) if leaf == target => {
+                    *node = TileNode::Split {
+                        axis,
+                        first: Box::new(TileNode::Leaf(leaf.clone())),
+                        second: Box::new(TileNode::Leaf(id)),
+                    };
+                    None
+                }
+                TileNode::Leaf(_) => Some(id),
+                TileNode::Split { first, second, .. } => {
+                    split(first, target, id, axis).and_then(|id| split(second, target, id, axis))
+                }
+            }
+        }
+        if let Some(root) = self.root.as_mut() {
+            split(root, &target, id, axis);
+        }
+    }
+
+    /// Remove a tile; its sibling takes over the parent's area.
+    pub fn remove(&mut self, id: &T) -> bool {
+        fn remove<T: PartialEq>(node: TileNode<T>, id: &T) -> Option<TileNode<T>> {
+            match node {
+                TileNode::Leaf(leaf) if leaf == *id => None,
+                TileNode::Leaf(leaf) => Some(TileNode::Leaf(leaf)),
+                TileNode::Split {
+                    axis,
+                    first,
+                    second,
+                } => match (remove(*first, id), remove(*second, id)) {
+                    (Some(first), Some(second)) => Some(TileNode::Split {
+                        axis,
+                        first: Box::new(first),
+                        second: Box::new(second),
+                    }),
+                    (Some(node), None) | (None, Some(node)) => Some(node),
+                    (None, None) => None,
+                },
+            }
+        }
+        if !self.contains(id) {
+            return false;
+        }
+        self.root = self.root.take().and_then(|root| remove(root, id));
+        true
+    }
+
+    /// Drop every tile for which `keep` returns false
+    pub fn retain(&mut self, mut keep: impl FnMut(&T) -> bool) {
+        for id in self.leaves() {
+            if !keep(&id) {
+                self.remove(&id);
+            }
+        }
+    }
+
+    /// Exchange the positions of two tiles
+    pub fn swap(&mut self, a: &T, b: &T) {
+        fn swap<T: Clone + PartialEq>(node: &mut TileNode<T>, a: &T, b: &T) {
+            match node {
+                TileNode::Leaf(leaf) if leaf == a => *leaf = b.clone(),
+                TileNode::Leaf(leaf) if leaf == b => *leaf = a.clone(),
+                TileNode::Leaf(_) => {}
+                TileNode::Split { first, second, .. } => {
+                    swap(first, a, b);
+                    swap(second, a, b);
+                }
+            }
+        }
+        if let Some(root) = self.root.as_mut() {
+            swap(root, a, b);
+        }
+    }
+
+    /// Rectangle of every tile within `area`, separated by `gap` logical pixels
+    pub fn layout(
+        &self,
+        layout: WorkspaceLayout,
+        area: Rectangle<i32, Logical>,
+        gap: i32,
+    ) -> Vec<(T, Rectangle<i32, Logical>)> {
+        match layout {
+            WorkspaceLayout::Floating => Vec::new(),
+            WorkspaceLayout::TileBsp => {
+                let mut out = Vec::new();
+                if let Some(root) = &self.root {
+                    layout_bsp(root, area, gap, &mut out);
+                }
+                out
+            }
+            WorkspaceLayout::TileColumns => {
+                let leaves = self.leaves();
+                let count = leaves.len() as i32;
+                if count == 0 {
+                    return Vec::new();
+                }
+                let width = ((area.size.w - gap * (count - 1)) / count).max(1);
+                leaves
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, id)| {
+                        let i = i as i32;
+                        let x = area.loc.x + i * (width + gap);
+                        // The last column absorbs the rounding remainder
+                        let w = if i == count - 1 {
+                            (area.loc.x + area.size.w - x).max(1)
+                        } else {
+                            width
+                        };
+                        (
+                            id,
+                            Rectangle::new((x, area.loc.y).into(), (w, area.size.h).into()),
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+fn layout_bsp<T: Clone>(
+    node: &TileNode<T>,
+    area: Rectangle<i32, Logical>,
+    gap: i32,
+    out: &mut Vec<(T, Rectangle<i32, Logical>)>,
+) {
+    match node {
+        TileNode::Leaf(id) => out.push((id.clone(), area)),
+        TileNode::Split {
+            axis,
+            first,
+            second,
+        } => {
+            let (first_area, second_area) = match axis {
+                SplitAxis::Horizontal => {
+                    let w = ((area.size.w - gap) / 2).max(1);
+                    (
+                        Rectangle::new(area.loc, (w, area.size.h).into()),
+                        Rectangle::new(
+                            (area.loc.x + w + gap, area.loc.y).into(),
+                            ((area.size.w - w - gap).max(1), area.size.h).into(),
+                        ),
+                    )
+                }
+                SplitAxis::Vertical => {
+                    let h = ((area.size.h - gap) / 2).max(1);
+                    (
+                        Rectangle::new(area.loc, (area.size.w, h).into()),
+                        Rectangle::new(
+                            (area.loc.x, area.loc.y + h + gap).into(),
+                            (area.size.w, (area.size.h - h - gap).max(1)).into(),
+                        ),
+                    )
+                }
+            };
+            layout_bsp(first, first_area, gap, out);
+            layout_bsp(second, second_area, gap, out);
+        }
+    }
+}
+
+/// `area` shrunk by `gap` on every side
+pub fn inset(area: Rectangle<i32, Logical>, gap: i32) -> Rectangle<i32, Logical> {
+    Rectangle::new(
+        (area.loc.x + gap, area.loc.y + gap).into(),
+        (
+            (area.size.w - 2 * gap).max(1),
+            (area.size.h - 2 * gap).max(1),
+        )
+            .into(),
+    )
+}
+
+/// The rectangle closest to `from` in `direction`, weighing misalignment on
+/// the other axis twice as much as distance.
+pub fn neighbor_in_direction<T: Clone>(
+    rects: &[(T, Rectangle<i32, Logical>)],
+    from: Rectangle<i32, Logical>,
+    direction: Direction,
+) -> Option<T> {
+    let center = |r: &Rectangle<i32, Logical>| -> Point<i32, Logical> {
+        (r.loc.x + r.size.w / 2, r.loc.y + r.size.h / 2).into()
+    };
+    let origin = center(&from);
+    rects
+        .iter()
+        .filter_map(|(id, rect)| {
+            let c = center(rect);
+            let (dx, dy) = (c.x - origin.x, c.y - origin.y);
+            let (along, across) = match direction {
+                Direction::Left => (-dx, dy.abs()),
+                Direction::Right => (dx, dy.abs()),
+                Direction::Up => (-dy, dx.abs()),
+                Direction::Down => (dy, dx.abs()),
+            };
+            (along > 0).then_some((along as i64 + 2 * across as i64, id))
+        })
+        .min_by_key(|(score, _)| *score)
+        .map(|(_, id)| id.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area() -> Rectangle<i32, Logical> {
+        Rectangle::new((0, 0).into(), (1000, 600).into())
+    }
+
+    fn rect_of(tree: &TileTree<u32>, layout: WorkspaceLayout, id: u32) -> Rectangle<i32, Logical> {
+        tree.layout(layout, area(), 0)
+            .into_iter()
+            .find(|(leaf, _)| *leaf == id)
+            .map(|(_, r)| r)
+            .unwrap()
+    }
+
+    #[test]
+    fn layouts_cycle_back_to_floating() {
+        let sequence: Vec<_> =
+            std::iter::successors(Some(WorkspaceLayout::Floating), |l| Some(l.next()))
+                .take(4)
+                .collect();
+        assert_eq!(
+            sequence,
+            [
+                WorkspaceLayout::Floating,
+                WorkspaceLayout::TileBsp,
+                WorkspaceLayout::TileColumns,
+                WorkspaceLayout::Floating,
+            ]
+        );
+    }
+
+    #[test]
+    fn bsp_splits_focused_tile_along_longer_side() {
+        let mut tree = TileTree::default();
+        tree.insert(1, None, area());
+        tree.insert(2, Some(&1), area());
+        // 1000x600: side by side
+        assert_eq!(rect_of(&tree, WorkspaceLayout::TileBsp, 1).size.w, 500);
+        assert_eq!(rect_of(&tree, WorkspaceLayout::TileBsp, 2).loc.x, 500);
+
+        // 500x600 tile: stacked
+        tree.insert(3, Some(&2), area());
+        let r2 = rect_of(&tree, WorkspaceLayout::TileBsp, 2);
+        let r3 = rect_of(&tree, WorkspaceLayout::TileBsp, 3);
+        assert_eq!((r2.loc.x, r2.size.h), (500, 300));
+        assert_eq!((r3.loc.x, r3.loc.y), (500, 300));
+        assert_eq!(rect_of(&tree, WorkspaceLayout::TileBsp, 1).size.h, 600);
+    }
+
+    #[test]
+    fn removing_a_tile_gives_its_area_to_the_sibling() {
+        let mut tree = TileTree::default();
+        tree.insert(1, None, area());
+        tree.insert(2, Some(&1), area());
+        tree.insert(3, Some(&2), area());
+        assert!(tree.remove(&2));
+        assert_eq!(
+            rect_of(&tree, WorkspaceLayout::TileBsp, 3),
+            Rectangle::new((500, 0).into(), (500, 600).into())
+        );
+        tree.retain(|id| *id != 1);
+        assert_eq!(rect_of(&tree, WorkspaceLayout::TileBsp, 3), area());
+        assert!(!tree.remove(&42));
+    }
+
+    #[test]
+    fn gaps_separate_tiles() {
+        let mut tree = TileTree::default();
+        tree.insert(1, None, area());
+        tree.insert(2, Some(&1), area());
+        let rects = tree.layout(WorkspaceLayout::TileBsp, inset(area(), 10), 8);
+        assert_eq!(
+            rects[0].1,
+            Rectangle::new((10, 10).into(), (486, 580).into())
+        );
+        assert_eq!(
+            rects[1].1,
+            Rectangle::new((504, 10).into(), (486, 580).into())
+        );
+    }
+
+    #[test]
+    fn columns_follow_tile_order_and_fill_width() {
+        let mut tree = TileTree::default();
+        for id in 1..=3 {
+            tree.insert(id, None, area());
+        }
+        let rects = tree.layout(WorkspaceLayout::TileColumns, area(), 5);
+        let xs: Vec<_> = rects.iter().map(|(id, r)| (*id, r.loc.x)).collect();
+        assert_eq!(xs, vec![(1, 0), (2, 335), (3, 670)]);
+        let last = rects[2].1;
+        assert_eq!(last.loc.x + last.size.w, 1000);
+    }
+
+    #[test]
+    fn swap_exchanges_tiles() {
+        let mut tree = TileTree::default();
+        tree.insert(1, None, area());
+        tree.insert(2, Some(&1), area());
+        tree.swap(&1, &2);
+        assert_eq!(tree.leaves(), vec![2, 1]);
+    }
+
+    #[test]
+    fn neighbor_prefers_aligned_windows() {
+        let rects = vec![
+            (1, Rectangle::new((0, 0).into(), (500, 600).into())),
+            (2, Rectangle::new((500, 0).into(), (500, 300).into())),
+            (3, Rectangle::new((500, 300).into(), (500, 300).into())),
+        ];
+        let from = rects[2].1;
+        assert_eq!(
+            neighbor_in_direction(&rects, from, Direction::Left),
+            Some(1)
+        );
+        assert_eq!(neighbor_in_direction(&rects, from, Direction::Up), Some(2));
+        assert_eq!(neighbor_in_direction(&rects, from, Direction::Right), None);
+    }
+}
@@ -3,33 +3,115 @@ use layers::{
     prelude::*,
     types::{Point, Size},
 };
-use smithay::utils::{Logical, Rectangle};
+use smithay::utils::{Logical, Rectangle, Size as LogicalSize};
 use std::sync::Arc;
 
-/// A window-tiling drop zone a dragged window can snap into.
+/// A window-tiling zone a window can snap into.
 ///
 /// Push a dragged window toward the top-center to maximize it,
 /// or toward the left/right edge to tile it to that half of the screen.
+/// The thirds and quarters are only reachable from the snap shortcuts.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TileZone {
     Maximize,
     LeftHalf,
     RightHalf,
+    LeftThird,
+    RightThird,
+    LeftTwoThirds,
+    RightTwoThirds,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
 }
 
 impl TileZone {
     /// The target rectangle (logical pixels) for this zone within the usable area.
     pub fn target_rect(self, usable: Rectangle<i32, Logical>) -> Rectangle<i32, Logical> {
-        let half_w = usable.size.w / 2;
+        let width = match self {
+            TileZone::Maximize => usable.size.w,
+            TileZone::LeftThird | TileZone::RightThird => usable.size.w / 3,
+            TileZone::LeftTwoThirds | TileZone::RightTwoThirds => usable.size.w * 2 / 3,
+            _ => usable.size.w / 2,
+        };
+        let height = match self {
+            TileZone::TopLeft
+            | TileZone::TopRight
+            | TileZone::BottomLeft
+            | TileZone::BottomRight => usable.size.h / 2,
+            _ => usable.size.h,
+        };
+        let (left, right, top, bottom) = self.tiled_edges();
+        let x = if right && !left {
+            usable.loc.x + usable.size.w - width
+        } else {
+            usable.loc.x
+        };
+        let y = if bottom && !top {
+            usable.loc.y + usable.size.h - height
+        } else {
+            usable.loc.y
+        };
+        Rectangle::new((x, y).into(), (width, height).into())
+    }
+
+    /// Which screen edges (left, right, top, bottom) the zone is attached to.
+    pub fn tiled_edges(self) -> (bool, bool, bool, bool) {
         match self {
-            TileZone::Maximize => usable,
-            TileZone::LeftHalf => Rectangle::new(usable.loc, (half_w, usable.size.h).into()),
-            TileZone::RightHalf => Rectangle::new(
-                (usable.loc.x + usable.size.w - half_w, usable.loc.y).into(),
-                (half_w, usable.size.h).into(),
-            ),
+            TileZone::Maximize => (true, true, true, true),
+            TileZone::LeftHalf | TileZone::LeftThird | TileZone::LeftTwoThirds => {
+                (true, false, true, true)
+            }
+            TileZone::RightHalf | TileZone::RightThird | TileZone::RightTwoThirds => {
+                (false, true, true, true)
+            }
+            TileZone::TopLeft => (true, false, true, false),
+            TileZone::TopRight => (false, true, true, false),
+            TileZone::BottomLeft => (true, false, false, true),
+            TileZone::BottomRight => (false, true, false, true),
         }
     }
+
+    /// The zone to snap to when this zone is requested again while the window
+    /// is in `current`: halves cycle half → third → two-thirds.
+    pub fn cycle_from(self, current: Option<TileZone>) -> TileZone {
+        match (self, current) {
+            (TileZone::LeftHalf, Some(TileZone::LeftHalf)) => TileZone::LeftThird,
+            (TileZone::LeftHalf, Some(TileZone::LeftThird)) => TileZone::LeftTwoThirds,
+            (TileZone::RightHalf, Some(TileZone::RightHalf)) => TileZone::RightThird,
+            (TileZone::RightHalf, Some(TileZone::RightThird)) => TileZone::RightTwoThirds,
+            (zone, _) => zone,
+        }
+    }
+
+    /// Shrink or grow `target` to respect the window's size hints (a 0
+    /// component means unconstrained), keeping it attached to the zone's edges.
+    pub fn constrain_rect(
+        self,
+        target: Rectangle<i32, Logical>,
+        min: LogicalSize<i32, Logical>,
+        max: LogicalSize<i32, Logical>,
+    ) -> Rectangle<i32, Logical> {
+        let clamp = |value: i32, min: i32, max: i32| {
+            let value = if max > 0 { value.min(max) } else { value };
+            value.max(min)
+        };
+        let width = clamp(target.size.w, min.w, max.w);
+        let height = clamp(target.size.h, min.h, max.h);
+        let (left, right, top, bottom) = self.tiled_edges();
+        let x = if right && !left {
+            target.loc.x + target.size.w - width
+        } else {
+            target.loc.x
+        };
+        let y = if bottom && !top {
+            target.loc.y + target.size.h - height
+        } else {
+            target.loc.y
+        };
+        Rectangle::new((x, y).into(), (width, height).into())
+    }
 }
 
 /// Classify a pointer position (logical) within the usable output area into a
@@ -162,3 +244,55 @@ impl TilingOverlayView {
         !self.wrap_layer.hidden()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usable() -> Rectangle<i32, Logical> {
+        Rectangle::new((0, 30).into(), (1200, 800).into())
+    }
+
+    #[test]
+    fn thirds_and_quarters_attach_to_their_edges() {
+        assert_eq!(
+            TileZone::RightThird.target_rect(usable()),
+            Rectangle::new((800, 30).into(), (400, 800).into())
+        );
+        assert_eq!(
+            TileZone::LeftTwoThirds.target_rect(usable()),
+            Rectangle::new((0, 30).into(), (800, 800).into())
+        );
+        assert_eq!(
+            TileZone::BottomRight.target_rect(usable()),
+            Rectangle::new((600, 430).into(), (600, 400).into())
+        );
+    }
+
+    #[test]
+    fn repeated_snap_cycles_half_third_two_thirds() {
+        let mut zone = TileZone::LeftHalf.cycle_from(None);
+        assert_eq!(zone, TileZone::LeftHalf);
+        zone = TileZone::LeftHalf.cycle_from(Some(zone));
+        assert_eq!(zone, TileZone::LeftThird);
+        zone = TileZone::LeftHalf.cycle_from(Some(zone));
+        assert_eq!(zone, TileZone::LeftTwoThirds);
+        zone = TileZone::LeftHalf.cycle_from(Some(zone));
+        assert_eq!(zone, TileZone::LeftHalf);
+        assert_eq!(
+            TileZone::RightHalf.cycle_from(Some(TileZone::LeftHalf)),
+            TileZone::RightHalf
+        );
+    }
+
+    #[test]
+    fn size_hints_keep_zone_edges() {
+        let target = TileZone::RightThird.target_rect(usable());
+        let constrained =
+            TileZone::RightThird.constrain_rect(target, (500, 0).into(), (0, 600).into());
+        assert_eq!(
+            constrained,
+            Rectangle::new((700, 30).into(), (500, 600).into())
+        );
+    }
+}
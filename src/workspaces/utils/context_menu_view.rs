@@ -15,8 +15,6 @@ use smithay::utils::IsAlive;
 
 use crate::theme::theme_colors;
 
-use crate::config::Config;
-
 /// A compositor-integrated context menu view
 ///
 /// Wraps the otto-kit `ContextMenuState` + `ContextMenuRenderer` components
@@ -87,10 +85,16 @@ impl ContextMenuView {
         }
     }
 
+    /// Set which point of the menu lands on the position given to `show_at`,
+    /// as a fraction of its size; the bottom center by default.
+    pub fn set_anchor_point(&self, x: f32, y: f32) {
+        self.view_layer.set_anchor_point((x, y), None);
+    }
+
     /// Show the context menu at the given position with a fade-in animation
     pub fn show_at(&self, x: f32, y: f32) {
         println!("Showing context menu at ({}, {})", x, y);
-        let scale = Config::with(|c| c.screen_scale) as f32;
+        let scale = self.view.get_state().style.draw_scale;
         self.view_layer.set_position(
             Point {
                 x: x * scale,
@@ -267,9 +271,8 @@ impl ContextMenuView {
 ///
 /// Produces a `LayerTree` with one layer per depth level, laid out horizontally.
 fn render_menu(state: &ContextMenuState, _view: &View<ContextMenuState>) -> LayerTree {
-    let draw_scale = Config::with(|c| c.screen_scale) as f32;
-    let mut style = state.style.clone();
-    style.draw_scale = draw_scale;
+    let style = state.style.clone();
+    let draw_scale = style.draw_scale;
 
     let mut children = Vec::new();
     let mut x_offset = 0.0_f32;
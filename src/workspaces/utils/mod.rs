@@ -220,6 +220,7 @@ pub fn configure_surface_layer(
     gravity: crate::surface_style::ContentsGravity,
     client_owns_size: bool,
     shared_gravity: Option<std::sync::Arc<std::sync::atomic::AtomicU8>>,
+    translation: (f32, f32),
 ) {
     use crate::surface_style::ContentsGravity;
 
@@ -245,8 +246,8 @@ pub fn configure_surface_layer(
 
         let anchor_point = layer.anchor_point();
         let adjusted_pos = Point {
-            x: pos_x + (wvs.phy_dst_w * anchor_point.x),
-            y: pos_y + (wvs.phy_dst_h * anchor_point.y),
+            x: pos_x + (wvs.phy_dst_w * anchor_point.x) + translation.0,
+            y: pos_y + (wvs.phy_dst_h * anchor_point.y) + translation.1,
         };
         layer.set_position(adjusted_pos, None);
     }
@@ -258,6 +259,19 @@ pub fn configure_surface_layer(
     // the cache (lay-rs only swaps the closure); the closure's returned
     // damage rect is the source of truth for partial repaint.
     layer.set_picture_cached(true);
+
+    // Single-pixel buffer: a solid rect, stretched to the layer like a
+    // texture would be
+    if let Some([r, g, b, a]) = wvs.solid_color {
+        layer.set_content_opaque(a >= 1.0);
+        layer.set_draw_content(move |canvas: &layers::skia::Canvas, w: f32, h: f32| {
+            let rect = layers::skia::Rect::from_xywh(0.0, 0.0, w, h);
+            let paint = layers::skia::Paint::new(layers::skia::Color4f::new(r, g, b, a), None);
+            canvas.draw_rect(rect, &paint);
+            rect
+        });
+        return;
+    }
     layer.set_content_opaque(true);
 
     let draw_wvs = wvs.clone();
@@ -312,18 +326,33 @@ pub fn configure_surface_layer(
         };
 
         // Convert buffer-pixel damage to layer-local coords using the same
-        // scale + offset that the texture is drawn with.
+        // viewport crop, scale and offset that the texture is drawn with.
         let mut damage = layers::skia::Rect::default();
         if let Some(tex_damage) = tex.damage {
-            tex_damage.iter().for_each(|bd| {
-                let r = layers::skia::Rect::from_xywh(
-                    bd.loc.x as f32 * scale_x + tx,
-                    bd.loc.y as f32 * scale_y + ty,
-                    bd.size.w as f32 * scale_x,
-                    bd.size.h as f32 * scale_y,
+            if draw_wvs.transform == Transform::Normal {
+                let src = layers::skia::Rect::from_xywh(
+                    draw_wvs.phy_src_x,
+                    draw_wvs.phy_src_y,
+                    src_w,
+                    src_h,
                 );
-                damage.join(r);
-            });
+                tex_damage.iter().for_each(|bd| {
+                    let buffer_damage = layers::skia::Rect::from_xywh(
+                        bd.loc.x as f32,
+                        bd.loc.y as f32,
+                        bd.size.w as f32,
+                        bd.size.h as f32,
+                    );
+                    if let Some(r) =
+                        buffer_damage_to_layer(buffer_damage, src, (scale_x, scale_y), (tx, ty))
+                    {
+                        damage.join(r);
+                    }
+                });
+            } else if !tex_damage.is_empty() {
+                // Rotated and flipped buffers: repaint the whole surface
+                damage = layers::skia::Rect::from_xywh(0.0, 0.0, w, h);
+            }
         }
 
         let mut matrix = layers::skia::Matrix::new_identity();
@@ -366,10 +395,57 @@ pub fn configure_surface_layer(
     });
 }
 
+/// Map a damaged rectangle of the buffer into layer-local coordinates.
+///
+/// The buffer is cropped to the viewport source `src`, then scaled by `scale`
+/// and moved by `offset`. Damage outside the source is not visible and
+/// returns `None`.
+fn buffer_damage_to_layer(
+    damage: layers::skia::Rect,
+    src: layers::skia::Rect,
+    scale: (f32, f32),
+    offset: (f32, f32),
+) -> Option<layers::skia::Rect> {
+    let left = damage.left.max(src.left);
+    let top = damage.top.max(src.top);
+    let right = damage.right.min(src.right);
+    let bottom = damage.bottom.min(src.bottom);
+    if left >= right || top >= bottom {
+        return None;
+    }
+    Some(layers::skia::Rect::from_ltrb(
+        (left - src.left) * scale.0 + offset.0,
+        (top - src.top) * scale.1 + offset.1,
+        (right - src.left) * scale.0 + offset.0,
+        (bottom - src.top) * scale.1 + offset.1,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn damage_follows_viewport_crop_and_scale() {
+        // A 1.5x client: 300x300 buffer with a viewport source at (100, 50)
+        // of 150x150, shown at 100x100 layer points
+        let src = layers::skia::Rect::from_xywh(100.0, 50.0, 150.0, 150.0);
+        let scale = (100.0 / 150.0, 100.0 / 150.0);
+        let damage = layers::skia::Rect::from_xywh(130.0, 80.0, 30.0, 30.0);
+        let r = buffer_damage_to_layer(damage, src, scale, (0.0, 0.0)).unwrap();
+        assert!((r.left - 20.0).abs() < 1e-4 && (r.top - 20.0).abs() < 1e-4);
+        assert!((r.width() - 20.0).abs() < 1e-4 && (r.height() - 20.0).abs() < 1e-4);
+
+        // Damage straddling the crop is clipped to it
+        let damage = layers::skia::Rect::from_xywh(0.0, 0.0, 130.0, 80.0);
+        let r = buffer_damage_to_layer(damage, src, (1.0, 1.0), (5.0, 5.0)).unwrap();
+        assert_eq!(r, layers::skia::Rect::from_xywh(5.0, 5.0, 30.0, 30.0));
+
+        // Damage outside the crop is invisible
+        let damage = layers::skia::Rect::from_xywh(0.0, 0.0, 50.0, 50.0);
+        assert!(buffer_damage_to_layer(damage, src, (1.0, 1.0), (0.0, 0.0)).is_none());
+    }
+
     fn make_test_cache() -> FontCache {
         let font_mgr = layers::skia::FontMgr::new();
         let type_face_font_provider = layers::skia::textlayout::TypefaceFontProvider::new();
@@ -24,6 +24,11 @@ use super::{utils::FONT_CACHE, WORKSPACE_SELECTOR_PREVIEW_WIDTH};
 // Logical (unscaled) values - will be multiplied by screen scale when used
 const WINDOW_SELECTOR_DRAG_THRESHOLD_LOGICAL: f32 = 1.5;
 const WORKSPACE_SELECTOR_TARGET_Y_LOGICAL: f32 = 200.0;
+/// Opacity of previews that do not match the typed filter
+const FILTER_DIMMED_OPACITY: f32 = 0.25;
+/// Vertical center of the typed filter, between the workspace selector and
+/// the previews (physical pixels, like the expose layout)
+const FILTER_LABEL_Y: f32 = 340.0;
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct WindowSelection {
@@ -32,6 +37,7 @@ pub struct WindowSelection {
     pub w: f32,
     pub h: f32,
     pub window_title: String,
+    pub app_id: String,
     pub index: usize,
     pub window_id: Option<ObjectId>,
 }
@@ -40,6 +46,8 @@ pub struct WindowSelection {
 pub struct WindowSelectorState {
     pub rects: Vec<WindowSelection>,
     pub current_selection: Option<usize>,
+    /// Text typed to filter the previews
+    pub filter: String,
 }
 
 impl Hash for WindowSelectorState {
@@ -50,6 +58,8 @@ impl Hash for WindowSelectorState {
             .map(|x| self.rects.get(*x).unwrap());
         current.hash(state);
         self.rects.hash(state);
+        self.filter.hash(state);
+        crate::theme::theme_generation().hash(state);
     }
 }
 
@@ -68,6 +78,7 @@ pub struct WindowSelectorWindow {
     pub id: ObjectId,
     pub rect: LayoutRect,
     pub title: String,
+    pub app_id: String,
 }
 
 #[derive(Clone, Debug)]
@@ -158,6 +169,7 @@ impl WindowSelectorView {
         let state = WindowSelectorState {
             rects: vec![],
             current_selection: None,
+            filter: String::new(),
         };
         let view = layers::prelude::View::new(
             format!("window_selector_view_{}", index),
@@ -242,6 +254,63 @@ impl WindowSelectorView {
         }
     }
 
+    /// Text typed to filter the previews
+    pub fn filter(&self) -> String {
+        self.view.get_state().filter.clone()
+    }
+
+    /// Filter the previews by window title and app id: previews that do not
+    /// match are dimmed and the best match is selected, so confirming
+    /// focuses it. An empty filter shows every preview again.
+    pub fn set_filter(&self, filter: &str) {
+        let mut state = self.view.get_state();
+        state.filter = filter.to_string();
+
+        let scores: Vec<Option<u8>> = state
+            .rects
+            .iter()
+            .map(|rect| filter_score(filter, &rect.window_title, &rect.app_id))
+            .collect();
+        {
+            let windows = self.windows.read().unwrap();
+            for (rect, score) in state.rects.iter().zip(&scores) {
+                let Some(layer) = rect.window_id.as_ref().and_then(|id| windows.get(id)) else {
+                    continue;
+                };
+                let opacity = if score.is_some() {
+                    1.0
+                } else {
+                    FILTER_DIMMED_OPACITY
+                };
+                layer.set_opacity(opacity, Some(Transition::ease_out_quad(0.15)));
+            }
+        }
+
+        if filter.is_empty() {
+            self.view.update_state(&state);
+            return;
+        }
+        // On a tie the topmost preview wins
+        let best = scores
+            .iter()
+            .enumerate()
+            .filter_map(|(index, score)| score.map(|score| (score, index)))
+            .max()
+            .map(|(_, index)| index);
+        state.current_selection = best;
+        self.view.update_state(&state);
+        if let Some(window_id) = best.and_then(|index| state.rects[index].window_id.clone()) {
+            self.bring_window_to_front(&window_id);
+        }
+    }
+
+    /// Drop the typed filter and undim every preview
+    pub fn clear_filter(&self) {
+        if !self.view.get_state().filter.is_empty() {
+            self.set_filter("");
+        }
+    }
+
     /// Invalidate the cached layout so the next `update_windows` call forces a fresh
     /// natural layout recalculation regardless of whether the window set changed.
     pub fn invalidate_layout(&self) {
@@ -571,6 +640,55 @@ pub fn get_paragraph_for_text(text: &str, font_size: f32) -> skia::textlayout::P
     paragraph
 }
 
+/// The typed filter in a pill centered horizontally at `FILTER_LABEL_Y`
+fn draw_filter_label(canvas: &skia::Canvas, w: f32, filter: &str, font_size: f32, scale: f32) {
+    let mut paragraph = get_paragraph_for_text(filter, font_size);
+    paragraph.layout(w);
+    let text_width = paragraph.longest_line();
+    let padding_x = 16.0 * scale;
+    let padding_y = 8.0 * scale;
+    let pill = skia::Rect::from_xywh(
+        (w - text_width) / 2.0 - padding_x,
+        FILTER_LABEL_Y - paragraph.height() / 2.0 - padding_y,
+        text_width + padding_x * 2.0,
+        paragraph.height() + padding_y * 2.0,
+    );
+    let mut paint = skia::Paint::new(skia::Color4f::new(1.0, 1.0, 1.0, 0.7), None);
+    paint.set_anti_alias(true);
+    let radius = pill.height() / 2.0;
+    canvas.draw_round_rect(pill, radius, radius, &paint);
+    paragraph.paint(canvas, (0.0, FILTER_LABEL_Y - paragraph.height() / 2.0));
+}
+
+/// How well `filter` matches a window, higher is better, or `None` when it
+/// does not match. Case-insensitive; a title or app id starting with the
+/// filter beats one with a word starting with it, which beats a match
+/// anywhere. An empty filter matches everything.
+fn filter_score(filter: &str, title: &str, app_id: &str) -> Option<u8> {
+    let filter = filter.trim().to_lowercase();
+    if filter.is_empty() {
+        return Some(0);
+    }
+    [title, app_id]
+        .iter()
+        .filter_map(|field| {
+            let field = field.to_lowercase();
+            if field.starts_with(&filter) {
+                Some(3)
+            } else if field
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|word| word.starts_with(&filter))
+            {
+                Some(2)
+            } else if field.contains(&filter) {
+                Some(1)
+            } else {
+                None
+            }
+        })
+        .max()
+}
+
 pub fn view_window_selector(
     state: &WindowSelectorState,
     view: &View<WindowSelectorState>,
@@ -600,8 +718,12 @@ pub fn view_window_selector(
     let window_selection = current
         .as_ref()
         .map(|(window_selection, _)| window_selection.clone());
+    let filter = state.filter.clone();
 
     let draw_container = Some(move |canvas: &skia::Canvas, w, h| {
+        if !filter.is_empty() {
+            draw_filter_label(canvas, w, &filter, font_size * 1.4, draw_scale);
+        }
         if let Some(window_selection) = window_selection.as_ref() {
             let color = crate::theme::accent_color().c4f();
             let mut paint = skia::Paint::new(color, None);
@@ -764,9 +886,11 @@ impl WindowSelectorView {
             *stored_hash = layout_hash;
         }
 
+        let filter = self.filter();
         let mut state = WindowSelectorState {
             rects: vec![],
             current_selection: None,
+            filter: String::new(),
         };
 
         for (index, window) in windows.iter().enumerate() {
@@ -781,6 +905,7 @@ impl WindowSelectorView {
                     w: window.rect.width * scale,
                     h: window.rect.height * scale,
                     window_title: window.title.clone(),
+                    app_id: window.app_id.clone(),
                     index,
                     window_id: Some(window.id.clone()),
                 });
@@ -788,6 +913,12 @@ impl WindowSelectorView {
         }
 
         self.view.update_state(&state);
+        // Apply the filter to windows that appeared or changed title
+        drop(bin);
+        drop(stored_hash);
+        if !filter.is_empty() {
+            self.set_filter(&filter);
+        }
     }
 }
 impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WindowSelectorView {
@@ -935,8 +1066,8 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WindowSelecto
         }
 
         self.view.update_state(&WindowSelectorState {
-            rects: state.rects,
             current_selection: hovered_selection,
+            ..state
         });
     }
     fn on_button(
@@ -1016,6 +1147,7 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WindowSelecto
                                     target_pos,
                                     position,
                                 );
+                                otto.refresh_foreign_toplevel(&window_element.id());
                                 // Refresh expose view
                                 otto.workspaces.expose_set_visible(true);
                             } else {
@@ -1068,3 +1200,23 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WindowSelecto
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_matches_title_or_app_id_case_insensitively() {
+        assert_eq!(filter_score("FIRE", "Mozilla Firefox", "firefox"), Some(3));
+        assert_eq!(filter_score("moz", "Mozilla Firefox", "firefox"), Some(3));
+        assert_eq!(filter_score("term", "~/src", "org.gnome.Terminal"), Some(2));
+        assert_eq!(filter_score("fox", "Mozilla Firefox", "firefox"), Some(1));
+        assert_eq!(filter_score("code", "Mozilla Firefox", "firefox"), None);
+    }
+
+    #[test]
+    fn empty_filter_matches_everything() {
+        assert_eq!(filter_score("", "Files", "nautilus"), Some(0));
+        assert_eq!(filter_score("  ", "Files", "nautilus"), Some(0));
+    }
+}
@@ -0,0 +1,6 @@
+mod model;
+mod render;
+mod view;
+
+pub use model::WindowSwitcherItem;
+pub use view::WindowSwitcherView;
@@ -0,0 +1,73 @@
+use std::hash::{Hash, Hasher};
+
+use layers::engine::NodeRef;
+use smithay::reexports::wayland_server::backend::ObjectId;
+
+use crate::shell::WindowElement;
+
+/// A window shown in the switcher, mirrored from its scene layer.
+#[derive(Debug, Clone)]
+pub struct WindowSwitcherItem {
+    pub wid: ObjectId,
+    pub title: String,
+    pub node: NodeRef,
+    /// Physical size of the window layer.
+    pub size: (f32, f32),
+}
+
+impl WindowSwitcherItem {
+    pub fn new(window: &WindowElement) -> Self {
+        let size = window.base_layer().render_size();
+        Self {
+            wid: window.id(),
+            title: window.xdg_title(),
+            node: window.base_layer().id(),
+            size: (size.x.max(1.0), size.y.max(1.0)),
+        }
+    }
+}
+
+impl Hash for WindowSwitcherItem {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.wid.hash(state);
+        self.title.hash(state);
+        self.node.0.hash(state);
+        self.size.0.to_bits().hash(state);
+        self.size.1.to_bits().hash(state);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WindowSwitcherModel {
+    /// Windows of the current workspace, topmost first.
+    pub windows: Vec<WindowSwitcherItem>,
+    pub current: usize,
+    /// Thumbnail under the pointer, if any.
+    pub hovered: Option<usize>,
+    /// The physical width of the output the switcher is shown on.
+    pub width: i32,
+    /// Scale of the output the switcher is shown on.
+    pub scale: f32,
+}
+
+impl Hash for WindowSwitcherModel {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.windows.hash(state);
+        self.current.hash(state);
+        self.hovered.hash(state);
+        self.width.hash(state);
+        self.scale.to_bits().hash(state);
+        crate::theme::theme_generation().hash(state);
+    }
+}
+
+impl WindowSwitcherModel {
+    /// Index `steps` away from the current window, wrapping around.
+    pub fn step(&self, steps: isize) -> usize {
+        if self.windows.is_empty() {
+            return 0;
+        }
+        let len = self.windows.len() as isize;
+        (self.current as isize + steps).rem_euclid(len) as usize
+    }
+}
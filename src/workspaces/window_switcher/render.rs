@@ -0,0 +1,262 @@
+use layers::{
+    prelude::*,
+    types::{BlendMode, BorderRadius, Size},
+};
+
+use crate::{config::Config, theme::theme_colors, workspaces::utils::FONT_CACHE};
+
+use super::model::WindowSwitcherModel;
+
+/// Largest thumbnail, in logical pixels.
+const THUMBNAIL_MAX_WIDTH: f32 = 240.0;
+const THUMBNAIL_MAX_HEIGHT: f32 = 160.0;
+/// Space around and between the thumbnails, in logical pixels.
+const PADDING: f32 = 16.0;
+/// Size of the titles under the thumbnails, in logical pixels.
+const TITLE_SIZE: f32 = 13.0;
+/// Space kept free on each side of the panel, in logical pixels.
+const SCREEN_MARGIN: f32 = 40.0;
+
+/// Position of a thumbnail in the panel, in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Slot {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// Scale applied to the window layer to fit the slot.
+    pub fit: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitcherLayout {
+    pub width: f32,
+    pub height: f32,
+    pub slots: Vec<Slot>,
+    pub padding: f32,
+    pub title_size: f32,
+}
+
+/// Lay the thumbnails out in a row, each fitted in the largest thumbnail
+/// size and all shrunk together when the row is wider than the output.
+pub fn layout_thumbnails(sizes: &[(f32, f32)], scale: f32, output_width: f32) -> SwitcherLayout {
+    let padding = PADDING * scale;
+    let title_size = TITLE_SIZE * scale;
+    let fits: Vec<f32> = sizes
+        .iter()
+        .map(|(w, h)| (THUMBNAIL_MAX_WIDTH * scale / w).min(THUMBNAIL_MAX_HEIGHT * scale / h))
+        .collect();
+    let row_width: f32 = sizes.iter().zip(fits.iter()).map(|((w, _), f)| w * f).sum();
+    let gaps = padding * (sizes.len() as f32 + 1.0);
+    let available = (output_width - SCREEN_MARGIN * scale * 2.0 - gaps).max(1.0);
+    let shrink = if row_width > available {
+        available / row_width
+    } else {
+        1.0
+    };
+
+    let row_height = THUMBNAIL_MAX_HEIGHT * scale * shrink;
+    let mut x = padding;
+    let slots = sizes
+        .iter()
+        .zip(fits.iter())
+        .map(|((w, h), fit)| {
+            let fit = fit * shrink;
+            let (width, height) = (w * fit, h * fit);
+            let slot = Slot {
+                x,
+                y: padding + (row_height - height) / 2.0,
+                width,
+                height,
+                fit,
+            };
+            x += width + padding;
+            slot
+        })
+        .collect();
+
+    SwitcherLayout {
+        width: x,
+        height: padding * 2.5 + row_height + title_size * 1.5,
+        slots,
+        padding,
+        title_size,
+    }
+}
+
+pub fn layout(state: &WindowSwitcherModel) -> SwitcherLayout {
+    let scale = state.scale;
+    let sizes: Vec<(f32, f32)> = state.windows.iter().map(|w| w.size).collect();
+    layout_thumbnails(&sizes, scale, state.width as f32)
+}
+
+/// Draws the selection highlight and the window titles; the thumbnails are
+/// mirror layers.
+fn draw_switcher_overlay(
+    state: &WindowSwitcherModel,
+    layout: &SwitcherLayout,
+) -> ContentDrawFunction {
+    let titles: Vec<String> = state.windows.iter().map(|w| w.title.clone()).collect();
+    let current = state.current;
+    let layout = layout.clone();
+
+    let draw = move |canvas: &layers::skia::Canvas, w: f32, h: f32| -> layers::skia::Rect {
+        let inset = layout.padding / 2.0;
+        let title_top = layout.height - layout.padding - layout.title_size * 1.5;
+        if let Some(slot) = layout.slots.get(current) {
+            let mut paint = layers::skia::Paint::new(theme_colors().fills_primary.c4f(), None);
+            paint.set_anti_alias(true);
+            let rect = layers::skia::Rect::from_xywh(
+                slot.x - inset,
+                inset,
+                slot.width + inset * 2.0,
+                layout.height - inset * 2.0,
+            );
+            canvas.draw_round_rect(rect, inset, inset, &paint);
+        }
+
+        let font_family = Config::with(|c| c.font_family.clone());
+        let font = FONT_CACHE.with(|fc| {
+            fc.make_font_with_fallback(
+                font_family,
+                layers::skia::FontStyle::normal(),
+                layout.title_size,
+            )
+        });
+        for (index, (slot, title)) in layout.slots.iter().zip(titles.iter()).enumerate() {
+            let color = if index == current {
+                theme_colors().text_primary
+            } else {
+                theme_colors().text_secondary
+            };
+            let mut text_paint = layers::skia::Paint::new(color.c4f(), None);
+            text_paint.set_anti_alias(true);
+            let text_width = font.measure_str(title, Some(&text_paint)).0;
+            let text_x = slot.x + ((slot.width - text_width) / 2.0).max(0.0);
+            canvas.save();
+            canvas.clip_rect(
+                layers::skia::Rect::from_xywh(
+                    slot.x,
+                    title_top,
+                    slot.width,
+                    layout.title_size * 1.5,
+                ),
+                None,
+                Some(true),
+            );
+            canvas.draw_str(
+                title,
+                (text_x, title_top + layout.title_size * 1.1),
+                &font,
+                &text_paint,
+            );
+            canvas.restore();
+        }
+
+        layers::skia::Rect::from_xywh(0.0, 0.0, w, h)
+    };
+    draw.into()
+}
+
+/// Build the switcher panel: a blurred background with one mirror of each
+/// window's scene layer.
+pub fn render_window_switcher(
+    state: &WindowSwitcherModel,
+    view: &View<WindowSwitcherModel>,
+) -> LayerTree {
+    let layout = layout(state);
+    let overlay = draw_switcher_overlay(state, &layout);
+
+    let children: Vec<LayerTree> = state
+        .windows
+        .iter()
+        .zip(layout.slots.iter())
+        .enumerate()
+        .map(|(index, (window, slot))| {
+            let hover_ref = view.clone();
+            let leave_ref = view.clone();
+            LayerTreeBuilder::with_key(format!("window_switcher_thumbnail_{:?}", window.wid))
+                .layout_style(taffy::Style {
+                    position: taffy::Position::Absolute,
+                    ..Default::default()
+                })
+                .position(Point::new(slot.x, slot.y))
+                .size(Size::points(slot.width, slot.height))
+                .on_pointer_in(move |_: &Layer, _x, _y| {
+                    hover_ref.update_state(&WindowSwitcherModel {
+                        current: index,
+                        hovered: Some(index),
+                        ..hover_ref.get_state()
+                    });
+                })
+                .on_pointer_out(move |_: &Layer, _x, _y| {
+                    let state = leave_ref.get_state();
+                    if state.hovered == Some(index) {
+                        leave_ref.update_state(&WindowSwitcherModel {
+                            hovered: None,
+                            ..state
+                        });
+                    }
+                })
+                .children(vec![LayerTreeBuilder::with_key(format!(
+                    "window_switcher_mirror_{:?}",
+                    window.wid
+                ))
+                .layout_style(taffy::Style {
+                    position: taffy::Position::Absolute,
+                    ..Default::default()
+                })
+                .size(Size::points(window.size.0, window.size.1))
+                .scale(Point::new(slot.fit, slot.fit))
+                .replicate_node(Some(window.node))
+                .picture_cached(false)
+                .pointer_events(false)
+                .build()
+                .unwrap()])
+                .build()
+                .unwrap()
+        })
+        .collect();
+
+    LayerTreeBuilder::with_key("window_switcher_panel")
+        .blend_mode(BlendMode::BackgroundBlur)
+        .background_color(theme_colors().materials_medium)
+        .size((
+            Size::points(layout.width, layout.height),
+            Some(Config::transition_or_instant(Transition::spring(0.4, 0.0))),
+        ))
+        .border_corner_radius((BorderRadius::new_single(layout.padding), None))
+        .shadow_color(theme_colors().shadow_color)
+        .shadow_offset(((0.0, 0.0).into(), None))
+        .shadow_radius((20.0, None))
+        .content(Some(overlay))
+        .pointer_events(false)
+        .children(children)
+        .build()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thumbnails_fit_the_largest_size() {
+        let layout = layout_thumbnails(&[(1920.0, 1080.0), (800.0, 1200.0)], 1.0, 3000.0);
+        let wide = layout.slots[0];
+        assert_eq!(wide.width, THUMBNAIL_MAX_WIDTH);
+        assert_eq!(wide.height, 135.0);
+        let tall = layout.slots[1];
+        assert!((tall.height - THUMBNAIL_MAX_HEIGHT).abs() < 0.01);
+        assert_eq!(tall.x, PADDING * 2.0 + wide.width);
+        assert_eq!(layout.width, tall.x + tall.width + PADDING);
+    }
+
+    #[test]
+    fn a_wide_row_is_shrunk_to_the_output() {
+        let sizes = vec![(1600.0, 1000.0); 10];
+        let layout = layout_thumbnails(&sizes, 1.0, 1280.0);
+        assert!(layout.width <= 1280.0 - SCREEN_MARGIN * 2.0 + 0.01);
+        assert!(layout.slots.iter().all(|s| s.width < THUMBNAIL_MAX_WIDTH));
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use layers::{
+    engine::{
+        animation::{TimingFunction, Transition},
+        Engine,
+    },
+    prelude::{taffy, View},
+    taffy::style::Style,
+    types::Size,
+};
+use smithay::{
+    backend::input::ButtonState, reexports::wayland_server::backend::ObjectId, utils::IsAlive,
+};
+
+use crate::{config::Config, interactive_view::ViewInteractions};
+
+use super::{
+    model::{WindowSwitcherItem, WindowSwitcherModel},
+    render::render_window_switcher,
+};
+
+/// Alt-Tab overlay: a row of live thumbnails of the current workspace's
+/// windows, centred on the primary output.
+#[derive(Debug, Clone)]
+pub struct WindowSwitcherView {
+    pub wrap_layer: layers::prelude::Layer,
+    pub view: View<WindowSwitcherModel>,
+    active: Arc<AtomicBool>,
+}
+impl PartialEq for WindowSwitcherView {
+    fn eq(&self, other: &Self) -> bool {
+        self.wrap_layer == other.wrap_layer
+    }
+}
+impl IsAlive for WindowSwitcherView {
+    fn alive(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+}
+
+impl WindowSwitcherView {
+    pub fn new(layers_engine: Arc<Engine>) -> Self {
+        let wrap = layers_engine.new_layer();
+        wrap.set_key("window_switcher");
+        wrap.set_size(Size::percent(1.0, 1.0), None);
+        wrap.set_layout_style(Style {
+            position: taffy::Position::Absolute,
+            display: taffy::Display::Flex,
+            justify_content: Some(taffy::JustifyContent::Center),
+            align_items: Some(taffy::AlignItems::Center),
+            ..Default::default()
+        });
+        wrap.set_opacity(0.0, None);
+        wrap.set_pointer_events(false);
+        wrap.set_hidden(true);
+
+        let view_layer = layers_engine.new_layer();
+        let _ = wrap.add_sublayer(&view_layer);
+
+        let view = View::new(
+            "window_switcher_view",
+            WindowSwitcherModel::default(),
+            render_window_switcher,
+        );
+        view.set_layer(view_layer);
+
+        Self {
+            wrap_layer: wrap,
+            view,
+            active: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Show `windows`, topmost first, with the topmost one selected.
+    pub fn show(&self, windows: Vec<WindowSwitcherItem>, width: i32, scale: f32) {
+        self.view.update_state(&WindowSwitcherModel {
+            windows,
+            current: 0,
+            hovered: None,
+            width,
+            scale,
+        });
+        self.active.store(true, Ordering::Relaxed);
+        self.wrap_layer.set_hidden(false);
+        self.wrap_layer.set_opacity(
+            1.0,
+            Some(Config::transition_or_instant(Transition {
+                delay: 0.05,
+                timing: TimingFunction::ease_out_quad(0.1),
+            })),
+        );
+    }
+
+    pub fn next(&self) {
+        self.select_step(1);
+    }
+
+    pub fn previous(&self) {
+        self.select_step(-1);
+    }
+
+    fn select_step(&self, steps: isize) {
+        let state = self.view.get_state();
+        self.view.update_state(&WindowSwitcherModel {
+            current: state.step(steps),
+            ..state
+        });
+    }
+
+    pub fn hide(&self) -> layers::engine::TransactionRef {
+        self.active.store(false, Ordering::Relaxed);
+        let transition = Config::transition_or_instant(Transition::ease_in_quad(0.05));
+        let tr = self.wrap_layer.set_opacity(0.0, Some(transition));
+        tr.on_finish(
+            move |l: &layers::prelude::Layer, _p: f32| {
+                l.set_hidden(true);
+            },
+            true,
+        );
+        tr
+    }
+
+    /// The selected window.
+    pub fn current_window(&self) -> Option<ObjectId> {
+        let state = self.view.get_state();
+        state.windows.get(state.current).map(|w| w.wid.clone())
+    }
+
+    /// The window whose thumbnail is under the pointer.
+    pub fn hovered_window(&self) -> Option<ObjectId> {
+        let state = self.view.get_state();
+        state
+            .hovered
+            .and_then(|index| state.windows.get(index))
+            .map(|w| w.wid.clone())
+    }
+
+    /// Drop a window that went away while the switcher is shown, closing
+    /// the switcher when it was the last one.
+    pub fn remove_window(&self, wid: &ObjectId) {
+        let mut state = self.view.get_state();
+        let Some(index) = state.windows.iter().position(|w| w.wid == *wid) else {
+            return;
+        };
+        state.windows.remove(index);
+        state.hovered = None;
+        if state.current > index || state.current >= state.windows.len() {
+            state.current = state.current.saturating_sub(1);
+        }
+        let empty = state.windows.is_empty();
+        self.view.update_state(&state);
+        if empty && self.alive() {
+            self.hide();
+        }
+    }
+}
+
+impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WindowSwitcherView {
+    fn id(&self) -> Option<usize> {
+        Some(self.wrap_layer.id.0.into())
+    }
+    fn is_alive(&self) -> bool {
+        self.alive()
+    }
+    fn on_button(
+        &self,
+        _seat: &smithay::input::Seat<crate::Otto<Backend>>,
+        data: &mut crate::Otto<Backend>,
+        event: &smithay::input::pointer::ButtonEvent,
+    ) {
+        // A click on a thumbnail switches to its window, a click anywhere
+        // else closes the switcher.
+        match event.state {
+            ButtonState::Pressed if self.hovered_window().is_none() => {
+                data.cancel_window_switcher();
+            }
+            ButtonState::Released if self.hovered_window().is_some() => {
+                data.commit_window_switcher();
+            }
+            _ => {}
+        }
+    }
+}
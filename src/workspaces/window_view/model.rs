@@ -20,6 +20,9 @@ pub struct WindowViewSurface {
     pub(crate) log_offset_x: f32,
     pub(crate) log_offset_y: f32,
     pub(crate) texture_id: Option<u32>,
+    /// Unpremultiplied RGBA of a single-pixel buffer, drawn instead of a
+    /// texture
+    pub(crate) solid_color: Option<[f32; 4]>,
     pub(crate) commit: CommitCounter,
     pub(crate) transform: Transform,
 }
@@ -38,6 +41,7 @@ impl fmt::Debug for WindowViewSurface {
             .field("dst_h", &self.phy_dst_h)
             .field("offset_x", &self.log_offset_x)
             .field("offset_y", &self.log_offset_y)
+            .field("solid_color", &self.solid_color)
             .field("commit", &self.commit)
             .field("transform", &self.transform)
             .finish()
@@ -72,6 +76,9 @@ impl Hash for WindowViewSurface {
         if let Some(tid) = self.texture_id {
             tid.hash(state);
         }
+        if let Some(color) = self.solid_color {
+            color.map(f32::to_bits).hash(state);
+        }
         self.id.hash(state);
         if let Some(ref parent_id) = self.parent_id {
             parent_id.hash(state);
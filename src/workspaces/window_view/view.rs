@@ -1,14 +1,14 @@
 use layers::{
     engine::{Engine, TransactionRef},
-    prelude::{taffy, Layer, Transition},
+    prelude::{taffy, BorderRadius, Color, Layer, Transition},
     skia,
-    types::Point,
+    types::{Point, Size},
     view::RenderLayerTree,
 };
 use smithay::{reexports::wayland_server::backend::ObjectId, utils::Logical};
 use std::sync::{atomic::AtomicBool, Arc};
 
-use crate::shell::WindowElement;
+use crate::{config::Config, shell::WindowElement, utils::parse_hex_color};
 
 use super::{effects::GenieEffect, model::WindowViewBaseModel, render::view_window_shadow};
 
@@ -22,6 +22,8 @@ pub struct WindowView {
     pub window_layer: layers::prelude::Layer,
     pub shadow_layer: layers::prelude::Layer,
     pub content_layer: layers::prelude::Layer,
+    /// Configured `[window_border]`, drawn above the content
+    pub border_layer: layers::prelude::Layer,
     pub mirror_layer: layers::prelude::Layer,
 
     pub genie_effect: GenieEffect,
@@ -56,8 +58,18 @@ impl WindowView {
             ..Default::default()
         });
 
+        let border_layer = layers_engine.new_layer();
+        border_layer.set_layout_style(taffy::Style {
+            position: taffy::Position::Absolute,
+            ..Default::default()
+        });
+        border_layer.set_pointer_events(false);
+        border_layer.set_border_color(window_border_color(false), None);
+        border_layer.set_hidden(true);
+
         let _ = layers_engine.append_layer(&shadow_layer, layer.id());
         let _ = layers_engine.append_layer(&content_layer, layer.id());
+        let _ = layers_engine.append_layer(&border_layer, layer.id());
 
         let base_rect = WindowViewBaseModel {
             x: 0.0,
@@ -84,6 +96,7 @@ impl WindowView {
             view_base,
             window_layer: layer,
             content_layer,
+            border_layer,
             shadow_layer,
             genie_effect,
             mirror_layer,
@@ -102,6 +115,49 @@ impl WindowView {
             ..current_state.clone()
         };
         self.view_base.update_state(&new_state);
+        self.set_border_active(active);
+    }
+
+    /// Fade the border to the focused or unfocused color
+    pub fn set_border_active(&self, active: bool) {
+        self.border_layer.set_border_color(
+            window_border_color(active),
+            Some(Config::transition_or_instant(Transition::ease_out_quad(
+                BORDER_TRANSITION,
+            ))),
+        );
+    }
+
+    /// Clip the content of a `w` x `h` window (physical pixels) to
+    /// `window_corner_radius` and size its border, scaled for the output the
+    /// window is on. Maximized and fullscreen windows stay square; fullscreen
+    /// windows get no border.
+    pub fn update_frame(&self, w: f32, h: f32, scale: f32, maximized: bool, fullscreen: bool) {
+        let (radius, border_width) = Config::with(|c| {
+            frame_radius_and_border(
+                c.window_corner_radius,
+                c.window_border.width,
+                scale,
+                maximized,
+                fullscreen,
+            )
+        });
+        let size = Size::points(w, h);
+
+        // Clipping to the rounded rect antialiases the corners against
+        // whatever is below the window
+        let clip = radius > 0.0;
+        self.content_layer.set_size(size, None);
+        self.content_layer
+            .set_border_corner_radius(BorderRadius::new_single(radius), None);
+        self.content_layer.set_clip_content(clip, None);
+        self.content_layer.set_clip_children(clip, None);
+
+        self.border_layer.set_size(size, None);
+        self.border_layer
+            .set_border_corner_radius(BorderRadius::new_single(radius), None);
+        self.border_layer.set_border_width(border_width, None);
+        self.border_layer.set_hidden(border_width == 0.0);
     }
 
     pub fn set_is_minimizing(&self, minimizing: bool) {
@@ -130,6 +186,18 @@ impl WindowView {
     }
 
     pub fn minimize(&self, to_rect: skia::Rect) -> TransactionRef {
+        if Config::reduce_motion() {
+            // Drop straight into the drawer as a miniwindow
+            let bounds = self.window_layer.render_bounds_with_children();
+            self.apply_minimized_scale_to_layer(
+                &self.window_layer,
+                (bounds.width(), bounds.height()),
+                to_rect,
+            );
+            return self
+                .window_layer
+                .set_position(Point { x: 0.0, y: 0.0 }, None);
+        }
         self.window_layer.set_effect(self.genie_effect.clone());
         self.genie_effect.set_destination(to_rect, true);
 
@@ -172,6 +240,12 @@ impl WindowView {
     }
 
     pub fn unminimize(&self, from: skia::Rect) -> TransactionRef {
+        if Config::reduce_motion() {
+            self.window_layer.set_hidden(false);
+            self.window_layer.set_scale(Point { x: 1.0, y: 1.0 }, None);
+            self.window_layer.remove_effect();
+            return self.window_layer.set_opacity(1.0, None);
+        }
         self.set_is_minimizing(true);
         // Re-enable the shader and reset the scale before running the animation
         // we need set opacity to 0 first to avoid flickering
@@ -236,6 +310,49 @@ impl WindowView {
     }
 }
 
+/// Duration of the border color fade on focus changes, in seconds
+const BORDER_TRANSITION: f32 = 0.25;
+
+/// `[window_border]` color for a focused or unfocused window
+fn window_border_color(active: bool) -> Color {
+    let hex = Config::with(|c| {
+        if active {
+            c.window_border.focused_color.clone()
+        } else {
+            Some(c.window_border.unfocused_color.clone())
+        }
+    });
+    match hex {
+        Some(hex) => {
+            let color = parse_hex_color(&hex);
+            Color::new_rgba(color.r, color.g, color.b, color.a)
+        }
+        None => crate::theme::accent_color(),
+    }
+}
+
+/// Corner radius and border width of a window frame in physical pixels,
+/// from the logical config values and the scale of the window's output
+fn frame_radius_and_border(
+    radius: f32,
+    border_width: f32,
+    scale: f32,
+    maximized: bool,
+    fullscreen: bool,
+) -> (f32, f32) {
+    let radius = if maximized || fullscreen {
+        0.0
+    } else {
+        radius.max(0.0) * scale
+    };
+    let border_width = if fullscreen {
+        0.0
+    } else {
+        border_width.max(0.0) * scale
+    };
+    (radius, border_width)
+}
+
 impl std::fmt::Debug for WindowView {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("WindowView")
@@ -245,3 +362,40 @@ impl std::fmt::Debug for WindowView {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_follows_the_output_scale() {
+        assert_eq!(
+            frame_radius_and_border(12.0, 2.0, 1.0, false, false),
+            (12.0, 2.0)
+        );
+        assert_eq!(
+            frame_radius_and_border(12.0, 2.0, 2.0, false, false),
+            (24.0, 4.0)
+        );
+        assert_eq!(
+            frame_radius_and_border(12.0, 2.0, 1.5, false, false),
+            (18.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn maximized_and_fullscreen_frames() {
+        assert_eq!(
+            frame_radius_and_border(12.0, 2.0, 2.0, true, false),
+            (0.0, 4.0)
+        );
+        assert_eq!(
+            frame_radius_and_border(12.0, 2.0, 2.0, false, true),
+            (0.0, 0.0)
+        );
+        assert_eq!(
+            frame_radius_and_border(-1.0, -1.0, 2.0, false, false),
+            (0.0, 0.0)
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use super::{BackgroundView, WindowSelectorView};
+use super::{BackgroundView, TileTree, WindowSelectorView, WorkspaceLayout};
 use crate::{
     config::Config,
     shell::WindowElement,
@@ -13,7 +13,7 @@ use layers::{
 };
 use smithay::reexports::wayland_server::backend::ObjectId;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{atomic::AtomicBool, Arc, RwLock},
 };
 
@@ -33,11 +33,16 @@ pub struct WorkspaceView {
     pub layers_engine: Arc<Engine>,
     pub workspace_layer: Layer,
     pub windows_layer: Layer,
+    /// Container for always-on-top windows, stacked above `windows_layer`
+    pub on_top_windows_layer: Layer,
 
     fullscreen_mode: Arc<AtomicBool>,
     is_fullscreen_animating: Arc<AtomicBool>,
     name: Arc<RwLock<Option<String>>>,
+    layout: Arc<RwLock<WorkspaceLayout>>,
+    tile_tree: Arc<RwLock<TileTree<ObjectId>>>,
     window_base_layers: Arc<RwLock<HashMap<ObjectId, Layer>>>,
+    on_top_windows: Arc<RwLock<HashSet<ObjectId>>>,
     /// Stacking order (bottom→top ObjectIds) saved when expose opens,
     /// so it can be restored verbatim when expose closes without selection.
     pre_expose_order: Arc<RwLock<Vec<ObjectId>>>,
@@ -67,6 +72,8 @@ impl fmt::Debug for WorkspaceView {
 ///     │   ├── window
 ///     │   ├── window
 ///     │   └── window
+///     ├── workspace_on_top_windows_container (hidden in fullscreen mode)
+///     │   └── window (always on top)
 ///     └── overlay
 ///         └── fullscreen_surface
 /// ```
@@ -112,6 +119,18 @@ impl WorkspaceView {
         });
         windows_layer.set_pointer_events(false);
 
+        let on_top_windows_layer = layers_engine.new_layer();
+        on_top_windows_layer.set_key(format!("workspace_on_top_windows_container_{}", index));
+        on_top_windows_layer.set_layout_style(taffy::Style {
+            position: taffy::Position::Absolute,
+            size: taffy::Size {
+                width: taffy::Dimension::Percent(1.0),
+                height: taffy::Dimension::Percent(1.0),
+            },
+            ..Default::default()
+        });
+        on_top_windows_layer.set_pointer_events(false);
+
         let _ = layers_engine.append_layer(&workspace_layer, parent.id);
         let _ = layers_engine.append_layer(&background_layer, Some(workspace_layer.id));
 
@@ -131,6 +150,7 @@ impl WorkspaceView {
         let _ = layers_engine.append_layer(&layer_shell_bg_mirror, Some(workspace_layer.id));
 
         let _ = layers_engine.append_layer(&windows_layer, Some(workspace_layer.id));
+        let _ = layers_engine.append_layer(&on_top_windows_layer, Some(workspace_layer.id));
 
         // Parse background color from config
         let background_color = Config::with(|c| parse_hex_color(&c.background_color));
@@ -167,11 +187,15 @@ impl WorkspaceView {
             background_view,
             layers_engine,
             windows_layer,
+            on_top_windows_layer,
             workspace_layer,
             fullscreen_mode: Arc::new(AtomicBool::new(false)),
             is_fullscreen_animating: Arc::new(AtomicBool::new(false)),
             name: Arc::new(RwLock::new(None)),
+            layout: Arc::new(RwLock::new(Config::with(|c| c.tiling.default_layout))),
+            tile_tree: Arc::new(RwLock::new(TileTree::default())),
             window_base_layers: Arc::new(RwLock::new(HashMap::new())),
+            on_top_windows: Arc::new(RwLock::new(HashSet::new())),
             pre_expose_order: Arc::new(RwLock::new(Vec::new())),
         }
     }
@@ -197,10 +221,17 @@ impl WorkspaceView {
         let mut window_list = self.windows_list.write().unwrap();
         let wid = window_element.id();
         if !window_list.contains(&wid) {
-            window_list.push(wid.clone());
+            // Fullscreen windows stay in the normal stack, which fullscreen
+            // mode keeps visible; unfullscreening remaps them on top
+            let on_top = window_element.is_always_on_top() && !window_element.is_fullscreen();
+            if on_top {
+                self.on_top_windows.write().unwrap().insert(wid.clone());
+            }
+            let position = self.stack_position(&window_list, on_top);
+            window_list.insert(position, wid.clone());
 
             let _ = self
-                .windows_layer
+                .windows_container(on_top)
                 .add_sublayer(&window_element.base_layer().id);
 
             let mirror_window = window_element.mirror_layer();
@@ -229,7 +260,7 @@ impl WorkspaceView {
                 x: location.x as f32,
                 y: location.y as f32,
             },
-            transition,
+            transition.map(Config::transition_or_instant),
         );
 
         if let Some(l) = self
@@ -271,16 +302,17 @@ impl WorkspaceView {
         if let Some(index) = window_list.iter().position(|x| x == window_id) {
             window_list.remove(index);
         }
+        self.on_top_windows.write().unwrap().remove(window_id);
     }
 
     pub fn raise_window_to_front(&self, window_id: &ObjectId) {
+        let on_top = self.is_window_on_top(window_id);
         {
             let mut window_list = self.windows_list.write().unwrap();
             if let Some(index) = window_list.iter().position(|x| x == window_id) {
-                if index + 1 != window_list.len() {
-                    let wid = window_list.remove(index);
-                    window_list.push(wid);
-                }
+                let wid = window_list.remove(index);
+                let position = self.stack_position(&window_list, on_top);
+                window_list.insert(position, wid);
             }
         }
 
@@ -291,7 +323,7 @@ impl WorkspaceView {
             .get(window_id)
             .cloned()
         {
-            if let Err(e) = self.windows_layer.add_sublayer(&base_layer) {
+            if let Err(e) = self.windows_container(on_top).add_sublayer(&base_layer) {
                 tracing::warn!("raise_window_to_front: failed to reparent window layer: {e}");
                 return;
             }
@@ -300,9 +332,59 @@ impl WorkspaceView {
         self.window_selector_view.bring_window_to_front(window_id);
     }
 
+    /// Move a window between the normal stack and the always-on-top stack,
+    /// placing it at the top of its new stack
+    pub fn set_window_on_top(&self, window_id: &ObjectId, on_top: bool) {
+        if !self
+            .window_base_layers
+            .read()
+            .unwrap()
+            .contains_key(window_id)
+        {
+            return;
+        }
+        if on_top {
+            self.on_top_windows
+                .write()
+                .unwrap()
+                .insert(window_id.clone());
+        } else {
+            self.on_top_windows.write().unwrap().remove(window_id);
+        }
+        self.raise_window_to_front(window_id);
+    }
+
+    pub fn is_window_on_top(&self, window_id: &ObjectId) -> bool {
+        self.on_top_windows.read().unwrap().contains(window_id)
+    }
+
+    /// Scene container for a window in the normal or always-on-top stack
+    pub fn windows_container(&self, on_top: bool) -> &Layer {
+        if on_top {
+            &self.on_top_windows_layer
+        } else {
+            &self.windows_layer
+        }
+    }
+
+    /// Insertion index that keeps `windows_list` ordered bottom→top with all
+    /// always-on-top windows after the normal ones
+    fn stack_position(&self, window_list: &[ObjectId], on_top: bool) -> usize {
+        if on_top {
+            return window_list.len();
+        }
+        let on_top_windows = self.on_top_windows.read().unwrap();
+        window_list
+            .iter()
+            .position(|id| on_top_windows.contains(id))
+            .unwrap_or(window_list.len())
+    }
+
     pub fn set_fullscreen_mode(&self, fullscreen: bool) {
         self.fullscreen_mode
             .store(fullscreen, std::sync::atomic::Ordering::Relaxed);
+        // A fullscreen window covers the output, always-on-top windows included
+        self.on_top_windows_layer.set_hidden(fullscreen);
     }
 
     pub fn get_fullscreen_mode(&self) -> bool {
@@ -328,6 +410,19 @@ impl WorkspaceView {
         self.name.read().unwrap().clone()
     }
 
+    pub fn layout(&self) -> WorkspaceLayout {
+        *self.layout.read().unwrap()
+    }
+
+    pub fn set_layout(&self, layout: WorkspaceLayout) {
+        *self.layout.write().unwrap() = layout;
+    }
+
+    /// Run `f` on the tile tree of this workspace
+    pub fn with_tile_tree<T>(&self, f: impl FnOnce(&mut TileTree<ObjectId>) -> T) -> T {
+        f(&mut self.tile_tree.write().unwrap())
+    }
+
     /// Returns true if no pre-expose stacking order has been saved yet.
     pub fn peek_pre_expose_order_empty(&self) -> bool {
         self.pre_expose_order.read().unwrap().is_empty()
@@ -347,6 +442,7 @@ impl WorkspaceView {
 impl Drop for WorkspaceView {
     fn drop(&mut self) {
         self.windows_layer.remove();
+        self.on_top_windows_layer.remove();
         self.workspace_layer.remove();
         self.window_selector_view.window_selector_root.remove();
     }
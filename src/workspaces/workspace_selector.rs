@@ -22,7 +22,7 @@ use crate::{
     },
 };
 
-use super::WorkspacesModel;
+use super::{WorkspaceRemoval, WorkspacesModel};
 
 pub const WORKSPACE_SELECTOR_PREVIEW_WIDTH: f32 = 300.0;
 const WORKSPACE_SELECTOR_GAP: f32 = 50.0;
@@ -68,6 +68,7 @@ impl Hash for WorkspaceSelectorViewState {
         self.workspaces.hash(state);
         self.current.hash(state);
         self.drop_hover_index.hash(state);
+        crate::theme::theme_generation().hash(state);
     }
 }
 
@@ -80,7 +81,7 @@ pub struct WorkspaceSelectorView {
     pub drop_hover_index: Arc<RwLock<Option<usize>>>,
     known_indices: Arc<RwLock<HashSet<usize>>>,
     pressed_action: Arc<RwLock<Option<String>>>,
-    remove_sender: CalloopSender<usize>,
+    remove_sender: CalloopSender<WorkspaceRemoval>,
 }
 
 /// # WorkspaceSelectorView Layer Structure
@@ -103,7 +104,7 @@ impl WorkspaceSelectorView {
     pub fn new(
         _layers_engine: Arc<Engine>,
         layer: Layer,
-        remove_sender: CalloopSender<usize>,
+        remove_sender: CalloopSender<WorkspaceRemoval>,
     ) -> Self {
         let state = WorkspaceSelectorViewState {
             workspaces: Vec::new(),
@@ -666,7 +667,7 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WorkspaceSele
                     if pressed_key == release_key {
                         if release_key == "workspace_selector_desktop_add" {
                             // Add new workspace
-                            otto.workspaces.add_workspace();
+                            otto.workspaces.create_workspace(None);
                         } else if let Some(index) = release_key
                             .strip_prefix("workspace_selector_desktop_remove_")
                             .and_then(|idx| idx.parse::<usize>().ok())
@@ -684,7 +685,10 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WorkspaceSele
                                         Transition::spring(0.6, 0.1),
                                     )
                                     .then(move |_layer: &Layer, _| {
-                                        let _ = remove_sender.send(index);
+                                        let _ = remove_sender.send(WorkspaceRemoval {
+                                            index,
+                                            persist: true,
+                                        });
                                     });
                             }
                             if let Some(wrap_layer) = self.view.layer_by_key(wrap_key.as_str()) {
@@ -5,6 +5,7 @@ use std::{
 };
 
 use crate::{
+    input::actions::KeyAction,
     render::*,
     render_elements::workspace_render_elements::WorkspaceRenderElements,
     shell::WindowElement,
@@ -134,9 +135,13 @@ impl Backend for X11Data {
     fn request_redraw(&mut self) {
         self.render = true;
     }
+    fn run_key_action(state: &mut Otto<Self>, action: KeyAction) {
+        state.process_key_action_windowed(action, OUTPUT_NAME);
+    }
 }
 
 pub fn run_x11() {
+    crate::config::Config::write_starter_config_on_first_run();
     let mut event_loop = EventLoop::try_new().unwrap();
     let display = Display::new().unwrap();
     let mut display_handle = display.handle();
@@ -491,6 +496,8 @@ pub fn run_x11() {
                     }
 
                     if render_output_result.damage.is_some() {
+                        state.screenshot_frame_rendered();
+                        state.screenshot_frame_presented();
                         let all_window_elements: Vec<&WindowElement> =
                             state.workspaces.spaces_elements().collect();
                         let mut output_presentation_feedback = take_presentation_feedback(
@@ -565,6 +572,7 @@ pub fn run_x11() {
         } else {
             state.workspaces.refresh_space();
             state.popups.cleanup();
+            state.publish_compositor_events();
             display_handle.flush_clients().unwrap();
         }
     }
@@ -591,6 +591,35 @@ mod headless_tests {
         );
     }
 
+    // ── Virtual input ────────────────────────────────────────────────────
+
+    #[test]
+    #[serial]
+    fn locked_session_drops_virtual_pointer_input() {
+        let handle = start_compositor();
+        let mut client = connect_client(&handle);
+        let pointer = client.create_virtual_pointer();
+
+        handle.pointer_move(100.0, 100.0);
+        handle.set_session_locked(true);
+        pointer.motion_absolute(0, 960, 540, 1920, 1080);
+        pointer.frame();
+        let _ = client.roundtrip();
+        assert_eq!(
+            handle.pointer_location(),
+            (100.0, 100.0),
+            "Virtual pointer should not move the pointer while locked"
+        );
+
+        handle.set_session_locked(false);
+        pointer.motion_absolute(0, 960, 540, 1920, 1080);
+        pointer.frame();
+        let _ = client.roundtrip();
+        assert_eq!(handle.pointer_location(), (960.0, 540.0));
+
+        handle.stop();
+    }
+
     // ── Scene JSON for debugging ─────────────────────────────────────────
 
     #[test]